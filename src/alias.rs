@@ -0,0 +1,314 @@
+use crate::config::{self, Resolved};
+use crate::error::CliError;
+use crate::table::{Column, Table};
+use crate::OutputFormat;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// Aliases expanding to aliases are followed this many times before giving up
+const MAX_DEPTH: usize = 8;
+
+/// The command line aliases expand into, set once by the binary. Without it, as for a
+/// tool linking the library, no name is taken and no option is known to take a value.
+static COMMAND: OnceLock<fn() -> clap::Command> = OnceLock::new();
+
+pub fn set_command(command: fn() -> clap::Command) {
+    let _ = COMMAND.set(command);
+}
+
+fn command() -> clap::Command {
+    COMMAND
+        .get()
+        .map_or_else(|| clap::Command::new("rustar-cli"), |command| command())
+}
+
+/// Every name clap answers to at the top level, aliases of commands included
+fn builtin_names() -> Vec<String> {
+    let command = command();
+    let mut names = vec!["help".to_string()];
+    for subcommand in command.get_subcommands() {
+        names.push(subcommand.get_name().to_string());
+        names.extend(subcommand.get_all_aliases().map(str::to_string));
+    }
+    names
+}
+
+/// Split an alias into arguments at whitespace; single or double quotes keep a phrase
+/// together, as in `jobs cancel --reason "rf interference"`
+pub fn words(expansion: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quote: Option<char> = None;
+    for c in expansion.chars() {
+        match (quote, c) {
+            (Some(open), c) if c == open => quote = None,
+            (Some(_), c) => word.get_or_insert_with(String::new).push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                word.get_or_insert_with(String::new);
+            }
+            (None, c) if c.is_whitespace() => words.extend(word.take()),
+            (None, c) => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    if let Some(open) = quote {
+        return Err(format!("unterminated {} quote", open));
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+/// Config-load check of one profile's `alias` table and `default_command`: an alias may
+/// not take the name of a built-in command, which would always win
+pub fn check(
+    profile: &str,
+    aliases: &BTreeMap<String, String>,
+    default_command: Option<&str>,
+) -> Result<(), CliError> {
+    let invalid = |what: String, reason: String| {
+        CliError::Configuration(format!(
+            "profile '{}' in {}: {} {}",
+            profile,
+            config::config_path().display(),
+            what,
+            reason
+        ))
+    };
+    let builtins = builtin_names();
+    for (name, expansion) in aliases {
+        let what = format!("alias.{}", name);
+        if builtins.contains(name) {
+            return Err(invalid(
+                what,
+                "has the name of a built-in command; choose another name".to_string(),
+            ));
+        }
+        if name.is_empty() || name.starts_with('-') || name.contains(char::is_whitespace) {
+            return Err(invalid(
+                what,
+                "must be a single word not starting with '-'".to_string(),
+            ));
+        }
+        match words(expansion) {
+            Ok(words) if words.is_empty() => {
+                return Err(invalid(what, "expands to nothing".to_string()))
+            }
+            Ok(_) => {}
+            Err(reason) => return Err(invalid(what, reason)),
+        }
+    }
+    if let Some(default) = default_command {
+        match words(default) {
+            Ok(words) if words.is_empty() => {
+                return Err(invalid(
+                    "default_command".to_string(),
+                    "is empty".to_string(),
+                ))
+            }
+            Ok(_) => {}
+            Err(reason) => return Err(invalid("default_command".to_string(), reason)),
+        }
+    }
+    Ok(())
+}
+
+/// Where the command name sits in `argv`, past the global flags, and the values of the
+/// flags that choose a profile
+struct Scan {
+    command: Option<usize>,
+    profile: Option<String>,
+    env_file: Option<PathBuf>,
+    overrides: Vec<(String, String)>,
+    verbose: bool,
+    /// `--help` or `--version` given without a command: clap answers those
+    informational: bool,
+}
+
+fn scan(argv: &[OsString]) -> Scan {
+    let command = command();
+    let takes_value = |long: Option<&str>, short: Option<char>| {
+        command.get_arguments().any(|arg| {
+            arg.get_action().takes_values()
+                && ((long.is_some() && arg.get_long() == long)
+                    || (short.is_some() && arg.get_short() == short))
+        })
+    };
+    let mut scan = Scan {
+        command: None,
+        profile: None,
+        env_file: None,
+        overrides: Vec::new(),
+        verbose: false,
+        informational: false,
+    };
+    let mut index = 1;
+    while index < argv.len() {
+        let Some(token) = argv[index].to_str() else {
+            break;
+        };
+        if token == "--" {
+            break;
+        }
+        let (flag, inline) = match token.strip_prefix("--") {
+            Some(long) => match long.split_once('=') {
+                Some((name, value)) => (Some(name), Some(value.to_string())),
+                None => (Some(long), None),
+            },
+            None if token.starts_with('-') && token.len() > 1 => (None, None),
+            None => {
+                scan.command = Some(index);
+                break;
+            }
+        };
+        let short = flag.is_none().then(|| token.chars().nth(1)).flatten();
+        let value = match inline {
+            Some(value) => Some(value),
+            None if takes_value(flag, short) => {
+                index += 1;
+                argv.get(index).and_then(|v| v.to_str()).map(str::to_string)
+            }
+            None => None,
+        };
+        match (flag, short) {
+            (Some("profile"), _) => scan.profile = value,
+            (Some("env-file"), _) => scan.env_file = value.map(PathBuf::from),
+            (Some("set"), _) => scan
+                .overrides
+                .extend(value.and_then(|v| config::parse_override(&v).ok())),
+            (Some("verbose"), _) | (None, Some('v')) => scan.verbose = true,
+            (Some("help" | "version"), _) | (None, Some('h' | 'V')) => scan.informational = true,
+            _ => {}
+        }
+        index += 1;
+    }
+    scan
+}
+
+/// Expand a profile alias in the command position of `argv`, or run the profile's
+/// `default_command` when no command is given. A built-in command is never expanded,
+/// and nothing is loaded for one.
+pub fn expand(mut argv: Vec<OsString>) -> Result<Vec<OsString>, CliError> {
+    let builtins = builtin_names();
+    let first = scan(&argv);
+    // clap's help only knows commands; `help examples TOPIC` asks for the topic
+    if let Some(at) = first.command {
+        if argv[at] == "help" && argv.get(at + 1).is_some_and(|next| next == "examples") {
+            argv.remove(at);
+            return Ok(argv);
+        }
+    }
+    let is_builtin = |argv: &[OsString], at: usize| {
+        argv[at]
+            .to_str()
+            .is_none_or(|name| builtins.iter().any(|b| b == name))
+    };
+    match first.command {
+        Some(at) if is_builtin(&argv, at) => return Ok(argv),
+        None if first.informational => return Ok(argv),
+        _ => {}
+    }
+
+    let resolved = Resolved::load(
+        first.profile.as_deref(),
+        None,
+        None,
+        None,
+        first.env_file.as_deref(),
+        &first.overrides,
+        false,
+    )?;
+    let profile = &resolved.profile;
+    let profile_name = &resolved.profile_name.value;
+    let mut chain: Vec<String> = Vec::new();
+    loop {
+        let scanned = scan(&argv);
+        let (at, expansion, name) = match scanned.command {
+            None => match (&profile.default_command, chain.is_empty()) {
+                (Some(default), true) => (argv.len(), default, None),
+                _ => return Ok(argv),
+            },
+            Some(at) if is_builtin(&argv, at) => return Ok(argv),
+            Some(at) => {
+                let name = argv[at].to_string_lossy().to_string();
+                match profile.alias.get(&name) {
+                    Some(expansion) => (at, expansion, Some(name)),
+                    // Not ours: clap reports the unknown command
+                    None => return Ok(argv),
+                }
+            }
+        };
+        let label = name
+            .clone()
+            .unwrap_or_else(|| "default_command".to_string());
+        if chain.contains(&label) {
+            chain.push(label);
+            return Err(CliError::Configuration(format!(
+                "alias loop in profile '{}': {}",
+                profile_name,
+                chain.join(" → ")
+            )));
+        }
+        if chain.len() == MAX_DEPTH {
+            return Err(CliError::Configuration(format!(
+                "aliases in profile '{}' nest deeper than {}: {}",
+                profile_name,
+                MAX_DEPTH,
+                chain.join(" → ")
+            )));
+        }
+        // Checked when the config was loaded
+        let words = words(expansion).unwrap_or_default();
+        if scanned.verbose {
+            match &name {
+                Some(name) => eprintln!(
+                    "🔁 Alias {} of profile {} expands to: {}",
+                    name, profile_name, expansion
+                ),
+                None => eprintln!(
+                    "🔁 No command given; running default_command of profile {}: {}",
+                    profile_name, expansion
+                ),
+            }
+        }
+        let end = if name.is_some() { at + 1 } else { at };
+        argv.splice(at..end, words.into_iter().map(OsString::from));
+        chain.push(label);
+    }
+}
+
+/// `alias list`: the active profile's aliases and default command
+pub fn list(resolved: &Resolved, output: OutputFormat) -> Result<(), CliError> {
+    let profile = &resolved.profile;
+    if output == OutputFormat::Json {
+        let document = serde_json::json!({
+            "profile": resolved.profile_name.value,
+            "aliases": profile.alias,
+            "default_command": profile.default_command,
+        });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&document).map_err(|e| CliError::parse("aliases", e))?
+        );
+        return Ok(());
+    }
+
+    if profile.alias.is_empty() {
+        println!(
+            "No aliases in profile {}; add them as alias.NAME = \"COMMAND\" in {}",
+            resolved.profile_name.value,
+            config::config_path().display()
+        );
+    } else {
+        let mut table = Table::new(vec![Column::new("ALIAS"), Column::new("EXPANDS TO")]);
+        for (name, expansion) in &profile.alias {
+            table.row(vec![name.clone(), expansion.clone()]);
+        }
+        table.print();
+    }
+    if let Some(default) = &profile.default_command {
+        println!("\nWith no command: {}", default);
+    }
+    Ok(())
+}
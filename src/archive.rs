@@ -0,0 +1,197 @@
+use crate::error::CliError;
+
+/// Tar works in blocks of this many bytes
+const BLOCK: usize = 512;
+/// Largest stored deflate block
+const STORED_MAX: usize = 0xFFFF;
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// A file of the archive: its name and contents
+#[derive(Debug, Clone)]
+pub struct Member {
+    pub name: String,
+    pub contents: Vec<u8>,
+}
+
+/// Put a number in a tar header field as zero-padded octal, NUL-terminated
+fn octal(field: &mut [u8], value: u64) {
+    let digits = format!("{:0width$o}", value, width = field.len() - 1);
+    field[..digits.len()].copy_from_slice(digits.as_bytes());
+}
+
+fn header(member: &Member, mtime: u64) -> Result<[u8; BLOCK], CliError> {
+    if member.name.len() >= 100 || !member.name.is_ascii() {
+        return Err(CliError::validation(
+            "archive",
+            format!("'{}' is not a name a tar header holds", member.name),
+        ));
+    }
+    let mut block = [0u8; BLOCK];
+    block[..member.name.len()].copy_from_slice(member.name.as_bytes());
+    octal(&mut block[100..108], 0o600);
+    octal(&mut block[108..116], 0);
+    octal(&mut block[116..124], 0);
+    octal(&mut block[124..136], member.contents.len() as u64);
+    octal(&mut block[136..148], mtime);
+    block[156] = b'0';
+    block[257..263].copy_from_slice(b"ustar\0");
+    block[263..265].copy_from_slice(b"00");
+    // The checksum is taken with its own field as spaces
+    block[148..156].copy_from_slice(b"        ");
+    let sum: u32 = block.iter().map(|byte| u32::from(*byte)).sum();
+    block[148..155].copy_from_slice(format!("{:06o}\0", sum).as_bytes());
+    Ok(block)
+}
+
+/// The members as a ustar archive
+fn tar(members: &[Member], mtime: u64) -> Result<Vec<u8>, CliError> {
+    let mut archive = Vec::new();
+    for member in members {
+        archive.extend_from_slice(&header(member, mtime)?);
+        archive.extend_from_slice(&member.contents);
+        archive.resize(archive.len().div_ceil(BLOCK) * BLOCK, 0);
+    }
+    archive.resize(archive.len() + 2 * BLOCK, 0);
+    Ok(archive)
+}
+
+fn untar(archive: &[u8]) -> Result<Vec<Member>, String> {
+    let field = |block: &[u8], range: std::ops::Range<usize>| {
+        let raw = &block[range];
+        let end = raw.iter().position(|byte| *byte == 0).unwrap_or(raw.len());
+        String::from_utf8_lossy(&raw[..end]).trim().to_string()
+    };
+    let mut members = Vec::new();
+    let mut offset = 0;
+    while offset + BLOCK <= archive.len() {
+        let block = &archive[offset..offset + BLOCK];
+        if block.iter().all(|byte| *byte == 0) {
+            return Ok(members);
+        }
+        let name = field(block, 0..100);
+        let size = u64::from_str_radix(&field(block, 124..136), 8)
+            .map_err(|_| format!("the header of '{}' has an unreadable size", name))?
+            as usize;
+        let start = offset + BLOCK;
+        let contents = archive
+            .get(start..start + size)
+            .ok_or_else(|| format!("'{}' is cut short", name))?;
+        // Only regular files are kept; directories and the like are stepped over
+        if matches!(block[156], b'0' | 0) {
+            members.push(Member {
+                name,
+                contents: contents.to_vec(),
+            });
+        }
+        offset = start + size.div_ceil(BLOCK) * BLOCK;
+    }
+    Err("the archive ends without its end-of-archive blocks".to_string())
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+/// `data` as a gzip member of stored (uncompressed) deflate blocks: the backups are a
+/// few kilobytes of text, and any gzip reader takes them
+fn gzip(data: &[u8], mtime: u32) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len() + data.len() / STORED_MAX * 5 + 32);
+    out.extend_from_slice(&GZIP_MAGIC);
+    out.extend_from_slice(&[8, 0]);
+    out.extend_from_slice(&mtime.to_le_bytes());
+    out.extend_from_slice(&[0, 255]);
+    let mut chunks = data.chunks(STORED_MAX).peekable();
+    if chunks.peek().is_none() {
+        out.extend_from_slice(&[1, 0, 0, 0xFF, 0xFF]);
+    }
+    while let Some(chunk) = chunks.next() {
+        out.push(u8::from(chunks.peek().is_none()));
+        let len = chunk.len() as u16;
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(chunk);
+    }
+    out.extend_from_slice(&crc32(data).to_le_bytes());
+    out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    out
+}
+
+/// The contents of a gzip file of stored blocks, as `gzip` writes them
+fn gunzip(file: &[u8]) -> Result<Vec<u8>, String> {
+    let truncated = || "the file is cut short".to_string();
+    if file.len() < 18 || file[..2] != GZIP_MAGIC || file[2] != 8 {
+        return Err("not a gzip file".to_string());
+    }
+    let flags = file[3];
+    let mut at = 10;
+    if flags & 0x04 != 0 {
+        let extra = file.get(at..at + 2).ok_or_else(truncated)?;
+        at += 2 + usize::from(u16::from_le_bytes([extra[0], extra[1]]));
+    }
+    for flag in [0x08, 0x10] {
+        if flags & flag != 0 {
+            let end = file[at.min(file.len())..]
+                .iter()
+                .position(|byte| *byte == 0)
+                .ok_or_else(truncated)?;
+            at += end + 1;
+        }
+    }
+    if flags & 0x02 != 0 {
+        at += 2;
+    }
+
+    let mut data = Vec::new();
+    loop {
+        let block = *file.get(at).ok_or_else(truncated)?;
+        if (block >> 1) & 0b11 != 0 {
+            return Err(
+                "it was recompressed; only archives as backup export writes them can be read \
+                 (gunzip and gzip -0 turn it back into one)"
+                    .to_string(),
+            );
+        }
+        let lengths = file.get(at + 1..at + 5).ok_or_else(truncated)?;
+        let len = u16::from_le_bytes([lengths[0], lengths[1]]);
+        if !len != u16::from_le_bytes([lengths[2], lengths[3]]) {
+            return Err("a block length does not check out".to_string());
+        }
+        let start = at + 5;
+        let end = start + usize::from(len);
+        data.extend_from_slice(file.get(start..end).ok_or_else(truncated)?);
+        at = end;
+        if block & 1 == 1 {
+            break;
+        }
+    }
+    let trailer = file.get(at..at + 8).ok_or_else(truncated)?;
+    let crc = u32::from_le_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]);
+    let size = u32::from_le_bytes([trailer[4], trailer[5], trailer[6], trailer[7]]);
+    if crc != crc32(&data) || size != data.len() as u32 {
+        return Err("the contents fail their checksum".to_string());
+    }
+    Ok(data)
+}
+
+/// The members as a `.tar.gz`, each stamped with `mtime` (seconds since the epoch)
+pub fn write(members: &[Member], mtime: u64) -> Result<Vec<u8>, CliError> {
+    Ok(gzip(&tar(members, mtime)?, mtime as u32))
+}
+
+/// The members of a `.tar.gz` read by `what`, in archive order
+pub fn read(what: &str, file: &[u8]) -> Result<Vec<Member>, CliError> {
+    gunzip(file)
+        .and_then(|archive| untar(&archive))
+        .map_err(|reason| CliError::parse(what.to_string(), reason))
+}
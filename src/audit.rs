@@ -0,0 +1,292 @@
+use crate::clock;
+use crate::error::{CliError, ResultExt};
+use crate::paths;
+use crate::purge::Removal;
+use crate::snapshot::Snapshot;
+use crate::storage;
+use crate::OutputFormat;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Flag names whose value is replaced before an invocation is logged
+const SECRET_WORDS: &[&str] = &[
+    "token",
+    "password",
+    "secret",
+    "key",
+    "credential",
+    "authorization",
+];
+pub const REDACTED: &str = "[redacted]";
+
+/// One mutating invocation, as stored one JSON object per line
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Entry {
+    pub timestamp: DateTime<Utc>,
+    pub user: String,
+    pub profile: String,
+    pub command: String,
+    pub arguments: Vec<String>,
+    #[serde(default)]
+    pub job_ids: Vec<String>,
+    /// Field values of changed jobs from before the change, by job ID, so it can be undone
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub previous: BTreeMap<String, serde_json::Value>,
+    /// Why the jobs were cancelled, when the command cancelled any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cancel_reason: Option<String>,
+    /// Transmissions scheduled into quiet hours with --override-quiet-hours, one per job
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub quiet_hours_overrides: Vec<String>,
+    /// What went into each job the command submitted, unless the profile turns this off
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub snapshots: Vec<Snapshot>,
+    /// "success", or the exit status it failed with
+    pub outcome: String,
+}
+
+/// The entry for the running command, filled in as it goes and written at exit
+static CURRENT: Mutex<Option<Entry>> = Mutex::new(None);
+
+pub fn log_path() -> PathBuf {
+    paths::state_dir().join("audit.jsonl")
+}
+
+/// Operating system account running the CLI
+pub fn username() -> String {
+    ["USER", "USERNAME", "LOGNAME"]
+        .iter()
+        .find_map(|var| std::env::var(var).ok().filter(|v| !v.is_empty()))
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// A flag, setting or header name whose value should not be written down
+pub fn is_secret_name(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    SECRET_WORDS.iter().any(|word| name.contains(word))
+}
+
+fn is_secret_flag(flag: &str) -> bool {
+    flag.starts_with("--") && is_secret_name(flag.trim_start_matches('-'))
+}
+
+/// Command-line arguments with the values of secret-looking flags replaced
+pub fn redact(arguments: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut redacted = Vec::new();
+    let mut hide_next = false;
+    for argument in arguments {
+        if hide_next {
+            redacted.push(REDACTED.to_string());
+            hide_next = false;
+            continue;
+        }
+        match argument.split_once('=') {
+            Some((flag, _)) if is_secret_flag(flag) => {
+                redacted.push(format!("{}={}", flag, REDACTED));
+            }
+            _ => {
+                hide_next = is_secret_flag(&argument);
+                redacted.push(argument);
+            }
+        }
+    }
+    redacted
+}
+
+/// Start recording a mutating command run with `arguments`; written out by `finish`
+pub fn begin(command: &str, profile: &str, arguments: Vec<String>) {
+    let entry = Entry {
+        timestamp: clock::now(),
+        user: username(),
+        profile: profile.to_string(),
+        command: command.to_string(),
+        arguments: redact(arguments),
+        job_ids: Vec::new(),
+        previous: BTreeMap::new(),
+        cancel_reason: None,
+        quiet_hours_overrides: Vec::new(),
+        snapshots: Vec::new(),
+        outcome: "success".to_string(),
+    };
+    *CURRENT.lock().unwrap_or_else(|e| e.into_inner()) = Some(entry);
+}
+
+/// Note a job the running command created or changed
+pub fn job(id: &str) {
+    if let Some(entry) = CURRENT.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+        entry.job_ids.push(id.to_string());
+    }
+}
+
+/// Note what a job's changed fields held before the running command changed them
+pub fn previous(id: &str, fields: serde_json::Value) {
+    if let Some(entry) = CURRENT.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+        entry.previous.insert(id.to_string(), fields);
+    }
+}
+
+/// Note why the running command cancels jobs
+pub fn reason(reason: &str) {
+    if let Some(entry) = CURRENT.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+        entry.cancel_reason = Some(reason.to_string());
+    }
+}
+
+/// Note a transmission the running command schedules into quiet hours
+pub fn quiet_hours_override(overlap: &str) {
+    if let Some(entry) = CURRENT.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+        entry.quiet_hours_overrides.push(overlap.to_string());
+    }
+}
+
+/// Keep a snapshot of a job the running command submitted
+pub fn snapshot(snapshot: Snapshot) {
+    if let Some(entry) = CURRENT.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+        entry.snapshots.push(snapshot);
+    }
+}
+
+fn append(entry: &Entry) -> Result<(), CliError> {
+    let path = log_path();
+    // Other invocations append to the same file, and purge replaces it; hold the lock
+    // for the whole line
+    let _lock = storage::lock(&path)?;
+    let mut options = std::fs::OpenOptions::new();
+    options.create(true).append(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(&path)
+        .with_context(|| format!("opening {}", path.display()))?;
+    let mut line = serde_json::to_string(entry).map_err(|e| CliError::parse("audit entry", e))?;
+    line.push('\n');
+    file.write_all(line.as_bytes())
+        .with_context(|| format!("writing {}", path.display()))
+}
+
+/// Write the running command's entry with its exit status.
+/// Never fails the command: a log that cannot be written only produces a warning.
+pub fn finish(code: i32) {
+    let Some(mut entry) = CURRENT.lock().unwrap_or_else(|e| e.into_inner()).take() else {
+        return;
+    };
+    if code != crate::error::EXIT_SUCCESS {
+        entry.outcome = format!("failed (exit status {})", code);
+    }
+    if let Err(e) = append(&entry) {
+        eprintln!("⚠️ Could not write the audit log: {}", e.full_message());
+    }
+}
+
+/// Every readable entry of the log, oldest first; a missing log has none
+pub fn read() -> Result<Vec<Entry>, CliError> {
+    let path = log_path();
+    let mut entries = Vec::new();
+    if path.exists() {
+        let file =
+            std::fs::File::open(&path).with_context(|| format!("opening {}", path.display()))?;
+        for (number, line) in std::io::BufReader::new(file).lines().enumerate() {
+            let line = line.with_context(|| format!("reading {}", path.display()))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<Entry>(&line) {
+                Ok(entry) => entries.push(entry),
+                Err(e) => eprintln!(
+                    "⚠️ Skipping unreadable line {} of {}: {}",
+                    number + 1,
+                    path.display(),
+                    e
+                ),
+            }
+        }
+    }
+    Ok(entries)
+}
+
+/// `purge`: drop the entries recorded before `cutoff`. Lines that do not parse carry no
+/// date and are kept. Only counts unless `apply`.
+pub fn purge(cutoff: DateTime<Utc>, apply: bool) -> Result<Removal, CliError> {
+    let path = log_path();
+    if !path.exists() {
+        return Ok(Removal::default());
+    }
+    let _lock = storage::lock(&path)?;
+    let contents =
+        std::fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+    let mut removal = Removal::default();
+    let mut kept = String::new();
+    for line in contents.lines() {
+        if serde_json::from_str::<Entry>(line).is_ok_and(|entry| entry.timestamp < cutoff) {
+            removal.entries += 1;
+            removal.bytes += line.len() as u64 + 1;
+        } else if !line.trim().is_empty() {
+            kept.push_str(line);
+            kept.push('\n');
+        }
+    }
+    if apply && removal.entries > 0 {
+        storage::write_atomic(&path, kept)?;
+    }
+    Ok(removal)
+}
+
+/// Print the audit log, oldest first, optionally from a time on and for one user
+pub fn show(
+    since: Option<DateTime<Utc>>,
+    user: Option<&str>,
+    output: OutputFormat,
+) -> Result<(), CliError> {
+    let path = log_path();
+    let mut entries = read()?;
+    entries.retain(|entry| {
+        since.is_none_or(|since| entry.timestamp >= since)
+            && user.is_none_or(|user| entry.user == user)
+    });
+
+    if output == OutputFormat::Json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&entries)
+                .map_err(|e| CliError::parse("audit entries", e))?
+        );
+        return Ok(());
+    }
+    if entries.is_empty() {
+        println!("📭 No audit entries in {}", path.display());
+        return Ok(());
+    }
+
+    println!("🧾 {} audit entr(ies) in {}", entries.len(), path.display());
+    for entry in &entries {
+        let jobs = if entry.job_ids.is_empty() {
+            String::new()
+        } else {
+            format!(" [jobs {}]", entry.job_ids.join(", "))
+        };
+        println!(
+            "  {}  {}@{}  {}{}  → {}",
+            entry.timestamp.format("%Y-%m-%d %H:%M:%S"),
+            entry.user,
+            entry.profile,
+            entry.command,
+            jobs,
+            entry.outcome
+        );
+        println!("      rustar-cli {}", entry.arguments.join(" "));
+        if let Some(reason) = &entry.cancel_reason {
+            println!("      reason: {}", reason);
+        }
+        for overlap in &entry.quiet_hours_overrides {
+            println!("      quiet hours overridden: {}", overlap);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,674 @@
+use crate::archive::{self, Member};
+use crate::audit;
+use crate::clock;
+use crate::config::{self, Config};
+use crate::error::{CliError, ResultExt};
+use crate::history;
+use crate::humanize;
+use crate::output::OutputTarget;
+use crate::paths;
+use crate::satellites::{self, AliasBook};
+use crate::schema::{Store, VERSION_KEY};
+use crate::storage;
+use crate::transcript::Recorded;
+use crate::tty;
+use crate::OutputFormat;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// The archive member describing the rest; written first
+const MANIFEST: &str = "manifest.json";
+/// Layout of the manifest this CLI writes
+const FORMAT: u32 = 1;
+
+/// What `backup import` does with a category this machine already has
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Policy {
+    /// Keep this machine's copy
+    Skip,
+    /// Replace it with the backup's copy
+    Overwrite,
+    /// Add what the backup has and this machine lacks; what is here wins
+    Merge,
+}
+
+impl std::fmt::Display for Policy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            Policy::Merge => "🔀 Merge: add what is missing here, keep what is here",
+            Policy::Skip => "⏭️ Skip: keep this machine's copy",
+            Policy::Overwrite => "♻️ Overwrite: replace it with the backup's copy",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// `--config`, `--aliases`, `--history` and `--audit`; a category left out is asked about
+/// when this machine has it too
+#[derive(Debug, Default)]
+pub struct Policies {
+    pub config: Option<Policy>,
+    pub aliases: Option<Policy>,
+    pub history: Option<Policy>,
+    pub audit: Option<Policy>,
+}
+
+impl Policies {
+    fn get(&self, category: Category) -> Option<Policy> {
+        match category {
+            Category::Config => self.config,
+            Category::Aliases => self.aliases,
+            Category::History => self.history,
+            Category::Audit => self.audit,
+        }
+    }
+}
+
+/// A part of the local state, backed up and restored as a whole
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Category {
+    Config,
+    Aliases,
+    History,
+    Audit,
+}
+
+const CATEGORIES: [Category; 4] = [
+    Category::Config,
+    Category::Aliases,
+    Category::History,
+    Category::Audit,
+];
+
+impl Category {
+    /// As in the manifest and the import flags
+    fn name(self) -> &'static str {
+        match self {
+            Category::Config => "config",
+            Category::Aliases => "aliases",
+            Category::History => "history",
+            Category::Audit => "audit",
+        }
+    }
+
+    fn describe(self) -> &'static str {
+        match self {
+            Category::Config => "config file",
+            Category::Aliases => "alias book",
+            Category::History => "prompt history",
+            Category::Audit => "audit log",
+        }
+    }
+
+    /// Name of its file in the archive
+    fn member(self) -> &'static str {
+        match self {
+            Category::Config => "config.toml",
+            Category::Aliases => "satellites.toml",
+            Category::History => "prompt_history.json",
+            Category::Audit => "audit.jsonl",
+        }
+    }
+
+    fn path(self) -> PathBuf {
+        match self {
+            Category::Config => config::config_path(),
+            Category::Aliases => satellites::book_path(),
+            Category::History => paths::prompt_history_file(),
+            Category::Audit => audit::log_path(),
+        }
+    }
+
+    /// The store whose migrations bring an older copy up to date; the config file and the
+    /// audit log carry no layout version
+    fn store(self) -> Option<&'static Store> {
+        match self {
+            Category::Aliases => Some(&satellites::STORE),
+            Category::History => Some(&history::STORE),
+            Category::Config | Category::Audit => None,
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        CATEGORIES
+            .into_iter()
+            .find(|category| category.name() == name)
+    }
+}
+
+/// `manifest.json`
+#[derive(Debug, Serialize, Deserialize)]
+struct Manifest {
+    format: u32,
+    created_at: DateTime<Utc>,
+    cli_version: String,
+    /// Whether the config file kept its passwords, tokens and keys
+    secrets: bool,
+    /// Category name to its file in the archive
+    categories: BTreeMap<String, Listed>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Listed {
+    file: String,
+    /// Layout version of the file when it was backed up
+    version: u32,
+}
+
+/// Drop every setting of the config file named like a secret, at any depth. Returns the
+/// file without them and how many there were.
+fn without_secrets(path: &Path, contents: &str) -> Result<(String, usize), CliError> {
+    fn strip(table: &mut toml::Table) -> usize {
+        let secret: Vec<String> = table
+            .keys()
+            .filter(|key| audit::is_secret_name(key))
+            .cloned()
+            .collect();
+        for key in &secret {
+            table.remove(key);
+        }
+        secret.len()
+            + table
+                .iter_mut()
+                .map(|(_, value)| match value {
+                    toml::Value::Table(table) => strip(table),
+                    toml::Value::Array(items) => items
+                        .iter_mut()
+                        .filter_map(toml::Value::as_table_mut)
+                        .map(strip)
+                        .sum(),
+                    _ => 0,
+                })
+                .sum::<usize>()
+    }
+
+    let mut table: toml::Table = toml::from_str(contents)
+        .map_err(|e| CliError::parse(format!("config file {}", path.display()), e))?;
+    let removed = strip(&mut table);
+    let contents =
+        toml::to_string_pretty(&table).map_err(|e| CliError::parse("config file contents", e))?;
+    Ok((contents, removed))
+}
+
+/// `backup export`: the config file, alias book, prompt history and audit log that exist,
+/// with a manifest of their layout versions, as a `.tar.gz`
+pub fn export(target: &OutputTarget, no_secrets: bool) -> Result<(), CliError> {
+    target.check()?;
+    let now = clock::now();
+    let mut members = Vec::new();
+    let mut categories = BTreeMap::new();
+    let mut included = Vec::new();
+    let mut secrets = 0;
+    for category in CATEGORIES {
+        let path = category.path();
+        if !path.exists() {
+            continue;
+        }
+        let mut contents = std::fs::read_to_string(&path)
+            .with_context(|| format!("reading {}", path.display()))?;
+        let version = match category.store() {
+            Some(store) => store.version(&path, &contents)?,
+            None => 1,
+        };
+        if category == Category::Config {
+            let (stripped, removed) = without_secrets(&path, &contents)?;
+            secrets = removed;
+            if no_secrets {
+                contents = stripped;
+            }
+        }
+        categories.insert(
+            category.name().to_string(),
+            Listed {
+                file: category.member().to_string(),
+                version,
+            },
+        );
+        members.push(Member {
+            name: category.member().to_string(),
+            contents: contents.into_bytes(),
+        });
+        included.push(category.describe());
+    }
+    if members.is_empty() {
+        return Err(CliError::validation(
+            "backup",
+            "there is nothing to back up: no config file, alias book, prompt history or audit \
+             log yet",
+        ));
+    }
+
+    let manifest = Manifest {
+        format: FORMAT,
+        created_at: now,
+        cli_version: env!("CARGO_PKG_VERSION").to_string(),
+        secrets: !no_secrets,
+        categories,
+    };
+    let manifest =
+        serde_json::to_vec_pretty(&manifest).map_err(|e| CliError::parse("backup manifest", e))?;
+    members.insert(
+        0,
+        Member {
+            name: MANIFEST.to_string(),
+            contents: manifest,
+        },
+    );
+    target.write(archive::write(&members, now.timestamp().max(0) as u64)?)?;
+
+    eprintln!("📦 Backed up the {}", included.join(", the "));
+    match (secrets, no_secrets) {
+        (0, _) => {}
+        (n, true) => eprintln!("🔒 Left out {} secret setting(s) of the config file", n),
+        (n, false) => eprintln!(
+            "⚠️ The backup holds {} secret setting(s) of the config file; keep it private, or \
+             pass --no-secrets",
+            n
+        ),
+    }
+    Ok(())
+}
+
+/// What a category of the backup became
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Outcome {
+    /// Nothing was here, so the backup's copy was taken
+    Restored,
+    Merged,
+    Overwritten,
+    Skipped,
+}
+
+impl Outcome {
+    fn label(self) -> &'static str {
+        match self {
+            Outcome::Restored => "restored",
+            Outcome::Merged => "merged",
+            Outcome::Overwritten => "overwritten",
+            Outcome::Skipped => "skipped",
+        }
+    }
+
+    fn of(policy: Option<Policy>) -> Self {
+        match policy {
+            None => Outcome::Restored,
+            Some(Policy::Merge) => Outcome::Merged,
+            Some(Policy::Overwrite) => Outcome::Overwritten,
+            Some(Policy::Skip) => Outcome::Skipped,
+        }
+    }
+}
+
+/// A category's new contents, worked out in full before anything is written
+enum Contents {
+    Store(&'static Store, Value),
+    Text(String),
+}
+
+struct Planned {
+    category: Category,
+    path: PathBuf,
+    outcome: Outcome,
+    contents: Option<Contents>,
+}
+
+fn merge_config(mut local: toml::Table, incoming: toml::Table) -> toml::Table {
+    for (key, value) in incoming {
+        match (local.get_mut(&key), value) {
+            (Some(toml::Value::Table(profiles)), toml::Value::Table(incoming))
+                if key == "profiles" =>
+            {
+                for (name, profile) in incoming {
+                    profiles.entry(name).or_insert(profile);
+                }
+            }
+            (Some(_), _) => {}
+            (None, value) => {
+                local.insert(key, value);
+            }
+        }
+    }
+    local
+}
+
+/// Aliases and groups of `incoming` that `local` lacks, added to it
+fn merge_aliases(mut local: Value, incoming: Value) -> Value {
+    let (Some(fields), Value::Object(incoming)) = (local.as_object_mut(), incoming) else {
+        return local;
+    };
+    for (key, value) in incoming {
+        match (fields.get_mut(&key), value) {
+            (Some(Value::Object(groups)), Value::Object(incoming)) if key == "groups" => {
+                for (name, members) in incoming {
+                    groups.entry(name).or_insert(members);
+                }
+            }
+            (Some(_), _) => {}
+            (None, value) => {
+                fields.insert(key, value);
+            }
+        }
+    }
+    local
+}
+
+/// Answers of `incoming` that `local` lacks, added to each prompt's list, newest first
+fn merge_history(local: Value, incoming: Value) -> Value {
+    let prompts = |document: &Value| -> BTreeMap<String, Vec<Value>> {
+        document["prompts"]
+            .as_object()
+            .map(|prompts| {
+                prompts
+                    .iter()
+                    .map(|(name, answers)| {
+                        (
+                            name.clone(),
+                            answers.as_array().cloned().unwrap_or_default(),
+                        )
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let mut merged = prompts(&local);
+    for (name, answers) in prompts(&incoming) {
+        let kept = merged.entry(name).or_default();
+        for answer in answers {
+            let known = kept.iter().any(|other| {
+                other["value"] == answer["value"] && other["profile"] == answer["profile"]
+            });
+            if !known {
+                kept.push(answer);
+            }
+        }
+        // Dated answers newest first; undated ones, from before dates were kept, last
+        kept.sort_by_key(|answer| {
+            std::cmp::Reverse(
+                answer["at"]
+                    .as_str()
+                    .and_then(|at| DateTime::parse_from_rfc3339(at).ok()),
+            )
+        });
+        kept.truncate(history::LIMIT);
+    }
+    json!({ "prompts": merged })
+}
+
+/// Every line of either log once, in time order; lines that do not parse go last
+fn merge_audit(local: &str, incoming: &str) -> String {
+    let lines: BTreeSet<&str> = local
+        .lines()
+        .chain(incoming.lines())
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    let mut lines: Vec<(Option<DateTime<Utc>>, &str)> = lines
+        .into_iter()
+        .map(|line| {
+            let at = serde_json::from_str::<audit::Entry>(line)
+                .ok()
+                .map(|entry| entry.timestamp);
+            (at, line)
+        })
+        .collect();
+    lines.sort_by_key(|(at, _)| (at.is_none(), *at));
+    lines
+        .into_iter()
+        .map(|(_, line)| format!("{}\n", line))
+        .collect()
+}
+
+/// Ask what to do with a category this machine has too
+fn choose(category: Category) -> Result<Policy, CliError> {
+    tty::require(
+        "backup import",
+        &[&format!("--{} <skip|overwrite|merge>", category.name())],
+    )?;
+    let question = format!(
+        "This machine has a {} already. What should happen to the backup's?",
+        category.describe()
+    );
+    Ok(inquire::Select::new(
+        &question,
+        vec![Policy::Merge, Policy::Skip, Policy::Overwrite],
+    )
+    .prompt()
+    .recorded(&question)?)
+}
+
+/// Work out what one category becomes, reading this machine's copy; nothing is written
+fn plan(
+    category: Category,
+    origin: &Path,
+    incoming: &str,
+    policy: Option<Policy>,
+) -> Result<Planned, CliError> {
+    let path = category.path();
+    let policy = match (path.exists(), policy) {
+        (false, _) => None,
+        (true, Some(policy)) => Some(policy),
+        (true, None) => Some(choose(category)?),
+    };
+    let outcome = Outcome::of(policy);
+    let mut planned = Planned {
+        category,
+        path,
+        outcome,
+        contents: None,
+    };
+    if policy == Some(Policy::Skip) {
+        return Ok(planned);
+    }
+    let merge = policy == Some(Policy::Merge);
+    let path = &planned.path;
+
+    // A copy taken as it is keeps its comments and layout; only merged or upgraded ones
+    // are written afresh
+    planned.contents = Some(match category.store() {
+        Some(store) => {
+            let current = store.version(origin, incoming)? == store.current();
+            let upgraded = store.upgrade(origin, incoming)?;
+            let document = match store.read(path)? {
+                Some(local) if merge => match category {
+                    Category::History => merge_history(local, upgraded),
+                    _ => merge_aliases(local, upgraded),
+                },
+                _ => upgraded,
+            };
+            if category == Category::Aliases {
+                let mut book = document.clone();
+                if let Some(fields) = book.as_object_mut() {
+                    fields.remove(VERSION_KEY);
+                }
+                serde_json::from_value::<AliasBook>(book)
+                    .map_err(|e| CliError::parse(format!("alias book {}", origin.display()), e))?;
+            }
+            if current && !merge {
+                Contents::Text(incoming.to_string())
+            } else {
+                Contents::Store(store, document)
+            }
+        }
+        None if category == Category::Config => {
+            let parse = |what: &Path, contents: &str| {
+                toml::from_str::<toml::Table>(contents)
+                    .map_err(|e| CliError::parse(format!("config file {}", what.display()), e))
+            };
+            let contents = if merge {
+                let local = std::fs::read_to_string(path)
+                    .with_context(|| format!("reading {}", path.display()))?;
+                let table = merge_config(parse(path, &local)?, parse(origin, incoming)?);
+                toml::to_string_pretty(&table)
+                    .map_err(|e| CliError::parse("config file contents", e))?
+            } else {
+                incoming.to_string()
+            };
+            toml::from_str::<Config>(&contents)
+                .map_err(|e| CliError::parse(format!("config file {}", origin.display()), e))?;
+            Contents::Text(contents)
+        }
+        None => {
+            let contents = if merge {
+                let local = std::fs::read_to_string(path)
+                    .with_context(|| format!("reading {}", path.display()))?;
+                merge_audit(&local, incoming)
+            } else {
+                incoming.to_string()
+            };
+            Contents::Text(contents)
+        }
+    });
+    Ok(planned)
+}
+
+#[derive(Serialize)]
+struct Report {
+    category: &'static str,
+    path: PathBuf,
+    outcome: Outcome,
+}
+
+/// `backup import`: restore the categories of a backup, each decided by `policies` or
+/// asked about. Every category is worked out before any is written, and each file is
+/// replaced whole, so a failure leaves none half-merged.
+pub fn import(file: &Path, policies: &Policies, output: OutputFormat) -> Result<(), CliError> {
+    let bytes = std::fs::read(file).with_context(|| format!("reading {}", file.display()))?;
+    let what = format!("backup {}", file.display());
+    let members = archive::read(&what, &bytes)?;
+    let member = |name: &str| members.iter().find(|member| member.name == name);
+    let text = |member: &Member| {
+        String::from_utf8(member.contents.clone())
+            .map_err(|e| CliError::parse(format!("{} in {}", member.name, what), e))
+    };
+
+    let manifest = member(MANIFEST)
+        .ok_or_else(|| CliError::parse(what.clone(), format!("there is no {}", MANIFEST)))?;
+    let manifest: Manifest = serde_json::from_slice(&manifest.contents)
+        .map_err(|e| CliError::parse(format!("{} in {}", MANIFEST, what), e))?;
+    if manifest.format > FORMAT {
+        return Err(CliError::Configuration(format!(
+            "{} is a format {} backup, written by rustar-cli {}; this one reads up to format {}",
+            file.display(),
+            manifest.format,
+            manifest.cli_version,
+            FORMAT
+        )));
+    }
+    if output != OutputFormat::Json {
+        println!(
+            "📦 Backup of {} by rustar-cli {}",
+            humanize::timestamp(manifest.created_at),
+            manifest.cli_version
+        );
+    }
+
+    // Held until every category is written, so no other rustar process changes a file
+    // between reading it for a merge and replacing it
+    let mut _locks = Vec::new();
+    let mut planned = Vec::new();
+    for category in CATEGORIES {
+        let Some(listed) = manifest.categories.get(category.name()) else {
+            continue;
+        };
+        let member = member(&listed.file).ok_or_else(|| {
+            CliError::parse(
+                what.clone(),
+                format!(
+                    "the manifest lists {} but the archive has no {}",
+                    category.name(),
+                    listed.file
+                ),
+            )
+        })?;
+        if let Some(store) = category.store() {
+            if listed.version < store.current() && output != OutputFormat::Json {
+                println!(
+                    "   The {} is version {}; it is upgraded to version {} on the way in",
+                    category.describe(),
+                    listed.version,
+                    store.current()
+                );
+            }
+        }
+        _locks.push(storage::lock(&category.path())?);
+        let origin = PathBuf::from(format!("{}:{}", file.display(), listed.file));
+        let contents = text(member)?;
+        planned.push(
+            plan(category, &origin, &contents, policies.get(category))
+                .with_context(|| format!("restoring the {}", category.describe()))?,
+        );
+    }
+    for name in manifest.categories.keys() {
+        if Category::from_name(name).is_none() {
+            eprintln!(
+                "⚠️ Left out '{}', which this rustar-cli does not know how to restore",
+                name
+            );
+        }
+    }
+
+    let mut reports = Vec::new();
+    for planned in planned {
+        match planned.contents {
+            Some(Contents::Store(store, document)) => store.write(&planned.path, &document)?,
+            Some(Contents::Text(contents)) => storage::write_atomic(&planned.path, contents)?,
+            None => {}
+        }
+        // A new copy of the config file or the audit log is for its owner only, as the
+        // CLI creates them
+        #[cfg(unix)]
+        if planned.outcome == Outcome::Restored
+            && matches!(planned.category, Category::Config | Category::Audit)
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&planned.path, std::fs::Permissions::from_mode(0o600))
+                .with_context(|| format!("restricting {}", planned.path.display()))?;
+        }
+        if output != OutputFormat::Json {
+            println!(
+                "  {} {}: {} ({})",
+                if planned.outcome == Outcome::Skipped {
+                    "⏭️"
+                } else {
+                    "✅"
+                },
+                planned.category.describe(),
+                planned.outcome.label(),
+                planned.path.display()
+            );
+        }
+        if planned.category == Category::Config
+            && planned.outcome != Outcome::Skipped
+            && !manifest.secrets
+            && output != OutputFormat::Json
+        {
+            println!(
+                "ℹ️ The backup was made with --no-secrets: set passwords, tokens and keys again \
+                 in {}",
+                planned.path.display()
+            );
+        }
+        reports.push(Report {
+            category: planned.category.name(),
+            path: planned.path,
+            outcome: planned.outcome,
+        });
+    }
+    if output == OutputFormat::Json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&json!({
+                "created_at": manifest.created_at,
+                "cli_version": manifest.cli_version,
+                "secrets": manifest.secrets,
+                "categories": reports,
+            }))
+            .map_err(|e| CliError::parse("import report", e))?
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,206 @@
+use crate::client::{JobRequestDTO, JobType};
+use crate::frequency::Frequency;
+
+/// Which way a band carries satellite traffic
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Satellites listen here: uplinks
+    EarthToSpace,
+    /// Satellites transmit here: downlinks
+    SpaceToEarth,
+    /// Allocated without a direction, as the amateur-satellite bands are
+    Either,
+}
+
+impl Direction {
+    fn describe(self) -> &'static str {
+        match self {
+            Direction::EarthToSpace => "Earth-to-space",
+            Direction::SpaceToEarth => "space-to-Earth",
+            Direction::Either => "either direction",
+        }
+    }
+}
+
+/// A slice of the spectrum allocated to a satellite service, edges included
+#[derive(Debug, Clone, Copy)]
+pub struct Segment {
+    pub low: Frequency,
+    pub high: Frequency,
+    pub service: &'static str,
+    pub direction: Direction,
+}
+
+const fn segment(
+    low_khz: u64,
+    high_khz: u64,
+    service: &'static str,
+    direction: Direction,
+) -> Segment {
+    Segment {
+        low: Frequency::from_hz(low_khz * 1000),
+        high: Frequency::from_hz(high_khz * 1000),
+        service,
+        direction,
+    }
+}
+
+/// The satellite allocations stations commonly work, after the ITU Radio Regulations
+/// Article 5 table; where the regions differ, the allocation they share. Not a complete
+/// table: a frequency outside every segment draws no hint.
+pub const SEGMENTS: &[Segment] = &[
+    segment(
+        137_000,
+        138_000,
+        "weather satellite APT and space operation",
+        Direction::SpaceToEarth,
+    ),
+    segment(144_000, 146_000, "2 m amateur-satellite", Direction::Either),
+    segment(
+        148_000,
+        149_900,
+        "mobile-satellite and space operation",
+        Direction::EarthToSpace,
+    ),
+    segment(
+        399_900,
+        400_050,
+        "mobile-satellite",
+        Direction::EarthToSpace,
+    ),
+    segment(
+        400_150,
+        401_000,
+        "meteorological-satellite and space research",
+        Direction::SpaceToEarth,
+    ),
+    segment(
+        401_000,
+        403_000,
+        "meteorological-satellite data collection",
+        Direction::EarthToSpace,
+    ),
+    segment(
+        435_000,
+        438_000,
+        "70 cm amateur-satellite",
+        Direction::Either,
+    ),
+    segment(
+        1_260_000,
+        1_270_000,
+        "23 cm amateur-satellite",
+        Direction::EarthToSpace,
+    ),
+    segment(
+        1_525_000,
+        1_559_000,
+        "L-band mobile-satellite",
+        Direction::SpaceToEarth,
+    ),
+    segment(
+        1_626_500,
+        1_660_500,
+        "L-band mobile-satellite",
+        Direction::EarthToSpace,
+    ),
+    segment(
+        1_690_000,
+        1_710_000,
+        "meteorological-satellite HRPT",
+        Direction::SpaceToEarth,
+    ),
+    segment(
+        2_025_000,
+        2_110_000,
+        "S-band space research and operation",
+        Direction::EarthToSpace,
+    ),
+    segment(
+        2_200_000,
+        2_290_000,
+        "S-band space research and operation",
+        Direction::SpaceToEarth,
+    ),
+    segment(
+        2_400_000,
+        2_450_000,
+        "13 cm amateur-satellite",
+        Direction::Either,
+    ),
+    segment(
+        5_650_000,
+        5_670_000,
+        "6 cm amateur-satellite",
+        Direction::EarthToSpace,
+    ),
+    segment(
+        5_830_000,
+        5_850_000,
+        "6 cm amateur-satellite",
+        Direction::SpaceToEarth,
+    ),
+    segment(
+        7_190_000,
+        7_235_000,
+        "X-band space research",
+        Direction::EarthToSpace,
+    ),
+    segment(
+        8_025_000,
+        8_500_000,
+        "X-band Earth exploration and space research",
+        Direction::SpaceToEarth,
+    ),
+    segment(
+        10_450_000,
+        10_500_000,
+        "3 cm amateur-satellite",
+        Direction::Either,
+    ),
+];
+
+/// The segment `hz` falls in; where two meet, the one starting there
+pub fn lookup(hz: Frequency) -> Option<&'static Segment> {
+    SEGMENTS
+        .iter()
+        .rev()
+        .find(|s| (s.low..=s.high).contains(&hz))
+}
+
+/// A hint when `hz`, used for `role` ("RX" or "TX"), sits in a band allocated for the
+/// other direction
+fn hint(role: &str, hz: Frequency, wrong_way: Direction) -> Option<String> {
+    let segment = lookup(hz).filter(|s| s.direction == wrong_way)?;
+    let (listens, other) = match wrong_way {
+        Direction::EarthToSpace => ("satellites listen there", "the uplink"),
+        _ => ("satellites transmit there", "the downlink"),
+    };
+    Some(format!(
+        "{} {} is in {}–{} ({}), allocated {}: {}; is this {}?",
+        role,
+        hz,
+        segment.low,
+        segment.high,
+        segment.service,
+        segment.direction.describe(),
+        listens,
+        other
+    ))
+}
+
+/// Frequencies of a tracking job that look swapped: a downlink in an uplink band, or
+/// the other way round. Advisory; the allocation table cannot know every satellite.
+pub fn hints(job: &JobRequestDTO) -> Vec<String> {
+    if !matches!(job.job_type, JobType::Tracking { .. }) {
+        return Vec::new();
+    }
+    let mut hints = Vec::new();
+    if !job.rx_frequency.is_zero() {
+        hints.extend(hint("RX", job.rx_frequency, Direction::EarthToSpace));
+    }
+    if !job.tx_frequency.is_zero() {
+        hints.extend(hint("TX", job.tx_frequency, Direction::SpaceToEarth));
+    }
+    hints
+}
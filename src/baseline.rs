@@ -0,0 +1,249 @@
+//! What each satellite's jobs usually look like, from the jobs this CLI submitted, so the
+//! summary before a submission can point out a value that differs from the usual one.
+//! Informational only: nothing here stops a job.
+
+use crate::client::{JobRequestDTO, JobType};
+use crate::error::CliError;
+use crate::frequency::Frequency;
+use crate::humanize;
+use crate::paths;
+use crate::schema::{Format, Store};
+use crate::storage;
+use crate::text;
+use crate::tle;
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// `baselines.json`, the latest jobs for each satellite
+pub const STORE: Store = Store {
+    name: "job baseline index",
+    format: Format::Json,
+    migrations: &[],
+};
+
+/// Jobs kept for each satellite, the most recent last
+const KEPT: usize = 20;
+
+/// Fewer earlier jobs than this make no baseline
+pub const MIN_JOBS: usize = 3;
+
+/// The values of one job a baseline is made of
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Sample {
+    pub rx_frequency: Frequency,
+    pub tx_frequency: Frequency,
+    pub duration_seconds: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+}
+
+impl Sample {
+    pub fn of(job: &JobRequestDTO) -> Self {
+        Self {
+            rx_frequency: job.rx_frequency,
+            tx_frequency: job.tx_frequency,
+            duration_seconds: (job.end - job.start).num_seconds(),
+            mode: job.mode.clone(),
+        }
+    }
+}
+
+/// Satellite to its latest jobs
+type Index = BTreeMap<String, Vec<Sample>>;
+
+#[derive(Deserialize)]
+struct Layout {
+    satellites: Index,
+}
+
+fn index_path() -> PathBuf {
+    paths::baselines_file()
+}
+
+fn load() -> Result<Index, CliError> {
+    Ok(STORE
+        .load::<Layout>(&index_path())?
+        .map(|layout| layout.satellites)
+        .unwrap_or_default())
+}
+
+/// The catalog number of a tracking job's elements, else its name in NFC form; other
+/// jobs go by their name or kind ("loopback test")
+fn key(job: &JobRequestDTO) -> String {
+    match &job.job_type {
+        JobType::Tracking { tle } => tle::norad_id(&tle.tle1)
+            .map(|id| id.to_string())
+            .unwrap_or_else(|| text::nfc(tle.tle0.trim()).into_owned()),
+        _ => text::nfc(job.label()).into_owned(),
+    }
+}
+
+/// Add a submitted job to its satellite's baseline. Failures are warnings: the job
+/// exists either way.
+pub async fn remember(job: &JobRequestDTO) {
+    let result = storage::lock_async(&index_path()).await.and_then(|_lock| {
+        let mut index = load()?;
+        let samples = index.entry(key(job)).or_default();
+        samples.push(Sample::of(job));
+        let excess = samples.len().saturating_sub(KEPT);
+        samples.drain(..excess);
+        STORE.write(&index_path(), &json!({ "satellites": index }))
+    });
+    if let Err(e) = result {
+        eprintln!(
+            "⚠️ Could not add {} to the job baselines: {}",
+            job.label(),
+            e.full_message()
+        );
+    }
+}
+
+/// What sets `job` apart from its satellite's earlier jobs; an index that cannot be read
+/// has nothing to say
+pub fn hints(job: &JobRequestDTO) -> Vec<String> {
+    load()
+        .ok()
+        .and_then(|mut index| index.remove(&key(job)))
+        .map(|earlier| anomalies(&Sample::of(job), &earlier))
+        .unwrap_or_default()
+}
+
+/// The value used most among `values`, and by how many
+fn usual<T: Ord + Clone>(values: impl Iterator<Item = T>) -> Option<(T, usize)> {
+    let mut counts = BTreeMap::new();
+    for value in values {
+        *counts.entry(value).or_insert(0) += 1;
+    }
+    counts.into_iter().max_by_key(|(_, count)| *count)
+}
+
+/// "the previous 12 jobs", or "9 of the previous 12 jobs" when not all of them agree
+fn how_many(count: usize, of: usize) -> String {
+    match count == of {
+        true => format!("the previous {} jobs", of),
+        false => format!("{} of the previous {} jobs", count, of),
+    }
+}
+
+fn frequency(hz: Frequency) -> String {
+    match hz.is_zero() {
+        true => "none".to_string(),
+        false => hz.to_string(),
+    }
+}
+
+/// What sets `job` apart from `earlier`, the same satellite's earlier jobs: an RX or TX
+/// frequency or a mode other than the one they used most, or a duration more than half
+/// as long again as their median, or under half of it. Fewer than `MIN_JOBS` earlier
+/// jobs say nothing.
+pub fn anomalies(job: &Sample, earlier: &[Sample]) -> Vec<String> {
+    let of = earlier.len();
+    if of < MIN_JOBS {
+        return Vec::new();
+    }
+    let mut hints = Vec::new();
+    let rx: Vec<Frequency> = earlier.iter().map(|s| s.rx_frequency).collect();
+    let tx: Vec<Frequency> = earlier.iter().map(|s| s.tx_frequency).collect();
+    for (name, value, past) in [("RX", job.rx_frequency, rx), ("TX", job.tx_frequency, tx)] {
+        if let Some((typical, count)) =
+            usual(past.into_iter()).filter(|(typical, _)| *typical != value)
+        {
+            hints.push(format!(
+                "{} {} — {} used {}",
+                name,
+                frequency(value),
+                how_many(count, of),
+                frequency(typical)
+            ));
+        }
+    }
+    if let Some((typical, count)) =
+        usual(earlier.iter().map(|s| s.mode.clone())).filter(|(typical, _)| *typical != job.mode)
+    {
+        let mode = |mode: &Option<String>| {
+            mode.clone()
+                .unwrap_or_else(|| "the station default".to_string())
+        };
+        hints.push(format!(
+            "Mode {} — {} used {}",
+            mode(&job.mode),
+            how_many(count, of),
+            mode(&typical)
+        ));
+    }
+    let mut durations: Vec<i64> = earlier.iter().map(|s| s.duration_seconds).collect();
+    durations.sort_unstable();
+    let median = durations[of / 2];
+    if (job.duration_seconds - median).abs() * 2 > median {
+        hints.push(format!(
+            "Duration {} — the previous {} jobs ran {} at the median",
+            humanize::duration(Duration::seconds(job.duration_seconds)),
+            of,
+            humanize::duration(Duration::seconds(median))
+        ));
+    }
+    hints
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(rx_mhz: u64, minutes: i64, mode: Option<&str>) -> Sample {
+        Sample {
+            rx_frequency: Frequency::from_hz(rx_mhz * 100_000),
+            tx_frequency: Frequency::ZERO,
+            duration_seconds: minutes * 60,
+            mode: mode.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn fewer_than_three_earlier_jobs_give_no_hints() {
+        let earlier = vec![sample(1458, 10, None); 2];
+        assert!(anomalies(&sample(4375, 60, Some("BPSK")), &earlier).is_empty());
+    }
+
+    #[test]
+    fn the_usual_values_give_no_hints() {
+        let earlier = vec![sample(1458, 10, None); 5];
+        assert!(anomalies(&sample(1458, 10, None), &earlier).is_empty());
+    }
+
+    #[test]
+    fn any_other_frequency_is_flagged_against_the_most_used_one() {
+        let mut earlier = vec![sample(1458, 10, None); 3];
+        earlier.push(sample(4375, 10, None));
+        let hints = anomalies(&sample(4375, 10, None), &earlier);
+        assert_eq!(
+            hints,
+            ["RX 437.5 MHz — 3 of the previous 4 jobs used 145.8 MHz"]
+        );
+        let hints = anomalies(&sample(1459, 10, None), &vec![sample(1458, 10, None); 3]);
+        assert_eq!(hints, ["RX 145.9 MHz — the previous 3 jobs used 145.8 MHz"]);
+    }
+
+    #[test]
+    fn a_duration_is_flagged_only_past_half_again_or_under_half() {
+        let earlier = vec![sample(1458, 10, None); 3];
+        assert!(anomalies(&sample(1458, 15, None), &earlier).is_empty());
+        assert!(anomalies(&sample(1458, 5, None), &earlier).is_empty());
+        assert_eq!(
+            anomalies(&sample(1458, 16, None), &earlier),
+            ["Duration 16m — the previous 3 jobs ran 10m at the median"]
+        );
+        assert_eq!(anomalies(&sample(1458, 4, None), &earlier).len(), 1);
+    }
+
+    #[test]
+    fn a_different_mode_is_flagged() {
+        let earlier = vec![sample(1458, 10, Some("AFSK")); 3];
+        assert_eq!(
+            anomalies(&sample(1458, 10, None), &earlier),
+            ["Mode the station default — the previous 3 jobs used AFSK"]
+        );
+    }
+}
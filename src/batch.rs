@@ -0,0 +1,664 @@
+use crate::audit;
+use crate::baseline;
+use crate::batch_csv;
+use crate::campaign;
+use crate::capabilities;
+use crate::client::{ApiClient, JobRequestDTO, ServerWarning};
+use crate::clock;
+use crate::error::{self, CliError, ResultExt};
+use crate::examples::Example;
+use crate::findings::{self, Checks};
+use crate::hooks::HookRunner;
+use crate::input_schema;
+use crate::job_request::JobRequestBuilder;
+use crate::maintenance;
+use crate::output::OutputTarget;
+use crate::quiet_hours;
+use crate::regulatory::TxLimits;
+use crate::rotator;
+use crate::schedule::TimeWindow;
+use crate::snap;
+use crate::station;
+use crate::storage;
+use crate::submit_window;
+use crate::validation::{self, Problem, Rule};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// One entry of a batch file after pre-flight checks
+pub struct Entry {
+    pub index: usize,
+    /// The entry exactly as it appeared in the input
+    pub raw: serde_json::Value,
+    pub job: Option<JobRequestDTO>,
+    pub problems: Vec<Problem>,
+    /// How the window was moved onto the station's grid, when it was
+    pub snapped: Option<String>,
+}
+
+impl Entry {
+    fn is_valid(&self) -> bool {
+        self.job.is_some() && self.problems.is_empty()
+    }
+}
+
+/// Batch file layouts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum BatchFormat {
+    /// A JSON array of jobs, the layout the job dump writes
+    Json,
+    /// A schedule spreadsheet export with a header row
+    Csv,
+}
+
+impl BatchFormat {
+    /// Guess the format from the file extension, defaulting to JSON
+    pub fn detect(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("csv") => BatchFormat::Csv,
+            _ => BatchFormat::Json,
+        }
+    }
+}
+
+/// `--batch -`: the entries come from stdin
+pub fn is_stdin(path: &Path) -> bool {
+    path == Path::new("-")
+}
+
+/// Read a batch file, or stdin for "-", into raw entries in the JSON batch layout
+pub async fn load_values(
+    path: &Path,
+    format: BatchFormat,
+) -> Result<Vec<serde_json::Value>, CliError> {
+    match format {
+        BatchFormat::Json => {
+            let contents = if is_stdin(path) {
+                std::io::read_to_string(std::io::stdin()).context("reading the batch from stdin")?
+            } else {
+                std::fs::read_to_string(path)
+                    .with_context(|| format!("reading {}", path.display()))?
+            };
+            let value: serde_json::Value = serde_json::from_str(&contents)
+                .map_err(|e| CliError::parse(format!("batch file {}", path.display()), e))?;
+            match value {
+                serde_json::Value::Array(entries) => Ok(entries),
+                _ => Err(CliError::validation(
+                    "/",
+                    format!(
+                        "{} is not a JSON array of jobs (the layout is printed by rustar-cli schema jobs-batch)",
+                        path.display()
+                    ),
+                )),
+            }
+        }
+        BatchFormat::Csv => batch_csv::load(path).await,
+    }
+}
+
+/// Parse and validate every raw entry without submitting anything. Entries are checked
+/// against the job schema first, so a misnamed field is reported by its JSON pointer.
+/// TX entries get the profile's default power and are held to its licence limits.
+pub fn preflight(values: Vec<serde_json::Value>, limits: &TxLimits) -> Vec<Entry> {
+    let mut entries: Vec<Entry> = values
+        .into_iter()
+        .enumerate()
+        .map(|(index, raw)| (index, input_schema::check_job(&raw), raw))
+        .map(|(index, problems, raw)| {
+            if !problems.is_empty() {
+                return Entry {
+                    index,
+                    raw,
+                    job: None,
+                    problems,
+                    snapped: None,
+                };
+            }
+            match serde_json::from_value::<JobRequestDTO>(raw.clone()) {
+                Ok(job) => {
+                    let entered = (job.start, job.end);
+                    // Checked on the grid, so overlaps are found between the windows sent
+                    let job = JobRequestBuilder::from(job).tx_limits(limits).snapped();
+                    let mut problems = job.problems();
+                    problems.extend(submit_window::check(job.times().0));
+                    Entry {
+                        index,
+                        raw,
+                        problems,
+                        snapped: snap::describe(entered, job.times()),
+                        job: Some(job.into_draft()),
+                    }
+                }
+                Err(e) => Entry {
+                    index,
+                    raw,
+                    job: None,
+                    problems: vec![Problem::new("entry", e.to_string())],
+                    snapped: None,
+                },
+            }
+        })
+        .collect();
+
+    flag_internal_overlaps(&mut entries);
+    entries
+}
+
+/// Flag entries whose windows collide with an earlier valid entry in the same file
+fn flag_internal_overlaps(entries: &mut [Entry]) {
+    let windows: Vec<(usize, TimeWindow)> = entries
+        .iter()
+        .filter(|e| e.is_valid())
+        .filter_map(|e| e.job.as_ref().map(|job| (e.index, job.window())))
+        .collect();
+
+    for (later, &(index, window)) in windows.iter().enumerate() {
+        for &(other, other_window) in &windows[..later] {
+            if window.overlaps(&other_window) {
+                entries[index].problems.push(Problem::new(
+                    "start",
+                    format!(
+                        "window overlaps entry {} ({} – {})",
+                        other,
+                        other_window.start(),
+                        other_window.end()
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+enum Outcome {
+    Created {
+        job_id: Option<String>,
+        status: String,
+        /// Where to follow a job the server is still creating, with `--no-wait`
+        #[serde(skip_serializing_if = "Option::is_none")]
+        location: Option<String>,
+        /// What the server warned about while accepting the job
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        warnings: Vec<ServerWarning>,
+    },
+    Failed {
+        kind: &'static str,
+        status: Option<u16>,
+        message: String,
+    },
+    /// Sent, but timed out before the server answered: it may or may not be scheduled
+    Unconfirmed { message: String },
+    Skipped {
+        problems: Vec<String>,
+        /// With `--explain`, the limits behind the problems
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        rules: Vec<Rule>,
+    },
+}
+
+#[derive(Debug, Serialize)]
+struct EntryReport {
+    index: usize,
+    satellite: Option<String>,
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+    #[serde(flatten)]
+    outcome: Outcome,
+    submitted_at: Option<DateTime<Utc>>,
+    duration_ms: Option<u128>,
+}
+
+#[derive(Debug, Serialize)]
+struct BatchReport {
+    input: PathBuf,
+    started_at: DateTime<Utc>,
+    finished_at: Option<DateTime<Utc>>,
+    total: usize,
+    entries: Vec<EntryReport>,
+}
+
+/// Keeps the report and the retry file on disk up to date after every entry,
+/// so an interrupted run still leaves a usable record
+struct ReportWriter {
+    target: Option<OutputTarget>,
+    failed_path: PathBuf,
+    report: BatchReport,
+    failed: Vec<serde_json::Value>,
+}
+
+impl ReportWriter {
+    fn new(input: &Path, total: usize, target: Option<OutputTarget>) -> Self {
+        let failed_path = target
+            .as_ref()
+            .and_then(OutputTarget::path)
+            .and_then(Path::parent)
+            .unwrap_or_else(|| Path::new(""))
+            .join("failed.json");
+
+        Self {
+            target,
+            failed_path,
+            report: BatchReport {
+                input: input.to_path_buf(),
+                started_at: clock::now(),
+                finished_at: None,
+                total,
+                entries: Vec::new(),
+            },
+            failed: Vec::new(),
+        }
+    }
+
+    fn record(&mut self, entry: EntryReport, raw: &serde_json::Value) -> Result<(), CliError> {
+        let failed = matches!(entry.outcome, Outcome::Failed { .. });
+        self.report.entries.push(entry);
+        if failed {
+            self.failed.push(raw.clone());
+        }
+        self.flush(failed)
+    }
+
+    /// Fail on an existing or unwritable report path before anything is scheduled
+    fn start(&self) -> Result<(), CliError> {
+        if let Some(target) = &self.target {
+            target.check()?;
+        }
+        self.flush(false)
+    }
+
+    /// Write the final report, which is the only thing a stdout report gets
+    fn finish(&mut self) -> Result<(), CliError> {
+        self.report.finished_at = Some(clock::now());
+        match &self.target {
+            Some(target) => {
+                let mut contents = serde_json::to_string_pretty(&self.report)
+                    .map_err(|e| CliError::parse("batch report", e))?;
+                contents.push('\n');
+                target.write(contents)
+            }
+            None => Ok(()),
+        }
+    }
+
+    fn flush(&self, failures_changed: bool) -> Result<(), CliError> {
+        if let Some(path) = self.target.as_ref().and_then(OutputTarget::path) {
+            write_json(path, &self.report)?;
+        }
+        if failures_changed {
+            write_json(&self.failed_path, &self.failed)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), CliError> {
+    let contents = serde_json::to_string_pretty(value)
+        .map_err(|e| CliError::parse(format!("contents of {}", path.display()), e))?;
+    storage::write_atomic(path, contents)
+}
+
+/// Print every pre-flight problem with its entry index and field
+pub fn print_problems(entries: &[Entry]) {
+    for entry in entries {
+        for problem in &entry.problems {
+            eprintln!("   [{}] {}: {}", entry.index, problem.field, problem.reason);
+            if let Some(rule) = problem.rule.as_deref().filter(|_| validation::explaining()) {
+                eprintln!("       📐 {}", rule);
+            }
+        }
+    }
+}
+
+/// Submit every job in a batch file, recording each outcome as it happens.
+/// Nothing is submitted while any entry is invalid unless `skip_invalid` is set.
+/// Returns the number of entries that failed, were left unconfirmed, or were scheduled
+/// with server warnings that `--strict` fails on.
+#[allow(clippy::too_many_arguments)]
+pub async fn run(
+    client: &ApiClient,
+    hooks: &HookRunner,
+    input: &Path,
+    format: BatchFormat,
+    report: Option<OutputTarget>,
+    skip_invalid: bool,
+    limits: &TxLimits,
+    campaign: Option<&str>,
+    checks: &Checks,
+) -> Result<usize, CliError> {
+    let mut values = load_values(input, format).await?;
+    if let Some(campaign) = campaign {
+        // The flag puts every entry in the campaign, over whatever the file says
+        for value in &mut values {
+            if let Some(fields) = value.as_object_mut() {
+                fields.insert("campaign".to_string(), campaign.into());
+            }
+        }
+    }
+    submit_entries(
+        client,
+        hooks,
+        input,
+        values,
+        report,
+        skip_invalid,
+        limits,
+        checks,
+    )
+    .await
+}
+
+/// Validate and submit already-loaded entries; `input` names their source in reports
+#[allow(clippy::too_many_arguments)]
+pub async fn submit_entries(
+    client: &ApiClient,
+    hooks: &HookRunner,
+    input: &Path,
+    values: Vec<serde_json::Value>,
+    report: Option<OutputTarget>,
+    skip_invalid: bool,
+    limits: &TxLimits,
+    checks: &Checks,
+) -> Result<usize, CliError> {
+    let mut entries = preflight(values, limits);
+    let asks_polarization = entries
+        .iter()
+        .any(|e| e.job.as_ref().is_some_and(|j| j.polarization.is_some()));
+    if asks_polarization {
+        if let Some(supported) = station::polarizations(client).await {
+            for entry in &mut entries {
+                let problem = entry
+                    .job
+                    .as_ref()
+                    .and_then(|j| j.polarization)
+                    .and_then(|p| station::check_polarization(p, &supported));
+                entry.problems.extend(problem);
+            }
+        }
+    }
+    if let Some(capabilities) = capabilities::get(client).await {
+        let now = clock::now();
+        for entry in &mut entries {
+            let start = entry.job.as_ref().map(|job| job.start);
+            entry.problems.extend(
+                start.and_then(|start| capabilities::check_lead(client, &capabilities, start, now)),
+            );
+        }
+        if let Some(queue) = capabilities::queue(client, &capabilities).await {
+            eprintln!("📋 Station {}", queue);
+            // The entries past the free slots are the ones that will not fit
+            let mut free = queue.free();
+            for entry in entries.iter_mut().filter(|e| e.is_valid()) {
+                match free {
+                    0 => entry.problems.push(queue.full()),
+                    _ => free -= 1,
+                }
+            }
+        }
+    }
+    let (valid, invalid): (Vec<&Entry>, Vec<&Entry>) = entries.iter().partition(|e| e.is_valid());
+
+    if !invalid.is_empty() {
+        eprintln!(
+            "🚫 {} of {} entries in {} are invalid:",
+            invalid.len(),
+            entries.len(),
+            input.display()
+        );
+        print_problems(&entries);
+        if !skip_invalid {
+            return Err(CliError::validation(
+                "batch",
+                "nothing was submitted; fix the entries above or pass --skip-invalid",
+            ));
+        }
+        eprintln!("⏭️ Skipping the invalid entries");
+    }
+
+    // Batches cannot stop to ask, so maintenance clashes are reported but not enforced
+    let windows = maintenance::windows(client).await;
+    for entry in &valid {
+        let Some(job) = entry.job.as_ref() else {
+            continue;
+        };
+        if let Some(snapped) = &entry.snapped {
+            eprintln!("📐 [{}] {}", entry.index, snapped);
+        }
+        for window in maintenance::intersecting(&windows, job.window()) {
+            eprintln!(
+                "🚧 [{}] {} overlaps maintenance {}",
+                entry.index,
+                job.label(),
+                maintenance::describe(window)
+            );
+        }
+    }
+
+    let jobs: Vec<(Option<usize>, &JobRequestDTO)> = valid
+        .iter()
+        .filter_map(|entry| Some((Some(entry.index), entry.job.as_ref()?)))
+        .collect();
+    findings::gather(client, &jobs, checks).await.settle()?;
+    let jobs: Vec<&JobRequestDTO> = jobs.into_iter().map(|(_, job)| job).collect();
+    quiet_hours::review(&jobs)?;
+    rotator::review(&jobs).await?;
+
+    eprintln!(
+        "📦 Submitting {} job(s) from {}",
+        valid.len(),
+        input.display()
+    );
+
+    let mut writer = ReportWriter::new(input, entries.len(), report);
+    writer.start()?;
+
+    for entry in &invalid {
+        let job = entry.job.as_ref();
+        writer.record(
+            EntryReport {
+                index: entry.index,
+                satellite: job.map(|j| j.label().to_string()),
+                start: job.map(|j| j.start),
+                end: job.map(|j| j.end),
+                outcome: Outcome::Skipped {
+                    problems: entry
+                        .problems
+                        .iter()
+                        .map(|p| format!("{}: {}", p.field, p.reason))
+                        .collect(),
+                    rules: entry
+                        .problems
+                        .iter()
+                        .filter(|_| validation::explaining())
+                        .filter_map(|p| p.rule.as_deref().cloned())
+                        .collect(),
+                },
+                submitted_at: None,
+                duration_ms: None,
+            },
+            &entry.raw,
+        )?;
+    }
+
+    let mut created = 0;
+    // Scheduled, but failing `--strict` on what the server warned
+    let mut warned = 0;
+    let mut unconfirmed = Vec::new();
+    for (done, entry) in valid.iter().enumerate() {
+        let Some(job) = entry.job.clone() else {
+            continue;
+        };
+        let job = &JobRequestBuilder::from(job).tx_limits(limits).build()?;
+        let index = entry.index;
+
+        let submitted_at = clock::now();
+        let started = Instant::now();
+        let result = client.add_job(job).await;
+        let duration_ms = started.elapsed().as_millis();
+
+        let outcome = match result {
+            Ok(response) => {
+                created += 1;
+                match response.location() {
+                    Some(location) => eprintln!(
+                        "✅ [{}] {}: {}; follow it at {}",
+                        index,
+                        job.label(),
+                        response.status(),
+                        location
+                    ),
+                    None => eprintln!("✅ [{}] {}: {}", index, job.label(), response.status()),
+                }
+                if let Err(e) = findings::server(Some(index), &response.warnings) {
+                    error::report(&format!("[{}] {}", index, job.label()), &e);
+                    warned += 1;
+                }
+                if let Some(id) = &response.id {
+                    audit::job(id);
+                }
+                campaign::remember(job, &response).await;
+                baseline::remember(job).await;
+                hooks.after_submit(job, &response).await;
+                Outcome::Created {
+                    status: response.status().to_string(),
+                    location: response.location().map(str::to_string),
+                    job_id: response.id,
+                    warnings: response.warnings,
+                }
+            }
+            Err(e) if e.unanswered() => {
+                unconfirmed.push(index);
+                eprintln!(
+                    "❓ [{}] {}: no answer in time ({}); the station may have scheduled it",
+                    index,
+                    job.label(),
+                    e.full_message()
+                );
+                Outcome::Unconfirmed {
+                    message: e.full_message(),
+                }
+            }
+            Err(e) => {
+                error::report(&format!("[{}] {}", index, job.label()), &e);
+                Outcome::Failed {
+                    kind: e.kind(),
+                    status: e.status(),
+                    message: e.full_message(),
+                }
+            }
+        };
+
+        writer.record(
+            EntryReport {
+                index,
+                satellite: Some(job.label().to_string()),
+                start: Some(job.start),
+                end: Some(job.end),
+                outcome,
+                submitted_at: Some(submitted_at),
+                duration_ms: Some(duration_ms),
+            },
+            &entry.raw,
+        )?;
+        client.observe(|o| o.on_batch_progress(done + 1, valid.len()));
+    }
+
+    writer.finish()?;
+
+    let failed = valid.len() - created - unconfirmed.len();
+    let mut summary = format!("📊 {} submitted, {} failed", created, failed);
+    if !unconfirmed.is_empty() {
+        summary.push_str(&format!(", {} unconfirmed", unconfirmed.len()));
+    }
+    if !invalid.is_empty() {
+        summary.push_str(&format!(", {} skipped", invalid.len()));
+    }
+    if warned > 0 {
+        summary.push_str(&format!(", {} warned about under --strict", warned));
+    }
+    if let Some(path) = writer.target.as_ref().and_then(OutputTarget::path) {
+        summary.push_str(&format!(" — report written to {}", path.display()));
+    }
+    eprintln!("{}", summary);
+    if failed > 0 {
+        eprintln!(
+            "🔁 Retry the failures with `rustar-cli jobs add --batch {}`",
+            writer.failed_path.display()
+        );
+    }
+    if !unconfirmed.is_empty() {
+        let entries: Vec<String> = unconfirmed.iter().map(|i| format!("[{}]", i)).collect();
+        eprintln!(
+            "🔎 Check `rustar-cli jobs list` for {} before submitting them again; they are \
+             left out of {} so a retry does not schedule them twice",
+            entries.join(", "),
+            writer.failed_path.display()
+        );
+    }
+    Ok(failed + unconfirmed.len() + warned)
+}
+
+/// The jobs.json of `examples batch`: one tracking job and one loopback test
+pub const EXAMPLE_FILE: &str = r#"[
+  {
+    "job_type": "tracking",
+    "start": "2026-10-20T12:15:00Z",
+    "end": "2026-10-20T12:25:00Z",
+    "tle": {
+      "tle0": "ISS (ZARYA)",
+      "tle1": "1 25544U 98067A   08264.51782528 -.00002182  00000-0 -11606-4 0  2927",
+      "tle2": "2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.72125391563537"
+    },
+    "rx_frequency": "145.8M",
+    "tx_frequency": 0,
+    "mode": "FM"
+  },
+  {
+    "job_type": "test",
+    "start": "2026-10-20T13:00:00Z",
+    "end": "2026-10-20T13:05:00Z",
+    "rx_frequency": 437500000,
+    "tx_frequency": 0
+  }
+]"#;
+
+/// `examples batch`
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        note: "Check the file with every pre-flight check, submitting nothing",
+        input: None,
+        command: "rustar-cli schema --validate-only jobs.json",
+    },
+    Example {
+        note: "Submit every job in it",
+        input: None,
+        command: "rustar-cli jobs add --batch jobs.json",
+    },
+    Example {
+        note: "Submit the entries that pass, reporting each outcome on stdout",
+        input: None,
+        command: "rustar-cli jobs add --batch jobs.json --skip-invalid --report -",
+    },
+    Example {
+        note: "Jobs written by another program, on stdin",
+        input: None,
+        command: "plan-passes --days 2 | rustar-cli jobs add --batch -",
+    },
+    Example {
+        note: "A spreadsheet export with a header row, detected by its extension",
+        input: None,
+        command: "rustar-cli jobs add --batch schedule.csv",
+    },
+    Example {
+        note: "One job written in $VISUAL or $EDITOR, laid out like one entry of the file",
+        input: None,
+        command: "rustar-cli jobs add --edit --template jobs.json",
+    },
+    Example {
+        note: "The JSON Schema entries are checked against",
+        input: None,
+        command: "rustar-cli schema jobs-batch",
+    },
+];
@@ -0,0 +1,258 @@
+use crate::batch;
+use crate::client::{JobType, TleData};
+use crate::error::{CliError, ResultExt};
+use crate::frequency::Frequency;
+use crate::job_request::JobRequestBuilder;
+use crate::tle;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Columns a schedule CSV may contain, in any order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Column {
+    Satellite,
+    NoradId,
+    Start,
+    End,
+    Duration,
+    Rx,
+    Tx,
+    Mode,
+    Tle1,
+    Tle2,
+}
+
+impl Column {
+    fn from_header(header: &str) -> Option<Self> {
+        Some(match header.trim().to_lowercase().as_str() {
+            "satellite" | "name" => Column::Satellite,
+            "norad_id" | "norad" => Column::NoradId,
+            "start" => Column::Start,
+            "end" => Column::End,
+            "duration" => Column::Duration,
+            "rx" | "rx_frequency" => Column::Rx,
+            "tx" | "tx_frequency" => Column::Tx,
+            "mode" => Column::Mode,
+            "tle1" => Column::Tle1,
+            "tle2" => Column::Tle2,
+            _ => return None,
+        })
+    }
+}
+
+const KNOWN_COLUMNS: &str = "satellite, norad_id, start, end, duration, rx, tx, mode, tle1, tle2";
+
+/// Where a row's element set comes from
+enum TleSource {
+    Lines(String, String),
+    Norad(u32),
+}
+
+/// A row before its TLE is resolved
+struct Row {
+    line: u64,
+    satellite: String,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    rx: Frequency,
+    tx: Frequency,
+    mode: Option<String>,
+    tle: TleSource,
+}
+
+/// Read a schedule CSV into batch entries shaped like the JSON batch format.
+/// Every malformed cell is reported with its row and column before giving up.
+pub async fn load(path: &Path) -> Result<Vec<serde_json::Value>, CliError> {
+    let source: Box<dyn std::io::Read> = if batch::is_stdin(path) {
+        Box::new(std::io::stdin())
+    } else {
+        Box::new(
+            std::fs::File::open(path)
+                .map_err(|e| CliError::parse(format!("CSV file {}", path.display()), e))?,
+        )
+    };
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(source);
+
+    let headers = reader
+        .headers()
+        .map_err(|e| CliError::parse(format!("CSV header of {}", path.display()), e))?
+        .clone();
+
+    let mut columns = HashMap::new();
+    for (index, header) in headers.iter().enumerate() {
+        let column = Column::from_header(header).ok_or_else(|| {
+            CliError::validation(
+                format!("row 1, column {}", index + 1),
+                format!("unknown column '{}' (known: {})", header, KNOWN_COLUMNS),
+            )
+        })?;
+        columns.insert(column, index);
+    }
+
+    let has_tle = columns.contains_key(&Column::Tle1) && columns.contains_key(&Column::Tle2);
+    let mut missing = Vec::new();
+    for (column, name) in [
+        (Column::Satellite, "satellite"),
+        (Column::Start, "start"),
+        (Column::Rx, "rx"),
+    ] {
+        if !columns.contains_key(&column) {
+            missing.push(name);
+        }
+    }
+    if !columns.contains_key(&Column::End) && !columns.contains_key(&Column::Duration) {
+        missing.push("end or duration");
+    }
+    if !has_tle && !columns.contains_key(&Column::NoradId) {
+        missing.push("norad_id (or tle1 and tle2)");
+    }
+    if !missing.is_empty() {
+        return Err(CliError::validation(
+            "row 1",
+            format!("missing required column(s): {}", missing.join(", ")),
+        ));
+    }
+
+    let mut rows = Vec::new();
+    let mut problems = Vec::new();
+
+    for record in reader.records() {
+        let record =
+            record.map_err(|e| CliError::parse(format!("CSV file {}", path.display()), e))?;
+        let line = record.position().map(|p| p.line()).unwrap_or_default();
+
+        // Fetch a cell, recording a problem with its coordinates when it doesn't parse
+        let cell = |column: Column| -> Option<(usize, &str)> {
+            columns
+                .get(&column)
+                .map(|&index| (index, record.get(index).unwrap_or("")))
+        };
+        let mut fail = |index: usize, reason: String| {
+            problems.push(format!(
+                "row {}, column {} ({}): {}",
+                line,
+                index + 1,
+                &headers[index],
+                reason
+            ));
+        };
+
+        let satellite = cell(Column::Satellite).map(|(_, v)| v.to_string());
+        let norad_id = match cell(Column::NoradId) {
+            Some((_, "")) | None => None,
+            Some((index, value)) => match value.parse::<u32>() {
+                Ok(id) => Some(id),
+                Err(_) => {
+                    fail(index, format!("'{}' is not a NORAD catalog number", value));
+                    None
+                }
+            },
+        };
+
+        let start = cell(Column::Start).and_then(|(index, value)| {
+            crate::parse::datetime(value)
+                .map_err(|e| fail(index, e.full_message()))
+                .ok()
+        });
+
+        let end = match (cell(Column::End), cell(Column::Duration)) {
+            (Some((index, value)), _) if !value.is_empty() => crate::parse::datetime(value)
+                .map_err(|e| fail(index, e.full_message()))
+                .ok(),
+            (_, Some((index, value))) => crate::parse::duration(value)
+                .map_err(|e| fail(index, e.full_message()))
+                .ok()
+                .and_then(|duration| start.map(|s| s + duration)),
+            (Some((index, _)), None) => {
+                fail(index, "end is empty".to_string());
+                None
+            }
+            (None, None) => None,
+        };
+
+        let frequency = |column: Column, fail: &mut dyn FnMut(usize, String)| match cell(column) {
+            Some((_, "")) | None if column == Column::Tx => Some(Frequency::ZERO),
+            Some((index, value)) => Frequency::parse(value)
+                .map_err(|e| fail(index, e.full_message()))
+                .ok(),
+            None => None,
+        };
+        let rx = frequency(Column::Rx, &mut fail);
+        let tx = frequency(Column::Tx, &mut fail);
+
+        let mode = cell(Column::Mode)
+            .map(|(_, v)| v.to_string())
+            .filter(|v| !v.is_empty());
+
+        let tle = match (cell(Column::Tle1), cell(Column::Tle2)) {
+            (Some((_, l1)), Some((_, l2))) if !l1.is_empty() && !l2.is_empty() => {
+                Some(TleSource::Lines(l1.to_string(), l2.to_string()))
+            }
+            _ => norad_id.map(TleSource::Norad),
+        };
+        let norad_given = matches!(cell(Column::NoradId), Some((_, v)) if !v.is_empty());
+        if tle.is_none() && !norad_given {
+            let index = columns
+                .get(&Column::NoradId)
+                .or_else(|| columns.get(&Column::Tle1))
+                .copied()
+                .unwrap_or(0);
+            fail(index, "row needs a norad_id or both TLE lines".to_string());
+        }
+
+        if let (Some(satellite), Some(start), Some(end), Some(rx), Some(tx), Some(tle)) =
+            (satellite, start, end, rx, tx, tle)
+        {
+            rows.push(Row {
+                line,
+                satellite,
+                start,
+                end,
+                rx,
+                tx,
+                mode,
+                tle,
+            });
+        }
+    }
+
+    if !problems.is_empty() {
+        for problem in &problems {
+            eprintln!("   {}", problem);
+        }
+        return Err(CliError::validation(
+            format!("CSV file {}", path.display()),
+            format!("{} cell(s) could not be parsed", problems.len()),
+        ));
+    }
+
+    let mut entries = Vec::with_capacity(rows.len());
+    for row in rows {
+        let tle = match row.tle {
+            TleSource::Lines(tle1, tle2) => TleData {
+                tle0: row.satellite.clone(),
+                tle1,
+                tle2,
+            },
+            TleSource::Norad(norad_id) => TleData {
+                tle0: row.satellite.clone(),
+                ..tle::resolve(norad_id)
+                    .await
+                    .with_context(|| format!("resolving the TLE for row {}", row.line))?
+            },
+        };
+
+        let job = JobRequestBuilder::new(row.start, row.end, JobType::Tracking { tle })
+            .rx_frequency(row.rx)
+            .tx_frequency(row.tx)
+            .mode(row.mode)
+            .into_draft();
+        entries
+            .push(serde_json::to_value(&job).map_err(|e| CliError::parse("CSV row as a job", e))?);
+    }
+
+    Ok(entries)
+}
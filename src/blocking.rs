@@ -0,0 +1,137 @@
+//! `BlockingApiClient`: the API client for synchronous tools, behind the `blocking`
+//! feature. Each call runs the `ApiClient` method of the same name to completion on a
+//! current-thread runtime the facade owns, so the requests, DTOs, errors and checks are
+//! the async client's own and cannot drift from it. Calls block the thread they are made
+//! on, and must not be made from inside another runtime.
+
+use crate::cancel::Reason;
+use crate::client::{
+    ApiClient, ApiResponse, Capabilities, CatalogEntry, Identity, JobDTO, JobResults,
+    MaintenanceWindow, SatelliteDTO, StationInfo, Version,
+};
+use crate::download::{Downloaded, RecordingInfo};
+use crate::error::{CliError, ResultExt};
+use crate::job_request::JobRequest;
+use chrono::{DateTime, Utc};
+use std::future::Future;
+use std::path::Path;
+use tokio::runtime::Runtime;
+
+pub struct BlockingApiClient {
+    client: ApiClient,
+    runtime: Runtime,
+}
+
+impl BlockingApiClient {
+    /// Make the calls of `client`, built with `ApiClient::builder`, blocking
+    pub fn new(client: ApiClient) -> Result<Self, CliError> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .context("starting the runtime of the blocking client")?;
+        Ok(Self { client, runtime })
+    }
+
+    /// The async client underneath
+    pub fn client(&self) -> &ApiClient {
+        &self.client
+    }
+
+    fn block_on<T>(&self, call: impl Future<Output = T>) -> T {
+        self.runtime.block_on(call)
+    }
+
+    pub fn ping(&self) -> Result<(), CliError> {
+        self.block_on(self.client.ping())
+    }
+
+    /// Submit a job that `JobRequestBuilder::build` has checked
+    pub fn add_job(&self, job: &JobRequest) -> Result<ApiResponse, CliError> {
+        self.block_on(self.client.add_job(job))
+    }
+
+    pub fn list_jobs(&self) -> Result<Vec<JobDTO>, CliError> {
+        self.block_on(self.client.list_jobs())
+    }
+
+    /// One job as the server holds it, whatever its fields
+    pub fn job_json(&self, id: &str) -> Result<serde_json::Value, CliError> {
+        self.block_on(self.client.job_json(id))
+    }
+
+    pub fn job_results(&self, id: &str) -> Result<Option<JobResults>, CliError> {
+        self.block_on(self.client.job_results(id))
+    }
+
+    /// Fetch a recording to `dest`, resuming an earlier attempt's `.partial` file
+    pub fn download_recording(
+        &self,
+        id: &str,
+        name: &str,
+        dest: &Path,
+    ) -> Result<Downloaded, CliError> {
+        self.block_on(self.client.download_recording(id, name, dest))
+    }
+
+    pub fn recording_info(&self, id: &str, name: &str) -> Result<RecordingInfo, CliError> {
+        self.block_on(self.client.recording_info(id, name))
+    }
+
+    pub fn reschedule_job(
+        &self,
+        id: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<ApiResponse, CliError> {
+        self.block_on(self.client.reschedule_job(id, start, end))
+    }
+
+    pub fn cancel_job(
+        &self,
+        id: &str,
+        reason: Option<&Reason>,
+        version: Option<&Version>,
+    ) -> Result<ApiResponse, CliError> {
+        self.block_on(self.client.cancel_job(id, reason, version))
+    }
+
+    pub fn restore_job(&self, id: &str) -> Result<ApiResponse, CliError> {
+        self.block_on(self.client.restore_job(id))
+    }
+
+    pub fn maintenance(&self) -> Result<Vec<MaintenanceWindow>, CliError> {
+        self.block_on(self.client.maintenance())
+    }
+
+    pub fn station_info(&self) -> Result<StationInfo, CliError> {
+        self.block_on(self.client.station_info())
+    }
+
+    pub fn capabilities(&self) -> Result<Capabilities, CliError> {
+        self.block_on(self.client.capabilities())
+    }
+
+    pub fn whoami(&self) -> Result<Identity, CliError> {
+        self.block_on(self.client.whoami())
+    }
+
+    pub fn create_satellite(&self, satellite: &SatelliteDTO) -> Result<ApiResponse, CliError> {
+        self.block_on(self.client.create_satellite(satellite))
+    }
+
+    pub fn update_satellite(&self, satellite: &SatelliteDTO) -> Result<ApiResponse, CliError> {
+        self.block_on(self.client.update_satellite(satellite))
+    }
+
+    pub fn satellite(&self, norad_id: u32) -> Result<Option<SatelliteDTO>, CliError> {
+        self.block_on(self.client.satellite(norad_id))
+    }
+
+    pub fn search_satellites(
+        &self,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<CatalogEntry>, CliError> {
+        self.block_on(self.client.search_satellites(query, limit))
+    }
+}
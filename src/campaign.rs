@@ -0,0 +1,366 @@
+use crate::audit;
+use crate::batch;
+use crate::cancel::{self, Reasons};
+use crate::client::{ApiClient, ApiResponse, JobDTO, JobRequestDTO, JobType};
+use crate::clock;
+use crate::error::{CliError, ResultExt};
+use crate::findings::Checks;
+use crate::hooks::HookRunner;
+use crate::humanize;
+use crate::jobs;
+use crate::paths;
+use crate::regulatory::TxLimits;
+use crate::schema::{Format, Store};
+use crate::storage;
+use crate::text;
+use crate::tle;
+use crate::tty;
+use crate::OutputFormat;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Campaign name to the IDs of the jobs submitted under it
+type Index = BTreeMap<String, Vec<String>>;
+
+/// `campaigns.json` layouts: version 1 was the bare name-to-IDs map
+pub const STORE: Store = Store {
+    name: "campaign index",
+    format: Format::Json,
+    migrations: &[wrap_campaigns],
+};
+
+fn wrap_campaigns(index: Value) -> Result<Value, String> {
+    if !index.is_object() {
+        return Err("expected a map of campaign names".to_string());
+    }
+    Ok(json!({ "campaigns": index }))
+}
+
+#[derive(Deserialize)]
+struct Layout {
+    campaigns: Index,
+}
+
+fn index_path() -> PathBuf {
+    paths::campaigns_file()
+}
+
+/// Read the campaign index; a missing file is an empty index
+fn load() -> Result<Index, CliError> {
+    Ok(STORE
+        .load::<Layout>(&index_path())?
+        .map(|layout| layout.campaigns)
+        .unwrap_or_default())
+}
+
+/// Replace the index contents; callers hold the index lock
+fn store(index: &Index) -> Result<(), CliError> {
+    STORE.write(&index_path(), &json!({ "campaigns": index }))
+}
+
+/// `--campaign`: a name with something in it
+pub fn parse_name(input: &str) -> Result<String, String> {
+    let name = input.trim();
+    if name.is_empty() {
+        return Err("a campaign needs a name".to_string());
+    }
+    Ok(name.to_string())
+}
+
+async fn record(name: &str, id: &str) -> Result<(), CliError> {
+    let _lock = storage::lock_async(&index_path()).await?;
+    let mut index = load()?;
+    let ids = index.entry(name.to_string()).or_default();
+    if !ids.iter().any(|known| known == id) {
+        ids.push(id.to_string());
+    }
+    store(&index)
+}
+
+/// Index a job just created under its campaign. Failures are warnings: the job exists either way.
+pub async fn remember(job: &JobRequestDTO, response: &ApiResponse) {
+    let Some(name) = &job.campaign else {
+        return;
+    };
+    let Some(id) = &response.id else {
+        eprintln!(
+            "⚠️ The server returned no job ID, so {} is not in the local index of campaign {}",
+            job.label(),
+            name
+        );
+        return;
+    };
+    if let Err(e) = record(name, id).await {
+        eprintln!(
+            "⚠️ Could not add job {} to campaign {}: {}",
+            id,
+            name,
+            e.full_message()
+        );
+    }
+}
+
+/// Drop cancelled jobs from the index, and the campaign once it has none left
+async fn forget(name: &str, ids: &[String]) -> Result<(), CliError> {
+    let _lock = storage::lock_async(&index_path()).await?;
+    let mut index = load()?;
+    if let Some(known) = index.get_mut(name) {
+        known.retain(|id| !ids.contains(id));
+        if known.is_empty() {
+            index.remove(name);
+        }
+    }
+    store(&index)
+}
+
+/// A campaign's jobs on the server: the ones indexed here and the ones the server
+/// keeps the campaign field for, earliest first, plus how many indexed IDs are gone
+async fn members(client: &ApiClient, name: &str) -> Result<(Vec<JobDTO>, usize), CliError> {
+    let indexed = load()?.remove(name).unwrap_or_default();
+    let mut jobs: Vec<JobDTO> = client
+        .list_jobs()
+        .await?
+        .into_iter()
+        .filter(|job| {
+            job.job.campaign.as_deref() == Some(name)
+                || job.id.as_ref().is_some_and(|id| indexed.contains(id))
+        })
+        .collect();
+    if jobs.is_empty() && indexed.is_empty() {
+        return Err(CliError::NotFound {
+            message: Some(format!(
+                "no campaign named '{}' (see rustar-cli campaign list)",
+                name
+            )),
+        });
+    }
+    jobs.sort_by(JobDTO::schedule_order);
+    let missing = indexed
+        .iter()
+        .filter(|id| !jobs.iter().any(|job| job.id.as_ref() == Some(*id)))
+        .count();
+    Ok((jobs, missing))
+}
+
+#[derive(Serialize)]
+struct Summary {
+    name: String,
+    jobs: usize,
+    first_start: Option<DateTime<Utc>>,
+    last_end: Option<DateTime<Utc>>,
+}
+
+/// Every campaign known locally or to the server, with its span
+pub async fn list(client: &ApiClient, output: OutputFormat) -> Result<(), CliError> {
+    let index = load()?;
+    let jobs = client.list_jobs().await?;
+
+    let mut campaigns: BTreeMap<String, Vec<&JobDTO>> = index
+        .keys()
+        .map(|name| (name.clone(), Vec::new()))
+        .collect();
+    for job in &jobs {
+        let indexed = index.iter().filter(|(_, ids)| {
+            job.id
+                .as_ref()
+                .is_some_and(|id| ids.iter().any(|known| known == id))
+        });
+        let mut names: Vec<&String> = indexed.map(|(name, _)| name).collect();
+        if let Some(name) = &job.job.campaign {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        for name in names {
+            campaigns.entry(name.clone()).or_default().push(job);
+        }
+    }
+
+    let summaries: Vec<Summary> = campaigns
+        .into_iter()
+        .map(|(name, jobs)| Summary {
+            name,
+            jobs: jobs.len(),
+            first_start: jobs.iter().map(|j| j.job.start).min(),
+            last_end: jobs.iter().map(|j| j.job.end).max(),
+        })
+        .collect();
+
+    if output == OutputFormat::Json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&summaries)
+                .map_err(|e| CliError::parse("campaigns", e))?
+        );
+        return Ok(());
+    }
+    if summaries.is_empty() {
+        println!("📭 No campaigns (submit with jobs add --campaign <name>)");
+        return Ok(());
+    }
+
+    println!("🗂️ {} campaign(s)", summaries.len());
+    let width = summaries
+        .iter()
+        .map(|s| text::width(&s.name))
+        .max()
+        .unwrap_or(0);
+    for summary in &summaries {
+        let span = match (summary.first_start, summary.last_end) {
+            (Some(start), Some(end)) => format!(
+                "{} → {}",
+                humanize::timestamp(start),
+                humanize::timestamp(end)
+            ),
+            _ => "no jobs left on the server".to_string(),
+        };
+        println!(
+            "  {}  {:>4} job(s)  {}",
+            text::pad(&summary.name, width),
+            summary.jobs,
+            span
+        );
+    }
+    Ok(())
+}
+
+/// The campaign's jobs with their statuses
+pub async fn show(client: &ApiClient, name: &str, output: OutputFormat) -> Result<(), CliError> {
+    let (jobs, missing) = members(client, name).await?;
+    if output == OutputFormat::Json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&jobs).map_err(|e| CliError::parse("jobs", e))?
+        );
+        return Ok(());
+    }
+
+    println!("🗂️ Campaign {}: {} job(s)", name, jobs.len());
+    if !jobs.is_empty() {
+        jobs::print_table(&jobs);
+    }
+    if missing > 0 {
+        println!(
+            "  ℹ️ {} indexed job(s) are no longer on the server",
+            missing
+        );
+    }
+    Ok(())
+}
+
+/// Cancel every job of the campaign that has not ended, after showing what will go.
+/// Returns the number of jobs that could not be cancelled.
+pub async fn cancel(
+    client: &ApiClient,
+    name: &str,
+    yes: bool,
+    reasons: &Reasons,
+) -> Result<usize, CliError> {
+    let (jobs, _) = members(client, name).await?;
+    let now = clock::now();
+    let (pending, ended): (Vec<JobDTO>, Vec<JobDTO>) =
+        jobs.into_iter().partition(|job| job.job.end > now);
+    if pending.is_empty() {
+        println!("📭 Campaign {} has no jobs left to cancel", name);
+        return Ok(0);
+    }
+
+    println!(
+        "🗑️ Campaign {}: {} job(s) will be cancelled",
+        name,
+        pending.len()
+    );
+    jobs::print_table(&pending);
+    if !ended.is_empty() {
+        println!(
+            "  ⏭️ {} job(s) have already ended and are kept",
+            ended.len()
+        );
+    }
+    let question = format!("Cancel these {} job(s)?", pending.len());
+    if !tty::confirm("campaign cancel", &question, yes)? {
+        println!("🛑 Nothing cancelled");
+        return Ok(0);
+    }
+    let reason = reasons.pick()?;
+    if let Some(reason) = &reason {
+        audit::reason(&reason.to_string());
+    }
+
+    let mut cancelled = Vec::new();
+    let mut failed = 0;
+    for job in &pending {
+        if cancel::cancel(client, job, reason.as_ref(), None, None).await {
+            cancelled.extend(job.id.clone());
+        } else {
+            failed += 1;
+        }
+    }
+    forget(name, &cancelled).await?;
+    println!("📊 {} cancelled, {} failed", cancelled.len(), failed);
+    Ok(failed)
+}
+
+/// Submit a copy of the campaign `shift` later, as campaign `new_name`, with current
+/// element sets. Goes through the batch pipeline; returns the number of failed entries.
+#[allow(clippy::too_many_arguments)]
+pub async fn clone(
+    client: &ApiClient,
+    hooks: &HookRunner,
+    name: &str,
+    shift: Duration,
+    new_name: &str,
+    skip_invalid: bool,
+    limits: &TxLimits,
+    checks: &Checks,
+) -> Result<usize, CliError> {
+    if shift <= Duration::zero() {
+        return Err(CliError::validation(
+            "shift",
+            "must move the campaign forward, e.g. 7d",
+        ));
+    }
+    let (jobs, _) = members(client, name).await?;
+    if jobs.is_empty() {
+        return Err(CliError::NotFound {
+            message: Some(format!("campaign {} has no jobs on the server", name)),
+        });
+    }
+
+    let mut values = Vec::with_capacity(jobs.len());
+    for job in jobs {
+        let mut copy = job.job;
+        copy.start += shift;
+        copy.end += shift;
+        copy.campaign = Some(new_name.to_string());
+        if let JobType::Tracking { tle } = &copy.job_type {
+            let tle = tle::refreshed(tle)
+                .await
+                .with_context(|| format!("refreshing the TLE of {}", copy.label()))?;
+            copy.job_type = JobType::Tracking { tle };
+        }
+        values.push(serde_json::to_value(&copy).map_err(|e| CliError::parse("cloned job", e))?);
+    }
+
+    eprintln!(
+        "🗂️ Cloning campaign {} as {}, {} later",
+        name,
+        new_name,
+        humanize::duration(shift)
+    );
+    let source = format!("campaign {}", name);
+    batch::submit_entries(
+        client,
+        hooks,
+        Path::new(&source),
+        values,
+        None,
+        skip_invalid,
+        limits,
+        checks,
+    )
+    .await
+}
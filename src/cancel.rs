@@ -0,0 +1,241 @@
+use crate::audit;
+use crate::client::{ApiClient, JobDTO, Versioned};
+use crate::conflict::{self, OnConflict};
+use crate::error::{self, CliError};
+use crate::jobs;
+use crate::transcript::Recorded;
+use crate::tty;
+use crate::OutputFormat;
+use inquire::{Select, Text};
+use serde::Serialize;
+
+/// Reasons every profile offers, as sent to the server and as shown in the prompt.
+/// `other` always comes last and needs a description.
+const BUILT_IN: &[(&str, &str)] = &[
+    ("weather", "Weather"),
+    ("hardware", "Hardware issue"),
+    ("duplicate", "Duplicate job"),
+    ("tle-error", "TLE error"),
+];
+const OTHER: &str = "other";
+
+/// Why a job was cancelled, sent with the DELETE request and kept in the audit log
+#[derive(Debug, Clone, Serialize)]
+pub struct Reason {
+    pub reason: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason_text: Option<String>,
+}
+
+impl std::fmt::Display for Reason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.reason_text {
+            Some(text) => write!(f, "{}: {}", self.reason, text),
+            None => write!(f, "{}", self.reason),
+        }
+    }
+}
+
+/// Where the cancellation reason comes from: `--reason`/`--reason-text`, or a prompt
+pub struct Reasons {
+    /// The built-in codes followed by the profile's `cancel_reasons`
+    codes: Vec<(String, String)>,
+    given: Option<Reason>,
+}
+
+impl Reasons {
+    /// Check the flags against the reasons this profile offers
+    pub fn new(
+        reason: Option<String>,
+        text: Option<String>,
+        extra: &[String],
+    ) -> Result<Self, CliError> {
+        let mut codes: Vec<(String, String)> = BUILT_IN
+            .iter()
+            .map(|(code, label)| (code.to_string(), label.to_string()))
+            .collect();
+        for code in extra {
+            let code = code.trim();
+            if !code.is_empty() && !codes.iter().any(|(known, _)| known == code) {
+                codes.push((code.to_string(), code.to_string()));
+            }
+        }
+        codes.push((OTHER.to_string(), "Other (describe)".to_string()));
+
+        let text = text.map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+        let given = match reason {
+            Some(reason) => {
+                let reason = reason.trim().to_string();
+                if !codes.iter().any(|(code, _)| *code == reason) {
+                    let known: Vec<&str> = codes.iter().map(|(code, _)| code.as_str()).collect();
+                    return Err(CliError::validation(
+                        "reason",
+                        format!("'{}' is not one of {}", reason, known.join(", ")),
+                    ));
+                }
+                if reason == OTHER && text.is_none() {
+                    return Err(CliError::validation(
+                        "reason-text",
+                        "--reason other needs a description",
+                    ));
+                }
+                Some(Reason {
+                    reason,
+                    reason_text: text,
+                })
+            }
+            // A description on its own is an "other" reason
+            None => text.map(|text| Reason {
+                reason: OTHER.to_string(),
+                reason_text: Some(text),
+            }),
+        };
+        Ok(Self { codes, given })
+    }
+
+    /// The flags' reason, or one asked for; none when there are no flags and no terminal
+    pub fn pick(&self) -> Result<Option<Reason>, CliError> {
+        if let Some(given) = &self.given {
+            return Ok(Some(given.clone()));
+        }
+        if !tty::interactive() {
+            return Ok(None);
+        }
+        let labels: Vec<&str> = self.codes.iter().map(|(_, label)| label.as_str()).collect();
+        let picked = Select::new("📝 Reason for cancelling:", labels)
+            .raw_prompt()
+            .recorded("📝 Reason for cancelling:")?;
+        let reason = self.codes[picked.index].0.clone();
+        let prompt = Text::new("📝 Details:");
+        let text = if reason == OTHER {
+            prompt.prompt()
+        } else {
+            prompt.with_placeholder("optional").prompt()
+        }
+        .recorded("📝 Details:")?;
+        let text = Some(text.trim().to_string()).filter(|t| !t.is_empty());
+        if reason == OTHER && text.is_none() {
+            return Err(CliError::validation(
+                "reason",
+                "an 'other' reason needs a description",
+            ));
+        }
+        Ok(Some(Reason {
+            reason,
+            reason_text: text,
+        }))
+    }
+}
+
+/// Cancel one job, recording the reason in the audit log whether or not the server takes it.
+/// With `fetched`, the job as it was read before the operator confirmed, the cancellation
+/// is sent against that version; if someone changed the job since, `on_conflict` or the
+/// operator decides whether it is cancelled anyway.
+pub async fn cancel(
+    client: &ApiClient,
+    job: &JobDTO,
+    reason: Option<&Reason>,
+    fetched: Option<&Versioned>,
+    on_conflict: Option<OnConflict>,
+) -> bool {
+    let Some(id) = &job.id else {
+        eprintln!("⚠️ {} has no ID and cannot be cancelled", job.job.label());
+        return false;
+    };
+    let mut base = fetched.map(|fetched| fetched.value.clone());
+    let mut version = fetched.and_then(|fetched| fetched.version.clone());
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        let error = match client.cancel_job(id, reason, version.as_ref()).await {
+            Ok(response) => {
+                audit::job(id);
+                println!("✅ {} ({}): {}", id, job.job.label(), response.status());
+                return true;
+            }
+            Err(e) => e,
+        };
+        let (Some(before), true) = (&base, error.is_stale()) else {
+            error::report(&format!("{} ({})", id, job.job.label()), &error);
+            return false;
+        };
+        let theirs = match client.job_versioned(id).await {
+            Ok(theirs) => theirs,
+            Err(e) => {
+                error::report(&format!("{} ({})", id, job.job.label()), &e);
+                return false;
+            }
+        };
+        conflict::show(id, before, before, &theirs.value, OutputFormat::Text);
+        let decided = match attempts == conflict::ATTEMPTS {
+            true => Ok(OnConflict::Abort),
+            false => conflict::resolve(id, on_conflict),
+        };
+        match decided {
+            Ok(OnConflict::Mine) => {
+                println!("🔁 Cancelling job {} in its newer version", id);
+                (base, version) = (Some(theirs.value), theirs.version);
+            }
+            Ok(OnConflict::Theirs) => {
+                println!("↩️ Job {} left as the server now holds it", id);
+                return true;
+            }
+            Ok(OnConflict::Abort) => {
+                let e = conflict::aborted(id, attempts);
+                error::report(&format!("{} ({})", id, job.job.label()), &e);
+                return false;
+            }
+            Err(e) => {
+                error::report(&format!("{} ({})", id, job.job.label()), &e);
+                return false;
+            }
+        }
+    }
+}
+
+/// `jobs cancel`: show the jobs, confirm, ask why, cancel. Returns how many failed.
+pub async fn run(
+    client: &ApiClient,
+    ids: &[String],
+    yes: bool,
+    reasons: &Reasons,
+    on_conflict: Option<OnConflict>,
+) -> Result<usize, CliError> {
+    let mut jobs = Vec::with_capacity(ids.len());
+    let mut versions = Vec::with_capacity(ids.len());
+    for id in ids {
+        let fetched = client.job_versioned(id).await?;
+        if fetched.value.is_null() {
+            return Err(CliError::NotFound {
+                message: Some(format!("the server returned no body for job {}", id)),
+            });
+        }
+        let job: JobDTO = serde_json::from_value(fetched.value.clone())
+            .map_err(|e| CliError::parse(format!("job {}", id), e))?;
+        jobs.push(job);
+        versions.push(fetched);
+    }
+
+    println!("🗑️ {} job(s) will be cancelled", jobs.len());
+    jobs::print_table(&jobs);
+    if !tty::confirm(
+        "jobs cancel",
+        &format!("Cancel {} job(s)?", jobs.len()),
+        yes,
+    )? {
+        println!("🛑 Nothing cancelled");
+        return Ok(0);
+    }
+    let reason = reasons.pick()?;
+    if let Some(reason) = &reason {
+        audit::reason(&reason.to_string());
+    }
+
+    let mut failed = 0;
+    for (job, fetched) in jobs.iter().zip(&versions) {
+        if !cancel(client, job, reason.as_ref(), Some(fetched), on_conflict).await {
+            failed += 1;
+        }
+    }
+    Ok(failed)
+}
@@ -0,0 +1,156 @@
+use crate::client::{ApiClient, Capabilities, JobRequestDTO};
+use crate::clock;
+use crate::error::CliError;
+use crate::humanize;
+use crate::validation::{self, Problem, Rule};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Mutex;
+
+const ENDPOINT: &str = "/station/capabilities";
+
+/// Each server's capabilities, asked for once per session; none where it has no endpoint
+static FETCHED: Mutex<BTreeMap<String, Option<Capabilities>>> = Mutex::new(BTreeMap::new());
+
+/// What the server at `client` says its scheduler enforces. A server without the
+/// endpoint, or one that cannot say, leaves submissions as they were.
+pub async fn get(client: &ApiClient) -> Option<Capabilities> {
+    let known = FETCHED
+        .lock()
+        .ok()
+        .and_then(|fetched| fetched.get(client.base_url()).cloned());
+    if let Some(known) = known {
+        return known;
+    }
+    let capabilities = client.capabilities().await.ok();
+    if let Ok(mut fetched) = FETCHED.lock() {
+        fetched.insert(client.base_url().to_string(), capabilities.clone());
+    }
+    capabilities
+}
+
+fn rule(client: &ApiClient, name: &'static str, limit: String) -> Rule {
+    Rule::server(name, client.url(ENDPOINT).to_string(), limit)
+}
+
+/// Why a job starting at `start` is too soon for a scheduler that needs `min_lead_seconds`
+pub fn check_lead(
+    client: &ApiClient,
+    capabilities: &Capabilities,
+    start: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Option<Problem> {
+    let lead = Duration::seconds(i64::try_from(capabilities.min_lead_seconds?).ok()?);
+    let left = start - now;
+    if left >= lead {
+        return None;
+    }
+    let starts = match left > Duration::zero() {
+        true => format!("starts in {}s", left.num_seconds()),
+        false => "has already started".to_string(),
+    };
+    Some(
+        Problem::new(
+            "start",
+            format!(
+                "the job {}; the station accepts jobs at least {}s ({}) before they start",
+                starts,
+                lead.num_seconds(),
+                humanize::duration(lead)
+            ),
+        )
+        .breaking(rule(
+            client,
+            "scheduling lead",
+            format!("{}s", lead.num_seconds()),
+        )),
+    )
+}
+
+/// The station's pending jobs against its cap
+pub struct Queue {
+    pub pending: u64,
+    pub max: u64,
+    rule: Rule,
+}
+
+impl Queue {
+    /// Jobs that can still be added
+    pub fn free(&self) -> u64 {
+        self.max.saturating_sub(self.pending)
+    }
+
+    /// Why one more job does not fit
+    pub fn full(&self) -> Problem {
+        Problem::new(
+            "queue",
+            format!(
+                "the station queue is full: {} of at most {} jobs are pending",
+                self.pending, self.max
+            ),
+        )
+        .breaking(self.rule.clone())
+    }
+}
+
+/// "queue 48/50"
+impl fmt::Display for Queue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "queue {}/{}", self.pending, self.max)
+    }
+}
+
+/// How many jobs the station has pending: its own count when it reports one, else the
+/// jobs it lists that have not started and are not cancelled
+async fn pending(client: &ApiClient) -> Option<u64> {
+    if let Some(depth) = client
+        .station_info()
+        .await
+        .ok()
+        .and_then(|info| info.queue_depth)
+    {
+        return Some(depth);
+    }
+    let now = clock::now();
+    let jobs = client.list_jobs().await.ok()?;
+    Some(
+        jobs.iter()
+            .filter(|job| job.cancelled_at.is_none() && job.job.start > now)
+            .count() as u64,
+    )
+}
+
+/// The station's queue, when it caps the number of pending jobs
+pub async fn queue(client: &ApiClient, capabilities: &Capabilities) -> Option<Queue> {
+    let max = capabilities.max_pending_jobs?;
+    Some(Queue {
+        pending: pending(client).await?,
+        max,
+        rule: rule(client, "pending jobs", format!("at most {}", max)),
+    })
+}
+
+/// Before `jobs` are submitted to `client`: refuse the ones that start too soon for the
+/// scheduler, or that do not fit in its queue. Returns the queue, for the summary.
+pub async fn review(
+    client: &ApiClient,
+    jobs: &[&JobRequestDTO],
+) -> Result<Option<Queue>, CliError> {
+    let Some(capabilities) = get(client).await else {
+        return Ok(None);
+    };
+    let now = clock::now();
+    let mut problems: Vec<Problem> = jobs
+        .iter()
+        .filter_map(|job| check_lead(client, &capabilities, job.start, now))
+        .collect();
+    let queue = queue(client, &capabilities).await;
+    if let Some(queue) = &queue {
+        if queue.free() < jobs.len() as u64 {
+            problems.push(queue.full());
+        }
+    }
+    validation::into_result(problems)?;
+    Ok(queue)
+}
@@ -0,0 +1,400 @@
+use crate::client::{ApiClient, CatalogEntry, SatelliteDTO, TleData};
+use crate::error::CliError;
+use crate::history::{self, Suggestions};
+use crate::satellite_id;
+use crate::satellites::Preset;
+use crate::text;
+use crate::tle;
+use crate::transcript::Recorded;
+use crate::OutputFormat;
+use inquire::autocompletion::{Autocomplete, Replacement};
+use inquire::{CustomUserError, Text};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Characters typed before the catalog is asked
+const MIN_QUERY: usize = 2;
+/// Entries asked for per lookup; fewer back means the answer was complete
+pub const SEARCH_LIMIT: usize = 20;
+/// A lookup slower than this is dropped, and the catalog with it, so typing never stalls
+const LOOKUP_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// The catalog entry an element set becomes, after the same checks jobs get
+fn entry_for(tle: TleData) -> Result<SatelliteDTO, CliError> {
+    if let Some((field, reason)) = tle::validate(&tle).into_iter().next() {
+        return Err(CliError::validation(field, reason));
+    }
+    let norad_id = tle::norad_id(&tle.tle1).ok_or_else(|| {
+        CliError::validation("tle.tle1", "columns 3–7 hold no NORAD catalog number")
+    })?;
+
+    Ok(SatelliteDTO {
+        norad_id,
+        tle,
+        updated_at: None,
+    })
+}
+
+/// Upload an element set to the server's catalog, replacing the stored one when `update` is set
+pub async fn push(client: &ApiClient, tle: TleData, update: bool) -> Result<(), CliError> {
+    let entry = entry_for(tle)?;
+    let norad_id = entry.norad_id;
+
+    let response = if update {
+        client.update_satellite(&entry).await.map_err(|e| {
+            e.with_conflict_hint(format!(
+                "the catalog refused the new elements (it may hold a newer epoch); \
+                 compare them with `rustar-cli tle pull {}`",
+                norad_id
+            ))
+        })?
+    } else {
+        client.create_satellite(&entry).await.map_err(|e| {
+            e.with_conflict_hint(format!(
+                "NORAD {} is already in the catalog; compare with `rustar-cli tle pull {}` \
+                 and replace it with `rustar-cli tle push --update`",
+                norad_id, norad_id
+            ))
+        })?
+    };
+
+    let action = if update { "updated" } else { "added" };
+    println!(
+        "✅ NORAD {} ({}) {}: {}",
+        norad_id,
+        entry.tle.tle0,
+        action,
+        response.status()
+    );
+    if let Some(message) = &response.message {
+        println!("   {}", message);
+    }
+    Ok(())
+}
+
+/// One field whose value differs between two element sets
+#[derive(Debug, Serialize)]
+struct Difference {
+    field: &'static str,
+    local: String,
+    server: String,
+}
+
+fn diff(local: &TleData, server: &TleData) -> Vec<Difference> {
+    tle::fields(local)
+        .into_iter()
+        .zip(tle::fields(server))
+        .filter(|((_, a), (_, b))| a != b)
+        .map(|((field, local), (_, server))| Difference {
+            field,
+            local,
+            server,
+        })
+        .collect()
+}
+
+/// Element set to compare the catalog against: a file, else the fetcher/cache
+async fn comparison(norad_id: u32, file: Option<&Path>) -> Result<Option<TleData>, CliError> {
+    if let Some(path) = file {
+        return tle::read_file(path).map(Some);
+    }
+    match tle::resolve(norad_id).await {
+        Ok(tle) => Ok(Some(tle)),
+        Err(e) => {
+            eprintln!(
+                "⚠️ No local elements to compare against: {}",
+                e.full_message()
+            );
+            Ok(None)
+        }
+    }
+}
+
+fn print_fields(tle: &TleData) {
+    let fields = tle::fields(tle);
+    let width = fields.iter().map(|(k, _)| k.len()).max().unwrap_or(0);
+    for (field, value) in fields {
+        println!("  {:<width$} : {}", field, value, width = width);
+    }
+}
+
+/// Show the catalog's elements for a NORAD ID and how they differ from a local copy
+pub async fn pull(
+    client: &ApiClient,
+    norad_id: u32,
+    file: Option<&Path>,
+    compare: bool,
+    output: OutputFormat,
+) -> Result<(), CliError> {
+    let stored = client
+        .satellite(norad_id)
+        .await?
+        .ok_or_else(|| CliError::NotFound {
+            message: Some(format!("NORAD {} is not in the server's catalog", norad_id)),
+        })?;
+
+    let local = if compare {
+        comparison(norad_id, file).await?
+    } else {
+        None
+    };
+    let differences = local.as_ref().map(|local| diff(local, &stored.tle));
+
+    if output == OutputFormat::Json {
+        let document = serde_json::json!({
+            "server": stored,
+            "local": local,
+            "differences": differences,
+        });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&document)
+                .map_err(|e| CliError::parse("catalog entry", e))?
+        );
+        return Ok(());
+    }
+
+    println!(
+        "🛰️ Catalog entry for {}",
+        satellite_id::describe(norad_id, tle::intl_designator(&stored.tle.tle1).as_deref())
+    );
+    print_fields(&stored.tle);
+    if let Some(updated_at) = stored.updated_at {
+        println!("  stored {}", updated_at);
+    }
+
+    match differences {
+        None => {}
+        Some(differences) if differences.is_empty() => {
+            println!("\n✅ Local elements match the catalog");
+        }
+        Some(differences) => {
+            println!(
+                "\n🔀 {} field(s) differ (local → server):",
+                differences.len()
+            );
+            let width = differences.iter().map(|d| d.field.len()).max().unwrap_or(0);
+            for d in differences {
+                println!(
+                    "  {:<width$} : {} → {}",
+                    d.field,
+                    d.local,
+                    d.server,
+                    width = width
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Whether every character of `query` appears in `name` in order, ignoring case and spaces:
+/// "noaa19" finds "NOAA 19"
+pub fn matches(query: &str, name: &str) -> bool {
+    let mut name = name.chars().flat_map(char::to_lowercase);
+    query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .all(|wanted| name.any(|c| c == wanted))
+}
+
+impl CatalogEntry {
+    /// How the entry reads in the suggestion list; also how a pick is recognised
+    fn label(&self) -> String {
+        format!("{} · {}", self.name, self.identity())
+    }
+
+    /// "NORAD 25544 · 1998-067A", the designator taken from the stored elements
+    pub fn identity(&self) -> String {
+        satellite_id::describe(
+            self.norad_id,
+            self.tle1
+                .as_deref()
+                .and_then(tle::intl_designator)
+                .as_deref(),
+        )
+    }
+
+    /// The stored element set, named as the catalog names the satellite
+    pub fn tle(&self) -> Option<TleData> {
+        Some(TleData {
+            tle0: self.name.clone(),
+            tle1: self.tle1.clone()?,
+            tle2: self.tle2.clone()?,
+        })
+    }
+
+    /// The catalog's defaults, as the prompts take an alias preset
+    pub fn preset(&self) -> Preset {
+        Preset {
+            norad_id: Some(self.norad_id),
+            intl_designator: self.tle1.as_deref().and_then(tle::intl_designator),
+            rx_frequency: self.rx_frequency,
+            rx_offset_hz: None,
+            tx_frequency: self.tx_frequency,
+            mode: self.mode.clone(),
+            baud: self.baud,
+            transponder: None,
+        }
+    }
+}
+
+/// What the lookups have found so far; shared with the copies inquire makes of the hook
+#[derive(Default)]
+struct Found {
+    /// Lowercased query to the entries the server returned for it
+    answers: HashMap<String, Vec<CatalogEntry>>,
+    /// Why the catalog stopped being asked, once it has
+    unavailable: Option<String>,
+}
+
+/// Satellite name suggestions: catalog entries matching what is typed, then earlier answers.
+/// inquire calls the hook on every keystroke with no timer to wait on, so lookups are held
+/// to queries of `MIN_QUERY` characters or more, answered once each, and narrowed locally
+/// when a shorter query already came back complete.
+#[derive(Clone)]
+pub struct Search {
+    client: ApiClient,
+    local: Suggestions,
+    found: Arc<Mutex<Found>>,
+}
+
+impl Search {
+    pub fn new(client: &ApiClient) -> Self {
+        Self {
+            client: client.with_timeout(LOOKUP_TIMEOUT),
+            local: Suggestions::new(history::Prompt::SatelliteName, None),
+            found: Arc::default(),
+        }
+    }
+
+    fn found(&self) -> std::sync::MutexGuard<'_, Found> {
+        self.found.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Entries for `query`, from an earlier answer when one covers it
+    fn lookup(&self, query: &str) -> Vec<CatalogEntry> {
+        let key = query.trim().to_lowercase();
+        if key.chars().count() < MIN_QUERY {
+            return Vec::new();
+        }
+        {
+            let mut found = self.found();
+            if found.unavailable.is_some() {
+                return Vec::new();
+            }
+            if let Some(entries) = found.answers.get(&key) {
+                return entries.clone();
+            }
+            let covering = found
+                .answers
+                .iter()
+                .filter(|(known, entries)| {
+                    key.starts_with(known.as_str()) && entries.len() < SEARCH_LIMIT
+                })
+                .max_by_key(|(known, _)| known.len())
+                .map(|(_, entries)| entries.clone());
+            if let Some(entries) = covering {
+                let narrowed: Vec<CatalogEntry> = entries
+                    .into_iter()
+                    .filter(|entry| {
+                        matches(&key, &entry.name)
+                            || entry.norad_id.to_string().starts_with(&key)
+                            || entry.identity().to_lowercase().contains(&key)
+                    })
+                    .collect();
+                found.answers.insert(key, narrowed.clone());
+                return narrowed;
+            }
+        }
+
+        // The prompt runs inside the runtime; wait for the answer on this worker thread
+        let client = self.client.clone();
+        let answer = tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current()
+                .block_on(client.search_satellites(query.trim(), SEARCH_LIMIT))
+        });
+        let mut found = self.found();
+        match answer {
+            Ok(entries) => {
+                found.answers.insert(key, entries.clone());
+                entries
+            }
+            // Missing endpoint, refusal or timeout: the name is entered by hand from here on
+            Err(e) => {
+                found.unavailable = Some(e.full_message());
+                Vec::new()
+            }
+        }
+    }
+
+    /// The catalog entry an accepted answer stands for: a picked suggestion, or a name
+    /// typed out in full that only one entry carries
+    fn chosen(&self, answer: &str) -> Option<CatalogEntry> {
+        let answer = answer.trim();
+        let found = self.found();
+        let mut seen: Vec<&CatalogEntry> = found.answers.values().flatten().collect();
+        seen.sort_by_key(|entry| entry.norad_id);
+        seen.dedup_by_key(|entry| entry.norad_id);
+        if let Some(entry) = seen.iter().find(|entry| entry.label() == answer) {
+            return Some((*entry).clone());
+        }
+        let named: Vec<&&CatalogEntry> = seen
+            .iter()
+            .filter(|entry| text::same_name(&entry.name, answer))
+            .collect();
+        match named.as_slice() {
+            [entry] => Some((**entry).clone()),
+            _ => None,
+        }
+    }
+}
+
+impl Autocomplete for Search {
+    fn get_suggestions(&mut self, input: &str) -> Result<Vec<String>, CustomUserError> {
+        let entries = self.lookup(input);
+        let mut values: Vec<String> = entries.iter().map(CatalogEntry::label).collect();
+        for value in self.local.get_suggestions(input)? {
+            if !entries
+                .iter()
+                .any(|entry| text::same_name(&entry.name, &value))
+            {
+                values.push(value);
+            }
+        }
+        Ok(values)
+    }
+
+    fn get_completion(
+        &mut self,
+        _input: &str,
+        highlighted: Option<String>,
+    ) -> Result<Replacement, CustomUserError> {
+        Ok(highlighted)
+    }
+}
+
+/// Ask for the satellite name, offering the server's catalog as it is typed.
+/// Returns the name and the catalog entry picked, if any.
+pub fn prompt_name(client: &ApiClient) -> Result<(String, Option<CatalogEntry>), CliError> {
+    let search = Search::new(client);
+    let answer = Text::new("🛰️ Satellite name:")
+        .with_placeholder("ISS (ZARYA)")
+        .with_autocomplete(search.clone())
+        .prompt()
+        .recorded("🛰️ Satellite name:")?;
+    if let Some(reason) = &search.found().unavailable {
+        eprintln!(
+            "ℹ️ Satellite catalog unavailable, so the name was taken as typed: {}",
+            reason
+        );
+    }
+    Ok(match search.chosen(&answer) {
+        Some(entry) => (entry.name.clone(), Some(entry)),
+        None => (answer.trim().to_string(), None),
+    })
+}
@@ -0,0 +1,277 @@
+use crate::client::{ApiClient, ApiResponse, JobDTO, JobRequestDTO, JobType};
+use crate::clock;
+use crate::error::CliError;
+use crate::humanize;
+use crate::orbit::Satellite;
+use crate::paths;
+use crate::predict::{self, Horizon};
+use crate::reschedule::{self, Change};
+use crate::schema::{Format, Store};
+use crate::storage;
+use crate::OutputFormat;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// How far either side of a chained window to look for the pass it is meant to cover
+const PASS_SEARCH_HOURS: i64 = 2;
+
+/// `chains.json`, which job was scheduled to start after which
+pub const STORE: Store = Store {
+    name: "job chain index",
+    format: Format::Json,
+    migrations: &[],
+};
+
+/// A job that starts `gap_seconds` after job `after` ends
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Link {
+    after: String,
+    gap_seconds: i64,
+}
+
+/// Job ID to the job it follows
+type Links = BTreeMap<String, Link>;
+
+#[derive(Deserialize)]
+struct Layout {
+    links: Links,
+}
+
+fn index_path() -> PathBuf {
+    paths::chains_file()
+}
+
+/// Read the chain index; a missing file is an empty index
+fn load() -> Result<Links, CliError> {
+    Ok(STORE
+        .load::<Layout>(&index_path())?
+        .map(|layout| layout.links)
+        .unwrap_or_default())
+}
+
+async fn record(id: &str, after: &str, gap: Duration) -> Result<(), CliError> {
+    let _lock = storage::lock_async(&index_path()).await?;
+    let mut links = load()?;
+    links.insert(
+        id.to_string(),
+        Link {
+            after: after.to_string(),
+            gap_seconds: gap.num_seconds(),
+        },
+    );
+    STORE.write(&index_path(), &json!({ "links": links }))
+}
+
+/// Where a job given `--after` starts: `gap` after the end of job `after`
+#[derive(Debug, Clone)]
+pub struct Anchor {
+    pub after: String,
+    pub gap: Duration,
+    pub start: DateTime<Utc>,
+}
+
+/// The anchor of the job being added, kept for `remember` once the server gives it an ID
+static PENDING: OnceLock<Anchor> = OnceLock::new();
+
+async fn fetch(client: &ApiClient, id: &str) -> Result<JobDTO, CliError> {
+    let value = client.job_json(id).await?;
+    if value.is_null() {
+        return Err(CliError::NotFound {
+            message: Some(format!("the server returned no body for job {}", id)),
+        });
+    }
+    serde_json::from_value(value).map_err(|e| CliError::parse(format!("job {}", id), e))
+}
+
+/// `jobs add --after`: fetch job `after` and start the new job `gap` after its end
+pub async fn anchor(client: &ApiClient, after: &str, gap: Duration) -> Result<Anchor, CliError> {
+    let previous = fetch(client, after).await?;
+    let start = previous.job.end + gap;
+    if start <= clock::now() {
+        return Err(CliError::validation(
+            "after",
+            format!(
+                "job {} ended at {}, so a job after it would start in the past",
+                after,
+                humanize::timestamp(previous.job.end)
+            ),
+        ));
+    }
+    println!(
+        "🔗 Starting at {}, {} after job {} ({}) ends\n",
+        humanize::timestamp(start),
+        humanize::duration(gap),
+        after,
+        previous.job.label()
+    );
+    let anchor = Anchor {
+        after: after.to_string(),
+        gap,
+        start,
+    };
+    let _ = PENDING.set(anchor.clone());
+    Ok(anchor)
+}
+
+/// Index the job just created after its anchor. Failures are warnings: the job exists
+/// either way.
+pub async fn remember(response: &ApiResponse) {
+    let Some(anchor) = PENDING.get() else {
+        return;
+    };
+    let Some(id) = &response.id else {
+        eprintln!(
+            "⚠️ The server returned no job ID, so jobs rebase cannot find what the job follows"
+        );
+        return;
+    };
+    if let Err(e) = record(id, &anchor.after, anchor.gap).await {
+        eprintln!(
+            "⚠️ Could not record that job {} follows job {}: {}",
+            id,
+            anchor.after,
+            e.full_message()
+        );
+    }
+}
+
+/// A tracking job pushed to start when another ends must still cover its pass: refuse a
+/// window that misses every pass, and warn about the part of one it cuts off. Without
+/// elements there is nothing to predict, so other job types pass as they are.
+pub fn check_pass(job: &JobRequestDTO, horizon: &Horizon) -> Result<(), CliError> {
+    let JobType::Tracking { tle } = &job.job_type else {
+        return Ok(());
+    };
+    let satellite = Satellite::from_tle(tle)?;
+    let margin = Duration::hours(PASS_SEARCH_HOURS);
+    let passes = predict::passes(&satellite, horizon, job.start - margin, job.end + margin)?;
+    let usable: Vec<(DateTime<Utc>, DateTime<Utc>)> = passes
+        .iter()
+        .filter_map(|pass| pass.aos.zip(pass.los))
+        .collect();
+    let Some((aos, los)) = usable
+        .iter()
+        .find(|(aos, los)| *aos < job.end && job.start < *los)
+    else {
+        let next = usable
+            .iter()
+            .find(|(aos, _)| *aos >= job.start)
+            .map(|(aos, _)| format!("; the next one begins at {}", humanize::timestamp(*aos)))
+            .unwrap_or_default();
+        return Err(CliError::validation(
+            "window",
+            format!(
+                "{} – {} covers no pass of {} over the station{}",
+                humanize::timestamp(job.start),
+                job.end.format("%H:%M:%S UTC"),
+                satellite.name,
+                next
+            ),
+        ));
+    };
+    if *aos < job.start {
+        eprintln!(
+            "⚠️ The window opens {} after AOS at {}; that part of the pass is lost",
+            humanize::duration(job.start - *aos),
+            aos.format("%H:%M:%S UTC")
+        );
+    }
+    if *los > job.end {
+        eprintln!(
+            "⚠️ The window closes {} before LOS at {}; that part of the pass is lost",
+            humanize::duration(*los - job.end),
+            los.format("%H:%M:%S UTC")
+        );
+    }
+    Ok(())
+}
+
+/// Refuse to put `id` after `after` when `after` already follows `id`, directly or
+/// through other jobs
+fn check_cycle(links: &Links, id: &str, after: &str) -> Result<(), CliError> {
+    let mut path = vec![id.to_string(), after.to_string()];
+    let mut current = after;
+    while current != id {
+        let Some(link) = links.get(current) else {
+            return Ok(());
+        };
+        // A loop that does not pass through `id` was refused when it was made; stop anyway
+        if path[1..].contains(&link.after) {
+            return Ok(());
+        }
+        path.push(link.after.clone());
+        current = &link.after;
+    }
+    Err(CliError::validation(
+        "after",
+        format!(
+            "job {} would start after itself: {}",
+            id,
+            path.join(" → after ")
+        ),
+    ))
+}
+
+/// `jobs rebase`: move job `id` to start `gap` after job `after` ends, keeping its
+/// length. Without `--gap` the gap it was chained with is kept, 0 for a new link. With a
+/// `horizon` the new window is checked against the job's pass.
+pub async fn rebase(
+    client: &ApiClient,
+    id: &str,
+    after: &str,
+    gap: Option<Duration>,
+    horizon: Option<&Horizon<'_>>,
+    output: OutputFormat,
+) -> Result<(), CliError> {
+    let links = load()?;
+    check_cycle(&links, id, after)?;
+    let gap = gap.unwrap_or_else(|| match links.get(id) {
+        Some(link) if link.after == after => Duration::seconds(link.gap_seconds),
+        _ => Duration::zero(),
+    });
+    let previous = fetch(client, after).await?;
+    let start = previous.job.end + gap;
+    if let Some(horizon) = horizon {
+        let mut moved = fetch(client, id).await?.job;
+        moved.end = start + (moved.end - moved.start);
+        moved.start = start;
+        check_pass(&moved, horizon)?;
+    }
+    if output != OutputFormat::Json {
+        println!(
+            "🔗 Job {} ends at {}; job {} moves to start {} later",
+            after,
+            humanize::timestamp(previous.job.end),
+            id,
+            humanize::duration(gap)
+        );
+    }
+    reschedule::reschedule(
+        client,
+        id,
+        Change::Set {
+            start: Some(start),
+            end: None,
+        },
+        output,
+    )
+    .await?;
+    record(id, after, gap).await?;
+
+    let followers: Vec<&String> = links
+        .iter()
+        .filter(|(_, link)| link.after == id)
+        .map(|(follower, _)| follower)
+        .collect();
+    for follower in followers {
+        eprintln!(
+            "ℹ️ Job {} starts after job {}; jobs rebase {} --after {} moves it along",
+            follower, id, follower, id
+        );
+    }
+    Ok(())
+}
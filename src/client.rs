@@ -1,77 +1,2088 @@
+use crate::base_url;
+use crate::clock;
+use crate::download::{Digest, Downloaded, Partial, RecordingInfo};
+use crate::error::{CliError, ResultExt};
+use crate::event_stream::{Event, EventSplitter};
+use crate::frequency::{self, Frequency};
+use crate::hints;
+use crate::http_cache::{Cached, HttpCache};
+use crate::job_request::JobRequest;
+use crate::json_array::ArraySplitter;
+use crate::latency;
+use crate::phases::{self, Phase};
+use crate::progress::{self, Observer};
+use crate::receipt;
+use crate::schedule::{self, TimeWindow};
+use crate::snapshot;
+use crate::timing;
+use crate::transcript;
 use chrono::{DateTime, Utc};
+use reqwest::header::{
+    HeaderMap, CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE, ETAG, IF_MATCH, IF_MODIFIED_SINCE,
+    IF_NONE_MATCH, IF_RANGE, IF_UNMODIFIED_SINCE, LAST_MODIFIED, LOCATION, RANGE, RETRY_AFTER,
+};
+use reqwest::{StatusCode, Url};
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::collections::BTreeMap;
+use std::panic::AssertUnwindSafe;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::AsyncWriteExt;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TleData {
     pub tle0: String,
     pub tle1: String,
     pub tle2: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Azimuth/elevation grid a calibration job sweeps the rotator through, in degrees
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SweepPattern {
+    pub az_start: f64,
+    pub az_end: f64,
+    pub az_step: f64,
+    pub el_start: f64,
+    pub el_end: f64,
+    pub el_step: f64,
+}
+
+/// Where a job holds the antenna for its whole window, in degrees: a geostationary
+/// satellite, a beacon, anything without an element set
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct FixedPointing {
+    pub azimuth: f64,
+    pub elevation: f64,
+    /// What is being pointed at, shown in place of a satellite name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// What a job does, with the parameters only that kind of job needs.
+/// Serialized as a `job_type` tag beside the common job fields.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "job_type", rename_all = "snake_case")]
+pub enum JobType {
+    /// Follow a satellite described by its element set
+    Tracking { tle: TleData },
+    /// Sweep the rotator through an azimuth/elevation grid
+    Calibration { sweep: SweepPattern },
+    /// Hold the antenna at one azimuth/elevation; nothing to predict or Doppler-correct
+    Pointing { pointing: FixedPointing },
+    /// Loopback test of the RF chain; no pointing involved
+    Test,
+}
+
+impl JobType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            JobType::Tracking { .. } => "tracking",
+            JobType::Calibration { .. } => "calibration",
+            JobType::Pointing { .. } => "pointing",
+            JobType::Test => "test",
+        }
+    }
+}
+
+/// Jobs written before job types existed carry no tag; they are all tracking jobs
+impl<'de> Deserialize<'de> for JobType {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(tag = "job_type", rename_all = "snake_case")]
+        enum Tagged {
+            Tracking { tle: TleData },
+            Calibration { sweep: SweepPattern },
+            Pointing { pointing: FixedPointing },
+            Test,
+        }
+
+        let mut value = serde_json::Value::deserialize(deserializer)?;
+        if let Some(fields) = value.as_object_mut() {
+            fields
+                .entry("job_type")
+                .or_insert_with(|| "tracking".into());
+        }
+        Ok(
+            match Tagged::deserialize(value).map_err(serde::de::Error::custom)? {
+                Tagged::Tracking { tle } => JobType::Tracking { tle },
+                Tagged::Calibration { sweep } => JobType::Calibration { sweep },
+                Tagged::Pointing { pointing } => JobType::Pointing { pointing },
+                Tagged::Test => JobType::Test,
+            },
+        )
+    }
+}
+
+/// The fields each job type needs, picked by `job_type`; a job without one is tracking,
+/// as the deserializer reads it
+impl JsonSchema for JobType {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "JobType".into()
+    }
+
+    fn json_schema(generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        let tle = generator.subschema_for::<TleData>();
+        let sweep = generator.subschema_for::<SweepPattern>();
+        let pointing = generator.subschema_for::<FixedPointing>();
+        let is = |name: &str| {
+            serde_json::json!({
+                "properties": { "job_type": { "const": name } },
+                "required": ["job_type"],
+            })
+        };
+        schemars::json_schema!({
+            "type": "object",
+            "properties": {
+                "job_type": {
+                    "description": "tracking follows a satellite through `tle`, calibration sweeps the rotator through `sweep`, pointing holds the antenna at `pointing`, test loops the RF chain back",
+                    "enum": ["tracking", "calibration", "pointing", "test"],
+                    "default": "tracking",
+                },
+            },
+            "if": is("calibration"),
+            "then": {
+                "properties": { "sweep": sweep },
+                "required": ["sweep"],
+            },
+            "else": {
+                "if": is("pointing"),
+                "then": {
+                    "properties": { "pointing": pointing },
+                    "required": ["pointing"],
+                },
+                "else": {
+                    "if": is("test"),
+                    "then": {},
+                    "else": {
+                        "properties": { "tle": tle },
+                        "required": ["tle"],
+                    },
+                },
+            },
+        })
+    }
+}
+
+/// Feed polarization for the pass, for stations that can switch it (e.g. crossed yagis)
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, clap::ValueEnum,
+)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Polarization {
+    /// Right-hand circular
+    Rhcp,
+    /// Left-hand circular
+    Lhcp,
+    /// Linear, horizontal
+    #[value(alias = "horizontal")]
+    H,
+    /// Linear, vertical
+    #[value(alias = "vertical")]
+    V,
+}
+
+impl Polarization {
+    /// The name as the API spells it
+    pub fn code(&self) -> &'static str {
+        match self {
+            Polarization::Rhcp => "RHCP",
+            Polarization::Lhcp => "LHCP",
+            Polarization::H => "H",
+            Polarization::V => "V",
+        }
+    }
+}
+
+impl std::fmt::Display for Polarization {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let description = match self {
+            Polarization::Rhcp => "right-hand circular",
+            Polarization::Lhcp => "left-hand circular",
+            Polarization::H => "horizontal",
+            Polarization::V => "vertical",
+        };
+        write!(f, "{} ({})", self.code(), description)
+    }
+}
+
+/// What the rotator does once the window closes
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, clap::ValueEnum,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum PostPassAction {
+    /// Return to the park position
+    Park,
+    /// Stay where the pass ended
+    Hold,
+    /// Turn straight to where the next job starts
+    NextJob,
+}
+
+impl std::fmt::Display for PostPassAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            PostPassAction::Park => "park the rotator",
+            PostPassAction::Hold => "hold the last position",
+            PostPassAction::NextJob => "point at the next job",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[schemars(
+    rename = "Job",
+    deny_unknown_fields,
+    transform = crate::input_schema::legacy_names
+)]
 pub struct JobRequestDTO {
+    #[serde(with = "crate::schedule::wire_time")]
+    #[schemars(with = "DateTime<Utc>")]
     pub start: DateTime<Utc>,
+    #[serde(with = "crate::schedule::wire_time")]
+    #[schemars(with = "DateTime<Utc>")]
     pub end: DateTime<Utc>,
-    pub tle: TleData,
-    pub rx_frequency: f64,
-    pub tx_frequency: f64,
+    #[serde(flatten)]
+    pub job_type: JobType,
+    #[serde(alias = "rx_freq")]
+    pub rx_frequency: Frequency,
+    /// Correction already added to `rx_frequency`, in signed hertz, for a satellite that
+    /// transmits off its published downlink
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rx_offset_hz: Option<i64>,
+    #[serde(alias = "tx_freq")]
+    pub tx_frequency: Frequency,
+    /// Modulation or decoder name (e.g. "AFSK", "APT"), left to the station default when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    /// Symbol rate for the decoder, when the mode needs one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub baud: Option<u32>,
+    /// Uplink power in dBm EIRP, for jobs that transmit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tx_power_dbm: Option<f64>,
+    /// Share of the window the uplink is keyed, 0 to 1
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tx_duty_cycle: Option<f64>,
+    /// Feed polarization, left to the station default when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub polarization: Option<Polarization>,
+    /// Rotator behaviour after the window, left to the station default when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_pass_action: Option<PostPassAction>,
+    /// Campaign the job belongs to, for servers that keep it; also indexed locally
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub campaign: Option<String>,
+}
+
+impl JobRequestDTO {
+    pub fn window(&self) -> TimeWindow {
+        TimeWindow::new(self.start, self.end)
+    }
+
+    /// Name to show for the job: the satellite, or the kind of job when nothing is tracked
+    pub fn label(&self) -> &str {
+        match &self.job_type {
+            JobType::Tracking { tle } => &tle.tle0,
+            JobType::Calibration { .. } => "calibration sweep",
+            JobType::Pointing { pointing } => pointing.name.as_deref().unwrap_or("fixed pointing"),
+            JobType::Test => "loopback test",
+        }
+    }
+
+    /// The downlink as published, before the job's offset
+    pub fn published_rx(&self) -> Frequency {
+        self.rx_frequency.shifted(-self.rx_offset_hz.unwrap_or(0))
+    }
+
+    /// "RX 145.8 MHz +2.1 kHz → 145.8021 MHz", for jobs listening off the published downlink
+    pub fn describe_rx_offset(&self) -> Option<String> {
+        let offset = self.rx_offset_hz.filter(|hz| *hz != 0)?;
+        Some(format!(
+            "RX {} {} → {}",
+            self.published_rx(),
+            frequency::describe_offset(offset),
+            self.rx_frequency
+        ))
+    }
 }
 
-#[derive(Debug, Deserialize)]
+/// Media type of an RFC 7386 JSON Merge Patch
+const MERGE_PATCH: &str = "application/merge-patch+json";
+
+/// How a server takes a change to one job
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobUpdate {
+    /// `PATCH` with a JSON Merge Patch of the changed fields
+    MergePatch,
+    /// `PUT` of the whole updated job
+    Replace,
+}
+
+/// The version of a job a change is made against, so that the server can refuse it when
+/// someone else changed the job in between: the `ETag` of the fetch, else the job's
+/// `updated_at`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Version {
+    /// Sent back as `If-Match`
+    ETag(String),
+    /// Sent back as `If-Unmodified-Since`, to the second as HTTP dates go
+    UpdatedAt(DateTime<Utc>),
+}
+
+impl Version {
+    /// The precondition header a change against this version carries
+    fn precondition(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match self {
+            Version::ETag(etag) => request.header(IF_MATCH, etag),
+            Version::UpdatedAt(at) => request.header(
+                IF_UNMODIFIED_SINCE,
+                at.format("%a, %d %b %Y %H:%M:%S GMT").to_string(),
+            ),
+        }
+    }
+}
+
+/// A job exactly as the server stores it, with the version a change to it is made against
+/// when the server gave one
+#[derive(Debug)]
+pub struct Versioned {
+    pub value: serde_json::Value,
+    pub version: Option<Version>,
+}
+
+/// What the server answers to a write. Servers have sent it without `status` and with
+/// an empty body, so nothing here is required.
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct ApiResponse {
-    pub status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub message: Option<String>,
+    /// Identifier of the created job, when the server reports one
+    #[serde(
+        default,
+        alias = "job_id",
+        deserialize_with = "deserialize_opt_id",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub id: Option<String>,
+    /// What the server warned about while still accepting the request
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<ServerWarning>,
+    /// Whatever else the server answered, kept for job snapshots
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl ApiResponse {
+    /// The server's word for the outcome; a success answer without one is just "ok"
+    pub fn status(&self) -> &str {
+        self.status.as_deref().unwrap_or("ok")
+    }
+
+    /// Where to follow a job the server accepted to create later, answered with `--no-wait`
+    pub fn location(&self) -> Option<&str> {
+        self.extra.get("location").and_then(|l| l.as_str())
+    }
+}
+
+/// A warning the server attached to an answer it otherwise accepted, such as an element
+/// set older than it recommends. Servers send plain strings or objects; both read into this.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "WarningForm")]
+pub struct ServerWarning {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    pub message: String,
+    /// The request field the warning is about
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+}
+
+/// The shapes a server warning arrives in
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum WarningForm {
+    Text(String),
+    Detailed {
+        #[serde(default)]
+        code: Option<String>,
+        message: String,
+        #[serde(default)]
+        field: Option<String>,
+    },
+    /// Anything else, kept as it came rather than failing the whole answer
+    Other(serde_json::Value),
+}
+
+impl From<WarningForm> for ServerWarning {
+    fn from(form: WarningForm) -> Self {
+        match form {
+            WarningForm::Text(message) => ServerWarning {
+                code: None,
+                message,
+                field: None,
+            },
+            WarningForm::Detailed {
+                code,
+                message,
+                field,
+            } => ServerWarning {
+                code,
+                message,
+                field,
+            },
+            WarningForm::Other(value) => ServerWarning {
+                code: None,
+                message: value.to_string(),
+                field: None,
+            },
+        }
+    }
+}
+
+/// Where a job accepted for later creation stands, by the status its Location reports
+enum CreationState {
+    Pending,
+    Rejected,
+    Created,
+}
+
+fn creation_state(answer: &ApiResponse) -> CreationState {
+    match answer.status().to_ascii_lowercase().as_str() {
+        "accepted" | "pending" | "processing" | "queued" => CreationState::Pending,
+        "rejected" | "failed" | "invalid" | "error" => CreationState::Rejected,
+        _ => CreationState::Created,
+    }
+}
+
+/// Accept job IDs sent either as JSON numbers or strings
+fn deserialize_opt_id<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum RawId {
+        Number(u64),
+        Text(String),
+    }
+
+    Ok(
+        Option::<RawId>::deserialize(deserializer)?.map(|raw| match raw {
+            RawId::Number(n) => n.to_string(),
+            RawId::Text(s) => s,
+        }),
+    )
+}
+
+/// A scheduled job as listed by `GET /jobs` and returned by `GET /jobs/{id}`. Fields the
+/// CLI does not know are ignored rather than refused: servers add them without notice.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobDTO {
+    #[serde(default, alias = "job_id", deserialize_with = "deserialize_opt_id")]
+    pub id: Option<String>,
+    /// Older servers leave it out of list items
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(flatten)]
+    pub job: JobRequestDTO,
+    /// When the job was cancelled; the server keeps cancelled jobs for a day before purging
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cancelled_at: Option<DateTime<Utc>>,
+    /// Reason given when it was cancelled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cancel_reason: Option<String>,
+}
+
+/// Status of a job that was cancelled but not yet purged
+pub const CANCELLED: &str = "cancelled";
+
+impl JobDTO {
+    /// Cancelled and waiting to be purged, so it can still be restored
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled_at.is_some() || self.status.as_deref() == Some(CANCELLED)
+    }
+
+    /// The order jobs are listed in: by start, then by ID, so jobs that start together
+    /// come out the same way every time
+    pub fn schedule_order(a: &Self, b: &Self) -> std::cmp::Ordering {
+        (a.job.start, &a.id).cmp(&(b.job.start, &b.id))
+    }
+}
+
+/// What a finished job produced, as `GET /jobs/{id}/results` reports it
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct JobResults {
+    pub frames_decoded: Option<u64>,
+    #[serde(default)]
+    pub recordings: Vec<Recording>,
+    /// The station's or its operator's remarks on the pass
+    pub notes: Option<String>,
+    /// Fields this CLI does not know about yet, kept for display
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, serde_json::Value>,
+}
+
+/// One file the station recorded during a job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Recording {
+    pub name: String,
+    #[serde(default, alias = "size")]
+    pub size_bytes: Option<u64>,
 }
 
+/// A slot the station keeps for a job until `expires_at`, from `POST /jobs/reserve`
+#[derive(Debug, Clone)]
+pub struct Hold {
+    pub id: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// `POST /jobs/reserve` as sent; checked into a `Hold`
+#[derive(Debug, Default, Deserialize)]
+struct HoldDTO {
+    #[serde(default, alias = "hold_id", deserialize_with = "deserialize_opt_id")]
+    id: Option<String>,
+    #[serde(default, alias = "deadline")]
+    expires_at: Option<DateTime<Utc>>,
+}
+
+/// A period during which the station will not run jobs, from `GET /maintenance`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceWindow {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub reason: Option<String>,
+}
+
+impl MaintenanceWindow {
+    pub fn window(&self) -> TimeWindow {
+        TimeWindow::new(self.start, self.end)
+    }
+}
+
+/// Ground station status as reported by `GET /station`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StationInfo {
+    pub name: Option<String>,
+    pub location: Option<serde_json::Value>,
+    #[serde(default)]
+    pub antennas: Vec<serde_json::Value>,
+    pub azimuth: Option<f64>,
+    pub elevation: Option<f64>,
+    pub uptime_seconds: Option<u64>,
+    pub queue_depth: Option<u64>,
+    pub disk_free_bytes: Option<u64>,
+    /// Polarizations the antennas can be switched to, when the station reports them
+    pub polarizations: Option<Vec<String>>,
+    /// Version of the scheduling API the server speaks, when it says
+    pub api_version: Option<String>,
+    /// Least time between a job's submission and its start, when the station reports it
+    pub scheduling_lead_seconds: Option<u64>,
+    /// Fields this CLI does not know about yet, kept for display
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, serde_json::Value>,
+}
+
+/// Limits the station scheduler enforces, as `GET /station/capabilities` reports them
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Capabilities {
+    /// Least time between a job's submission and its start
+    pub min_lead_seconds: Option<u64>,
+    /// Most jobs the station keeps pending at once
+    pub max_pending_jobs: Option<u64>,
+}
+
+/// Who the server takes the caller to be, as reported by `GET /auth/me`
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Identity {
+    #[serde(alias = "sub", alias = "username")]
+    pub subject: Option<String>,
+    #[serde(alias = "iss")]
+    pub issuer: Option<String>,
+    /// When the credentials the server saw stop being accepted
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(default, alias = "permissions")]
+    pub scopes: Vec<String>,
+    /// Fields this CLI does not know about yet, kept for display
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, serde_json::Value>,
+}
+
+/// An element set stored in the server's satellite catalog
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SatelliteDTO {
+    pub norad_id: u32,
+    #[serde(flatten)]
+    pub tle: TleData,
+    /// When the server last stored these elements
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// A satellite the server knows how to track, as listed by `GET /satellites?q=`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogEntry {
+    pub norad_id: u32,
+    pub name: String,
+    /// Element set the catalog holds, when it has one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tle1: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tle2: Option<String>,
+    /// Default downlink, in Hz
+    #[serde(default, alias = "rx_freq", skip_serializing_if = "Option::is_none")]
+    pub rx_frequency: Option<Frequency>,
+    /// Default uplink, in Hz
+    #[serde(default, alias = "tx_freq", skip_serializing_if = "Option::is_none")]
+    pub tx_frequency: Option<Frequency>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub baud: Option<u32>,
+}
+
+#[derive(Clone)]
 pub struct ApiClient {
     client: reqwest::Client,
+    /// Request paths are joined onto this; it ends in a slash
+    base: Url,
+    /// `base` as shown and compared, without the final slash
     base_url: String,
+    timeouts: Timeouts,
+    verbose: bool,
+    cache: Arc<HttpCache>,
+    observer: Arc<dyn Observer>,
+    creation: Creation,
+}
+
+/// Request time limits, as resolved from `--timeout` and the environment
+#[derive(Debug, Clone, Copy)]
+pub struct Timeouts {
+    /// Limit for ordinary requests, from sending to the end of the body
+    pub request: Duration,
+    /// Set by `--timeout` or the environment, so it also overrides the ping default
+    pub explicit: bool,
+    /// Longest silence tolerated while a streamed body is arriving
+    pub idle: Duration,
+}
+
+/// What a request is for, which decides its time limit
+#[derive(Debug, Clone, Copy)]
+enum Operation {
+    /// Reachability checks, which should fail fast
+    Ping,
+    /// Reading and changing jobs, the catalog and station state
+    Request,
+    /// Bodies read as they arrive: no overall limit, only the idle one
+    Stream,
+}
+
+/// Environment variable naming the API when `--base-url` is not given
+pub const BASE_URL_ENV: &str = "API_BASE_URL";
+/// Environment variable holding the request timeout in whole seconds
+pub const TIMEOUT_ENV: &str = "API_TIMEOUT_SECONDS";
+/// Environment variable holding the streaming idle timeout in whole seconds
+pub const IDLE_TIMEOUT_ENV: &str = "API_IDLE_TIMEOUT_SECONDS";
+pub const DEFAULT_BASE_URL: &str = "http://localhost:3000";
+pub const DEFAULT_TIMEOUT_SECONDS: u64 = 30;
+pub const DEFAULT_IDLE_TIMEOUT_SECONDS: u64 = 30;
+/// Pings give up sooner than other requests unless a timeout was set explicitly
+const PING_TIMEOUT: Duration = Duration::from_secs(5);
+/// How long `jobs add` follows a job the server accepted to create later
+pub const DEFAULT_CREATION_WAIT: Duration = Duration::from_secs(60);
+/// First pause between looks at an accepted job; it doubles up to the longest
+const CREATION_POLL_FIRST: Duration = Duration::from_millis(500);
+const CREATION_POLL_LONGEST: Duration = Duration::from_secs(5);
+
+/// What `add_job` does when the server accepts a job to create it later (202 Accepted
+/// with a `Location` to follow)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Creation {
+    /// Follow the job until the server creates or rejects it, for at most this long
+    Wait(Duration),
+    /// Answer at once with where the job can be followed
+    NoWait,
+}
+
+impl Default for Creation {
+    fn default() -> Self {
+        Creation::Wait(DEFAULT_CREATION_WAIT)
+    }
+}
+
+/// How the client keeps connections to the API, from the profile's `[connection]` table.
+/// The defaults keep one connection alive across a whole batch submitted in sequence.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default)]
+pub struct ConnectionSettings {
+    /// Close pooled connections left unused this long
+    pub pool_idle_timeout_seconds: u64,
+    /// Idle connections kept per host; a sequential batch needs one
+    pub pool_max_idle_per_host: usize,
+    /// TCP keepalive probes on open connections, 0 to turn them off
+    pub tcp_keepalive_seconds: u64,
+    pub http2: Http2,
+}
+
+impl Default for ConnectionSettings {
+    fn default() -> Self {
+        Self {
+            pool_idle_timeout_seconds: 90,
+            pool_max_idle_per_host: 4,
+            tcp_keepalive_seconds: 60,
+            http2: Http2::Auto,
+        }
+    }
+}
+
+/// Which HTTP version to speak to the API
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Http2 {
+    /// HTTP/2 when TLS negotiates it, HTTP/1.1 otherwise
+    #[default]
+    Auto,
+    /// HTTP/2 without negotiation, also over plain http
+    PriorKnowledge,
+    /// HTTP/1.1 only
+    Never,
+}
+
+/// Everything `ApiClient` is built from; start with `ApiClient::builder`
+pub struct ApiClientBuilder {
+    base_url: String,
+    timeouts: Timeouts,
+    connection: ConnectionSettings,
+    cache: HttpCache,
+    verbose: bool,
+    timing: bool,
+    observer: Arc<dyn Observer>,
+}
+
+impl ApiClientBuilder {
+    /// Pool, keepalive and HTTP version settings
+    pub fn connection(self, connection: ConnectionSettings) -> Self {
+        Self { connection, ..self }
+    }
+
+    /// Revalidate GET responses through `cache`
+    pub fn cache(self, cache: HttpCache) -> Self {
+        Self { cache, ..self }
+    }
+
+    /// Log each request's timeout
+    pub fn verbose(self, verbose: bool) -> Self {
+        Self { verbose, ..self }
+    }
+
+    /// Measure connection set-up, for `--timing`
+    pub fn timing(self, timing: bool) -> Self {
+        Self { timing, ..self }
+    }
+
+    /// Report requests, retries and download progress to `observer`
+    pub fn observer(self, observer: Arc<dyn Observer>) -> Self {
+        Self { observer, ..self }
+    }
+
+    pub fn build(self) -> Result<ApiClient, CliError> {
+        let base = base_url::parse(&self.base_url)?;
+        let base_url = base_url::display(&base);
+        let connection = self.connection;
+        // No client-wide timeout: each request sets its own, and streams have none
+        let mut builder = reqwest::Client::builder()
+            .connect_timeout(self.timeouts.request)
+            .pool_idle_timeout(Duration::from_secs(connection.pool_idle_timeout_seconds))
+            .pool_max_idle_per_host(connection.pool_max_idle_per_host)
+            .tcp_keepalive(
+                Some(Duration::from_secs(connection.tcp_keepalive_seconds))
+                    .filter(|keepalive| !keepalive.is_zero()),
+            );
+        builder = match connection.http2 {
+            Http2::Auto => builder,
+            Http2::PriorKnowledge => builder.http2_prior_knowledge(),
+            Http2::Never => builder.http1_only(),
+        };
+        if self.timing {
+            builder = builder
+                .dns_resolver(Arc::new(timing::TimedResolver))
+                .connector_layer(timing::ConnectTiming);
+        }
+        let client = builder.build()?;
+
+        let client = ApiClient {
+            client,
+            base,
+            base_url,
+            timeouts: self.timeouts,
+            verbose: self.verbose,
+            cache: Arc::new(self.cache),
+            observer: self.observer,
+            creation: Creation::default(),
+        };
+        client.observe(|o| o.on_client_ready(&client.base_url));
+        Ok(client)
+    }
 }
 
 impl ApiClient {
-    pub fn new() -> Result<Self, crate::error::CliError> {
-        dotenv::dotenv().ok();
+    /// Start building a client for `base_url`, as resolved by the start-up configuration
+    pub fn builder(base_url: &str, timeouts: Timeouts) -> ApiClientBuilder {
+        ApiClientBuilder {
+            base_url: base_url.to_string(),
+            timeouts,
+            connection: ConnectionSettings::default(),
+            cache: HttpCache::new(None, false),
+            verbose: false,
+            timing: false,
+            observer: Arc::new(progress::Silent),
+        }
+    }
 
-        let base_url =
-            std::env::var("API_BASE_URL").unwrap_or_else(|_| "http://localhost:3000".to_string());
+    /// Per-request timeout currently in effect
+    pub fn timeout(&self) -> Duration {
+        self.timeouts.request
+    }
 
-        let timeout_seconds: u64 = std::env::var("API_TIMEOUT_SECONDS")
-            .unwrap_or_else(|_| "30".to_string())
-            .parse()
-            .unwrap_or(30);
+    /// Same client and connection pool with a different per-request timeout
+    pub fn with_timeout(&self, timeout: Duration) -> Self {
+        Self {
+            timeouts: Timeouts {
+                request: timeout,
+                explicit: true,
+                ..self.timeouts
+            },
+            ..self.clone()
+        }
+    }
 
-        let client = reqwest::Client::builder()
-            .timeout(Duration::from_secs(timeout_seconds))
-            .build()
-            .map_err(crate::error::CliError::HttpError)?;
+    /// Same client and connection pool, treating jobs accepted for later creation this way
+    pub fn with_creation(&self, creation: Creation) -> Self {
+        Self {
+            creation,
+            ..self.clone()
+        }
+    }
 
-        println!("🌐 API Client initialized: {}", base_url);
+    /// A request to `path` with the time limit for `operation`, logged with `--verbose`
+    fn request(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        operation: Operation,
+    ) -> reqwest::RequestBuilder {
+        let request = self.client.request(method.clone(), self.url(path));
+        let limit = self.limit(operation);
+        if self.verbose {
+            match limit {
+                Some(limit) => eprintln!("⏱️ {} {}: timeout {}s", method, path, limit.as_secs()),
+                None => eprintln!(
+                    "⏱️ {} {}: no overall timeout, {}s idle timeout",
+                    method,
+                    path,
+                    self.timeouts.idle.as_secs()
+                ),
+            }
+        }
+        match limit {
+            Some(limit) => request.timeout(limit),
+            None => request,
+        }
+    }
 
-        Ok(Self { client, base_url })
+    /// Overall time limit for a kind of request; `None` for streams
+    fn limit(&self, operation: Operation) -> Option<Duration> {
+        match operation {
+            Operation::Ping if !self.timeouts.explicit => {
+                Some(PING_TIMEOUT.min(self.timeouts.request))
+            }
+            Operation::Ping | Operation::Request => Some(self.timeouts.request),
+            Operation::Stream => None,
+        }
     }
 
-    pub async fn add_job(&self, job: JobRequestDTO) -> Result<ApiResponse, crate::error::CliError> {
-        println!("🚀 Submitting job to: {}/jobs", self.base_url);
+    /// The server the client talks to, without a trailing slash
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
 
-        let response = self
-            .client
-            .post(&format!("{}/jobs", self.base_url))
+    /// Where a request to `path` goes, under the base URL's prefix
+    pub fn url(&self, path: &str) -> Url {
+        base_url::join(&self.base, path)
+    }
+
+    /// Whether the API answers its health check
+    pub async fn ping(&self) -> Result<(), CliError> {
+        let mut timer = timing::start(&reqwest::Method::GET, "/health");
+        let request = self.request(reqwest::Method::GET, "/health", Operation::Ping);
+        let response = self.send(request, &mut timer).await.map_err(|error| {
+            let limit = self.limit(Operation::Ping).unwrap_or(PING_TIMEOUT);
+            let hint = hints::hint(hints::classify(&error), &self.base_url, limit);
+            CliError::transport(error, hint)
+        })?;
+        check_status(response).await.map(drop)
+    }
+
+    /// Submit a checked job; build one with `JobRequestBuilder`
+    pub async fn add_job(&self, job: &JobRequest) -> Result<ApiResponse, CliError> {
+        let url = self.url("/jobs");
+        self.observe(|o| o.on_job_submit(url.as_str(), None));
+        transcript::job(&**job);
+
+        let mut timer = timing::start(&reqwest::Method::POST, "/jobs");
+        let request = self
+            .request(reqwest::Method::POST, "/jobs", Operation::Request)
             .header("Content-Type", "application/json")
-            .json(&job)
-            .send()
+            .json(&**job);
+        let submitted_at = clock::now();
+        let response = self
+            .send(request, &mut timer)
             .await
-            .map_err(crate::error::CliError::HttpError)?;
+            .map_err(|e| self.transport_error(e))?;
+        self.cache.invalidate("/jobs");
+
+        let response = check_status(response).await?;
+        let header = |name: &str| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        let location = header(LOCATION.as_str());
+        let request_id = header("x-request-id");
+
+        let answer = match location {
+            Some(location) if response.status() == StatusCode::ACCEPTED => {
+                self.follow_creation(&location).await?
+            }
+            _ => self
+                .read_json(response)
+                .await
+                .context("decoding the job submission response")?,
+        };
+        snapshot::record(job, &answer);
+        receipt::write(job, &answer, request_id.as_deref(), submitted_at).await;
+        Ok(answer)
+    }
+
+    /// Follow a job the server accepted to create later, at `location`, until it is
+    /// created or rejected, so the outcome reads as a synchronous answer would. Lost
+    /// looks and server errors are retried until the deadline, since the job is with the
+    /// server either way and submitting it again could schedule it twice.
+    async fn follow_creation(&self, location: &str) -> Result<ApiResponse, CliError> {
+        let url = self
+            .url("/jobs")
+            .join(location)
+            .map_err(|e| CliError::parse(format!("the Location '{}'", location), e))?;
+        let limit = match self.creation {
+            Creation::Wait(limit) => limit,
+            Creation::NoWait => {
+                let mut extra = serde_json::Map::new();
+                extra.insert("location".to_string(), url.as_str().into());
+                return Ok(ApiResponse {
+                    status: Some("accepted".to_string()),
+                    message: Some(format!(
+                        "The server is still creating the job; follow it at {}",
+                        url
+                    )),
+                    id: None,
+                    warnings: Vec::new(),
+                    extra,
+                });
+            }
+        };
+        eprintln!(
+            "⏳ The server accepted the job to create it later; waiting up to {}s",
+            limit.as_secs()
+        );
+
+        let started = Instant::now();
+        let mut pause = CREATION_POLL_FIRST;
+        loop {
+            let left = limit.saturating_sub(started.elapsed());
+            if left.is_zero() {
+                return Err(CliError::Pending {
+                    location: url.to_string(),
+                    waited: limit,
+                });
+            }
+            tokio::time::sleep(pause.min(left)).await;
+            pause = (pause * 2).min(CREATION_POLL_LONGEST);
 
-        if !response.status().is_success() {
-            return Err(crate::error::CliError::HttpError(reqwest::Error::from(
-                response.error_for_status().unwrap_err(),
-            )));
+            let mut timer = timing::start(&reqwest::Method::GET, url.path());
+            let request = self
+                .client
+                .get(url.clone())
+                .timeout(self.timeouts.request.min(left));
+            let Ok(response) = self.send(request, &mut timer).await else {
+                continue;
+            };
+            if let Some(seconds) = response
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.trim().parse::<u64>().ok())
+            {
+                pause = Duration::from_secs(seconds).min(CREATION_POLL_LONGEST);
+            }
+            let status = response.status();
+            if status == StatusCode::ACCEPTED || status.is_server_error() {
+                continue;
+            }
+            let response = check_status(response).await?;
+            let answer: ApiResponse = self
+                .read_json(response)
+                .await
+                .context("decoding the state of the accepted job")?;
+            match creation_state(&answer) {
+                CreationState::Pending => continue,
+                CreationState::Rejected => {
+                    let message = answer.message.clone().or_else(|| {
+                        answer
+                            .extra
+                            .get("error")
+                            .and_then(|e| e.as_str())
+                            .map(str::to_string)
+                    });
+                    return Err(CliError::Api {
+                        status: StatusCode::UNPROCESSABLE_ENTITY,
+                        message,
+                        request_id: None,
+                    });
+                }
+                CreationState::Created => return Ok(answer),
+            }
         }
+    }
 
-        response
-            .json::<ApiResponse>()
+    /// Have the station keep the job's slot while the operator reviews it; the job is
+    /// only scheduled once the hold is confirmed
+    pub async fn reserve_job(&self, job: &JobRequest) -> Result<Hold, CliError> {
+        transcript::job(&**job);
+        let dto: HoldDTO = self
+            .send_json_as(reqwest::Method::POST, "/jobs/reserve", &**job)
             .await
-            .map_err(crate::error::CliError::HttpError)
+            .with_context(|| format!("reserving the slot for {}", job.label()))?;
+        match dto {
+            HoldDTO {
+                id: Some(id),
+                expires_at: Some(expires_at),
+            } => Ok(Hold { id, expires_at }),
+            _ => Err(CliError::parse(
+                "the reservation",
+                "the server answered without a hold ID and deadline",
+            )),
+        }
+    }
+
+    /// Turn a hold into the scheduled job
+    pub async fn confirm_hold(&self, hold: &str) -> Result<ApiResponse, CliError> {
+        let url = self.url("/jobs");
+        self.observe(|o| o.on_job_submit(url.as_str(), Some(hold)));
+        let response = self
+            .send_json(
+                reqwest::Method::POST,
+                &format!("/jobs/{}/confirm", hold),
+                &serde_json::json!({}),
+            )
+            .await;
+        self.cache.invalidate("/jobs");
+        response.with_context(|| format!("confirming hold {}", hold))
+    }
+
+    /// Give a held slot back before its deadline
+    pub async fn release_hold(&self, hold: &str) -> Result<(), CliError> {
+        let path = format!("/jobs/{}", hold);
+        let mut timer = timing::start(&reqwest::Method::DELETE, &path);
+        let request = self.request(reqwest::Method::DELETE, &path, Operation::Request);
+        let response = self
+            .send(request, &mut timer)
+            .await
+            .map_err(|e| self.transport_error(e))?;
+        check_status(response)
+            .await
+            .map(drop)
+            .with_context(|| format!("releasing hold {}", hold))
+    }
+
+    pub async fn list_jobs(&self) -> Result<Vec<JobDTO>, CliError> {
+        self.get_json("/jobs").await.context("listing jobs")
+    }
+
+    /// The job list decoded as it arrives, for lists too big to hold at once, with the
+    /// cancelled jobs the server has not purged yet when `include_cancelled` is set.
+    /// Bypasses the response cache, which needs the whole body.
+    pub async fn stream_jobs(&self, include_cancelled: bool) -> Result<JobStream, CliError> {
+        let path = if include_cancelled {
+            "/jobs?include_cancelled=true"
+        } else {
+            "/jobs"
+        };
+        let mut timer = timing::start(&reqwest::Method::GET, path);
+        let request = self.request(reqwest::Method::GET, path, Operation::Stream);
+        // The headers still have to arrive within the ordinary limit
+        let response = tokio::time::timeout(self.timeouts.request, self.send(request, &mut timer))
+            .await
+            .map_err(|_| silence(self.timeouts.request))
+            .context("listing jobs")?
+            .map_err(|e| self.transport_error(e))
+            .context("listing jobs")?;
+        let response = check_status(response).await.context("listing jobs")?;
+        Ok(JobStream {
+            total: response.content_length(),
+            received: 0,
+            client: self.clone(),
+            response,
+            splitter: ArraySplitter::default(),
+            idle: self.timeouts.idle,
+            done: false,
+            salvage: false,
+            cut: None,
+            _timer: timer,
+        })
+    }
+
+    /// Server-sent events about the schedule from `GET /jobs/events`, for views that follow
+    /// it as it changes. None when the server offers no such stream, so the caller polls.
+    pub async fn job_events(&self) -> Result<Option<JobEvents>, CliError> {
+        let path = "/jobs/events";
+        let mut timer = timing::start(&reqwest::Method::GET, path);
+        let request = self
+            .request(reqwest::Method::GET, path, Operation::Stream)
+            .header(reqwest::header::ACCEPT, EVENT_STREAM);
+        // The headers still have to arrive within the ordinary limit
+        let response = tokio::time::timeout(self.timeouts.request, self.send(request, &mut timer))
+            .await
+            .map_err(|_| silence(self.timeouts.request))
+            .context("following job events")?
+            .map_err(|e| self.transport_error(e))
+            .context("following job events")?;
+        let streams = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|kind| kind.starts_with(EVENT_STREAM));
+        if !response.status().is_success() || !streams {
+            return Ok(None);
+        }
+        Ok(Some(JobEvents {
+            response,
+            splitter: EventSplitter::default(),
+            _timer: timer,
+        }))
+    }
+
+    /// A job exactly as the server stores it, unknown fields included
+    pub async fn job_json(&self, id: &str) -> Result<serde_json::Value, CliError> {
+        self.get_json(&format!("/jobs/{}", id))
+            .await
+            .with_context(|| format!("fetching job {}", id))
+    }
+
+    /// What job `id` produced; none when the server keeps no results for it, or has no
+    /// results endpoint at all
+    pub async fn job_results(&self, id: &str) -> Result<Option<JobResults>, CliError> {
+        match self.get_json(&format!("/jobs/{}/results", id)).await {
+            Err(CliError::NotFound { .. }) => Ok(None),
+            result => result.with_context(|| format!("fetching the results of job {}", id)),
+        }
+    }
+
+    /// What the server says about recording `name` of job `id`, from a HEAD request, so a
+    /// file already downloaded can be checked without fetching it again
+    pub async fn recording_info(&self, id: &str, name: &str) -> Result<RecordingInfo, CliError> {
+        let path = format!("/jobs/{}/results/{}", id, name);
+        let mut timer = timing::start(&reqwest::Method::HEAD, &path);
+        let request = self.request(reqwest::Method::HEAD, &path, Operation::Request);
+        let response = self
+            .send(request, &mut timer)
+            .await
+            .map_err(|e| self.transport_error(e))?;
+        let response = check_status(response)
+            .await
+            .with_context(|| format!("looking up recording {} of job {}", name, id))?;
+        Ok(RecordingInfo {
+            length: response
+                .headers()
+                .get(CONTENT_LENGTH)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse().ok()),
+            etag: header_string(response.headers(), ETAG),
+            digest: Digest::from_headers(response.headers())?,
+        })
+    }
+
+    /// Fetch recording `name` of job `id` to `dest`, picking up where an earlier attempt
+    /// left its `.partial` file when the server still holds the same recording. The whole
+    /// file is checked against the server's digest, when it sends one, before it is
+    /// renamed to `dest`; a file that does not match is deleted. A body that times out or
+    /// breaks off leaves what arrived in the `.partial` file, and the error says how much.
+    pub async fn download_recording(
+        &self,
+        id: &str,
+        name: &str,
+        dest: &Path,
+    ) -> Result<Downloaded, CliError> {
+        let path = format!("/jobs/{}/results/{}", id, name);
+        let url = self.url(&path).to_string();
+        let partial = Partial::of(dest);
+        let mut resume = partial.resumable(&url);
+
+        let mut timer = timing::start(&reqwest::Method::GET, &path);
+        let mut response = loop {
+            let mut request = self.request(reqwest::Method::GET, &path, Operation::Stream);
+            if let Some((saved, etag)) = &resume {
+                request = request
+                    .header(RANGE, format!("bytes={}-", saved))
+                    .header(IF_RANGE, etag);
+            }
+            let response = self
+                .send(request, &mut timer)
+                .await
+                .map_err(|e| self.transport_error(e))?;
+            // Already whole, or shorter than what was saved: start over
+            if response.status() == StatusCode::RANGE_NOT_SATISFIABLE && resume.take().is_some() {
+                partial.discard()?;
+                continue;
+            }
+            break check_status(response)
+                .await
+                .with_context(|| format!("downloading recording {} of job {}", name, id))?;
+        };
+
+        let headers = response.headers();
+        let resumed_from = match (&resume, response.status()) {
+            (Some((saved, _)), StatusCode::PARTIAL_CONTENT) => {
+                match content_range(headers).map(|(start, _)| start) {
+                    Some(start) if start == *saved => *saved,
+                    _ => {
+                        partial.discard()?;
+                        return Err(CliError::parse(
+                            format!("the range of recording {}", name),
+                            format!("asked for the bytes from {}, got something else", saved),
+                        ));
+                    }
+                }
+            }
+            // The If-Range ETag no longer matches, so the whole recording came back
+            _ => 0,
+        };
+        let total = match content_range(headers) {
+            Some((_, total)) => total,
+            None => response
+                .content_length()
+                .map(|length| length + resumed_from),
+        };
+        let digest = Digest::from_headers(headers)?;
+        partial.remember(&url, header_string(headers, ETAG).as_deref())?;
+
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(resumed_from > 0)
+            .truncate(resumed_from == 0)
+            .open(&partial.path)
+            .await
+            .with_context(|| format!("opening {}", partial.path.display()))?;
+        let mut saved = resumed_from;
+        let received = async {
+            while let Some(chunk) = tokio::time::timeout(self.timeouts.idle, response.chunk())
+                .await
+                .map_err(|_| silence(self.timeouts.idle))?
+                .map_err(|e| self.transport_error(e))?
+            {
+                file.write_all(&chunk)
+                    .await
+                    .with_context(|| format!("writing {}", partial.path.display()))?;
+                saved += chunk.len() as u64;
+                self.observe(|o| o.on_download_progress(&path, saved, total));
+            }
+            match total {
+                Some(total) if total != saved => Err(CliError::parse(
+                    format!("recording {}", name),
+                    format!("the body ended after {} of its {} bytes", saved, total),
+                )),
+                _ => Ok(()),
+            }
+        }
+        .await;
+        // What arrived is kept either way, so a broken-off download can be resumed
+        let kept = file.flush().await.and(file.sync_all().await);
+        drop(timer);
+        if let Err(source) = received {
+            return Err(CliError::Interrupted {
+                path: partial.path.clone(),
+                saved,
+                total,
+                resumable: partial.resumable(&url).is_some(),
+                source: Box::new(source),
+            });
+        }
+        kept.with_context(|| format!("writing {}", partial.path.display()))?;
+
+        if let Some(digest) = &digest {
+            if !digest.matches(&partial.path).await? {
+                partial.discard()?;
+                return Err(CliError::Checksum {
+                    path: dest.to_path_buf(),
+                    algorithm: digest.algorithm(),
+                });
+            }
+        }
+        partial.finish()?;
+        Ok(Downloaded {
+            path: dest.to_path_buf(),
+            bytes: saved,
+            resumed_from,
+            checked: digest.as_ref().map(Digest::algorithm),
+        })
+    }
+
+    /// A job as `job_json` reads it, with its version. Always fetched afresh: a cached body
+    /// is no base to change a job from.
+    pub async fn job_versioned(&self, id: &str) -> Result<Versioned, CliError> {
+        let path = format!("/jobs/{}", id);
+        let mut timer = timing::start(&reqwest::Method::GET, &path);
+        let request = self.request(reqwest::Method::GET, &path, Operation::Request);
+        let response = self
+            .send(request, &mut timer)
+            .await
+            .map_err(|e| self.transport_error(e))
+            .with_context(|| format!("fetching job {}", id))?;
+        let response = check_status(response)
+            .await
+            .with_context(|| format!("fetching job {}", id))?;
+        let etag = response
+            .headers()
+            .get(ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let value: serde_json::Value = self
+            .read_json(response)
+            .await
+            .with_context(|| format!("fetching job {}", id))?;
+        let updated_at = value
+            .get("updated_at")
+            .and_then(serde_json::Value::as_str)
+            .and_then(|at| DateTime::parse_from_rfc3339(at).ok())
+            .map(|at| at.with_timezone(&Utc));
+        let version = etag
+            .map(Version::ETag)
+            .or(updated_at.map(Version::UpdatedAt));
+        Ok(Versioned { value, version })
+    }
+
+    /// Move a job to a new window
+    pub async fn reschedule_job(
+        &self,
+        id: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<ApiResponse, CliError> {
+        let body = serde_json::json!({
+            "start": schedule::format(start),
+            "end": schedule::format(end),
+        });
+        self.send_json(reqwest::Method::PATCH, &format!("/jobs/{}", id), &body)
+            .await
+            .with_context(|| format!("rescheduling job {}", id))
+    }
+
+    /// How job `id` can be changed, read from the `Accept-Patch` and `Allow` headers of
+    /// `OPTIONS /jobs/{id}`: a merge patch when the server names that type or allows PATCH,
+    /// else the whole job when it allows PUT. A server that does not answer OPTIONS gets a
+    /// merge patch, the PATCH `reschedule_job` relies on.
+    pub async fn job_update(&self, id: &str) -> Result<JobUpdate, CliError> {
+        let path = format!("/jobs/{}", id);
+        let mut timer = timing::start(&reqwest::Method::OPTIONS, &path);
+        let request = self.request(reqwest::Method::OPTIONS, &path, Operation::Request);
+        let response = match self.send(request, &mut timer).await {
+            Ok(response) if response.status().is_success() => response,
+            _ => return Ok(JobUpdate::MergePatch),
+        };
+        let header = |name: &str| {
+            response
+                .headers()
+                .get_all(name)
+                .iter()
+                .filter_map(|value| value.to_str().ok())
+                .flat_map(|value| value.split(','))
+                .map(|item| item.trim().to_ascii_lowercase())
+                .filter(|item| !item.is_empty())
+                .collect::<Vec<_>>()
+        };
+        let accept_patch = header("accept-patch");
+        let allow = header("allow");
+        if accept_patch
+            .iter()
+            .any(|kind| kind.starts_with(MERGE_PATCH))
+        {
+            return Ok(JobUpdate::MergePatch);
+        }
+        if allow.is_empty() && accept_patch.is_empty() {
+            return Ok(JobUpdate::MergePatch);
+        }
+        if allow.iter().any(|method| method == "put") {
+            return Ok(JobUpdate::Replace);
+        }
+        if allow.iter().any(|method| method == "patch") {
+            return Ok(JobUpdate::MergePatch);
+        }
+        Err(CliError::Conflict {
+            message: Some(format!(
+                "the server takes neither PATCH nor PUT for job {} (Allow: {})",
+                id,
+                allow.join(", ").to_uppercase()
+            )),
+            hint: Some("cancel the job and add the changed one instead".to_string()),
+        })
+    }
+
+    /// Send a change to job `id` the way `how` says: `body` is the merge patch, or the
+    /// whole updated job. With a `version`, a server that holds a newer one answers 412,
+    /// which comes back as `CliError::Stale`.
+    pub async fn update_job(
+        &self,
+        id: &str,
+        how: JobUpdate,
+        body: &serde_json::Value,
+        version: Option<&Version>,
+    ) -> Result<ApiResponse, CliError> {
+        let path = format!("/jobs/{}", id);
+        let (method, content_type) = match how {
+            JobUpdate::MergePatch => (reqwest::Method::PATCH, MERGE_PATCH),
+            JobUpdate::Replace => (reqwest::Method::PUT, "application/json"),
+        };
+        transcript::job(body);
+        let mut timer = timing::start(&method, &path);
+        let mut request = self
+            .request(method, &path, Operation::Request)
+            .header(CONTENT_TYPE, content_type)
+            .json(body);
+        if let Some(version) = version {
+            request = version.precondition(request);
+        }
+        let response = self
+            .send(request, &mut timer)
+            .await
+            .map_err(|e| self.transport_error(e))?;
+        self.cache.invalidate(&path);
+
+        self.read_json(check_status(response).await?)
+            .await
+            .with_context(|| format!("updating job {}", id))
+    }
+
+    /// DELETE a job, sending the reason as a JSON body. A server that refuses the body
+    /// gets the plain request; the reason then stays in the local audit log only. With a
+    /// `version`, as in `update_job`, a newer job on the server makes it `CliError::Stale`.
+    pub async fn cancel_job(
+        &self,
+        id: &str,
+        reason: Option<&crate::cancel::Reason>,
+        version: Option<&Version>,
+    ) -> Result<ApiResponse, CliError> {
+        let path = format!("/jobs/{}", id);
+        let delete = || {
+            let request = self.request(reqwest::Method::DELETE, &path, Operation::Request);
+            match version {
+                Some(version) => version.precondition(request),
+                None => request,
+            }
+        };
+        let mut timer = timing::start(&reqwest::Method::DELETE, &path);
+        let mut request = delete();
+        if let Some(reason) = reason {
+            request = request.json(reason);
+        }
+        let mut response = self
+            .send(request, &mut timer)
+            .await
+            .map_err(|e| self.transport_error(e))?;
+        let refused = matches!(
+            response.status(),
+            StatusCode::BAD_REQUEST
+                | StatusCode::UNSUPPORTED_MEDIA_TYPE
+                | StatusCode::UNPROCESSABLE_ENTITY
+        );
+        if reason.is_some() && refused {
+            eprintln!(
+                "⚠️ The server did not accept a cancellation reason for job {} (HTTP {}); it is kept in the local audit log only",
+                id,
+                response.status().as_u16()
+            );
+            self.observe(|o| o.on_retry(2, "cancelling without the reason"));
+            let mut timer = timing::start(&reqwest::Method::DELETE, &path);
+            let request = delete();
+            response = self
+                .send(request, &mut timer)
+                .await
+                .map_err(|e| self.transport_error(e))?;
+        }
+        self.cache.invalidate(&path);
+
+        self.read_json(check_status(response).await?)
+            .await
+            .with_context(|| format!("cancelling job {}", id))
+    }
+
+    /// Bring back a cancelled job the server has not purged yet
+    pub async fn restore_job(&self, id: &str) -> Result<ApiResponse, CliError> {
+        let path = format!("/jobs/{}/restore", id);
+        let mut timer = timing::start(&reqwest::Method::POST, &path);
+        let request = self.request(reqwest::Method::POST, &path, Operation::Request);
+        let response = self
+            .send(request, &mut timer)
+            .await
+            .map_err(|e| self.transport_error(e))?;
+        self.cache.invalidate(&path);
+
+        self.read_json(check_status(response).await?)
+            .await
+            .with_context(|| format!("restoring job {}", id))
+    }
+
+    pub async fn maintenance(&self) -> Result<Vec<MaintenanceWindow>, CliError> {
+        self.get_json("/maintenance")
+            .await
+            .context("fetching maintenance windows")
+    }
+
+    pub async fn station_info(&self) -> Result<StationInfo, CliError> {
+        self.get_json("/station")
+            .await
+            .context("fetching station info")
+    }
+
+    pub async fn capabilities(&self) -> Result<Capabilities, CliError> {
+        self.get_json("/station/capabilities")
+            .await
+            .context("fetching the station capabilities")
+    }
+
+    /// The identity and permissions the server grants this client
+    pub async fn whoami(&self) -> Result<Identity, CliError> {
+        self.get_json("/auth/me")
+            .await
+            .context("fetching the authenticated identity")
+    }
+
+    /// Add a satellite to the server's catalog
+    pub async fn create_satellite(
+        &self,
+        satellite: &SatelliteDTO,
+    ) -> Result<ApiResponse, CliError> {
+        self.send_json(reqwest::Method::POST, "/satellites", satellite)
+            .await
+            .with_context(|| format!("adding NORAD {} to the catalog", satellite.norad_id))
+    }
+
+    /// Replace the elements the catalog holds for an existing NORAD ID
+    pub async fn update_satellite(
+        &self,
+        satellite: &SatelliteDTO,
+    ) -> Result<ApiResponse, CliError> {
+        let path = format!("/satellites/{}", satellite.norad_id);
+        self.send_json(reqwest::Method::PUT, &path, satellite)
+            .await
+            .with_context(|| format!("updating NORAD {} in the catalog", satellite.norad_id))
+    }
+
+    /// The catalog entry for a NORAD ID; `None` when the server answers with an empty body
+    pub async fn satellite(&self, norad_id: u32) -> Result<Option<SatelliteDTO>, CliError> {
+        self.get_json(&format!("/satellites/{}", norad_id))
+            .await
+            .with_context(|| format!("fetching NORAD {} from the catalog", norad_id))
+    }
+
+    /// Catalog entries whose name or NORAD ID matches `query`, at most `limit` of them.
+    /// Not cached: the answers go stale as fast as they are typed.
+    pub async fn search_satellites(
+        &self,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<CatalogEntry>, CliError> {
+        let mut timer = timing::start(&reqwest::Method::GET, "/satellites");
+        let request = self
+            .request(reqwest::Method::GET, "/satellites", Operation::Request)
+            .query(&[("q", query.to_string()), ("limit", limit.to_string())]);
+        let response = self
+            .send(request, &mut timer)
+            .await
+            .map_err(|e| self.transport_error(e))?;
+        self.read_json(check_status(response).await?)
+            .await
+            .with_context(|| format!("searching the catalog for '{}'", query))
+    }
+
+    /// Send a JSON body to a path under the base URL and decode the API response
+    async fn send_json<B: Serialize>(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: &B,
+    ) -> Result<ApiResponse, CliError> {
+        self.send_json_as(method, path, body).await
+    }
+
+    /// A JSON write whose answer is decoded as `T`
+    async fn send_json_as<B: Serialize, T: DeserializeOwned + Default>(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: &B,
+    ) -> Result<T, CliError> {
+        let mut timer = timing::start(&method, path);
+        let request = self.request(method, path, Operation::Request).json(body);
+        let response = self
+            .send(request, &mut timer)
+            .await
+            .map_err(|e| self.transport_error(e))?;
+        // Even a rejected write may have changed something
+        self.cache.invalidate(path);
+
+        self.read_json(check_status(response).await?).await
+    }
+
+    /// GET a path under the base URL and decode the JSON body.
+    /// A cached copy is revalidated and reused when the server answers 304.
+    async fn get_json<T: DeserializeOwned + Default>(&self, path: &str) -> Result<T, CliError> {
+        let url = self.url(path).to_string();
+        let cached = self.cache.get(&url, path);
+        let mut timer = timing::start(&reqwest::Method::GET, path);
+        let mut request = self.request(reqwest::Method::GET, path, Operation::Request);
+        if let Some(cached) = &cached {
+            if let Some(etag) = &cached.etag {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &cached.last_modified {
+                request = request.header(IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+        let response = self
+            .send(request, &mut timer)
+            .await
+            .map_err(|e| self.transport_error(e))?;
+
+        if response.status() == StatusCode::NOT_MODIFIED {
+            if let Some(cached) = cached {
+                self.cache
+                    .note(format!("GET {}: not modified, using the cached body", path));
+                let result = decode(StatusCode::OK, cached.content_type.clone(), &cached.body);
+                // Just revalidated, so it starts a fresh TTL
+                self.cache.store(
+                    &url,
+                    path,
+                    Cached {
+                        stored_at: clock::now(),
+                        ..cached
+                    },
+                );
+                return result;
+            }
+        }
+        let response = check_status(response).await?;
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        let (etag, last_modified, content_type) =
+            (header(ETAG), header(LAST_MODIFIED), header(CONTENT_TYPE));
+        self.cache.note(match (&cached, &etag, &last_modified) {
+            (Some(_), ..) => format!("GET {}: changed on the server, refreshing", path),
+            (None, None, None) => format!("GET {}: no validator, not cached", path),
+            (None, ..) => format!("GET {}: fetched and cached", path),
+        });
+
+        let status = response.status();
+        let body = self.text(response).await?;
+        self.cache.store(
+            &url,
+            path,
+            Cached {
+                etag,
+                last_modified,
+                content_type: content_type.clone(),
+                body: body.clone(),
+                stored_at: clock::now(),
+            },
+        );
+        decode(status, content_type, &body)
+    }
+
+    /// Tell the observer something. A panicking observer is not the request's problem:
+    /// the panic is contained here and the request goes on.
+    pub fn observe(&self, report: impl FnOnce(&dyn Observer)) {
+        let observer = &*self.observer;
+        let _ = std::panic::catch_unwind(AssertUnwindSafe(|| report(observer)));
+    }
+
+    /// Send a request, noting when its headers arrive for `--timing` and the observer.
+    /// The caller keeps the timer until the body has been read.
+    async fn send(
+        &self,
+        request: reqwest::RequestBuilder,
+        timer: &mut Option<timing::Timer>,
+    ) -> Result<reqwest::Response, reqwest::Error> {
+        let request = request.build()?;
+        let (method, path) = (request.method().clone(), request.url().path().to_string());
+        self.observe(|o| o.on_request_start(&method, &path));
+        let _span = phases::span(Phase::Http, || format!("{} {}", method, path));
+        let started = Instant::now();
+        let response = self.client.execute(request).await;
+        if let Some(timer) = timer {
+            timer.headers();
+        }
+        if let Ok(response) = &response {
+            latency::record(started.elapsed());
+            if let Some(date) = response
+                .headers()
+                .get(reqwest::header::DATE)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| DateTime::parse_from_rfc2822(v).ok())
+            {
+                latency::record_date(date.with_timezone(&Utc));
+            }
+        }
+        let status = response.as_ref().ok().map(reqwest::Response::status);
+        self.observe(|o| o.on_request_end(&method, &path, status, started.elapsed()));
+        let request_id = response.as_ref().ok().and_then(|response| {
+            response
+                .headers()
+                .get("x-request-id")
+                .and_then(|v| v.to_str().ok())
+        });
+        if let Some(request_id) = request_id {
+            self.observe(|o| o.on_request_id(&method, &path, request_id));
+        }
+        transcript::http(&method, &path, status, request_id, started.elapsed());
+        response
+    }
+
+    /// The whole body as text, read chunk by chunk so the observer sees it arrive
+    async fn text(&self, mut response: reqwest::Response) -> Result<String, CliError> {
+        let path = response.url().path().to_string();
+        let total = response.content_length();
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            body.extend_from_slice(&chunk);
+            self.observe(|o| o.on_download_progress(&path, body.len() as u64, total));
+        }
+        Ok(String::from_utf8_lossy(&body).into_owned())
+    }
+
+    /// Read the body as text and deserialize it, keeping the raw text for error reports.
+    /// An empty success body maps to `T::default()`.
+    async fn read_json<T: DeserializeOwned + Default>(
+        &self,
+        response: reqwest::Response,
+    ) -> Result<T, CliError> {
+        let status = response.status();
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+
+        let body = self.text(response).await?;
+        decode(status, content_type, &body)
+    }
+
+    /// Wrap a failed send with a hint based on the configured base URL and timeout
+    fn transport_error(&self, error: reqwest::Error) -> CliError {
+        let hint = hints::hint(
+            hints::classify(&error),
+            &self.base_url,
+            self.timeouts.request,
+        );
+        CliError::transport(error, hint)
+    }
+}
+
+/// Media type of a server-sent event stream
+const EVENT_STREAM: &str = "text/event-stream";
+
+/// Events read one at a time from `/jobs/events`, for as long as the server keeps it open
+pub struct JobEvents {
+    response: reqwest::Response,
+    splitter: EventSplitter,
+    _timer: Option<timing::Timer>,
+}
+
+impl JobEvents {
+    /// The next event, or `None` once the server has closed the stream
+    pub async fn next(&mut self) -> Result<Option<Event>, CliError> {
+        loop {
+            if let Some(event) = self.splitter.pop() {
+                return Ok(Some(event));
+            }
+            match self
+                .response
+                .chunk()
+                .await
+                .context("following job events")?
+            {
+                Some(chunk) => self.splitter.push(&chunk),
+                None => return Ok(None),
+            }
+        }
+    }
+}
+
+/// Jobs decoded one at a time from a `/jobs` response body
+pub struct JobStream {
+    response: reqwest::Response,
+    /// Body bytes so far, and the length the server announced
+    received: u64,
+    total: Option<u64>,
+    /// The client the stream came from, which reports its progress
+    client: ApiClient,
+    splitter: ArraySplitter,
+    /// Give up when no bytes arrive for this long
+    idle: Duration,
+    done: bool,
+    /// With partial results accepted, why the list stopped short instead of failing
+    salvage: bool,
+    cut: Option<CliError>,
+    /// Covers the whole body, so it is only recorded once the stream is dropped
+    _timer: Option<timing::Timer>,
+}
+
+/// The server went quiet for longer than `limit`
+fn silence(limit: Duration) -> CliError {
+    CliError::Io(std::io::Error::new(
+        std::io::ErrorKind::TimedOut,
+        format!(
+            "no data from the server for {}s (raise {} to wait longer)",
+            limit.as_secs(),
+            IDLE_TIMEOUT_ENV
+        ),
+    ))
+}
+
+fn header_string(headers: &HeaderMap, name: reqwest::header::HeaderName) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+}
+
+/// The first byte and the full length from `Content-Range: bytes 100-199/200`; the length
+/// is unknown when the server writes `*`
+fn content_range(headers: &HeaderMap) -> Option<(u64, Option<u64>)> {
+    let value = headers.get(CONTENT_RANGE)?.to_str().ok()?;
+    let (range, total) = value.strip_prefix("bytes ")?.split_once('/')?;
+    let start = range.split_once('-')?.0.trim().parse().ok()?;
+    Some((start, total.trim().parse().ok()))
+}
+
+/// A listing, and whether all of it arrived
+#[derive(Debug)]
+pub struct ListOutcome<T> {
+    pub items: Vec<T>,
+    pub complete: bool,
+}
+
+impl JobStream {
+    /// Accept a list cut short: a timeout or broken body after the list has started
+    /// ends it early instead of failing, and `cut` says why
+    pub fn partial_ok(mut self, salvage: bool) -> Self {
+        self.salvage = salvage;
+        self
+    }
+
+    /// Why the list ended before the server finished sending it, with `partial_ok`
+    pub fn cut(&self) -> Option<&CliError> {
+        self.cut.as_ref()
+    }
+
+    /// The next job in server order, or `None` once the list has ended
+    pub async fn next(&mut self) -> Result<Option<JobDTO>, CliError> {
+        match self.advance().await {
+            Err(e) if self.salvage => {
+                self.done = true;
+                self.splitter = ArraySplitter::default();
+                self.cut = Some(e);
+                Ok(None)
+            }
+            next => next,
+        }
+    }
+
+    /// Every remaining job `keep` accepts
+    pub async fn collect(
+        &mut self,
+        keep: impl Fn(&JobDTO) -> bool,
+    ) -> Result<ListOutcome<JobDTO>, CliError> {
+        let mut items = Vec::new();
+        while let Some(job) = self.next().await? {
+            if keep(&job) {
+                items.push(job);
+            }
+        }
+        Ok(ListOutcome {
+            items,
+            complete: self.cut.is_none(),
+        })
+    }
+
+    async fn advance(&mut self) -> Result<Option<JobDTO>, CliError> {
+        loop {
+            if let Some(element) = self.splitter.pop() {
+                return serde_json::from_slice(&element)
+                    .map(Some)
+                    .map_err(|e| CliError::parse("a job in the job list", e));
+            }
+            if self.done {
+                return Ok(None);
+            }
+            let chunk = tokio::time::timeout(self.idle, self.response.chunk())
+                .await
+                .map_err(|_| silence(self.idle))
+                .context("listing jobs")?;
+            match chunk.context("listing jobs")? {
+                Some(chunk) => {
+                    self.received += chunk.len() as u64;
+                    let (received, total) = (self.received, self.total);
+                    self.client
+                        .observe(|o| o.on_download_progress("/jobs", received, total));
+                    self.splitter.push(&chunk)
+                }
+                None => {
+                    self.done = true;
+                    self.splitter.end()
+                }
+            }
+            .map_err(|e| CliError::parse("the job list", e))?;
+        }
+    }
+}
+
+/// How much of an undecodable body to echo back to the user
+const BODY_SNIPPET_CHARS: usize = 300;
+
+fn decode<T: DeserializeOwned + Default>(
+    status: StatusCode,
+    content_type: Option<String>,
+    body: &str,
+) -> Result<T, CliError> {
+    if body.trim().is_empty() {
+        return Ok(T::default());
+    }
+
+    serde_json::from_str(body).map_err(|source| CliError::Decode {
+        status,
+        content_type,
+        snippet: snippet(body),
+        source,
+    })
+}
+
+fn snippet(body: &str) -> String {
+    let trimmed = body.trim();
+    let mut snippet: String = trimmed.chars().take(BODY_SNIPPET_CHARS).collect();
+    if snippet.len() < trimmed.len() {
+        snippet.push('…');
+    }
+    snippet
+}
+
+/// Turn a non-success response into the matching `CliError` variant
+async fn check_status(response: reqwest::Response) -> Result<reqwest::Response, CliError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let request_id = response
+        .headers()
+        .get("x-request-id")
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+
+    // Prefer the structured message from the API, fall back to the raw body
+    let body = response.text().await.unwrap_or_default();
+    let message = serde_json::from_str::<ApiResponse>(&body)
+        .ok()
+        .and_then(|r| r.message)
+        .or_else(|| Some(body.trim().to_string()).filter(|b| !b.is_empty()));
+
+    Err(match status {
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => CliError::Unauthorized { message },
+        StatusCode::NOT_FOUND => CliError::NotFound { message },
+        StatusCode::GONE => CliError::Gone { message },
+        StatusCode::CONFLICT => CliError::Conflict {
+            message,
+            hint: None,
+        },
+        StatusCode::PRECONDITION_FAILED => CliError::Stale { message },
+        _ => CliError::Api {
+            status,
+            message,
+            request_id,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn warnings(body: serde_json::Value) -> Vec<ServerWarning> {
+        serde_json::from_value::<ApiResponse>(body)
+            .expect("an answer with warnings")
+            .warnings
+    }
+
+    #[test]
+    fn plain_string_warnings_read_as_messages() {
+        let read = warnings(serde_json::json!({
+            "status": "created",
+            "warnings": ["pass max elevation below 5°"],
+        }));
+        assert_eq!(
+            read,
+            vec![ServerWarning {
+                code: None,
+                message: "pass max elevation below 5°".to_string(),
+                field: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn object_warnings_keep_code_and_field() {
+        let read = warnings(serde_json::json!({
+            "warnings": [
+                { "code": "stale_tle", "message": "TLE epoch older than recommended", "field": "tle" },
+                { "code": "low_pass", "message": "pass max elevation below 5°" },
+            ],
+        }));
+        assert_eq!(read[0].code.as_deref(), Some("stale_tle"));
+        assert_eq!(read[0].field.as_deref(), Some("tle"));
+        assert_eq!(read[0].message, "TLE epoch older than recommended");
+        assert_eq!(read[1].code.as_deref(), Some("low_pass"));
+        assert_eq!(read[1].field, None);
+    }
+
+    #[test]
+    fn other_shapes_are_kept_rather_than_failing_the_answer() {
+        let read = warnings(serde_json::json!({
+            "id": 4,
+            "warnings": [{ "detail": "odd" }, 7],
+        }));
+        assert_eq!(read[0].message, r#"{"detail":"odd"}"#);
+        assert_eq!(read[1].message, "7");
+        assert!(warnings(serde_json::json!({ "id": 4 })).is_empty());
     }
 }
@@ -0,0 +1,79 @@
+//! The current time, for everything that depends on it: element ages, countdowns,
+//! horizons, windows and the stamps on what this CLI records. `RUSTAR_FAKE_NOW` stops
+//! the clock at one instant, so a run gives the same answers whenever it is made.
+//! Timeouts and waits still run on the real clock.
+
+use crate::error::CliError;
+use chrono::{DateTime, Utc};
+use std::sync::OnceLock;
+use std::time::SystemTime;
+
+/// Environment variable holding the instant to take as now, in RFC 3339
+pub const FAKE_NOW_ENV: &str = "RUSTAR_FAKE_NOW";
+
+/// Where the current time comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Clock {
+    System,
+    /// One instant, all run long
+    Fixed(DateTime<Utc>),
+}
+
+impl Clock {
+    /// The fixed clock `value` names, or the system clock when there is none
+    fn parse(value: Option<&str>) -> Result<Self, CliError> {
+        let Some(value) = value.map(str::trim).filter(|value| !value.is_empty()) else {
+            return Ok(Clock::System);
+        };
+        DateTime::parse_from_rfc3339(value)
+            .map(|at| Clock::Fixed(at.with_timezone(&Utc)))
+            .map_err(|e| CliError::parse(format!("{} '{}'", FAKE_NOW_ENV, value), e))
+    }
+
+    /// `RUSTAR_FAKE_NOW` when it is set, the system clock otherwise
+    pub fn from_env() -> Result<Self, CliError> {
+        Self::parse(std::env::var(FAKE_NOW_ENV).ok().as_deref())
+    }
+
+    pub fn now(self) -> DateTime<Utc> {
+        match self {
+            Clock::System => Utc::now(),
+            Clock::Fixed(at) => at,
+        }
+    }
+}
+
+/// Set once at startup; the system clock until then
+static CLOCK: OnceLock<Clock> = OnceLock::new();
+
+pub fn set(clock: Clock) {
+    let _ = CLOCK.set(clock);
+}
+
+/// The current time by the clock in use
+pub fn now() -> DateTime<Utc> {
+    CLOCK.get().copied().unwrap_or(Clock::System).now()
+}
+
+/// How long ago `at` was by the clock in use, as for a file's modification time; zero
+/// for a time still ahead of it
+pub fn age(at: SystemTime) -> std::time::Duration {
+    (now() - DateTime::<Utc>::from(at))
+        .to_std()
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_fake_now_stops_the_clock_and_nothing_leaves_it_running() {
+        let fixed = Clock::parse(Some("2026-03-01T12:00:00+02:00")).unwrap();
+        assert_eq!(fixed.now().to_rfc3339(), "2026-03-01T10:00:00+00:00");
+        assert_eq!(fixed.now(), fixed.now());
+        assert_eq!(Clock::parse(None).unwrap(), Clock::System);
+        assert_eq!(Clock::parse(Some(" ")).unwrap(), Clock::System);
+        assert!(Clock::parse(Some("yesterday")).is_err());
+    }
+}
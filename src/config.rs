@@ -0,0 +1,886 @@
+use crate::alias;
+use crate::base_url;
+use crate::client;
+use crate::coordination::CoordinationSettings;
+use crate::error::{CliError, ResultExt};
+use crate::hooks::Hook;
+use crate::humanize;
+use crate::location::{self, Location};
+use crate::mask::ElevationMask;
+use crate::mqtt::MqttSettings;
+use crate::pass_score::PassScoring;
+use crate::paths;
+use crate::receipt;
+use crate::regulatory;
+use crate::rotator::RotatorCheck;
+use crate::snap::SnapPolicy;
+use crate::stats::{self, DayBoundary};
+use crate::storage;
+use crate::tle;
+use crate::weather::WeatherSettings;
+use crate::OutputFormat;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Profile used when neither `--profile` nor `RUSTAR_PROFILE` picks one
+pub const DEFAULT_PROFILE: &str = "default";
+/// Environment variable selecting a profile when `--profile` is not given
+pub const PROFILE_ENV: &str = "RUSTAR_PROFILE";
+
+/// Settings for one ground station
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    /// API base URL of this station; `--base-url`, `--set`, API_BASE_URL and the env file win
+    pub base_url: Option<String>,
+    /// Where days begin for stats and bare dates: utc, local or an offset like +02:00
+    pub timezone: Option<String>,
+    /// Hooks run in order after every successful submission
+    pub hooks: Vec<Hook>,
+    /// Broker the station publishes rotator and job state on
+    pub mqtt: Option<MqttSettings>,
+    /// Station position in decimal degrees (north and east positive)
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    /// Meters above the WGS84 ellipsoid
+    pub altitude_m: Option<f64>,
+    /// `[[azimuth, min_elevation], ...]` in degrees, for obstructions around the antenna
+    pub elevation_mask: ElevationMask,
+    /// Keep validated API responses on disk this long, for reuse by later invocations
+    pub http_cache_ttl_seconds: Option<u64>,
+    /// Connection pooling, keepalive and HTTP version for the API client
+    pub connection: client::ConnectionSettings,
+    /// Uplink power and duty-cycle limits from the station licence
+    pub tx_limits: regulatory::TxLimits,
+    /// How far back `undo` reaches, in minutes (10 when unset)
+    pub undo_window_minutes: Option<u64>,
+    /// How soon a pass must start for `quick` to schedule it, in minutes (10 when unset)
+    pub quick_horizon_minutes: Option<u64>,
+    /// Cancellation reasons offered besides the built-in ones, e.g. `["rf-interference"]`
+    pub cancel_reasons: Vec<String>,
+    /// Commands that change anything must be confirmed by typing the profile name
+    pub protected: bool,
+    /// Commands that change anything are refused outright, for terminals that only browse
+    pub read_only: bool,
+    /// Advisory warnings about a submission stop it, as `--strict` does
+    pub strict: bool,
+    /// Where element sets are fetched from, `{norad}` standing for the catalog number;
+    /// `--set` and TLE_SOURCE_URL win
+    pub tle_source: Option<String>,
+    /// Requests a minute to one public element-set host (20 when unset)
+    pub tle_requests_per_minute: Option<u32>,
+    /// Handoff bundles older than this have their elements fetched again on import
+    /// (24 when unset)
+    pub handoff_max_age_hours: Option<u64>,
+    /// Unfinished `jobs add` drafts older than this are no longer offered (72 when unset)
+    pub draft_max_age_hours: Option<u64>,
+    /// Keep the request, elements and server answer of each submission in the audit log,
+    /// for `history show` and `history replay` (on when unset)
+    pub job_snapshots: Option<bool>,
+    /// Write a hash-chained receipt of each accepted submission, for `receipts verify`
+    /// (on when unset)
+    pub job_receipts: Option<bool>,
+    /// Sign each receipt with minisign, e.g. `receipt_signing = { secret_key = "...",
+    /// public_key = "..." }`; the secret key must not ask for a password
+    pub receipt_signing: receipt::Signing,
+    /// Unit frequencies are printed in: mhz, khz, hz, or auto for three to six digits
+    /// before the point (auto when unset); `--freq-unit` wins
+    pub frequency_display: Option<humanize::FrequencyUnit>,
+    /// Latest job start accepted without question, in days from now (30 when unset)
+    pub max_horizon_days: Option<u64>,
+    /// Earliest job start accepted, in minutes before now (5 when unset)
+    pub max_past_minutes: Option<u64>,
+    /// Round trips a submission is given before a job that starts sooner than that, plus
+    /// the station's scheduling lead, is warned about (3 when unset)
+    pub latency_margin: Option<f64>,
+    /// Most jobs one `jobs sweep` may submit (48 when unset)
+    pub sweep_max_jobs: Option<usize>,
+    /// Largest receive offset a job or preset may apply, in hertz either way (50000 when unset)
+    pub max_rx_offset_hz: Option<u64>,
+    /// Directory every invocation records a session transcript in, as `--transcript` would
+    pub transcript_dir: Option<PathBuf>,
+    /// Grid the station scheduler keeps job times on; start and end are moved onto it
+    /// before submission, e.g. `snap = { granularity_seconds = 10, direction = "nearest" }`
+    pub snap: SnapPolicy,
+    /// Times of day no job may transmit, in the profile's timezone, e.g. `["22:00-06:00"]`;
+    /// a span whose end is not after its start runs past midnight
+    pub quiet_hours: Vec<String>,
+    /// Weights for ranking predicted passes by elevation, usable time and time of day
+    pub pass_scoring: PassScoring,
+    /// Callsign, operator and columns for `export coordination`
+    pub coordination: CoordinationSettings,
+    /// Forecast provider and limits for rain-fade warnings on high-frequency jobs
+    pub weather: Option<WeatherSettings>,
+    /// How to tell the rotator controller is attached before a job transmits: a rotctld
+    /// to ask for the position or a station-local health URL, warning or blocking on failure
+    pub rotator_check: Option<RotatorCheck>,
+    /// Command shorthands, e.g. `alias.up = "jobs list --upcoming"`; `rustar-cli up`
+    /// then runs the expansion, followed by any further arguments
+    pub alias: BTreeMap<String, String>,
+    /// What `rustar-cli` runs when given no command, instead of printing help
+    pub default_command: Option<String>,
+}
+
+/// Contents of the config file
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub default_profile: Option<String>,
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+/// Location of the config file
+pub fn config_path() -> PathBuf {
+    paths::config_file()
+}
+
+impl Config {
+    /// Read the config file; a missing file is an empty config
+    pub fn load() -> Result<Self, CliError> {
+        let path = config_path();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = std::fs::read_to_string(&path)
+            .with_context(|| format!("reading {}", path.display()))?;
+        let config: Self = toml::from_str(&contents)
+            .map_err(|e| CliError::parse(format!("config file {}", path.display()), e))?;
+        for (name, profile) in &config.profiles {
+            alias::check(name, &profile.alias, profile.default_command.as_deref())?;
+        }
+        Ok(config)
+    }
+
+    /// Pick a profile by explicit name (flag or `RUSTAR_PROFILE`), then the configured default.
+    /// Only an explicitly requested profile has to exist.
+    pub fn select(&self, requested: Option<&str>) -> Result<(String, Profile), CliError> {
+        match requested.map(str::to_string) {
+            Some(name) => match self.profiles.get(&name) {
+                Some(profile) => Ok((name, profile.clone())),
+                None => Err(CliError::Configuration(format!(
+                    "profile '{}' is not defined in {} (known: {})",
+                    name,
+                    config_path().display(),
+                    self.profiles.keys().cloned().collect::<Vec<_>>().join(", ")
+                ))),
+            },
+            None => {
+                let name = self
+                    .default_profile
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_PROFILE.to_string());
+                let profile = self.profiles.get(&name).cloned().unwrap_or_default();
+                Ok((name, profile))
+            }
+        }
+    }
+}
+
+/// Where a resolved setting came from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", content = "source", rename_all = "snake_case")]
+pub enum Origin {
+    Flag(String),
+    /// `--set key=value`
+    CommandLine,
+    Env(String),
+    EnvFile(PathBuf),
+    ConfigFile(PathBuf),
+    /// Reported by the server at this endpoint
+    Server(String),
+    Default,
+}
+
+impl std::fmt::Display for Origin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Origin::Flag(flag) => write!(f, "flag {}", flag),
+            Origin::CommandLine => write!(f, "command line"),
+            Origin::Env(var) => write!(f, "env {}", var),
+            Origin::EnvFile(path) => write!(f, "env file {}", path.display()),
+            Origin::ConfigFile(path) => write!(f, "config file {}", path.display()),
+            Origin::Server(endpoint) => write!(f, "reported by the server at {}", endpoint),
+            Origin::Default => write!(f, "default"),
+        }
+    }
+}
+
+/// A value together with where it was found
+#[derive(Debug, Clone, Serialize)]
+pub struct Setting {
+    pub value: String,
+    pub origin: Origin,
+}
+
+impl Setting {
+    fn default(value: impl Into<String>) -> Self {
+        Self {
+            value: value.into(),
+            origin: Origin::Default,
+        }
+    }
+}
+
+/// `.env` in the config directory, loaded when `--env-file` is not given
+pub fn default_env_file() -> PathBuf {
+    paths::config_dir().join(".env")
+}
+
+fn unreadable(path: &Path) -> impl Fn(dotenv::Error) -> CliError + '_ {
+    move |e| CliError::parse(format!("env file {}", path.display()), e)
+}
+
+/// Every `KEY=value` pair in the env file, read without applying any of them
+fn read_env_file(path: &Path) -> Result<BTreeMap<String, String>, CliError> {
+    // The iterator is the only way dotenv reads a file without applying it, which is
+    // needed to see values shadowed by variables already set
+    #[allow(deprecated)]
+    dotenv::from_path_iter(path)
+        .map_err(unreadable(path))?
+        .collect::<Result<BTreeMap<_, _>, _>>()
+        .map_err(unreadable(path))
+}
+
+/// Load `KEY=value` lines into the environment without overriding variables already set,
+/// returning every pair in the file and the names it set
+fn load_env_file(path: &Path) -> Result<(BTreeMap<String, String>, BTreeSet<String>), CliError> {
+    let values = read_env_file(path)?;
+    let before: BTreeSet<String> = std::env::vars_os()
+        .filter_map(|(key, _)| key.into_string().ok())
+        .collect();
+    dotenv::from_path(path).map_err(unreadable(path))?;
+    let applied = std::env::vars_os()
+        .filter_map(|(key, _)| key.into_string().ok())
+        .filter(|key| !before.contains(key))
+        .collect();
+    Ok((values, applied))
+}
+
+/// Keys `--set` can override, as `config list` names them
+pub const OVERRIDABLE: &[&str] = &[
+    "profile",
+    "base_url",
+    "timeout_seconds",
+    "idle_timeout_seconds",
+    "tle_source",
+    "location",
+];
+/// Shorter names accepted by `--set`, after the flags they stand in for
+const OVERRIDE_ALIASES: &[(&str, &str)] = &[("timeout", "timeout_seconds"), ("coords", "location")];
+
+/// `--set key=value`: a known key (or its alias) and the value to use for this invocation
+pub fn parse_override(input: &str) -> Result<(String, String), String> {
+    let (key, value) = input
+        .split_once('=')
+        .ok_or_else(|| format!("expected key=value, got '{}'", input))?;
+    let key = key.trim();
+    let key = OVERRIDE_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == key)
+        .map_or(key, |(_, target)| target);
+    if !OVERRIDABLE.contains(&key) {
+        return Err(format!(
+            "unknown key '{}' (known: {})",
+            key,
+            OVERRIDABLE.join(", ")
+        ));
+    }
+    Ok((key.to_string(), value.trim().to_string()))
+}
+
+/// Flag, then `--set`, then environment (noting values that came from the env file).
+/// Values that lost to a higher source are kept for the conflict notice.
+struct Sources {
+    env_file: Option<PathBuf>,
+    env_file_values: BTreeMap<String, String>,
+    from_env_file: BTreeSet<String>,
+    overrides: BTreeMap<String, String>,
+    shadowed: BTreeMap<&'static str, Vec<Setting>>,
+}
+
+impl Sources {
+    fn lookup(
+        &mut self,
+        flag: Option<(&str, &str)>,
+        key: &'static str,
+        var: &str,
+    ) -> Option<Setting> {
+        let mut candidates = Vec::new();
+        if let Some((name, value)) = flag {
+            candidates.push(Setting {
+                value: value.to_string(),
+                origin: Origin::Flag(name.to_string()),
+            });
+        }
+        if let Some(value) = self.overrides.get(key) {
+            candidates.push(Setting {
+                value: value.clone(),
+                origin: Origin::CommandLine,
+            });
+        }
+        if let Ok(value) = std::env::var(var) {
+            let origin = match &self.env_file {
+                Some(path) if self.from_env_file.contains(var) => Origin::EnvFile(path.clone()),
+                _ => Origin::Env(var.to_string()),
+            };
+            candidates.push(Setting { value, origin });
+        }
+        // The env file never replaces a variable that was already set
+        if let (Some(path), Some(value)) = (&self.env_file, self.env_file_values.get(var)) {
+            if !self.from_env_file.contains(var) {
+                candidates.push(Setting {
+                    value: value.clone(),
+                    origin: Origin::EnvFile(path.clone()),
+                });
+            }
+        }
+
+        let mut candidates = candidates.into_iter();
+        let winner = candidates.next()?;
+        for candidate in candidates {
+            self.shadow(key, &winner, candidate);
+        }
+        Some(winner)
+    }
+
+    /// Note a value for `key` that lost to `winner`, unless the two agree
+    fn shadow(&mut self, key: &'static str, winner: &Setting, candidate: Setting) {
+        if candidate.value != winner.value {
+            self.shadowed.entry(key).or_default().push(candidate);
+        }
+    }
+}
+
+/// The profile `Resolved::load` would select, when it sets `read_only`. The env file is
+/// read but not applied, so the load that follows still sees and reports what it brings.
+/// Anything that keeps the configuration from loading is left for that load to report.
+pub fn read_only_profile(
+    profile: Option<&str>,
+    env_file: Option<&Path>,
+    overrides: &[(String, String)],
+) -> Option<String> {
+    let env_file = match env_file {
+        Some(path) => Some(path.to_path_buf()),
+        None => Some(default_env_file()).filter(|path| path.is_file()),
+    };
+    let requested = match profile {
+        Some(profile) => Some(profile.to_string()),
+        None => overrides
+            .iter()
+            .rev()
+            .find(|(key, _)| key == "profile")
+            .map(|(_, value)| value.clone())
+            .or_else(|| std::env::var(PROFILE_ENV).ok())
+            .or_else(|| read_env_file(env_file.as_ref()?).ok()?.remove(PROFILE_ENV)),
+    };
+    let (name, selected) = Config::load().ok()?.select(requested.as_deref()).ok()?;
+    selected.read_only.then_some(name)
+}
+
+/// Everything start-up settled on, with the provenance of each value
+pub struct Resolved {
+    pub env_file: Option<PathBuf>,
+    pub profile_name: Setting,
+    pub profile: Profile,
+    pub base_url: Setting,
+    pub timeout_seconds: Setting,
+    pub idle_timeout_seconds: Setting,
+    pub tle_source: Setting,
+    /// Station position given with `--coords` or `--set location=`, which wins over the profile's
+    pub coords: Option<(Location, Origin)>,
+    /// Values sources gave for a setting that lost to the one above, by `config list` key
+    pub shadowed: BTreeMap<&'static str, Vec<Setting>>,
+}
+
+impl Resolved {
+    /// Load the env file and config file, then settle every value.
+    /// An explicitly requested env file has to exist; the default one is optional.
+    pub fn load(
+        profile: Option<&str>,
+        base_url: Option<&str>,
+        timeout: Option<&str>,
+        coords: Option<Location>,
+        env_file: Option<&Path>,
+        overrides: &[(String, String)],
+        verbose: bool,
+    ) -> Result<Self, CliError> {
+        let env_file = match env_file {
+            Some(path) if !path.is_file() => {
+                return Err(CliError::Configuration(format!(
+                    "env file {} does not exist",
+                    path.display()
+                )))
+            }
+            Some(path) => Some(path.to_path_buf()),
+            None => Some(default_env_file()).filter(|path| path.is_file()),
+        };
+        let (env_file_values, from_env_file) = match &env_file {
+            Some(path) => {
+                let loaded = load_env_file(path)?;
+                if verbose {
+                    eprintln!(
+                        "📄 Loaded env file {} ({} variable(s) applied)",
+                        path.display(),
+                        loaded.1.len()
+                    );
+                }
+                loaded
+            }
+            None => {
+                if verbose {
+                    eprintln!(
+                        "📄 No env file loaded (looked for {})",
+                        default_env_file().display()
+                    );
+                }
+                (BTreeMap::new(), BTreeSet::new())
+            }
+        };
+        let mut sources = Sources {
+            env_file,
+            env_file_values,
+            from_env_file,
+            // A key given twice takes its last value
+            overrides: overrides.iter().cloned().collect(),
+            shadowed: BTreeMap::new(),
+        };
+        let set_location = match sources.overrides.get("location") {
+            Some(value) => Some(location::parse(value).context("reading --set location")?),
+            None => None,
+        };
+
+        let config = Config::load()?;
+        let requested = sources.lookup(profile.map(|p| ("--profile", p)), "profile", PROFILE_ENV);
+        let (name, selected) = config.select(requested.as_ref().map(|s| s.value.as_str()))?;
+        let from_file = config.default_profile.as_ref().map(|value| Setting {
+            value: value.clone(),
+            origin: Origin::ConfigFile(config_path()),
+        });
+        let profile_name = match (requested, from_file) {
+            (Some(setting), Some(from_file)) => {
+                sources.shadow("profile", &setting, from_file);
+                setting
+            }
+            (Some(setting), None) => setting,
+            (None, Some(from_file)) => from_file,
+            (None, None) => Setting::default(name),
+        };
+
+        let mut candidates = coords
+            .map(|location| (location, Origin::Flag("--coords".to_string())))
+            .into_iter()
+            .chain(set_location.map(|location| (location, Origin::CommandLine)))
+            .chain(
+                match (selected.latitude, selected.longitude) {
+                    (Some(latitude), Some(longitude)) => {
+                        Location::new(latitude, longitude, selected.altitude_m.unwrap_or(0.0)).ok()
+                    }
+                    _ => None,
+                }
+                .map(|location| (location, Origin::ConfigFile(config_path()))),
+            );
+        // Only a command-line position goes in `coords`; the profile's is read on demand
+        let coords = candidates
+            .next()
+            .filter(|(_, origin)| !matches!(origin, Origin::ConfigFile(_)));
+        if let Some((location, origin)) = &coords {
+            let winner = Setting {
+                value: location.to_string(),
+                origin: origin.clone(),
+            };
+            for (other, origin) in candidates {
+                let candidate = Setting {
+                    value: other.to_string(),
+                    origin,
+                };
+                sources.shadow("location", &winner, candidate);
+            }
+        }
+
+        let from_profile = selected.base_url.clone().map(|value| Setting {
+            value,
+            origin: Origin::ConfigFile(config_path()),
+        });
+        let base_url = match (
+            sources.lookup(
+                base_url.map(|u| ("--base-url", u)),
+                "base_url",
+                client::BASE_URL_ENV,
+            ),
+            from_profile,
+        ) {
+            (Some(setting), Some(from_profile)) => {
+                sources.shadow("base_url", &setting, from_profile);
+                setting
+            }
+            (Some(setting), None) | (None, Some(setting)) => setting,
+            (None, None) => Setting::default(client::DEFAULT_BASE_URL),
+        };
+        // Checked here, so a broken URL is reported with where it came from
+        let base_url = match base_url::parse(&base_url.value) {
+            Ok(url) => Setting {
+                value: base_url::display(&url),
+                ..base_url
+            },
+            Err(CliError::Configuration(problem)) => {
+                return Err(CliError::Configuration(format!(
+                    "{} ({})",
+                    problem, base_url.origin
+                )))
+            }
+            Err(e) => return Err(e),
+        };
+
+        let from_profile = selected.tle_source.clone().map(|value| Setting {
+            value,
+            origin: Origin::ConfigFile(config_path()),
+        });
+        let tle_source = match (
+            sources.lookup(None, "tle_source", tle::SOURCE_ENV),
+            from_profile,
+        ) {
+            (Some(setting), Some(from_profile)) => {
+                sources.shadow("tle_source", &setting, from_profile);
+                setting
+            }
+            (Some(setting), None) | (None, Some(setting)) => setting,
+            (None, None) => Setting::default(tle::DEFAULT_TLE_SOURCE),
+        };
+
+        Ok(Self {
+            profile_name,
+            profile: selected,
+            base_url,
+            timeout_seconds: sources
+                .lookup(
+                    timeout.map(|t| ("--timeout", t)),
+                    "timeout_seconds",
+                    client::TIMEOUT_ENV,
+                )
+                .unwrap_or_else(|| Setting::default(client::DEFAULT_TIMEOUT_SECONDS.to_string())),
+            idle_timeout_seconds: sources
+                .lookup(None, "idle_timeout_seconds", client::IDLE_TIMEOUT_ENV)
+                .unwrap_or_else(|| {
+                    Setting::default(client::DEFAULT_IDLE_TIMEOUT_SECONDS.to_string())
+                }),
+            tle_source,
+            env_file: sources.env_file,
+            coords,
+            shadowed: sources.shadowed,
+        })
+    }
+
+    /// One line per setting that sources disagree on, naming the value used and the ones it beat
+    pub fn conflicts(&self) -> Vec<String> {
+        self.settings()
+            .into_iter()
+            .filter_map(|(key, setting)| {
+                let shadowed = self.shadowed.get(key)?;
+                let others: Vec<String> = shadowed
+                    .iter()
+                    .map(|other| format!("{} ({})", other.value, other.origin))
+                    .collect();
+                Some(format!(
+                    "{} is set differently by {} sources: using {} ({}) over {}",
+                    key,
+                    shadowed.len() + 1,
+                    setting.value,
+                    setting.origin,
+                    others.join(", ")
+                ))
+            })
+            .collect()
+    }
+
+    /// Request time limits; checked here so only commands that talk to the API can trip on them
+    pub fn timeouts(&self) -> Result<client::Timeouts, CliError> {
+        let seconds = |name: &str, setting: &Setting| {
+            setting
+                .value
+                .trim()
+                .parse()
+                .map(Duration::from_secs)
+                .map_err(|_| {
+                    CliError::Configuration(format!(
+                        "{} must be a whole number of seconds, got '{}' ({})",
+                        name, setting.value, setting.origin
+                    ))
+                })
+        };
+        Ok(client::Timeouts {
+            request: seconds("the timeout", &self.timeout_seconds)?,
+            explicit: !matches!(self.timeout_seconds.origin, Origin::Default),
+            idle: seconds("the idle timeout", &self.idle_timeout_seconds)?,
+        })
+    }
+
+    /// The station position from `--coords` or the profile. Every command that needs one
+    /// goes through here, so a missing or broken location always reads the same.
+    pub fn location(&self) -> Result<Location, CliError> {
+        if let Some((location, _)) = &self.coords {
+            return Ok(*location);
+        }
+
+        let key = |field: &str| format!("profiles.{}.{}", self.profile_name.value, field);
+        let profile = &self.profile;
+        match (profile.latitude, profile.longitude) {
+            (Some(latitude), Some(longitude)) => {
+                Location::new(latitude, longitude, profile.altitude_m.unwrap_or(0.0)).map_err(|e| {
+                    match e {
+                        CliError::Validation { field, reason, .. } => CliError::Configuration(
+                            format!("{} in {}: {}", key(&field), config_path().display(), reason),
+                        ),
+                        other => other,
+                    }
+                })
+            }
+            (latitude, longitude) => {
+                let missing: Vec<String> = [("latitude", latitude), ("longitude", longitude)]
+                    .iter()
+                    .filter(|(_, value)| value.is_none())
+                    .map(|(field, _)| key(field))
+                    .collect();
+                Err(CliError::Configuration(format!(
+                    "the ground station location is not set: add {} to {} \
+                     (rustar-cli config set-location) or pass --coords",
+                    missing.join(" and "),
+                    config_path().display()
+                )))
+            }
+        }
+    }
+
+    /// Where days begin for stats and bare dates: UTC with `--utc-only`, then the
+    /// profile's timezone, then local midnight
+    pub fn day_boundary(&self) -> Result<DayBoundary, CliError> {
+        match &self.profile.timezone {
+            Some(zone) if !humanize::utc_only() => stats::parse_timezone(zone).map_err(|reason| {
+                CliError::Configuration(format!(
+                    "profiles.{}.timezone in {}: {}",
+                    self.profile_name.value,
+                    config_path().display(),
+                    reason
+                ))
+            }),
+            _ => Ok(DayBoundary::default_for_display()),
+        }
+    }
+
+    fn settings(&self) -> Vec<(&'static str, Setting)> {
+        let location = match self.location() {
+            Ok(location) => Setting {
+                value: location.to_string(),
+                origin: match &self.coords {
+                    Some((_, origin)) => origin.clone(),
+                    None => Origin::ConfigFile(config_path()),
+                },
+            },
+            Err(_) if self.profile.latitude.is_none() && self.profile.longitude.is_none() => {
+                Setting::default("not set")
+            }
+            Err(_) => Setting {
+                value: "invalid".to_string(),
+                origin: Origin::ConfigFile(config_path()),
+            },
+        };
+        vec![
+            ("profile", self.profile_name.clone()),
+            ("base_url", self.base_url.clone()),
+            ("timeout_seconds", self.timeout_seconds.clone()),
+            ("idle_timeout_seconds", self.idle_timeout_seconds.clone()),
+            ("tle_source", self.tle_source.clone()),
+            ("location", location),
+        ]
+    }
+}
+
+#[derive(Serialize)]
+struct SettingRow<'a> {
+    key: &'a str,
+    value: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    origin: Option<&'a Origin>,
+}
+
+/// Print the resolved settings, optionally with where each one came from
+pub fn list(resolved: &Resolved, show_origin: bool, output: OutputFormat) -> Result<(), CliError> {
+    let settings = resolved.settings();
+
+    if output == OutputFormat::Json {
+        let rows: Vec<SettingRow> = settings
+            .iter()
+            .map(|(key, setting)| SettingRow {
+                key,
+                value: &setting.value,
+                origin: show_origin.then_some(&setting.origin),
+            })
+            .collect();
+        let document = serde_json::json!({
+            "config_file": config_path(),
+            "env_file": resolved.env_file,
+            "settings": rows,
+        });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&document).map_err(|e| CliError::parse("settings", e))?
+        );
+        return Ok(());
+    }
+
+    println!("⚙️ Configuration ({})", config_path().display());
+    if let Some(path) = &resolved.env_file {
+        println!("  env file: {}", path.display());
+    }
+    let width = settings.iter().map(|(key, _)| key.len()).max().unwrap_or(0);
+    let value_width = settings
+        .iter()
+        .map(|(_, s)| s.value.chars().count())
+        .max()
+        .unwrap_or(0);
+    for (key, setting) in &settings {
+        if show_origin {
+            println!(
+                "  {:<width$}  {:<value_width$}  ({})",
+                key,
+                setting.value,
+                setting.origin,
+                width = width,
+                value_width = value_width
+            );
+        } else {
+            println!("  {:<width$}  {}", key, setting.value, width = width);
+        }
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct ResolvedRow<'a> {
+    key: &'a str,
+    value: &'a str,
+    origin: &'a Origin,
+    overridden: &'a [Setting],
+}
+
+/// Print every resolved setting with its origin and the values it took precedence over
+pub fn resolve(resolved: &Resolved, output: OutputFormat) -> Result<(), CliError> {
+    let settings = resolved.settings();
+    let overridden =
+        |key: &str| -> &[Setting] { resolved.shadowed.get(key).map_or(&[], Vec::as_slice) };
+
+    if output == OutputFormat::Json {
+        let rows: Vec<ResolvedRow> = settings
+            .iter()
+            .map(|(key, setting)| ResolvedRow {
+                key,
+                value: &setting.value,
+                origin: &setting.origin,
+                overridden: overridden(key),
+            })
+            .collect();
+        let document = serde_json::json!({
+            "config_file": config_path(),
+            "env_file": resolved.env_file,
+            "profile": resolved.profile_name.value,
+            "settings": rows,
+        });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&document).map_err(|e| CliError::parse("settings", e))?
+        );
+        return Ok(());
+    }
+
+    println!("⚙️ Resolved configuration");
+    println!("  config file: {}", config_path().display());
+    match &resolved.env_file {
+        Some(path) => println!("  env file:    {}", path.display()),
+        None => println!("  env file:    none"),
+    }
+    let width = settings.iter().map(|(key, _)| key.len()).max().unwrap_or(0);
+    let value_width = settings
+        .iter()
+        .map(|(_, s)| s.value.chars().count())
+        .max()
+        .unwrap_or(0);
+    for (key, setting) in &settings {
+        println!(
+            "  {:<width$}  {:<value_width$}  ({})",
+            key,
+            setting.value,
+            setting.origin,
+            width = width,
+            value_width = value_width
+        );
+        for other in overridden(key) {
+            println!(
+                "  {:<width$}    overrides {} ({})",
+                "",
+                other.value,
+                other.origin,
+                width = width
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Change one profile of the config file, creating either when missing; with
+/// `claim_default`, the profile also becomes the default if the file names none.
+/// Other profiles and settings are kept, but comments in the file are not.
+pub fn update_profile(
+    profile: &str,
+    claim_default: bool,
+    edit: impl FnOnce(&mut toml::Table),
+) -> Result<PathBuf, CliError> {
+    let path = config_path();
+    let _lock = storage::lock(&path)?;
+    let mut document: toml::Table = if path.exists() {
+        let contents = std::fs::read_to_string(&path)
+            .with_context(|| format!("reading {}", path.display()))?;
+        toml::from_str(&contents)
+            .map_err(|e| CliError::parse(format!("config file {}", path.display()), e))?
+    } else {
+        toml::Table::new()
+    };
+
+    let not_a_table = |key: String| {
+        CliError::Configuration(format!("{} in {} is not a table", key, path.display()))
+    };
+    let profiles = document
+        .entry("profiles")
+        .or_insert_with(|| toml::Table::new().into())
+        .as_table_mut()
+        .ok_or_else(|| not_a_table("profiles".to_string()))?;
+    let entry = profiles
+        .entry(profile)
+        .or_insert_with(|| toml::Table::new().into())
+        .as_table_mut()
+        .ok_or_else(|| not_a_table(format!("profiles.{}", profile)))?;
+    edit(entry);
+    if claim_default && !document.contains_key("default_profile") {
+        document.insert("default_profile".into(), profile.into());
+    }
+
+    let contents = toml::to_string_pretty(&document)
+        .map_err(|e| CliError::parse("config file contents", e))?;
+    storage::write_atomic(&path, contents)?;
+    Ok(path)
+}
+
+/// Store a station position in a profile of the config file
+pub fn set_location(profile: &str, location: &Location) -> Result<PathBuf, CliError> {
+    update_profile(profile, false, |entry| insert_location(entry, location))
+}
+
+/// The position as the profile's latitude, longitude and altitude_m
+pub fn insert_location(entry: &mut toml::Table, location: &Location) {
+    // Six decimals is about 10 cm on the ground
+    let round = |degrees: f64| (degrees * 1e6).round() / 1e6;
+    entry.insert("latitude".into(), round(location.latitude).into());
+    entry.insert("longitude".into(), round(location.longitude).into());
+    entry.insert("altitude_m".into(), location.altitude_m.into());
+}
@@ -0,0 +1,109 @@
+//! Two operators changing one job from different machines. Changes are sent against the
+//! version of the job they were made on; when the server holds a newer one it refuses
+//! them, and the operator sees what each side changed and decides how to go on.
+
+use crate::diff;
+use crate::error::CliError;
+use crate::table::{Column, Table};
+use crate::transcript::Recorded;
+use crate::tty;
+use crate::OutputFormat;
+use inquire::Select;
+use serde_json::Value;
+
+/// Attempts at a change before giving up on a job that keeps changing under it
+pub const ATTEMPTS: usize = 3;
+
+/// What to do when a change meets a newer version of the job: `--on-conflict`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OnConflict {
+    /// Stop, changing nothing, and fail
+    Abort,
+    /// Leave the job as the server now holds it
+    Theirs,
+    /// Send the change again on top of the newer version
+    Mine,
+}
+
+/// How to go on with job `id`: `--on-conflict` when given, else the operator's pick at a
+/// terminal, else abort
+pub fn resolve(id: &str, flag: Option<OnConflict>) -> Result<OnConflict, CliError> {
+    if let Some(flag) = flag {
+        return Ok(flag);
+    }
+    if !tty::interactive() {
+        return Ok(OnConflict::Abort);
+    }
+    let question = format!("🔀 How should job {} go on?", id);
+    let choices = vec![
+        "Retry with my changes on top of theirs",
+        "Keep their version",
+        "Abort",
+    ];
+    let picked = Select::new(&question, choices)
+        .raw_prompt()
+        .recorded(&question)?;
+    Ok(match picked.index {
+        0 => OnConflict::Mine,
+        1 => OnConflict::Theirs,
+        _ => OnConflict::Abort,
+    })
+}
+
+/// Show what changed in job `id` since `base`, the version the change was made against:
+/// `yours` is the job as the change would leave it, `theirs` the job the server now holds.
+/// A table in text output; with `--output json` a line on stderr, so stdout stays the
+/// command's document.
+pub fn show(id: &str, base: &Value, yours: &Value, theirs: &Value, output: OutputFormat) {
+    let fields = diff::three_way(base, yours, theirs);
+    let clashes = fields.iter().filter(|field| field.clashes()).count();
+    let summary = format!(
+        "⚠️ Job {} was changed on the server since it was fetched: {} field(s) differ, {} changed on both sides",
+        id,
+        fields.len(),
+        clashes
+    );
+    if output == OutputFormat::Json {
+        eprintln!("{}", summary);
+        return;
+    }
+    println!("{}", summary);
+    let mut table = Table::new(vec![
+        Column::new("FIELD"),
+        Column::name("BASE"),
+        Column::name("YOURS"),
+        Column::name("THEIRS"),
+        Column::new("CHANGED BY"),
+    ]);
+    for field in &fields {
+        let [base, yours, theirs] = field.shown();
+        table.row(vec![
+            field.path.clone(),
+            base,
+            yours,
+            theirs,
+            field.changed_by().to_string(),
+        ]);
+    }
+    table.print();
+}
+
+/// The error for a change to job `id` given up on
+pub fn aborted(id: &str, attempts: usize) -> CliError {
+    CliError::Conflict {
+        message: Some(match attempts {
+            1 => format!(
+                "job {} was changed on the server since it was fetched; nothing was sent over it",
+                id
+            ),
+            n => format!(
+                "job {} was changed on the server again on each of {} attempts",
+                id, n
+            ),
+        }),
+        hint: Some(
+            "look at the job again and redo the change, or pass --on-conflict theirs or mine"
+                .to_string(),
+        ),
+    }
+}
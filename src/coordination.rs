@@ -0,0 +1,275 @@
+use crate::client::{ApiClient, JobDTO};
+use crate::config::{config_path, Resolved};
+use crate::error::{CliError, ResultExt};
+use crate::location::Location;
+use crate::output::OutputTarget;
+use crate::stats::{self, DayBoundary};
+use chrono::{DateTime, Duration, NaiveDate, SecondsFormat, Utc};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Serialize, Serializer};
+
+/// A column of the coordination export, named as in the profile's `columns` template
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Column {
+    Date,
+    StartUtc,
+    EndUtc,
+    Satellite,
+    UplinkHz,
+    Mode,
+    PowerDbm,
+    DutyCycle,
+    DurationMinutes,
+    Operator,
+    Callsign,
+    Latitude,
+    Longitude,
+    AltitudeM,
+    JobId,
+    Campaign,
+}
+
+/// Columns written when the profile does not list its own
+const DEFAULT_COLUMNS: &[Column] = &[
+    Column::Date,
+    Column::StartUtc,
+    Column::EndUtc,
+    Column::Satellite,
+    Column::UplinkHz,
+    Column::Mode,
+    Column::PowerDbm,
+    Column::DurationMinutes,
+    Column::Operator,
+    Column::Callsign,
+    Column::Latitude,
+    Column::Longitude,
+];
+
+impl Column {
+    fn header(self) -> &'static str {
+        match self {
+            Column::Date => "date",
+            Column::StartUtc => "start_utc",
+            Column::EndUtc => "end_utc",
+            Column::Satellite => "satellite",
+            Column::UplinkHz => "uplink_hz",
+            Column::Mode => "mode",
+            Column::PowerDbm => "power_dbm",
+            Column::DutyCycle => "duty_cycle",
+            Column::DurationMinutes => "duration_minutes",
+            Column::Operator => "operator",
+            Column::Callsign => "callsign",
+            Column::Latitude => "latitude",
+            Column::Longitude => "longitude",
+            Column::AltitudeM => "altitude_m",
+            Column::JobId => "job_id",
+            Column::Campaign => "campaign",
+        }
+    }
+
+    fn needs_location(self) -> bool {
+        matches!(
+            self,
+            Column::Latitude | Column::Longitude | Column::AltitudeM
+        )
+    }
+}
+
+/// Station details for the regulator's schedule of planned transmissions, from the
+/// profile's `[coordination]` table
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct CoordinationSettings {
+    /// Station callsign as licensed, e.g. "LU1ABC"
+    pub callsign: Option<String>,
+    /// Person or body responsible for the transmissions
+    pub operator: Option<String>,
+    /// Columns in the order they are written; the usual set when empty
+    pub columns: Vec<Column>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportFormat {
+    /// One row per transmission with a header line
+    Csv,
+    /// An array of objects keyed by column name
+    Json,
+}
+
+/// Everything a row may need besides the job itself, checked before any row is written
+struct Station<'a> {
+    callsign: Option<&'a str>,
+    operator: Option<&'a str>,
+    location: Option<Location>,
+}
+
+impl<'a> Station<'a> {
+    /// The details `columns` use; every missing one is named at once, with where to set it
+    fn for_columns(resolved: &'a Resolved, columns: &[Column]) -> Result<Self, CliError> {
+        let settings = &resolved.profile.coordination;
+        let wanted = |column| columns.contains(&column);
+        let missing: Vec<&str> = [
+            (
+                "callsign",
+                wanted(Column::Callsign) && settings.callsign.is_none(),
+            ),
+            (
+                "operator",
+                wanted(Column::Operator) && settings.operator.is_none(),
+            ),
+        ]
+        .iter()
+        .filter(|(_, missing)| *missing)
+        .map(|(field, _)| *field)
+        .collect();
+        if !missing.is_empty() {
+            return Err(CliError::Configuration(format!(
+                "the coordination export needs the station's {}: set {} under \
+                 [profiles.{}.coordination] in {}, or leave the column out of its columns list",
+                missing.join(" and "),
+                if missing.len() == 1 { "it" } else { "them" },
+                resolved.profile_name.value,
+                config_path().display()
+            )));
+        }
+        let location = if columns.iter().any(|column| column.needs_location()) {
+            Some(resolved.location()?)
+        } else {
+            None
+        };
+        Ok(Self {
+            callsign: settings.callsign.as_deref(),
+            operator: settings.operator.as_deref(),
+            location,
+        })
+    }
+
+    fn cell(&self, column: Column, job: &JobDTO) -> String {
+        let optional = |value: Option<f64>| value.map(|v| v.to_string()).unwrap_or_default();
+        let seconds = |at: DateTime<Utc>| at.to_rfc3339_opts(SecondsFormat::Secs, true);
+        let location = self.location.as_ref();
+        match column {
+            Column::Date => job.job.start.format("%Y-%m-%d").to_string(),
+            Column::StartUtc => seconds(job.job.start),
+            Column::EndUtc => seconds(job.job.end),
+            Column::Satellite => job.job.label().to_string(),
+            Column::UplinkHz => job.job.tx_frequency.hz().to_string(),
+            Column::Mode => job.job.mode.clone().unwrap_or_default(),
+            Column::PowerDbm => optional(job.job.tx_power_dbm),
+            Column::DutyCycle => optional(job.job.tx_duty_cycle),
+            Column::DurationMinutes => {
+                format!(
+                    "{:.1}",
+                    (job.job.end - job.job.start).num_seconds() as f64 / 60.0
+                )
+            }
+            Column::Operator => self.operator.unwrap_or_default().to_string(),
+            Column::Callsign => self.callsign.unwrap_or_default().to_string(),
+            Column::Latitude => optional(location.map(|l| l.latitude)),
+            Column::Longitude => optional(location.map(|l| l.longitude)),
+            Column::AltitudeM => optional(location.map(|l| l.altitude_m)),
+            Column::JobId => job.id.clone().unwrap_or_default(),
+            Column::Campaign => job.job.campaign.clone().unwrap_or_default(),
+        }
+    }
+}
+
+/// One row as a JSON object, keys in column order
+struct Object<'a> {
+    columns: &'a [Column],
+    row: &'a [String],
+}
+
+impl Serialize for Object<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.columns.len()))?;
+        for (column, cell) in self.columns.iter().zip(self.row) {
+            map.serialize_entry(column.header(), cell)?;
+        }
+        map.end()
+    }
+}
+
+/// `--from`/`--to` in UTC. A bare `--to` date includes that day, as a regulator reads
+/// "1 to 31 October".
+pub fn parse_range(from: &str, to: &str) -> Result<(DateTime<Utc>, DateTime<Utc>), CliError> {
+    let start = stats::parse_bound(from, DayBoundary::Utc, "--from")?;
+    let mut end = stats::parse_bound(to, DayBoundary::Utc, "--to")?;
+    if NaiveDate::parse_from_str(to.trim(), "%Y-%m-%d").is_ok() {
+        end += Duration::days(1);
+    }
+    if end <= start {
+        return Err(CliError::validation("--to", "must be after --from"));
+    }
+    Ok((start, end))
+}
+
+/// Jobs that may transmit, cancelled ones aside
+fn transmits(job: &JobDTO) -> bool {
+    !job.job.tx_frequency.is_zero()
+        && !job.status.as_deref().is_some_and(|status| {
+            matches!(
+                status.to_ascii_lowercase().as_str(),
+                "cancelled" | "canceled"
+            )
+        })
+}
+
+/// Write the schedule of planned transmissions starting in `[from, to)`, one row per
+/// TX job in start order
+pub async fn export(
+    client: &ApiClient,
+    resolved: &Resolved,
+    (from, to): (DateTime<Utc>, DateTime<Utc>),
+    format: ExportFormat,
+    output: &OutputTarget,
+) -> Result<(), CliError> {
+    output.check()?;
+    let columns = match resolved.profile.coordination.columns.as_slice() {
+        [] => DEFAULT_COLUMNS,
+        columns => columns,
+    };
+    let station = Station::for_columns(resolved, columns)?;
+
+    let mut jobs: Vec<JobDTO> = client
+        .list_jobs()
+        .await?
+        .into_iter()
+        .filter(|job| transmits(job) && job.job.start >= from && job.job.start < to)
+        .collect();
+    jobs.sort_by(JobDTO::schedule_order);
+    let rows: Vec<Vec<String>> = jobs
+        .iter()
+        .map(|job| columns.iter().map(|c| station.cell(*c, job)).collect())
+        .collect();
+
+    let mut document = Vec::new();
+    match format {
+        ExportFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(&mut document);
+            let error = |e: csv::Error| CliError::parse("coordination schedule as CSV", e);
+            writer
+                .write_record(columns.iter().map(|c| c.header()))
+                .map_err(error)?;
+            for row in &rows {
+                writer.write_record(row).map_err(error)?;
+            }
+            writer.flush().context("writing CSV")?;
+        }
+        ExportFormat::Json => {
+            let objects: Vec<Object> = rows.iter().map(|row| Object { columns, row }).collect();
+            serde_json::to_writer_pretty(&mut document, &objects)
+                .map_err(|e| CliError::parse("coordination schedule", e))?;
+            document.push(b'\n');
+        }
+    }
+
+    eprintln!(
+        "📡 {} planned transmission(s) from {} to {}",
+        jobs.len(),
+        from.format("%Y-%m-%d %H:%M UTC"),
+        to.format("%Y-%m-%d %H:%M UTC")
+    );
+    output.write(document)
+}
@@ -0,0 +1,295 @@
+//! `--demo`: a built-in station with sample data, for screenshots and training material.
+//! The mock server's API is served on a loopback port, seeded with a fixture, and the
+//! command runs against it through the ordinary client, so every request and rendering
+//! path is the real one. Changes are answered as the station would, marked DEMO, and
+//! are gone when the command exits. Configuration, cache and state live in a scratch
+//! directory: nothing the operator set up is read, and nothing of theirs is written.
+
+use crate::client::{JobDTO, JobRequestDTO, JobType, SatelliteDTO, StationInfo, TleData};
+use crate::clock;
+use crate::error::{CliError, ResultExt};
+use crate::frequency::Frequency;
+use crate::location::Location;
+use crate::mask::ElevationMask;
+use crate::mock_server::{self, Fixture};
+use crate::orbit::Satellite;
+use crate::paths;
+use crate::predict::{self, Horizon, Pass};
+use crate::schedule::TimeWindow;
+use crate::tle;
+use chrono::{DateTime, Datelike, Duration, Timelike, Utc};
+use serde_json::json;
+use std::collections::BTreeMap;
+
+/// The demo station: Buenos Aires, where the mock server also stands
+pub const STATION: Location = Location {
+    latitude: -34.6037,
+    longitude: -58.3816,
+    altitude_m: 25.0,
+};
+
+/// Jobs scheduled per satellite
+const JOBS_PER_SATELLITE: usize = 4;
+/// Passes lower than this are not scheduled
+const MIN_MAX_ELEVATION_DEG: f64 = 20.0;
+
+/// A satellite of the fixture, with the elements that vary by seed left out
+struct Sample {
+    name: &'static str,
+    norad_id: u32,
+    designator: &'static str,
+    inclination_deg: f64,
+    mean_motion: f64,
+    rx_hz: u64,
+    /// Uplink for jobs that transmit, with its power and duty cycle
+    tx: Option<(u64, f64, f64)>,
+    mode: &'static str,
+    baud: Option<u32>,
+    campaign: Option<&'static str>,
+}
+
+const SAMPLES: [Sample; 3] = [
+    Sample {
+        name: "ISS (ZARYA)",
+        norad_id: 25544,
+        designator: "98067A",
+        inclination_deg: 51.6416,
+        mean_motion: 15.501_297_87,
+        rx_hz: 145_825_000,
+        tx: Some((145_825_000, 37.0, 0.25)),
+        mode: "AFSK",
+        baud: Some(1200),
+        campaign: None,
+    },
+    Sample {
+        name: "NOAA 19",
+        norad_id: 33591,
+        designator: "09005A",
+        inclination_deg: 99.1930,
+        mean_motion: 14.125_427_41,
+        rx_hz: 137_100_000,
+        tx: None,
+        mode: "APT",
+        baud: None,
+        campaign: Some("weather-images"),
+    },
+    Sample {
+        name: "FUNCUBE-1 (AO-73)",
+        norad_id: 39444,
+        designator: "13066B",
+        inclination_deg: 97.5515,
+        mean_motion: 14.824_711_39,
+        rx_hz: 145_935_000,
+        tx: None,
+        mode: "BPSK",
+        baud: Some(1200),
+        campaign: None,
+    },
+];
+
+/// A small deterministic generator (SplitMix64), so a seed always gives the same data
+struct Dice(u64);
+
+impl Dice {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in [0, 1)
+    fn unit(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Elements for `sample` with their epoch at `epoch`, in an orbit plane and place the
+/// seed picks
+fn elements(sample: &Sample, epoch: DateTime<Utc>, dice: &mut Dice) -> TleData {
+    let day = f64::from(epoch.ordinal()) + f64::from(epoch.num_seconds_from_midnight()) / 86_400.0;
+    let mut tle1 = format!(
+        "1 {:05}U {:<8} {:02}{:012.8}  .00001764  00000+0  10270-3 0  999",
+        sample.norad_id,
+        sample.designator,
+        epoch.year() % 100,
+        day
+    );
+    tle1.push_str(&tle::checksum(&tle1).to_string());
+    let mut tle2 = format!(
+        "2 {:05} {:8.4} {:8.4} {:07} {:8.4} {:8.4} {:11.8}{:05}",
+        sample.norad_id,
+        sample.inclination_deg,
+        dice.unit() * 360.0,
+        1000 + (dice.unit() * 2000.0) as u32,
+        dice.unit() * 360.0,
+        dice.unit() * 360.0,
+        sample.mean_motion,
+        10_000 + (dice.unit() * 80_000.0) as u32
+    );
+    tle2.push_str(&tle::checksum(&tle2).to_string());
+    TleData {
+        tle0: sample.name.to_string(),
+        tle1,
+        tle2,
+    }
+}
+
+fn whole_seconds(at: DateTime<Utc>) -> DateTime<Utc> {
+    DateTime::from_timestamp(at.timestamp(), 0).unwrap_or(at)
+}
+
+fn job(sample: &Sample, pass: &Pass, now: DateTime<Utc>) -> JobDTO {
+    let (start, end) = (whole_seconds(pass.rise), whole_seconds(pass.set));
+    let status = match (start <= now, end <= now) {
+        (_, true) => "completed",
+        (true, false) => "running",
+        _ => "scheduled",
+    };
+    JobDTO {
+        id: None,
+        status: Some(status.to_string()),
+        job: JobRequestDTO {
+            start,
+            end,
+            job_type: JobType::Tracking {
+                tle: pass.tle.clone(),
+            },
+            rx_frequency: Frequency::from_hz(sample.rx_hz),
+            rx_offset_hz: None,
+            tx_frequency: Frequency::from_hz(sample.tx.map_or(0, |(hz, _, _)| hz)),
+            mode: Some(sample.mode.to_string()),
+            baud: sample.baud,
+            tx_power_dbm: sample.tx.map(|(_, power, _)| power),
+            tx_duty_cycle: sample.tx.map(|(_, _, duty)| duty),
+            polarization: None,
+            post_pass_action: None,
+            campaign: sample.campaign.map(str::to_string),
+        },
+        cancelled_at: None,
+        cancel_reason: None,
+    }
+}
+
+/// The dataset for `seed`, around `now`: three satellites, each with jobs on good passes
+/// over the station from yesterday to three days ahead, none overlapping another
+pub fn fixture(seed: u64, now: DateTime<Utc>) -> Result<Fixture, CliError> {
+    let mut dice = Dice(seed);
+    // Elements and passes hold still through the day, so output repeats until midnight
+    let epoch = now
+        .date_naive()
+        .and_hms_opt(0, 0, 0)
+        .map_or(now, |midnight| midnight.and_utc());
+    let mask = ElevationMask::default();
+    let horizon = Horizon {
+        station: &STATION,
+        min_elevation_deg: 0.0,
+        mask: &mask,
+    };
+
+    let mut satellites = Vec::new();
+    let mut taken: Vec<TimeWindow> = Vec::new();
+    let mut jobs = Vec::new();
+    for sample in &SAMPLES {
+        let tle = elements(sample, epoch, &mut dice);
+        let orbit = Satellite::from_tle(&tle)?;
+        let mut good: Vec<Pass> = predict::passes(
+            &orbit,
+            &horizon,
+            epoch - Duration::days(1),
+            epoch + Duration::days(3),
+        )?
+        .into_iter()
+        .filter(|pass| pass.max_elevation_deg >= MIN_MAX_ELEVATION_DEG)
+        .collect();
+        let mut chosen = 0;
+        while chosen < JOBS_PER_SATELLITE && !good.is_empty() {
+            let pass = good.remove(dice.next() as usize % good.len());
+            let window = TimeWindow::new(pass.rise, pass.set);
+            if taken.iter().any(|other| other.overlaps(&window)) {
+                continue;
+            }
+            taken.push(window);
+            jobs.push(job(sample, &pass, now));
+            chosen += 1;
+        }
+        satellites.push(SatelliteDTO {
+            norad_id: sample.norad_id,
+            tle,
+            updated_at: Some(epoch),
+        });
+    }
+    jobs.sort_by(JobDTO::schedule_order);
+    for (number, job) in jobs.iter_mut().enumerate() {
+        job.id = Some((number + 1).to_string());
+    }
+
+    Ok(Fixture {
+        station: StationInfo {
+            name: Some("DEMO ground station".to_string()),
+            location: Some(json!({
+                "name": "Buenos Aires (demo)",
+                "latitude": STATION.latitude,
+                "longitude": STATION.longitude,
+                "altitude_m": STATION.altitude_m,
+            })),
+            antennas: vec![
+                json!({ "name": "VHF crossed yagi", "band": "2 m" }),
+                json!({ "name": "UHF yagi", "band": "70 cm" }),
+            ],
+            azimuth: Some(180.0),
+            elevation: Some(90.0),
+            uptime_seconds: None,
+            queue_depth: None,
+            disk_free_bytes: Some(412_316_860_416),
+            polarizations: Some(vec!["RHCP".to_string(), "LHCP".to_string()]),
+            api_version: None,
+            scheduling_lead_seconds: None,
+            extra: BTreeMap::new(),
+        },
+        jobs,
+        satellites,
+        note: "DEMO — nothing was sent to a real station; the change is gone when the \
+               command exits"
+            .to_string(),
+    })
+}
+
+/// Where the demo station answers, for the command to be pointed at
+pub struct Demo {
+    pub base_url: String,
+    /// The station's own element sets, as a `tle_source` template
+    pub tle_source: String,
+}
+
+/// Serve the fixture for `seed` on a loopback port for as long as the process runs, and
+/// move the configuration, cache and state directories to a scratch directory of their
+/// own
+pub async fn start(seed: u64) -> Result<Demo, CliError> {
+    let scratch = std::env::temp_dir().join(format!("rustar-demo-{}", std::process::id()));
+    for dir in ["config", "cache", "state"] {
+        let dir = scratch.join(dir);
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("creating the demo directory {}", dir.display()))?;
+    }
+    paths::use_scratch(&scratch);
+
+    let router = mock_server::demo(fixture(seed, clock::now())?);
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", 0))
+        .await
+        .context("binding the demo station's port")?;
+    let base_url = format!(
+        "http://{}",
+        listener
+            .local_addr()
+            .context("reading the demo station's address")?
+    );
+    tokio::spawn(async move {
+        let _ = axum::serve(listener, router).await;
+    });
+    Ok(Demo {
+        tle_source: format!("{}/elements/{{norad}}", base_url),
+        base_url,
+    })
+}
@@ -0,0 +1,523 @@
+use crate::client::ApiClient;
+use crate::error::{CliError, ResultExt};
+use crate::humanize;
+use crate::reproducible;
+use crate::schedule;
+use crate::OutputFormat;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::IsTerminal;
+use std::path::Path;
+
+/// Fields the server adds on its own; they never count as differences
+const SERVER_FIELDS: &[&str] = &["id", "status", "created_at", "updated_at"];
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Change {
+    Changed,
+    /// In the local file only
+    Missing,
+    /// On the server only
+    Extra,
+}
+
+#[derive(Debug, Serialize)]
+struct Difference {
+    path: String,
+    change: Change,
+    local: Option<Value>,
+    server: Option<Value>,
+}
+
+/// Flatten nested objects and arrays into `a.b[0]` paths; nulls count as absent
+fn flatten(prefix: &str, value: &Value, out: &mut BTreeMap<String, Value>) {
+    match value {
+        Value::Object(fields) => {
+            for (key, value) in fields {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten(&path, value, out);
+            }
+        }
+        Value::Array(items) => {
+            for (index, item) in items.iter().enumerate() {
+                flatten(&format!("{}[{}]", prefix, index), item, out);
+            }
+        }
+        Value::Null => {}
+        other => {
+            out.insert(prefix.to_string(), other.clone());
+        }
+    }
+}
+
+/// Flattened fields of a job, with the defaults the server applies filled in
+fn fields(job: &Value) -> BTreeMap<String, Value> {
+    let mut out = BTreeMap::new();
+    flatten("", job, &mut out);
+    // Jobs without a tag are tracking jobs
+    out.entry("job_type".to_string())
+        .or_insert_with(|| "tracking".into());
+    for field in SERVER_FIELDS {
+        out.remove(*field);
+    }
+    out
+}
+
+/// Compare two leaf values: instants by time rather than spelling, frequencies within a tolerance.
+/// Instants are compared as the server stores them, so "+05:30" against "Z", or
+/// microseconds the server truncated, are not differences.
+fn same(path: &str, local: &Value, server: &Value, tolerance_hz: f64) -> bool {
+    if let (Some(a), Some(b)) = (local.as_str(), server.as_str()) {
+        if let (Ok(a), Ok(b)) = (
+            DateTime::parse_from_rfc3339(a),
+            DateTime::parse_from_rfc3339(b),
+        ) {
+            return schedule::to_server_precision(a.with_timezone(&Utc))
+                == schedule::to_server_precision(b.with_timezone(&Utc));
+        }
+        return a == b;
+    }
+    if let (Some(a), Some(b)) = (local.as_f64(), server.as_f64()) {
+        let tolerance = if path.ends_with("frequency") {
+            tolerance_hz
+        } else {
+            0.0
+        };
+        return (a - b).abs() <= tolerance;
+    }
+    local == server
+}
+
+fn compare(local: &Value, server: &Value, tolerance_hz: f64) -> Vec<Difference> {
+    let local = fields(local);
+    let mut server = fields(server);
+    let mut differences = Vec::new();
+
+    for (path, value) in local {
+        match server.remove(&path) {
+            Some(other) if same(&path, &value, &other, tolerance_hz) => {}
+            Some(other) => differences.push(Difference {
+                path,
+                change: Change::Changed,
+                local: Some(value),
+                server: Some(other),
+            }),
+            None => differences.push(Difference {
+                path,
+                change: Change::Missing,
+                local: Some(value),
+                server: None,
+            }),
+        }
+    }
+    for (path, value) in server {
+        differences.push(Difference {
+            path,
+            change: Change::Extra,
+            local: None,
+            server: Some(value),
+        });
+    }
+
+    differences.sort_by(|a, b| a.path.cmp(&b.path));
+    differences
+}
+
+/// ANSI styling, only when stdout is a terminal and NO_COLOR is unset
+struct Style {
+    enabled: bool,
+}
+
+impl Style {
+    fn detect() -> Self {
+        Self {
+            enabled: reproducible::styled(std::io::stdout().is_terminal()),
+        }
+    }
+
+    fn paint(&self, code: &str, text: &str) -> String {
+        if self.enabled {
+            format!("\x1b[{}m{}\x1b[0m", code, text)
+        } else {
+            text.to_string()
+        }
+    }
+
+    fn removed(&self, text: &str) -> String {
+        self.paint("31", text)
+    }
+
+    fn added(&self, text: &str) -> String {
+        self.paint("32", text)
+    }
+}
+
+fn show_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Caret line under the columns where two TLE lines differ
+fn column_markers(a: &str, b: &str) -> String {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let markers: String = (0..a.len().max(b.len()))
+        .map(|i| if a.get(i) == b.get(i) { ' ' } else { '^' })
+        .collect();
+    markers.trim_end().to_string()
+}
+
+fn print_text(differences: &[Difference], id: &str, file: &Path) {
+    let style = Style::detect();
+    if differences.is_empty() {
+        println!("✅ Job {} on the server matches {}", id, file.display());
+        return;
+    }
+
+    println!(
+        "🔀 Job {}: {} difference(s) ({} local, {} server)",
+        id,
+        differences.len(),
+        style.removed("-"),
+        style.added("+")
+    );
+    for d in differences {
+        match (&d.change, &d.local, &d.server) {
+            (Change::Changed, Some(local), Some(server)) if d.path.starts_with("tle.tle") => {
+                let (local, server) = (show_value(local), show_value(server));
+                println!("  ~ {}:", d.path);
+                println!("      {}", style.removed(&format!("- {}", local)));
+                println!("      {}", style.added(&format!("+ {}", server)));
+                println!("        {}", column_markers(&local, &server));
+            }
+            (Change::Changed, Some(local), Some(server)) => println!(
+                "  ~ {}: {} → {}",
+                d.path,
+                style.removed(&show_value(local)),
+                style.added(&show_value(server))
+            ),
+            (Change::Missing, Some(local), _) => println!(
+                "  {}",
+                style.removed(&format!(
+                    "- {}: {} (not stored on the server)",
+                    d.path,
+                    show_value(local)
+                ))
+            ),
+            (_, _, Some(server)) => println!(
+                "  {}",
+                style.added(&format!(
+                    "+ {}: {} (only on the server)",
+                    d.path,
+                    show_value(server)
+                ))
+            ),
+            _ => {}
+        }
+    }
+}
+
+/// Leaf values in a change list: instants as times of day when both ends share the date,
+/// frequencies with their unit
+fn brief(path: &str, before: Option<&Value>, after: Option<&Value>) -> (String, String) {
+    let instant = |value: Option<&Value>| {
+        value
+            .and_then(Value::as_str)
+            .and_then(|text| DateTime::parse_from_rfc3339(text).ok())
+            .map(|at| at.with_timezone(&Utc))
+    };
+    let shown = |value: Option<&Value>| value.map_or_else(|| "(unset)".to_string(), show_value);
+    if let (Some(a), Some(b)) = (instant(before), instant(after)) {
+        let format = |at: DateTime<Utc>, date: bool| {
+            let time = match at.timestamp_subsec_millis() {
+                0 => at.format("%H:%M:%S"),
+                _ => at.format("%H:%M:%S%.3f"),
+            };
+            match date {
+                true => format!("{} {}", at.format("%Y-%m-%d"), time),
+                false => time.to_string(),
+            }
+        };
+        let date = a.date_naive() != b.date_naive();
+        return (format(a, date), format(b, date));
+    }
+    if path.ends_with("frequency") {
+        let hz = |value: Option<&Value>| value.and_then(Value::as_f64);
+        if let (Some(a), Some(b)) = (hz(before), hz(after)) {
+            return (humanize::frequency(a), humanize::frequency(b));
+        }
+    }
+    (shown(before), shown(after))
+}
+
+/// One field as it was before a change and as the server holds it after
+#[derive(Debug, Serialize)]
+struct Held {
+    path: String,
+    before: Option<Value>,
+    after: Option<Value>,
+}
+
+/// A requested value the server stored differently
+#[derive(Debug, Serialize)]
+struct Normalized {
+    path: String,
+    requested: Option<Value>,
+    stored: Option<Value>,
+}
+
+/// The top-level field a flattened path belongs to
+fn field_of(path: &str) -> &str {
+    path.split(['.', '[']).next().unwrap_or(path)
+}
+
+/// After job `id` was changed: read it back, list what the server now holds against
+/// `before`, and point out where it stored something other than `expected` in the
+/// `requested` fields, which means the server normalized the request. A failed read is
+/// only a warning; the change itself went through.
+pub async fn report_change(
+    client: &ApiClient,
+    id: &str,
+    before: &Value,
+    expected: &Value,
+    requested: &[&str],
+    output: OutputFormat,
+) -> Result<(), CliError> {
+    let after = match client.job_json(id).await {
+        Ok(after) if !after.is_null() => after,
+        Ok(_) => {
+            eprintln!(
+                "⚠️ The server returned no body for job {} after the change",
+                id
+            );
+            return Ok(());
+        }
+        Err(e) => {
+            eprintln!(
+                "⚠️ Could not read job {} back after the change: {}",
+                id,
+                e.full_message()
+            );
+            return Ok(());
+        }
+    };
+    let changes: Vec<Held> = compare(before, &after, 0.0)
+        .into_iter()
+        .map(|d| Held {
+            path: d.path,
+            before: d.local,
+            after: d.server,
+        })
+        .collect();
+    let normalized: Vec<Normalized> = compare(expected, &after, 0.0)
+        .into_iter()
+        .filter(|d| requested.contains(&field_of(&d.path)))
+        .map(|d| Normalized {
+            path: d.path,
+            requested: d.local,
+            stored: d.server,
+        })
+        .collect();
+
+    if output == OutputFormat::Json {
+        let document = serde_json::json!({
+            "before": before,
+            "after": after,
+            "changes": changes,
+            "normalized": normalized,
+        });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&document)
+                .map_err(|e| CliError::parse("job changes", e))?
+        );
+        return Ok(());
+    }
+
+    let listed = |paths: Vec<(String, String, String)>| {
+        paths
+            .into_iter()
+            .map(|(path, from, to)| format!("{}: {} → {}", path, from, to))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    if changes.is_empty() {
+        println!(
+            "⚠️ Job {} on the server is the same as before the change",
+            id
+        );
+    } else {
+        println!(
+            "📋 Job {} now holds {}",
+            id,
+            listed(
+                changes
+                    .iter()
+                    .map(|c| {
+                        let (from, to) = brief(&c.path, c.before.as_ref(), c.after.as_ref());
+                        (c.path.clone(), from, to)
+                    })
+                    .collect()
+            )
+        );
+    }
+    if !normalized.is_empty() {
+        println!(
+            "⚠️ The server stored something other than what was sent (requested → stored): {}",
+            listed(
+                normalized
+                    .iter()
+                    .map(|n| {
+                        let (from, to) = brief(&n.path, n.requested.as_ref(), n.stored.as_ref());
+                        (n.path.clone(), from, to)
+                    })
+                    .collect()
+            )
+        );
+    }
+    Ok(())
+}
+
+/// One field of a job that was changed on one side or both since the version a change
+/// was made against
+#[derive(Debug)]
+pub struct ThreeWay {
+    pub path: String,
+    base: Option<Value>,
+    yours: Option<Value>,
+    theirs: Option<Value>,
+}
+
+/// Whether two sides hold different values at `path`, compared as `compare` does
+fn differs(path: &str, a: Option<&Value>, b: Option<&Value>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => !same(path, a, b, 0.0),
+        (a, b) => a.is_some() != b.is_some(),
+    }
+}
+
+impl ThreeWay {
+    /// Whether both sides changed the field, to different values
+    pub fn clashes(&self) -> bool {
+        let (base, yours, theirs) = (
+            self.base.as_ref(),
+            self.yours.as_ref(),
+            self.theirs.as_ref(),
+        );
+        differs(&self.path, base, yours)
+            && differs(&self.path, base, theirs)
+            && differs(&self.path, yours, theirs)
+    }
+
+    /// Who changed the field: "you", "them" or "both"
+    pub fn changed_by(&self) -> &'static str {
+        let (base, yours, theirs) = (
+            self.base.as_ref(),
+            self.yours.as_ref(),
+            self.theirs.as_ref(),
+        );
+        match (
+            differs(&self.path, base, yours),
+            differs(&self.path, base, theirs),
+        ) {
+            (true, true) => "both",
+            (true, false) => "you",
+            _ => "them",
+        }
+    }
+
+    /// The base, yours and theirs values as shown, "(unset)" where a side has none
+    pub fn shown(&self) -> [String; 3] {
+        [&self.base, &self.yours, &self.theirs].map(|value| {
+            value
+                .as_ref()
+                .map_or_else(|| "(unset)".to_string(), show_value)
+        })
+    }
+}
+
+/// The fields where `yours` or `theirs` differ from `base`, the version both started from
+pub fn three_way(base: &Value, yours: &Value, theirs: &Value) -> Vec<ThreeWay> {
+    let (base, yours, theirs) = (fields(base), fields(yours), fields(theirs));
+    let paths: BTreeSet<&String> = base
+        .keys()
+        .chain(yours.keys())
+        .chain(theirs.keys())
+        .collect();
+    paths
+        .into_iter()
+        .filter(|path| {
+            differs(path, base.get(*path), yours.get(*path))
+                || differs(path, base.get(*path), theirs.get(*path))
+        })
+        .map(|path| ThreeWay {
+            path: path.clone(),
+            base: base.get(path).cloned(),
+            yours: yours.get(path).cloned(),
+            theirs: theirs.get(path).cloned(),
+        })
+        .collect()
+}
+
+/// Read a job definition: a single object, or a one-job array as the dump writes
+fn read_local(path: &Path) -> Result<Value, CliError> {
+    let contents =
+        std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    let value: Value = serde_json::from_str(&contents)
+        .map_err(|e| CliError::parse(format!("job file {}", path.display()), e))?;
+    match value {
+        Value::Array(mut items) if items.len() == 1 => Ok(items.remove(0)),
+        Value::Array(items) => Err(CliError::validation(
+            "file",
+            format!(
+                "{} holds {} jobs; jobs diff compares exactly one",
+                path.display(),
+                items.len()
+            ),
+        )),
+        other => Ok(other),
+    }
+}
+
+/// Compare a local job definition with the server's copy.
+/// Returns whether any difference was found.
+pub async fn diff_job(
+    client: &ApiClient,
+    id: &str,
+    file: &Path,
+    tolerance_hz: f64,
+    output: OutputFormat,
+) -> Result<bool, CliError> {
+    let local = read_local(file)?;
+    let server = client.job_json(id).await?;
+    if server.is_null() {
+        return Err(CliError::NotFound {
+            message: Some(format!("the server returned no body for job {}", id)),
+        });
+    }
+
+    let differences = compare(&local, &server, tolerance_hz);
+    if output == OutputFormat::Json {
+        let document = serde_json::json!({
+            "id": id,
+            "identical": differences.is_empty(),
+            "differences": differences,
+        });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&document)
+                .map_err(|e| CliError::parse("job differences", e))?
+        );
+    } else {
+        print_text(&differences, id, file);
+    }
+    Ok(!differences.is_empty())
+}
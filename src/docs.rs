@@ -0,0 +1,406 @@
+use crate::client;
+use crate::config;
+use crate::error::{self, CliError, ResultExt};
+use crate::paths;
+use crate::tle;
+use clap::{Arg, Command};
+use clap_mangen::roff::{bold, roman, Roff};
+use std::path::Path;
+
+/// What `generate-docs` writes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum DocsFormat {
+    /// One section 1 man page per command
+    Man,
+    /// A single markdown reference covering every command
+    Markdown,
+}
+
+/// An environment variable the CLI reads, and the global flag that overrides it
+struct EnvVar {
+    name: &'static str,
+    flag: Option<&'static str>,
+    description: String,
+}
+
+fn environment() -> Vec<EnvVar> {
+    vec![
+        EnvVar {
+            name: client::BASE_URL_ENV,
+            flag: Some("base_url"),
+            description: format!(
+                "Ground station API base URL (default {})",
+                client::DEFAULT_BASE_URL
+            ),
+        },
+        EnvVar {
+            name: client::TIMEOUT_ENV,
+            flag: None,
+            description: format!(
+                "Request timeout in whole seconds (default {})",
+                client::DEFAULT_TIMEOUT_SECONDS
+            ),
+        },
+        EnvVar {
+            name: config::PROFILE_ENV,
+            flag: Some("profile"),
+            description: format!(
+                "Configuration profile to use (default: the configured default, then '{}')",
+                config::DEFAULT_PROFILE
+            ),
+        },
+        EnvVar {
+            name: tle::SOURCE_ENV,
+            flag: None,
+            description: format!(
+                "Where element sets are fetched by NORAD ID; {{norad}} is replaced (default {})",
+                tle::DEFAULT_TLE_SOURCE
+            ),
+        },
+        EnvVar {
+            name: tle::DESIGNATOR_SOURCE_ENV,
+            flag: None,
+            description: format!(
+                "Where element sets are fetched by international designator; {{designator}} is replaced (default {})",
+                tle::DEFAULT_TLE_DESIGNATOR_SOURCE
+            ),
+        },
+        EnvVar {
+            name: "NO_COLOR",
+            flag: None,
+            description: "Disable colour in jobs diff output and greyed-out rows in tables".to_string(),
+        },
+        EnvVar {
+            name: "COLUMNS",
+            flag: None,
+            description:
+                "Width tables and timelines are fitted to (default the terminal's; --wide ignores it)"
+                    .to_string(),
+        },
+        EnvVar {
+            name: paths::CONFIG_DIR_ENV,
+            flag: None,
+            description: "Directory of config.toml (default $XDG_CONFIG_HOME/rustar)".to_string(),
+        },
+        EnvVar {
+            name: paths::CACHE_DIR_ENV,
+            flag: None,
+            description: "Directory of the TLE cache (default $XDG_CACHE_HOME/rustar)".to_string(),
+        },
+        EnvVar {
+            name: paths::STATE_DIR_ENV,
+            flag: None,
+            description: "Directory of the offline queue (default $XDG_STATE_HOME/rustar)"
+                .to_string(),
+        },
+    ]
+}
+
+/// A titled list of terms and their meaning
+struct Section {
+    title: &'static str,
+    intro: Option<String>,
+    entries: Vec<(String, String)>,
+}
+
+/// Reference sections for the top-level page, built from the tables the parsers use
+fn reference_sections() -> Vec<Section> {
+    let (_, date) = crate::parse::DATE_FORMAT;
+    let separators: Vec<String> = crate::parse::DATETIME_SEPARATORS
+        .iter()
+        .map(|c| format!("'{}'", c))
+        .collect();
+    let mut datetimes: Vec<(String, String)> = crate::parse::TIME_FORMATS
+        .iter()
+        .map(|(_, time)| {
+            (
+                format!("{} {}", date, time),
+                "UTC wall-clock time".to_string(),
+            )
+        })
+        .collect();
+    datetimes.push((
+        "RFC 3339".to_string(),
+        "Any offset, e.g. 2025-10-02T14:30:00+02:00; converted to UTC".to_string(),
+    ));
+
+    let mut frequencies = vec![("145800000".to_string(), "Plain hertz".to_string())];
+    let mut multipliers: Vec<(u64, Vec<&str>)> = Vec::new();
+    for (suffix, multiplier) in crate::frequency::SUFFIXES {
+        match multipliers.iter_mut().find(|(m, _)| m == multiplier) {
+            Some((_, suffixes)) => suffixes.push(suffix),
+            None => multipliers.push((*multiplier, vec![suffix])),
+        }
+    }
+    for (multiplier, suffixes) in multipliers {
+        frequencies.push((
+            suffixes
+                .iter()
+                .map(|s| format!("N{}", s))
+                .collect::<Vec<_>>()
+                .join(", "),
+            format!("N × {} Hz", multiplier),
+        ));
+    }
+
+    let mut durations: Vec<(String, String)> = crate::parse::DURATION_UNITS
+        .iter()
+        .map(|(unit, _, name)| (format!("N{}", unit), name.to_string()))
+        .collect();
+    durations.push((
+        "1h30m".to_string(),
+        "Parts add up; no spaces between them".to_string(),
+    ));
+
+    vec![
+        Section {
+            title: "EXIT STATUS",
+            intro: None,
+            entries: error::EXIT_CODES
+                .iter()
+                .map(|(code, meaning)| (code.to_string(), meaning.to_string()))
+                .collect(),
+        },
+        Section {
+            title: "ENVIRONMENT",
+            intro: Some(
+                "The .env file in the config directory, or the one given with --env-file, \
+                 is loaded first; variables already set take precedence. --set key=value \
+                 overrides any of them for one invocation."
+                    .to_string(),
+            ),
+            entries: environment()
+                .into_iter()
+                .map(|var| {
+                    let description = match var.flag {
+                        Some(flag) => format!(
+                            "{}; --{} takes precedence",
+                            var.description,
+                            flag.replace('_', "-")
+                        ),
+                        None => var.description,
+                    };
+                    (var.name.to_string(), description)
+                })
+                .collect(),
+        },
+        Section {
+            title: "DATETIME INPUT",
+            intro: Some(format!(
+                "Date and time may be joined by {}.",
+                separators.join(" or ")
+            )),
+            entries: datetimes,
+        },
+        Section {
+            title: "FREQUENCY INPUT",
+            intro: Some(
+                "An optional Hz may follow the suffix; values are rounded to whole hertz."
+                    .to_string(),
+            ),
+            entries: frequencies,
+        },
+        Section {
+            title: "DURATION INPUT",
+            intro: None,
+            entries: durations,
+        },
+    ]
+}
+
+/// The command tree ready for rendering: built, with env-linked flags marked
+fn prepared(cmd: Command) -> Command {
+    let mut cmd = environment()
+        .into_iter()
+        .filter_map(|var| var.flag.map(|flag| (flag, var.name)))
+        .fold(cmd, |cmd, (flag, name)| cmd.mut_arg(flag, |a| a.env(name)))
+        .disable_help_subcommand(true);
+    cmd.build();
+    cmd
+}
+
+fn visible_subcommands(cmd: &Command) -> impl Iterator<Item = &Command> {
+    cmd.get_subcommands().filter(|s| !s.is_hide_set())
+}
+
+fn write_man_pages(cmd: &Command, out_dir: &Path, root: bool) -> Result<usize, CliError> {
+    let man = clap_mangen::Man::new(cmd.clone());
+    let mut page = Vec::new();
+    man.render(&mut page).context("rendering man page")?;
+
+    if root {
+        let mut roff = Roff::default();
+        for section in reference_sections() {
+            roff.control("SH", [section.title]);
+            if let Some(intro) = section.intro {
+                roff.text([roman(intro)]);
+            }
+            for (term, meaning) in section.entries {
+                roff.control("TP", []);
+                roff.text([bold(term)]);
+                roff.text([roman(meaning)]);
+            }
+        }
+        roff.to_writer(&mut page).context("rendering man page")?;
+    }
+
+    let path = out_dir.join(man.get_filename());
+    std::fs::write(&path, page).with_context(|| format!("writing {}", path.display()))?;
+
+    let mut written = 1;
+    for sub in visible_subcommands(cmd) {
+        written += write_man_pages(sub, out_dir, false)?;
+    }
+    Ok(written)
+}
+
+/// `--flag <VALUE>` or `<NAME>` as typed on the command line
+fn arg_signature(arg: &Arg) -> String {
+    let value = || {
+        arg.get_value_names()
+            .map(|names| {
+                names
+                    .iter()
+                    .map(|n| format!("<{}>", n))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .unwrap_or_else(|| format!("<{}>", arg.get_id().as_str().to_uppercase()))
+    };
+
+    if arg.is_positional() {
+        return value();
+    }
+    let mut names = Vec::new();
+    if let Some(short) = arg.get_short() {
+        names.push(format!("-{}", short));
+    }
+    if let Some(long) = arg.get_long() {
+        names.push(format!("--{}", long));
+    }
+    let mut signature = names.join(", ");
+    if arg.get_action().takes_values() {
+        signature.push(' ');
+        signature.push_str(&value());
+    }
+    signature
+}
+
+fn markdown_arg(arg: &Arg, out: &mut String) {
+    let mut line = format!("- `{}`", arg_signature(arg));
+    if let Some(help) = arg.get_long_help().or(arg.get_help()) {
+        line.push_str(&format!(" — {}", help));
+    }
+
+    let mut notes = Vec::new();
+    if arg.get_action().takes_values() {
+        let defaults: Vec<String> = arg
+            .get_default_values()
+            .iter()
+            .map(|v| v.to_string_lossy().into_owned())
+            .collect();
+        if !defaults.is_empty() {
+            notes.push(format!("default `{}`", defaults.join(",")));
+        }
+        let values: Vec<String> = arg
+            .get_possible_values()
+            .iter()
+            .filter(|v| !v.is_hide_set())
+            .map(|v| format!("`{}`", v.get_name()))
+            .collect();
+        if !values.is_empty() {
+            notes.push(format!("one of {}", values.join(", ")));
+        }
+    }
+    if let Some(env) = arg.get_env() {
+        notes.push(format!("env `{}`", env.to_string_lossy()));
+    }
+    if arg.is_required_set() {
+        notes.push("required".to_string());
+    }
+    if !notes.is_empty() {
+        line.push_str(&format!(" ({})", notes.join("; ")));
+    }
+    out.push_str(&line);
+    out.push('\n');
+}
+
+fn markdown_command(cmd: &Command, depth: usize, out: &mut String) {
+    let name = cmd.get_bin_name().unwrap_or_else(|| cmd.get_name());
+    out.push_str(&format!("{} `{}`\n\n", "#".repeat(depth.min(4)), name));
+    if let Some(about) = cmd.get_long_about().or(cmd.get_about()) {
+        out.push_str(&format!("{}\n\n", about));
+    }
+
+    let usage = cmd.clone().render_usage().to_string();
+    let usage = usage.strip_prefix("Usage: ").unwrap_or(&usage);
+    out.push_str(&format!("```\n{}\n```\n\n", usage));
+
+    // Global flags are documented once, on the top-level command
+    let args: Vec<&Arg> = cmd
+        .get_arguments()
+        .filter(|a| !a.is_hide_set())
+        .filter(|a| depth == 1 || (!a.is_global_set() && a.get_id() != "help"))
+        .collect();
+    if !args.is_empty() {
+        for arg in args {
+            markdown_arg(arg, out);
+        }
+        out.push('\n');
+    }
+
+    let subcommands: Vec<&Command> = visible_subcommands(cmd).collect();
+    if !subcommands.is_empty() {
+        for sub in &subcommands {
+            out.push_str(&format!(
+                "- `{}` — {}\n",
+                sub.get_name(),
+                sub.get_about().map(|a| a.to_string()).unwrap_or_default()
+            ));
+        }
+        out.push('\n');
+        for sub in subcommands {
+            markdown_command(sub, depth + 1, out);
+        }
+    }
+}
+
+fn markdown(cmd: &Command) -> String {
+    let mut out = String::new();
+    markdown_command(cmd, 1, &mut out);
+
+    for section in reference_sections() {
+        let mut title = section.title.to_lowercase();
+        title[..1].make_ascii_uppercase();
+        out.push_str(&format!("## {}\n\n", title));
+        if let Some(intro) = section.intro {
+            out.push_str(&format!("{}\n\n", intro));
+        }
+        out.push_str("| | |\n|---|---|\n");
+        for (term, meaning) in section.entries {
+            out.push_str(&format!("| `{}` | {} |\n", term, meaning));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Render the reference for every command into `out_dir`
+pub fn generate(cmd: Command, format: DocsFormat, out_dir: &Path) -> Result<(), CliError> {
+    std::fs::create_dir_all(out_dir).with_context(|| format!("creating {}", out_dir.display()))?;
+    let cmd = prepared(cmd);
+
+    match format {
+        DocsFormat::Man => {
+            let written = write_man_pages(&cmd, out_dir, true)?;
+            println!("📖 Wrote {} man page(s) to {}", written, out_dir.display());
+        }
+        DocsFormat::Markdown => {
+            let path = out_dir.join(format!("{}.md", cmd.get_name()));
+            std::fs::write(&path, markdown(&cmd))
+                .with_context(|| format!("writing {}", path.display()))?;
+            println!("📖 Wrote {}", path.display());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,397 @@
+//! `jobs download`: the recordings a job produced, fetched to local files that survive a
+//! broken link. The body is written to `<file>.partial`, with the ETag it came with kept
+//! beside it in `<file>.partial.json`; the next attempt asks only for the rest (`Range`
+//! with `If-Range`, so a recording that changed on the server is sent whole instead). A
+//! finished file is checked against the digest the server sends before it takes its name.
+
+use crate::client::{ApiClient, Recording};
+use crate::error::{self, CliError, ResultExt};
+use crate::humanize;
+use crate::storage;
+use base64::Engine;
+use reqwest::header::HeaderMap;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// SHA-256 of the whole recording, in hex or base64
+pub const SHA256_HEADER: &str = "x-checksum-sha256";
+/// MD5 of the whole recording, in base64 as RFC 1864 has it
+pub const MD5_HEADER: &str = "content-md5";
+
+/// Files are hashed this much at a time, so a recording of gigabytes is never held whole
+const HASH_CHUNK: usize = 1024 * 1024;
+
+/// What the server says a recording's bytes hash to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Digest {
+    Sha256(Vec<u8>),
+    Md5(Vec<u8>),
+}
+
+impl Digest {
+    /// The stronger of the digests in `headers`. One that does not decode is an error
+    /// rather than no digest, since the server meant the file to be checked.
+    pub fn from_headers(headers: &HeaderMap) -> Result<Option<Digest>, CliError> {
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::trim)
+        };
+        if let Some(value) = header(SHA256_HEADER) {
+            return decode(value, 32)
+                .map(|bytes| Some(Digest::Sha256(bytes)))
+                .ok_or_else(|| CliError::parse(format!("the {} header", SHA256_HEADER), value));
+        }
+        if let Some(value) = header(MD5_HEADER) {
+            return decode(value, 16)
+                .map(|bytes| Some(Digest::Md5(bytes)))
+                .ok_or_else(|| CliError::parse(format!("the {} header", MD5_HEADER), value));
+        }
+        Ok(None)
+    }
+
+    pub fn algorithm(&self) -> &'static str {
+        match self {
+            Digest::Sha256(_) => "SHA-256",
+            Digest::Md5(_) => "MD5",
+        }
+    }
+
+    fn bytes(&self) -> &[u8] {
+        match self {
+            Digest::Sha256(bytes) | Digest::Md5(bytes) => bytes,
+        }
+    }
+
+    /// Whether the file at `path` hashes to this digest
+    pub async fn matches(&self, path: &Path) -> Result<bool, CliError> {
+        let digest = self.clone();
+        let path = path.to_path_buf();
+        tokio::task::spawn_blocking(move || {
+            let hashed =
+                hash(&path, &digest).with_context(|| format!("hashing {}", path.display()))?;
+            Ok(hashed == digest.bytes())
+        })
+        .await
+        .map_err(|e| CliError::Io(std::io::Error::other(e)))?
+    }
+}
+
+/// A digest of `len` bytes written in hex or in base64
+fn decode(value: &str, len: usize) -> Option<Vec<u8>> {
+    let bytes = if value.len() == len * 2 && value.bytes().all(|b| b.is_ascii_hexdigit()) {
+        (0..len)
+            .map(|i| u8::from_str_radix(&value[i * 2..i * 2 + 2], 16).ok())
+            .collect::<Option<Vec<u8>>>()?
+    } else {
+        base64::engine::general_purpose::STANDARD
+            .decode(value)
+            .ok()?
+    };
+    (bytes.len() == len).then_some(bytes)
+}
+
+/// `path` hashed the way `digest` was made, read a chunk at a time
+fn hash(path: &Path, digest: &Digest) -> std::io::Result<Vec<u8>> {
+    use md5::Digest as _;
+
+    let mut file = std::fs::File::open(path)?;
+    let mut buffer = vec![0; HASH_CHUNK];
+    let mut sha256 = ring::digest::Context::new(&ring::digest::SHA256);
+    let mut md5 = md5::Md5::new();
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        match digest {
+            Digest::Sha256(_) => sha256.update(&buffer[..read]),
+            Digest::Md5(_) => md5.update(&buffer[..read]),
+        }
+    }
+    Ok(match digest {
+        Digest::Sha256(_) => sha256.finish().as_ref().to_vec(),
+        Digest::Md5(_) => md5.finalize().to_vec(),
+    })
+}
+
+/// What the server says about a recording, from the headers it would send it with
+#[derive(Debug, Clone, Default)]
+pub struct RecordingInfo {
+    pub length: Option<u64>,
+    pub etag: Option<String>,
+    pub digest: Option<Digest>,
+}
+
+/// A recording now whole on disk
+#[derive(Debug, Clone)]
+pub struct Downloaded {
+    pub path: PathBuf,
+    pub bytes: u64,
+    /// Bytes an earlier attempt had already saved, zero for a fresh download
+    pub resumed_from: u64,
+    /// The algorithm of the server's digest the file was checked against
+    pub checked: Option<&'static str>,
+}
+
+/// Beside a `.partial` file: what it is a part of
+#[derive(Debug, Serialize, Deserialize)]
+struct Sidecar {
+    url: String,
+    etag: String,
+}
+
+/// A download of `dest` under way, or broken off and waiting to be resumed
+pub struct Partial {
+    dest: PathBuf,
+    pub path: PathBuf,
+    sidecar: PathBuf,
+}
+
+impl Partial {
+    pub fn of(dest: &Path) -> Self {
+        let with = |suffix: &str| {
+            let mut name = dest.file_name().unwrap_or_default().to_os_string();
+            name.push(suffix);
+            dest.with_file_name(name)
+        };
+        Self {
+            dest: dest.to_path_buf(),
+            path: with(".partial"),
+            sidecar: with(".partial.json"),
+        }
+    }
+
+    /// Where an earlier attempt at `url` stopped, and the ETag of what it was fetching.
+    /// None when there is nothing to resume: no partial file, or no ETag to ask for the
+    /// same recording by.
+    pub fn resumable(&self, url: &str) -> Option<(u64, String)> {
+        let text = std::fs::read_to_string(&self.sidecar).ok()?;
+        let sidecar: Sidecar = serde_json::from_str(&text).ok()?;
+        let saved = std::fs::metadata(&self.path).ok()?.len();
+        (sidecar.url == url && saved > 0).then_some((saved, sidecar.etag))
+    }
+
+    /// Note what the partial file holds part of; without an ETag it cannot be resumed
+    pub fn remember(&self, url: &str, etag: Option<&str>) -> Result<(), CliError> {
+        match etag {
+            Some(etag) => {
+                let sidecar = Sidecar {
+                    url: url.to_string(),
+                    etag: etag.to_string(),
+                };
+                let text = serde_json::to_string_pretty(&sidecar)
+                    .map_err(|e| CliError::parse("the download sidecar", e))?;
+                storage::write_atomic(&self.sidecar, text)
+            }
+            None => remove(&self.sidecar),
+        }
+    }
+
+    /// Drop what was saved, so the next attempt starts over
+    pub fn discard(&self) -> Result<(), CliError> {
+        remove(&self.path)?;
+        remove(&self.sidecar)
+    }
+
+    /// Give the finished file its name
+    pub fn finish(&self) -> Result<(), CliError> {
+        std::fs::rename(&self.path, &self.dest)
+            .with_context(|| format!("moving the download to {}", self.dest.display()))?;
+        remove(&self.sidecar)
+    }
+}
+
+fn remove(path: &Path) -> Result<(), CliError> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(e).with_context(|| format!("removing {}", path.display()))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// The station names each recording; one that would land outside `dir` is refused
+fn destination(dir: &Path, name: &str) -> Result<PathBuf, CliError> {
+    let plain = !name.is_empty()
+        && name != "."
+        && name != ".."
+        && !name.contains(['/', '\\'])
+        && !name.starts_with('.');
+    if !plain {
+        return Err(CliError::validation(
+            "recording name",
+            format!("'{}' is not a plain file name", name),
+        ));
+    }
+    Ok(dir.join(name))
+}
+
+/// `jobs download`: fetch job `id`'s recordings (only those named in `only`, when any)
+/// into `dir`, resuming any a previous run broke off. With `verify_only`, transfer
+/// nothing and check the files already there against the server's digests instead.
+/// Returns how many recordings failed.
+pub async fn run(
+    client: &ApiClient,
+    id: &str,
+    dir: &Path,
+    only: &[String],
+    verify_only: bool,
+) -> Result<usize, CliError> {
+    let Some(results) = client.job_results(id).await? else {
+        return Err(CliError::NotFound {
+            message: Some(format!("the server keeps no results for job {}", id)),
+        });
+    };
+    if let Some(missing) = only
+        .iter()
+        .find(|name| !results.recordings.iter().any(|r| &r.name == *name))
+    {
+        return Err(CliError::NotFound {
+            message: Some(format!("job {} has no recording named '{}'", id, missing)),
+        });
+    }
+    let recordings: Vec<&Recording> = results
+        .recordings
+        .iter()
+        .filter(|r| only.is_empty() || only.contains(&r.name))
+        .collect();
+    if recordings.is_empty() {
+        println!("📭 Job {} has no recordings", id);
+        return Ok(0);
+    }
+    if !verify_only {
+        std::fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+    }
+
+    let mut failed = 0;
+    for recording in recordings {
+        let outcome = match destination(dir, &recording.name) {
+            Ok(dest) if verify_only => verify(client, id, &recording.name, &dest).await,
+            Ok(dest) => fetch(client, id, &recording.name, &dest).await,
+            Err(e) => Err(e),
+        };
+        match outcome {
+            Ok(true) => {}
+            Ok(false) => failed += 1,
+            Err(e) => {
+                error::report(&recording.name, &e);
+                failed += 1;
+            }
+        }
+    }
+    Ok(failed)
+}
+
+async fn fetch(client: &ApiClient, id: &str, name: &str, dest: &Path) -> Result<bool, CliError> {
+    let downloaded = client.download_recording(id, name, dest).await?;
+    let mut line = format!(
+        "⬇️ {}: {}",
+        downloaded.path.display(),
+        humanize::bytes(downloaded.bytes)
+    );
+    if downloaded.resumed_from > 0 {
+        line.push_str(&format!(
+            ", resumed after {}",
+            humanize::bytes(downloaded.resumed_from)
+        ));
+    }
+    match downloaded.checked {
+        Some(algorithm) => line.push_str(&format!(", {} checked", algorithm)),
+        None => line.push_str(", the server gave no digest to check"),
+    }
+    println!("{}", line);
+    Ok(true)
+}
+
+/// Check a file already downloaded against what the server says without fetching it
+async fn verify(client: &ApiClient, id: &str, name: &str, dest: &Path) -> Result<bool, CliError> {
+    let local = match std::fs::metadata(dest) {
+        Ok(metadata) => metadata.len(),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            println!("📭 {}: not downloaded", dest.display());
+            return Ok(false);
+        }
+        Err(e) => return Err(e).with_context(|| format!("reading {}", dest.display())),
+    };
+    let info = client.recording_info(id, name).await?;
+    if let Some(length) = info.length.filter(|length| *length != local) {
+        println!(
+            "❌ {}: {} on disk, {} on the server",
+            dest.display(),
+            humanize::bytes(local),
+            humanize::bytes(length)
+        );
+        return Ok(false);
+    }
+    match info.digest {
+        Some(digest) if digest.matches(dest).await? => {
+            println!(
+                "✅ {}: matches the server's {}",
+                dest.display(),
+                digest.algorithm()
+            );
+            Ok(true)
+        }
+        Some(digest) => {
+            println!(
+                "❌ {}: does not match the server's {}",
+                dest.display(),
+                digest.algorithm()
+            );
+            Ok(false)
+        }
+        None => {
+            println!(
+                "⚠️ {}: the server gives no digest; only the size was compared",
+                dest.display()
+            );
+            Ok(true)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn sha256_is_read_in_hex_or_base64_and_preferred_over_md5() {
+        let hex = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+        let base64 = "n4bQgYhMfWWaL+qgxVrQFaO/TxsrC4Is0V1sFbDwCgg=";
+        let md5 = "CY9rzUYh03PK3k6DJie09g==";
+        let read = |pairs: &[(&'static str, &str)]| Digest::from_headers(&headers(pairs)).unwrap();
+
+        let expected = Some(Digest::Sha256(decode(hex, 32).unwrap()));
+        assert_eq!(read(&[(SHA256_HEADER, hex), (MD5_HEADER, md5)]), expected);
+        assert_eq!(read(&[(SHA256_HEADER, base64)]), expected);
+        assert!(matches!(read(&[(MD5_HEADER, md5)]), Some(Digest::Md5(_))));
+        assert_eq!(read(&[]), None);
+    }
+
+    #[test]
+    fn a_digest_that_does_not_decode_is_an_error() {
+        assert!(Digest::from_headers(&headers(&[(SHA256_HEADER, "abc")])).is_err());
+        assert!(Digest::from_headers(&headers(&[(MD5_HEADER, "not base64!")])).is_err());
+    }
+
+    #[test]
+    fn names_that_would_leave_the_directory_are_refused() {
+        let dir = Path::new("out");
+        assert_eq!(destination(dir, "pass.iq").unwrap(), dir.join("pass.iq"));
+        for name in ["", "..", "../pass.iq", "a/b", "a\\b", ".hidden"] {
+            assert!(destination(dir, name).is_err(), "{:?}", name);
+        }
+    }
+}
@@ -0,0 +1,369 @@
+//! Drafts of `jobs add`: the answers of the interactive flow, saved after each prompt so a
+//! session that dies halfway can be picked up where it stopped. A draft is a batch file of
+//! one job whose fields are filled in as they are answered, so a finished one also goes in
+//! with `--batch`. Only the job's own fields are kept: nothing of the profile, its token
+//! or its settings ever reaches a draft.
+
+use crate::client::JobRequestDTO;
+use crate::clock;
+use crate::error::{CliError, ResultExt};
+use crate::frequency::Frequency;
+use crate::humanize;
+use crate::paths;
+use crate::storage;
+use crate::transcript::Recorded;
+use crate::tty;
+use chrono::{DateTime, Utc};
+use inquire::Select;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Drafts older than this are not offered, unless the profile's `draft_max_age_hours`
+/// says otherwise
+const DEFAULT_MAX_AGE_HOURS: u64 = 72;
+
+/// A draft on disk: the job's fields answered so far, and when the last was
+#[derive(Debug, Clone)]
+struct Draft {
+    path: PathBuf,
+    job: Map<String, Value>,
+    saved: DateTime<Utc>,
+}
+
+impl Draft {
+    /// A draft of its own for this session, written once the first answer is in
+    fn new() -> Self {
+        Self {
+            path: paths::drafts_dir().join(format!(
+                "{}-{}.json",
+                clock::now().format("%Y%m%dT%H%M%SZ"),
+                std::process::id()
+            )),
+            job: Map::new(),
+            saved: clock::now(),
+        }
+    }
+
+    /// Read a draft file; anything but a batch of one job is not a draft
+    fn load(path: &Path) -> Option<Self> {
+        let contents = std::fs::read_to_string(path).ok()?;
+        let Value::Array(mut entries) = serde_json::from_str(&contents).ok()? else {
+            return None;
+        };
+        let Some(Value::Object(job)) = entries.pop().filter(|_| entries.is_empty()) else {
+            return None;
+        };
+        let saved = std::fs::metadata(path).and_then(|m| m.modified()).ok()?;
+        Some(Self {
+            path: path.to_path_buf(),
+            job,
+            saved: saved.into(),
+        })
+    }
+
+    fn save(&mut self) -> Result<(), CliError> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+        }
+        let batch = Value::Array(vec![Value::Object(self.job.clone())]);
+        let mut contents =
+            serde_json::to_string_pretty(&batch).map_err(|e| CliError::parse("the draft", e))?;
+        contents.push('\n');
+        storage::write_atomic(&self.path, contents)?;
+        self.saved = clock::now();
+        Ok(())
+    }
+
+    /// What the draft is for, the satellite or kind of job and its start when known
+    fn label(&self) -> String {
+        let target = self
+            .job
+            .get("tle")
+            .and_then(|tle| tle.get("tle0"))
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .or_else(|| {
+                let pointing = self.job.get("pointing")?;
+                Some(match pointing.get("name").and_then(Value::as_str) {
+                    Some(name) => name.to_string(),
+                    None => "fixed pointing".to_string(),
+                })
+            })
+            .unwrap_or_else(|| {
+                match self.job.get("job_type").and_then(Value::as_str) {
+                    Some("calibration") => "calibration sweep",
+                    Some("test") => "loopback test",
+                    Some("pointing") => "fixed pointing",
+                    _ => "tracking job",
+                }
+                .to_string()
+            });
+        match self.time("start") {
+            Some(start) => format!("{}, from {}", target, humanize::timestamp(start)),
+            None => target,
+        }
+    }
+
+    fn time(&self, key: &str) -> Option<DateTime<Utc>> {
+        serde_json::from_value(self.job.get(key)?.clone()).ok()
+    }
+
+    /// "ISS (ZARYA), from … · 4 answers · saved 12m ago", for the picker
+    fn describe(&self) -> String {
+        format!(
+            "{} · {} · saved {}",
+            self.label(),
+            match self.job.len() {
+                1 => "1 answer".to_string(),
+                n => format!("{} answers", n),
+            },
+            humanize::since(self.saved, clock::now())
+        )
+    }
+
+    /// One line per answer, in the order the flow asks
+    fn summary(&self) -> Vec<String> {
+        const ORDER: [&str; 12] = [
+            "job_type",
+            "pointing",
+            "start",
+            "end",
+            "tle",
+            "sweep",
+            "rx_frequency",
+            "tx_frequency",
+            "mode",
+            "baud",
+            "polarization",
+            "post_pass_action",
+        ];
+        let known = ORDER
+            .iter()
+            .filter_map(|key| Some((*key, self.job.get(*key)?)));
+        let others = self
+            .job
+            .iter()
+            .filter(|(key, _)| !ORDER.contains(&key.as_str()))
+            .map(|(key, value)| (key.as_str(), value));
+        known
+            .chain(others)
+            .map(|(key, value)| format!("   {}: {}", key, self.show(key, value)))
+            .collect()
+    }
+
+    fn show(&self, key: &str, value: &Value) -> String {
+        match (key, value) {
+            (_, Value::Null) => "station default".to_string(),
+            (_, Value::String(text)) if key != "start" && key != "end" => text.clone(),
+            ("start" | "end", _) => match self.time(key) {
+                Some(at) => humanize::timestamp(at),
+                None => value.to_string(),
+            },
+            ("tle", _) => value
+                .get("tle0")
+                .and_then(Value::as_str)
+                .unwrap_or("entered")
+                .to_string(),
+            ("pointing", _) => format!(
+                "az {}°, el {}°",
+                value.get("azimuth").unwrap_or(&Value::Null),
+                value.get("elevation").unwrap_or(&Value::Null)
+            ),
+            ("rx_frequency" | "tx_frequency", _) => {
+                match serde_json::from_value::<Frequency>(value.clone()) {
+                    Ok(hz) => hz.to_string(),
+                    Err(_) => value.to_string(),
+                }
+            }
+            _ => value.to_string(),
+        }
+    }
+}
+
+/// The draft of this session; none when nobody is at the terminal to resume it
+static CURRENT: Mutex<Option<Draft>> = Mutex::new(None);
+
+fn current() -> std::sync::MutexGuard<'static, Option<Draft>> {
+    CURRENT.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Drafts saved within `max_age_hours`, the most recent first; unreadable files are skipped
+fn recent(max_age_hours: Option<u64>) -> Vec<Draft> {
+    // Capped well inside what chrono can hold; a century is no limit at all
+    let max_age =
+        chrono::Duration::hours(max_age_hours.unwrap_or(DEFAULT_MAX_AGE_HOURS).min(876_000) as i64);
+    let Ok(entries) = std::fs::read_dir(paths::drafts_dir()) else {
+        return Vec::new();
+    };
+    let mut drafts: Vec<Draft> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| Draft::load(&path))
+        .filter(|draft| clock::now() - draft.saved <= max_age)
+        .collect();
+    drafts.sort_by_key(|draft| std::cmp::Reverse(draft.saved));
+    drafts
+}
+
+/// What to do with a draft found at the start of `jobs add`
+#[derive(Clone, Copy)]
+enum Choice {
+    Resume,
+    Discard,
+    Keep,
+}
+
+impl std::fmt::Display for Choice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            Choice::Resume => "▶️ Resume it",
+            Choice::Discard => "🗑️ Discard it and start afresh",
+            Choice::Keep => "📝 Keep it for later and start a new job",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// Start the draft of an interactive `jobs add`. When `offer` is set and recent drafts are
+/// on disk the operator picks one, sees what it holds and resumes or discards it; whatever
+/// is not resumed starts a new draft. Nothing is kept where nobody is at the terminal.
+pub fn begin(offer: bool, max_age_hours: Option<u64>) -> Result<(), CliError> {
+    if !tty::interactive() {
+        return Ok(());
+    }
+    let drafts = match offer {
+        true => recent(max_age_hours),
+        false => Vec::new(),
+    };
+    let picked = match drafts.len() {
+        0 => None,
+        1 => drafts.into_iter().next(),
+        _ => {
+            let message = "📝 Unfinished jobs:";
+            let mut labels: Vec<String> = drafts.iter().map(Draft::describe).collect();
+            labels.push("➕ Start a new job".to_string());
+            let picked = Select::new(message, labels)
+                .raw_prompt()
+                .recorded(message)?;
+            drafts.into_iter().nth(picked.index)
+        }
+    };
+    let draft = match picked {
+        None => Draft::new(),
+        Some(draft) => {
+            println!(
+                "📝 Unfinished job saved {}: {}",
+                humanize::since(draft.saved, clock::now()),
+                draft.label()
+            );
+            for line in draft.summary() {
+                println!("{}", line);
+            }
+            let message = "What about this draft?";
+            let choice = Select::new(message, vec![Choice::Resume, Choice::Discard, Choice::Keep])
+                .prompt()
+                .recorded(message)?;
+            match choice {
+                Choice::Resume => {
+                    println!("▶️ Resuming; the questions answered are skipped\n");
+                    draft
+                }
+                Choice::Discard => {
+                    remove(&draft.path);
+                    Draft::new()
+                }
+                Choice::Keep => Draft::new(),
+            }
+        }
+    };
+    *current() = Some(draft);
+    Ok(())
+}
+
+/// The draft's answer for `key`, when it holds one of the right shape
+pub fn get<T: DeserializeOwned>(key: &str) -> Option<T> {
+    let current = current();
+    let value = current.as_ref()?.job.get(key)?.clone();
+    serde_json::from_value(value).ok()
+}
+
+/// Change the draft with `change` and write it at once. A draft that cannot be written
+/// is given up with a warning; the job being entered goes on.
+fn update(change: impl FnOnce(&mut Map<String, Value>)) {
+    let mut current = current();
+    let Some(draft) = current.as_mut() else {
+        return;
+    };
+    change(&mut draft.job);
+    if let Err(e) = draft.save() {
+        eprintln!("⚠️ Not keeping a draft: {}", e.full_message());
+        *current = None;
+    }
+}
+
+/// Keep `value` as the draft's `key`
+pub fn note(key: &str, value: &impl Serialize) {
+    if let Ok(value) = serde_json::to_value(value) {
+        update(|job| {
+            job.insert(key.to_string(), value);
+        });
+    }
+}
+
+/// The draft's answer for `key`, or else `ask`'s, which is then kept
+pub fn resumed<T: Serialize + DeserializeOwned>(
+    key: &str,
+    ask: impl FnOnce() -> Result<T, CliError>,
+) -> Result<T, CliError> {
+    if let Some(value) = get(key) {
+        return Ok(value);
+    }
+    let value = ask()?;
+    note(key, &value);
+    Ok(value)
+}
+
+/// Replace the draft with the whole job, once every question is answered
+pub fn complete(job: &JobRequestDTO) {
+    if let Ok(Value::Object(fields)) = serde_json::to_value(job) {
+        update(|job| *job = fields);
+    }
+}
+
+/// The job was submitted, or its submission given up: the draft goes
+pub fn discard() {
+    if let Some(draft) = current().take() {
+        remove(&draft.path);
+    }
+}
+
+/// The flow stopped at `error`. Escape or Ctrl-C at a prompt gave the job up, so the draft
+/// goes; anything else leaves it to resume.
+pub fn interrupted(error: &CliError) {
+    use inquire::InquireError;
+    match error {
+        CliError::Prompt(InquireError::OperationCanceled | InquireError::OperationInterrupted) => {
+            discard()
+        }
+        _ => {
+            if let Some(draft) = current().take().filter(|draft| draft.path.exists()) {
+                eprintln!(
+                    "📝 The answers so far are kept in {}; jobs add offers to resume them",
+                    draft.path.display()
+                );
+            }
+        }
+    }
+}
+
+fn remove(path: &Path) {
+    if let Err(e) = std::fs::remove_file(path) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            eprintln!("⚠️ Could not remove the draft {}: {}", path.display(), e);
+        }
+    }
+}
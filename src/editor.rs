@@ -0,0 +1,225 @@
+use crate::batch;
+use crate::client::{JobRequestDTO, JobType};
+use crate::clock;
+use crate::error::{CliError, ResultExt};
+use crate::history::{self, Prompt};
+use crate::input_schema;
+use crate::job_request::JobRequestBuilder;
+use crate::regulatory::TxLimits;
+use crate::reproducible;
+use crate::validation::Problem;
+use chrono::{Duration, DurationRound};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Lines the CLI puts in the document to say what is wrong with it; replaced on each round
+const PROBLEM_MARK: &str = "# ! ";
+
+/// How far ahead the default job starts, and how long it lasts
+const DEFAULT_LEAD_MINUTES: i64 = 60;
+const DEFAULT_LENGTH_MINUTES: i64 = 15;
+
+/// The temporary document, removed however the session ends
+struct Document {
+    path: PathBuf,
+}
+
+impl Document {
+    fn create(contents: &str) -> Result<Self, CliError> {
+        let path = std::env::temp_dir().join(format!("rustar-cli-job-{}.json", std::process::id()));
+        std::fs::write(&path, contents).with_context(|| format!("writing {}", path.display()))?;
+        Ok(Self { path })
+    }
+
+    fn read(&self) -> Result<String, CliError> {
+        std::fs::read_to_string(&self.path)
+            .with_context(|| format!("reading {}", self.path.display()))
+    }
+
+    fn write(&self, contents: &str) -> Result<(), CliError> {
+        std::fs::write(&self.path, contents)
+            .with_context(|| format!("writing {}", self.path.display()))
+    }
+}
+
+impl Drop for Document {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// `$VISUAL`, else `$EDITOR`, split into the program and its arguments
+fn editor() -> Result<Vec<String>, CliError> {
+    let (name, command) = ["VISUAL", "EDITOR"]
+        .into_iter()
+        .find_map(|name| {
+            std::env::var(name)
+                .ok()
+                .filter(|value| !value.trim().is_empty())
+                .map(|value| (name, value))
+        })
+        .ok_or_else(|| {
+            CliError::Configuration(
+                "jobs add --edit opens $VISUAL or $EDITOR, and neither is set".to_string(),
+            )
+        })?;
+    crate::alias::words(&command)
+        .map_err(|reason| CliError::Configuration(format!("${}: {}", name, reason)))
+}
+
+/// The document with its comment lines blanked, so a parse error keeps its line number
+fn uncommented(text: &str) -> String {
+    text.lines()
+        .map(|line| match line.trim_start().starts_with('#') {
+            true => "",
+            false => line,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A job file read as the document is: one JSON object, `#` lines ignored. A batch file
+/// holding a single job is taken as that job.
+pub fn read_template(path: &Path) -> Result<Value, CliError> {
+    let text =
+        std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    let value: Value = serde_json::from_str(&uncommented(&text))
+        .map_err(|e| CliError::parse(format!("template {}", path.display()), e))?;
+    match value {
+        Value::Object(_) => Ok(value),
+        Value::Array(mut entries) if entries.len() == 1 => Ok(entries.remove(0)),
+        _ => Err(CliError::validation(
+            "template",
+            format!(
+                "{} is not a single job (the layout is printed by rustar-cli schema job)",
+                path.display()
+            ),
+        )),
+    }
+}
+
+/// The job the document starts from when there is no template: a tracking job an hour
+/// out, with the answers last given to the prompts, or the prompts' own defaults
+pub fn default_job() -> Value {
+    let start = (clock::now() + Duration::minutes(DEFAULT_LEAD_MINUTES))
+        .duration_trunc(Duration::minutes(1))
+        .unwrap_or_else(|_| clock::now());
+    let end = start + Duration::minutes(DEFAULT_LENGTH_MINUTES);
+    let frequency = |prompt: Prompt, default: u64| match history::last(prompt) {
+        Some(last) => Value::from(last),
+        None => Value::from(default),
+    };
+    json!({
+        "job_type": "tracking",
+        "start": start.to_rfc3339(),
+        "end": end.to_rfc3339(),
+        "tle": {
+            "tle0": history::last(Prompt::SatelliteName).unwrap_or_default(),
+            "tle1": "",
+            "tle2": "",
+        },
+        "rx_frequency": frequency(Prompt::RxFrequency, 145_800_000),
+        "tx_frequency": frequency(Prompt::TxFrequency, 437_500_000),
+    })
+}
+
+/// What the document says about itself, above the job
+fn header() -> String {
+    let fields: Vec<String> = input_schema::job_fields().into_iter().collect();
+    [
+        "A job for rustar-cli jobs add, written as one entry of a --batch file.".to_string(),
+        "Lines starting with # are ignored; rustar-cli schema job prints the layout.".to_string(),
+        "job_type is tracking (with a tle), calibration (with a sweep) or test.".to_string(),
+        format!("Fields: {}.", fields.join(", ")),
+        "Save an empty file, or quit the editor with an error, to submit nothing.".to_string(),
+    ]
+    .iter()
+    .map(|line| format!("# {}\n", line))
+    .collect()
+}
+
+/// The document with `problems` above it in place of the ones from the round before
+fn with_problems(text: &str, problems: &[Problem]) -> String {
+    let mut marked = format!(
+        "{}The job is not valid yet; fix it and save again:\n",
+        PROBLEM_MARK
+    );
+    for problem in problems {
+        marked.push_str(&format!(
+            "{}  {}: {}\n",
+            PROBLEM_MARK, problem.field, problem.reason
+        ));
+    }
+    for line in text.lines().filter(|line| !line.starts_with(PROBLEM_MARK)) {
+        marked.push_str(line);
+        marked.push('\n');
+    }
+    marked
+}
+
+/// Read the document as jobs add --batch reads one entry: a parse failure or any
+/// pre-flight problem sends it back to the editor
+fn check(text: &str, limits: &TxLimits) -> Result<JobRequestDTO, Vec<Problem>> {
+    let value: Value =
+        serde_json::from_str(text).map_err(|e| vec![Problem::new("document", e.to_string())])?;
+    let entry = batch::preflight(vec![value.clone()], limits).remove(0);
+    if !entry.problems.is_empty() {
+        return Err(entry.problems);
+    }
+    // The entry's own job is already snapped; the submission says how it moves
+    serde_json::from_value(value).map_err(|e| vec![Problem::new("entry", e.to_string())])
+}
+
+/// Keep the edited job's answers for the prompts, and for the next document
+fn remember(job: &JobRequestDTO) {
+    if let JobType::Tracking { tle } = &job.job_type {
+        history::record(Prompt::SatelliteName, &tle.tle0);
+    }
+    history::record(Prompt::RxFrequency, &job.rx_frequency.to_string());
+    history::record(Prompt::TxFrequency, &job.tx_frequency.to_string());
+}
+
+/// `jobs add --edit`: open `base` in the editor until it saves a valid job. None when
+/// the editor fails or the document is saved empty; nothing is submitted then.
+pub fn job(base: &Value, limits: &TxLimits) -> Result<Option<JobRequestBuilder>, CliError> {
+    let editor = editor()?;
+    let body =
+        serde_json::to_string_pretty(base).map_err(|e| CliError::parse("the job document", e))?;
+    let document = Document::create(&format!("{}{}\n", header(), body))?;
+    loop {
+        let status = Command::new(&editor[0])
+            .args(&editor[1..])
+            .arg(&document.path)
+            .envs(reproducible::child_env().iter().copied())
+            .status()
+            .with_context(|| format!("starting the editor {}", editor[0]))?;
+        if !status.success() {
+            let reason = match status.code() {
+                Some(code) => format!("the editor exited with status {}", code),
+                None => "the editor was stopped by a signal".to_string(),
+            };
+            println!("🛑 Job not submitted: {}", reason);
+            return Ok(None);
+        }
+        let text = document.read()?;
+        let job = uncommented(&text);
+        if job.trim().is_empty() {
+            println!("🛑 Job not submitted: the document was saved empty");
+            return Ok(None);
+        }
+        match check(&job, limits) {
+            Ok(job) => {
+                remember(&job);
+                return Ok(Some(JobRequestBuilder::from(job)));
+            }
+            Err(problems) => {
+                eprintln!("⚠️ The job is not valid yet; reopening the editor:");
+                for problem in &problems {
+                    eprintln!("   {}: {}", problem.field, problem.reason);
+                }
+                document.write(&with_problems(&text, &problems))?;
+            }
+        }
+    }
+}
@@ -1,28 +1,492 @@
-use std::fmt;
+use crate::findings::Finding;
+use crate::transcript;
+use crate::validation::{self, Rule};
+use thiserror::Error;
 
-#[derive(Debug)]
+type BoxedError = Box<dyn std::error::Error + Send + Sync>;
+
+/// The command did what was asked
+pub const EXIT_SUCCESS: i32 = 0;
+/// An error was reported, some batch entries failed, or jobs diff found differences
+pub const EXIT_FAILURE: i32 = 1;
+/// The command line itself was rejected (clap's own exit code)
+pub const EXIT_USAGE: i32 = 2;
+
+/// `jobs next` found no job to report
+pub const EXIT_NO_JOB: i32 = 3;
+/// `jobs list --partial-ok` printed a list the server cut short
+pub const EXIT_PARTIAL: i32 = 4;
+/// `jobs audit-tles` left jobs whose elements will be too old at their pass
+pub const EXIT_STALE_TLE: i32 = 5;
+/// `receipts verify` found a receipt missing, changed or out of order
+pub const EXIT_BROKEN_CHAIN: i32 = 6;
+
+/// Exit status contract, as documented in the generated reference
+pub const EXIT_CODES: &[(i32, &str)] = &[
+    (EXIT_SUCCESS, "success"),
+    (
+        EXIT_FAILURE,
+        "an error was reported, a batch or import had failed entries, or jobs diff found differences",
+    ),
+    (
+        EXIT_USAGE,
+        "invalid command line: unknown flag, missing argument, bad value, prompts without a terminal, \
+         or a submission with warnings under --strict",
+    ),
+    (
+        EXIT_NO_JOB,
+        "jobs next found no future job (within --within, when given)",
+    ),
+    (
+        EXIT_PARTIAL,
+        "jobs list --partial-ok printed only part of the list: the server stopped sending it",
+    ),
+    (
+        EXIT_STALE_TLE,
+        "jobs audit-tles found jobs whose elements will be older than --max-age-at-pass at \
+         their start, and did not refresh them all",
+    ),
+    (
+        EXIT_BROKEN_CHAIN,
+        "receipts verify found the receipt chain broken: a receipt missing, changed or out of order",
+    ),
+];
+
+#[derive(Debug, Error)]
 pub enum CliError {
-    HttpError(reqwest::Error),
-    ConfigurationError(String),
+    /// Transport-level failure: DNS, connection, TLS, body decoding
+    #[error("HTTP request failed")]
+    Http {
+        #[source]
+        source: reqwest::Error,
+        hint: Option<String>,
+    },
+
+    /// The server answered with a non-success status
+    #[error("API returned {status}{}", fmt_api_detail(.message, .request_id))]
+    Api {
+        status: reqwest::StatusCode,
+        message: Option<String>,
+        request_id: Option<String>,
+    },
+
+    /// A response body that could not be deserialized
+    #[error(
+        "unexpected response body (status {status}, content-type {}): {snippet}",
+        .content_type.as_deref().unwrap_or("unknown")
+    )]
+    Decode {
+        status: reqwest::StatusCode,
+        content_type: Option<String>,
+        snippet: String,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    #[error("invalid {field}: {reason}")]
+    Validation {
+        field: String,
+        reason: String,
+        /// Limits the value breaks, for `--explain`
+        rules: Vec<Rule>,
+    },
+
+    #[error("Configuration error: {0}")]
+    Configuration(String),
+
+    #[error("I/O error")]
+    Io(#[from] std::io::Error),
+
+    #[error("could not parse {what}")]
+    Parse {
+        what: String,
+        #[source]
+        source: BoxedError,
+    },
+
+    #[error("request timed out")]
+    Timeout {
+        #[source]
+        source: reqwest::Error,
+        hint: Option<String>,
+    },
+
+    #[error("unauthorized{}", fmt_message(.message))]
+    Unauthorized { message: Option<String> },
+
+    #[error("not found{}", fmt_message(.message))]
+    NotFound { message: Option<String> },
+
+    /// The server held it once and has purged it for good
+    #[error("gone{}", fmt_message(.message))]
+    Gone { message: Option<String> },
+
+    /// The request clashes with what the server already holds
+    #[error("conflict{}", fmt_message(.message))]
+    Conflict {
+        message: Option<String>,
+        hint: Option<String>,
+    },
+
+    /// A change made against a version of the job the server no longer holds
+    #[error("the job changed on the server since it was fetched{}", fmt_message(.message))]
+    Stale { message: Option<String> },
+
+    /// The server does not offer the requested feature
+    #[error("{0}")]
+    Unsupported(String),
+
+    /// A post-submit hook that ran but did not succeed
+    #[error("{0}")]
+    Hook(String),
+
+    #[error("prompt failed")]
+    Prompt(#[from] inquire::InquireError),
+
+    /// An interactive flow started where nobody can answer its prompts
+    #[error("{command} asks questions, but stdin or stderr is not a terminal")]
+    NoTerminal {
+        command: String,
+        /// Flags that make the same command run without prompting
+        instead: Option<String>,
+    },
+
+    /// A changing command on a terminal set up only to browse
+    #[error("{command} changes the schedule or this CLI's records, and this terminal is read-only ({reason})")]
+    ReadOnly { command: String, reason: String },
+
+    /// Advisory findings that `--strict` holds a submission to, every one of them. Warnings
+    /// the server sent with a job it accepted fail the command after the job is scheduled.
+    #[error(
+        "{} at {} warning{}",
+        if *.scheduled { "the job is scheduled, but --strict fails the command" } else { "--strict stops the submission" },
+        .findings.len(),
+        if .findings.len() == 1 { "" } else { "s" }
+    )]
+    Strict {
+        findings: Vec<Finding>,
+        scheduled: bool,
+    },
+
+    /// `--tle-offline` kept a command from fetching elements the cache could not give
+    #[error("--tle-offline is set, so the elements for {what} cannot be fetched")]
+    Offline { what: String },
+
+    /// A download of elements shared with another fetch of the same URL, which failed
+    #[error("fetching {url} failed: {message}")]
+    Coalesced { url: String, message: String },
+
+    /// A job the server accepted to create later and had not created by the deadline
+    #[error(
+        "the server accepted the job but had not created it after {}s; it can be followed at {location}",
+        .waited.as_secs()
+    )]
+    Pending {
+        location: String,
+        waited: std::time::Duration,
+    },
+
+    /// A download whose bytes do not hash to the digest the server sent with them
+    #[error("{} does not match the server's {algorithm} digest", .path.display())]
+    Checksum {
+        path: std::path::PathBuf,
+        algorithm: &'static str,
+    },
+
+    /// A download broken off part way, with the bytes that arrived kept in `path`
+    #[error(
+        "the download stopped after {saved} bytes{}; they are kept in {}",
+        .total.map(|total| format!(" of {}", total)).unwrap_or_default(),
+        .path.display()
+    )]
+    Interrupted {
+        path: std::path::PathBuf,
+        saved: u64,
+        total: Option<u64>,
+        /// The server sent an ETag, so the next attempt can ask for only the rest
+        resumable: bool,
+        #[source]
+        source: Box<CliError>,
+    },
+
+    /// Another CLI process kept a state file locked for longer than we wait
+    #[error("another rustar process is holding the lock on {}", .path.display())]
+    Locked { path: std::path::PathBuf },
+
+    /// An error annotated with what the CLI was doing when it happened
+    #[error("{context}")]
+    Context {
+        context: String,
+        #[source]
+        source: Box<CliError>,
+    },
 }
 
-impl fmt::Display for CliError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl CliError {
+    pub fn validation(field: impl Into<String>, reason: impl Into<String>) -> Self {
+        CliError::Validation {
+            field: field.into(),
+            reason: reason.into(),
+            rules: Vec::new(),
+        }
+    }
+
+    /// Wrap a reqwest error, keeping timeouts distinct from other transport failures
+    pub fn transport(source: reqwest::Error, hint: Option<String>) -> Self {
+        if source.is_timeout() {
+            CliError::Timeout { source, hint }
+        } else {
+            CliError::Http { source, hint }
+        }
+    }
+
+    /// The operator hint attached to this error or the one it wraps
+    pub fn hint(&self) -> Option<&str> {
         match self {
-            CliError::HttpError(e) => {
-                write!(f, "HTTP request failed: {}", e)
+            CliError::Http { hint, .. }
+            | CliError::Timeout { hint, .. }
+            | CliError::Conflict { hint, .. } => hint.as_deref(),
+            CliError::Pending { .. } => Some(
+                "the server may still create it; look for it with jobs list before submitting it again",
+            ),
+            CliError::Checksum { .. } => {
+                Some("the damaged download was deleted; run the command again to fetch it afresh")
+            }
+            CliError::Interrupted {
+                resumable: true, ..
+            } => Some("run the same command again to fetch only the rest"),
+            CliError::Interrupted { .. } => Some(
+                "the server sent no ETag to resume by, so the next attempt fetches the whole file",
+            ),
+            CliError::Locked { .. } => {
+                Some("wait for the other rustar command to finish, then try again")
             }
-            CliError::ConfigurationError(msg) => {
-                write!(f, "Configuration error: {}", msg)
+            CliError::ReadOnly { .. } => Some(
+                "listing, predicting and exporting work here; make changes from an operator terminal",
+            ),
+            CliError::Unauthorized { .. } => Some(
+                "this CLI sends no credentials; check that the profile points at the right server",
+            ),
+            CliError::NoTerminal { instead, .. } => instead.as_deref(),
+            CliError::Offline { .. } => Some(
+                "run it without --tle-offline, or fill the cache first with tle refresh-all",
+            ),
+            CliError::Strict {
+                scheduled: true, ..
+            } => Some(
+                "the job stays on the schedule; cancel it with jobs cancel if a warning matters",
+            ),
+            CliError::Strict { .. } => {
+                Some("resolve each one, or leave out --strict (and the profile's strict) to go ahead")
             }
+            CliError::Stale { .. } => Some(
+                "fetch the job again and redo the change, or choose with --on-conflict abort, theirs or mine",
+            ),
+            CliError::Context { source, .. } => source.hint(),
+            _ => None,
         }
     }
-}
 
-impl std::error::Error for CliError {}
+    /// The limits a validation failure breaks, wherever it sits in the chain
+    pub fn rules(&self) -> &[Rule] {
+        match self {
+            CliError::Validation { rules, .. } => rules,
+            CliError::Context { source, .. } => source.rules(),
+            _ => &[],
+        }
+    }
+
+    /// The findings `--strict` refused, wherever they sit in the chain
+    pub fn findings(&self) -> &[Finding] {
+        match self {
+            CliError::Strict { findings, .. } => findings,
+            CliError::Context { source, .. } => source.findings(),
+            _ => &[],
+        }
+    }
+
+    /// Whether the request never got a response (as opposed to being rejected)
+    pub fn is_transport(&self) -> bool {
+        match self {
+            CliError::Http { .. } | CliError::Timeout { .. } => true,
+            CliError::Context { source, .. } => source.is_transport(),
+            _ => false,
+        }
+    }
+
+    /// Whether the server refused a change for being made against an older version of the job
+    pub fn is_stale(&self) -> bool {
+        match self {
+            CliError::Stale { .. } => true,
+            CliError::Context { source, .. } => source.is_stale(),
+            _ => false,
+        }
+    }
+
+    /// Whether a request timed out after it was sent, so the server may have acted on it
+    /// without the answer getting back
+    pub fn unanswered(&self) -> bool {
+        match self {
+            CliError::Timeout { source, .. } => !source.is_connect(),
+            CliError::Context { source, .. } => source.unanswered(),
+            _ => false,
+        }
+    }
+
+    /// Short machine-readable name of the innermost error kind
+    pub fn kind(&self) -> &'static str {
+        match self {
+            CliError::Http { .. } => "http",
+            CliError::Api { .. } => "api",
+            CliError::Decode { .. } => "decode",
+            CliError::Validation { .. } => "validation",
+            CliError::Configuration(_) => "configuration",
+            CliError::Io(_) => "io",
+            CliError::Parse { .. } => "parse",
+            CliError::Timeout { .. } => "timeout",
+            CliError::Unauthorized { .. } => "unauthorized",
+            CliError::NotFound { .. } => "not_found",
+            CliError::Gone { .. } => "gone",
+            CliError::Conflict { .. } => "conflict",
+            CliError::Stale { .. } => "stale",
+            CliError::Unsupported(_) => "unsupported",
+            CliError::Hook(_) => "hook",
+            CliError::Prompt(_) => "prompt",
+            CliError::NoTerminal { .. } => "no_terminal",
+            CliError::ReadOnly { .. } => "read_only",
+            CliError::Strict { .. } => "strict",
+            CliError::Offline { .. } => "offline",
+            CliError::Coalesced { .. } => "http",
+            CliError::Pending { .. } => "pending",
+            CliError::Checksum { .. } => "checksum",
+            CliError::Interrupted { .. } => "interrupted",
+            CliError::Locked { .. } => "locked",
+            CliError::Context { source, .. } => source.kind(),
+        }
+    }
+
+    /// Process exit status for this error: a usage error when it needed a terminal
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CliError::NoTerminal { .. } | CliError::Strict { .. } => EXIT_USAGE,
+            CliError::Context { source, .. } => source.exit_code(),
+            _ => EXIT_FAILURE,
+        }
+    }
+
+    /// HTTP status that caused this error, if the server answered at all
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            CliError::Api { status, .. } | CliError::Decode { status, .. } => Some(status.as_u16()),
+            CliError::Unauthorized { .. } => Some(401),
+            CliError::NotFound { .. } => Some(404),
+            CliError::Gone { .. } => Some(410),
+            CliError::Conflict { .. } => Some(409),
+            CliError::Stale { .. } => Some(412),
+            CliError::Context { source, .. } => source.status(),
+            _ => None,
+        }
+    }
+
+    /// The whole chain on one line, for reports and logs
+    pub fn full_message(&self) -> String {
+        let mut message = self.to_string();
+        let mut source = std::error::Error::source(self);
+        while let Some(cause) = source {
+            message.push_str(": ");
+            message.push_str(&cause.to_string());
+            source = cause.source();
+        }
+        message
+    }
+
+    /// Attach an operator hint to a conflict, wherever it sits in the chain
+    pub fn with_conflict_hint(self, hint: impl Into<String>) -> Self {
+        match self {
+            CliError::Conflict { message, .. } => CliError::Conflict {
+                message,
+                hint: Some(hint.into()),
+            },
+            CliError::Context { context, source } => CliError::Context {
+                context,
+                source: Box::new(source.with_conflict_hint(hint)),
+            },
+            other => other,
+        }
+    }
+
+    pub fn parse(what: impl Into<String>, source: impl Into<BoxedError>) -> Self {
+        CliError::Parse {
+            what: what.into(),
+            source: source.into(),
+        }
+    }
+}
 
 impl From<reqwest::Error> for CliError {
     fn from(error: reqwest::Error) -> Self {
-        CliError::HttpError(error)
+        CliError::transport(error, None)
+    }
+}
+
+/// Attach human context to any error convertible into `CliError`
+pub trait ResultExt<T> {
+    fn context(self, context: impl Into<String>) -> Result<T, CliError>;
+
+    fn with_context<C: Into<String>>(self, f: impl FnOnce() -> C) -> Result<T, CliError>;
+}
+
+impl<T, E: Into<CliError>> ResultExt<T> for Result<T, E> {
+    fn context(self, context: impl Into<String>) -> Result<T, CliError> {
+        self.with_context(|| context)
+    }
+
+    fn with_context<C: Into<String>>(self, f: impl FnOnce() -> C) -> Result<T, CliError> {
+        self.map_err(|e| CliError::Context {
+            context: f().into(),
+            source: Box::new(e.into()),
+        })
+    }
+}
+
+fn fmt_message(message: &Option<String>) -> String {
+    message
+        .as_deref()
+        .map(|m| format!(": {}", m))
+        .unwrap_or_default()
+}
+
+fn fmt_api_detail(message: &Option<String>, request_id: &Option<String>) -> String {
+    let mut detail = fmt_message(message);
+    if let Some(id) = request_id {
+        detail.push_str(&format!(" (request id {})", id));
+    }
+    detail
+}
+
+/// Print an error followed by its source chain, one indented line per cause
+pub fn report(prefix: &str, error: &CliError) {
+    eprintln!("❌ {}: {}", prefix, error);
+    transcript::error(&format!("{}: {}", prefix, error.full_message()));
+    for finding in error.findings() {
+        eprintln!("   ✖ {}: {}", finding.check, finding);
+        transcript::error(&format!("{}: {}", finding.check, finding));
+    }
+
+    let mut depth = 1;
+    let mut source = std::error::Error::source(error);
+    while let Some(cause) = source {
+        eprintln!("{}↳ {}", "   ".repeat(depth), cause);
+        depth += 1;
+        source = cause.source();
+    }
+
+    if let Some(hint) = error.hint() {
+        eprintln!("💡 {}", hint);
+    }
+    if validation::explaining() {
+        for rule in error.rules() {
+            eprintln!("📐 {}", rule);
+        }
     }
 }
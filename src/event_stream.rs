@@ -0,0 +1,65 @@
+use std::collections::VecDeque;
+
+/// One server-sent event: its `event:` name, "message" when it has none, and its `data:`
+/// lines joined by newlines
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    pub name: String,
+    pub data: String,
+}
+
+/// Cuts a `text/event-stream` body into events as bytes arrive. Comments, `id:` and
+/// `retry:` lines are skipped; an event without data is not dispatched, as the format says.
+#[derive(Debug, Default)]
+pub struct EventSplitter {
+    /// Bytes of the line being read
+    line: Vec<u8>,
+    name: Option<String>,
+    data: Vec<String>,
+    ready: VecDeque<Event>,
+}
+
+impl EventSplitter {
+    /// Feed the next chunk of the body
+    pub fn push(&mut self, chunk: &[u8]) {
+        for &byte in chunk {
+            match byte {
+                b'\n' => {
+                    let line = String::from_utf8_lossy(&self.line).into_owned();
+                    self.line.clear();
+                    self.field(line.strip_suffix('\r').unwrap_or(&line));
+                }
+                byte => self.line.push(byte),
+            }
+        }
+    }
+
+    /// One complete line: a field of the event being read, or the blank line ending it
+    fn field(&mut self, line: &str) {
+        if line.is_empty() {
+            let name = self.name.take();
+            if !self.data.is_empty() {
+                self.ready.push_back(Event {
+                    name: name.unwrap_or_else(|| "message".to_string()),
+                    data: std::mem::take(&mut self.data).join("\n"),
+                });
+            }
+            return;
+        }
+        if line.starts_with(':') {
+            return;
+        }
+        let (field, value) = line.split_once(':').unwrap_or((line, ""));
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "event" => self.name = Some(value.to_string()),
+            "data" => self.data.push(value.to_string()),
+            _ => {}
+        }
+    }
+
+    /// The next complete event, if one has arrived
+    pub fn pop(&mut self) -> Option<Event> {
+        self.ready.pop_front()
+    }
+}
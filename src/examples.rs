@@ -0,0 +1,205 @@
+use crate::batch;
+use crate::error::CliError;
+use crate::frequency;
+use crate::parse;
+use crate::tle;
+use crate::OutputFormat;
+use clap::ValueEnum;
+use serde::Serialize;
+
+/// A runnable command line and what it shows
+#[derive(Debug, Serialize)]
+pub struct Example {
+    pub note: &'static str,
+    /// The value the example is about, exactly as it appears in `command`; checked
+    /// against the topic's parser. None when the command line itself is the point.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input: Option<&'static str>,
+    pub command: &'static str,
+}
+
+/// What `rustar-cli examples` can show
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Topic {
+    /// Dates and times: UTC wall clock, offsets, now+DURATION
+    Datetimes,
+    /// Window lengths, margins and ages: 90s, 1h30m, 30d
+    Durations,
+    /// Frequencies in hertz or with a k, M or G suffix
+    Frequencies,
+    /// Files of jobs for jobs add --batch
+    Batch,
+    /// Where element sets come from: catalog numbers, designators, files
+    Tle,
+    /// Scripts reading --output json, csv or ndjson, and exit statuses
+    Scripting,
+}
+
+/// Scripting has no parser of its own to sit beside
+const SCRIPTING: &[Example] = &[
+    Example {
+        note: "Jobs as a JSON array, for jq",
+        input: None,
+        command: "rustar-cli --output json jobs list | jq -r '.[].id'",
+    },
+    Example {
+        note: "The next job on one line; the exit status is 3 when there is none",
+        input: None,
+        command: "rustar-cli jobs next --within 2h || echo 'nothing soon'",
+    },
+    Example {
+        note: "Counts per day as CSV, for a spreadsheet",
+        input: None,
+        command: "rustar-cli --output csv stats --group-by day",
+    },
+    Example {
+        note: "One JSON object per line while following the broker",
+        input: None,
+        command: "rustar-cli --output ndjson mqtt follow",
+    },
+];
+
+impl Topic {
+    pub fn examples(self) -> &'static [Example] {
+        match self {
+            Topic::Datetimes => parse::DATETIME_EXAMPLES,
+            Topic::Durations => parse::DURATION_EXAMPLES,
+            Topic::Frequencies => frequency::EXAMPLES,
+            Topic::Batch => batch::EXAMPLES,
+            Topic::Tle => tle::EXAMPLES,
+            Topic::Scripting => SCRIPTING,
+        }
+    }
+
+    /// A file the examples refer to, by name and contents
+    pub fn file(self) -> Option<(&'static str, &'static str)> {
+        match self {
+            Topic::Batch => Some(("jobs.json", batch::EXAMPLE_FILE)),
+            Topic::Tle => Some(("iss.tle", tle::EXAMPLE_FILE)),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> String {
+        self.to_possible_value()
+            .map(|value| value.get_name().to_string())
+            .unwrap_or_default()
+    }
+
+    fn about(self) -> String {
+        self.to_possible_value()
+            .and_then(|value| value.get_help().map(|help| help.to_string()))
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Serialize)]
+struct Shown {
+    topic: String,
+    about: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file: Option<File>,
+    examples: &'static [Example],
+}
+
+#[derive(Serialize)]
+struct File {
+    name: &'static str,
+    contents: &'static str,
+}
+
+fn shown(topic: Topic) -> Shown {
+    Shown {
+        topic: topic.name(),
+        about: topic.about(),
+        file: topic.file().map(|(name, contents)| File { name, contents }),
+        examples: topic.examples(),
+    }
+}
+
+/// `examples [TOPIC]`: the examples of one topic, or every topic with what it covers
+pub fn show(topic: Option<Topic>, output: OutputFormat) -> Result<(), CliError> {
+    if output == OutputFormat::Json {
+        let document = match topic {
+            Some(topic) => serde_json::to_value(shown(topic)),
+            None => serde_json::to_value(
+                Topic::value_variants()
+                    .iter()
+                    .map(|topic| shown(*topic))
+                    .collect::<Vec<_>>(),
+            ),
+        }
+        .map_err(|e| CliError::parse("examples", e))?;
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&document).map_err(|e| CliError::parse("examples", e))?
+        );
+        return Ok(());
+    }
+
+    let Some(topic) = topic else {
+        println!("📚 Example topics (rustar-cli examples TOPIC):");
+        let width = Topic::value_variants()
+            .iter()
+            .map(|topic| topic.name().len())
+            .max()
+            .unwrap_or(0);
+        for topic in Topic::value_variants() {
+            println!(
+                "  {:<width$}  {}",
+                topic.name(),
+                topic.about(),
+                width = width
+            );
+        }
+        return Ok(());
+    };
+
+    println!("📚 {}: {}", topic.name(), topic.about());
+    if let Some((name, contents)) = topic.file() {
+        println!("\n  # {}", name);
+        for line in contents.lines() {
+            println!("  {}", line);
+        }
+    }
+    for example in topic.examples() {
+        println!("\n  # {}", example.note);
+        println!("  {}", example.command);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::JobRequestDTO;
+    use crate::input_schema;
+    use crate::job_request::JobRequestBuilder;
+    use crate::regulatory::TxLimits;
+
+    /// Every check jobs add --batch makes of an entry, less the submit window: the
+    /// example's dates are fixed and today is not
+    #[test]
+    fn the_batch_file_is_valid() {
+        let values: Vec<serde_json::Value> =
+            serde_json::from_str(batch::EXAMPLE_FILE).expect("a JSON array");
+        assert!(!values.is_empty());
+        for (index, raw) in values.into_iter().enumerate() {
+            let problems = input_schema::check_job(&raw);
+            assert!(problems.is_empty(), "entry {}: {:?}", index, problems);
+            let job: JobRequestDTO = serde_json::from_value(raw).expect("a job");
+            let problems = JobRequestBuilder::from(job)
+                .tx_limits(&TxLimits::default())
+                .problems();
+            assert!(problems.is_empty(), "entry {}: {:?}", index, problems);
+        }
+    }
+
+    #[test]
+    fn the_element_set_file_is_well_formed() {
+        let tle = tle::parse_text(tle::EXAMPLE_FILE, "iss").expect("an element set");
+        assert_eq!(tle::check_line(&tle.tle1, '1'), None);
+        assert_eq!(tle::check_line(&tle.tle2, '2'), None);
+        assert!(tle::validate(&tle).is_empty());
+    }
+}
@@ -0,0 +1,370 @@
+use crate::audit;
+use crate::base_url;
+use crate::baseline;
+use crate::campaign;
+use crate::capabilities;
+use crate::client::{ApiClient, JobType, StationInfo};
+use crate::config::Resolved;
+use crate::error::{CliError, ResultExt};
+use crate::hooks::HookRunner;
+use crate::humanize;
+use crate::job_request::{JobRequest, JobRequestBuilder};
+use crate::latency;
+use crate::maintenance;
+use crate::orbit::Satellite;
+use crate::predict::{self, Horizon};
+use crate::protect;
+use crate::quiet_hours;
+use crate::rotator;
+use crate::station;
+use crate::submit_window;
+use crate::text;
+use crate::transcript::{self, Recorded};
+use crate::tty;
+use chrono::{DateTime, Duration, SubsecRound, Utc};
+use inquire::Confirm;
+
+/// How far either side of the typed window each station's own pass is looked for
+const PASS_SEARCH_HOURS: i64 = 2;
+
+/// `--profiles north,south` names two or more distinct profiles
+fn check_names(names: &[String]) -> Result<(), CliError> {
+    for (i, name) in names.iter().enumerate() {
+        if name.trim().is_empty() {
+            return Err(CliError::validation("profiles", "a profile name is empty"));
+        }
+        if names[..i].contains(name) {
+            return Err(CliError::validation(
+                "profiles",
+                format!("profile '{}' is named twice", name),
+            ));
+        }
+    }
+    if names.len() < 2 {
+        return Err(CliError::validation(
+            "profiles",
+            "name at least two profiles; --profile picks a single one",
+        ));
+    }
+    Ok(())
+}
+
+/// A profile the job fans out to, with its own client and hooks
+pub struct Station {
+    pub profile: String,
+    pub base_url: String,
+    resolved: Resolved,
+    client: ApiClient,
+    hooks: HookRunner,
+    info: Option<StationInfo>,
+}
+
+impl Station {
+    pub fn client(&self) -> &ApiClient {
+        &self.client
+    }
+}
+
+/// Resolve each named profile with `load` and connect to its station. A read-only
+/// profile refuses the whole fan-out; a protected one is confirmed as the selected
+/// profile would be.
+pub fn open(
+    names: &[String],
+    load: impl Fn(&str) -> Result<Resolved, CliError>,
+    verbose: bool,
+    hooks: bool,
+    confirmed: Option<&str>,
+) -> Result<Vec<Station>, CliError> {
+    check_names(names)?;
+    let mut stations: Vec<Station> = Vec::new();
+    for name in names {
+        let resolved = load(name)?;
+        if resolved.profile.read_only {
+            return Err(CliError::ReadOnly {
+                command: "jobs add".to_string(),
+                reason: format!("profile {} sets read_only", name),
+            });
+        }
+        // Flags and the environment name one server; each station is found by its profile
+        let Some(base_url) = resolved.profile.base_url.clone() else {
+            return Err(CliError::Configuration(format!(
+                "profile {} sets no base_url, so there is no station to send its job to",
+                name
+            )));
+        };
+        // Compared as parsed, so a trailing slash does not hide a duplicate
+        let base_url = base_url::parse(&base_url)
+            .map(|url| base_url::display(&url))
+            .with_context(|| format!("reading the base_url of profile {}", name))?;
+        if let Some(other) = stations.iter().find(|s| s.base_url == base_url) {
+            return Err(CliError::Configuration(format!(
+                "profiles {} and {} both point at {}; the job would be scheduled there twice",
+                other.profile, name, base_url
+            )));
+        }
+        if resolved.profile.protected {
+            protect::check("add-job", name, &base_url, confirmed)?;
+        }
+        let client = crate::api_client(&resolved, &base_url, verbose)
+            .with_context(|| format!("connecting to the station of profile {}", name))?;
+        stations.push(Station {
+            profile: name.clone(),
+            hooks: HookRunner::new(resolved.profile.hooks.clone(), name, hooks),
+            base_url,
+            resolved,
+            client,
+            info: None,
+        });
+    }
+    Ok(stations)
+}
+
+/// Ask every station what it is. A server without station info is left without; any
+/// other failure is a warning, and its submission reports the rest.
+pub async fn survey(stations: &mut [Station]) {
+    for station in stations {
+        match station.client.station_info().await {
+            Ok(info) => station.info = Some(info),
+            Err(e) if e.status() == Some(404) => {}
+            Err(e) => eprintln!(
+                "⚠️ Could not ask the {} station about itself: {}",
+                station.profile,
+                e.full_message()
+            ),
+        }
+    }
+}
+
+/// Polarizations every station that reports them can switch to
+pub fn polarizations(stations: &[Station]) -> Option<Vec<crate::client::Polarization>> {
+    stations
+        .iter()
+        .filter_map(|s| s.info.as_ref().and_then(station::supported_polarizations))
+        .reduce(|common, next| common.into_iter().filter(|p| next.contains(p)).collect())
+}
+
+/// Stations that do not speak the same API version, when any of them says
+fn version_mismatch(stations: &[Station]) -> Option<String> {
+    let versions: Vec<(&str, Option<&str>)> = stations
+        .iter()
+        .map(|s| {
+            let version = s.info.as_ref().and_then(|info| info.api_version.as_deref());
+            (s.profile.as_str(), version)
+        })
+        .collect();
+    let first = versions[0].1;
+    if versions.iter().all(|(_, version)| *version == first) {
+        return None;
+    }
+    let listed: Vec<String> = versions
+        .iter()
+        .map(|(profile, version)| format!("{} {}", profile, version.unwrap_or("unreported")))
+        .collect();
+    Some(format!(
+        "The stations report different API versions ({}); a job one accepts may mean \
+         something else to another",
+        listed.join(", ")
+    ))
+}
+
+/// The window of the job at `station`: for a tracking job at a station whose position
+/// is known, the pass there that overlaps the typed window most; otherwise the typed
+/// window itself. The note says which.
+fn window_at(
+    station: &Station,
+    job: &JobRequestBuilder,
+) -> Result<(DateTime<Utc>, DateTime<Utc>, String), CliError> {
+    let draft = job.clone().into_draft();
+    let JobType::Tracking { tle } = &draft.job_type else {
+        return Ok((draft.start, draft.end, "same window".to_string()));
+    };
+    let Ok(location) = station.resolved.location() else {
+        return Ok((
+            draft.start,
+            draft.end,
+            "same window (the profile gives no station position)".to_string(),
+        ));
+    };
+    let satellite = Satellite::from_tle(tle)?;
+    let horizon = Horizon {
+        station: &location,
+        min_elevation_deg: 0.0,
+        mask: &station.resolved.profile.elevation_mask,
+    };
+    let margin = Duration::hours(PASS_SEARCH_HOURS);
+    let passes = predict::passes(
+        &satellite,
+        &horizon,
+        draft.start - margin,
+        draft.end + margin,
+    )?;
+    let overlap = |(aos, los): &(DateTime<Utc>, DateTime<Utc>)| {
+        (*los).min(draft.end) - (*aos).max(draft.start)
+    };
+    let usable: Vec<(DateTime<Utc>, DateTime<Utc>)> = passes
+        .iter()
+        .filter_map(|pass| pass.aos.zip(pass.los))
+        .collect();
+    // Far from the other stations the pass may come a while before or after
+    let Some((aos, los)) = usable
+        .iter()
+        .copied()
+        .filter(|window| overlap(window) > Duration::zero())
+        .max_by_key(overlap)
+        .or_else(|| {
+            usable
+                .iter()
+                .copied()
+                .min_by_key(|(aos, _)| (*aos - draft.start).abs())
+        })
+    else {
+        return Err(CliError::validation(
+            "window",
+            format!(
+                "{} makes no pass over the {} station within {} of {} – {}",
+                satellite.name,
+                station.profile,
+                humanize::duration(margin),
+                humanize::timestamp(draft.start),
+                draft.end.format("%H:%M:%S UTC")
+            ),
+        ));
+    };
+    let (start, end) = (aos.trunc_subsecs(0), los.trunc_subsecs(0));
+    let note = match start - draft.start {
+        shift if shift.num_seconds() == 0 => "its pass there".to_string(),
+        shift if shift > Duration::zero() => {
+            format!("its pass there, {} later", humanize::duration(shift))
+        }
+        shift => format!("its pass there, {} earlier", humanize::duration(-shift)),
+    };
+    Ok((start, end, note))
+}
+
+/// Every station's job, checked against that station's profile before anything is sent
+fn plan(
+    stations: &[Station],
+    job: &JobRequestBuilder,
+) -> Result<Vec<(JobRequest, String)>, CliError> {
+    let mut planned = Vec::with_capacity(stations.len());
+    for station in stations {
+        let checked = || -> Result<(JobRequest, String), CliError> {
+            let (start, end, note) = window_at(station, job)?;
+            let mut draft = job.clone().into_draft();
+            draft.start = start;
+            draft.end = end;
+            let request = JobRequestBuilder::from(draft)
+                .tx_limits(&station.resolved.profile.tx_limits)
+                .build()?;
+            if let Some(problem) = submit_window::check(request.start) {
+                return Err(problem.into());
+            }
+            Ok((request, note))
+        };
+        planned
+            .push(checked().with_context(|| format!("planning the job for {}", station.profile))?);
+    }
+    quiet_hours::review(&planned.iter().map(|(job, _)| &**job).collect::<Vec<_>>())?;
+    Ok(planned)
+}
+
+/// `jobs add --profiles`: show the job each station gets, then submit them one after
+/// the other. A failure at one station leaves the jobs already made at the others in
+/// place. Returns how many stations failed.
+pub async fn submit(stations: &[Station], job: JobRequestBuilder) -> Result<usize, CliError> {
+    let planned = plan(stations, &job)?;
+    // Each station's rotator is checked as its own profile says, and each scheduler
+    // holds its job to its own capabilities
+    let mut queues = Vec::with_capacity(stations.len());
+    for (station, (job, _)) in stations.iter().zip(&planned) {
+        if let Some(check) = &station.resolved.profile.rotator_check {
+            rotator::review_with(check, &[job])
+                .await
+                .with_context(|| format!("checking the rotator of {}", station.profile))?;
+        }
+        let queue = capabilities::review(&station.client, &[job])
+            .await
+            .with_context(|| format!("planning the job for {}", station.profile))?;
+        queues.push(queue);
+    }
+
+    if let Some(rx) = planned
+        .first()
+        .and_then(|(job, _)| job.describe_rx_offset())
+    {
+        println!("📻 {}", rx);
+    }
+    println!("📋 The job goes to {} stations:", stations.len());
+    for ((station, (job, note)), queue) in stations.iter().zip(&planned).zip(&queues) {
+        println!("\n  {} — {}", station.profile, station.base_url);
+        println!(
+            "    🕐 {} — {} ({})",
+            humanize::timestamp(job.start),
+            humanize::window(job.start, job.end),
+            note
+        );
+        if let Some(version) = station.info.as_ref().and_then(|i| i.api_version.as_ref()) {
+            println!("    🔌 API version {}", version);
+        }
+        if let Some(queue) = queue {
+            println!("    📋 Station {}", queue);
+        }
+        let windows = maintenance::windows(&station.client).await;
+        for clash in maintenance::intersecting(&windows, job.window()) {
+            println!(
+                "    🚧 Overlaps maintenance {}",
+                maintenance::describe(clash)
+            );
+        }
+        if let Some(warning) = latency::check(&station.client, job.start).await {
+            println!("    ⏱️ {}", warning);
+        }
+    }
+    println!();
+    if let Some(mismatch) = version_mismatch(stations) {
+        eprintln!("⚠️ {}", mismatch);
+        transcript::warning(&mismatch);
+    }
+    let question = format!("Submit to all {} stations?", stations.len());
+    if tty::interactive()
+        && !Confirm::new(&question)
+            .with_default(true)
+            .prompt()
+            .recorded(&question)?
+    {
+        println!("🛑 Job not submitted");
+        return Ok(0);
+    }
+
+    let mut results = Vec::new();
+    for (station, (job, _)) in stations.iter().zip(&planned) {
+        let result = station.client.add_job(job).await;
+        if let Ok(response) = &result {
+            if let Some(id) = &response.id {
+                audit::job(id);
+            }
+            campaign::remember(job, response).await;
+            baseline::remember(job).await;
+            station.hooks.after_submit(job, response).await;
+        }
+        results.push((station.profile.as_str(), result));
+    }
+
+    let width = stations.iter().map(|s| text::width(&s.profile)).max();
+    println!("\n📊 Fan-out results");
+    let mut failed = 0;
+    for (profile, result) in results {
+        let outcome = match result {
+            Ok(response) => match &response.id {
+                Some(id) => format!("✅ job {} ({})", id, response.status()),
+                None => format!("✅ {} (no job ID returned)", response.status()),
+            },
+            Err(e) => {
+                failed += 1;
+                format!("❌ {}", e.full_message())
+            }
+        };
+        println!("  {}  {}", text::pad(profile, width.unwrap_or(0)), outcome);
+    }
+    Ok(failed)
+}
@@ -0,0 +1,382 @@
+//! Advisory checks on jobs about to be submitted. Each finding is a note or a warning the
+//! operator reads and may go ahead past; under `--strict` every one is an error, and the
+//! submission stops with all of them listed rather than the first.
+
+use crate::bands;
+use crate::chain;
+use crate::client::{ApiClient, JobDTO, JobRequestDTO, JobType, ServerWarning};
+use crate::clock;
+use crate::error::CliError;
+use crate::humanize;
+use crate::latency;
+use crate::location::Location;
+use crate::mask::ElevationMask;
+use crate::predict::Horizon;
+use crate::quiet_hours;
+use crate::satellites::AliasBook;
+use crate::submit_window;
+use crate::text;
+use crate::tle;
+use crate::transcript;
+use crate::weather::Lookout;
+use crate::OutputFormat;
+use chrono::Duration;
+use serde::Serialize;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Element sets older than this at the start of a job are warned about
+pub const STALE_TLE_DAYS: i64 = 14;
+
+static STRICT: AtomicBool = AtomicBool::new(false);
+/// Findings go to stderr as JSON objects, one per line, under `--output json`
+static JSON: AtomicBool = AtomicBool::new(false);
+
+/// `--strict` or the profile's `strict`
+pub fn set_strict(strict: bool) {
+    STRICT.store(strict, Ordering::Relaxed);
+}
+
+pub fn strict() -> bool {
+    STRICT.load(Ordering::Relaxed)
+}
+
+/// The command's `--output`, which findings are written in
+pub fn use_output(output: OutputFormat) {
+    JSON.store(
+        matches!(output, OutputFormat::Json | OutputFormat::Ndjson),
+        Ordering::Relaxed,
+    );
+}
+
+/// What a finding is about; the name is what `--strict` failures and JSON output list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Check {
+    /// Outside the profile's submit window; asked about on a terminal
+    SubmitWindow,
+    /// Already started, within the minutes the profile allows
+    PastStart,
+    StaleTle,
+    /// The name line is an alias for another catalog number
+    NameMismatch,
+    /// The station has the same job scheduled already
+    Duplicate,
+    ClockSkew,
+    Latency,
+    /// A receive-only job in quiet hours
+    QuietHours,
+    BandHint,
+    Weather,
+    /// A tracking job covers no pass over this station; handoffs, planned for another one
+    NotVisible,
+    /// The server accepted the job but warned about it
+    Server,
+}
+
+impl Check {
+    pub fn name(self) -> &'static str {
+        match self {
+            Check::SubmitWindow => "submit-window",
+            Check::PastStart => "past-start",
+            Check::StaleTle => "stale-tle",
+            Check::NameMismatch => "name-mismatch",
+            Check::Duplicate => "duplicate",
+            Check::ClockSkew => "clock-skew",
+            Check::Latency => "latency",
+            Check::QuietHours => "quiet-hours",
+            Check::BandHint => "band-hint",
+            Check::Weather => "weather",
+            Check::NotVisible => "not-visible",
+            Check::Server => "server",
+        }
+    }
+
+    fn icon(self) -> &'static str {
+        match self {
+            Check::Latency => "⏱️",
+            Check::QuietHours => "🌙",
+            Check::Weather => "🌧️",
+            Check::ClockSkew => "🕰️",
+            Check::Server => "📣",
+            _ => "⚠️",
+        }
+    }
+
+    /// How much the finding matters outside `--strict`
+    fn severity(self) -> Severity {
+        match self {
+            Check::QuietHours => Severity::Note,
+            _ => Severity::Warning,
+        }
+    }
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// For the record; nothing is wrong
+    Note,
+    /// Worth a look before going ahead
+    Warning,
+    /// Stops the submission: any finding under `--strict`
+    Error,
+}
+
+/// One check's finding about one job, or about the session
+#[derive(Debug, Clone, Serialize)]
+pub struct Finding {
+    pub check: Check,
+    pub severity: Severity,
+    /// Batch entry the finding is about
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entry: Option<usize>,
+    pub message: String,
+    /// The server's code for a warning it sent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    /// The request field a server warning is about
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+}
+
+/// "[3] Job starts in 40s; …", with the entry only for batch jobs, and what a server
+/// warning names after it: "… (field tle, code stale_tle)"
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(entry) = self.entry {
+            write!(f, "[{}] ", entry)?;
+        }
+        f.write_str(&self.message)?;
+        let about: Vec<String> = [
+            self.field.as_ref().map(|field| format!("field {}", field)),
+            self.code.as_ref().map(|code| format!("code {}", code)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        if !about.is_empty() {
+            write!(f, " ({})", about.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Checks the profile and flags configure or turn off
+#[derive(Default)]
+pub struct Checks {
+    /// The profile's forecast check, unless `--no-weather`
+    pub lookout: Option<Lookout>,
+    /// `--no-band-hints` was not given
+    pub band_hints: bool,
+    /// `--no-anomaly-hints` was not given: the summary compares the job with the
+    /// satellite's earlier ones
+    pub anomaly_hints: bool,
+    /// Where the station is and what it sees past, when tracking windows were picked
+    /// elsewhere and must be checked against this sky
+    pub visibility: Option<(Location, ElevationMask)>,
+}
+
+/// Everything the checks found about one submission
+#[derive(Debug, Default)]
+pub struct Findings {
+    found: Vec<Finding>,
+}
+
+impl Findings {
+    pub fn add(&mut self, check: Check, entry: Option<usize>, message: impl Into<String>) {
+        self.found.push(Finding {
+            check,
+            severity: match strict() {
+                true => Severity::Error,
+                false => check.severity(),
+            },
+            entry,
+            message: message.into(),
+            code: None,
+            field: None,
+        });
+    }
+
+    /// Whether `check` found anything
+    pub fn has(&self, check: Check) -> bool {
+        self.found.iter().any(|finding| finding.check == check)
+    }
+
+    /// Under `--strict`, refuse the submission with every finding; else show them and
+    /// let it go ahead
+    pub fn settle(&self) -> Result<(), CliError> {
+        self.settle_as(false)
+    }
+
+    fn settle_as(&self, scheduled: bool) -> Result<(), CliError> {
+        if strict() && !self.found.is_empty() {
+            return Err(CliError::Strict {
+                findings: self.found.clone(),
+                scheduled,
+            });
+        }
+        let json = JSON.load(Ordering::Relaxed);
+        for finding in &self.found {
+            match json {
+                true => eprintln!("{}", serde_json::json!(finding)),
+                false => eprintln!("{} {}", finding.check.icon(), finding),
+            }
+            transcript::warning(finding);
+        }
+        Ok(())
+    }
+}
+
+/// Show the warnings the server sent with a job it accepted, as findings about `entry`.
+/// Under `--strict` they fail the command instead, though the job is already scheduled.
+pub fn server(entry: Option<usize>, warnings: &[ServerWarning]) -> Result<(), CliError> {
+    let mut findings = Findings::default();
+    for warning in warnings {
+        findings.add(Check::Server, entry, &warning.message);
+        if let Some(finding) = findings.found.last_mut() {
+            finding.code = warning.code.clone();
+            finding.field = warning.field.clone();
+        }
+    }
+    findings.settle_as(true)
+}
+
+/// The same observation twice: one target, named alike once normalized, overlapping
+/// windows and the same frequencies
+fn same_job(a: &JobRequestDTO, b: &JobRequestDTO) -> bool {
+    let target = |job: &JobRequestDTO| match &job.job_type {
+        JobType::Tracking { tle } => tle::norad_id(&tle.tle1).map(|id| id.to_string()),
+        _ => Some(text::nfc(job.label()).into_owned()),
+    };
+    target(a) == target(b)
+        && a.window().overlaps(&b.window())
+        && a.rx_frequency == b.rx_frequency
+        && a.tx_frequency == b.tx_frequency
+}
+
+/// Run every advisory check on `jobs`, each paired with its batch entry when it has one.
+/// Limits and refusals are not findings; `validation::check_job` and the reviews have them.
+pub async fn gather(
+    client: &ApiClient,
+    jobs: &[(Option<usize>, &JobRequestDTO)],
+    checks: &Checks,
+) -> Findings {
+    let mut findings = Findings::default();
+    let now = clock::now();
+    let book = AliasBook::load().ok();
+    for &(entry, job) in jobs {
+        if job.start < now {
+            // A start beyond the allowance is the submit window's to refuse
+            if submit_window::check(job.start).is_none() {
+                findings.add(
+                    Check::PastStart,
+                    entry,
+                    format!(
+                        "{} started {} ago; the station misses that much of the window",
+                        humanize::timestamp(job.start),
+                        humanize::duration(now - job.start)
+                    ),
+                );
+            }
+        } else if let Some(warning) = latency::check(client, job.start).await {
+            findings.add(Check::Latency, entry, warning);
+        }
+        if let JobType::Tracking { tle } = &job.job_type {
+            if let Some(epoch) = tle::epoch(&tle.tle1) {
+                if job.start - epoch > Duration::days(STALE_TLE_DAYS) {
+                    findings.add(
+                        Check::StaleTle,
+                        entry,
+                        format!(
+                            "The elements for {} are {} old at the start of the job (epoch {}); \
+                             predictions drift, so fetch a fresh set",
+                            tle.tle0.trim(),
+                            humanize::duration(job.start - epoch),
+                            humanize::timestamp(epoch)
+                        ),
+                    );
+                }
+            }
+            let aliased = book
+                .as_ref()
+                .and_then(|book| book.get(tle.tle0.trim()))
+                .and_then(|(name, preset)| Some((name, preset.norad_id?)));
+            if let (Some((name, aliased)), Some(norad_id)) = (aliased, tle::norad_id(&tle.tle1)) {
+                if aliased != norad_id {
+                    findings.add(
+                        Check::NameMismatch,
+                        entry,
+                        format!(
+                            "The elements are named {}, the alias for NORAD {}, but are for \
+                             NORAD {}",
+                            name, aliased, norad_id
+                        ),
+                    );
+                }
+            }
+        }
+        if let Some((station, mask)) = &checks.visibility {
+            let horizon = Horizon {
+                station,
+                min_elevation_deg: 0.0,
+                mask,
+            };
+            if let Err(e) = chain::check_pass(job, &horizon) {
+                let message = match e {
+                    CliError::Validation { reason, .. } => reason,
+                    other => other.to_string(),
+                };
+                findings.add(Check::NotVisible, entry, message);
+            }
+        }
+        if let Some(note) = quiet_hours::receiving(job) {
+            findings.add(Check::QuietHours, entry, note);
+        }
+        if checks.band_hints {
+            for hint in bands::hints(job) {
+                findings.add(Check::BandHint, entry, hint);
+            }
+        }
+        if let Some(lookout) = &checks.lookout {
+            if let Some(warning) = lookout.warning(job).await {
+                findings.add(Check::Weather, entry, warning);
+            }
+        }
+    }
+    // Listed after the latency checks, which its round trip would skew. A station that
+    // cannot list its jobs leaves duplicates to the scheduler.
+    let scheduled: Vec<JobDTO> = client
+        .list_jobs()
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|scheduled| scheduled.cancelled_at.is_none())
+        .collect();
+    for &(entry, job) in jobs {
+        if let Some(other) = scheduled
+            .iter()
+            .find(|scheduled| same_job(&scheduled.job, job))
+        {
+            findings.add(
+                Check::Duplicate,
+                entry,
+                format!(
+                    "The same {} is already scheduled, as job {}",
+                    job.label(),
+                    other.id.as_deref().unwrap_or("without an ID")
+                ),
+            );
+        }
+    }
+    if let Some(skew) = latency::clock_skew() {
+        findings.add(Check::ClockSkew, None, skew);
+    }
+    findings
+}
@@ -0,0 +1,213 @@
+use crate::error::CliError;
+use crate::examples::Example;
+use crate::humanize;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// Frequency suffixes and their multipliers; an optional "Hz" may follow
+pub const SUFFIXES: &[(&str, u64)] = &[
+    ("k", 1_000),
+    ("K", 1_000),
+    ("M", 1_000_000),
+    ("G", 1_000_000_000),
+    ("g", 1_000_000_000),
+];
+
+/// A radio frequency in whole hertz. Zero stands for "none" in job fields, as it does on
+/// the wire. Kept as an integer so unit conversions and passband offsets leave no float
+/// dust; the Doppler math turns it into `f64` at its edges.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Frequency(u64);
+
+impl Frequency {
+    pub const ZERO: Frequency = Frequency(0);
+
+    pub const fn from_hz(hz: u64) -> Self {
+        Self(hz)
+    }
+
+    pub const fn hz(self) -> u64 {
+        self.0
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn as_f64(self) -> f64 {
+        self.0 as f64
+    }
+
+    /// The nearest whole hertz; none for negative, non-finite or out-of-range values
+    pub fn from_f64(hz: f64) -> Option<Self> {
+        let hz = hz.round();
+        (hz.is_finite() && (0.0..=u64::MAX as f64).contains(&hz)).then_some(Self(hz as u64))
+    }
+
+    /// Parse a frequency in Hz, accepting k/M/G suffixes with an optional "Hz"
+    /// ("145800000", "145.8M", "437.5 MHz", "2.4GHz")
+    pub fn parse(input: &str) -> Result<Self, CliError> {
+        let trimmed = input.trim();
+        let error =
+            |reason: &str| CliError::parse(format!("frequency '{}'", trimmed), reason.to_string());
+
+        let without_hz = trimmed
+            .strip_suffix("Hz")
+            .or_else(|| trimmed.strip_suffix("hz"))
+            .unwrap_or(trimmed)
+            .trim_end();
+        let (number, multiplier) = SUFFIXES
+            .iter()
+            .find_map(|(suffix, multiplier)| {
+                without_hz
+                    .strip_suffix(suffix)
+                    .map(|number| (number, *multiplier))
+            })
+            .unwrap_or((without_hz, 1));
+
+        let value: f64 = number
+            .trim()
+            .parse()
+            .map_err(|_| error("expected a number with an optional k/M/G suffix"))?;
+        if !value.is_finite() || value < 0.0 {
+            return Err(error("must be a non-negative number"));
+        }
+        Self::from_f64(value * multiplier as f64).ok_or_else(|| error("is too large"))
+    }
+
+    pub fn checked_add(self, other: Frequency) -> Option<Self> {
+        self.0.checked_add(other.0).map(Self)
+    }
+
+    pub fn checked_sub(self, other: Frequency) -> Option<Self> {
+        self.0.checked_sub(other.0).map(Self)
+    }
+
+    /// Moved by a signed number of hertz, stopping at zero and at the top of the range
+    pub fn shifted(self, hz: i64) -> Self {
+        Self(self.0.saturating_add_signed(hz))
+    }
+
+    /// Halfway between the two, rounded down to the hertz
+    pub fn midpoint(self, other: Frequency) -> Self {
+        Self(self.0.min(other.0) + self.0.abs_diff(other.0) / 2)
+    }
+}
+
+/// A signed correction in whole hertz, with the suffixes `Frequency::parse` takes
+/// ("+2.1k", "-500", "-1.5 kHz"); no sign means upwards
+pub fn parse_offset(input: &str) -> Result<i64, CliError> {
+    let trimmed = input.trim();
+    let (sign, magnitude) = match trimmed.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, trimmed.strip_prefix('+').unwrap_or(trimmed)),
+    };
+    let hz = Frequency::parse(magnitude)
+        .map_err(|_| {
+            CliError::parse(
+                format!("offset '{}'", trimmed),
+                "expected a signed number with an optional k/M/G suffix (e.g. +2.1k)",
+            )
+        })?
+        .hz();
+    i64::try_from(hz)
+        .map(|hz| sign * hz)
+        .map_err(|_| CliError::parse(format!("offset '{}'", trimmed), "is too large"))
+}
+
+/// "+2.1 kHz", "-500 Hz"
+pub fn describe_offset(hz: i64) -> String {
+    let sign = if hz < 0 { '-' } else { '+' };
+    format!("{}{}", sign, humanize::frequency_step(hz.unsigned_abs()))
+}
+
+impl fmt::Display for Frequency {
+    /// "145.825 MHz", "2400 MHz", or in the unit the profile or `--freq-unit` asks for
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&humanize::frequency(self.as_f64()))
+    }
+}
+
+impl FromStr for Frequency {
+    type Err = CliError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Self::parse(input)
+    }
+}
+
+/// A JSON number, written as the float the API has always been sent ("145800000.0")
+impl Serialize for Frequency {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(self.as_f64())
+    }
+}
+
+/// Either spelling the deserializer takes: hertz as a number, or text with a unit
+impl schemars::JsonSchema for Frequency {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "Frequency".into()
+    }
+
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "description": "Hertz as a number (145800000), or text with a k/M/G suffix (\"145.8M\")",
+            "oneOf": [
+                { "type": "number", "minimum": 0 },
+                { "type": "string" },
+            ],
+        })
+    }
+}
+
+/// A number of hertz, or a string as the prompts read it: some server versions send
+/// frequencies bare ("145800000") or with a unit ("145.8 MHz")
+impl<'de> Deserialize<'de> for Frequency {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Number(f64),
+            Text(String),
+        }
+
+        match Raw::deserialize(deserializer)? {
+            Raw::Number(hz) => Self::from_f64(hz).ok_or_else(|| {
+                serde::de::Error::custom(format!("{} is not a frequency in Hz", hz))
+            }),
+            Raw::Text(text) => {
+                Self::parse(&text).map_err(|e| serde::de::Error::custom(e.full_message()))
+            }
+        }
+    }
+}
+
+/// `examples frequencies`
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        note: "Plain hertz",
+        input: Some("145800000"),
+        command: "rustar-cli sat set-freq iss --rx 145800000",
+    },
+    Example {
+        note: "A k, M or G suffix; decimals are rounded to whole hertz",
+        input: Some("437.8M"),
+        command: "rustar-cli sat set-freq iss --rx 145.8M --tx 437.8M",
+    },
+    Example {
+        note: "Hz may follow the suffix; quote it for the space",
+        input: Some("437.5 MHz"),
+        command: "rustar-cli sat set-freq cubesat --rx \"437.5 MHz\" --mode AFSK --baud 1200",
+    },
+    Example {
+        note: "Signed offsets, for a satellite known to transmit off its published downlink",
+        input: Some("+2.1k"),
+        command: "rustar-cli sat set-freq cubesat --rx 145.8M --rx-offset +2.1k",
+    },
+    Example {
+        note: "Linear transponder passbands, low-high",
+        input: Some("145.9M-145.93M"),
+        command: "rustar-cli sat set-freq fo-29 --downlink-band 145.9M-145.93M --uplink-band 435.8M-435.83M --inverting",
+    },
+];
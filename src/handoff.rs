@@ -0,0 +1,198 @@
+//! Job handoffs to a partner station running this CLI: `jobs handoff` writes one job with
+//! where it came from into a JSON bundle, and `jobs import-handoff` reads it on the other
+//! station, freshening its elements when they have aged, before the usual submission.
+
+use crate::client::{ApiClient, JobDTO, JobRequestDTO, JobType};
+use crate::clock;
+use crate::error::{CliError, ResultExt};
+use crate::findings::STALE_TLE_DAYS;
+use crate::humanize;
+use crate::output::OutputTarget;
+use crate::tle;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// What a bundle's `kind` says, so a handoff is told apart from other JSON files
+const KIND: &str = "rustar-handoff";
+
+/// Layout of the bundle; newer layouts are refused rather than half read
+const FORMAT: u32 = 1;
+
+/// Bundles older than this have their elements fetched again, unless the profile's
+/// `handoff_max_age_hours` says otherwise
+const DEFAULT_MAX_AGE_HOURS: u64 = 24;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Bundle {
+    kind: String,
+    format: u32,
+    provenance: Provenance,
+    /// For the operator at the receiving station
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    notes: Option<String>,
+    job: JobRequestDTO,
+}
+
+/// Where and when the bundle was written
+#[derive(Debug, Serialize, Deserialize)]
+struct Provenance {
+    /// The source station's name, when it reports one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    station: Option<String>,
+    base_url: String,
+    profile: String,
+    /// ID of the job on the source station
+    job_id: String,
+    exported_at: DateTime<Utc>,
+    cli_version: String,
+}
+
+impl Provenance {
+    /// "ground-1 (https://…)", or the address alone for a station without a name
+    fn source(&self) -> String {
+        match &self.station {
+            Some(name) => format!("{} ({})", name, self.base_url),
+            None => self.base_url.clone(),
+        }
+    }
+}
+
+/// `jobs handoff`: write job `id` with its provenance to `target`. `notes` wins over the
+/// notes the server keeps with the job, if it keeps any.
+pub async fn export(
+    client: &ApiClient,
+    id: &str,
+    profile: &str,
+    notes: Option<String>,
+    target: &OutputTarget,
+) -> Result<(), CliError> {
+    target.check()?;
+    let value = client.job_json(id).await?;
+    if value.is_null() {
+        return Err(CliError::NotFound {
+            message: Some(format!("the server returned no body for job {}", id)),
+        });
+    }
+    let stored: JobDTO = serde_json::from_value(value.clone())
+        .map_err(|e| CliError::parse(format!("job {}", id), e))?;
+    if stored.cancelled_at.is_some() {
+        return Err(CliError::validation(
+            "job",
+            format!("job {} is cancelled; restore it before handing it off", id),
+        ));
+    }
+    let notes = notes.or_else(|| value["notes"].as_str().map(str::to_string));
+    let station = client.station_info().await.ok().and_then(|info| info.name);
+    let mut job = stored.job;
+    // Campaigns are this station's bookkeeping
+    job.campaign = None;
+    let bundle = Bundle {
+        kind: KIND.to_string(),
+        format: FORMAT,
+        provenance: Provenance {
+            station,
+            base_url: client.base_url().to_string(),
+            profile: profile.to_string(),
+            job_id: id.to_string(),
+            exported_at: clock::now(),
+            cli_version: env!("CARGO_PKG_VERSION").to_string(),
+        },
+        notes,
+        job,
+    };
+    let mut text =
+        serde_json::to_string_pretty(&bundle).map_err(|e| CliError::parse("handoff bundle", e))?;
+    text.push('\n');
+    target.write(text)?;
+    eprintln!(
+        "🤝 Handed off {} ({} – {})",
+        bundle.job.label(),
+        humanize::timestamp(bundle.job.start),
+        bundle.job.end.format("%H:%M:%S UTC")
+    );
+    Ok(())
+}
+
+/// `jobs import-handoff`: the job of the bundle at `file`, with fresh elements when the
+/// bundle is older than `max_age_hours` or its elements are stale by the job's start.
+/// A failed fetch keeps the bundle's elements, which the stale-TLE check then flags.
+pub async fn import(file: &Path, max_age_hours: Option<u64>) -> Result<JobRequestDTO, CliError> {
+    let text =
+        std::fs::read_to_string(file).with_context(|| format!("reading {}", file.display()))?;
+    let value: serde_json::Value =
+        serde_json::from_str(&text).map_err(|e| CliError::parse(file.display().to_string(), e))?;
+    if value["kind"] != KIND {
+        return Err(CliError::validation(
+            "handoff",
+            format!(
+                "{} is not a handoff bundle (write one with jobs handoff)",
+                file.display()
+            ),
+        ));
+    }
+    let format = value["format"].as_u64().unwrap_or(0);
+    if format > u64::from(FORMAT) {
+        return Err(CliError::Configuration(format!(
+            "{} is a format {} handoff, written by rustar-cli {}; this one reads up to format {}",
+            file.display(),
+            format,
+            value["provenance"]["cli_version"]
+                .as_str()
+                .unwrap_or("unknown"),
+            FORMAT
+        )));
+    }
+    let bundle: Bundle = serde_json::from_value(value)
+        .map_err(|e| CliError::parse(file.display().to_string(), e))?;
+
+    let now = clock::now();
+    let age = now - bundle.provenance.exported_at;
+    eprintln!(
+        "🤝 Job {} from {}, handed off {} by rustar-cli {}",
+        bundle.provenance.job_id,
+        bundle.provenance.source(),
+        humanize::since(bundle.provenance.exported_at, now),
+        bundle.provenance.cli_version
+    );
+    if let Some(notes) = &bundle.notes {
+        eprintln!("📝 {}", notes);
+    }
+
+    let mut job = bundle.job;
+    let JobType::Tracking { tle } = &job.job_type else {
+        return Ok(job);
+    };
+    let max_age = Duration::hours(max_age_hours.unwrap_or(DEFAULT_MAX_AGE_HOURS) as i64);
+    let elements_age = tle::epoch(&tle.tle1).map(|epoch| job.start - epoch);
+    let why = if age > max_age {
+        format!("the bundle is {} old", humanize::duration(age))
+    } else if let Some(elements_age) =
+        elements_age.filter(|age| *age > Duration::days(STALE_TLE_DAYS))
+    {
+        format!(
+            "the elements are {} old at the start of the job",
+            humanize::duration(elements_age)
+        )
+    } else {
+        return Ok(job);
+    };
+    match tle::refreshed(tle).await {
+        Ok(fresh) => {
+            eprintln!(
+                "🔄 Fetched fresh elements for {}, as {}{}",
+                fresh.tle0.trim(),
+                why,
+                tle::epoch(&fresh.tle1)
+                    .map(|epoch| format!(" (epoch now {})", humanize::timestamp(epoch)))
+                    .unwrap_or_default()
+            );
+            job.job_type = JobType::Tracking { tle: fresh };
+        }
+        Err(e) => eprintln!(
+            "⚠️ Keeping the bundle's elements, as fetching fresh ones failed: {}",
+            e.full_message()
+        ),
+    }
+    Ok(job)
+}
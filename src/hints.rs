@@ -0,0 +1,190 @@
+use std::error::Error as _;
+use std::time::Duration;
+
+/// Coarse kinds of transport failure an operator can act on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransportFailure {
+    Dns,
+    ConnectionRefused,
+    Tls,
+    Timeout,
+    Other,
+}
+
+/// Classify a reqwest transport error by walking its source chain
+pub fn classify(error: &reqwest::Error) -> TransportFailure {
+    if error.is_timeout() {
+        return TransportFailure::Timeout;
+    }
+
+    let mut source = error.source();
+    while let Some(cause) = source {
+        if let Some(io) = cause.downcast_ref::<std::io::Error>() {
+            match io.kind() {
+                std::io::ErrorKind::ConnectionRefused => {
+                    return TransportFailure::ConnectionRefused
+                }
+                std::io::ErrorKind::TimedOut => return TransportFailure::Timeout,
+                _ => {}
+            }
+        }
+
+        let message = cause.to_string().to_lowercase();
+        if message.contains("dns error") || message.contains("failed to lookup address") {
+            return TransportFailure::Dns;
+        }
+        if message.contains("connection refused") {
+            return TransportFailure::ConnectionRefused;
+        }
+        if ["tls", "ssl", "certificate", "handshake"]
+            .iter()
+            .any(|needle| message.contains(needle))
+        {
+            return TransportFailure::Tls;
+        }
+
+        source = cause.source();
+    }
+
+    TransportFailure::Other
+}
+
+/// Suggest a fix for a transport failure, quoting the values actually configured
+pub fn hint(failure: TransportFailure, base_url: &str, timeout: Duration) -> Option<String> {
+    let url = reqwest::Url::parse(base_url).ok();
+    let host = url
+        .as_ref()
+        .and_then(|u| u.host_str().map(str::to_string))
+        .unwrap_or_else(|| base_url.to_string());
+    let port = url
+        .as_ref()
+        .and_then(|u| u.port_or_known_default())
+        .map(|p| p.to_string())
+        .unwrap_or_else(|| "the configured port".to_string());
+
+    match failure {
+        TransportFailure::Dns => Some(format!(
+            "check the hostname in API_BASE_URL (currently {})",
+            host
+        )),
+        TransportFailure::ConnectionRefused => Some(format!(
+            "is the ground station service running on port {} of {}?",
+            port, host
+        )),
+        TransportFailure::Tls => Some(format!(
+            "the TLS handshake with {} failed: the server may be using http://, not https:// \
+             (API_BASE_URL is currently {})",
+            host, base_url
+        )),
+        TransportFailure::Timeout => Some(format!(
+            "no answer within {}s: try --timeout {} or check the VPN",
+            timeout.as_secs(),
+            (timeout.as_secs() * 2).max(60)
+        )),
+        TransportFailure::Other => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The error a GET of `url` fails with, giving up after `timeout`
+    async fn failure(url: &str, timeout: Duration) -> reqwest::Error {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .expect("an HTTP client");
+        client.get(url).send().await.expect_err("the request fails")
+    }
+
+    #[tokio::test]
+    async fn a_closed_port_is_a_refused_connection() {
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .and_then(|listener| listener.local_addr())
+            .expect("a free port")
+            .port();
+        let url = format!("http://127.0.0.1:{}", port);
+
+        let failure = classify(&failure(&url, Duration::from_secs(5)).await);
+        assert_eq!(failure, TransportFailure::ConnectionRefused);
+        assert_eq!(
+            hint(failure, &url, Duration::from_secs(30)).unwrap(),
+            format!(
+                "is the ground station service running on port {} of 127.0.0.1?",
+                port
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn a_server_that_never_answers_is_a_timeout() {
+        // Connections are accepted by the backlog, but nothing ever reads or writes them
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("a free port");
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        let failure = classify(&failure(&url, Duration::from_millis(200)).await);
+        assert_eq!(failure, TransportFailure::Timeout);
+        assert_eq!(
+            hint(failure, &url, Duration::from_secs(45)).unwrap(),
+            "no answer within 45s: try --timeout 90 or check the VPN"
+        );
+        assert!(hint(failure, &url, Duration::from_secs(10))
+            .unwrap()
+            .contains("--timeout 60"));
+    }
+
+    #[tokio::test]
+    async fn a_name_that_does_not_resolve_is_a_dns_failure() {
+        // .invalid is reserved never to resolve
+        let url = "http://ground-station.invalid:8080";
+
+        let failure = classify(&failure(url, Duration::from_secs(10)).await);
+        assert_eq!(failure, TransportFailure::Dns);
+        assert_eq!(
+            hint(failure, url, Duration::from_secs(30)).unwrap(),
+            "check the hostname in API_BASE_URL (currently ground-station.invalid)"
+        );
+    }
+
+    #[tokio::test]
+    async fn plain_http_answering_https_is_a_tls_failure() {
+        // Answers every connection in plain HTTP, whatever the client sends first
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("a free port");
+        let url = format!("https://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for mut socket in listener.incoming().flatten() {
+                use std::io::Write;
+                let _ = socket.write_all(b"HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\n\r\n");
+            }
+        });
+
+        let failure = classify(&failure(&url, Duration::from_secs(10)).await);
+        assert_eq!(failure, TransportFailure::Tls);
+        assert_eq!(
+            hint(failure, &url, Duration::from_secs(30)).unwrap(),
+            format!(
+                "the TLS handshake with 127.0.0.1 failed: the server may be using http://, \
+                 not https:// (API_BASE_URL is currently {})",
+                url
+            )
+        );
+    }
+
+    #[test]
+    fn the_port_is_the_scheme_default_when_none_is_given() {
+        let shown = hint(
+            TransportFailure::ConnectionRefused,
+            "https://station.example.org/api",
+            Duration::from_secs(30),
+        );
+        assert_eq!(
+            shown.unwrap(),
+            "is the ground station service running on port 443 of station.example.org?"
+        );
+        assert_eq!(
+            hint(TransportFailure::Other, "http://x", Duration::ZERO),
+            None
+        );
+    }
+}
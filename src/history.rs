@@ -0,0 +1,292 @@
+use crate::clock;
+use crate::error::{CliError, ResultExt};
+use crate::frequency::Frequency;
+use crate::paths;
+use crate::purge::Removal;
+use crate::satellites::AliasBook;
+use crate::schema::{Format, Store};
+use crate::storage;
+use crate::text;
+use chrono::{DateTime, Utc};
+use inquire::autocompletion::{Autocomplete, Replacement};
+use inquire::CustomUserError;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// Answers kept per prompt, most recent first
+pub const LIMIT: usize = 200;
+
+/// `prompt_history.json` layouts: version 1 kept bare strings, with no profile
+pub const STORE: Store = Store {
+    name: "prompt history",
+    format: Format::Json,
+    migrations: &[answers_with_profile],
+};
+
+fn answers_with_profile(history: Value) -> Result<Value, String> {
+    let Value::Object(prompts) = history else {
+        return Err("expected a map of prompt names".to_string());
+    };
+    let mut upgraded = serde_json::Map::new();
+    for (prompt, answers) in prompts {
+        let Value::Array(answers) = answers else {
+            return Err(format!("the answers to {} are not a list", prompt));
+        };
+        let answers = answers
+            .into_iter()
+            .map(|answer| match answer {
+                Value::String(value) => Ok(json!({ "value": value })),
+                other => Err(format!("{} is not an answer to {}", other, prompt)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        upgraded.insert(prompt, Value::Array(answers));
+    }
+    Ok(json!({ "prompts": upgraded }))
+}
+
+/// One remembered answer, the profile it was given under and when; none for answers
+/// kept before the history knew about profiles, or dates
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Answer {
+    value: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    profile: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    at: Option<DateTime<Utc>>,
+}
+
+/// Prompt name to its earlier answers, most recent first
+type History = BTreeMap<String, Vec<Answer>>;
+
+#[derive(Deserialize)]
+struct Layout {
+    prompts: History,
+}
+
+/// Profile the answers of this run belong to
+static PROFILE: OnceLock<String> = OnceLock::new();
+
+/// Tag this run's answers with the active profile, and offer its own answers first
+pub fn use_profile(profile: &str) {
+    let _ = PROFILE.set(profile.to_string());
+}
+
+/// Which prompt an answer belongs to. TLE lines are never kept: too long to be worth offering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Prompt {
+    SatelliteName,
+    RxFrequency,
+    TxFrequency,
+    TestFrequency,
+    Downlink,
+    /// A line typed into `shell`
+    ShellCommand,
+}
+
+impl Prompt {
+    fn key(self) -> &'static str {
+        match self {
+            Prompt::SatelliteName => "satellite_name",
+            Prompt::RxFrequency => "rx_frequency",
+            Prompt::TxFrequency => "tx_frequency",
+            Prompt::TestFrequency => "test_frequency",
+            Prompt::Downlink => "downlink",
+            Prompt::ShellCommand => "shell_command",
+        }
+    }
+
+    /// What the alias book has to offer for this prompt
+    fn alias_book_values(self, book: &AliasBook) -> Vec<String> {
+        let frequencies = |pick: fn(&crate::satellites::Preset) -> Option<Frequency>| {
+            book.iter()
+                .filter_map(|(_, preset)| pick(preset))
+                .map(|hz| hz.to_string())
+                .collect()
+        };
+        match self {
+            Prompt::SatelliteName => book.iter().map(|(name, _)| name.to_string()).collect(),
+            Prompt::RxFrequency => frequencies(|p| p.rx_frequency),
+            Prompt::TxFrequency => frequencies(|p| p.tx_frequency),
+            Prompt::TestFrequency | Prompt::Downlink | Prompt::ShellCommand => Vec::new(),
+        }
+    }
+}
+
+fn history_path() -> PathBuf {
+    paths::prompt_history_file()
+}
+
+/// Read the prompt history; a missing file is an empty history
+fn load() -> Result<History, CliError> {
+    Ok(STORE
+        .load::<Layout>(&history_path())?
+        .map(|layout| layout.prompts)
+        .unwrap_or_default())
+}
+
+/// Suggestions for a prompt, filtered by what has been typed so far
+#[derive(Clone)]
+pub struct Suggestions {
+    values: Vec<String>,
+}
+
+impl Suggestions {
+    /// Earlier answers under the active profile, then other earlier answers, most recent
+    /// first, then alias book values not already among them.
+    /// `default` is left out: the prompt already offers it on Enter.
+    pub fn new(prompt: Prompt, default: Option<&str>) -> Self {
+        let answers = load()
+            .unwrap_or_default()
+            .remove(prompt.key())
+            .unwrap_or_default();
+        let (own, other): (Vec<Answer>, Vec<Answer>) = answers
+            .into_iter()
+            .partition(|answer| answer.profile.as_ref() == PROFILE.get());
+        let mut known: Vec<String> = own
+            .into_iter()
+            .chain(other)
+            .map(|answer| answer.value)
+            .collect();
+        known.extend(
+            AliasBook::load()
+                .map(|book| prompt.alias_book_values(&book))
+                .unwrap_or_default(),
+        );
+
+        let mut values: Vec<String> = Vec::with_capacity(known.len());
+        for value in known {
+            let duplicate = values.iter().any(|v| text::same_name(v, &value))
+                || default.is_some_and(|d| text::same_name(d, &value));
+            if !duplicate {
+                values.push(value);
+            }
+        }
+        Self { values }
+    }
+}
+
+impl Autocomplete for Suggestions {
+    fn get_suggestions(&mut self, input: &str) -> Result<Vec<String>, CustomUserError> {
+        let needle = input.trim().to_lowercase();
+        Ok(self
+            .values
+            .iter()
+            .filter(|value| value.to_lowercase().contains(&needle))
+            .cloned()
+            .collect())
+    }
+
+    fn get_completion(
+        &mut self,
+        _input: &str,
+        highlighted: Option<String>,
+    ) -> Result<Replacement, CustomUserError> {
+        Ok(highlighted)
+    }
+}
+
+fn push(prompt: Prompt, value: &str) -> Result<(), CliError> {
+    let path = history_path();
+    let _lock = storage::lock(&path)?;
+    let mut history = load()?;
+    let answers = history.entry(prompt.key().to_string()).or_default();
+    answers.retain(|known| !text::same_name(&known.value, value));
+    answers.insert(
+        0,
+        Answer {
+            value: value.to_string(),
+            profile: PROFILE.get().cloned(),
+            at: Some(clock::now()),
+        },
+    );
+    answers.truncate(LIMIT);
+    STORE.write(&path, &json!({ "prompts": history }))
+}
+
+/// Keep an accepted answer for next time. Failures are warnings: the answer itself stands.
+pub fn record(prompt: Prompt, value: &str) {
+    let value = value.trim();
+    if value.is_empty() {
+        return;
+    }
+    if let Err(e) = push(prompt, value) {
+        eprintln!("⚠️ Could not save the prompt history: {}", e.full_message());
+    }
+}
+
+/// The latest earlier answer to `prompt`, preferring the active profile's
+pub fn last(prompt: Prompt) -> Option<String> {
+    let answers = load().ok()?.remove(prompt.key())?;
+    let own = answers
+        .iter()
+        .position(|answer| answer.profile.as_ref() == PROFILE.get());
+    let answer = match own {
+        Some(at) => answers.into_iter().nth(at),
+        None => answers.into_iter().next(),
+    };
+    answer.map(|answer| answer.value)
+}
+
+/// Every earlier answer to `prompt`, most recent first, whatever the profile
+pub fn earlier(prompt: Prompt) -> Vec<String> {
+    load()
+        .unwrap_or_default()
+        .remove(prompt.key())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|answer| answer.value)
+        .collect()
+}
+
+/// `history clear-prompts`: forget every remembered answer
+pub fn clear() -> Result<(), CliError> {
+    let path = history_path();
+    let _lock = storage::lock(&path)?;
+    let answers: usize = load()?.values().map(Vec::len).sum();
+    if path.exists() {
+        std::fs::remove_file(&path).with_context(|| format!("removing {}", path.display()))?;
+    }
+    println!(
+        "🧹 Cleared {} remembered prompt answer(s) from {}",
+        answers,
+        path.display()
+    );
+    Ok(())
+}
+
+/// `purge`: forget the answers given before `cutoff`. An answer from before they were
+/// dated counts from when the history was last written. Only counts unless `apply`.
+pub fn purge(cutoff: DateTime<Utc>, apply: bool) -> Result<Removal, CliError> {
+    let path = history_path();
+    let Ok(metadata) = std::fs::metadata(&path) else {
+        return Ok(Removal::default());
+    };
+    let _lock = storage::lock(&path)?;
+    let written = metadata.modified().ok().map(DateTime::<Utc>::from);
+    let Some(Layout {
+        prompts: mut history,
+    }) = STORE.peek::<Layout>(&path)?
+    else {
+        return Ok(Removal::default());
+    };
+    let mut entries = 0;
+    for answers in history.values_mut() {
+        let before = answers.len();
+        answers.retain(|answer| answer.at.or(written).is_none_or(|at| at >= cutoff));
+        entries += before - answers.len();
+    }
+    history.retain(|_, answers| !answers.is_empty());
+    let document = json!({ "prompts": history });
+    let removal = Removal {
+        entries,
+        bytes: metadata.len().saturating_sub(STORE.size(&document)?),
+        dropped: Vec::new(),
+    };
+    if apply && entries > 0 {
+        STORE.write(&path, &document)?;
+    }
+    Ok(removal)
+}
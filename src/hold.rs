@@ -0,0 +1,183 @@
+use crate::client::{ApiClient, ApiResponse, Hold};
+use crate::clock;
+use crate::error::{CliError, ResultExt};
+use crate::humanize;
+use crate::job_request::JobRequest;
+use crate::snapshot;
+use crate::transcript::Recorded;
+use crate::tty::RawMode;
+use chrono::{DateTime, Duration, Utc};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use std::io::Write;
+
+/// Longest a held slot waits for an answer before it is given back
+const REVIEW_TIMEOUT_MIN: i64 = 15;
+
+/// Statuses meaning the server takes jobs in a single POST and has no reservations
+fn is_missing_endpoint(error: &CliError) -> bool {
+    matches!(error.status(), Some(404 | 405 | 501))
+}
+
+/// Whether confirming failed because the server already let the hold go
+fn is_lapsed(error: &CliError) -> bool {
+    matches!(error.status(), Some(404 | 410))
+}
+
+/// How a held job left the review
+pub enum Outcome {
+    Scheduled(ApiResponse),
+    /// Declined or left unanswered; the hold is released
+    NotScheduled,
+}
+
+enum Answer {
+    Accept,
+    Decline,
+    Interrupt,
+    Expired,
+    Unanswered,
+}
+
+/// Have the station keep the job's slot during the review; None on servers without
+/// reservations, which take the job in a single POST
+pub async fn reserve(client: &ApiClient, job: &JobRequest) -> Result<Option<Hold>, CliError> {
+    match client.reserve_job(job).await {
+        Ok(hold) => Ok(Some(hold)),
+        Err(e) if is_missing_endpoint(&e) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Give the slot back; a hold the server will not release lapses at its deadline anyway
+pub async fn release(client: &ApiClient, hold: &Hold) {
+    match client.release_hold(&hold.id).await {
+        Ok(()) => println!("🔓 Hold {} released", hold.id),
+        Err(e) => eprintln!(
+            "⚠️ Could not release hold {}: {}; it lapses at {}",
+            hold.id,
+            e.full_message(),
+            hold.expires_at.format("%H:%M:%S UTC")
+        ),
+    }
+}
+
+async fn reacquire(client: &ApiClient, job: &JobRequest) -> Result<Hold, CliError> {
+    println!("⌛ The hold on the slot lapsed; reserving it again");
+    let hold = client.reserve_job(job).await?;
+    println!(
+        "🔒 Slot held again until {}",
+        hold.expires_at.format("%H:%M:%S UTC")
+    );
+    Ok(hold)
+}
+
+/// Ask whether to schedule, counting the hold down on one line until it lapses or the
+/// review runs out at `deadline`
+fn ask(hold: &Hold, deadline: DateTime<Utc>) -> Result<Answer, CliError> {
+    let Some(raw) = RawMode::enable() else {
+        let accepted = inquire::Confirm::new("Schedule the job?")
+            .with_default(true)
+            .with_help_message(&format!(
+                "the slot is held until {}",
+                hold.expires_at.format("%H:%M:%S UTC")
+            ))
+            .prompt()
+            .recorded("Schedule the job?")?;
+        return Ok(if clock::now() >= hold.expires_at {
+            Answer::Expired
+        } else if accepted {
+            Answer::Accept
+        } else {
+            Answer::Decline
+        });
+    };
+    let mut stdout = std::io::stdout();
+    let answer = loop {
+        let now = clock::now();
+        if now >= hold.expires_at {
+            break Answer::Expired;
+        }
+        if now >= deadline {
+            break Answer::Unanswered;
+        }
+        let _ = write!(
+            stdout,
+            "\r🔒 Slot held for {:<7} Schedule the job? [Y/n] ",
+            format!("{}.", humanize::duration(hold.expires_at - now))
+        );
+        let _ = stdout.flush();
+        let tick = (hold.expires_at - now)
+            .min(Duration::seconds(1))
+            .to_std()
+            .unwrap_or_default();
+        if !event::poll(tick).context("reading the keyboard")? {
+            continue;
+        }
+        let Event::Key(key) = event::read().context("reading the keyboard")? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                break Answer::Interrupt
+            }
+            KeyCode::Char('y' | 'Y') | KeyCode::Enter => break Answer::Accept,
+            KeyCode::Char('n' | 'N') | KeyCode::Esc => break Answer::Decline,
+            _ => {}
+        }
+    };
+    // Raw mode needs the carriage return spelled out
+    let _ = write!(stdout, "\r\n");
+    drop(raw);
+    Ok(answer)
+}
+
+/// Review a held job: the operator accepts while the countdown runs, and the hold is
+/// confirmed into the job. A hold that lapses meanwhile is taken again; declining,
+/// Ctrl-C and leaving the question unanswered all release it.
+pub async fn review(
+    client: &ApiClient,
+    job: &JobRequest,
+    mut hold: Hold,
+) -> Result<Outcome, CliError> {
+    let deadline = clock::now() + Duration::minutes(REVIEW_TIMEOUT_MIN);
+    loop {
+        let answer = match ask(&hold, deadline) {
+            Ok(answer) => answer,
+            Err(e) => {
+                release(client, &hold).await;
+                return Err(e);
+            }
+        };
+        match answer {
+            Answer::Accept => break,
+            Answer::Decline => {
+                release(client, &hold).await;
+                return Ok(Outcome::NotScheduled);
+            }
+            Answer::Interrupt => {
+                release(client, &hold).await;
+                return Err(inquire::InquireError::OperationInterrupted.into());
+            }
+            Answer::Unanswered => {
+                println!("⌛ No answer within {}m", REVIEW_TIMEOUT_MIN);
+                release(client, &hold).await;
+                return Ok(Outcome::NotScheduled);
+            }
+            Answer::Expired => hold = reacquire(client, job).await?,
+        }
+    }
+
+    let response = match client.confirm_hold(&hold.id).await {
+        // The server's clock ran out before ours did
+        Err(e) if is_lapsed(&e) => {
+            let hold = reacquire(client, job).await?;
+            client.confirm_hold(&hold.id).await
+        }
+        other => other,
+    }?;
+    snapshot::record(job, &response);
+    Ok(Outcome::Scheduled(response))
+}
@@ -0,0 +1,147 @@
+use crate::client::{ApiResponse, JobRequestDTO};
+use crate::error::{CliError, ResultExt};
+use crate::reproducible;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+
+/// How long a single hook may take before it is abandoned
+const HOOK_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Something to notify after a job is scheduled
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Hook {
+    /// POST the created job as JSON to a URL
+    Webhook {
+        url: String,
+        #[serde(default)]
+        headers: BTreeMap<String, String>,
+    },
+    /// Run a shell command with the created job as JSON on stdin
+    Command { command: String },
+}
+
+impl Hook {
+    fn describe(&self) -> String {
+        match self {
+            Hook::Webhook { url, .. } => format!("webhook {}", url),
+            Hook::Command { command } => format!("command `{}`", command),
+        }
+    }
+}
+
+/// Runs the active profile's hooks after each successful submission
+pub struct HookRunner {
+    hooks: Vec<Hook>,
+    profile: String,
+}
+
+impl HookRunner {
+    pub fn new(hooks: Vec<Hook>, profile: &str, enabled: bool) -> Self {
+        Self {
+            hooks: if enabled { hooks } else { Vec::new() },
+            profile: profile.to_string(),
+        }
+    }
+
+    /// Run every hook in order. Failures are printed as warnings and never
+    /// change the outcome of the submission itself.
+    pub async fn after_submit(&self, job: &JobRequestDTO, response: &ApiResponse) {
+        if self.hooks.is_empty() {
+            return;
+        }
+
+        let payload = match created_job_json(job, response) {
+            Ok(payload) => payload,
+            Err(e) => {
+                eprintln!("⚠️ Skipping post-submit hooks: {}", e.full_message());
+                return;
+            }
+        };
+
+        for hook in &self.hooks {
+            let result = match hook {
+                Hook::Webhook { url, headers } => run_webhook(url, headers, &payload).await,
+                Hook::Command { command } => {
+                    self.run_command(command, job, response, &payload).await
+                }
+            };
+
+            if let Err(e) = result {
+                eprintln!("⚠️ Hook {} failed: {}", hook.describe(), e.full_message());
+            }
+        }
+    }
+
+    async fn run_command(
+        &self,
+        command: &str,
+        job: &JobRequestDTO,
+        response: &ApiResponse,
+        payload: &str,
+    ) -> Result<(), CliError> {
+        let mut child = tokio::process::Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .env("RUSTAR_JOB_ID", response.id.as_deref().unwrap_or(""))
+            .env("RUSTAR_SATELLITE", job.label())
+            .env("RUSTAR_JOB_TYPE", job.job_type.name())
+            .env("RUSTAR_START", job.start.to_rfc3339())
+            .env("RUSTAR_END", job.end.to_rfc3339())
+            .env("RUSTAR_PROFILE", &self.profile)
+            .envs(reproducible::child_env().iter().copied())
+            .stdin(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .context("starting hook command")?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            // A hook that ignores stdin closes the pipe early; that is not a failure
+            let _ = stdin.write_all(payload.as_bytes()).await;
+        }
+
+        let status = tokio::time::timeout(HOOK_TIMEOUT, child.wait())
+            .await
+            .map_err(|_| {
+                CliError::Hook(format!("did not finish within {}s", HOOK_TIMEOUT.as_secs()))
+            })?
+            .context("waiting for hook command")?;
+
+        if status.success() {
+            Ok(())
+        } else {
+            Err(CliError::Hook(format!("exited with {}", status)))
+        }
+    }
+}
+
+async fn run_webhook(
+    url: &str,
+    headers: &BTreeMap<String, String>,
+    payload: &str,
+) -> Result<(), CliError> {
+    let client = reqwest::Client::builder().timeout(HOOK_TIMEOUT).build()?;
+    let mut request = client
+        .post(url)
+        .header("Content-Type", "application/json")
+        .body(payload.to_string());
+    for (name, value) in headers {
+        request = request.header(name, value);
+    }
+
+    request.send().await?.error_for_status()?;
+    Ok(())
+}
+
+/// The submitted job with the identifiers the server assigned
+fn created_job_json(job: &JobRequestDTO, response: &ApiResponse) -> Result<String, CliError> {
+    let mut value = serde_json::to_value(job).map_err(|e| CliError::parse("job", e))?;
+    if let Some(object) = value.as_object_mut() {
+        object.insert("id".to_string(), response.id.clone().into());
+        object.insert("status".to_string(), response.status().into());
+    }
+    Ok(value.to_string())
+}
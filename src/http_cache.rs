@@ -0,0 +1,136 @@
+use crate::clock;
+use crate::error::CliError;
+use crate::paths;
+use crate::storage;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// A GET response kept for revalidation with `If-None-Match` / `If-Modified-Since`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Cached {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub content_type: Option<String>,
+    pub body: String,
+    pub stored_at: DateTime<Utc>,
+}
+
+/// Validated GET bodies: in memory for the process, keyed by path under the one base URL,
+/// and on disk between invocations, keyed by full URL, when the profile sets a TTL for that
+pub struct HttpCache {
+    memory: Mutex<HashMap<String, Cached>>,
+    disk_ttl: Option<Duration>,
+    verbose: bool,
+}
+
+/// First path segment, e.g. "jobs" for `/jobs/12`; a write anywhere in it drops the whole family
+fn family(path: &str) -> &str {
+    path.trim_start_matches('/')
+        .split(['/', '?'])
+        .next()
+        .unwrap_or_default()
+}
+
+fn dir() -> PathBuf {
+    paths::cache_dir().join("http")
+}
+
+/// `jobs-1a2b….json`, so a family can be dropped by file name alone
+fn file_name(url: &str, path: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    url.hash(&mut hasher);
+    format!("{}-{:016x}.json", family(path), hasher.finish())
+}
+
+impl HttpCache {
+    pub fn new(disk_ttl: Option<Duration>, verbose: bool) -> Self {
+        Self {
+            memory: Mutex::new(HashMap::new()),
+            disk_ttl: disk_ttl.filter(|ttl| !ttl.is_zero()),
+            verbose,
+        }
+    }
+
+    /// Report cache activity with `--verbose`
+    pub fn note(&self, message: impl AsRef<str>) {
+        if self.verbose {
+            eprintln!("🗄️ {}", message.as_ref());
+        }
+    }
+
+    /// The stored response for `url`, from memory or from a disk entry still within its TTL
+    pub fn get(&self, url: &str, path: &str) -> Option<Cached> {
+        if let Some(cached) = self
+            .memory
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(path)
+        {
+            return Some(cached.clone());
+        }
+        let ttl = chrono::Duration::from_std(self.disk_ttl?).ok()?;
+        let text = std::fs::read_to_string(dir().join(file_name(url, path))).ok()?;
+        let cached: Cached = serde_json::from_str(&text).ok()?;
+        (clock::now() - cached.stored_at < ttl).then_some(cached)
+    }
+
+    /// Remember a response that carries a validator; ones without one cannot be revalidated
+    pub fn store(&self, url: &str, path: &str, cached: Cached) {
+        if cached.etag.is_none() && cached.last_modified.is_none() {
+            return;
+        }
+        if self.disk_ttl.is_some() {
+            let written = serde_json::to_string(&cached)
+                .map_err(|e| CliError::parse("cached response", e))
+                .and_then(|text| storage::write_atomic(&dir().join(file_name(url, path)), text));
+            if let Err(e) = written {
+                self.note(format!(
+                    "could not keep {} on disk: {}",
+                    path,
+                    e.full_message()
+                ));
+            }
+        }
+        self.memory
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(path.to_string(), cached);
+    }
+
+    /// Forget every response in the family of `path`, after a write to it
+    pub fn invalidate(&self, path: &str) {
+        let family = family(path);
+        let dropped = {
+            let mut memory = self.memory.lock().unwrap_or_else(|e| e.into_inner());
+            let before = memory.len();
+            memory.retain(|key, _| self::family(key) != family);
+            before - memory.len()
+        };
+
+        // Also when this process does not use the disk: an earlier one may have
+        if let Ok(entries) = std::fs::read_dir(dir()) {
+            let file_prefix = format!("{}-", family);
+            for entry in entries.flatten() {
+                if entry
+                    .file_name()
+                    .to_string_lossy()
+                    .starts_with(&file_prefix)
+                {
+                    let _ = std::fs::remove_file(entry.path());
+                }
+            }
+        }
+        if dropped > 0 {
+            self.note(format!(
+                "dropped {} cached /{} response(s)",
+                dropped, family
+            ));
+        }
+    }
+}
@@ -0,0 +1,248 @@
+use crate::clock;
+use crate::reproducible;
+use chrono::{DateTime, Duration, Local, Utc};
+use serde::Deserialize;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
+/// Set once from `--utc-only`: skip the local-time rendering everywhere
+static UTC_ONLY: AtomicBool = AtomicBool::new(false);
+
+pub fn set_utc_only(utc_only: bool) {
+    UTC_ONLY.store(utc_only, Ordering::Relaxed);
+}
+
+pub fn utc_only() -> bool {
+    UTC_ONLY.load(Ordering::Relaxed)
+}
+
+/// The unit frequencies are shown in: the profile's `frequency_display`, or `--freq-unit`.
+/// Only what is printed changes; requests, exports and JSON output stay in hertz.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum FrequencyUnit {
+    /// The unit that leaves three to six digits before the point: "145.8 MHz", "14200 kHz"
+    #[default]
+    Auto,
+    Hz,
+    Khz,
+    Mhz,
+}
+
+/// Set once from the profile and `--freq-unit`, as `FrequencyUnit as u8`
+static FREQUENCY_UNIT: AtomicU8 = AtomicU8::new(FrequencyUnit::Auto as u8);
+
+pub fn set_frequency_unit(unit: FrequencyUnit) {
+    FREQUENCY_UNIT.store(unit as u8, Ordering::Relaxed);
+}
+
+fn frequency_unit() -> FrequencyUnit {
+    match FREQUENCY_UNIT.load(Ordering::Relaxed) {
+        u if u == FrequencyUnit::Hz as u8 => FrequencyUnit::Hz,
+        u if u == FrequencyUnit::Khz as u8 => FrequencyUnit::Khz,
+        u if u == FrequencyUnit::Mhz as u8 => FrequencyUnit::Mhz,
+        _ => FrequencyUnit::Auto,
+    }
+}
+
+/// Units from the largest, with their size in hertz
+const UNITS: [(u64, &str); 4] = [
+    (1_000_000_000, "GHz"),
+    (1_000_000, "MHz"),
+    (1_000, "kHz"),
+    (1, "Hz"),
+];
+
+/// `hz` in a unit of `size` hertz, worked out on whole hertz so that 145800000 is
+/// "145.8" and never "145.79999": "145.8 MHz", "14200 kHz", "-500 Hz"
+fn in_unit(hz: f64, (size, unit): (u64, &str)) -> String {
+    let sign = if hz < 0.0 && hz.round() != 0.0 {
+        "-"
+    } else {
+        ""
+    };
+    let whole = hz.abs().round() as u64;
+    let mut text = format!("{}{}", sign, whole / size);
+    if size > 1 && !whole.is_multiple_of(size) {
+        let width = size.ilog10() as usize;
+        let fraction = format!("{:0width$}", whole % size, width = width);
+        text.push('.');
+        text.push_str(fraction.trim_end_matches('0'));
+    }
+    format!("{} {}", text, unit)
+}
+
+/// A frequency as the operator asked to see them; see `FrequencyUnit`
+pub fn frequency(hz: f64) -> String {
+    frequency_in(hz, frequency_unit())
+}
+
+/// A frequency in `unit`, whatever the preference in force
+pub fn frequency_in(hz: f64, unit: FrequencyUnit) -> String {
+    let size = match unit {
+        FrequencyUnit::Hz => UNITS[3],
+        FrequencyUnit::Khz => UNITS[2],
+        FrequencyUnit::Mhz => UNITS[1],
+        FrequencyUnit::Auto => *UNITS
+            .iter()
+            .find(|(size, _)| hz.abs().round() >= (size * 100) as f64)
+            .unwrap_or(&UNITS[3]),
+    };
+    in_unit(hz, size)
+}
+
+/// A difference between frequencies in the largest unit that keeps it above one, as
+/// corrections are spoken of: "2.1 kHz", "500 Hz". Not bound by `FrequencyUnit`.
+pub fn frequency_step(hz: u64) -> String {
+    let size = UNITS
+        .iter()
+        .find(|(size, _)| hz >= *size)
+        .unwrap_or(&UNITS[3]);
+    in_unit(hz as f64, *size)
+}
+
+/// "512.0 B", "1.4 MiB"
+pub fn bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+/// Compact two-unit rendering: "45s", "12m 30s", "3h 12m", "2d 4h"
+/// "4,200"
+pub fn count(n: usize) -> String {
+    let digits = n.to_string();
+    let mut grouped = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+pub fn duration(duration: Duration) -> String {
+    let seconds = duration.num_seconds().abs();
+    let (days, hours, minutes, secs) = (
+        seconds / 86_400,
+        seconds % 86_400 / 3600,
+        seconds % 3600 / 60,
+        seconds % 60,
+    );
+
+    let (major, major_unit, minor, minor_unit) = match (days, hours, minutes) {
+        (0, 0, 0) => return format!("{}s", secs),
+        (0, 0, _) => (minutes, "m", secs, "s"),
+        (0, _, _) => (hours, "h", minutes, "m"),
+        _ => (days, "d", hours, "h"),
+    };
+    if minor == 0 {
+        format!("{}{}", major, major_unit)
+    } else {
+        format!("{}{} {}{}", major, major_unit, minor, minor_unit)
+    }
+}
+
+/// Largest whole unit in words, for things already in the past: "3 hours ago"
+fn ago(elapsed: Duration) -> String {
+    let seconds = elapsed.num_seconds();
+    let (count, unit) = match seconds {
+        s if s < 60 => return "moments ago".to_string(),
+        s if s < 3600 => (s / 60, "minute"),
+        s if s < 86_400 => (s / 3600, "hour"),
+        s => (s / 86_400, "day"),
+    };
+    format!(
+        "{} {}{} ago",
+        count,
+        unit,
+        if count == 1 { "" } else { "s" }
+    )
+}
+
+/// Where a window stands relative to `now`: "starts in 3h 12m", "ends in 5m", "ended 2 days ago".
+/// Under `--reproducible` the times are given instead: "ends at 2026-03-01 10:00:00 UTC".
+pub fn relative_to(start: DateTime<Utc>, end: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let absolute = reproducible::enabled();
+    if now < start {
+        match absolute {
+            true => format!("starts at {}", timestamp(start)),
+            false => format!("starts in {}", duration(start - now)),
+        }
+    } else if now < end {
+        match absolute {
+            true => format!("running, ends at {}", timestamp(end)),
+            false => format!("running, ends in {}", duration(end - now)),
+        }
+    } else {
+        match absolute {
+            true => format!("ended at {}", timestamp(end)),
+            false => format!("ended {}", ago(now - end)),
+        }
+    }
+}
+
+/// How long before `now` something happened at `at`: "12m ago", or "at 2026-03-01
+/// 10:00:00 UTC" under `--reproducible`
+pub fn since(at: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    match reproducible::enabled() {
+        true => format!("at {}", timestamp(at)),
+        false => format!("{} ago", duration((now - at).max(Duration::zero()))),
+    }
+}
+
+/// How long after `now` something happens at `at`: "in 3m", or "at 2026-03-01 10:00:00
+/// UTC" under `--reproducible`
+pub fn until(at: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    match reproducible::enabled() {
+        true => format!("at {}", timestamp(at)),
+        false => format!("in {}", duration(at - now)),
+    }
+}
+
+/// Relative position and length of a window: "starts in 3h 12m, duration 12m 30s"
+pub fn window(start: DateTime<Utc>, end: DateTime<Utc>) -> String {
+    format!(
+        "{}, duration {}",
+        relative_to(start, end, clock::now()),
+        duration(end - start)
+    )
+}
+
+/// "%Y-%m-%d %H:%M:%S", with ".%3f" when `at` is not on a whole second
+fn seconds_format(at: DateTime<Utc>) -> &'static str {
+    if at.timestamp_subsec_millis() == 0 {
+        "%Y-%m-%d %H:%M:%S"
+    } else {
+        "%Y-%m-%d %H:%M:%S%.3f"
+    }
+}
+
+/// The same instant on the operator's wall clock, unless `--utc-only` is set
+pub fn local(at: DateTime<Utc>) -> Option<String> {
+    if utc_only() {
+        return None;
+    }
+    Some(format!(
+        "{} {}",
+        at.with_timezone(&Local).format(seconds_format(at)),
+        at.with_timezone(&Local).format("%:z")
+    ))
+}
+
+/// UTC timestamp followed by its local rendering when that is wanted and differs.
+/// Milliseconds are shown only when there are any.
+pub fn timestamp(at: DateTime<Utc>) -> String {
+    let utc = at.format(seconds_format(at)).to_string() + " UTC";
+    match local(at) {
+        Some(local) if at.with_timezone(&Local).offset().local_minus_utc() != 0 => {
+            format!("{} ({} local)", utc, local)
+        }
+        _ => utc,
+    }
+}
@@ -0,0 +1,433 @@
+use crate::client::{JobRequestDTO, JobType, TleData};
+use crate::error::{CliError, ResultExt};
+use crate::frequency::Frequency;
+use crate::job_request::JobRequestBuilder;
+use crate::tle;
+use crate::transponder::Passband;
+use crate::validation::Problem;
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Window applied to entries whose source carries no schedule (gpredict files)
+#[derive(Debug, Clone, Copy)]
+pub struct Window {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+/// A job built from an external file, plus the source fields that had no mapping
+pub struct Imported {
+    pub source: String,
+    pub job: JobRequestDTO,
+    pub unmapped: Vec<String>,
+}
+
+/// Parsed `key=value` sections of a gpredict `.sat` / `.trsp` file
+type IniSections = Vec<(String, BTreeMap<String, String>)>;
+
+fn parse_ini(text: &str) -> IniSections {
+    let mut sections: IniSections = Vec::new();
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            sections.push((name.trim().to_string(), BTreeMap::new()));
+        } else if let Some((key, value)) = line.split_once('=') {
+            if sections.is_empty() {
+                sections.push((String::new(), BTreeMap::new()));
+            }
+            if let Some((_, entries)) = sections.last_mut() {
+                entries.insert(key.trim().to_uppercase(), value.trim().to_string());
+            }
+        }
+    }
+    sections
+}
+
+fn read(path: &Path) -> Result<String, CliError> {
+    std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))
+}
+
+/// Keys of a gpredict `.sat` file that end up in the job (or are safe to ignore)
+const SAT_KEYS: &[&str] = &["VERSION", "NAME", "NICKNAME", "TLE1", "TLE2"];
+/// Keys of a gpredict transponder section that end up in the job
+const TRSP_KEYS: &[&str] = &[
+    "UP_LOW",
+    "UP_HIGH",
+    "DOWN_LOW",
+    "DOWN_HIGH",
+    "INVERT",
+    "MODE",
+];
+
+/// Frequencies and mode picked from a gpredict transponder file
+struct Transponder {
+    name: String,
+    downlink: Frequency,
+    uplink: Frequency,
+    mode: Option<String>,
+    unmapped: Vec<String>,
+}
+
+/// The uplink to pair with `downlink`: through the passband when both edges of each
+/// band are known, or the lower uplink edge as given when they are not
+fn linked_uplink(
+    downlink: Frequency,
+    downlink_band: (Frequency, Frequency),
+    uplink_band: (Frequency, Frequency),
+    inverting: bool,
+) -> Result<Frequency, Problem> {
+    let edges = [
+        downlink_band.0,
+        downlink_band.1,
+        uplink_band.0,
+        uplink_band.1,
+    ];
+    if downlink.is_zero() || edges.iter().any(|edge| edge.is_zero()) {
+        return Ok(uplink_band.0);
+    }
+    Passband::new(downlink_band, uplink_band, inverting).uplink_for(downlink)
+}
+
+fn load_transponder(path: &Path, wanted: Option<&str>) -> Result<Transponder, CliError> {
+    let sections = parse_ini(&read(path)?);
+    let mut unmapped = Vec::new();
+
+    let position = match wanted {
+        Some(name) => sections
+            .iter()
+            .position(|(section, _)| section.eq_ignore_ascii_case(name))
+            .ok_or_else(|| {
+                CliError::validation(
+                    "transponder",
+                    format!(
+                        "{} has no transponder '{}' (available: {})",
+                        path.display(),
+                        name,
+                        sections
+                            .iter()
+                            .map(|(s, _)| s.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                )
+            })?,
+        None => 0,
+    };
+    let (name, keys) = sections.get(position).ok_or_else(|| {
+        CliError::validation("transponder", format!("{} is empty", path.display()))
+    })?;
+
+    for (other, _) in sections.iter().filter(|(s, _)| s != name) {
+        unmapped.push(format!("[{}] (unused transponder)", other));
+    }
+    for key in keys.keys().filter(|k| !TRSP_KEYS.contains(&k.as_str())) {
+        unmapped.push(format!("[{}] {}", name, key));
+    }
+
+    let frequency = |key: &str| -> Result<Frequency, CliError> {
+        match keys.get(key).map(String::as_str) {
+            None | Some("") | Some("0") => Ok(Frequency::ZERO),
+            Some(value) => Frequency::parse(value)
+                .with_context(|| format!("{} [{}] {}", path.display(), name, key)),
+        }
+    };
+
+    let downlink = frequency("DOWN_LOW")?;
+    let inverting = keys
+        .get("INVERT")
+        .is_some_and(|v| v.eq_ignore_ascii_case("true") || v == "1");
+    let uplink = linked_uplink(
+        downlink,
+        (downlink, frequency("DOWN_HIGH")?),
+        (frequency("UP_LOW")?, frequency("UP_HIGH")?),
+        inverting,
+    )
+    .map_err(CliError::from)
+    .with_context(|| format!("{} [{}]", path.display(), name))?;
+
+    Ok(Transponder {
+        name: name.clone(),
+        downlink,
+        uplink,
+        mode: keys.get("MODE").cloned().filter(|m| !m.is_empty()),
+        unmapped,
+    })
+}
+
+fn load_sat(path: &Path) -> Result<(TleData, Vec<String>), CliError> {
+    let sections = parse_ini(&read(path)?);
+    let (_, keys) = sections
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("Satellite"))
+        .or_else(|| sections.first())
+        .ok_or_else(|| CliError::validation("sat", format!("{} is empty", path.display())))?;
+
+    let get = |key: &str| {
+        keys.get(key)
+            .cloned()
+            .ok_or_else(|| CliError::validation(key, format!("missing from {}", path.display())))
+    };
+    let tle = TleData {
+        tle0: keys
+            .get("NAME")
+            .or_else(|| keys.get("NICKNAME"))
+            .cloned()
+            .unwrap_or_default(),
+        tle1: get("TLE1")?,
+        tle2: get("TLE2")?,
+    };
+
+    let unmapped = keys
+        .keys()
+        .filter(|k| !SAT_KEYS.contains(&k.as_str()))
+        .cloned()
+        .collect();
+    Ok((tle, unmapped))
+}
+
+/// NORAD catalog number a gpredict file is named after (`25544.sat`, `25544.trsp`)
+fn norad_from_stem(path: &Path) -> Option<u32> {
+    path.file_stem()?.to_str()?.parse().ok()
+}
+
+/// Import gpredict `.sat` files (TLE) with their matching `.trsp` files (frequencies).
+/// A `.trsp` without a `.sat` gets its TLE from the fetcher/cache by file name.
+async fn import_gpredict(
+    sats: &[PathBuf],
+    transponders: &[PathBuf],
+    window: Option<Window>,
+    transponder: Option<&str>,
+) -> Result<Vec<Imported>, CliError> {
+    let window = window.ok_or_else(|| {
+        CliError::validation(
+            "start",
+            "gpredict files carry no schedule; pass --start with --end or --duration",
+        )
+    })?;
+
+    let mut satellites = Vec::new();
+    for path in sats {
+        let (tle, unmapped) = load_sat(path)?;
+        satellites.push((path.clone(), tle, unmapped));
+    }
+    for path in transponders {
+        let norad = norad_from_stem(path);
+        let matched = satellites
+            .iter()
+            .any(|(_, tle, _)| norad.is_some() && tle::norad_id(&tle.tle1) == norad);
+        if matched {
+            continue;
+        }
+        let norad = norad.ok_or_else(|| {
+            CliError::validation(
+                "trsp",
+                format!(
+                    "{} has no matching .sat file and is not named after a NORAD ID",
+                    path.display()
+                ),
+            )
+        })?;
+        let tle = tle::resolve(norad).await?;
+        satellites.push((path.clone(), tle, Vec::new()));
+    }
+
+    let mut imported = Vec::new();
+    for (path, tle, mut unmapped) in satellites {
+        let catalog = tle::norad_id(&tle.tle1);
+        let trsp = transponders
+            .iter()
+            .find(|t| catalog.is_some() && norad_from_stem(t) == catalog);
+
+        let (rx, tx, mode, used) = match trsp {
+            Some(trsp_path) => {
+                let t = load_transponder(trsp_path, transponder)?;
+                unmapped.extend(t.unmapped);
+                (t.downlink, t.uplink, t.mode, Some(t.name))
+            }
+            None => (Frequency::ZERO, Frequency::ZERO, None, None),
+        };
+
+        let source = match used {
+            Some(name) => format!("{} [{}]", path.display(), name),
+            None => path.display().to_string(),
+        };
+        imported.push(Imported {
+            source,
+            job: JobRequestBuilder::new(window.start, window.end, JobType::Tracking { tle })
+                .rx_frequency(rx)
+                .tx_frequency(tx)
+                .mode(mode)
+                .into_draft(),
+            unmapped,
+        });
+    }
+
+    Ok(imported)
+}
+
+/// Fields of a SatNOGS observation that end up in the job
+const SATNOGS_MAPPED: &[&str] = &[
+    "start",
+    "end",
+    "tle0",
+    "tle1",
+    "tle2",
+    "norad_cat_id",
+    "observation_frequency",
+    "transmitter_downlink_low",
+    "transmitter_downlink_high",
+    "transmitter_uplink_low",
+    "transmitter_uplink_high",
+    "transmitter_invert",
+    "transmitter_mode",
+];
+
+/// Import a SatNOGS Network observation export (a JSON array, or a single object)
+async fn import_satnogs(path: &Path) -> Result<Vec<Imported>, CliError> {
+    let value: serde_json::Value = serde_json::from_str(&read(path)?)
+        .map_err(|e| CliError::parse(format!("SatNOGS export {}", path.display()), e))?;
+    let observations = match value {
+        serde_json::Value::Array(items) => items,
+        other => vec![other],
+    };
+
+    let mut imported = Vec::new();
+    for (index, observation) in observations.iter().enumerate() {
+        let source = format!("{} #{}", path.display(), index);
+        let object = observation.as_object().ok_or_else(|| {
+            CliError::validation(source.clone(), "observation is not a JSON object")
+        })?;
+
+        let text = |key: &str| object.get(key).and_then(|v| v.as_str()).map(str::to_string);
+        let number = |key: &str| object.get(key).and_then(|v| v.as_f64());
+        let frequency = |key: &str| number(key).and_then(Frequency::from_f64);
+        let datetime = |key: &str| -> Result<DateTime<Utc>, CliError> {
+            let value = text(key).ok_or_else(|| {
+                CliError::validation(format!("{} {}", source, key), "missing or not a string")
+            })?;
+            crate::parse::datetime(&value).with_context(|| format!("{} {}", source, key))
+        };
+
+        let tle = match (text("tle1"), text("tle2")) {
+            (Some(tle1), Some(tle2)) => TleData {
+                tle0: text("tle0").unwrap_or_default(),
+                tle1,
+                tle2,
+            },
+            _ => {
+                let norad = number("norad_cat_id").ok_or_else(|| {
+                    CliError::validation(
+                        source.clone(),
+                        "observation has neither TLE lines nor norad_cat_id",
+                    )
+                })?;
+                tle::resolve(norad as u32).await?
+            }
+        };
+
+        let rx = frequency("observation_frequency")
+            .or_else(|| frequency("transmitter_downlink_low"))
+            .unwrap_or_default();
+        let band = |low: &str, high: &str| {
+            (
+                frequency(low).unwrap_or_default(),
+                frequency(high).unwrap_or_default(),
+            )
+        };
+        let tx = linked_uplink(
+            rx,
+            band("transmitter_downlink_low", "transmitter_downlink_high"),
+            band("transmitter_uplink_low", "transmitter_uplink_high"),
+            object
+                .get("transmitter_invert")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+        )
+        .map_err(CliError::from)
+        .with_context(|| source.clone())?;
+
+        let mut unmapped: Vec<String> = object
+            .keys()
+            .filter(|k| !SATNOGS_MAPPED.contains(&k.as_str()))
+            .cloned()
+            .collect();
+        unmapped.sort();
+
+        let start = datetime("start")?;
+        let end = datetime("end")?;
+        imported.push(Imported {
+            source,
+            job: JobRequestBuilder::new(start, end, JobType::Tracking { tle })
+                .rx_frequency(rx)
+                .tx_frequency(tx)
+                .mode(text("transmitter_mode"))
+                .into_draft(),
+            unmapped,
+        });
+    }
+
+    Ok(imported)
+}
+
+/// Import every file, dispatching on extension: `.sat`/`.trsp` for gpredict,
+/// `.json` for SatNOGS observation exports
+pub async fn import_files(
+    files: &[PathBuf],
+    window: Option<Window>,
+    transponder: Option<&str>,
+) -> Result<Vec<Imported>, CliError> {
+    let mut sats = Vec::new();
+    let mut transponders = Vec::new();
+    let mut imported = Vec::new();
+
+    for path in files {
+        match path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_lowercase)
+            .as_deref()
+        {
+            Some("sat") => sats.push(path.clone()),
+            Some("trsp") => transponders.push(path.clone()),
+            Some("json") => imported.extend(import_satnogs(path).await?),
+            _ => {
+                return Err(CliError::validation(
+                    "file",
+                    format!(
+                        "{}: unsupported file type (expected .sat, .trsp or SatNOGS .json)",
+                        path.display()
+                    ),
+                ))
+            }
+        }
+    }
+
+    if !sats.is_empty() || !transponders.is_empty() {
+        imported.extend(import_gpredict(&sats, &transponders, window, transponder).await?);
+    }
+
+    Ok(imported)
+}
+
+/// List the source fields imported jobs left behind, once per distinct set
+pub fn report_unmapped(imported: &[Imported]) {
+    let mut groups: BTreeMap<&[String], Vec<&str>> = BTreeMap::new();
+    for item in imported.iter().filter(|i| !i.unmapped.is_empty()) {
+        groups
+            .entry(item.unmapped.as_slice())
+            .or_default()
+            .push(&item.source);
+    }
+
+    for (fields, sources) in groups {
+        let from = match sources.as_slice() {
+            [single] => single.to_string(),
+            [first, ..] => format!("{} and {} more", first, sources.len() - 1),
+            [] => continue,
+        };
+        eprintln!("ℹ️ Not mapped to the job ({}): {}", from, fields.join(", "));
+    }
+}
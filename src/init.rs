@@ -0,0 +1,217 @@
+use crate::base_url;
+use crate::client::{self, ApiClient, Timeouts};
+use crate::config::{self, Config, Profile};
+use crate::error::CliError;
+use crate::location::{self, Location};
+use crate::stats;
+use crate::transcript::Recorded;
+use crate::tty;
+use inquire::{Confirm, Text};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Settings a first profile is set up with; each is left as it is when not given
+#[derive(Debug, Clone, Default)]
+pub struct Answers {
+    pub base_url: Option<String>,
+    pub location: Option<Location>,
+    pub timezone: Option<String>,
+}
+
+/// `--timezone` value parser: kept as typed, once it is known to be a timezone
+pub fn parse_timezone(input: &str) -> Result<String, String> {
+    stats::parse_timezone(input).map(|_| input.trim().to_string())
+}
+
+/// The profile init writes: `--profile`, then RUSTAR_PROFILE, then the configured default.
+/// Unlike every other command, it may name a profile that does not exist yet.
+fn profile_name(requested: Option<&str>, config: &Config) -> String {
+    requested
+        .map(str::to_string)
+        .or_else(|| std::env::var(config::PROFILE_ENV).ok())
+        .or_else(|| config.default_profile.clone())
+        .unwrap_or_else(|| config::DEFAULT_PROFILE.to_string())
+}
+
+/// Whether `url` answers `GET /health`, within the usual reachability limit
+async fn check_reachable(url: &str) -> Result<(), CliError> {
+    let timeouts = Timeouts {
+        request: Duration::from_secs(client::DEFAULT_TIMEOUT_SECONDS),
+        explicit: false,
+        idle: Duration::from_secs(client::DEFAULT_IDLE_TIMEOUT_SECONDS),
+    };
+    ApiClient::builder(url, timeouts).build()?.ping().await
+}
+
+/// Ask for the base URL until it answers, or the operator keeps one that does not
+async fn ask_base_url(default: &str) -> Result<String, CliError> {
+    loop {
+        let url = Text::new("API base URL:")
+            .with_default(default)
+            .with_validator(|input: &str| {
+                Ok(match base_url::parse(input) {
+                    Ok(_) => inquire::validator::Validation::Valid,
+                    Err(e) => inquire::validator::Validation::Invalid(e.full_message().into()),
+                })
+            })
+            .prompt()
+            .recorded("API base URL:")?;
+        let url = base_url::display(&base_url::parse(&url)?);
+        match check_reachable(&url).await {
+            Ok(()) => {
+                println!("✅ {} is reachable", url);
+                return Ok(url);
+            }
+            Err(e) => {
+                eprintln!("⚠️ Could not reach {}: {}", url, e.full_message());
+                if let Some(hint) = e.hint() {
+                    eprintln!("💡 {}", hint);
+                }
+                if Confirm::new("Keep this URL anyway?")
+                    .with_default(false)
+                    .prompt()
+                    .recorded("Keep this URL anyway?")?
+                {
+                    return Ok(url);
+                }
+            }
+        }
+    }
+}
+
+/// Prompt for every setting, offering what was given on the command line or is
+/// already in the profile
+async fn ask(given: Answers, existing: &Profile) -> Result<Answers, CliError> {
+    let url = given
+        .base_url
+        .or_else(|| existing.base_url.clone())
+        .unwrap_or_else(|| client::DEFAULT_BASE_URL.to_string());
+    let base_url = ask_base_url(&url).await?;
+
+    let current = given.location.map(|l| l.to_string()).or_else(|| {
+        existing
+            .latitude
+            .zip(existing.longitude)
+            .map(|(lat, lon)| format!("{},{},{}", lat, lon, existing.altitude_m.unwrap_or(0.0)))
+    });
+    let question = "Station coordinates (lat,lon[,alt m]; blank to skip):";
+    let mut prompt = Text::new(question).with_validator(|input: &str| {
+        if input.trim().is_empty() {
+            return Ok(inquire::validator::Validation::Valid);
+        }
+        Ok(match location::parse(input) {
+            Ok(_) => inquire::validator::Validation::Valid,
+            Err(e) => inquire::validator::Validation::Invalid(e.full_message().into()),
+        })
+    });
+    if let Some(current) = &current {
+        prompt = prompt.with_default(current);
+    }
+    let coordinates = prompt.prompt().recorded(question)?;
+    let location = match coordinates.trim() {
+        "" => None,
+        input => Some(location::parse(input)?),
+    };
+
+    let zone = given
+        .timezone
+        .or_else(|| existing.timezone.clone())
+        .unwrap_or_else(|| "local".to_string());
+    let question = "Timezone for day boundaries (utc, local or an offset like +02:00):";
+    let timezone = Text::new(question)
+        .with_default(&zone)
+        .with_validator(|input: &str| {
+            Ok(match parse_timezone(input) {
+                Ok(_) => inquire::validator::Validation::Valid,
+                Err(reason) => inquire::validator::Validation::Invalid(reason.into()),
+            })
+        })
+        .prompt()
+        .recorded(question)?;
+
+    Ok(Answers {
+        base_url: Some(base_url),
+        location,
+        timezone: Some(timezone.trim().to_string()),
+    })
+}
+
+/// Write the answers into `profile`, leaving every other profile and setting alone
+fn save(profile: &str, answers: &Answers) -> Result<PathBuf, CliError> {
+    config::update_profile(profile, true, |entry| {
+        if let Some(url) = &answers.base_url {
+            entry.insert("base_url".into(), url.clone().into());
+        }
+        if let Some(location) = &answers.location {
+            config::insert_location(entry, location);
+        }
+        if let Some(zone) = &answers.timezone {
+            entry.insert("timezone".into(), zone.clone().into());
+        }
+    })
+}
+
+/// `init`: set up a profile with prompts, or straight from the flags with
+/// `non_interactive` (the base URL is still checked, but only warned about)
+pub async fn run(
+    requested: Option<&str>,
+    given: Answers,
+    non_interactive: bool,
+) -> Result<(), CliError> {
+    let config = Config::load()?;
+    let profile = profile_name(requested, &config);
+    let existing = config.profiles.get(&profile).cloned().unwrap_or_default();
+
+    let answers = if non_interactive {
+        // An unreachable server is saved with a warning; a URL that cannot work is not
+        let given = Answers {
+            base_url: match &given.base_url {
+                Some(url) => Some(base_url::display(&base_url::parse(url)?)),
+                None => None,
+            },
+            ..given
+        };
+        if let Some(url) = &given.base_url {
+            if let Err(e) = check_reachable(url).await {
+                eprintln!(
+                    "⚠️ Could not reach {}, saving it anyway: {}",
+                    url,
+                    e.full_message()
+                );
+            }
+        }
+        given
+    } else {
+        tty::require("init", &["--non-interactive"])?;
+        println!(
+            "👋 Setting up profile '{}' in {}",
+            profile,
+            config::config_path().display()
+        );
+        ask(given, &existing).await?
+    };
+
+    let path = save(&profile, &answers)?;
+    println!("💾 Saved profile '{}' in {}", profile, path.display());
+    Ok(())
+}
+
+/// On a first run with nothing configured, offer to set up the profile the command is
+/// about to use, then let the command carry on with it
+pub async fn first_run(requested: Option<&str>) -> Result<(), CliError> {
+    let question = format!(
+        "No configuration found at {}. Set up a profile now?",
+        config::config_path().display()
+    );
+    if !Confirm::new(&question)
+        .with_default(true)
+        .prompt()
+        .recorded(&question)?
+    {
+        eprintln!("💡 rustar-cli init sets one up whenever you are ready");
+        return Ok(());
+    }
+    run(requested, Answers::default(), false).await?;
+    println!();
+    Ok(())
+}
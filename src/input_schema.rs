@@ -0,0 +1,199 @@
+use crate::batch::{self, BatchFormat};
+use crate::client::JobRequestDTO;
+use crate::error::CliError;
+use crate::regulatory::TxLimits;
+use crate::validation::Problem;
+use jsonschema::error::ValidationErrorKind;
+use jsonschema::ValidationError;
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Field names older job files used, still read: (field, old name)
+const LEGACY_NAMES: &[(&str, &str)] = &[("rx_frequency", "rx_freq"), ("tx_frequency", "tx_freq")];
+
+/// Inputs the CLI publishes a JSON Schema for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum InputKind {
+    /// A `jobs add --batch` file: a JSON array of jobs
+    JobsBatch,
+    /// A single job, as `simulate --file` reads it
+    Job,
+}
+
+/// The schema of `kind`, generated from the types the file is read into
+pub fn schema(kind: InputKind) -> serde_json::Value {
+    let generator = schemars::generate::SchemaSettings::draft2020_12()
+        .for_deserialize()
+        .into_generator();
+    let schema = match kind {
+        InputKind::JobsBatch => generator.into_root_schema_for::<Vec<JobRequestDTO>>(),
+        InputKind::Job => generator.into_root_schema_for::<JobRequestDTO>(),
+    };
+    schema.to_value()
+}
+
+/// Let a job spell a field the way older files did: the old name is listed as
+/// deprecated, and either name satisfies the requirement
+pub fn legacy_names(schema: &mut schemars::Schema) {
+    for (field, old) in LEGACY_NAMES {
+        let Some(property) = schema.pointer(&format!("/properties/{}", field)).cloned() else {
+            continue;
+        };
+        let mut deprecated = property;
+        if let Some(fields) = deprecated.as_object_mut() {
+            fields.insert("deprecated".to_string(), true.into());
+        }
+        if let Some(properties) = schema.get_mut("properties").and_then(|p| p.as_object_mut()) {
+            properties.insert(old.to_string(), deprecated);
+        }
+        if let Some(required) = schema.get_mut("required").and_then(|r| r.as_array_mut()) {
+            required.retain(|name| name != field);
+        }
+        let either = serde_json::json!({
+            "anyOf": [{ "required": [field] }, { "required": [old] }],
+        });
+        match schema.get_mut("allOf").and_then(|a| a.as_array_mut()) {
+            Some(all) => all.push(either),
+            None => {
+                schema.insert("allOf".to_string(), serde_json::json!([either]));
+            }
+        }
+    }
+}
+
+/// Every field a job of any type may have, without the legacy spellings
+pub fn job_fields() -> BTreeSet<String> {
+    fn collect(schema: &serde_json::Value, fields: &mut BTreeSet<String>) {
+        if let Some(properties) = schema.get("properties").and_then(|p| p.as_object()) {
+            fields.extend(
+                properties
+                    .iter()
+                    .filter(|(_, property)| property.get("deprecated") != Some(&true.into()))
+                    .map(|(name, _)| name.clone()),
+            );
+        }
+        // The fields of each job type sit in the branches taken on `job_type`
+        for branch in ["then", "else"] {
+            if let Some(inner) = schema.get(branch) {
+                collect(inner, fields);
+            }
+        }
+    }
+
+    let mut fields = BTreeSet::new();
+    collect(&schema(InputKind::Job), &mut fields);
+    fields
+}
+
+pub fn print(kind: InputKind) -> Result<(), CliError> {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&schema(kind)).map_err(|e| CliError::parse("schema", e))?
+    );
+    Ok(())
+}
+
+fn job_validator() -> &'static jsonschema::Validator {
+    static VALIDATOR: OnceLock<jsonschema::Validator> = OnceLock::new();
+    VALIDATOR.get_or_init(|| {
+        jsonschema::options()
+            .should_validate_formats(true)
+            .build(&schema(InputKind::Job))
+            .expect("the generated job schema is a valid JSON Schema")
+    })
+}
+
+/// The JSON pointer of the field an error is about: the one missing or not expected,
+/// rather than the object holding it
+fn pointer(error: &ValidationError) -> String {
+    let at = error.instance_path().as_str();
+    match error.kind() {
+        ValidationErrorKind::Required { property } => {
+            format!("{}/{}", at, property.as_str().unwrap_or_default())
+        }
+        ValidationErrorKind::UnevaluatedProperties { unexpected }
+        | ValidationErrorKind::AdditionalProperties { unexpected }
+            if unexpected.len() == 1 =>
+        {
+            format!("{}/{}", at, unexpected[0])
+        }
+        _ if at.is_empty() => "/".to_string(),
+        _ => at.to_string(),
+    }
+}
+
+/// Where and why `error` fails, read through "none of the alternatives matched": a choice
+/// of constants is listed, and otherwise the alternative that came closest speaks
+fn explain(error: &ValidationError) -> (String, String) {
+    let branches = match error.kind() {
+        ValidationErrorKind::AnyOf { context } | ValidationErrorKind::OneOfNotValid { context } => {
+            context
+        }
+        _ => return (pointer(error), error.masked().to_string()),
+    };
+    let mut allowed = Vec::new();
+    let only_constants = branches.iter().flatten().all(|e| match e.kind() {
+        ValidationErrorKind::Constant { expected_value } => {
+            allowed.push(expected_value.to_string());
+            true
+        }
+        // An optional field may also be null
+        ValidationErrorKind::Type { .. } => true,
+        _ => false,
+    });
+    if only_constants && !allowed.is_empty() {
+        allowed.dedup();
+        return (
+            pointer(error),
+            format!("value is not one of {}", allowed.join(", ")),
+        );
+    }
+    match branches
+        .iter()
+        .filter(|b| !b.is_empty())
+        .min_by_key(|b| b.len())
+    {
+        Some(closest) => explain(&closest[0]),
+        None => (pointer(error), error.masked().to_string()),
+    }
+}
+
+/// Every way `job` departs from the job schema, each named by the JSON pointer of the
+/// field at fault: "/tle/tle1", or "/polarisation" for a field the schema does not know
+pub fn check_job(job: &serde_json::Value) -> Vec<Problem> {
+    job_validator()
+        .iter_errors(job)
+        .map(|error| {
+            let (field, reason) = explain(&error);
+            Problem::new(field, reason)
+        })
+        .collect()
+}
+
+/// `schema --validate-only`: read `path` as `kind` and run every check a submission
+/// would, without sending anything. Returns the number of entries with problems.
+pub async fn validate_file(
+    kind: InputKind,
+    path: &Path,
+    limits: &TxLimits,
+) -> Result<usize, CliError> {
+    let values = match kind {
+        InputKind::JobsBatch => batch::load_values(path, BatchFormat::detect(path)).await?,
+        InputKind::Job => vec![crate::simulate::read_value(path)?],
+    };
+    let entries = batch::preflight(values, limits);
+    let invalid = entries.iter().filter(|e| !e.problems.is_empty()).count();
+    if invalid == 0 {
+        println!("✅ {}: {} job(s), all valid", path.display(), entries.len());
+    } else {
+        eprintln!(
+            "❌ {}: {} of {} job(s) have problems:",
+            path.display(),
+            invalid,
+            entries.len()
+        );
+        batch::print_problems(&entries);
+    }
+    Ok(invalid)
+}
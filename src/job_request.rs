@@ -0,0 +1,303 @@
+use crate::client::{JobRequestDTO, JobType, Polarization, PostPassAction};
+use crate::error::CliError;
+use crate::frequency::Frequency;
+use crate::quiet_hours;
+use crate::regulatory::{self, TxLimits};
+use crate::snap;
+use crate::validation::{self, Problem};
+use chrono::{DateTime, Utc};
+use std::ops::Deref;
+
+/// A job that has passed every check `JobRequestBuilder::build` makes. The client only
+/// submits these, so no path to the server can skip validation.
+#[derive(Debug, Clone)]
+pub struct JobRequest(JobRequestDTO);
+
+impl Deref for JobRequest {
+    type Target = JobRequestDTO;
+
+    fn deref(&self) -> &JobRequestDTO {
+        &self.0
+    }
+}
+
+/// Puts a job together field by field and checks it as a whole at `build`: the window
+/// ordering, the parameters its type needs, frequency ranges, transmit settings and,
+/// when given, the licence limits of the profile
+#[derive(Debug, Clone)]
+pub struct JobRequestBuilder {
+    job: JobRequestDTO,
+    limits: Option<TxLimits>,
+}
+
+impl JobRequestBuilder {
+    /// A receive-only job in `[start, end)`, every optional setting left to the station
+    pub fn new(start: DateTime<Utc>, end: DateTime<Utc>, job_type: JobType) -> Self {
+        Self {
+            job: JobRequestDTO {
+                start,
+                end,
+                job_type,
+                rx_frequency: Frequency::ZERO,
+                rx_offset_hz: None,
+                tx_frequency: Frequency::ZERO,
+                mode: None,
+                baud: None,
+                tx_power_dbm: None,
+                tx_duty_cycle: None,
+                polarization: None,
+                post_pass_action: None,
+                campaign: None,
+            },
+            limits: None,
+        }
+    }
+
+    /// The published downlink; an offset set before or after is added to it
+    pub fn rx_frequency(mut self, hz: Frequency) -> Self {
+        self.job.rx_frequency = hz.shifted(self.job.rx_offset_hz.unwrap_or(0));
+        self
+    }
+
+    /// Listen `offset` hertz off the published downlink, in place of any offset the job
+    /// had; none takes the offset away
+    pub fn rx_offset(mut self, offset: Option<i64>) -> Self {
+        let published = self.job.published_rx();
+        self.job.rx_offset_hz = offset;
+        self.rx_frequency(published)
+    }
+
+    /// Zero keeps the job receive-only
+    pub fn tx_frequency(mut self, hz: Frequency) -> Self {
+        self.job.tx_frequency = hz;
+        self
+    }
+
+    pub fn mode(mut self, mode: Option<String>) -> Self {
+        self.job.mode = mode;
+        self
+    }
+
+    pub fn baud(mut self, baud: Option<u32>) -> Self {
+        self.job.baud = baud;
+        self
+    }
+
+    pub fn tx_power_dbm(mut self, power: Option<f64>) -> Self {
+        self.job.tx_power_dbm = power;
+        self
+    }
+
+    pub fn tx_duty_cycle(mut self, duty: Option<f64>) -> Self {
+        self.job.tx_duty_cycle = duty;
+        self
+    }
+
+    pub fn polarization(mut self, polarization: Option<Polarization>) -> Self {
+        self.job.polarization = polarization;
+        self
+    }
+
+    pub fn post_pass_action(mut self, action: Option<PostPassAction>) -> Self {
+        self.job.post_pass_action = action;
+        self
+    }
+
+    pub fn campaign(mut self, campaign: Option<String>) -> Self {
+        self.job.campaign = campaign;
+        self
+    }
+
+    /// Hold the job to the profile's licence: a TX job without a power gets the
+    /// default one now, and `build` rejects what the limits do not allow
+    pub fn tx_limits(mut self, limits: &TxLimits) -> Self {
+        regulatory::apply_defaults(&mut self.job, limits);
+        self.limits = Some(*limits);
+        self
+    }
+
+    /// Start and end as they stand
+    pub fn times(&self) -> (DateTime<Utc>, DateTime<Utc>) {
+        (self.job.start, self.job.end)
+    }
+
+    /// Move the window onto the station's grid (the profile's `snap`); `build` does
+    /// this too, so a job is only sent on the grid
+    pub fn snapped(mut self) -> Self {
+        (self.job.start, self.job.end) = snap::window(self.job.start, self.job.end);
+        self
+    }
+
+    /// The job as it stands, unchecked: for batch entries, which are checked when read back
+    pub fn into_draft(self) -> JobRequestDTO {
+        self.job
+    }
+
+    /// Every rule the job breaks, in the order they are checked
+    pub fn problems(&self) -> Vec<Problem> {
+        let mut problems = validation::check_job(&self.job);
+        if let Some(limits) = &self.limits {
+            problems.extend(regulatory::check_limits(&self.job, limits));
+        }
+        problems.extend(quiet_hours::check(&self.job));
+        problems
+    }
+
+    /// The checked job on the station's grid, or an error naming each field and the
+    /// rule it breaks
+    pub fn build(self) -> Result<JobRequest, CliError> {
+        let checked = self.snapped();
+        validation::into_result(checked.problems())?;
+        Ok(JobRequest(checked.job))
+    }
+}
+
+/// Start from a job read from a file, the offline queue or the server
+impl From<JobRequestDTO> for JobRequestBuilder {
+    fn from(job: JobRequestDTO) -> Self {
+        Self { job, limits: None }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{SweepPattern, TleData};
+    use crate::tle;
+    use chrono::Duration;
+
+    fn start() -> DateTime<Utc> {
+        "2026-10-20T12:15:00Z".parse().unwrap()
+    }
+
+    fn test_job(length: Duration) -> JobRequestBuilder {
+        JobRequestBuilder::new(start(), start() + length, JobType::Test)
+            .rx_frequency(Frequency::from_hz(437_500_000))
+    }
+
+    fn iss() -> TleData {
+        let mut lines = tle::EXAMPLE_FILE.lines().map(str::to_string);
+        TleData {
+            tle0: lines.next().unwrap(),
+            tle1: lines.next().unwrap(),
+            tle2: lines.next().unwrap(),
+        }
+    }
+
+    fn sweep() -> SweepPattern {
+        SweepPattern {
+            az_start: 0.0,
+            az_end: 350.0,
+            az_step: 10.0,
+            el_start: 0.0,
+            el_end: 90.0,
+            el_step: 15.0,
+        }
+    }
+
+    /// The field and reason `builder` is refused with
+    fn refusal(builder: JobRequestBuilder) -> (String, String) {
+        match builder.build() {
+            Err(CliError::Validation { field, reason, .. }) => (field, reason),
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn a_valid_job_builds() {
+        let job = test_job(Duration::minutes(10)).build().unwrap();
+        assert_eq!(job.rx_frequency, Frequency::from_hz(437_500_000));
+        assert_eq!(job.end - job.start, Duration::minutes(10));
+    }
+
+    #[test]
+    fn the_end_must_come_after_the_start() {
+        for length in [Duration::zero(), Duration::minutes(-5)] {
+            let (field, reason) = refusal(test_job(length));
+            assert_eq!(field, "end");
+            assert!(reason.contains("is not after the start"), "{}", reason);
+        }
+    }
+
+    #[test]
+    fn frequencies_outside_the_supported_range_are_refused() {
+        let low = test_job(Duration::minutes(10)).rx_frequency(Frequency::from_hz(500_000));
+        let (field, reason) = refusal(low);
+        assert_eq!(field, "rx_frequency");
+        assert!(
+            reason.contains("500000 Hz is outside the supported range"),
+            "{}",
+            reason
+        );
+
+        let high = test_job(Duration::minutes(10)).tx_frequency(Frequency::from_hz(40_000_000_000));
+        match high.build() {
+            Err(CliError::Validation {
+                field,
+                reason,
+                rules,
+            }) => {
+                assert_eq!(field, "tx_frequency");
+                assert!(reason.contains("outside the supported range"), "{}", reason);
+                assert_eq!(rules[0].name, "supported frequency range");
+            }
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn a_bad_tle_checksum_names_the_line() {
+        let mut tle = iss();
+        tle.tle1.pop();
+        tle.tle1.push('0');
+        let job = JobRequestBuilder::new(
+            start(),
+            start() + Duration::minutes(10),
+            JobType::Tracking { tle },
+        )
+        .rx_frequency(Frequency::from_hz(145_800_000));
+        let (field, reason) = refusal(job);
+        assert_eq!(field, "tle.tle1");
+        assert!(reason.contains("checksum mismatch"), "{}", reason);
+    }
+
+    #[test]
+    fn a_calibration_needs_a_sweep_that_steps() {
+        let valid = JobRequestBuilder::new(
+            start(),
+            start() + Duration::minutes(10),
+            JobType::Calibration { sweep: sweep() },
+        );
+        assert!(valid.build().is_ok(), "a sweep need not listen");
+
+        let still = SweepPattern {
+            el_step: 0.0,
+            ..sweep()
+        };
+        let job = JobRequestBuilder::new(
+            start(),
+            start() + Duration::minutes(10),
+            JobType::Calibration { sweep: still },
+        );
+        let (field, reason) = refusal(job);
+        assert_eq!(field, "sweep.elevation");
+        assert_eq!(reason, "step must be a positive angle");
+    }
+
+    #[test]
+    fn a_test_job_needs_a_frequency_to_listen_on() {
+        let job = JobRequestBuilder::new(start(), start() + Duration::minutes(10), JobType::Test);
+        let (field, reason) = refusal(job);
+        assert_eq!(field, "rx_frequency");
+        assert!(reason.starts_with("0 Hz is outside"), "{}", reason);
+    }
+
+    #[test]
+    fn every_broken_rule_is_reported_together() {
+        let job = JobRequestBuilder::new(start(), start(), JobType::Test);
+        let (field, reason) = refusal(job);
+        assert_eq!(field, "job");
+        assert!(reason.contains("end: "), "{}", reason);
+        assert!(reason.contains("rx_frequency: "), "{}", reason);
+    }
+}
@@ -0,0 +1,480 @@
+use crate::client::{ApiClient, JobDTO, JobStream, JobType, ListOutcome, MaintenanceWindow};
+use crate::clock;
+use crate::error::{CliError, ResultExt};
+use crate::humanize;
+use crate::maintenance;
+use crate::reproducible;
+use crate::satellite_id::SatelliteId;
+use crate::schedule::{self, TimeWindow};
+use crate::table::{self, Column, Table};
+use crate::text;
+use crate::OutputFormat;
+use std::io::Write;
+
+/// Number of time cells across the timeline view
+const TIMELINE_WIDTH: usize = 60;
+/// Longest satellite name shown before timeline rows are cut
+const TIMELINE_LABEL_WIDTH: usize = 20;
+/// Rows the table shows without `--all`; only this many jobs are kept in memory
+const TABLE_ROWS: usize = 100;
+
+/// Jobs as an aligned table with their times and statuses
+pub fn print_table(jobs: &[JobDTO]) {
+    let local = !humanize::utc_only();
+    let now = clock::now();
+
+    let mut columns = vec![
+        Column::new("ID"),
+        Column::new("TYPE"),
+        Column::name("SATELLITE"),
+        Column::time("START (UTC)"),
+        Column::time("END (UTC)").from(100),
+    ];
+    if local {
+        columns.push(Column::time("START (LOCAL)").from(120));
+    }
+    columns.extend([
+        Column::new("DURATION"),
+        Column::new("WHEN").from(90),
+        Column::new("STATUS"),
+    ]);
+    let cancelled = jobs.iter().any(JobDTO::is_cancelled);
+    if cancelled {
+        columns.extend([
+            Column::time("CANCELLED (UTC)").from(100),
+            Column::name("REASON").from(110),
+        ]);
+    }
+
+    let mut table = Table::new(columns);
+    for j in jobs {
+        let mut row = vec![
+            j.id.clone().unwrap_or_else(|| "—".to_string()),
+            j.job.job_type.name().to_string(),
+            j.job.label().to_string(),
+            j.job.start.format("%Y-%m-%d %H:%M:%S").to_string(),
+            j.job.end.format("%Y-%m-%d %H:%M:%S").to_string(),
+        ];
+        if local {
+            row.push(humanize::local(j.job.start).unwrap_or_default());
+        }
+        row.extend([
+            humanize::duration(j.job.end - j.job.start),
+            humanize::relative_to(j.job.start, j.job.end, now),
+            j.status.clone().unwrap_or_else(|| "—".to_string()),
+        ]);
+        if cancelled {
+            row.extend([
+                j.cancelled_at
+                    .map(|at| at.format("%Y-%m-%d %H:%M:%S").to_string())
+                    .unwrap_or_default(),
+                j.cancel_reason.clone().unwrap_or_default(),
+            ]);
+        }
+        if j.is_cancelled() {
+            table.dimmed_row(row);
+        } else {
+            table.row(row);
+        }
+    }
+    table.print();
+}
+
+const CSV_HEADER: [&str; 6] = ["id", "job_type", "satellite", "start", "end", "status"];
+
+/// One job as a CSV record, in the order of `CSV_HEADER`
+fn csv_record(j: &JobDTO) -> [String; 6] {
+    [
+        j.id.clone().unwrap_or_default(),
+        j.job.job_type.name().to_string(),
+        j.job.label().to_string(),
+        j.job.start.to_rfc3339(),
+        j.job.end.to_rfc3339(),
+        j.status.clone().unwrap_or_default(),
+    ]
+}
+
+fn csv_error(e: csv::Error) -> CliError {
+    CliError::parse("jobs as CSV", e)
+}
+
+/// Write jobs as CSV as they arrive, in server order
+async fn stream_csv(jobs: &mut JobStream, keep: impl Fn(&JobDTO) -> bool) -> Result<(), CliError> {
+    let mut writer = csv::Writer::from_writer(std::io::stdout().lock());
+    writer.write_record(CSV_HEADER).map_err(csv_error)?;
+    while let Some(j) = jobs.next().await? {
+        if !keep(&j) {
+            continue;
+        }
+        writer.write_record(csv_record(&j)).map_err(csv_error)?;
+    }
+    writer.flush().context("writing CSV")
+}
+
+/// Write jobs already gathered as CSV
+fn write_csv(jobs: &[JobDTO]) -> Result<(), CliError> {
+    let mut writer = csv::Writer::from_writer(std::io::stdout().lock());
+    writer.write_record(CSV_HEADER).map_err(csv_error)?;
+    for j in jobs {
+        writer.write_record(csv_record(j)).map_err(csv_error)?;
+    }
+    writer.flush().context("writing CSV")
+}
+
+/// One compact JSON object and a newline
+fn ndjson_line(out: &mut impl Write, job: &JobDTO) -> Result<(), CliError> {
+    serde_json::to_writer(&mut *out, job).map_err(|e| CliError::parse("job", e))?;
+    writeln!(out).context("writing NDJSON")
+}
+
+/// Write one compact JSON object per job as they arrive, in server order
+async fn stream_ndjson(
+    jobs: &mut JobStream,
+    keep: impl Fn(&JobDTO) -> bool,
+) -> Result<(), CliError> {
+    let mut out = std::io::BufWriter::new(std::io::stdout().lock());
+    while let Some(job) = jobs.next().await? {
+        if !keep(&job) {
+            continue;
+        }
+        ndjson_line(&mut out, &job)?;
+    }
+    out.flush().context("writing NDJSON")
+}
+
+/// Write jobs already gathered as NDJSON
+fn write_ndjson(jobs: &[JobDTO]) -> Result<(), CliError> {
+    let mut out = std::io::BufWriter::new(std::io::stdout().lock());
+    for job in jobs {
+        ndjson_line(&mut out, job)?;
+    }
+    out.flush().context("writing NDJSON")
+}
+
+/// The `limit` earliest jobs, sorted, and how many matched in all.
+/// Holds at most twice `limit` jobs at a time.
+async fn earliest(
+    jobs: &mut JobStream,
+    keep: impl Fn(&JobDTO) -> bool,
+    limit: usize,
+) -> Result<(Vec<JobDTO>, usize), CliError> {
+    let mut kept = Vec::new();
+    let mut total = 0;
+    while let Some(job) = jobs.next().await? {
+        if !keep(&job) {
+            continue;
+        }
+        total += 1;
+        kept.push(job);
+        if kept.len() >= 2 * limit.max(1) {
+            kept.sort_by(JobDTO::schedule_order);
+            kept.truncate(limit);
+        }
+    }
+    kept.sort_by(JobDTO::schedule_order);
+    kept.truncate(limit);
+    Ok((kept, total))
+}
+
+/// One bar per job across the span of all jobs, with maintenance periods shaded behind them
+fn print_timeline(jobs: &[JobDTO], maintenance: &[MaintenanceWindow]) {
+    let (Some(from), Some(to)) = (
+        jobs.iter().map(|j| j.job.start).min(),
+        jobs.iter().map(|j| j.job.end).max(),
+    ) else {
+        return;
+    };
+    let span_ms = (to - from).num_milliseconds().max(1);
+    let cell = |i: usize| -> TimeWindow {
+        let at = |i: usize| {
+            from + chrono::Duration::milliseconds(span_ms * i as i64 / TIMELINE_WIDTH as i64)
+        };
+        TimeWindow::new(at(i), at(i + 1))
+    };
+    let shaded = maintenance::intersecting(maintenance, TimeWindow::new(from, to));
+    let in_maintenance = |cell: &TimeWindow| shaded.iter().any(|w| w.window().overlaps(cell));
+
+    let label_width = jobs
+        .iter()
+        .map(|j| text::width(j.job.label()))
+        .max()
+        .unwrap_or(0)
+        .clamp("maintenance".len(), TIMELINE_LABEL_WIDTH);
+    if table::width().is_some_and(|width| width < 2 + label_width + 1 + TIMELINE_WIDTH) {
+        print_agenda(jobs, &shaded);
+        return;
+    }
+
+    println!(
+        "  {:<label_width$} {} → {} ({})",
+        "",
+        from.format("%Y-%m-%d %H:%M"),
+        to.format("%Y-%m-%d %H:%M UTC"),
+        humanize::duration(to - from),
+        label_width = label_width
+    );
+    for job in jobs {
+        let bar: String = (0..TIMELINE_WIDTH)
+            .map(|i| {
+                let cell = cell(i);
+                if job.job.window().overlaps(&cell) {
+                    '█'
+                } else if in_maintenance(&cell) {
+                    '░'
+                } else {
+                    '·'
+                }
+            })
+            .collect();
+        let label = text::truncate(job.job.label(), label_width);
+        println!("  {} {}", text::pad(&label, label_width), bar);
+    }
+
+    if shaded.is_empty() {
+        return;
+    }
+    let bar: String = (0..TIMELINE_WIDTH)
+        .map(|i| if in_maintenance(&cell(i)) { '░' } else { ' ' })
+        .collect();
+    println!(
+        "  {:<label_width$} {}",
+        "maintenance",
+        bar.trim_end(),
+        label_width = label_width
+    );
+    println!();
+    for window in shaded {
+        println!("  ░ {}", maintenance::describe(window));
+    }
+}
+
+/// The timeline for terminals too narrow to draw it: jobs and maintenance periods one
+/// per line under the day they start, in start order
+fn print_agenda(jobs: &[JobDTO], maintenance: &[&MaintenanceWindow]) {
+    enum Entry<'a> {
+        Job(&'a JobDTO),
+        Maintenance(&'a MaintenanceWindow),
+    }
+    let mut entries: Vec<(TimeWindow, Entry)> = jobs
+        .iter()
+        .map(|j| (j.job.window(), Entry::Job(j)))
+        .chain(
+            maintenance
+                .iter()
+                .map(|w| (w.window(), Entry::Maintenance(w))),
+        )
+        .collect();
+    entries.sort_by_key(|(window, _)| window.start());
+
+    let width = table::width().unwrap_or(usize::MAX);
+    let mut day = None;
+    for (window, entry) in entries {
+        let date = window.start().date_naive();
+        if day != Some(date) {
+            println!("  {}", date.format("%Y-%m-%d (%a)"));
+            day = Some(date);
+        }
+        let end = if window.end().date_naive() == date {
+            window.end().format("%H:%M").to_string()
+        } else {
+            window.end().format("%m-%d %H:%M").to_string()
+        };
+        let (mark, label) = match entry {
+            Entry::Job(job) => (
+                '█',
+                format!("{} ({})", job.job.label(), job.job.job_type.name()),
+            ),
+            Entry::Maintenance(w) => (
+                '░',
+                format!(
+                    "maintenance: {}",
+                    w.reason.as_deref().unwrap_or("no reason given")
+                ),
+            ),
+        };
+        let lead = format!("    {}–{} {} ", window.start().format("%H:%M"), end, mark);
+        let room = width.saturating_sub(text::width(&lead)).max(10);
+        println!("{}{}", lead, text::truncate(&label, room));
+    }
+}
+
+/// Whether a job tracks one of `satellites`
+fn tracks_any(job: &JobDTO, satellites: &[SatelliteId]) -> bool {
+    match &job.job.job_type {
+        JobType::Tracking { tle } => satellites.iter().any(|id| id.matches_line(&tle.tle1)),
+        _ => false,
+    }
+}
+
+/// List the server's jobs as a table, or as a timeline shaded with maintenance windows.
+/// With `satellites`, only tracking jobs for those catalog numbers or designators are shown;
+/// cancelled jobs the server still holds only with `include_cancelled`.
+/// CSV, NDJSON and the capped table are written as the list arrives, so a long list
+/// is never held in memory; JSON, the timeline and `all` need every job at once, as do
+/// CSV and NDJSON under `--reproducible`, which are sorted like the rest.
+/// With `partial_ok`, a list the server cuts short is shown as far as it arrived, marked
+/// as incomplete, and false is returned instead of an error.
+pub async fn list(
+    client: &ApiClient,
+    output: OutputFormat,
+    timeline: bool,
+    satellites: Option<&[SatelliteId]>,
+    all: bool,
+    include_cancelled: bool,
+    partial_ok: bool,
+) -> Result<bool, CliError> {
+    let keep = |job: &JobDTO| {
+        (include_cancelled || !job.is_cancelled())
+            && satellites.is_none_or(|ids| tracks_any(job, ids))
+    };
+    let mut stream = client
+        .stream_jobs(include_cancelled)
+        .await?
+        .partial_ok(partial_ok);
+    match output {
+        OutputFormat::Csv if !reproducible::enabled() => {
+            stream_csv(&mut stream, keep).await?;
+            return Ok(complete(&stream));
+        }
+        OutputFormat::Ndjson if !reproducible::enabled() => {
+            stream_ndjson(&mut stream, keep).await?;
+            return Ok(complete(&stream));
+        }
+        OutputFormat::Text if !timeline && !all => {
+            let (jobs, total) = earliest(&mut stream, keep, TABLE_ROWS).await?;
+            if total == 0 && stream.cut().is_none() {
+                println!("📭 No jobs scheduled");
+                return Ok(true);
+            }
+            println!("📋 {} job(s){}", humanize::count(total), marker(&stream));
+            print_table(&jobs);
+            if total > jobs.len() {
+                println!(
+                    "  … and {} more (pass --all to list every job)",
+                    humanize::count(total - jobs.len())
+                );
+            }
+            return Ok(complete(&stream));
+        }
+        _ => {}
+    }
+
+    let ListOutcome {
+        items: mut jobs,
+        complete,
+    } = stream.collect(keep).await?;
+    jobs.sort_by(JobDTO::schedule_order);
+    match output {
+        OutputFormat::Csv => {
+            write_csv(&jobs)?;
+            warn_cut(&stream);
+            return Ok(complete);
+        }
+        OutputFormat::Ndjson => {
+            write_ndjson(&jobs)?;
+            warn_cut(&stream);
+            return Ok(complete);
+        }
+        _ => {}
+    }
+    let windows = if timeline {
+        maintenance::windows(client).await
+    } else {
+        Vec::new()
+    };
+
+    if output == OutputFormat::Json {
+        let document = if timeline {
+            serde_json::json!({ "jobs": jobs, "maintenance": windows })
+        } else {
+            serde_json::json!(jobs)
+        };
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&document).map_err(|e| CliError::parse("jobs", e))?
+        );
+        warn_cut(&stream);
+        return Ok(complete);
+    }
+
+    if jobs.is_empty() && complete {
+        println!("📭 No jobs scheduled");
+        return Ok(true);
+    }
+
+    println!(
+        "📋 {} job(s){}",
+        humanize::count(jobs.len()),
+        marker(&stream)
+    );
+    if timeline {
+        print_timeline(&jobs, &windows);
+    } else {
+        print_table(&jobs);
+    }
+    warn_cut(&stream);
+    Ok(complete)
+}
+
+/// After the count of a list the server cut short
+fn marker(stream: &JobStream) -> &'static str {
+    match stream.cut() {
+        Some(_) => " ⚠️ incomplete",
+        None => "",
+    }
+}
+
+/// Say on stderr why the list stopped short, once what did arrive has been written
+fn warn_cut(stream: &JobStream) {
+    if let Some(cut) = stream.cut() {
+        eprintln!(
+            "⚠️ Partial list: the server stopped sending it ({}); only the jobs received \
+             before then are shown",
+            cut.full_message()
+        );
+    }
+}
+
+/// Whether the whole of a list written as it arrived got through
+fn complete(stream: &JobStream) -> bool {
+    warn_cut(stream);
+    stream.cut().is_none()
+}
+
+/// Print the next job to start, as one line or one JSON object.
+/// Returns false when there is none, or none starting within `within`.
+pub async fn next(
+    client: &ApiClient,
+    output: OutputFormat,
+    within: Option<chrono::Duration>,
+) -> Result<bool, CliError> {
+    let now = clock::now();
+    let next = client
+        .list_jobs()
+        .await?
+        .into_iter()
+        .filter(|j| j.job.start > now)
+        .min_by_key(|j| j.job.start)
+        .filter(|j| within.is_none_or(|within| j.job.start <= now + within));
+    let Some(job) = next else {
+        return Ok(false);
+    };
+
+    if output == OutputFormat::Json {
+        println!(
+            "{}",
+            serde_json::to_string(&job).map_err(|e| CliError::parse("job", e))?
+        );
+    } else {
+        // Fixed field order with the free-form label last, so scripts can split on spaces
+        println!(
+            "{} {} {} {} {}",
+            job.id.as_deref().unwrap_or("-"),
+            schedule::format(job.job.start),
+            schedule::format(job.job.end),
+            job.job.job_type.name(),
+            job.job.label()
+        );
+    }
+    Ok(true)
+}
@@ -0,0 +1,95 @@
+use std::collections::VecDeque;
+
+/// Cuts a top-level JSON array into its elements as bytes arrive, so a huge
+/// response can be decoded one element at a time. Only brackets, braces and
+/// strings are tracked; each element is still validated by serde when decoded.
+#[derive(Debug, Default)]
+pub struct ArraySplitter {
+    /// Bytes of the element being read
+    current: Vec<u8>,
+    /// Complete elements not yet taken
+    ready: VecDeque<Vec<u8>>,
+    started: bool,
+    finished: bool,
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+}
+
+impl ArraySplitter {
+    /// Feed the next chunk of the body; fails on anything that is not an array
+    pub fn push(&mut self, chunk: &[u8]) -> Result<(), String> {
+        for &byte in chunk {
+            if self.finished {
+                if !byte.is_ascii_whitespace() {
+                    return Err("data after the end of the array".to_string());
+                }
+                continue;
+            }
+            if !self.started {
+                match byte {
+                    b'[' => self.started = true,
+                    b if b.is_ascii_whitespace() => {}
+                    _ => return Err("the body is not a JSON array".to_string()),
+                }
+                continue;
+            }
+
+            if self.in_string {
+                self.current.push(byte);
+                match byte {
+                    _ if self.escaped => self.escaped = false,
+                    b'\\' => self.escaped = true,
+                    b'"' => self.in_string = false,
+                    _ => {}
+                }
+                continue;
+            }
+            match byte {
+                b'"' => {
+                    self.in_string = true;
+                    self.current.push(byte);
+                }
+                b'[' | b'{' => {
+                    self.depth += 1;
+                    self.current.push(byte);
+                }
+                b']' if self.depth == 0 => {
+                    self.finish_element();
+                    self.finished = true;
+                }
+                b']' | b'}' => {
+                    self.depth = self
+                        .depth
+                        .checked_sub(1)
+                        .ok_or_else(|| "unbalanced brackets".to_string())?;
+                    self.current.push(byte);
+                }
+                b',' if self.depth == 0 => self.finish_element(),
+                _ => self.current.push(byte),
+            }
+        }
+        Ok(())
+    }
+
+    fn finish_element(&mut self) {
+        let element = std::mem::take(&mut self.current);
+        if element.iter().any(|b| !b.is_ascii_whitespace()) {
+            self.ready.push_back(element);
+        }
+    }
+
+    /// The next complete element, if one has arrived
+    pub fn pop(&mut self) -> Option<Vec<u8>> {
+        self.ready.pop_front()
+    }
+
+    /// Call once the body has ended; an empty body counts as an empty array
+    pub fn end(&self) -> Result<(), String> {
+        if self.started && !self.finished {
+            Err("the array was cut off before its closing bracket".to_string())
+        } else {
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use crate::client::{ApiClient, StationInfo};
+use crate::clock;
+use crate::humanize;
+use chrono::{DateTime, Utc};
+use std::collections::VecDeque;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+/// How many round trips are remembered; the median of these is the typical one
+const SAMPLES: usize = 8;
+/// Round trips a submission is allowed, when the profile's `latency_margin` is unset
+pub const DEFAULT_MARGIN: f64 = 3.0;
+/// Jobs further out than this are not worth a request to check
+const NEAR_TERM: Duration = Duration::from_secs(10 * 60);
+/// How far the station's clock may run from ours before job times are in doubt; HTTP
+/// dates are to the second, so a few seconds either way are just rounding
+const MAX_CLOCK_SKEW: Duration = Duration::from_secs(30);
+
+/// The session's latest round trips, time to the response headers
+static RECENT: Mutex<VecDeque<Duration>> = Mutex::new(VecDeque::new());
+/// Set once from the profile
+static MARGIN: OnceLock<f64> = OnceLock::new();
+/// The station's clock less ours, from the Date header of the latest response
+static SKEW: Mutex<Option<chrono::Duration>> = Mutex::new(None);
+/// The station's scheduling lead, asked for once per session
+static LEAD: tokio::sync::OnceCell<Option<Duration>> = tokio::sync::OnceCell::const_new();
+
+/// The profile's `latency_margin`
+pub fn set_margin(margin: Option<f64>) {
+    let _ = MARGIN.set(margin.unwrap_or(DEFAULT_MARGIN));
+}
+
+/// Note the round trip of a request that reached the server
+pub fn record(rtt: Duration) {
+    let Ok(mut recent) = RECENT.lock() else {
+        return;
+    };
+    if recent.len() == SAMPLES {
+        recent.pop_front();
+    }
+    recent.push_back(rtt);
+}
+
+/// Note the time a response says it was sent, against ours as its headers arrived
+pub fn record_date(date: DateTime<Utc>) {
+    if let Ok(mut skew) = SKEW.lock() {
+        *skew = Some(date - clock::now());
+    }
+}
+
+/// Why the station's clock is not to be trusted with job times: it runs more than
+/// `MAX_CLOCK_SKEW` from ours, by the latest response of the session
+pub fn clock_skew() -> Option<String> {
+    let skew = (*SKEW.lock().ok()?)?;
+    let off = skew.abs().to_std().ok()?;
+    if off <= MAX_CLOCK_SKEW {
+        return None;
+    }
+    Some(format!(
+        "The station's clock is {} {} this computer's; jobs may start that far from the times given",
+        seconds(off),
+        if skew > chrono::Duration::zero() {
+            "ahead of"
+        } else {
+            "behind"
+        }
+    ))
+}
+
+/// The median of the remembered round trips, the slower one of the middle two
+fn typical() -> Option<Duration> {
+    let mut samples: Vec<Duration> = RECENT.lock().ok()?.iter().copied().collect();
+    samples.sort();
+    samples.get(samples.len() / 2).copied()
+}
+
+/// Seconds with a decimal below ten, where rounding would hide a slow link
+fn seconds(duration: Duration) -> String {
+    match duration < Duration::from_secs(10) {
+        true => format!("{:.1}s", duration.as_secs_f64()),
+        false => humanize::duration(chrono::Duration::seconds(duration.as_secs() as i64)),
+    }
+}
+
+fn lead_time(info: &StationInfo) -> Option<Duration> {
+    info.scheduling_lead_seconds.map(Duration::from_secs)
+}
+
+/// Why a job starting at `start` may not reach the station in time: the time left is
+/// less than `latency_margin` round trips plus the lead the station asks for. A session
+/// with no request yet measures one with a health check; a server that cannot be
+/// reached is left for the submission itself to report.
+pub async fn check(client: &ApiClient, start: DateTime<Utc>) -> Option<String> {
+    let left = (start - clock::now()).to_std().unwrap_or_default();
+    if left > NEAR_TERM {
+        return None;
+    }
+    if typical().is_none() {
+        client.ping().await.ok()?;
+    }
+    let rtt = typical()?;
+    let lead = *LEAD
+        .get_or_init(|| async {
+            client
+                .station_info()
+                .await
+                .ok()
+                .as_ref()
+                .and_then(lead_time)
+        })
+        .await;
+    let margin = MARGIN.get().copied().unwrap_or(DEFAULT_MARGIN);
+    let submission = rtt.mul_f64(margin);
+    if left >= submission + lead.unwrap_or_default() {
+        return None;
+    }
+    let mut warning = format!(
+        "Job starts in {}; submission typically takes {} ({} round trips of {})",
+        seconds(left),
+        seconds(submission),
+        margin,
+        seconds(rtt)
+    );
+    if let Some(lead) = lead {
+        warning.push_str(&format!("; station needs {} lead", seconds(lead)));
+    }
+    Some(warning)
+}
@@ -0,0 +1,111 @@
+//! The ground-station client and everything it stands on: the API client and its DTOs,
+//! job requests and their checks, time windows, frequencies, the parsers for what
+//! operators type, the configuration, and the mock server. The `rustar-cli` binary is
+//! the command line on top; other tools can link this instead of running it.
+
+// Every line the crate prints goes through `reproducible`, which takes the emoji off the
+// front of it under `--reproducible`. Defined before the modules so they all use these.
+macro_rules! println {
+    () => {
+        $crate::reproducible::println(format_args!(""))
+    };
+    ($($arg:tt)*) => {
+        $crate::reproducible::println(format_args!($($arg)*))
+    };
+}
+
+macro_rules! eprintln {
+    () => {
+        $crate::reproducible::eprintln(format_args!(""))
+    };
+    ($($arg:tt)*) => {
+        $crate::reproducible::eprintln(format_args!($($arg)*))
+    };
+}
+
+pub mod alias;
+pub mod audit;
+pub mod bands;
+pub mod base_url;
+pub mod baseline;
+pub mod batch;
+pub mod batch_csv;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod campaign;
+pub mod cancel;
+pub mod capabilities;
+pub mod catalog;
+pub mod chain;
+pub mod client;
+pub mod clock;
+pub mod config;
+pub mod conflict;
+pub mod coordination;
+pub mod diff;
+pub mod download;
+pub mod error;
+pub mod event_stream;
+pub mod examples;
+pub mod findings;
+pub mod frequency;
+pub mod hints;
+pub mod history;
+pub mod hooks;
+pub mod http_cache;
+pub mod humanize;
+pub mod input_schema;
+pub mod job_request;
+pub mod jobs;
+pub mod json_array;
+pub mod latency;
+pub mod location;
+pub mod look_angles;
+pub mod maintenance;
+pub mod mask;
+pub mod metrics;
+pub mod mock_server;
+pub mod mqtt;
+pub mod orbit;
+pub mod output;
+pub mod parse;
+pub mod pass_score;
+pub mod paths;
+pub mod phases;
+pub mod polite;
+pub mod predict;
+pub mod progress;
+pub mod purge;
+pub mod queue;
+pub mod quiet_hours;
+pub mod receipt;
+pub mod regulatory;
+pub mod reproducible;
+pub mod reschedule;
+pub mod rotator;
+pub mod rotctld;
+pub mod satellite_id;
+pub mod satellites;
+pub mod schedule;
+pub mod schema;
+pub mod signal;
+pub mod simulate;
+pub mod snap;
+pub mod snapshot;
+pub mod station;
+pub mod stats;
+pub mod storage;
+pub mod submit_window;
+pub mod table;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+pub mod text;
+pub mod timing;
+pub mod tle;
+pub mod transcript;
+pub mod transponder;
+pub mod tty;
+pub mod validation;
+pub mod weather;
+
+pub use output::OutputFormat;
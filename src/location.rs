@@ -0,0 +1,190 @@
+use crate::error::CliError;
+use serde::Serialize;
+
+/// Where the ground station stands
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Location {
+    /// Decimal degrees, north positive
+    pub latitude: f64,
+    /// Decimal degrees, east positive
+    pub longitude: f64,
+    /// Meters above the WGS84 ellipsoid
+    pub altitude_m: f64,
+}
+
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:.6}, {:.6}, {} m",
+            self.latitude, self.longitude, self.altitude_m
+        )
+    }
+}
+
+impl Location {
+    /// Check the ranges, naming the offending key in errors
+    pub fn new(latitude: f64, longitude: f64, altitude_m: f64) -> Result<Self, CliError> {
+        if !latitude.is_finite() || latitude.abs() > 90.0 {
+            return Err(CliError::validation(
+                "latitude",
+                format!("{} is outside -90..90 degrees", latitude),
+            ));
+        }
+        if !longitude.is_finite() || longitude.abs() > 180.0 {
+            return Err(CliError::validation(
+                "longitude",
+                format!("{} is outside -180..180 degrees", longitude),
+            ));
+        }
+        if !altitude_m.is_finite() {
+            return Err(CliError::validation("altitude_m", "must be a number"));
+        }
+        Ok(Self {
+            latitude,
+            longitude,
+            altitude_m,
+        })
+    }
+}
+
+/// Which coordinate an (uppercase) hemisphere letter belongs to, and its sign
+fn hemisphere(letter: char) -> Option<(Axis, f64)> {
+    match letter {
+        'N' => Some((Axis::Latitude, 1.0)),
+        'S' => Some((Axis::Latitude, -1.0)),
+        'E' => Some((Axis::Longitude, 1.0)),
+        'W' => Some((Axis::Longitude, -1.0)),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Axis {
+    Latitude,
+    Longitude,
+}
+
+/// Degrees from "34.6", "-58.37", "34°36'", "34°36'12.5\"" or "34 36 12.5"
+fn parse_angle(input: &str) -> Result<f64, CliError> {
+    let error =
+        |reason: &str| CliError::parse(format!("angle '{}'", input.trim()), reason.to_string());
+
+    let normalized: String = input
+        .chars()
+        .map(|c| match c {
+            '°' | 'º' | '\'' | '′' | '’' | '"' | '″' | '”' => ' ',
+            other => other,
+        })
+        .collect();
+    let parts: Vec<&str> = normalized.split_whitespace().collect();
+    if parts.is_empty() || parts.len() > 3 {
+        return Err(error(
+            "expected degrees, optionally followed by minutes and seconds",
+        ));
+    }
+
+    let mut values = Vec::with_capacity(parts.len());
+    for part in &parts {
+        values.push(
+            part.parse::<f64>()
+                .map_err(|_| error("expected a number of degrees"))?,
+        );
+    }
+    let negative = values[0].is_sign_negative();
+    let mut degrees = values[0].abs();
+    for (value, divisor) in values.iter().skip(1).zip([60.0, 3600.0]) {
+        if *value < 0.0 || *value >= 60.0 {
+            return Err(error("minutes and seconds must be between 0 and 60"));
+        }
+        degrees += value / divisor;
+    }
+    Ok(if negative { -degrees } else { degrees })
+}
+
+/// Split "34°36'S 58°22'W 25m" into the angle before each hemisphere letter and whatever follows
+fn hemisphere_parts(input: &str) -> (Vec<(String, Axis, f64)>, String) {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    for c in input.chars() {
+        match hemisphere(c) {
+            Some((axis, sign)) => {
+                parts.push((
+                    current.trim().trim_matches(',').trim().to_string(),
+                    axis,
+                    sign,
+                ));
+                current.clear();
+            }
+            None => current.push(c),
+        }
+    }
+    (parts, current.trim().trim_matches(',').trim().to_string())
+}
+
+/// Parse a station position: "lat,lon[,alt]" in signed decimal degrees, or a DMS pair with
+/// hemispheres such as "34°36'S 58°22'W [alt]". Altitude, when given, is in meters.
+pub fn parse(input: &str) -> Result<Location, CliError> {
+    let input = input.trim();
+    let error = |reason: &str| CliError::parse(format!("location '{}'", input), reason.to_string());
+
+    if !input.chars().any(|c| hemisphere(c).is_some()) {
+        let parts: Vec<&str> = input
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|p| !p.is_empty())
+            .collect();
+        let (latitude, longitude, altitude) = match parts.as_slice() {
+            [lat, lon] => (parse_angle(lat)?, parse_angle(lon)?, 0.0),
+            [lat, lon, alt] => (parse_angle(lat)?, parse_angle(lon)?, parse_altitude(alt)?),
+            _ => {
+                return Err(error(
+                    "expected 'lat,lon[,alt]' or a pair like 34°36'S 58°22'W",
+                ))
+            }
+        };
+        return Location::new(latitude, longitude, altitude);
+    }
+
+    let (parts, rest) = hemisphere_parts(input);
+    let (mut latitude, mut longitude) = (None, None);
+    for (angle, axis, sign) in parts {
+        let value = sign * parse_angle(&angle)?.abs();
+        let slot = match axis {
+            Axis::Latitude => &mut latitude,
+            Axis::Longitude => &mut longitude,
+        };
+        if slot.replace(value).is_some() {
+            return Err(error("the same hemisphere axis is given twice"));
+        }
+    }
+    let altitude = if rest.is_empty() {
+        0.0
+    } else {
+        parse_altitude(&rest)?
+    };
+    match (latitude, longitude) {
+        (Some(latitude), Some(longitude)) => Location::new(latitude, longitude, altitude),
+        _ => Err(error("expected one N/S latitude and one E/W longitude")),
+    }
+}
+
+/// Meters, with an optional "m" suffix
+pub fn parse_altitude(input: &str) -> Result<f64, CliError> {
+    let trimmed = input.trim();
+    trimmed
+        .strip_suffix('m')
+        .unwrap_or(trimmed)
+        .trim()
+        .parse()
+        .map_err(|_| {
+            CliError::parse(
+                format!("altitude '{}'", trimmed),
+                "expected meters, e.g. 25 or 25m",
+            )
+        })
+}
+
+/// `--coords` value parser
+pub fn parse_coords(input: &str) -> Result<Location, String> {
+    parse(input).map_err(|e| e.full_message())
+}
@@ -0,0 +1,135 @@
+use crate::client::{ApiClient, JobDTO, JobType, TleData};
+use crate::error::{CliError, ResultExt};
+use crate::location::Location;
+use crate::orbit::{LookAngle, Satellite};
+use crate::output::OutputTarget;
+use chrono::{DateTime, Duration, Utc};
+use std::io::Write;
+
+/// Finer steps only inflate the table; rotator controllers interpolate anyway
+pub const MIN_STEP_MS: i64 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum TableFormat {
+    /// One row per step with a header line
+    Csv,
+    /// An array of objects
+    Json,
+}
+
+/// The elements and window of a scheduled tracking job; none for a fixed pointing, which
+/// has no pass to follow and is only noted
+pub async fn job_window(
+    client: &ApiClient,
+    id: &str,
+) -> Result<Option<(TleData, DateTime<Utc>, DateTime<Utc>)>, CliError> {
+    let value = client.job_json(id).await?;
+    if value.is_null() {
+        return Err(CliError::NotFound {
+            message: Some(format!("the server returned no body for job {}", id)),
+        });
+    }
+    let job: JobDTO =
+        serde_json::from_value(value).map_err(|e| CliError::parse(format!("job {}", id), e))?;
+    match job.job.job_type {
+        JobType::Tracking { tle } => Ok(Some((tle, job.job.start, job.job.end))),
+        JobType::Pointing { pointing } => {
+            eprintln!(
+                "🎯 Job {} holds the antenna at az {}°, el {}° for its whole window; there are no look angles to work out",
+                id, pointing.azimuth, pointing.elevation
+            );
+            Ok(None)
+        }
+        other => Err(CliError::validation(
+            "job",
+            format!(
+                "{} is a {} job and has no satellite to point at",
+                id,
+                other.name()
+            ),
+        )),
+    }
+}
+
+/// Look angles from `start` to `end` inclusive, every `step`
+pub fn table(
+    satellite: &Satellite,
+    station: &Location,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    step: Duration,
+) -> Result<Vec<LookAngle>, CliError> {
+    if step.num_milliseconds() < MIN_STEP_MS {
+        return Err(CliError::validation(
+            "step",
+            format!("must be at least {}ms", MIN_STEP_MS),
+        ));
+    }
+    if end <= start {
+        return Err(CliError::validation("end", "must be after the start"));
+    }
+
+    let mut rows = Vec::new();
+    let mut at = start;
+    while at < end {
+        rows.push(satellite.look_angle(station, at)?);
+        at += step;
+    }
+    rows.push(satellite.look_angle(station, end)?);
+    Ok(rows)
+}
+
+fn write_csv(rows: &[LookAngle], out: impl Write) -> Result<(), CliError> {
+    let mut writer = csv::Writer::from_writer(out);
+    let error = |e: csv::Error| CliError::parse("look angles as CSV", e);
+    writer
+        .write_record([
+            "time",
+            "azimuth_deg",
+            "elevation_deg",
+            "range_km",
+            "range_rate_km_s",
+        ])
+        .map_err(error)?;
+    for row in rows {
+        writer
+            .write_record([
+                row.at.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
+                format!("{:.3}", row.azimuth_deg),
+                format!("{:.3}", row.elevation_deg),
+                format!("{:.3}", row.range_km),
+                format!("{:.4}", row.range_rate_km_s),
+            ])
+            .map_err(error)?;
+    }
+    writer.flush().context("writing CSV")
+}
+
+/// Propagate `tle` over the window and write the pointing table to `output`
+pub fn export(
+    tle: &TleData,
+    station: &Location,
+    (start, end): (DateTime<Utc>, DateTime<Utc>),
+    step: Duration,
+    format: TableFormat,
+    output: &OutputTarget,
+) -> Result<(), CliError> {
+    output.check()?;
+    let satellite = Satellite::from_tle(tle)?;
+    let rows = table(&satellite, station, start, end, step)?;
+
+    let mut document = Vec::new();
+    match format {
+        TableFormat::Csv => write_csv(&rows, &mut document)?,
+        TableFormat::Json => {
+            serde_json::to_writer_pretty(&mut document, &rows)
+                .map_err(|e| CliError::parse("look angles", e))?;
+            writeln!(document).context("writing JSON")?;
+        }
+    }
+
+    if output.path().is_some() {
+        eprintln!("📐 {} look angle(s) for {}", rows.len(), satellite.name);
+    }
+    output.write(document)
+}
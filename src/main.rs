@@ -1,56 +1,1571 @@
-use crate::client::{ApiClient, JobRequestDTO, TleData};
-use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
-use clap::{Parser, Subcommand};
-use inquire::Text;
+use chrono::{DateTime, SubsecRound, Utc};
+use clap::parser::ValueSource;
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
+use inquire::{Confirm, Select, Text};
+use rustar_cli::client::{
+    ApiClient, FixedPointing, JobRequestDTO, JobType, Polarization, PostPassAction, SweepPattern,
+    TleData,
+};
+use rustar_cli::error::{CliError, ResultExt};
+use rustar_cli::findings::Check;
+use rustar_cli::frequency::Frequency;
+use rustar_cli::hooks::HookRunner;
+use rustar_cli::job_request::JobRequestBuilder;
+use rustar_cli::transcript::Recorded;
+use rustar_cli::transponder::Passband;
+use rustar_cli::OutputFormat;
+use rustar_cli::{
+    alias, audit, base_url, baseline, batch, campaign, cancel, capabilities, catalog, chain,
+    client, clock, config, conflict, coordination, diff, download, error, event_stream, examples,
+    findings, frequency, history, hooks, http_cache, humanize, input_schema, job_request, jobs,
+    latency, location, look_angles, maintenance, mask, metrics, mock_server, mqtt, orbit, output,
+    parse, pass_score, paths, phases, polite, predict, progress, purge, queue, quiet_hours,
+    receipt, regulatory, reproducible, reschedule, rotator, satellite_id, satellites, schedule,
+    schema, simulate, snap, snapshot, station, stats, storage, submit_window, table, text, timing,
+    tle, transcript, transponder, tty, validation, weather,
+};
+use std::cell::OnceCell;
+use std::ffi::OsString;
+use std::path::PathBuf;
 
-mod client;
-mod error;
+// Lines printed here go through the library's `reproducible` as its own do, so
+// `--reproducible` takes the emoji off every one
+macro_rules! println {
+    () => {
+        rustar_cli::reproducible::println(format_args!(""))
+    };
+    ($($arg:tt)*) => {
+        rustar_cli::reproducible::println(format_args!($($arg)*))
+    };
+}
+
+macro_rules! eprintln {
+    () => {
+        rustar_cli::reproducible::eprintln(format_args!(""))
+    };
+    ($($arg:tt)*) => {
+        rustar_cli::reproducible::eprintln(format_args!($($arg)*))
+    };
+}
+
+mod archive;
+mod backup;
+mod demo;
+mod docs;
+mod draft;
+mod editor;
+mod fanout;
+mod handoff;
+mod hold;
+mod import;
+mod init;
+mod protect;
+mod quick;
+mod report;
+mod restore;
+mod shell;
+mod smoke;
+mod sweep;
+mod tail;
+mod tle_audit;
+mod tle_compare;
+mod tle_refresh;
+mod undo;
+mod update;
+mod whoami;
 
 #[derive(Parser, Debug)]
 #[command(version, about = "Ground Station CLI", long_about = None)]
 struct Args {
+    /// Output format for commands that print data
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    output: OutputFormat,
+
+    /// Refuse every command that changes the schedule, the catalog or this CLI's stores,
+    /// as the profile's read_only does, for terminals that only browse
+    #[arg(long, global = true)]
+    read_only: bool,
+
+    /// Name of the protected profile a changing command runs against, confirming it without a prompt
+    #[arg(long, global = true, value_name = "PROFILE")]
+    confirm_protected: Option<String>,
+
+    #[command(flatten)]
+    global: GlobalArgs,
+
     #[command(subcommand)]
     command: Commands,
 }
 
+/// Global flags that set up the configuration, the client and how output looks. A shell
+/// takes them from its own invocation for every line; only the flags of `Args` itself may
+/// be given again on a line.
+#[derive(clap::Args, Debug, Clone)]
+struct GlobalArgs {
+    /// Configuration profile to use (defaults to RUSTAR_PROFILE, then the configured default)
+    #[arg(long, global = true)]
+    profile: Option<String>,
+
+    /// Ground station API to talk to (overrides API_BASE_URL)
+    #[arg(long, global = true, value_name = "URL")]
+    base_url: Option<String>,
+
+    /// Request timeout in whole seconds for this invocation (overrides API_TIMEOUT_SECONDS)
+    #[arg(long, global = true, value_name = "SECONDS")]
+    timeout: Option<String>,
+
+    /// Ground station position as "lat,lon[,alt]" (overrides the profile's coordinates)
+    #[arg(
+        long,
+        global = true,
+        visible_alias = "station-coords",
+        value_name = "LAT,LON[,ALT]",
+        value_parser = location::parse_coords,
+        allow_hyphen_values = true
+    )]
+    coords: Option<location::Location>,
+
+    /// Load environment variables from this file instead of the config directory's .env
+    #[arg(long, global = true, value_name = "FILE")]
+    env_file: Option<PathBuf>,
+
+    /// Override a setting for this invocation only (repeatable), e.g. --set timeout_seconds=5.
+    /// Keys are the ones config list shows: profile, base_url, timeout_seconds,
+    /// idle_timeout_seconds, tle_source, location
+    #[arg(
+        long = "set",
+        global = true,
+        value_name = "KEY=VALUE",
+        value_parser = config::parse_override
+    )]
+    overrides: Vec<(String, String)>,
+
+    /// Report which files and settings were loaded
+    #[arg(short, long, global = true)]
+    verbose: bool,
+
+    /// Do not run the profile's post-submit hooks
+    #[arg(long, global = true)]
+    no_hooks: bool,
+
+    /// Do not check the profile's weather forecast before submitting
+    #[arg(long, global = true)]
+    no_weather: bool,
+
+    /// Do not warn when a tracking job's RX sits in an uplink band or its TX in a downlink band
+    #[arg(long, global = true)]
+    no_band_hints: bool,
+
+    /// Do not point out values that differ from this satellite's earlier jobs in the summary
+    #[arg(long, global = true)]
+    no_anomaly_hints: bool,
+
+    /// Do not report settings that several sources give different values
+    #[arg(long, global = true)]
+    no_config_warnings: bool,
+
+    /// Show times in UTC only, without local-time conversions
+    #[arg(long, global = true)]
+    utc_only: bool,
+
+    /// Output that is the same on every machine and run, for golden tests and diffs:
+    /// absolute UTC times, no colour, emoji or live lines, the C locale for child
+    /// processes, and jobs listed by start, then ID. RUSTAR_FAKE_NOW fixes the current
+    /// time as well.
+    #[arg(
+        long,
+        global = true,
+        env = reproducible::ENV,
+        value_parser = clap::builder::BoolishValueParser::new()
+    )]
+    reproducible: bool,
+
+    /// Unit to print frequencies in, over the profile's `frequency_display`
+    #[arg(long, global = true, value_enum, value_name = "UNIT")]
+    freq_unit: Option<humanize::FrequencyUnit>,
+
+    /// Print tables and timelines in full, however narrow the terminal
+    #[arg(long, global = true)]
+    wide: bool,
+
+    /// With validation errors, say which setting set the limit, where it came from and
+    /// which flag overrides it
+    #[arg(long, global = true)]
+    explain: bool,
+
+    /// Stop a submission at any advisory warning (stale elements, a past start, band hints,
+    /// a suspected duplicate, clock skew, quiet-hours notes, weather, a name that is another
+    /// satellite's alias), listing every one, as the profile's strict does; for CI pipelines
+    #[arg(long, global = true)]
+    strict: bool,
+
+    /// Report DNS, connect, time-to-first-byte and total durations for each API request
+    #[arg(long, global = true)]
+    timing: bool,
+
+    /// After the command, print how long config, element sets, predictions, API requests
+    /// and rendering took (as a `timing` object on stderr with --output json)
+    #[arg(long, global = true)]
+    profile_timing: bool,
+
+    /// Take element sets from the cache only, making no request to the public TLE
+    /// sources; commands that need elements the cache lacks fail
+    #[arg(long, global = true)]
+    tle_offline: bool,
+
+    /// Record the session (prompts, warnings, jobs sent, API requests and their request
+    /// IDs, the outcome) as one JSON document in FILE, to attach to a support request
+    #[arg(long, global = true, value_name = "FILE")]
+    transcript: Option<PathBuf>,
+
+    /// Accept job starts up to this far ahead for this invocation, e.g. 90d
+    /// (overrides the profile's max_horizon_days)
+    #[arg(long, global = true, value_name = "DURATION", value_parser = parse::duration)]
+    max_horizon: Option<chrono::Duration>,
+
+    /// Let jobs transmit during the profile's quiet hours, once the operator types a
+    /// confirmation; each exception is recorded in the audit log
+    #[arg(long, global = true)]
+    override_quiet_hours: bool,
+
+    /// Send job times as entered, off the profile's snap grid, for servers that take any time
+    #[arg(long, global = true)]
+    no_snap: bool,
+
+    /// Run against a built-in station with sample data instead of a server, for screenshots
+    /// and training; changes pretend to succeed, marked DEMO, and nothing of the profile's
+    /// is read or written
+    #[arg(long, global = true, conflicts_with_all = ["base_url", "profile"])]
+    demo: bool,
+
+    /// Pick another reproducible --demo dataset
+    #[arg(
+        long,
+        global = true,
+        value_name = "N",
+        default_value_t = 1,
+        requires = "demo"
+    )]
+    demo_seed: u64,
+}
+
 #[derive(Subcommand, Debug)]
 enum Commands {
+    /// Add, list, reschedule, cancel and compare jobs on the ground station
+    ///
+    /// Examples: rustar-cli examples batch, datetimes, frequencies
+    Jobs {
+        #[command(subcommand)]
+        action: JobsAction,
+    },
+    /// Import passes planned in gpredict (.sat/.trsp) or SatNOGS observation exports (.json)
+    ///
+    /// Examples: rustar-cli examples datetimes, durations
+    Import {
+        /// Files to import
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+        /// Window start for gpredict files, which carry no schedule
+        #[arg(long)]
+        start: Option<String>,
+        /// Window end for gpredict files
+        #[arg(long, conflicts_with = "duration")]
+        end: Option<String>,
+        /// Window length for gpredict files (e.g. 12m, 1h30m)
+        #[arg(long)]
+        duration: Option<String>,
+        /// gpredict transponder section to use (defaults to the first one)
+        #[arg(long)]
+        transponder: Option<String>,
+        /// Show the resulting jobs without submitting them
+        #[arg(long)]
+        dry_run: bool,
+        /// Write the --dry-run jobs to this file instead of stdout
+        #[arg(long, value_name = "FILE", requires = "dry_run")]
+        output_file: Option<PathBuf>,
+        /// Write a per-entry outcome report ("-" for stdout)
+        #[arg(long, value_name = "FILE", conflicts_with = "dry_run")]
+        report: Option<PathBuf>,
+        /// Overwrite --output-file or --report if it exists
+        #[arg(long)]
+        force: bool,
+        /// Submit only the entries that pass validation
+        #[arg(long)]
+        skip_invalid: bool,
+    },
+    /// Count jobs and tracked minutes per satellite, day or status
+    ///
+    /// Examples: rustar-cli examples datetimes, scripting
+    Stats {
+        /// Only count jobs starting at or after this date or datetime
+        #[arg(long)]
+        from: Option<String>,
+        /// Only count jobs starting before this date or datetime
+        #[arg(long)]
+        to: Option<String>,
+        /// How to group the jobs
+        #[arg(long, value_enum, default_value_t = stats::GroupBy::Satellite)]
+        group_by: stats::GroupBy,
+        /// Day boundaries for --group-by day and bare dates: utc, local or an offset like +02:00
+        /// (defaults to the profile's timezone)
+        #[arg(long, value_parser = stats::parse_timezone)]
+        timezone: Option<stats::DayBoundary>,
+    },
+    /// Show ground station status: antennas, pointing, queue and disk
+    ///
+    /// Examples: rustar-cli examples scripting
+    #[command(name = "station-info")]
+    StationInfo {
+        /// Keep refreshing the view
+        #[arg(long)]
+        watch: bool,
+        /// Seconds between refreshes in watch mode
+        #[arg(long, default_value_t = 5, requires = "watch")]
+        interval: u64,
+        /// Serve Prometheus metrics on this address while watching, e.g. 127.0.0.1:9188
+        #[arg(long, value_name = "ADDR", requires = "watch")]
+        metrics_listen: Option<std::net::SocketAddr>,
+    },
+    /// Show who the server takes this client to be, with the profile and server in use
+    Whoami,
+    /// Compare element sets, and manage the ones in the server's satellite catalog
+    ///
+    /// Examples: rustar-cli examples tle
+    Tle {
+        #[command(subcommand)]
+        action: TleAction,
+    },
+    /// Predict passes over the station (prompts for the elements unless --norad-id,
+    /// --intl-designator or --tle-file)
+    ///
+    /// Examples: rustar-cli examples tle, datetimes
+    Predict {
+        /// Satellite to predict, using the cached or freshly fetched element set; repeat to compare.
+        /// Alpha-5 numbers (A0001) are accepted
+        #[arg(long, value_name = "NORAD_ID", conflicts_with = "tle_file", value_parser = satellite_id::norad_id_arg)]
+        norad_id: Vec<u32>,
+        /// Satellite to predict by international designator (1998-067A or 98067A); repeatable
+        #[arg(long, value_name = "DESIGNATOR", conflicts_with = "tle_file", value_parser = satellite_id::designator_arg)]
+        intl_designator: Vec<String>,
+        /// Predict every satellite in an alias-book group (or a single alias)
+        #[arg(long, value_name = "NAME", conflicts_with = "tle_file")]
+        alias_group: Option<String>,
+        /// Read a two- or three-line element set from a file
+        #[arg(long, value_name = "FILE")]
+        tle_file: Option<PathBuf>,
+        /// Start of the search window (defaults to now)
+        #[arg(long)]
+        from: Option<String>,
+        /// Length of the search window in hours
+        #[arg(long, default_value_t = 24)]
+        hours: u32,
+        /// Flat minimum elevation in degrees, on top of the elevation mask
+        #[arg(
+            long,
+            value_name = "DEG",
+            default_value_t = 0.0,
+            allow_negative_numbers = true
+        )]
+        min_elevation: f64,
+        /// Elevation mask as AZ:EL breakpoints (overrides the profile's elevation_mask)
+        #[arg(long, value_name = "AZ:EL,...")]
+        mask: Option<String>,
+        /// Show each pass's rise/set window and how much of it the mask hides
+        #[arg(long)]
+        show_mask: bool,
+        /// Order of the pass table
+        #[arg(long, value_enum, default_value_t = predict::PassOrder::Time)]
+        sort: predict::PassOrder,
+        /// Leave out passes scoring below this, 0 to 100 (see the profile's pass_scoring)
+        #[arg(long, value_name = "SCORE", value_parser = clap::value_parser!(u8).range(0..=100))]
+        min_score: Option<u8>,
+        /// List only the N best-scoring passes
+        #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+        best: Option<u32>,
+        /// Choose one of the passes and schedule a tracking job for its window
+        #[arg(long)]
+        pick: bool,
+    },
+    /// Schedule the pass in progress, or about to start, from now to LOS in one go: the
+    /// alias presets and cached elements fill in everything, and a 5-second countdown
+    /// replaces the confirmation
+    ///
+    /// Examples: rustar-cli examples durations
+    Quick {
+        /// Satellite alias; it needs a NORAD ID or designator with cached elements and an RX preset
+        #[arg(long, value_name = "ALIAS")]
+        satellite: String,
+        /// Refuse passes starting later than this (defaults to the profile's
+        /// quick_horizon_minutes, 10 minutes when unset)
+        #[arg(long, value_name = "DURATION")]
+        within: Option<String>,
+    },
+    /// Export a time/azimuth/elevation/range table for a job or an explicit window
+    ///
+    /// Examples: rustar-cli examples tle, datetimes, durations
+    #[command(name = "look-angles")]
+    LookAngles {
+        /// Scheduled tracking job whose elements and window to use
+        #[arg(required_unless_present = "tle", conflicts_with_all = ["tle", "start", "end"])]
+        job_id: Option<String>,
+        /// Read a two- or three-line element set from a file instead of a job
+        #[arg(long, value_name = "FILE", requires_all = ["start", "end"])]
+        tle: Option<PathBuf>,
+        /// Window start, with --tle
+        #[arg(long)]
+        start: Option<String>,
+        /// Window end, with --tle
+        #[arg(long)]
+        end: Option<String>,
+        /// Time between rows (at least 100ms)
+        #[arg(long, default_value = "1s")]
+        step: String,
+        #[arg(long, value_enum, default_value_t = look_angles::TableFormat::Csv)]
+        format: look_angles::TableFormat,
+        /// Write the table to this file instead of stdout ("-" for stdout)
+        #[arg(long, value_name = "FILE")]
+        output_file: Option<PathBuf>,
+        /// Overwrite --output-file if it exists
+        #[arg(long)]
+        force: bool,
+    },
+    /// Write the report of a pass: the job, what the station says it produced, the pass
+    /// predicted for its window and any notes. Sections whose source is missing are left
+    /// out with a note.
+    ///
+    /// A template is any text file; {{job.label}}, {{outcome.frames_decoded}},
+    /// {{geometry.max_elevation_deg}}, {{notes}} and the other names of --format json are
+    /// replaced by their values.
+    Report {
+        /// ID of the job on the server
+        job_id: String,
+        #[arg(long, value_enum, default_value_t = report::ReportFormat::Markdown)]
+        format: report::ReportFormat,
+        /// Lay the report out with this template instead
+        #[arg(long, value_name = "FILE", conflicts_with = "format")]
+        template: Option<PathBuf>,
+        /// A note to add before the ones the server keeps
+        #[arg(long, value_name = "TEXT")]
+        notes: Option<String>,
+        /// Write the report to this file instead of stdout ("-" for stdout)
+        #[arg(long, value_name = "FILE")]
+        output_file: Option<PathBuf>,
+        /// Overwrite --output-file if it exists
+        #[arg(long)]
+        force: bool,
+    },
+    /// Write schedules for paperwork outside the station
+    Export {
+        #[command(subcommand)]
+        action: ExportAction,
+    },
+    /// Rehearse a job's pass locally: countdown, pointing, Doppler and decoder changes.
+    /// Nothing is sent to the station; space pauses in a terminal.
+    ///
+    /// Examples: rustar-cli examples durations, batch
+    Simulate {
+        /// Scheduled job to rehearse
+        #[arg(long, required_unless_present = "file", conflicts_with = "file")]
+        job_id: Option<String>,
+        /// Rehearse the job defined in this file instead (one job, or a batch file holding one)
+        #[arg(long, value_name = "FILE")]
+        file: Option<PathBuf>,
+        /// Replay speed, e.g. 60x for a minute per second
+        #[arg(long, default_value = "1x")]
+        speed: String,
+        /// Simulated time between rows
+        #[arg(long, default_value = "10s")]
+        step: String,
+        /// Countdown before the window opens
+        #[arg(long, default_value = "5m")]
+        lead: String,
+    },
+    /// Manage the satellite alias book and its frequency presets
+    ///
+    /// Examples: rustar-cli examples frequencies, tle
+    Sat {
+        #[command(subcommand)]
+        action: SatAction,
+    },
+    /// Run a local stand-in for the ground station API, for training and testing
+    #[command(name = "mock-server")]
+    MockServer {
+        /// Port to listen on (127.0.0.1)
+        #[arg(long, default_value_t = 3001)]
+        port: u16,
+        /// Keep jobs in this JSON file between runs
+        #[arg(long, value_name = "FILE")]
+        state_file: Option<PathBuf>,
+    },
+    /// Watch station and job messages on the profile's MQTT broker
+    ///
+    /// Examples: rustar-cli examples scripting
+    Mqtt {
+        #[command(subcommand)]
+        action: MqttAction,
+    },
+    /// Set up a profile: base URL (checked at once), station coordinates and timezone.
+    /// --base-url and --coords given here are saved to the profile; other profiles are kept.
+    Init {
+        /// Take every setting from the flags instead of prompting, for provisioning scripts
+        #[arg(long)]
+        non_interactive: bool,
+        /// Day boundaries for stats and bare dates: utc, local or an offset like +02:00
+        #[arg(long, allow_hyphen_values = true, value_parser = init::parse_timezone)]
+        timezone: Option<String>,
+    },
+    /// Show the settings the CLI resolved from flags, environment and files
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Cancel the most recent submission, or revert the most recent reschedule, if it was
+    /// made within the last few minutes (the profile's undo_window_minutes, 10 by default)
+    Undo {
+        /// Undo without asking for confirmation
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Manage related jobs submitted with jobs add --campaign as one set
+    Campaign {
+        #[command(subcommand)]
+        action: CampaignAction,
+    },
+    /// Inspect or submit jobs saved while the station was unreachable
+    Queue {
+        #[command(subcommand)]
+        action: QueueAction,
+    },
+    /// Show the local log of commands that changed jobs, elements or settings
+    ///
+    /// Examples: rustar-cli examples datetimes
+    Audit {
+        /// Only show entries at or after this date or datetime
+        #[arg(long)]
+        since: Option<String>,
+        /// Only show entries recorded for this operating system user
+        #[arg(long)]
+        user: Option<String>,
+    },
+    /// Manage the answers remembered by the satellite and frequency prompts
+    History {
+        #[command(subcommand)]
+        action: HistoryAction,
+    },
+    /// Check the hash-chained receipts left by accepted submissions
+    Receipts {
+        #[command(subcommand)]
+        action: ReceiptsAction,
+    },
+    /// Remove old prompt answers, cache files and audit entries, and queued jobs whose
+    /// start has passed, after a report of what goes
+    ///
+    /// Examples: rustar-cli examples durations
+    Purge {
+        /// Remove what is older than this, e.g. 30d
+        #[arg(long, value_name = "DURATION", default_value = "90d", value_parser = parse::duration)]
+        older_than: chrono::Duration,
+        /// Stores to purge, comma-separated (all of them by default)
+        #[arg(long, value_enum, value_delimiter = ',', default_values_t = purge::Target::ALL)]
+        what: Vec<purge::Target>,
+        /// Remove without asking for confirmation
+        #[arg(long)]
+        yes: bool,
+        /// Only report what would be removed
+        #[arg(long, conflicts_with = "yes")]
+        dry_run: bool,
+    },
+    /// Carry this CLI's config, aliases, prompt history and audit log to another machine
+    Backup {
+        #[command(subcommand)]
+        action: BackupAction,
+    },
+    /// Show the command aliases of the active profile
+    Alias {
+        #[command(subcommand)]
+        action: AliasAction,
+    },
+    /// Report the layout version of every local store this CLI writes, and probe the
+    /// rotator when the profile sets a rotator_check
+    Doctor {
+        /// Also show a file written by --transcript, for reviewing before it is sent
+        #[arg(long, value_name = "FILE")]
+        attach_transcript: Option<PathBuf>,
+    },
+    /// Check a newly deployed station server end to end: health, capabilities, then a
+    /// short receive-only job submitted, listed, fetched, updated and cancelled, each step
+    /// timed. Stops at the first failure with the requests and bodies involved; refused on
+    /// protected profiles.
+    SmokeTest {
+        /// Cancel the job when a later step fails, instead of leaving it for a look
+        #[arg(long)]
+        cleanup: bool,
+    },
+    /// Run commands one after another at a prompt, keeping the configuration, the API
+    /// connection and its caches between them
+    ///
+    /// Commands are typed as they follow rustar-cli, e.g. jobs list; help lists them, the
+    /// up and down arrows go through earlier ones, and exit leaves. Flags given to shell
+    /// itself, such as --output json, apply to every command in it.
+    Shell,
+    /// Print the JSON Schema of an input file format, or check a file against it
+    ///
+    /// Examples: rustar-cli examples batch
+    Schema {
+        #[arg(value_enum, default_value_t = input_schema::InputKind::JobsBatch)]
+        kind: input_schema::InputKind,
+        /// Check this file as KIND, with every pre-flight check a submission makes, and
+        /// exit without submitting anything
+        #[arg(long, value_name = "FILE")]
+        validate_only: Option<PathBuf>,
+    },
+    /// Print runnable examples of the input syntaxes, by topic; `help examples TOPIC`
+    /// works as well
+    Examples {
+        /// Topic to show; lists the topics when omitted
+        #[arg(value_enum)]
+        topic: Option<examples::Topic>,
+    },
+    // The names jobs had before the jobs namespace, forwarded with a deprecation note
+    /// Deprecated: use `jobs add`
+    #[command(name = "add-job", hide = true)]
+    AddJob(AddJobArgs),
+    /// Deprecated: use `jobs list`
+    #[command(name = "list-jobs", hide = true)]
+    ListJobs(ListJobsArgs),
+    /// Deprecated: use `jobs next`
+    #[command(name = "next-job", hide = true)]
+    NextJob(NextJobArgs),
+    /// Deprecated: use `jobs reschedule`
+    #[command(hide = true)]
+    Reschedule(RescheduleArgs),
+    /// Deprecated: use `jobs cancel`
+    #[command(name = "cancel-job", hide = true)]
+    CancelJob(CancelJobArgs),
+    /// Deprecated: use `jobs diff`
+    #[command(name = "diff-job", hide = true)]
+    DiffJob(DiffJobArgs),
+    /// Write the man pages or markdown reference for packaging
+    #[command(name = "generate-docs", hide = true)]
+    GenerateDocs {
+        #[arg(long, value_enum)]
+        format: docs::DocsFormat,
+        /// Directory to write the files into (created if missing)
+        #[arg(long, value_name = "DIR")]
+        out_dir: PathBuf,
+    },
+}
+
+impl Commands {
+    /// The `jobs` form of a deprecated top-level job command, after a note naming it;
+    /// every other command as it is
+    fn canonical(self) -> Commands {
+        let (old, new, action) = match self {
+            Commands::AddJob(args) => ("add-job", "jobs add", JobsAction::Add(args)),
+            Commands::ListJobs(args) => ("list-jobs", "jobs list", JobsAction::List(args)),
+            Commands::NextJob(args) => ("next-job", "jobs next", JobsAction::Next(args)),
+            Commands::Reschedule(args) => (
+                "reschedule",
+                "jobs reschedule",
+                JobsAction::Reschedule(args),
+            ),
+            Commands::CancelJob(args) => ("cancel-job", "jobs cancel", JobsAction::Cancel(args)),
+            Commands::DiffJob(args) => ("diff-job", "jobs diff", JobsAction::Diff(args)),
+            command => return command,
+        };
+        eprintln!("⚠️ {} is deprecated; use rustar-cli {}", old, new);
+        Commands::Jobs { action }
+    }
+
+    /// Whether the command can write `format`; text and JSON work everywhere
+    fn supports(&self, format: OutputFormat) -> bool {
+        match format {
+            OutputFormat::Text | OutputFormat::Json => true,
+            OutputFormat::Csv => {
+                matches!(
+                    self,
+                    Commands::Jobs {
+                        action: JobsAction::List(_)
+                    } | Commands::Stats { .. }
+                        | Commands::Shell
+                )
+            }
+            OutputFormat::Ndjson => {
+                matches!(
+                    self,
+                    Commands::Jobs {
+                        action: JobsAction::List(_)
+                    } | Commands::Mqtt { .. }
+                        | Commands::Shell
+                )
+            }
+        }
+    }
+
+    /// Whether the command works against a station, so a first run with nothing configured
+    /// offers to set one up
+    fn wants_setup(&self) -> bool {
+        !matches!(
+            self,
+            Commands::Init { .. }
+                | Commands::Config { .. }
+                | Commands::Sat { .. }
+                | Commands::Audit { .. }
+                | Commands::History { .. }
+                | Commands::Receipts { .. }
+                | Commands::Purge { .. }
+                | Commands::Backup { .. }
+                | Commands::Alias { .. }
+                | Commands::Doctor { .. }
+                | Commands::Examples { .. }
+                | Commands::GenerateDocs { .. }
+                | Commands::MockServer { .. }
+                | Commands::Quick { .. }
+                | Commands::Schema { .. }
+                | Commands::Tle {
+                    action: TleAction::Compare { .. } | TleAction::RefreshAll { .. }
+                }
+        )
+    }
+
+    /// The command's name, for commands that change anything: jobs and elements on the
+    /// station, or the settings, aliases and stores this CLI keeps (caches excepted).
+    /// Matched without a catch-all, so a new command cannot be added without deciding;
+    /// read-only terminals refuse every command named here.
+    fn changes(&self) -> Option<&'static str> {
+        match self {
+            Commands::Jobs { action } => match action {
+                JobsAction::Add(_) => Some("jobs add"),
+                JobsAction::Reschedule(_) => Some("jobs reschedule"),
+                JobsAction::Update(_) => Some("jobs update"),
+                JobsAction::Cancel(_) => Some("jobs cancel"),
+                JobsAction::Restore(_) => Some("jobs restore"),
+                JobsAction::Rebase(_) => Some("jobs rebase"),
+                JobsAction::ImportHandoff(_) => Some("jobs import-handoff"),
+                JobsAction::Sweep(args) => (!args.dry_run).then_some("jobs sweep"),
+                JobsAction::AuditTles(args) => args.refresh.then_some("jobs audit-tles --refresh"),
+                JobsAction::List(_)
+                | JobsAction::Next(_)
+                | JobsAction::Tail(_)
+                | JobsAction::Diff(_)
+                | JobsAction::Download(_)
+                | JobsAction::Handoff(_) => None,
+            },
+            Commands::Import { dry_run, .. } => (!dry_run).then_some("import"),
+            Commands::Predict { pick, .. } => pick.then_some("predict --pick"),
+            Commands::Quick { .. } => Some("quick"),
+            Commands::Tle { action } => match action {
+                TleAction::Push { .. } => Some("tle push"),
+                TleAction::Pull { .. }
+                | TleAction::Compare { .. }
+                | TleAction::RefreshAll { .. } => None,
+            },
+            Commands::Sat { action } => match action {
+                SatAction::SetFreq { .. } => Some("sat set-freq"),
+                SatAction::Group { action } => match action {
+                    GroupAction::Add { .. } => Some("sat group add"),
+                    GroupAction::Remove { .. } => Some("sat group remove"),
+                    GroupAction::List => None,
+                },
+                SatAction::List => None,
+            },
+            Commands::Init { .. } => Some("init"),
+            Commands::Config { action } => match action {
+                ConfigAction::SetLocation { .. } => Some("config set-location"),
+                ConfigAction::List { .. } | ConfigAction::Resolve => None,
+            },
+            Commands::Undo { .. } => Some("undo"),
+            Commands::SmokeTest { .. } => Some("smoke-test"),
+            Commands::Campaign { action } => match action {
+                CampaignAction::Cancel { .. } => Some("campaign cancel"),
+                CampaignAction::Clone { .. } => Some("campaign clone"),
+                CampaignAction::List | CampaignAction::Show { .. } => None,
+            },
+            Commands::Queue { action } => match action {
+                QueueAction::Flush => Some("queue flush"),
+                QueueAction::List => None,
+            },
+            Commands::History { action } => match action {
+                HistoryAction::ClearPrompts => Some("history clear-prompts"),
+                HistoryAction::List | HistoryAction::Show { .. } | HistoryAction::Replay { .. } => {
+                    None
+                }
+            },
+            Commands::Purge { dry_run, .. } => (!dry_run).then_some("purge"),
+            Commands::Backup { action } => match action {
+                BackupAction::Import { .. } => Some("backup import"),
+                BackupAction::Export { .. } => None,
+            },
+            Commands::Stats { .. }
+            | Commands::StationInfo { .. }
+            | Commands::Whoami
+            | Commands::LookAngles { .. }
+            | Commands::Report { .. }
+            | Commands::Export { .. }
+            | Commands::Simulate { .. }
+            | Commands::MockServer { .. }
+            | Commands::Mqtt { .. }
+            | Commands::Audit { .. }
+            | Commands::Receipts { .. }
+            | Commands::Alias { .. }
+            | Commands::Doctor { .. }
+            | Commands::Schema { .. }
+            | Commands::Examples { .. }
+            | Commands::GenerateDocs { .. }
+            | Commands::Shell => None,
+            Commands::AddJob(_)
+            | Commands::ListJobs(_)
+            | Commands::NextJob(_)
+            | Commands::Reschedule(_)
+            | Commands::CancelJob(_)
+            | Commands::DiffJob(_) => unreachable!("forwarded to jobs by canonical()"),
+        }
+    }
+
+    /// Name under which the command is recorded in the audit log, for commands that change something.
+    /// Job commands keep their old top-level names so undo still finds entries logged before `jobs`.
+    fn audit_name(&self) -> Option<&'static str> {
+        self.changes().map(|name| match name {
+            "jobs add" => "add-job",
+            "jobs reschedule" => "reschedule",
+            "jobs update" => "update-job",
+            "jobs cancel" => "cancel-job",
+            "jobs restore" => "restore-job",
+            "jobs rebase" => "rebase-job",
+            "jobs import-handoff" => "import-handoff",
+            "jobs audit-tles --refresh" => "audit-tles --refresh",
+            name => name,
+        })
+    }
+
+    /// For invocations that would prompt: the command's name and the flags that avoid it.
+    /// Checked before anything runs, so a cron job fails on its first line rather than mid-flow.
+    fn prompts(&self) -> Option<(&'static str, &'static [&'static str])> {
+        match self {
+            Commands::Jobs {
+                action: JobsAction::Add(AddJobArgs { batch: None, .. }),
+            } => Some(("jobs add", &["--batch <FILE>", "--batch - (jobs on stdin)"])),
+            Commands::Predict { pick: true, .. } => Some((
+                "predict --pick",
+                &["predict without --pick, then jobs add --batch with the chosen window"],
+            )),
+            Commands::Predict {
+                norad_id,
+                intl_designator,
+                alias_group: None,
+                tle_file: None,
+                ..
+            } if norad_id.is_empty() && intl_designator.is_empty() => Some((
+                "predict",
+                &[
+                    "--norad-id <ID>",
+                    "--intl-designator <DESIGNATOR>",
+                    "--alias-group <NAME>",
+                    "--tle-file <FILE>",
+                ],
+            )),
+            Commands::Jobs {
+                action: JobsAction::Cancel(CancelJobArgs { yes: false, .. }),
+            } => Some(("jobs cancel", &["--yes"])),
+            Commands::Jobs {
+                action:
+                    JobsAction::Sweep(SweepArgs {
+                        yes: false,
+                        dry_run: false,
+                        ..
+                    }),
+            } => Some(("jobs sweep", &["--yes", "--dry-run"])),
+            Commands::Campaign {
+                action: CampaignAction::Cancel { yes: false, .. },
+            } => Some(("campaign cancel", &["--yes"])),
+            Commands::Undo { yes: false } => Some(("undo", &["--yes"])),
+            Commands::Purge {
+                yes: false,
+                dry_run: false,
+                ..
+            } => Some(("purge", &["--yes", "--dry-run"])),
+            _ => None,
+        }
+    }
+}
+
+// Parsed once per command; boxing `jobs add` would only get in the way of matching on it
+#[allow(clippy::large_enum_variant)]
+#[derive(Subcommand, Debug)]
+enum JobsAction {
     /// Add a new tracking job to the ground station
-    #[command(name = "add-job")]
-    AddJob,
-}
-
-struct UserInput {
-    start_datetime: DateTime<Utc>,
-    end_datetime: DateTime<Utc>,
-    tle_data: TleData,
-    rx_frequency: f64,
-    tx_frequency: f64,
-}
-
-/// Parse user-friendly date/time format to UTC DateTime
-fn parse_user_datetime(
-    date_str: &str,
-    time_str: &str,
-) -> Result<DateTime<Utc>, Box<dyn std::error::Error>> {
-    // Parse date in format YYYY-MM-DD
-    let date = NaiveDate::parse_from_str(date_str, "%Y-%m-%d")?;
-
-    // Parse time in format HH:MM or HH:MM:SS
-    let time = if time_str.matches(':').count() == 1 {
-        // Format HH:MM, add seconds
-        NaiveTime::parse_from_str(&format!("{}:00", time_str), "%H:%M:%S")?
-    } else {
-        // Format HH:MM:SS
-        NaiveTime::parse_from_str(time_str, "%H:%M:%S")?
-    };
+    Add(AddJobArgs),
+    /// List the jobs scheduled on the ground station
+    List(ListJobsArgs),
+    /// Print the next job to start on one line, exiting with 3 when there is none
+    Next(NextJobArgs),
+    /// Keep a view of the jobs that just ended, the running ones and the next ones on screen
+    Tail(TailJobsArgs),
+    /// Move a job that has not started yet to a new window
+    Reschedule(RescheduleArgs),
+    /// Change some fields of a job that has not started, from a JSON merge patch
+    Update(UpdateJobArgs),
+    /// Cancel jobs on the server after a preview, asking why unless --reason is given
+    Cancel(CancelJobArgs),
+    /// Bring back a cancelled job the server has not purged yet, if its window is still free
+    Restore(RestoreJobArgs),
+    /// Move a job to start when another one ends, keeping its length
+    Rebase(RebaseJobArgs),
+    /// Compare a local job definition with the job the server stored
+    Diff(DiffJobArgs),
+    /// Fetch the recordings a job produced, resuming broken-off downloads and checking
+    /// each against the server's digest
+    Download(DownloadArgs),
+    /// List upcoming jobs whose elements will be too old by the time of their pass, and
+    /// with --refresh send them fresh ones. Exits with 5 while any such job is left.
+    #[command(name = "audit-tles")]
+    AuditTles(AuditTlesArgs),
+    /// Step receive-only jobs back to back across a band, to find a downlink, after a
+    /// preview of the whole plan
+    Sweep(SweepArgs),
+    /// Write a job with where it came from to a bundle a partner station can import
+    Handoff(HandoffArgs),
+    /// Submit a job from another station's handoff bundle, checked against this station
+    ImportHandoff(ImportHandoffArgs),
+}
+
+#[derive(clap::Args, Debug)]
+struct AddJobArgs {
+    /// Satellite alias whose element set and frequency presets fill in the prompts
+    #[arg(long, value_name = "ALIAS", conflicts_with = "batch")]
+    satellite: Option<String>,
+    /// Submit every job from a batch file instead of prompting: JSON, or CSV when the file
+    /// ends in .csv or --format csv is given ("-" reads stdin)
+    #[arg(long, value_name = "FILE")]
+    batch: Option<PathBuf>,
+    /// Batch file format, detected from the extension when omitted
+    #[arg(long, value_enum, requires = "batch")]
+    format: Option<batch::BatchFormat>,
+    /// Write a per-entry outcome report for the batch run ("-" for stdout)
+    #[arg(long, value_name = "FILE", requires = "batch")]
+    report: Option<PathBuf>,
+    /// Overwrite the --report file if it exists
+    #[arg(long, requires = "report")]
+    force: bool,
+    /// Submit only the entries that pass pre-flight validation
+    #[arg(long, requires = "batch")]
+    skip_invalid: bool,
+    /// Uplink power in dBm EIRP (defaults to the profile's tx_limits.default_power_dbm)
+    #[arg(
+        long,
+        value_name = "DBM",
+        conflicts_with = "batch",
+        allow_hyphen_values = true
+    )]
+    tx_power: Option<f64>,
+    /// Share of the window the uplink is keyed, as a percentage or fraction (e.g. 25%)
+    #[arg(long, value_name = "SHARE", conflicts_with = "batch", value_parser = regulatory::parse_duty_cycle)]
+    duty_cycle: Option<f64>,
+    /// Listen this far off the published downlink (e.g. +2.1k, -500), in place of the
+    /// --satellite preset's offset
+    #[arg(
+        long,
+        value_name = "OFFSET",
+        allow_hyphen_values = true,
+        value_parser = frequency::parse_offset,
+        conflicts_with_all = ["batch", "edit"]
+    )]
+    rx_offset: Option<i64>,
+    /// Feed polarization for the pass (defaults to the station's)
+    #[arg(long, value_enum, conflicts_with = "batch")]
+    polarization: Option<Polarization>,
+    /// What the rotator does after the window (defaults to the station's)
+    #[arg(long, value_enum, conflicts_with = "batch")]
+    post_pass_action: Option<PostPassAction>,
+    /// Put the job, or every job of the batch, in this campaign
+    #[arg(long, value_name = "NAME", value_parser = campaign::parse_name)]
+    campaign: Option<String>,
+    /// Linear transponder downlink passband (e.g. 145.9M-145.93M); the uplink is then paired
+    #[arg(long, value_name = "LOW-HIGH", requires = "uplink_band", value_parser = transponder::parse_band, conflicts_with = "batch")]
+    downlink_band: Option<(Frequency, Frequency)>,
+    /// Linear transponder uplink passband, paired with --downlink-band
+    #[arg(long, value_name = "LOW-HIGH", requires = "downlink_band", value_parser = transponder::parse_band, conflicts_with = "batch")]
+    uplink_band: Option<(Frequency, Frequency)>,
+    /// The transponder inverts: the bottom of the downlink band pairs with the top of the uplink band
+    #[arg(long, requires = "downlink_band", conflicts_with = "batch")]
+    inverting: bool,
+    /// Start when this job on the server ends, instead of prompting for the start
+    #[arg(long, value_name = "JOB_ID", conflicts_with = "batch")]
+    after: Option<String>,
+    /// Time between the end of the --after job and the start of this one (e.g. 120s)
+    #[arg(long, value_name = "DURATION", requires = "after", default_value = "0s", value_parser = parse::duration)]
+    gap: chrono::Duration,
+    /// Schedule the same job at the station of each of these profiles (e.g. north,south),
+    /// each tracking job on its own pass there when the profile gives the position
+    #[arg(long, value_name = "NAMES", value_delimiter = ',', conflicts_with_all = ["batch", "after"])]
+    profiles: Option<Vec<String>>,
+    /// Write the job in $VISUAL or $EDITOR, as one entry of a --batch file, instead of
+    /// answering prompts
+    #[arg(long, conflicts_with_all = ["batch", "profiles", "after", "satellite", "downlink_band"])]
+    edit: bool,
+    /// Start the --edit document from this job file instead of the defaults
+    #[arg(long, value_name = "FILE", requires = "edit")]
+    template: Option<PathBuf>,
+    /// Hold the antenna at this azimuth in degrees instead of tracking a satellite, with
+    /// --fixed-el (e.g. for a geostationary satellite)
+    #[arg(
+        long,
+        value_name = "DEGREES",
+        requires = "fixed_el",
+        conflicts_with_all = ["batch", "edit", "satellite", "downlink_band"]
+    )]
+    fixed_az: Option<f64>,
+    /// Elevation in degrees of the --fixed-az pointing
+    #[arg(long, value_name = "DEGREES", requires = "fixed_az")]
+    fixed_el: Option<f64>,
+    /// What the --fixed-az pointing is aimed at, shown in place of a satellite name
+    #[arg(long, value_name = "NAME", requires = "fixed_az")]
+    fixed_name: Option<String>,
+    /// When the server accepts a job to create it later, answer at once with where to
+    /// follow it instead of waiting for the outcome
+    #[arg(long, conflicts_with = "profiles")]
+    no_wait: bool,
+    /// Longest wait for a job the server accepted to create later (e.g. 2m)
+    #[arg(long, value_name = "DURATION", default_value = "60s", value_parser = parse::duration, conflicts_with_all = ["no_wait", "profiles"])]
+    wait_timeout: chrono::Duration,
+}
+
+#[derive(clap::Args, Debug)]
+struct ListJobsArgs {
+    /// Draw the jobs on a timeline, shading maintenance windows
+    #[arg(long)]
+    timeline: bool,
+    /// Only list tracking jobs for the satellites of this alias-book group
+    #[arg(long, value_name = "GROUP")]
+    satellite_group: Option<String>,
+    /// Show every job in the table instead of the first 100
+    #[arg(long)]
+    all: bool,
+    /// Also list jobs cancelled within the last day, which can still be restored
+    #[arg(long)]
+    include_cancelled: bool,
+    /// When the list times out or breaks off, show the jobs received so far (marked as
+    /// incomplete, exit status 4) instead of failing
+    #[arg(long)]
+    partial_ok: bool,
+}
+
+#[derive(clap::Args, Debug)]
+struct NextJobArgs {
+    /// Only report a job starting within this long from now (e.g. 2h, 90m)
+    #[arg(long, value_name = "DURATION")]
+    within: Option<String>,
+}
+
+#[derive(clap::Args, Debug)]
+struct TailJobsArgs {
+    /// Seconds between reads of the job list when the server sends no job events
+    #[arg(long, default_value_t = 5, value_name = "SECONDS")]
+    interval: u64,
+    /// How many of the jobs that ended to show
+    #[arg(long, default_value_t = 3, value_name = "N")]
+    recent: usize,
+    /// How many of the jobs still to start to show
+    #[arg(long, default_value_t = 5, value_name = "N")]
+    upcoming: usize,
+    /// Stop after this many reads of the job list instead of running until Ctrl-C
+    #[arg(long, value_name = "N")]
+    count: Option<usize>,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(group(
+    clap::ArgGroup::new("change")
+        .required(true)
+        .multiple(true)
+        .args(["shift", "start", "end", "to_next_pass"])
+))]
+struct RescheduleArgs {
+    /// ID of the job on the server
+    id: String,
+    /// Move the whole window by a signed amount (e.g. +90s, -2m, +1h)
+    #[arg(
+        long,
+        allow_hyphen_values = true,
+        conflicts_with_all = ["start", "end", "to_next_pass"]
+    )]
+    shift: Option<String>,
+    /// New start; given alone, the job keeps its length
+    #[arg(long, conflicts_with = "to_next_pass")]
+    start: Option<String>,
+    /// New end
+    #[arg(long, conflicts_with = "to_next_pass")]
+    end: Option<String>,
+    /// Use the satellite's next pass over the station, from its current elements
+    #[arg(long)]
+    to_next_pass: bool,
+}
+
+#[derive(clap::Args, Debug)]
+struct RebaseJobArgs {
+    /// ID of the job to move
+    id: String,
+    /// ID of the job it should follow
+    #[arg(long, value_name = "JOB_ID")]
+    after: String,
+    /// Time between the end of the --after job and the start of this one; defaults to the
+    /// gap the job was chained with, or none
+    #[arg(long, value_name = "DURATION", value_parser = parse::duration)]
+    gap: Option<chrono::Duration>,
+}
+
+#[derive(clap::Args, Debug)]
+struct CancelJobArgs {
+    /// IDs of the jobs on the server
+    #[arg(required = true)]
+    ids: Vec<String>,
+    /// Cancel without asking for confirmation
+    #[arg(long)]
+    yes: bool,
+    /// Why: weather, hardware, duplicate, tle-error, other, or one of the profile's cancel_reasons
+    #[arg(long)]
+    reason: Option<String>,
+    /// Free-text details; required with --reason other
+    #[arg(long)]
+    reason_text: Option<String>,
+    /// When a job was changed on the server since it was listed: abort, leave it (theirs)
+    /// or cancel it anyway (mine); asked at a terminal, abort otherwise
+    #[arg(long, value_enum, value_name = "HOW")]
+    on_conflict: Option<conflict::OnConflict>,
+}
+
+#[derive(clap::Args, Debug)]
+struct UpdateJobArgs {
+    /// ID of the job on the server
+    id: String,
+    /// Fields to change as a JSON object, e.g. '{"rx_frequency": 145825000}'; null unsets one
+    #[arg(long, value_name = "JSON")]
+    patch: String,
+    /// When the job was changed on the server since it was fetched: abort, keep their
+    /// version (theirs) or send the change on top of it (mine); asked at a terminal,
+    /// abort otherwise
+    #[arg(long, value_enum, value_name = "HOW")]
+    on_conflict: Option<conflict::OnConflict>,
+}
+
+#[derive(clap::Args, Debug)]
+struct RestoreJobArgs {
+    /// ID of the cancelled job on the server
+    id: String,
+}
+
+#[derive(clap::Args, Debug)]
+struct DiffJobArgs {
+    /// ID of the job on the server
+    id: String,
+    /// Job definition to compare (a job object or a one-job batch file)
+    #[arg(long, value_name = "FILE")]
+    file: PathBuf,
+    /// Frequency differences up to this many Hz count as equal
+    #[arg(long, value_name = "HZ", default_value_t = 1.0)]
+    frequency_tolerance: f64,
+}
+
+#[derive(clap::Args, Debug)]
+struct DownloadArgs {
+    /// ID of the job on the server
+    id: String,
+    /// Directory the recordings are written to
+    #[arg(long, value_name = "DIR", default_value = ".")]
+    dir: PathBuf,
+    /// Fetch only this recording (repeatable)
+    #[arg(long = "only", value_name = "NAME")]
+    only: Vec<String>,
+    /// Transfer nothing: check the files already in --dir against the server's digests
+    #[arg(long)]
+    verify_only: bool,
+}
+
+#[derive(clap::Args, Debug)]
+struct AuditTlesArgs {
+    /// Oldest the elements may be at the start of a job
+    #[arg(long, value_name = "DURATION", default_value = "5d")]
+    max_age_at_pass: String,
+    /// Fetch fresh elements for each satellite found and update its jobs
+    #[arg(long)]
+    refresh: bool,
+}
+
+#[derive(clap::Args, Debug)]
+struct SweepArgs {
+    /// Satellite alias, NORAD catalog number or international designator
+    #[arg(long, value_name = "SATELLITE")]
+    satellite: String,
+    /// Frequency of the first job (e.g. 435M)
+    #[arg(long, value_name = "FREQ", value_parser = Frequency::parse)]
+    from: Frequency,
+    /// Highest frequency of the sweep; the last job is the last step at or below it
+    #[arg(long, value_name = "FREQ", value_parser = Frequency::parse)]
+    to: Frequency,
+    /// Distance between the frequencies of consecutive jobs (e.g. 25k)
+    #[arg(long, value_name = "FREQ", value_parser = Frequency::parse)]
+    step: Frequency,
+    /// How long each job listens (e.g. 60s)
+    #[arg(long, value_name = "DURATION", value_parser = parse::duration)]
+    dwell: chrono::Duration,
+    /// When the first job starts; defaults to the satellite's next pass over the station
+    #[arg(long, value_name = "TIME")]
+    start: Option<String>,
+    /// Campaign for every job of the sweep (defaults to "sweep-<satellite>-<start>")
+    #[arg(long, value_name = "NAME", value_parser = campaign::parse_name)]
+    campaign: Option<String>,
+    /// Submit without asking for confirmation
+    #[arg(long)]
+    yes: bool,
+    /// Show and check the plan without submitting it
+    #[arg(long)]
+    dry_run: bool,
+}
+
+#[derive(clap::Args, Debug)]
+struct HandoffArgs {
+    /// ID of the job on the server
+    id: String,
+    /// Bundle to write ("-" for stdout)
+    #[arg(long, value_name = "FILE")]
+    out: Option<PathBuf>,
+    /// Overwrite the --out file if it exists
+    #[arg(long, requires = "out")]
+    force: bool,
+    /// Message for the receiving operator, in place of the job's notes on the server
+    #[arg(long, value_name = "TEXT")]
+    notes: Option<String>,
+}
+
+#[derive(clap::Args, Debug)]
+struct ImportHandoffArgs {
+    /// Bundle written by jobs handoff
+    file: PathBuf,
+}
+
+#[derive(Subcommand, Debug)]
+enum ExportAction {
+    /// Planned transmissions for frequency coordination: one row per TX job, with the
+    /// profile's callsign, operator and station coordinates
+    Coordination {
+        /// First day or datetime to cover (UTC)
+        #[arg(long)]
+        from: String,
+        /// Last day to cover, inclusive, or a datetime to stop before (UTC)
+        #[arg(long)]
+        to: String,
+        #[arg(long, value_enum, default_value_t = coordination::ExportFormat::Csv)]
+        format: coordination::ExportFormat,
+        /// Write the schedule to this file instead of stdout ("-" for stdout)
+        #[arg(long, value_name = "FILE")]
+        output_file: Option<PathBuf>,
+        /// Overwrite --output-file if it exists
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum MqttAction {
+    /// Print job and status messages as they arrive (--output ndjson: one event record per line)
+    Follow {
+        /// Topic prefix (defaults to the profile's topic_prefix)
+        #[arg(long)]
+        topic: Option<String>,
+        /// Only show messages about this job
+        #[arg(long)]
+        job: Option<String>,
+        /// Draw the live signal line with ASCII bars instead of Unicode blocks
+        #[arg(long)]
+        ascii: bool,
+        /// Serve Prometheus metrics on this address while following, e.g. 127.0.0.1:9188
+        #[arg(long, value_name = "ADDR")]
+        metrics_listen: Option<std::net::SocketAddr>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum TleAction {
+    /// Upload an element set to the catalog (prompts for it unless --file or --norad is given)
+    Push {
+        /// Read a two- or three-line element set from a file
+        #[arg(long, value_name = "FILE", conflicts_with = "norad")]
+        file: Option<PathBuf>,
+        /// Fetch the current element set for this NORAD ID or international designator
+        #[arg(long, value_name = "ID", value_parser = satellite_id::catalog_id_arg)]
+        norad: Option<satellite_id::SatelliteId>,
+        /// Replace the catalog's elements for an existing NORAD ID
+        #[arg(long)]
+        update: bool,
+    },
+    /// Show the catalog's elements for a NORAD ID, diffed against a local copy
+    Pull {
+        /// Catalog number, Alpha-5 (A0001) included
+        #[arg(value_parser = satellite_id::norad_id_arg)]
+        norad_id: u32,
+        /// Compare against this element set file instead of the fetched one
+        #[arg(long, value_name = "FILE", conflicts_with = "no_compare")]
+        file: Option<PathBuf>,
+        /// Only show the catalog's elements
+        #[arg(long)]
+        no_compare: bool,
+    },
+    /// Show how much two element sets of one satellite differ: the mean elements, and
+    /// how far apart SGP4 puts the satellite at --at
+    Compare {
+        /// First element set: a file, "-" for stdin, "cache:ID" for the cached copy, or a
+        /// NORAD ID or designator to fetch now
+        #[arg(value_parser = tle_compare::parse_source)]
+        a: tle_compare::Source,
+        /// Second element set, in the same forms
+        #[arg(value_parser = tle_compare::parse_source)]
+        b: tle_compare::Source,
+        /// When to compare positions: "now", "now+24h", "now-90m", or a date and time
+        #[arg(long, value_name = "WHEN", default_value = "now", value_parser = parse::instant)]
+        at: DateTime<Utc>,
+        /// Compare element sets of different NORAD IDs
+        #[arg(long)]
+        force: bool,
+    },
+    /// Fetch a fresh element set into the cache for every satellite alias, or every
+    /// member of a group, printing the old and new epochs
+    RefreshAll {
+        /// Only the satellites of this group
+        #[arg(long, value_name = "GROUP")]
+        group: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum SatAction {
+    /// List the aliases and their presets
+    List,
+    /// Edit and show groups that name several satellites at once
+    Group {
+        #[command(subcommand)]
+        action: GroupAction,
+    },
+    /// Set default frequencies, mode and baud for an alias, creating it if needed
+    #[command(name = "set-freq")]
+    SetFreq {
+        /// Alias as used with jobs add --satellite (e.g. ISS)
+        alias: String,
+        /// Catalog number used to fetch the element set; Alpha-5 (A0001) is accepted
+        #[arg(long, value_name = "NORAD_ID", value_parser = satellite_id::norad_id_arg)]
+        norad_id: Option<u32>,
+        /// International designator (1998-067A), used to fetch the elements without a catalog number
+        #[arg(long, value_name = "DESIGNATOR", value_parser = satellite_id::designator_arg)]
+        intl_designator: Option<String>,
+        /// Default downlink (Hz, or with a k/M/G suffix)
+        #[arg(long, value_name = "FREQ")]
+        rx: Option<String>,
+        /// How far off its published downlink the satellite transmits (e.g. +2.1k, -500);
+        /// 0 clears it
+        #[arg(long, value_name = "OFFSET", allow_hyphen_values = true, value_parser = frequency::parse_offset)]
+        rx_offset: Option<i64>,
+        /// Default uplink
+        #[arg(long, value_name = "FREQ")]
+        tx: Option<String>,
+        /// Default modulation or decoder (e.g. AFSK)
+        #[arg(long)]
+        mode: Option<String>,
+        /// Default symbol rate
+        #[arg(long)]
+        baud: Option<u32>,
+        /// Linear transponder downlink passband (e.g. 145.9M-145.93M); the uplink is then paired
+        #[arg(long, value_name = "LOW-HIGH", requires = "uplink_band", value_parser = transponder::parse_band)]
+        downlink_band: Option<(Frequency, Frequency)>,
+        /// Linear transponder uplink passband, paired with --downlink-band
+        #[arg(long, value_name = "LOW-HIGH", requires = "downlink_band", value_parser = transponder::parse_band)]
+        uplink_band: Option<(Frequency, Frequency)>,
+        /// The transponder inverts: the bottom of the downlink band pairs with the top of the uplink band
+        #[arg(long, requires = "downlink_band")]
+        inverting: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum GroupAction {
+    /// Add aliases, groups or NORAD IDs to a group, creating it if needed
+    Add {
+        group: String,
+        #[arg(required = true)]
+        members: Vec<String>,
+    },
+    /// Remove members from a group
+    Remove {
+        group: String,
+        #[arg(required = true)]
+        members: Vec<String>,
+    },
+    /// Show every group, its members and the NORAD IDs it expands to
+    List,
+}
+
+#[derive(Subcommand, Debug)]
+enum ConfigAction {
+    /// List the resolved settings
+    List {
+        /// Show whether each value came from a flag, the environment, a file or a default
+        #[arg(long)]
+        show_origin: bool,
+    },
+    /// Show every resolved setting, where it came from and what it overrode
+    Resolve,
+    /// Store the ground station position in the active profile
+    #[command(name = "set-location", allow_negative_numbers = true)]
+    SetLocation {
+        /// Decimal "lat lon [alt]" or degrees-minutes-seconds such as 34°36'S 58°22'W
+        #[arg(required = true, num_args = 1..)]
+        location: Vec<String>,
+        /// Altitude in meters, overriding one given with the position
+        #[arg(long, value_name = "METERS")]
+        altitude: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum BackupAction {
+    /// Write the config file, alias book, prompt history and audit log to a .tar.gz
+    Export {
+        /// Archive to write ("-" for stdout)
+        file: PathBuf,
+        /// Leave out the config file's passwords, tokens, keys and authorization headers
+        #[arg(long)]
+        no_secrets: bool,
+        /// Overwrite FILE if it exists
+        #[arg(long)]
+        force: bool,
+    },
+    /// Restore a backup; what is already here is merged, skipped or overwritten per
+    /// category, asked about unless given
+    Import {
+        /// Archive written by backup export
+        file: PathBuf,
+        /// What to do with the config file when there is one here
+        #[arg(long, value_enum, value_name = "POLICY")]
+        config: Option<backup::Policy>,
+        /// What to do with the alias book when there is one here
+        #[arg(long, value_enum, value_name = "POLICY")]
+        aliases: Option<backup::Policy>,
+        /// What to do with the prompt history when there is one here
+        #[arg(long, value_enum, value_name = "POLICY")]
+        history: Option<backup::Policy>,
+        /// What to do with the audit log when there is one here
+        #[arg(long, value_enum, value_name = "POLICY")]
+        audit: Option<backup::Policy>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum HistoryAction {
+    /// Forget every remembered prompt answer
+    #[command(name = "clear-prompts")]
+    ClearPrompts,
+    /// List the job snapshots kept in the audit log, most recent first
+    List,
+    /// Show what went into submission <N>: elements, settings and CLI version
+    Show {
+        /// Snapshot number from history list, 1 for the most recent
+        n: usize,
+        /// Also print the exact request body and the server's full answer
+        #[arg(long)]
+        full: bool,
+    },
+    /// Check the exact body of submission <N> against today's rules, to tell whether it
+    /// would still be accepted
+    Replay {
+        /// Snapshot number from history list, 1 for the most recent
+        n: usize,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ReceiptsAction {
+    /// Walk the receipt chain from the first receipt and report where it first breaks: a
+    /// receipt missing, changed or out of order, or a signature that does not verify
+    Verify {
+        /// Directory of receipts to check, e.g. a copy handed to an auditor (defaults to
+        /// this machine's)
+        #[arg(long, value_name = "DIR")]
+        dir: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum AliasAction {
+    /// List the aliases and the default command configured in the profile
+    List,
+}
+
+#[derive(Subcommand, Debug)]
+enum CampaignAction {
+    /// List campaigns with their job counts and spans
+    List,
+    /// Show every job of a campaign with its status
+    Show { name: String },
+    /// Cancel the campaign's jobs that have not ended, after a preview
+    Cancel {
+        name: String,
+        /// Cancel without asking for confirmation
+        #[arg(long)]
+        yes: bool,
+        /// Why: weather, hardware, duplicate, tle-error, other, or one of the profile's cancel_reasons
+        #[arg(long)]
+        reason: Option<String>,
+        /// Free-text details; required with --reason other
+        #[arg(long)]
+        reason_text: Option<String>,
+    },
+    /// Submit the same schedule again, shifted later, with current element sets
+    Clone {
+        name: String,
+        /// How much later the copy runs (e.g. 7d)
+        #[arg(long)]
+        shift: String,
+        /// Campaign name for the copy (defaults to "<name>+<shift>")
+        #[arg(long = "as", value_name = "NAME", value_parser = campaign::parse_name)]
+        new_name: Option<String>,
+        /// Submit only the copies that pass pre-flight validation
+        #[arg(long)]
+        skip_invalid: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum QueueAction {
+    /// List queued jobs
+    List,
+    /// Submit all queued jobs
+    Flush,
+}
 
-    let naive_datetime = NaiveDateTime::new(date, time);
+/// Antenna settings given on the command line, and what the station supports
+struct AntennaChoice {
+    polarization: Option<Polarization>,
+    post_pass_action: Option<PostPassAction>,
+    /// Polarizations the station reported; None when it does not say
+    supported: Option<Vec<Polarization>>,
+}
+
+/// A satellite alias picked for jobs add, with its element set when the alias names one
+struct AliasChoice {
+    name: String,
+    preset: satellites::Preset,
+    tle: Option<TleData>,
+}
 
-    Ok(DateTime::<Utc>::from_naive_utc_and_offset(
-        naive_datetime,
-        Utc,
-    ))
+/// Prompt validator accepting what `parse` accepts, so a typo is asked again on the spot
+/// rather than failing the job after its last question
+fn accepted_by<T>(
+    parse: impl Fn(&str) -> Result<T, CliError> + Clone + 'static,
+) -> impl inquire::validator::StringValidator + 'static {
+    move |input: &str| {
+        Ok(match parse(input.trim()) {
+            Ok(_) => inquire::validator::Validation::Valid,
+            Err(e) => inquire::validator::Validation::Invalid(e.full_message().into()),
+        })
+    }
 }
 
 /// Collect datetime input from user
@@ -58,113 +1573,3103 @@ fn get_datetime_input(
     label: &str,
     date_placeholder: &str,
     time_placeholder: &str,
-) -> Result<DateTime<Utc>, Box<dyn std::error::Error>> {
-    let date = Text::new(&format!("📅 {} date:", label))
+) -> Result<DateTime<Utc>, CliError> {
+    let question = format!("📅 {} date:", label);
+    let date = Text::new(&question)
         .with_placeholder(date_placeholder)
-        .prompt()?;
+        .with_validator(accepted_by(|date| parse::user_datetime(date, "00:00")))
+        .prompt()
+        .recorded(&question)?;
 
-    let time = Text::new(&format!("🕐 {} time:", label))
+    let day = date.trim().to_string();
+    let question = format!("🕐 {} time:", label);
+    let time = Text::new(&question)
         .with_placeholder(time_placeholder)
-        .prompt()?;
+        .with_validator(accepted_by(move |time| parse::user_datetime(&day, time)))
+        .prompt()
+        .recorded(&question)?;
 
-    parse_user_datetime(&date, &time)
+    parse::user_datetime(date.trim(), time.trim())
+        .with_context(|| format!("reading {} time", label))
 }
 
 /// Collect TLE data from user
-fn get_tle_input() -> Result<TleData, Box<dyn std::error::Error>> {
+fn get_tle_input() -> Result<TleData, CliError> {
     let sat_name = Text::new("🛰️ Satellite name:")
         .with_placeholder("ISS (ZARYA)")
-        .prompt()?;
+        .with_autocomplete(history::Suggestions::new(
+            history::Prompt::SatelliteName,
+            None,
+        ))
+        .prompt()
+        .recorded("🛰️ Satellite name:")?;
+
+    let tle = get_tle_lines(&sat_name)?;
+    history::record(history::Prompt::SatelliteName, &sat_name);
+    Ok(tle)
+}
+
+/// Satellite of a new tracking job, searched for in the server's catalog as it is typed.
+/// A catalog entry brings its elements and default frequencies; any other name is
+/// followed by its TLE lines.
+fn get_catalog_tle_input(client: &ApiClient) -> Result<(TleData, satellites::Preset), CliError> {
+    let (sat_name, entry) = catalog::prompt_name(client)?;
+    let (tle, preset) = match entry {
+        Some(entry) => {
+            println!(
+                "📚 {} ({}) from the server catalog",
+                entry.name,
+                entry.identity()
+            );
+            let tle = match entry.tle() {
+                Some(tle) => tle,
+                None => {
+                    println!("   The catalog holds no elements for it; enter them below");
+                    get_tle_lines(&sat_name)?
+                }
+            };
+            (tle, entry.preset())
+        }
+        None => (get_tle_lines(&sat_name)?, satellites::Preset::default()),
+    };
+    history::record(history::Prompt::SatelliteName, &sat_name);
+    Ok((tle, preset))
+}
 
+/// The two element lines of a satellite already named
+fn get_tle_lines(sat_name: &str) -> Result<TleData, CliError> {
     let tle_line1 = Text::new("📡 TLE Line 1:")
         .with_placeholder("1 25544U 98067A   25235.75642456  .00011222  00000+0  20339-3 0  9993")
-        .prompt()?;
+        .prompt()
+        .recorded("📡 TLE Line 1:")?;
 
     let tle_line2 = Text::new("📡 TLE Line 2:")
         .with_placeholder("2 25544  51.6355 332.1708 0003307 260.2831  99.7785 15.50129787525648")
-        .prompt()?;
+        .prompt()
+        .recorded("📡 TLE Line 2:")?;
 
-    Ok(TleData {
-        tle0: sat_name.trim().to_string(),
-        tle1: tle_line1.trim().to_string(),
-        tle2: tle_line2.trim().to_string(),
-    })
+    Ok(tle::assemble(sat_name, &tle_line1, &tle_line2))
 }
 
-/// Collect frequency input from user
-fn get_frequency_input(label: &str, placeholder: &str) -> Result<f64, Box<dyn std::error::Error>> {
-    let freq_str = Text::new(&format!("📡 {} frequency (Hz):", label))
-        .with_placeholder(placeholder)
-        .prompt()?;
+/// Collect frequency input from user, suggesting earlier answers and alias presets
+fn get_frequency_input(
+    label: &str,
+    kind: history::Prompt,
+    placeholder: &str,
+    default: Option<Frequency>,
+) -> Result<Frequency, CliError> {
+    let prompt = format!("📡 {} frequency (Hz, or with a k/M/G suffix):", label);
+    let default = default.map(|hz| hz.to_string());
+    // A preset is shown as the default instead of the generic example
+    let text = match &default {
+        Some(default) => Text::new(&prompt).with_default(default),
+        None => Text::new(&prompt).with_placeholder(placeholder),
+    };
+    let freq_str = text
+        .with_autocomplete(history::Suggestions::new(kind, default.as_deref()))
+        .with_validator(accepted_by(Frequency::parse))
+        .prompt()
+        .recorded(&prompt)?;
+
+    let hz = Frequency::parse(&freq_str).with_context(|| format!("reading {} frequency", label))?;
+    history::record(kind, &hz.to_string());
+    Ok(hz)
+}
 
-    Ok(freq_str.parse()?)
+/// Downlink of a linear transponder, as a frequency or an offset from the passband
+/// centre, with the uplink paired from it
+fn get_linked_frequency_input(
+    passband: &Passband,
+    default: Option<Frequency>,
+) -> Result<(Frequency, Frequency), CliError> {
+    println!("🔗 Linear transponder: {}", passband.describe());
+    let default = default
+        .filter(|hz| passband.uplink_for(*hz).is_ok())
+        .unwrap_or_else(|| passband.downlink_centre())
+        .to_string();
+    let input = Text::new("📡 Downlink frequency, or +/- offset from the passband centre:")
+        .with_default(&default)
+        .with_autocomplete(history::Suggestions::new(
+            history::Prompt::Downlink,
+            Some(&default),
+        ))
+        .prompt()
+        .recorded("📡 Downlink frequency, or +/- offset from the passband centre:")?;
+    let downlink = passband
+        .parse_downlink(&input)
+        .context("reading the downlink")?;
+    let uplink = passband.uplink_for(downlink)?;
+    history::record(history::Prompt::Downlink, &downlink.to_string());
+    println!("   RX {} ↔ TX {}", downlink, uplink);
+    Ok((downlink, uplink))
 }
 
-/// Collect all job information from user
-fn collect_job_info() -> Result<UserInput, Box<dyn std::error::Error>> {
-    println!("🚀 Creating a new tracking job...\n");
+/// Collect the rotator sweep of a calibration job
+fn get_sweep_input() -> Result<SweepPattern, CliError> {
+    let azimuth = Text::new("🧭 Azimuth sweep (start-end/step, degrees):")
+        .with_placeholder("0-360/30")
+        .prompt()
+        .recorded("🧭 Azimuth sweep (start-end/step, degrees):")?;
+    let (az_start, az_end, az_step) =
+        parse::sweep_axis(&azimuth).context("reading the azimuth sweep")?;
 
-    let start_datetime = get_datetime_input("Start", "2025-10-02", "12:00")?;
-    let end_datetime = get_datetime_input("End", "2025-10-02", "12:15")?;
-    let tle_data = get_tle_input()?;
-    let rx_frequency = get_frequency_input("RX", "145800000")?;
-    let tx_frequency = get_frequency_input("TX", "437500000")?;
+    let elevation = Text::new("🧭 Elevation sweep (start-end/step, degrees):")
+        .with_placeholder("0-90/15")
+        .prompt()
+        .recorded("🧭 Elevation sweep (start-end/step, degrees):")?;
+    let (el_start, el_end, el_step) =
+        parse::sweep_axis(&elevation).context("reading the elevation sweep")?;
 
-    Ok(UserInput {
-        start_datetime,
-        end_datetime,
-        tle_data,
-        rx_frequency,
-        tx_frequency,
+    Ok(SweepPattern {
+        az_start,
+        az_end,
+        az_step,
+        el_start,
+        el_end,
+        el_step,
     })
 }
 
-/// Submit job to API
-async fn submit_job(
-    client: &ApiClient,
-    input: UserInput,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let job = JobRequestDTO{
-        start: input.start_datetime,
-        end: input.end_datetime,
-        tle: input.tle_data,
-        rx_frequency: input.rx_frequency,
-        tx_frequency: input.tx_frequency,
+/// What a tracking job points at: a satellite's element set or one fixed direction
+#[derive(Clone, Copy)]
+enum TargetKind {
+    Tle,
+    Fixed,
+}
+
+impl std::fmt::Display for TargetKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            TargetKind::Tle => "🛰️ TLE target",
+            TargetKind::Fixed => "🎯 Fixed pointing (azimuth/elevation)",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// Collect a fixed azimuth/elevation, each checked as it is typed, and what it points at
+fn get_pointing_input() -> Result<FixedPointing, CliError> {
+    let angle = |message: &str,
+                 placeholder: &str,
+                 check: fn(&str, f64) -> Option<validation::Problem>,
+                 field: &'static str| {
+        let text = Text::new(message)
+            .with_placeholder(placeholder)
+            .with_validator(accepted_by(move |input| {
+                let degrees = parse::degrees(input)?;
+                match check(field, degrees) {
+                    Some(problem) => Err(CliError::from(problem)),
+                    None => Ok(degrees),
+                }
+            }))
+            .prompt()
+            .recorded(message)?;
+        parse::degrees(&text).context("reading the pointing")
     };
+    let azimuth = angle(
+        "🧭 Azimuth (degrees, 0-360):",
+        "123.4",
+        validation::check_azimuth,
+        "pointing.azimuth",
+    )?;
+    let elevation = angle(
+        "🧭 Elevation (degrees, 0-90):",
+        "45.6",
+        validation::check_elevation,
+        "pointing.elevation",
+    )?;
+    let name = Text::new("🏷️ Target name (optional):")
+        .with_placeholder("e.g. ES'HAIL-2")
+        .prompt()
+        .recorded("🏷️ Target name (optional):")?;
+    let name = name.trim();
 
-    println!("\n📡 Submitting job to ground station...");
+    Ok(FixedPointing {
+        azimuth,
+        elevation,
+        name: (!name.is_empty()).then(|| name.to_string()),
+    })
+}
 
-    let response = client.add_job(job).await?;
-    println!("✅ Job submitted successfully: {}", response.status);
+/// The pointing of `--fixed-az`, `--fixed-el` and `--fixed-name`, which clap gives together
+fn fixed_pointing(
+    azimuth: Option<f64>,
+    elevation: Option<f64>,
+    name: Option<String>,
+) -> Option<FixedPointing> {
+    azimuth
+        .zip(elevation)
+        .map(|(azimuth, elevation)| FixedPointing {
+            azimuth,
+            elevation,
+            name,
+        })
+}
 
-    Ok(())
+/// Kinds of job the interactive flow can build, kept in a draft as its `job_type`
+#[derive(Clone, Copy, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum JobKind {
+    /// A draft says "pointing" once a fixed direction is picked
+    #[serde(alias = "pointing")]
+    Tracking,
+    Calibration,
+    Test,
 }
 
-#[tokio::main]
-async fn main() {
-    let args = Args::parse();
+impl std::fmt::Display for JobKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            JobKind::Tracking => "🛰️ Satellite tracking",
+            JobKind::Calibration => "🧭 Rotator calibration sweep",
+            JobKind::Test => "🔁 Loopback test",
+        };
+        write!(f, "{}", label)
+    }
+}
 
-    let client = match ApiClient::new() {
-        Ok(client) => client,
-        Err(e) => {
-            eprintln!("Failed to initialize API client: {}", e);
-            std::process::exit(1);
+/// Pick one of `choices`, or nothing to leave it to the station
+fn optional_choice<T: std::fmt::Display + Copy>(
+    message: &str,
+    choices: &[T],
+) -> Result<Option<T>, CliError> {
+    let mut labels = vec!["Station default".to_string()];
+    labels.extend(choices.iter().map(T::to_string));
+    let picked = Select::new(message, labels)
+        .raw_prompt()
+        .recorded(message)?;
+    Ok(picked.index.checked_sub(1).map(|i| choices[i]))
+}
+
+/// Polarization and post-pass prompts of a tracking job, skipped for what the flags set
+fn get_antenna_input(
+    antenna: &AntennaChoice,
+) -> Result<(Option<Polarization>, Option<PostPassAction>), CliError> {
+    let polarization = match antenna.polarization {
+        Some(polarization) => Some(polarization),
+        None => {
+            let all = [
+                Polarization::Rhcp,
+                Polarization::Lhcp,
+                Polarization::H,
+                Polarization::V,
+            ];
+            // Offer only what the station can switch to, when it says
+            let choices: Vec<Polarization> = match &antenna.supported {
+                Some(supported) => all.into_iter().filter(|p| supported.contains(p)).collect(),
+                None => all.to_vec(),
+            };
+            draft::resumed("polarization", || {
+                optional_choice("🔀 Polarization:", &choices)
+            })?
         }
     };
+    let post_pass_action = match antenna.post_pass_action {
+        Some(action) => Some(action),
+        None => draft::resumed("post_pass_action", || {
+            optional_choice(
+                "🅿️ After the pass:",
+                &[
+                    PostPassAction::Park,
+                    PostPassAction::Hold,
+                    PostPassAction::NextJob,
+                ],
+            )
+        })?,
+    };
+    Ok((polarization, post_pass_action))
+}
 
-    match args.command {
-        Commands::AddJob => {
-            let input = match collect_job_info() {
-                Ok(input) => input,
-                Err(e) => {
-                    eprintln!("Error collecting input: {}", e);
-                    std::process::exit(1);
+/// Collect all job information from user; an alias means a tracking job with its presets,
+/// a `fixed` pointing (from `--fixed-az`) a tracking job held in one direction. Without
+/// either, a tracking job's target is asked for first and a satellite is searched for in
+/// `client`'s catalog. A known `start`, as from `--after`, is not asked for. Each answer
+/// goes into the session's draft, and what the draft already holds is not asked again.
+fn collect_job_info(
+    client: &ApiClient,
+    alias: Option<AliasChoice>,
+    fixed: Option<FixedPointing>,
+    antenna: &AntennaChoice,
+    passband: Option<Passband>,
+    start: Option<DateTime<Utc>>,
+) -> Result<JobRequestBuilder, CliError> {
+    let start_input = || match start {
+        Some(start) => Ok(start),
+        None => get_datetime_input("Start", "2025-10-02", "12:00"),
+    };
+    let kind = match (&alias, &fixed) {
+        (Some(_), _) | (_, Some(_)) => {
+            draft::note("job_type", &JobKind::Tracking);
+            JobKind::Tracking
+        }
+        (None, None) => draft::resumed("job_type", || {
+            Ok(Select::new(
+                "🧰 Job type:",
+                vec![JobKind::Tracking, JobKind::Calibration, JobKind::Test],
+            )
+            .prompt()
+            .recorded("🧰 Job type:")?)
+        })?,
+    };
+
+    match kind {
+        JobKind::Tracking => {
+            let fixed = match (fixed, &alias) {
+                (Some(pointing), _) => {
+                    draft::note("job_type", &"pointing");
+                    draft::note("pointing", &pointing);
+                    Some(pointing)
+                }
+                (None, Some(_)) => None,
+                (None, None) => {
+                    // A draft that says which target it is for is not asked again
+                    let drafted = match draft::get::<String>("job_type").as_deref() {
+                        Some("pointing") => Some(TargetKind::Fixed),
+                        _ => draft::get::<TleData>("tle").map(|_| TargetKind::Tle),
+                    };
+                    let target = match drafted {
+                        Some(target) => target,
+                        None => Select::new("🎯 Target:", vec![TargetKind::Tle, TargetKind::Fixed])
+                            .prompt()
+                            .recorded("🎯 Target:")?,
+                    };
+                    match target {
+                        TargetKind::Tle => None,
+                        TargetKind::Fixed => {
+                            draft::note("job_type", &"pointing");
+                            Some(draft::resumed("pointing", get_pointing_input)?)
+                        }
+                    }
+                }
+            };
+            match (&alias, &fixed) {
+                (Some(choice), _) => {
+                    println!("🚀 Creating a new tracking job for {}...\n", choice.name)
+                }
+                (None, Some(pointing)) => println!(
+                    "🚀 Creating a new fixed-pointing job at az {}°, el {}°...\n",
+                    pointing.azimuth, pointing.elevation
+                ),
+                (None, None) => println!("🚀 Creating a new tracking job...\n"),
+            }
+
+            let start_datetime = draft::resumed("start", start_input)?;
+            let end_datetime =
+                draft::resumed("end", || get_datetime_input("End", "2025-10-02", "12:15"))?;
+            let (job_type, preset) = match (fixed, alias) {
+                (Some(pointing), _) => (
+                    JobType::Pointing { pointing },
+                    satellites::Preset::default(),
+                ),
+                (
+                    None,
+                    Some(AliasChoice {
+                        tle: Some(tle),
+                        preset,
+                        ..
+                    }),
+                ) => {
+                    draft::note("tle", &tle);
+                    (JobType::Tracking { tle }, preset)
                 }
+                (None, Some(AliasChoice { preset, .. })) => (
+                    JobType::Tracking {
+                        tle: draft::resumed("tle", get_tle_input)?,
+                    },
+                    preset,
+                ),
+                (None, None) => match draft::get::<TleData>("tle") {
+                    // The catalog's decoder settings came with the elements
+                    Some(tle) => (
+                        JobType::Tracking { tle },
+                        satellites::Preset {
+                            mode: draft::get("mode"),
+                            baud: draft::get("baud"),
+                            ..Default::default()
+                        },
+                    ),
+                    None => {
+                        let (tle, preset) = get_catalog_tle_input(client)?;
+                        draft::note("tle", &tle);
+                        if let Some(mode) = &preset.mode {
+                            draft::note("mode", mode);
+                        }
+                        if let Some(baud) = preset.baud {
+                            draft::note("baud", &baud);
+                        }
+                        (JobType::Tracking { tle }, preset)
+                    }
+                },
+            };
+            let (rx_frequency, tx_frequency) = match &passband {
+                Some(passband) => match (draft::get("rx_frequency"), draft::get("tx_frequency")) {
+                    (Some(rx), Some(tx)) => (rx, tx),
+                    _ => {
+                        let (rx, tx) = get_linked_frequency_input(passband, preset.rx_frequency)?;
+                        draft::note("rx_frequency", &rx);
+                        draft::note("tx_frequency", &tx);
+                        (rx, tx)
+                    }
+                },
+                None => (
+                    draft::resumed("rx_frequency", || {
+                        get_frequency_input(
+                            "RX",
+                            history::Prompt::RxFrequency,
+                            "145800000",
+                            preset.rx_frequency,
+                        )
+                    })?,
+                    draft::resumed("tx_frequency", || {
+                        get_frequency_input(
+                            "TX",
+                            history::Prompt::TxFrequency,
+                            "437500000",
+                            preset.tx_frequency,
+                        )
+                    })?,
+                ),
             };
+            let (polarization, post_pass_action) = get_antenna_input(antenna)?;
+
+            Ok(
+                JobRequestBuilder::new(start_datetime, end_datetime, job_type)
+                    .rx_frequency(rx_frequency)
+                    .tx_frequency(tx_frequency)
+                    .mode(preset.mode)
+                    .baud(preset.baud)
+                    .polarization(polarization)
+                    .post_pass_action(post_pass_action),
+            )
+        }
+        JobKind::Calibration => {
+            println!("🚀 Creating a new calibration job...\n");
+
+            let start_datetime = draft::resumed("start", start_input)?;
+            let end_datetime =
+                draft::resumed("end", || get_datetime_input("End", "2025-10-02", "12:30"))?;
+            let sweep = draft::resumed("sweep", get_sweep_input)?;
+
+            Ok(
+                JobRequestBuilder::new(
+                    start_datetime,
+                    end_datetime,
+                    JobType::Calibration { sweep },
+                )
+                .polarization(antenna.polarization)
+                .post_pass_action(antenna.post_pass_action),
+            )
+        }
+        JobKind::Test => {
+            println!("🚀 Creating a new loopback test...\n");
+
+            let start_datetime = draft::resumed("start", start_input)?;
+            let end_datetime = draft::resumed("end", || {
+                let duration = Text::new("⏱️ Duration (e.g. 90s, 15m, 1h30m):")
+                    .with_placeholder("5m")
+                    .with_validator(accepted_by(parse::duration))
+                    .prompt()
+                    .recorded("⏱️ Duration (e.g. 90s, 15m, 1h30m):")?;
+                Ok(start_datetime
+                    + parse::duration(&duration).context("reading the test duration")?)
+            })?;
+            let rx_frequency = draft::resumed("rx_frequency", || {
+                get_frequency_input("Test", history::Prompt::TestFrequency, "437500000", None)
+            })?;
+
+            Ok(
+                JobRequestBuilder::new(start_datetime, end_datetime, JobType::Test)
+                    .rx_frequency(rx_frequency)
+                    .polarization(antenna.polarization)
+                    .post_pass_action(antenna.post_pass_action),
+            )
+        }
+    }
+}
+
+/// Ways to recover a fully-built job after a transport failure
+enum RecoveryAction {
+    RetryNow,
+    RetryLongerTimeout,
+    SaveToQueue,
+    DumpToFile,
+    Abort,
+}
+
+impl std::fmt::Display for RecoveryAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            RecoveryAction::RetryNow => "🔁 Retry now",
+            RecoveryAction::RetryLongerTimeout => "⏱️ Retry with a longer timeout",
+            RecoveryAction::SaveToQueue => "📥 Save to the offline queue",
+            RecoveryAction::DumpToFile => "💾 Dump the job as JSON to a file",
+            RecoveryAction::Abort => "🛑 Abort",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// Ask the user how to recover from a failed submission
+fn prompt_recovery() -> Result<RecoveryAction, CliError> {
+    let options = vec![
+        RecoveryAction::RetryNow,
+        RecoveryAction::RetryLongerTimeout,
+        RecoveryAction::SaveToQueue,
+        RecoveryAction::DumpToFile,
+        RecoveryAction::Abort,
+    ];
+
+    Ok(Select::new("The job was not delivered. What now?", options)
+        .prompt()
+        .recorded("The job was not delivered. What now?")?)
+}
+
+/// Write jobs as a pretty JSON array, the layout `--batch` reads
+fn dump_jobs(path: &str, jobs: &[JobRequestDTO]) -> Result<(), CliError> {
+    let contents =
+        serde_json::to_string_pretty(jobs).map_err(|e| CliError::parse("job for export", e))?;
+    std::fs::write(path, contents).with_context(|| format!("writing {}", path))
+}
+
+/// Tell the operator the job is in, with what the server warned about it, and record it.
+/// Under `--strict` the warnings are the command's error, though the job is scheduled.
+async fn report_submitted(
+    hooks: &HookRunner,
+    job: &job_request::JobRequest,
+    response: &client::ApiResponse,
+) -> Result<(), CliError> {
+    println!("✅ Job submitted successfully: {}", response.status());
+    println!(
+        "   🕐 {} — {}",
+        humanize::timestamp(job.start),
+        humanize::window(job.start, job.end)
+    );
+    if let Some(message) = &response.message {
+        println!("   {}", message);
+    }
+    let warned = findings::server(None, &response.warnings);
+    if let Some(id) = &response.id {
+        audit::job(id);
+    }
+    campaign::remember(job, response).await;
+    baseline::remember(job).await;
+    chain::remember(response).await;
+    hooks.after_submit(job, response).await;
+    warned
+}
+
+/// Submit job to API, offering recovery options if it never reaches the server
+async fn submit_job(
+    client: &ApiClient,
+    hooks: &HookRunner,
+    job: JobRequestBuilder,
+    limits: &regulatory::TxLimits,
+    checks: &findings::Checks,
+) -> Result<(), CliError> {
+    let job = job.tx_limits(limits);
+    let entered = job.times();
+    let job = job.build()?;
+    let snapped = snap::describe(entered, (job.start, job.end));
+    let window = submit_window::check(job.start);
+    if let Some(problem) = &window {
+        if !tty::interactive() && !findings::strict() {
+            return Err(problem.clone().into());
+        }
+    }
+    let queue = capabilities::review(client, &[&job]).await?;
+    let mut found = findings::gather(client, &[(None, &job)], checks).await;
+    if let Some(problem) = window {
+        found.add(Check::SubmitWindow, None, problem.reason);
+    }
+    found.settle()?;
+    let questions = [
+        (Check::SubmitWindow, "Schedule the job anyway?", false, None),
+        (
+            Check::BandHint,
+            "Keep these frequencies?",
+            false,
+            Some("--no-band-hints skips this check"),
+        ),
+        // Scripts go ahead: the job may still make it, and the server has the last word
+        (Check::Latency, "Submit anyway?", true, None),
+    ];
+    for (check, question, default, help) in questions {
+        if !found.has(check) || !tty::interactive() {
+            continue;
+        }
+        let mut prompt = Confirm::new(question).with_default(default);
+        if let Some(help) = help {
+            prompt = prompt.with_help_message(help);
+        }
+        if !prompt.prompt().recorded(question)? {
+            println!("🛑 Job not submitted");
+            return Ok(());
+        }
+    }
+    quiet_hours::review(&[&job])?;
+    rotator::review(&[&job]).await?;
+    // The slot is kept while the operator reads the summary, on servers that can
+    let held = match tty::interactive() {
+        true => hold::reserve(client, &job).await?,
+        false => None,
+    };
+    if let Some(held) = &held {
+        println!(
+            "🔒 Slot held until {} (hold {})",
+            held.expires_at.format("%H:%M:%S UTC"),
+            held.id
+        );
+    }
+    if let Some(snapped) = snapped {
+        println!("📐 {}", snapped);
+    }
+    if let Some(queue) = &queue {
+        println!("📋 Station {}", queue);
+    }
+    match &job.job_type {
+        JobType::Tracking { tle } => {
+            if let Some(identity) = tle::identity(&tle.tle1) {
+                println!("🛰️ {} ({})", tle.tle0, identity);
+            }
+        }
+        JobType::Pointing { pointing } => println!(
+            "🎯 Fixed pointing at az {}°, el {}°: no pass prediction or Doppler correction",
+            pointing.azimuth, pointing.elevation
+        ),
+        _ => {}
+    }
+    if let Some(rx) = job.describe_rx_offset() {
+        println!("📻 {}", rx);
+    }
+    if let Some(transmit) = regulatory::describe_transmit(&job) {
+        println!("📶 {}", transmit);
+    }
+    let antenna: Vec<String> = [
+        job.polarization.map(|p| p.to_string()),
+        job.post_pass_action.map(|a| format!("then {}", a)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if !antenna.is_empty() {
+        println!("🔀 {}", antenna.join(", "));
+    }
+    if checks.anomaly_hints {
+        for hint in baseline::hints(&job) {
+            println!("🧭 {}", hint);
+        }
+    }
+
+    let clear = maintenance::confirm_window(client, job.window()).await;
+    if !matches!(clear, Ok(true)) {
+        if let Some(held) = &held {
+            hold::release(client, held).await;
+        }
+        clear?;
+        println!("🛑 Job not submitted");
+        return Ok(());
+    }
+
+    if let Some(held) = held {
+        match hold::review(client, &job, held).await? {
+            hold::Outcome::Scheduled(response) => report_submitted(hooks, &job, &response).await?,
+            hold::Outcome::NotScheduled => println!("🛑 Job not submitted"),
+        }
+        return Ok(());
+    }
+
+    println!("\n📡 Submitting job to ground station...");
+
+    let mut client = client.clone();
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        if attempt > 1 {
+            client.observe(|o| o.on_retry(attempt, &format!("submitting {}", job.label())));
+        }
+        let error = match client
+            .add_job(&job)
+            .await
+            .with_context(|| format!("while scheduling {}", job.label()))
+        {
+            Ok(response) => return report_submitted(hooks, &job, &response).await,
+            // Rejections from the server are final; only lost requests are recoverable
+            Err(e) if !e.is_transport() => return Err(e),
+            Err(e) => e,
+        };
+
+        error::report("Submission failed", &error);
+
+        match prompt_recovery()? {
+            RecoveryAction::RetryNow => {}
+            RecoveryAction::RetryLongerTimeout => {
+                let timeout = client.timeout() * 2;
+                println!("⏱️ Retrying with a {}s timeout", timeout.as_secs());
+                client = client.with_timeout(timeout);
+            }
+            RecoveryAction::SaveToQueue => {
+                let path = queue::enqueue(&job).await?;
+                println!("📥 Job saved to the offline queue: {}", path.display());
+                println!("   Submit it later with `rustar-cli queue flush`");
+                return Ok(());
+            }
+            RecoveryAction::DumpToFile => {
+                let path = Text::new("💾 Output file:")
+                    .with_default("job.json")
+                    .prompt()
+                    .recorded("💾 Output file:")?;
+                dump_jobs(path.trim(), std::slice::from_ref(&job))?;
+                println!("💾 Job written to {}", path.trim());
+                return Ok(());
+            }
+            RecoveryAction::Abort => return Err(error),
+        }
+    }
+}
+
+/// Look up a jobs add alias and fetch its element set when it names a NORAD ID or designator
+async fn alias_choice(alias: &str) -> Result<AliasChoice, CliError> {
+    let book = satellites::AliasBook::load()?;
+    let (name, preset) = satellites::lookup(&book, alias)?;
+    let tle = match preset.id() {
+        Some(id) => Some(TleData {
+            tle0: name.clone(),
+            ..tle::resolve_id(id).await?
+        }),
+        None => None,
+    };
+    println!("📻 Using the '{}' preset: {}", name, preset.describe());
+    Ok(AliasChoice { name, preset, tle })
+}
+
+/// The receive offset of a new job: `--rx-offset`, else the one the alias preset carries,
+/// saying which was applied
+fn rx_offset(flag: Option<i64>, alias: Option<&AliasChoice>) -> Option<i64> {
+    let preset = alias.and_then(|alias| Some((&alias.name, alias.preset.rx_offset_hz?)));
+    match (flag, preset) {
+        (Some(flag), Some((name, offset))) if flag != offset => println!(
+            "📻 --rx-offset {} replaces the '{}' preset's {}",
+            frequency::describe_offset(flag),
+            name,
+            frequency::describe_offset(offset)
+        ),
+        (None, Some((name, offset))) => println!(
+            "📻 Applying the '{}' preset's receive offset of {}",
+            name,
+            frequency::describe_offset(offset)
+        ),
+        _ => {}
+    }
+    flag.or(preset.map(|(_, offset)| offset))
+        .filter(|offset| *offset != 0)
+}
 
-            if let Err(e) = submit_job(&client, input).await {
-                eprintln!("Failed to submit job: {}", e);
-                std::process::exit(1);
+/// Store the `sat set-freq` values, leaving the ones not given untouched
+#[allow(clippy::too_many_arguments)]
+fn set_frequency_preset(
+    alias: &str,
+    norad_id: Option<u32>,
+    intl_designator: Option<String>,
+    rx: Option<String>,
+    rx_offset: Option<i64>,
+    tx: Option<String>,
+    mode: Option<String>,
+    baud: Option<u32>,
+    passband: Option<Passband>,
+) -> Result<(), CliError> {
+    if norad_id.is_none()
+        && intl_designator.is_none()
+        && rx.is_none()
+        && rx_offset.is_none()
+        && tx.is_none()
+        && mode.is_none()
+        && baud.is_none()
+        && passband.is_none()
+    {
+        return Err(CliError::validation(
+            "preset",
+            "give at least one of --norad-id, --intl-designator, --rx, --rx-offset, --tx, --mode, --baud or --downlink-band",
+        ));
+    }
+    if let Some(problem) = rx_offset.and_then(regulatory::check_offset) {
+        return Err(problem.into());
+    }
+    let _lock = satellites::AliasBook::lock()?;
+    let mut book = satellites::AliasBook::load()?;
+    let (name, preset) = book.entry(alias)?;
+    if norad_id.is_some() {
+        preset.norad_id = norad_id;
+    }
+    if intl_designator.is_some() {
+        preset.intl_designator = intl_designator;
+    }
+    if let Some(rx) = rx {
+        preset.rx_frequency = Some(Frequency::parse(&rx).context("reading --rx")?);
+    }
+    if let Some(offset) = rx_offset {
+        preset.rx_offset_hz = Some(offset).filter(|hz| *hz != 0);
+    }
+    if let Some(tx) = tx {
+        preset.tx_frequency = Some(Frequency::parse(&tx).context("reading --tx")?);
+    }
+    if mode.is_some() {
+        preset.mode = mode;
+    }
+    if baud.is_some() {
+        preset.baud = baud;
+    }
+    if passband.is_some() {
+        preset.transponder = passband;
+    }
+    let summary = preset.describe();
+    let path = book.save()?;
+    println!("📒 {}: {} (saved in {})", name, summary, path.display());
+    Ok(())
+}
+
+/// Window given on the command line for imports without their own schedule
+fn import_window(
+    start: Option<&str>,
+    end: Option<&str>,
+    duration: Option<&str>,
+) -> Result<Option<import::Window>, CliError> {
+    let Some(start) = start else {
+        return Ok(None);
+    };
+    let start = parse::datetime(start).context("reading --start")?;
+    let end = match (end, duration) {
+        (Some(end), _) => parse::datetime(end).context("reading --end")?,
+        (None, Some(duration)) => {
+            start + parse::duration(duration).context("reading --duration")?
+        }
+        (None, None) => {
+            return Err(CliError::validation(
+                "end",
+                "--start needs --end or --duration",
+            ))
+        }
+    };
+    Ok(Some(import::Window { start, end }))
+}
+
+/// Convert external planning files into jobs and hand them to the batch pipeline,
+/// or for a dry run write them to the given target. Returns the number of entries that failed.
+#[allow(clippy::too_many_arguments)]
+async fn import_jobs(
+    client: &ApiClient,
+    hooks: &HookRunner,
+    files: &[PathBuf],
+    window: Option<import::Window>,
+    transponder: Option<&str>,
+    dry_run: Option<output::OutputTarget>,
+    report: Option<output::OutputTarget>,
+    skip_invalid: bool,
+    limits: &regulatory::TxLimits,
+    checks: &findings::Checks,
+) -> Result<usize, CliError> {
+    if let Some(target) = &dry_run {
+        target.check()?;
+    }
+    let imported = import::import_files(files, window, transponder).await?;
+    import::report_unmapped(&imported);
+
+    let values = imported
+        .iter()
+        .map(|item| serde_json::to_value(&item.job))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| CliError::parse("imported jobs", e))?;
+
+    if let Some(target) = dry_run {
+        let entries = batch::preflight(values.clone(), limits);
+        batch::print_problems(&entries);
+        let mut document = serde_json::to_string_pretty(&values)
+            .map_err(|e| CliError::parse("imported jobs", e))?;
+        document.push('\n');
+        target.write(document)?;
+        eprintln!("🧪 Dry run: {} job(s) not submitted", values.len());
+        return Ok(0);
+    }
+
+    let label = files
+        .first()
+        .cloned()
+        .unwrap_or_else(|| PathBuf::from("import"));
+    batch::submit_entries(
+        client,
+        hooks,
+        &label,
+        values,
+        report,
+        skip_invalid,
+        limits,
+        checks,
+    )
+    .await
+}
+
+/// Show the jobs waiting in the offline queue
+fn list_queue() -> Result<(), CliError> {
+    let jobs = queue::load()?;
+    if jobs.is_empty() {
+        println!("📭 The offline queue is empty");
+        return Ok(());
+    }
+
+    println!(
+        "📥 {} job(s) in {}",
+        jobs.len(),
+        queue::queue_path().display()
+    );
+    for (index, job) in jobs.iter().enumerate() {
+        println!("  {}. {}", index + 1, queue::describe(job));
+    }
+    Ok(())
+}
+
+/// Submit every queued job, keeping the ones that still fail
+async fn flush_queue(client: &ApiClient, hooks: &HookRunner) -> Result<(), CliError> {
+    let jobs = queue::load()?;
+    if jobs.is_empty() {
+        println!("📭 The offline queue is empty");
+        return Ok(());
+    }
+
+    // An unreachable station would otherwise time out once per queued job
+    if let Err(e) = client.ping().await {
+        if e.is_transport() {
+            return Err(e).context("checking that the station is reachable");
+        }
+    }
+
+    let taken = jobs.len();
+    let mut remaining = Vec::new();
+    for job in jobs {
+        // Queued by an older rustar-cli, or edited by hand: checked again before it goes
+        let request = match JobRequestBuilder::from(job.clone()).build() {
+            Ok(request) => request,
+            Err(e) => {
+                error::report(&format!("Not submitting {}", job.label()), &e);
+                remaining.push(job);
+                continue;
+            }
+        };
+        match client.add_job(&request).await {
+            Ok(response) => {
+                println!("✅ {}: {}", job.label(), response.status());
+                if let Some(id) = &response.id {
+                    audit::job(id);
+                }
+                campaign::remember(&job, &response).await;
+                baseline::remember(&job).await;
+                hooks.after_submit(&job, &response).await;
+            }
+            Err(e) => {
+                error::report(&format!("Failed to submit {}", job.label()), &e);
+                remaining.push(job);
+            }
+        }
+    }
+
+    let left = queue::settle(taken, remaining).await?;
+    if left == 0 {
+        println!("📭 Offline queue flushed");
+    } else {
+        println!("📥 {} job(s) left in the queue", left);
+    }
+    Ok(())
+}
+
+/// What commands share: the resolved settings, the hooks and an API client built on first use
+struct Context {
+    resolved: config::Resolved,
+    hooks: HookRunner,
+    verbose: bool,
+    /// `--no-weather` was not given
+    weather: bool,
+    /// `--no-band-hints` was not given
+    band_hints: bool,
+    /// `--no-anomaly-hints` was not given
+    anomaly_hints: bool,
+    client: OnceCell<ApiClient>,
+}
+
+impl Context {
+    /// Advisory checks for submissions: the forecast when the profile configures it and
+    /// knows where the station is, and the band hints
+    fn checks(&self) -> findings::Checks {
+        let lookout = self
+            .resolved
+            .profile
+            .weather
+            .clone()
+            .filter(|_| self.weather)
+            .zip(self.resolved.location().ok())
+            .map(|(settings, station)| weather::Lookout::new(settings, station));
+        findings::Checks {
+            lookout,
+            band_hints: self.band_hints,
+            anomaly_hints: self.anomaly_hints,
+            visibility: None,
+        }
+    }
+
+    /// Only commands that talk to the API build the client, and with it read its settings
+    fn client(&self) -> Result<&ApiClient, CliError> {
+        if let Some(client) = self.client.get() {
+            return Ok(client);
+        }
+        let client = api_client(&self.resolved, &self.resolved.base_url.value, self.verbose)?;
+        Ok(self.client.get_or_init(|| client))
+    }
+}
+
+/// A client for `base_url` with the connection, cache and timeouts `resolved` settled on
+fn api_client(
+    resolved: &config::Resolved,
+    base_url: &str,
+    verbose: bool,
+) -> Result<ApiClient, CliError> {
+    client_builder(resolved, base_url, verbose)?
+        .observer(std::sync::Arc::new(observer(verbose)))
+        .build()
+}
+
+/// The settings of `api_client`, for a client that reports to another observer
+fn client_builder(
+    resolved: &config::Resolved,
+    base_url: &str,
+    verbose: bool,
+) -> Result<client::ApiClientBuilder, CliError> {
+    let cache = http_cache::HttpCache::new(
+        resolved
+            .profile
+            .http_cache_ttl_seconds
+            .map(std::time::Duration::from_secs),
+        verbose,
+    );
+    Ok(ApiClient::builder(base_url, resolved.timeouts()?)
+        .connection(resolved.profile.connection)
+        .cache(cache)
+        .verbose(verbose)
+        .timing(timing::enabled()))
+}
+
+/// What every client reports to: the terminal, through the metrics counters
+fn observer(verbose: bool) -> metrics::Counting<progress::Terminal> {
+    metrics::Counting::new(progress::Terminal::new(verbose))
+}
+
+/// End the process, writing the audit entry of a mutating command and the timing totals
+/// first. Only `main` ends the process; commands return the status they end with.
+fn exit(code: i32) -> ! {
+    timing::finish();
+    phases::finish();
+    audit::finish(code);
+    transcript::finish(code);
+    std::process::exit(code)
+}
+
+/// The shared API client, or the status to end with once the reason it could not be
+/// built is reported
+fn connect(ctx: &Context) -> Result<&ApiClient, i32> {
+    ctx.client().map_err(|e| {
+        error::report("Failed to initialize API client", &e);
+        error::EXIT_FAILURE
+    })
+}
+
+/// Point the command at the demo station, where the sample station's position is the
+/// default, and say on stderr that the output is a demonstration
+async fn start_demo(args: &mut Args) -> Result<(), i32> {
+    match demo::start(args.global.demo_seed).await {
+        Ok(station) => {
+            args.global.base_url = Some(station.base_url);
+            args.global
+                .overrides
+                .push(("tle_source".to_string(), station.tle_source));
+        }
+        Err(e) => {
+            error::report("Failed to start the demo station", &e);
+            return Err(e.exit_code());
+        }
+    }
+    args.global.coords.get_or_insert(demo::STATION);
+    eprintln!(
+        "🎭 DEMO — sample data (seed {}) from a built-in station; no real server is contacted",
+        args.global.demo_seed
+    );
+    if let Some(command) = args.command.changes() {
+        eprintln!(
+            "🎭 DEMO — {} only pretends: the change is gone when the command exits",
+            command
+        );
+    }
+    Ok(())
+}
+
+/// How `jobs add` treats a job the server accepts to create later, from its flags
+fn creation(no_wait: bool, wait_timeout: chrono::Duration) -> client::Creation {
+    match no_wait {
+        true => client::Creation::NoWait,
+        false => client::Creation::Wait(wait_timeout.to_std().unwrap_or_default()),
+    }
+}
+
+/// Stop a changing command on a read-only terminal, before it prompts, sets anything up
+/// or opens a connection. The profile is the one the full configuration load selects.
+fn refuse_read_only(args: &Args, command: &str) -> Result<(), CliError> {
+    let reason = if args.read_only {
+        "--read-only".to_string()
+    } else {
+        match config::read_only_profile(
+            args.global.profile.as_deref(),
+            args.global.env_file.as_deref(),
+            &args.global.overrides,
+        ) {
+            Some(profile) => format!("profile {} sets read_only", profile),
+            None => return Ok(()),
+        }
+    };
+    Err(CliError::ReadOnly {
+        command: command.to_string(),
+        reason,
+    })
+}
+
+/// Stop a changing command on a read-only terminal before it runs, with the status to end with
+fn check_read_only(args: &Args) -> Result<(), i32> {
+    if let Some(command) = args.command.changes() {
+        refuse_read_only(args, command).map_err(|e| {
+            error::report("Not allowed", &e);
+            e.exit_code()
+        })?;
+    }
+    Ok(())
+}
+
+/// Stop a command before it runs when it cannot write the output format asked for, with
+/// the status to end with
+fn check_output(args: &Args) -> Result<(), i32> {
+    if !args.command.supports(args.output) {
+        let message = match args.output {
+            OutputFormat::Ndjson => {
+                "--output ndjson is only available for jobs list and mqtt follow"
+            }
+            _ => "--output csv is only available for jobs list and stats",
+        };
+        error::report(
+            "Unsupported output format",
+            &CliError::Unsupported(message.to_string()),
+        );
+        return Err(error::EXIT_FAILURE);
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() {
+    alias::set_command(Args::command);
+    let argv = match alias::expand(std::env::args_os().collect()) {
+        Ok(argv) => argv,
+        Err(e) => {
+            error::report("Failed to load configuration", &e);
+            exit(error::EXIT_FAILURE);
+        }
+    };
+    let mut args = Args::parse_from(argv);
+    args.command = args.command.canonical();
+    match clock::Clock::from_env() {
+        Ok(now) => clock::set(now),
+        Err(e) => {
+            error::report("Invalid clock", &e);
+            exit(error::EXIT_USAGE);
+        }
+    }
+    reproducible::set(args.global.reproducible);
+    if args.global.demo {
+        if let Err(status) = start_demo(&mut args).await {
+            exit(status);
+        }
+    }
+    if let Some(path) = &args.global.transcript {
+        transcript::start(path);
+    }
+    let _transcript = transcript::Guard;
+    if let Err(status) = check_read_only(&args) {
+        exit(status);
+    }
+    humanize::set_utc_only(args.global.utc_only || args.global.reproducible);
+    table::set_wide(args.global.wide);
+    validation::set_explain(args.global.explain);
+    if args.global.timing {
+        timing::enable();
+    }
+    phases::set(args.global.profile_timing, args.global.verbose, args.output);
+    if let Err(status) = check_output(&args) {
+        exit(status);
+    }
+
+    if let Commands::GenerateDocs { format, out_dir } = &args.command {
+        if let Err(e) = docs::generate(Args::command(), *format, out_dir) {
+            error::report("Failed to generate documentation", &e);
+            exit(error::EXIT_FAILURE);
+        }
+        return;
+    }
+
+    if let Commands::Examples { topic } = &args.command {
+        if let Err(e) = examples::show(*topic, args.output) {
+            error::report("Failed to show examples", &e);
+            exit(error::EXIT_FAILURE);
+        }
+        return;
+    }
+
+    // The mock server is the API; it needs no client or profile of its own
+    if let Commands::MockServer { port, state_file } = &args.command {
+        if let Err(e) = mock_server::run(*port, state_file.clone()).await {
+            error::report("Mock server failed", &e);
+            exit(error::EXIT_FAILURE);
+        }
+        return;
+    }
+
+    if let Err(e) = paths::migrate_legacy() {
+        error::report("Failed to migrate files from ~/.rustar", &e);
+    }
+
+    // Init writes the profile the configuration would otherwise be loaded from
+    if let Commands::Init {
+        non_interactive,
+        timezone,
+    } = &args.command
+    {
+        let given = init::Answers {
+            base_url: args.global.base_url.clone(),
+            location: args.global.coords,
+            timezone: timezone.clone(),
+        };
+        if let Err(e) = init::run(args.global.profile.as_deref(), given, *non_interactive).await {
+            error::report("Setup failed", &e);
+            exit(e.exit_code());
+        }
+        return;
+    }
+    let nothing_configured = !config::config_path().exists()
+        && !config::default_env_file().exists()
+        && args.global.base_url.is_none()
+        && std::env::var_os(client::BASE_URL_ENV).is_none();
+    if nothing_configured && args.command.wants_setup() && tty::interactive() {
+        if let Err(e) = init::first_run(args.global.profile.as_deref()).await {
+            error::report("Setup failed", &e);
+            exit(e.exit_code());
+        }
+    }
+
+    let config_span = phases::span(phases::Phase::Config, || {
+        "resolving the profile".to_string()
+    });
+    let resolved = match config::Resolved::load(
+        args.global.profile.as_deref(),
+        args.global.base_url.as_deref(),
+        args.global.timeout.as_deref(),
+        args.global.coords,
+        args.global.env_file.as_deref(),
+        &args.global.overrides,
+        args.global.verbose,
+    ) {
+        Ok(resolved) => resolved,
+        Err(e) => {
+            error::report("Failed to load configuration", &e);
+            exit(error::EXIT_FAILURE);
+        }
+    };
+    drop(config_span);
+    if !args.global.no_config_warnings {
+        for conflict in resolved.conflicts() {
+            eprintln!("⚠️ {}", conflict);
+            transcript::warning(&conflict);
+        }
+    }
+    let hooks = hooks::HookRunner::new(
+        resolved.profile.hooks.clone(),
+        &resolved.profile_name.value,
+        !args.global.no_hooks,
+    );
+    let ctx = Context {
+        resolved,
+        hooks,
+        verbose: args.global.verbose,
+        weather: !args.global.no_weather,
+        band_hints: !args.global.no_band_hints,
+        anomaly_hints: !args.global.no_anomaly_hints,
+        client: OnceCell::new(),
+    };
+    history::use_profile(&ctx.resolved.profile_name.value);
+    if let Some(dir) = &ctx.resolved.profile.transcript_dir {
+        transcript::start(&dir.join(transcript::file_name()));
+    }
+    transcript::use_profile(&ctx.resolved.profile_name.value);
+    validation::use_profile(&ctx.resolved.profile_name.value);
+    submit_window::set(submit_window::SubmitWindow::new(
+        ctx.resolved.profile.max_horizon_days,
+        ctx.resolved.profile.max_past_minutes,
+        args.global.max_horizon,
+    ));
+    latency::set_margin(ctx.resolved.profile.latency_margin);
+    tle::use_source(&ctx.resolved.tle_source.value);
+    polite::set(
+        ctx.resolved.profile.tle_requests_per_minute,
+        args.global.tle_offline,
+        args.global.verbose,
+    );
+    findings::set_strict(args.global.strict || ctx.resolved.profile.strict);
+    snap::set(&ctx.resolved.profile.snap, args.global.no_snap);
+    snapshot::set(
+        ctx.resolved.profile.job_snapshots.unwrap_or(true),
+        snapshot::Settings {
+            base_url: ctx.resolved.base_url.value.clone(),
+            timezone: ctx.resolved.profile.timezone.clone(),
+            rounding: snap::policy(),
+        },
+    );
+    receipt::set(
+        ctx.resolved.profile.job_receipts.unwrap_or(true),
+        receipt::Settings {
+            base_url: ctx.resolved.base_url.value.clone(),
+            profile: ctx.resolved.profile_name.value.clone(),
+            signing: ctx.resolved.profile.receipt_signing.clone(),
+        },
+    );
+    regulatory::set_max_rx_offset(ctx.resolved.profile.max_rx_offset_hz);
+    humanize::set_frequency_unit(
+        args.global
+            .freq_unit
+            .or(ctx.resolved.profile.frequency_display)
+            .unwrap_or_default(),
+    );
+    rotator::set(ctx.resolved.profile.rotator_check.as_ref());
+    match quiet_hours::QuietHours::new(&ctx.resolved, args.global.override_quiet_hours) {
+        Ok(Some(quiet)) => quiet_hours::set(quiet),
+        Ok(None) => {}
+        Err(e) => {
+            error::report("Failed to load configuration", &e);
+            exit(e.exit_code());
+        }
+    }
+    if let Commands::Shell = args.command {
+        transcript::stops_on_interrupt();
+        let status = shell(&args, &ctx).await;
+        exit(status);
+    }
+    if let Err(status) = run(args, &ctx, std::env::args().skip(1).collect()).await {
+        exit(status);
+    }
+    timing::finish();
+    phases::finish();
+    audit::finish(error::EXIT_SUCCESS);
+}
+
+/// One command against the session's configuration and client: the one `main` was
+/// given, or each line typed into the shell. `arguments` are for the audit log. A
+/// command that fails reports why and returns the exit status to end with.
+async fn run(args: Args, ctx: &Context, arguments: Vec<String>) -> Result<(), i32> {
+    findings::use_output(args.output);
+    if let Some((command, instead)) = args.command.prompts() {
+        if let Err(e) = tty::require(command, instead) {
+            error::report("Cannot prompt", &e);
+            return Err(e.exit_code());
+        }
+    }
+    if let Some(name) = args.command.audit_name() {
+        audit::begin(name, &ctx.resolved.profile_name.value, arguments);
+        if ctx.resolved.profile.protected {
+            if let Err(e) = protect::check(
+                name,
+                &ctx.resolved.profile_name.value,
+                &ctx.resolved.base_url.value,
+                args.confirm_protected.as_deref(),
+            ) {
+                error::report("Not confirmed", &e);
+                return Err(e.exit_code());
+            }
+        }
+    }
+
+    match args.command {
+        Commands::Jobs {
+            action:
+                JobsAction::Add(AddJobArgs {
+                    batch: Some(path),
+                    format,
+                    report,
+                    force,
+                    skip_invalid,
+                    campaign,
+                    no_wait,
+                    wait_timeout,
+                    ..
+                }),
+        } => {
+            let format = format.unwrap_or_else(|| batch::BatchFormat::detect(&path));
+            let report = report.map(|path| output::OutputTarget::new(Some(path), force));
+            let client = connect(ctx)?.with_creation(creation(no_wait, wait_timeout));
+            let limits = &ctx.resolved.profile.tx_limits;
+            match batch::run(
+                &client,
+                &ctx.hooks,
+                &path,
+                format,
+                report,
+                skip_invalid,
+                limits,
+                campaign.as_deref(),
+                &ctx.checks(),
+            )
+            .await
+            {
+                Ok(0) => {}
+                Ok(_) => return Err(error::EXIT_FAILURE),
+                Err(e) => {
+                    error::report("Batch submission failed", &e);
+                    return Err(e.exit_code());
+                }
+            }
+        }
+        Commands::Jobs {
+            action:
+                JobsAction::Add(AddJobArgs {
+                    batch: None,
+                    edit: true,
+                    template,
+                    tx_power,
+                    duty_cycle,
+                    polarization,
+                    post_pass_action,
+                    campaign,
+                    no_wait,
+                    wait_timeout,
+                    ..
+                }),
+        } => {
+            let mut base = match &template {
+                Some(path) => match editor::read_template(path) {
+                    Ok(base) => base,
+                    Err(e) => {
+                        error::report("Error collecting input", &e);
+                        return Err(e.exit_code());
+                    }
+                },
+                None => editor::default_job(),
+            };
+            // Flags given with --edit fill in the document, where they can still be changed
+            if let Some(fields) = base.as_object_mut() {
+                let given = [
+                    ("tx_power_dbm", tx_power.map(serde_json::Value::from)),
+                    ("tx_duty_cycle", duty_cycle.map(serde_json::Value::from)),
+                    ("polarization", polarization.map(|p| serde_json::json!(p))),
+                    (
+                        "post_pass_action",
+                        post_pass_action.map(|a| serde_json::json!(a)),
+                    ),
+                    ("campaign", campaign.map(serde_json::Value::from)),
+                ];
+                for (field, value) in given {
+                    if let Some(value) = value {
+                        fields.insert(field.to_string(), value);
+                    }
+                }
+            }
+            let limits = &ctx.resolved.profile.tx_limits;
+            match editor::job(&base, limits) {
+                Ok(Some(input)) => {
+                    let client = connect(ctx)?.with_creation(creation(no_wait, wait_timeout));
+                    if let Err(e) =
+                        submit_job(&client, &ctx.hooks, input, limits, &ctx.checks()).await
+                    {
+                        error::report("Failed to submit job", &e);
+                        return Err(e.exit_code());
+                    }
+                }
+                // Given up in the editor, which said why
+                Ok(None) => {}
+                Err(e) => {
+                    error::report("Error collecting input", &e);
+                    return Err(e.exit_code());
+                }
+            }
+        }
+        Commands::Jobs {
+            action:
+                JobsAction::Add(AddJobArgs {
+                    batch: None,
+                    profiles: Some(profiles),
+                    satellite,
+                    rx_offset: offset,
+                    tx_power,
+                    duty_cycle,
+                    polarization,
+                    post_pass_action,
+                    downlink_band,
+                    uplink_band,
+                    inverting,
+                    campaign,
+                    fixed_az,
+                    fixed_el,
+                    fixed_name,
+                    ..
+                }),
+        } => {
+            // A position given for the selected profile is not every station's
+            let overrides: Vec<(String, String)> = args
+                .global
+                .overrides
+                .iter()
+                .filter(|(key, _)| key != "location")
+                .cloned()
+                .collect();
+            let load = |name: &str| {
+                config::Resolved::load(
+                    Some(name),
+                    None,
+                    args.global.timeout.as_deref(),
+                    None,
+                    args.global.env_file.as_deref(),
+                    &overrides,
+                    false,
+                )
+            };
+            let mut stations = match fanout::open(
+                &profiles,
+                load,
+                args.global.verbose,
+                !args.global.no_hooks,
+                args.confirm_protected.as_deref(),
+            ) {
+                Ok(stations) => stations,
+                Err(e) => {
+                    error::report("Failed to load configuration", &e);
+                    return Err(e.exit_code());
+                }
+            };
+            fanout::survey(&mut stations).await;
+            let alias = match satellite {
+                Some(alias) => match alias_choice(&alias).await {
+                    Ok(choice) => Some(choice),
+                    Err(e) => {
+                        error::report("Error collecting input", &e);
+                        return Err(e.exit_code());
+                    }
+                },
+                None => None,
+            };
+            let antenna = AntennaChoice {
+                polarization,
+                post_pass_action,
+                supported: fanout::polarizations(&stations),
+            };
+            if let (Some(polarization), Some(supported)) = (polarization, &antenna.supported) {
+                if let Some(problem) = station::check_polarization(polarization, supported) {
+                    error::report("Error collecting input", &problem.into());
+                    return Err(error::EXIT_FAILURE);
+                }
+            }
+            let passband = downlink_band
+                .zip(uplink_band)
+                .map(|(downlink, uplink)| Passband::new(downlink, uplink, inverting))
+                .or_else(|| alias.as_ref().and_then(|a| a.preset.transponder));
+            let catalog = stations[0].client();
+            let offset = rx_offset(offset, alias.as_ref());
+            let fixed = fixed_pointing(fixed_az, fixed_el, fixed_name);
+            let offer = alias.is_none() && fixed.is_none();
+            if let Err(e) = draft::begin(offer, ctx.resolved.profile.draft_max_age_hours) {
+                error::report("Error collecting input", &e);
+                return Err(e.exit_code());
+            }
+            let input = match collect_job_info(catalog, alias, fixed, &antenna, passband, None) {
+                Ok(job) => job
+                    .rx_offset(offset)
+                    .tx_power_dbm(tx_power)
+                    .tx_duty_cycle(duty_cycle)
+                    .campaign(campaign),
+                Err(e) => {
+                    draft::interrupted(&e);
+                    error::report("Error collecting input", &e);
+                    return Err(e.exit_code());
+                }
+            };
+            draft::complete(&input.clone().into_draft());
+            let submitted = fanout::submit(&stations, input).await;
+            draft::discard();
+            match submitted {
+                Ok(0) => {}
+                Ok(_) => return Err(error::EXIT_FAILURE),
+                Err(e) => {
+                    error::report("Failed to submit job", &e);
+                    return Err(e.exit_code());
+                }
+            }
+        }
+        Commands::Jobs {
+            action:
+                JobsAction::Add(AddJobArgs {
+                    batch: None,
+                    satellite,
+                    rx_offset: offset,
+                    tx_power,
+                    duty_cycle,
+                    polarization,
+                    post_pass_action,
+                    downlink_band,
+                    uplink_band,
+                    inverting,
+                    campaign,
+                    after,
+                    gap,
+                    fixed_az,
+                    fixed_el,
+                    fixed_name,
+                    no_wait,
+                    wait_timeout,
+                    ..
+                }),
+        } => {
+            let start = match &after {
+                Some(after) => match chain::anchor(connect(ctx)?, after, gap).await {
+                    Ok(anchor) => Some(anchor.start),
+                    Err(e) => {
+                        error::report("Error collecting input", &e);
+                        return Err(e.exit_code());
+                    }
+                },
+                None => None,
+            };
+            let alias = match satellite {
+                Some(alias) => match alias_choice(&alias).await {
+                    Ok(choice) => Some(choice),
+                    Err(e) => {
+                        error::report("Error collecting input", &e);
+                        return Err(e.exit_code());
+                    }
+                },
+                None => None,
+            };
+            let antenna = AntennaChoice {
+                polarization,
+                post_pass_action,
+                supported: station::polarizations(connect(ctx)?).await,
+            };
+            if let (Some(polarization), Some(supported)) = (polarization, &antenna.supported) {
+                if let Some(problem) = station::check_polarization(polarization, supported) {
+                    error::report("Error collecting input", &problem.into());
+                    return Err(error::EXIT_FAILURE);
+                }
+            }
+            let passband = downlink_band
+                .zip(uplink_band)
+                .map(|(downlink, uplink)| Passband::new(downlink, uplink, inverting))
+                .or_else(|| alias.as_ref().and_then(|a| a.preset.transponder));
+            let offset = rx_offset(offset, alias.as_ref());
+            let fixed = fixed_pointing(fixed_az, fixed_el, fixed_name);
+            // Flags that shape the job start a draft of their own rather than resume one
+            let offer = alias.is_none() && fixed.is_none() && after.is_none();
+            if let Err(e) = draft::begin(offer, ctx.resolved.profile.draft_max_age_hours) {
+                error::report("Error collecting input", &e);
+                return Err(e.exit_code());
+            }
+            let input =
+                match collect_job_info(connect(ctx)?, alias, fixed, &antenna, passband, start) {
+                    Ok(job) => job
+                        .rx_offset(offset)
+                        .tx_power_dbm(tx_power)
+                        .tx_duty_cycle(duty_cycle)
+                        .campaign(campaign),
+                    Err(e) => {
+                        draft::interrupted(&e);
+                        error::report("Error collecting input", &e);
+                        return Err(e.exit_code());
+                    }
+                };
+            draft::complete(&input.clone().into_draft());
+            // A chained start was not picked for the pass, so check it still covers one
+            if let (Some(_), Ok(station)) = (&after, ctx.resolved.location()) {
+                let horizon = predict::Horizon {
+                    station: &station,
+                    min_elevation_deg: 0.0,
+                    mask: &ctx.resolved.profile.elevation_mask,
+                };
+                if let Err(e) = chain::check_pass(&input.clone().into_draft(), &horizon) {
+                    draft::interrupted(&e);
+                    error::report("Error collecting input", &e);
+                    return Err(e.exit_code());
+                }
+            }
+
+            let limits = &ctx.resolved.profile.tx_limits;
+            let client = connect(ctx)?.with_creation(creation(no_wait, wait_timeout));
+            let submitted = submit_job(&client, &ctx.hooks, input, limits, &ctx.checks()).await;
+            draft::discard();
+            if let Err(e) = submitted {
+                error::report("Failed to submit job", &e);
+                return Err(e.exit_code());
+            }
+        }
+        Commands::Import {
+            files,
+            start,
+            end,
+            duration,
+            transponder,
+            dry_run,
+            output_file,
+            report,
+            force,
+            skip_invalid,
+        } => {
+            let dry_run = dry_run.then(|| output::OutputTarget::new(output_file, force));
+            let report = report.map(|path| output::OutputTarget::new(Some(path), force));
+            let result = match import_window(start.as_deref(), end.as_deref(), duration.as_deref())
+            {
+                Ok(window) => {
+                    import_jobs(
+                        connect(ctx)?,
+                        &ctx.hooks,
+                        &files,
+                        window,
+                        transponder.as_deref(),
+                        dry_run,
+                        report,
+                        skip_invalid,
+                        &ctx.resolved.profile.tx_limits,
+                        &ctx.checks(),
+                    )
+                    .await
+                }
+                Err(e) => Err(e),
+            };
+
+            match result {
+                Ok(0) => {}
+                Ok(_) => return Err(error::EXIT_FAILURE),
+                Err(e) => {
+                    error::report("Import failed", &e);
+                    return Err(e.exit_code());
+                }
+            }
+        }
+        Commands::Jobs {
+            action:
+                JobsAction::List(ListJobsArgs {
+                    timeline,
+                    satellite_group,
+                    all,
+                    include_cancelled,
+                    partial_ok,
+                }),
+        } => {
+            let result = match satellite_group
+                .map(|group| satellites::AliasBook::load()?.expand(&group))
+                .transpose()
+            {
+                Ok(satellites) => {
+                    jobs::list(
+                        connect(ctx)?,
+                        args.output,
+                        timeline,
+                        satellites.as_deref(),
+                        all,
+                        include_cancelled,
+                        partial_ok,
+                    )
+                    .await
+                }
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(true) => {}
+                Ok(false) => return Err(error::EXIT_PARTIAL),
+                Err(e) => {
+                    error::report("Failed to list jobs", &e);
+                    return Err(e.exit_code());
+                }
+            }
+        }
+        Commands::Jobs {
+            action: JobsAction::Next(NextJobArgs { within }),
+        } => {
+            let result = match within.as_deref().map(parse::duration).transpose() {
+                Ok(within) => jobs::next(connect(ctx)?, args.output, within).await,
+                Err(e) => Err(e).context("reading --within"),
+            };
+            match result {
+                Ok(true) => {}
+                Ok(false) => return Err(error::EXIT_NO_JOB),
+                Err(e) => {
+                    error::report("Failed to find the next job", &e);
+                    return Err(e.exit_code());
+                }
+            }
+        }
+        Commands::Jobs {
+            action:
+                JobsAction::Tail(TailJobsArgs {
+                    interval,
+                    recent,
+                    upcoming,
+                    count,
+                }),
+        } => {
+            let options = tail::Options {
+                interval: std::time::Duration::from_secs(interval.max(1)),
+                recent,
+                upcoming,
+                count,
+            };
+            if let Err(e) = tail::run(connect(ctx)?, &options, args.output).await {
+                error::report("Failed to follow the station", &e);
+                return Err(e.exit_code());
+            }
+        }
+        Commands::Jobs {
+            action:
+                JobsAction::Reschedule(RescheduleArgs {
+                    id,
+                    shift,
+                    start,
+                    end,
+                    to_next_pass,
+                }),
+        } => {
+            let result = async {
+                let change = match shift {
+                    Some(shift) => reschedule::Change::Shift(reschedule::parse_shift(&shift)?),
+                    None if to_next_pass => reschedule::Change::NextPass {
+                        station: ctx.resolved.location()?,
+                        mask: ctx.resolved.profile.elevation_mask.clone(),
+                    },
+                    None => reschedule::Change::Set {
+                        start: start
+                            .as_deref()
+                            .map(parse::datetime)
+                            .transpose()
+                            .context("reading --start")?,
+                        end: end
+                            .as_deref()
+                            .map(parse::datetime)
+                            .transpose()
+                            .context("reading --end")?,
+                    },
+                };
+                reschedule::reschedule(ctx.client()?, &id, change, args.output).await
+            }
+            .await;
+
+            if let Err(e) = result {
+                error::report("Failed to reschedule job", &e);
+                return Err(e.exit_code());
+            }
+        }
+        Commands::Jobs {
+            action:
+                JobsAction::Cancel(CancelJobArgs {
+                    ids,
+                    yes,
+                    reason,
+                    reason_text,
+                    on_conflict,
+                }),
+        } => {
+            let result = match cancel::Reasons::new(
+                reason,
+                reason_text,
+                &ctx.resolved.profile.cancel_reasons,
+            ) {
+                Ok(reasons) => cancel::run(connect(ctx)?, &ids, yes, &reasons, on_conflict).await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(0) => {}
+                Ok(_) => return Err(error::EXIT_FAILURE),
+                Err(e) => {
+                    error::report("Failed to cancel jobs", &e);
+                    return Err(e.exit_code());
+                }
+            }
+        }
+        Commands::Jobs {
+            action:
+                JobsAction::Update(UpdateJobArgs {
+                    id,
+                    patch,
+                    on_conflict,
+                }),
+        } => {
+            if let Err(e) = update::update(
+                connect(ctx)?,
+                &id,
+                &patch,
+                &ctx.resolved.profile.tx_limits,
+                on_conflict,
+                args.output,
+            )
+            .await
+            {
+                error::report(&format!("Failed to update job {}", id), &e);
+                return Err(e.exit_code());
+            }
+        }
+        Commands::Jobs {
+            action: JobsAction::Restore(RestoreJobArgs { id }),
+        } => {
+            if let Err(e) = restore::restore(connect(ctx)?, &id).await {
+                error::report(&format!("Failed to restore job {}", id), &e);
+                return Err(e.exit_code());
+            }
+        }
+        Commands::Jobs {
+            action: JobsAction::Rebase(RebaseJobArgs { id, after, gap }),
+        } => {
+            let station = ctx.resolved.location().ok();
+            let horizon = station.as_ref().map(|station| predict::Horizon {
+                station,
+                min_elevation_deg: 0.0,
+                mask: &ctx.resolved.profile.elevation_mask,
+            });
+            let client = connect(ctx)?;
+            if let Err(e) =
+                chain::rebase(client, &id, &after, gap, horizon.as_ref(), args.output).await
+            {
+                error::report("Failed to rebase job", &e);
+                return Err(e.exit_code());
+            }
+        }
+        Commands::Jobs {
+            action:
+                JobsAction::Diff(DiffJobArgs {
+                    id,
+                    file,
+                    frequency_tolerance,
+                }),
+        } => {
+            match diff::diff_job(connect(ctx)?, &id, &file, frequency_tolerance, args.output).await
+            {
+                Ok(false) => {}
+                Ok(true) => return Err(error::EXIT_FAILURE),
+                Err(e) => {
+                    error::report("Failed to diff job", &e);
+                    return Err(e.exit_code());
+                }
+            }
+        }
+        Commands::Jobs {
+            action:
+                JobsAction::Download(DownloadArgs {
+                    id,
+                    dir,
+                    only,
+                    verify_only,
+                }),
+        } => match download::run(connect(ctx)?, &id, &dir, &only, verify_only).await {
+            Ok(0) => {}
+            Ok(_) => return Err(error::EXIT_FAILURE),
+            Err(e) => {
+                error::report(
+                    &format!("Failed to download the recordings of job {}", id),
+                    &e,
+                );
+                return Err(e.exit_code());
+            }
+        },
+        Commands::Jobs {
+            action:
+                JobsAction::AuditTles(AuditTlesArgs {
+                    max_age_at_pass,
+                    refresh,
+                }),
+        } => {
+            let result = async {
+                let max_age =
+                    parse::duration(&max_age_at_pass).context("reading --max-age-at-pass")?;
+                tle_audit::run(ctx.client()?, max_age, refresh, args.output).await
+            }
+            .await;
+            match result {
+                Ok(false) => {}
+                Ok(true) => return Err(error::EXIT_STALE_TLE),
+                Err(e) => {
+                    error::report("TLE audit failed", &e);
+                    return Err(e.exit_code());
+                }
+            }
+        }
+        Commands::Jobs {
+            action:
+                JobsAction::Handoff(HandoffArgs {
+                    id,
+                    out,
+                    force,
+                    notes,
+                }),
+        } => {
+            let target = output::OutputTarget::new(out, force);
+            let profile = &ctx.resolved.profile_name.value;
+            if let Err(e) = handoff::export(connect(ctx)?, &id, profile, notes, &target).await {
+                error::report(&format!("Failed to hand off job {}", id), &e);
+                return Err(e.exit_code());
+            }
+        }
+        Commands::Jobs {
+            action: JobsAction::ImportHandoff(ImportHandoffArgs { file }),
+        } => {
+            let profile = &ctx.resolved.profile;
+            let job = match handoff::import(&file, profile.handoff_max_age_hours).await {
+                Ok(job) => job,
+                Err(e) => {
+                    error::report("Failed to read the handoff", &e);
+                    return Err(e.exit_code());
+                }
+            };
+            let mut checks = ctx.checks();
+            match ctx.resolved.location() {
+                Ok(station) => checks.visibility = Some((station, profile.elevation_mask.clone())),
+                Err(_) => eprintln!(
+                    "⚠️ The profile does not say where the station is, so the pass is not \
+                     checked against its sky"
+                ),
+            }
+            let input = JobRequestBuilder::from(job);
+            if let Err(e) = submit_job(
+                connect(ctx)?,
+                &ctx.hooks,
+                input,
+                &profile.tx_limits,
+                &checks,
+            )
+            .await
+            {
+                error::report("Failed to submit job", &e);
+                return Err(e.exit_code());
+            }
+        }
+        Commands::Jobs {
+            action:
+                JobsAction::Sweep(SweepArgs {
+                    satellite,
+                    from,
+                    to,
+                    step,
+                    dwell,
+                    start,
+                    campaign,
+                    yes,
+                    dry_run,
+                }),
+        } => {
+            let start = match start.as_deref().map(parse::datetime).transpose() {
+                Ok(start) => start,
+                Err(e) => {
+                    error::report("Invalid --start", &e);
+                    return Err(e.exit_code());
+                }
+            };
+            let profile = &ctx.resolved.profile;
+            let station = sweep::Station {
+                client: connect(ctx)?,
+                hooks: &ctx.hooks,
+                location: ctx.resolved.location().ok(),
+                mask: &profile.elevation_mask,
+                limits: &profile.tx_limits,
+                checks: &ctx.checks(),
+                max_jobs: profile.sweep_max_jobs.unwrap_or(sweep::DEFAULT_MAX_JOBS),
+            };
+            let request = sweep::Request {
+                satellite: &satellite,
+                from,
+                to,
+                step,
+                dwell,
+                start,
+                campaign,
+                yes,
+                dry_run,
+            };
+            match sweep::run(station, request).await {
+                Ok(0) => {}
+                Ok(_) => return Err(error::EXIT_FAILURE),
+                Err(e) => {
+                    error::report("Sweep failed", &e);
+                    return Err(e.exit_code());
+                }
+            }
+        }
+        Commands::Stats {
+            from,
+            to,
+            group_by,
+            timezone,
+        } => {
+            let result = match timezone.map_or_else(|| ctx.resolved.day_boundary(), Ok) {
+                Ok(boundary) => {
+                    stats::show(
+                        connect(ctx)?,
+                        args.output,
+                        from.as_deref(),
+                        to.as_deref(),
+                        group_by,
+                        boundary,
+                    )
+                    .await
+                }
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                error::report("Failed to compute statistics", &e);
+                return Err(e.exit_code());
+            }
+        }
+        Commands::StationInfo {
+            watch,
+            interval,
+            metrics_listen,
+        } => {
+            let watch = watch.then(|| std::time::Duration::from_secs(interval.max(1)));
+            let _exporter = match metrics_listen {
+                Some(address) => metrics::listen(address, Some(connect(ctx)?.clone())).await,
+                None => None,
+            };
+            if let Err(e) = station::show(connect(ctx)?, args.output, watch).await {
+                error::report("Failed to get station info", &e);
+                return Err(e.exit_code());
+            }
+        }
+        Commands::Whoami => {
+            if let Err(e) = whoami::show(
+                connect(ctx)?,
+                &ctx.resolved.profile_name.value,
+                &ctx.resolved.base_url.value,
+                args.output,
+            )
+            .await
+            {
+                error::report("Failed to get the identity", &e);
+                return Err(e.exit_code());
+            }
+        }
+        Commands::Tle {
+            action: TleAction::Compare { a, b, at, force },
+        } => {
+            if let Err(e) = tle_compare::run(a, b, at, force, args.output).await {
+                error::report("TLE comparison failed", &e);
+                return Err(e.exit_code());
+            }
+        }
+        Commands::Tle {
+            action: TleAction::RefreshAll { group },
+        } => {
+            if let Err(e) = tle_refresh::run(group.as_deref(), args.output).await {
+                error::report("TLE refresh failed", &e);
+                return Err(e.exit_code());
+            }
+        }
+        Commands::Tle { action } => {
+            let result = match action {
+                TleAction::Compare { .. } | TleAction::RefreshAll { .. } => {
+                    unreachable!("handled above")
+                }
+                TleAction::Push {
+                    file,
+                    norad,
+                    update,
+                } => {
+                    let tle = match (file, norad) {
+                        (Some(path), _) => tle::read_file(&path),
+                        (None, Some(id)) => tle::resolve_id(id).await,
+                        (None, None) => get_tle_input(),
+                    };
+                    match tle {
+                        Ok(tle) => catalog::push(connect(ctx)?, tle, update).await,
+                        Err(e) => Err(e),
+                    }
+                }
+                TleAction::Pull {
+                    norad_id,
+                    file,
+                    no_compare,
+                } => {
+                    catalog::pull(
+                        connect(ctx)?,
+                        norad_id,
+                        file.as_deref(),
+                        !no_compare,
+                        args.output,
+                    )
+                    .await
+                }
+            };
+
+            if let Err(e) = result {
+                error::report("TLE catalog operation failed", &e);
+                return Err(e.exit_code());
+            }
+        }
+        Commands::Predict {
+            norad_id,
+            intl_designator,
+            alias_group,
+            tle_file,
+            from,
+            hours,
+            min_elevation,
+            mask,
+            show_mask,
+            sort,
+            min_score,
+            best,
+            pick,
+        } => {
+            let result = async {
+                let station = ctx.resolved.location()?;
+                let mask = match mask {
+                    Some(mask) => mask::ElevationMask::parse(&mask)?,
+                    None => ctx.resolved.profile.elevation_mask.clone(),
+                };
+                let mut ids: Vec<satellite_id::SatelliteId> = norad_id
+                    .into_iter()
+                    .map(satellite_id::SatelliteId::NoradId)
+                    .chain(
+                        intl_designator
+                            .into_iter()
+                            .map(satellite_id::SatelliteId::IntlDesignator),
+                    )
+                    .collect();
+                if let Some(group) = alias_group {
+                    ids.extend(satellites::AliasBook::load()?.expand(&group)?);
+                }
+                let mut seen = std::collections::HashSet::new();
+                ids.retain(|id| seen.insert(id.clone()));
+                let tles = match (tle_file, ids.is_empty()) {
+                    (Some(path), _) => vec![tle::read_file(&path)?],
+                    (None, true) => vec![get_tle_input()?],
+                    (None, false) => {
+                        // Fetch concurrently; each one may have to go to the network
+                        let fetches: Vec<_> = ids
+                            .into_iter()
+                            .map(|id| tokio::spawn(tle::resolve_id(id)))
+                            .collect();
+                        let mut tles = Vec::with_capacity(fetches.len());
+                        for fetch in fetches {
+                            tles.push(fetch.await.expect("TLE fetch task panicked")?);
+                        }
+                        tles
+                    }
+                };
+                let from = match from {
+                    Some(from) => parse::datetime(&from).context("reading --from")?,
+                    None => clock::now(),
+                };
+                let horizon = predict::Horizon {
+                    station: &station,
+                    min_elevation_deg: min_elevation,
+                    mask: &mask,
+                };
+                let listing = predict::Listing {
+                    show_mask,
+                    order: sort,
+                    min_score,
+                    best,
+                };
+                let scorer = pass_score::Scorer::new(&ctx.resolved)?;
+                let passes =
+                    predict::show(&tles, &horizon, from, hours, &listing, &scorer, args.output)?;
+                if !pick {
+                    return Ok(());
+                }
+                let Some(pass) = predict::pick(&passes)? else {
+                    println!("📭 No usable pass to schedule");
+                    return Ok(());
+                };
+                let (Some(aos), Some(los)) = (pass.aos, pass.los) else {
+                    unreachable!("only passes with an AOS and LOS are offered");
+                };
+                // The station schedules in whole seconds
+                let (start_datetime, end_datetime) = (aos.trunc_subsecs(0), los.trunc_subsecs(0));
+                println!(
+                    "🚀 Creating a tracking job for {} from {} to {}\n",
+                    pass.satellite,
+                    humanize::timestamp(start_datetime),
+                    end_datetime.format("%H:%M:%S UTC")
+                );
+                let input = JobRequestBuilder::new(
+                    start_datetime,
+                    end_datetime,
+                    JobType::Tracking { tle: pass.tle },
+                )
+                .rx_frequency(get_frequency_input(
+                    "RX",
+                    history::Prompt::RxFrequency,
+                    "145800000",
+                    None,
+                )?)
+                .tx_frequency(get_frequency_input(
+                    "TX",
+                    history::Prompt::TxFrequency,
+                    "437500000",
+                    None,
+                )?);
+                submit_job(
+                    ctx.client()?,
+                    &ctx.hooks,
+                    input,
+                    &ctx.resolved.profile.tx_limits,
+                    &ctx.checks(),
+                )
+                .await
+            }
+            .await;
+
+            if let Err(e) = result {
+                error::report("Pass prediction failed", &e);
+                return Err(e.exit_code());
+            }
+        }
+        Commands::Schema {
+            kind,
+            validate_only: None,
+        } => {
+            if let Err(e) = input_schema::print(kind) {
+                error::report("Failed to print the schema", &e);
+                return Err(e.exit_code());
+            }
+        }
+        Commands::Schema {
+            kind,
+            validate_only: Some(path),
+        } => {
+            let limits = &ctx.resolved.profile.tx_limits;
+            match input_schema::validate_file(kind, &path, limits).await {
+                Ok(0) => {}
+                Ok(_) => return Err(error::EXIT_FAILURE),
+                Err(e) => {
+                    error::report(&format!("Failed to validate {}", path.display()), &e);
+                    return Err(e.exit_code());
+                }
+            }
+        }
+        Commands::Quick { satellite, within } => {
+            let result = async {
+                let within = match within {
+                    Some(within) => parse::duration(&within).context("reading --within")?,
+                    None => chrono::Duration::minutes(
+                        ctx.resolved
+                            .profile
+                            .quick_horizon_minutes
+                            .unwrap_or(quick::DEFAULT_HORIZON_MINUTES)
+                            .try_into()
+                            .unwrap_or(i64::MAX),
+                    ),
+                };
+                let station = quick::Station {
+                    client: ctx.client()?,
+                    hooks: &ctx.hooks,
+                    location: ctx.resolved.location()?,
+                    mask: &ctx.resolved.profile.elevation_mask,
+                    limits: &ctx.resolved.profile.tx_limits,
+                };
+                quick::run(station, &satellite, within).await
+            }
+            .await;
+
+            if let Err(e) = result {
+                error::report("Quick submission failed", &e);
+                return Err(e.exit_code());
+            }
+        }
+        Commands::LookAngles {
+            job_id,
+            tle,
+            start,
+            end,
+            step,
+            format,
+            output_file,
+            force,
+        } => {
+            let output = output::OutputTarget::new(output_file, force);
+            let result = async {
+                let station = ctx.resolved.location()?;
+                let step = parse::duration(&step).context("reading --step")?;
+                let (tle, start, end) = match (job_id, tle, start, end) {
+                    (Some(id), ..) => match look_angles::job_window(ctx.client()?, &id).await? {
+                        Some(window) => window,
+                        None => return Ok(()),
+                    },
+                    (None, Some(path), Some(start), Some(end)) => (
+                        tle::read_file(&path)?,
+                        parse::datetime(&start).context("reading --start")?,
+                        parse::datetime(&end).context("reading --end")?,
+                    ),
+                    _ => unreachable!("clap requires a job ID or --tle with --start and --end"),
+                };
+                look_angles::export(&tle, &station, (start, end), step, format, &output)
+            }
+            .await;
+
+            if let Err(e) = result {
+                error::report("Look-angle export failed", &e);
+                return Err(e.exit_code());
+            }
+        }
+        Commands::Report {
+            job_id,
+            format,
+            template,
+            notes,
+            output_file,
+            force,
+        } => {
+            let output = output::OutputTarget::new(output_file, force);
+            let sources = report::Sources {
+                station: ctx.resolved.location().map_err(|_| {
+                    "the profile gives no station position (config set-location, or --coords)"
+                        .to_string()
+                }),
+                mask: &ctx.resolved.profile.elevation_mask,
+                notes,
+            };
+            let template = template.as_deref();
+            if let Err(e) =
+                report::run(connect(ctx)?, &job_id, sources, format, template, &output).await
+            {
+                error::report(&format!("Failed to write the report of job {}", job_id), &e);
+                return Err(e.exit_code());
+            }
+        }
+        Commands::Export {
+            action:
+                ExportAction::Coordination {
+                    from,
+                    to,
+                    format,
+                    output_file,
+                    force,
+                },
+        } => {
+            let output = output::OutputTarget::new(output_file, force);
+            let result = async {
+                let range = coordination::parse_range(&from, &to)?;
+                coordination::export(ctx.client()?, &ctx.resolved, range, format, &output).await
+            }
+            .await;
+
+            if let Err(e) = result {
+                error::report("Coordination export failed", &e);
+                return Err(e.exit_code());
+            }
+        }
+        Commands::Simulate {
+            job_id,
+            file,
+            speed,
+            step,
+            lead,
+        } => {
+            let result = async {
+                let station = ctx.resolved.location()?;
+                let pace = simulate::Pace {
+                    speed: simulate::parse_speed(&speed)?,
+                    step: parse::duration(&step).context("reading --step")?,
+                    lead: parse::duration(&lead).context("reading --lead")?,
+                };
+                let job = match (job_id, file) {
+                    (Some(id), _) => simulate::fetch(ctx.client()?, &id).await?,
+                    (None, Some(path)) => simulate::read_file(&path)?,
+                    (None, None) => unreachable!("clap requires --job-id or --file"),
+                };
+                simulate::rehearse(&job, &station, &ctx.resolved.profile.elevation_mask, pace)
+            }
+            .await;
+
+            if let Err(e) = result {
+                error::report("Simulation failed", &e);
+                return Err(e.exit_code());
+            }
+        }
+        Commands::Sat { action } => {
+            let result = match action {
+                SatAction::List => satellites::list(args.output),
+                SatAction::Group { action } => match action {
+                    GroupAction::Add { group, members } => satellites::group_add(&group, &members),
+                    GroupAction::Remove { group, members } => {
+                        satellites::group_remove(&group, &members)
+                    }
+                    GroupAction::List => satellites::group_list(args.output),
+                },
+                SatAction::SetFreq {
+                    alias,
+                    norad_id,
+                    intl_designator,
+                    rx,
+                    rx_offset,
+                    tx,
+                    mode,
+                    baud,
+                    downlink_band,
+                    uplink_band,
+                    inverting,
+                } => {
+                    let passband = downlink_band
+                        .zip(uplink_band)
+                        .map(|(downlink, uplink)| Passband::new(downlink, uplink, inverting));
+                    set_frequency_preset(
+                        &alias,
+                        norad_id,
+                        intl_designator,
+                        rx,
+                        rx_offset,
+                        tx,
+                        mode,
+                        baud,
+                        passband,
+                    )
+                }
+            };
+
+            if let Err(e) = result {
+                error::report("Alias book operation failed", &e);
+                return Err(e.exit_code());
+            }
+        }
+        Commands::MockServer { .. }
+        | Commands::GenerateDocs { .. }
+        | Commands::Examples { .. }
+        | Commands::Init { .. } => {
+            unreachable!("handled before the configuration is loaded")
+        }
+        Commands::Shell => unreachable!("the shell runs commands rather than being one"),
+        Commands::AddJob(_)
+        | Commands::ListJobs(_)
+        | Commands::NextJob(_)
+        | Commands::Reschedule(_)
+        | Commands::CancelJob(_)
+        | Commands::DiffJob(_) => unreachable!("forwarded to jobs by canonical()"),
+        Commands::Mqtt {
+            action:
+                MqttAction::Follow {
+                    topic,
+                    job,
+                    ascii,
+                    metrics_listen,
+                },
+        } => {
+            // The next-job gauge is a bonus; following works without an API to ask
+            let _exporter = match metrics_listen {
+                Some(address) => metrics::listen(address, ctx.client().ok().cloned()).await,
+                None => None,
+            };
+            let result = match &ctx.resolved.profile.mqtt {
+                Some(settings) => {
+                    mqtt::follow(
+                        settings,
+                        topic.as_deref(),
+                        job.as_deref(),
+                        args.output,
+                        ascii,
+                    )
+                    .await
+                }
+                None => Err(CliError::Configuration(format!(
+                    "profile '{}' has no [mqtt] broker configured",
+                    ctx.resolved.profile_name.value
+                ))),
+            };
+
+            if let Err(e) = result {
+                error::report("MQTT follow failed", &e);
+                return Err(e.exit_code());
+            }
+        }
+        Commands::Config {
+            action: ConfigAction::List { show_origin },
+        } => {
+            if let Err(e) = config::list(&ctx.resolved, show_origin, args.output) {
+                error::report("Failed to list configuration", &e);
+                return Err(e.exit_code());
+            }
+        }
+        Commands::Config {
+            action: ConfigAction::Resolve,
+        } => {
+            if let Err(e) = config::resolve(&ctx.resolved, args.output) {
+                error::report("Failed to resolve configuration", &e);
+                return Err(e.exit_code());
+            }
+        }
+        Commands::Config {
+            action: ConfigAction::SetLocation { location, altitude },
+        } => {
+            let result = location::parse(&location.join(" ")).and_then(|mut parsed| {
+                if let Some(altitude) = altitude {
+                    parsed.altitude_m = location::parse_altitude(&altitude)?;
+                }
+                let path = config::set_location(&ctx.resolved.profile_name.value, &parsed)?;
+                println!(
+                    "📍 Saved {} for profile '{}' in {}",
+                    parsed,
+                    ctx.resolved.profile_name.value,
+                    path.display()
+                );
+                Ok(())
+            });
+
+            if let Err(e) = result {
+                error::report("Failed to set the station location", &e);
+                return Err(e.exit_code());
+            }
+        }
+        Commands::Undo { yes } => {
+            let window = ctx
+                .resolved
+                .profile
+                .undo_window_minutes
+                .unwrap_or(undo::DEFAULT_WINDOW_MINUTES);
+            let profile = &ctx.resolved.profile_name.value;
+            match undo::run(connect(ctx)?, profile, window, yes).await {
+                Ok(0) => {}
+                Ok(_) => return Err(error::EXIT_FAILURE),
+                Err(e) => {
+                    error::report("Undo failed", &e);
+                    return Err(e.exit_code());
+                }
+            }
+        }
+        Commands::Campaign { action } => {
+            let client = connect(ctx)?;
+            let result = match action {
+                CampaignAction::List => campaign::list(client, args.output).await.map(|_| 0),
+                CampaignAction::Show { name } => {
+                    campaign::show(client, &name, args.output).await.map(|_| 0)
+                }
+                CampaignAction::Cancel {
+                    name,
+                    yes,
+                    reason,
+                    reason_text,
+                } => match cancel::Reasons::new(
+                    reason,
+                    reason_text,
+                    &ctx.resolved.profile.cancel_reasons,
+                ) {
+                    Ok(reasons) => campaign::cancel(client, &name, yes, &reasons).await,
+                    Err(e) => Err(e),
+                },
+                CampaignAction::Clone {
+                    name,
+                    shift,
+                    new_name,
+                    skip_invalid,
+                } => match reschedule::parse_shift(&shift) {
+                    Ok(offset) => {
+                        let new_name =
+                            new_name.unwrap_or_else(|| format!("{}+{}", name, shift.trim()));
+                        campaign::clone(
+                            client,
+                            &ctx.hooks,
+                            &name,
+                            offset,
+                            &new_name,
+                            skip_invalid,
+                            &ctx.resolved.profile.tx_limits,
+                            &ctx.checks(),
+                        )
+                        .await
+                    }
+                    Err(e) => Err(e).context("reading --shift"),
+                },
+            };
+            match result {
+                Ok(0) => {}
+                Ok(_) => return Err(error::EXIT_FAILURE),
+                Err(e) => {
+                    error::report("Campaign operation failed", &e);
+                    return Err(e.exit_code());
+                }
+            }
+        }
+        Commands::Queue { action } => {
+            let result = match action {
+                QueueAction::List => list_queue(),
+                QueueAction::Flush => flush_queue(connect(ctx)?, &ctx.hooks).await,
+            };
+
+            if let Err(e) = result {
+                error::report("Queue operation failed", &e);
+                return Err(e.exit_code());
+            }
+        }
+        Commands::Audit { since, user } => {
+            let result = since
+                .as_deref()
+                .map(|since| stats::parse_bound(since, ctx.resolved.day_boundary()?, "since"))
+                .transpose()
+                .and_then(|since| audit::show(since, user.as_deref(), args.output));
+
+            if let Err(e) = result {
+                error::report("Failed to read the audit log", &e);
+                return Err(e.exit_code());
+            }
+        }
+        Commands::History { action } => {
+            let (result, what) = match action {
+                HistoryAction::ClearPrompts => (history::clear(), "clear the prompt history"),
+                HistoryAction::List => (snapshot::list(args.output), "list the job snapshots"),
+                HistoryAction::Show { n, full } => (
+                    snapshot::show(n, full, args.output),
+                    "show the job snapshot",
+                ),
+                HistoryAction::Replay { n } => (
+                    snapshot::replay(n, &ctx.resolved.profile.tx_limits),
+                    "replay the job snapshot",
+                ),
+            };
+            if let Err(e) = result {
+                error::report(&format!("Failed to {}", what), &e);
+                return Err(e.exit_code());
+            }
+        }
+        Commands::Receipts {
+            action: ReceiptsAction::Verify { dir },
+        } => {
+            let dir = dir.unwrap_or_else(paths::receipts_dir);
+            match receipt::verify(&dir, &ctx.resolved.profile.receipt_signing, args.output).await {
+                Ok(false) => {}
+                Ok(true) => return Err(error::EXIT_BROKEN_CHAIN),
+                Err(e) => {
+                    error::report("Failed to verify the receipts", &e);
+                    return Err(e.exit_code());
+                }
+            }
+        }
+        Commands::Purge {
+            older_than,
+            what,
+            yes,
+            dry_run,
+        } => {
+            if let Err(e) = purge::run(&what, older_than, yes, dry_run, args.output) {
+                error::report("Purge failed", &e);
+                return Err(e.exit_code());
+            }
+        }
+        Commands::Backup {
+            action:
+                BackupAction::Export {
+                    file,
+                    no_secrets,
+                    force,
+                },
+        } => {
+            let target = output::OutputTarget::new(Some(file), force);
+            if let Err(e) = backup::export(&target, no_secrets) {
+                error::report("Backup failed", &e);
+                return Err(e.exit_code());
+            }
+        }
+        Commands::Backup {
+            action:
+                BackupAction::Import {
+                    file,
+                    config,
+                    aliases,
+                    history,
+                    audit,
+                },
+        } => {
+            let policies = backup::Policies {
+                config,
+                aliases,
+                history,
+                audit,
+            };
+            if let Err(e) = backup::import(&file, &policies, args.output) {
+                error::report("Restoring the backup failed", &e);
+                return Err(e.exit_code());
+            }
+        }
+        Commands::Alias {
+            action: AliasAction::List,
+        } => {
+            if let Err(e) = alias::list(&ctx.resolved, args.output) {
+                error::report("Failed to list aliases", &e);
+                return Err(e.exit_code());
+            }
+        }
+        Commands::Doctor { attach_transcript } => {
+            let attached = match &attach_transcript {
+                Some(path) => match transcript::read(path) {
+                    Ok(transcript) => Some((path.as_path(), transcript)),
+                    Err(e) => {
+                        error::report("Failed to read the transcript", &e);
+                        return Err(e.exit_code());
+                    }
+                },
+                None => None,
+            };
+            let stores = [
+                (&queue::STORE, queue::queue_path()),
+                (&campaign::STORE, paths::campaigns_file()),
+                (&chain::STORE, paths::chains_file()),
+                (&baseline::STORE, paths::baselines_file()),
+                (&history::STORE, paths::prompt_history_file()),
+                (&satellites::STORE, satellites::book_path()),
+            ];
+            let rotator = rotator::selected().await;
+            let attached = attached
+                .as_ref()
+                .map(|(path, transcript)| (*path, transcript));
+            match schema::doctor(&stores, &rotator, attached, args.output) {
+                Ok(0) => {}
+                Ok(problems) => {
+                    eprintln!(
+                        "❌ {} local store(s) or rotator probe(s) need attention",
+                        problems
+                    );
+                    return Err(error::EXIT_FAILURE);
+                }
+                Err(e) => {
+                    error::report("Failed to inspect the local stores", &e);
+                    return Err(e.exit_code());
+                }
+            }
+        }
+        Commands::SmokeTest { cleanup } => {
+            // A client of its own, whose observer keeps each request's ID for the report
+            let recorder = std::sync::Arc::new(smoke::Recorder::new(observer(ctx.verbose)));
+            let result = async {
+                let client =
+                    client_builder(&ctx.resolved, &ctx.resolved.base_url.value, ctx.verbose)?
+                        .observer(recorder.clone())
+                        .build()?;
+                let profile = (
+                    ctx.resolved.profile_name.value.as_str(),
+                    ctx.resolved.profile.protected,
+                );
+                smoke::run(&client, &recorder, profile, cleanup, args.output).await
+            }
+            .await;
+
+            if let Err(e) = result {
+                error::report("Smoke test failed", &e);
+                return Err(e.exit_code());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// `shell`: run the lines typed until exit, Ctrl-D or the end of stdin, and return the
+/// status of the last command, as other shells do
+async fn shell(session: &Args, ctx: &Context) -> i32 {
+    if tty::interactive() {
+        println!(
+            "🐚 rustar-cli shell on profile {} at {}; help lists the commands, exit leaves",
+            ctx.resolved.profile_name.value, ctx.resolved.base_url.value
+        );
+    }
+    let mut editor = shell::LineEditor::new();
+    let mut status = error::EXIT_SUCCESS;
+    loop {
+        let line = match editor.read() {
+            Ok(shell::Input::Line(line)) => line,
+            Ok(shell::Input::Interrupted) => continue,
+            Ok(shell::Input::Closed) => break,
+            Err(e) => {
+                error::report("Failed to read a command", &e);
+                status = e.exit_code();
+                break;
+            }
+        };
+        let words = match alias::words(&line) {
+            Ok(words) => words,
+            Err(reason) => {
+                error::report(
+                    "Could not read the command",
+                    &CliError::validation("command", reason),
+                );
+                status = error::EXIT_USAGE;
+                continue;
+            }
+        };
+        let Some(first) = words.first() else {
+            continue;
+        };
+        transcript::answered(
+            shell::PROMPT.trim(),
+            &audit::redact(words.clone()).join(" "),
+        );
+        match first.as_str() {
+            "exit" | "quit" => break,
+            "help" if words.len() == 1 => {
+                shell_help();
+                continue;
+            }
+            _ => {}
+        }
+        status = shell::run_command(async {
+            match shell_line(words, session, ctx).await {
+                Ok(()) => error::EXIT_SUCCESS,
+                Err(status) => status,
+            }
+        })
+        .await;
+        timing::finish();
+        phases::finish();
+        audit::finish(status);
+    }
+    status
+}
+
+fn shell_help() {
+    let _ = Args::command().print_help();
+    println!(
+        "\nIn the shell, type commands without rustar-cli in front. help COMMAND shows one, \
+         the up and down arrows go through earlier commands, and exit or Ctrl-D leaves."
+    );
+}
+
+/// One line typed into the shell, checked and run as `main` runs its command
+async fn shell_line(words: Vec<String>, session: &Args, ctx: &Context) -> Result<(), i32> {
+    let argv: Vec<OsString> = std::iter::once(OsString::from("rustar-cli"))
+        .chain(words.iter().map(OsString::from))
+        .collect();
+    let argv = match alias::expand(argv) {
+        Ok(argv) => argv,
+        Err(e) => {
+            error::report("Failed to load configuration", &e);
+            return Err(e.exit_code());
+        }
+    };
+    let mut args = match shell_command(argv, session) {
+        Ok(args) => args,
+        Err(e) => {
+            // Help and version are "errors" too, printed on stdout
+            let _ = e.print();
+            return Err(e.exit_code());
+        }
+    };
+    args.command = args.command.canonical();
+    match &args.command {
+        Commands::Shell
+        | Commands::Init { .. }
+        | Commands::MockServer { .. }
+        | Commands::GenerateDocs { .. } => {
+            error::report(
+                "Not available in the shell",
+                &CliError::Unsupported(
+                    "shell, init, mock-server and generate-docs run on their own, outside the \
+                     shell"
+                        .to_string(),
+                ),
+            );
+            return Err(error::EXIT_FAILURE);
+        }
+        Commands::Examples { topic } => {
+            if let Err(e) = examples::show(*topic, args.output) {
+                error::report("Failed to show examples", &e);
+                return Err(e.exit_code());
+            }
+            return Ok(());
+        }
+        _ => {}
+    }
+    check_read_only(&args)?;
+    check_output(&args)?;
+    run(args, ctx, words).await
+}
+
+/// The command a line typed into the shell names, under the `GlobalArgs` the shell was
+/// started with. Only the flags of `Args` itself may be given again.
+fn shell_command(argv: Vec<OsString>, session: &Args) -> Result<Args, clap::Error> {
+    let matches = Args::command().try_get_matches_from(argv)?;
+    let mut args = Args::from_arg_matches(&matches)?;
+    let given = |id: &str| matches.value_source(id) == Some(ValueSource::CommandLine);
+    let globals = <GlobalArgs as clap::Args>::augment_args(clap::Command::new("global"));
+    let fixed = globals
+        .get_arguments()
+        .find(|arg| given(arg.get_id().as_str()))
+        .map(|arg| arg.get_long().unwrap_or(arg.get_id().as_str()).to_string());
+    if let Some(fixed) = fixed {
+        return Err(Args::command().error(
+            clap::error::ErrorKind::ArgumentConflict,
+            format!(
+                "--{} is set for the whole shell; start another shell with it",
+                fixed
+            ),
+        ));
+    }
+    if !given("output") {
+        args.output = session.output;
+    }
+    args.read_only |= session.read_only;
+    if args.confirm_protected.is_none() {
+        args.confirm_protected = session.confirm_protected.clone();
+    }
+    args.global = session.global.clone();
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::ValueEnum;
+    use examples::Topic;
+
+    /// Why `input` is not what `topic` says it shows
+    fn refused(topic: Topic, input: &str) -> Option<String> {
+        let result = match topic {
+            Topic::Datetimes => parse::instant(input).map(drop),
+            Topic::Durations => parse::duration(input).map(drop),
+            Topic::Frequencies if input.contains('-') => transponder::parse_band(input).map(drop),
+            Topic::Frequencies => Frequency::parse(input).map(drop),
+            Topic::Tle => {
+                return satellite_id::catalog_id_arg(input).err();
+            }
+            Topic::Batch | Topic::Scripting => return Some("no parser to check it".to_string()),
+        };
+        result.err().map(|e| e.full_message())
+    }
+
+    /// The arguments of every rustar-cli invocation in a shell line, past any pipe and
+    /// the environment assignments before it
+    fn invocations(line: &str) -> Vec<Vec<String>> {
+        line.split('|')
+            .filter_map(|segment| {
+                let words = alias::words(segment).expect("balanced quotes");
+                let at = words.iter().position(|word| word == "rustar-cli")?;
+                assert!(
+                    words[..at].iter().all(|word| word.contains('=')),
+                    "{}",
+                    line
+                );
+                Some(words[at..].to_vec())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn every_example_parses() {
+        for topic in Topic::value_variants() {
+            for example in topic.examples() {
+                let calls = invocations(example.command);
+                assert!(!calls.is_empty(), "{}", example.command);
+                for call in calls {
+                    if let Err(e) = Args::try_parse_from(&call) {
+                        panic!("{}: {}", example.command, e);
+                    }
+                }
+                if let Some(input) = example.input {
+                    assert!(example.command.contains(input), "{}", example.command);
+                    assert_eq!(refused(*topic, input), None, "{}", input);
+                }
             }
         }
     }
@@ -0,0 +1,68 @@
+use crate::client::{ApiClient, MaintenanceWindow};
+use crate::error::CliError;
+use crate::schedule::TimeWindow;
+use crate::transcript::Recorded;
+use crate::tty;
+use inquire::Confirm;
+
+/// Statuses meaning the server has no maintenance endpoint at all
+fn is_missing_endpoint(error: &CliError) -> bool {
+    matches!(error.status(), Some(404 | 405 | 501))
+}
+
+/// The station's maintenance windows; empty, without a word, when the server publishes none
+pub async fn windows(client: &ApiClient) -> Vec<MaintenanceWindow> {
+    match client.maintenance().await {
+        Ok(windows) => windows,
+        Err(e) if is_missing_endpoint(&e) => Vec::new(),
+        Err(e) => {
+            eprintln!(
+                "⚠️ Could not check maintenance windows: {}",
+                e.full_message()
+            );
+            Vec::new()
+        }
+    }
+}
+
+/// Maintenance windows sharing any time with `window`
+pub fn intersecting(windows: &[MaintenanceWindow], window: TimeWindow) -> Vec<&MaintenanceWindow> {
+    windows
+        .iter()
+        .filter(|w| w.window().overlaps(&window))
+        .collect()
+}
+
+/// One-line "start – end: reason" rendering of a window
+pub fn describe(window: &MaintenanceWindow) -> String {
+    format!(
+        "{} – {}: {}",
+        window.start,
+        window.end,
+        window.reason.as_deref().unwrap_or("no reason given")
+    )
+}
+
+/// Warn about maintenance overlapping a job window and ask whether to schedule it anyway.
+/// Returns whether the job should go ahead.
+pub async fn confirm_window(client: &ApiClient, window: TimeWindow) -> Result<bool, CliError> {
+    let windows = windows(client).await;
+    let clashes = intersecting(&windows, window);
+    if clashes.is_empty() {
+        return Ok(true);
+    }
+
+    eprintln!(
+        "🚧 The requested window overlaps {} maintenance period(s); the job will not run during them:",
+        clashes.len()
+    );
+    for window in clashes {
+        eprintln!("   {}", describe(window));
+    }
+    // Nothing on the command line overrides a maintenance clash; only a person may
+    tty::require("scheduling into a maintenance period", &[])?;
+    Ok(Confirm::new("Schedule the job anyway?")
+        .with_default(false)
+        .prompt()
+        .recorded("Schedule the job anyway?")?)
+}
@@ -0,0 +1,91 @@
+use crate::error::CliError;
+use serde::{Deserialize, Serialize};
+
+/// Lowest usable elevation by azimuth, from (azimuth, min elevation) breakpoints in degrees.
+/// Between breakpoints the limit is interpolated linearly, wrapping around through north.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "Vec<(f64, f64)>", into = "Vec<(f64, f64)>")]
+pub struct ElevationMask {
+    /// Sorted by azimuth, each in 0..360
+    points: Vec<(f64, f64)>,
+}
+
+impl TryFrom<Vec<(f64, f64)>> for ElevationMask {
+    type Error = String;
+
+    fn try_from(points: Vec<(f64, f64)>) -> Result<Self, Self::Error> {
+        let mut checked = Vec::with_capacity(points.len());
+        for (azimuth, elevation) in points {
+            if !azimuth.is_finite() || !(0.0..=360.0).contains(&azimuth) {
+                return Err(format!("azimuth {} is outside 0..360 degrees", azimuth));
+            }
+            if !elevation.is_finite() || !(-90.0..=90.0).contains(&elevation) {
+                return Err(format!(
+                    "elevation {} is outside -90..90 degrees",
+                    elevation
+                ));
+            }
+            checked.push((azimuth % 360.0, elevation));
+        }
+        checked.sort_by(|a, b| a.0.total_cmp(&b.0));
+        if let Some(pair) = checked.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+            return Err(format!("azimuth {} appears twice", pair[0].0));
+        }
+        Ok(Self { points: checked })
+    }
+}
+
+impl From<ElevationMask> for Vec<(f64, f64)> {
+    fn from(mask: ElevationMask) -> Self {
+        mask.points
+    }
+}
+
+impl ElevationMask {
+    /// Parse "AZ:EL,AZ:EL,..." such as "0:5,45:25,90:5"
+    pub fn parse(input: &str) -> Result<Self, CliError> {
+        let error = |reason: String| CliError::parse(format!("elevation mask '{}'", input), reason);
+        let mut points = Vec::new();
+        for part in input.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (azimuth, elevation) = part
+                .split_once(':')
+                .ok_or_else(|| error(format!("'{}' is not AZ:EL", part)))?;
+            let number = |text: &str| {
+                text.trim()
+                    .parse::<f64>()
+                    .map_err(|_| error(format!("'{}' is not a number", text.trim())))
+            };
+            points.push((number(azimuth)?, number(elevation)?));
+        }
+        Self::try_from(points).map_err(error)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Interpolated limit at `azimuth`; an empty mask allows everything above the horizon
+    pub fn min_elevation(&self, azimuth: f64) -> f64 {
+        let azimuth = azimuth.rem_euclid(360.0);
+        let (first, last) = match (self.points.first(), self.points.last()) {
+            (Some(first), Some(last)) => (*first, *last),
+            _ => return 0.0,
+        };
+        if self.points.len() == 1 {
+            return first.1;
+        }
+
+        // The segment holding `azimuth`, where the one past the last point wraps to the first
+        let (from, to) = match self.points.windows(2).find(|pair| azimuth < pair[1].0) {
+            Some(pair) if azimuth >= pair[0].0 => (pair[0], pair[1]),
+            _ => (last, (first.0 + 360.0, first.1)),
+        };
+        let azimuth = if azimuth < from.0 {
+            azimuth + 360.0
+        } else {
+            azimuth
+        };
+        let fraction = (azimuth - from.0) / (to.0 - from.0);
+        from.1 + fraction * (to.1 - from.1)
+    }
+}
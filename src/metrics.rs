@@ -0,0 +1,237 @@
+use crate::client::ApiClient;
+use crate::clock;
+use crate::progress::Observer;
+use axum::routing::get;
+use axum::Router;
+use chrono::{DateTime, Utc};
+use reqwest::{Method, StatusCode};
+use std::fmt::Write;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+/// How often the exporter asks the server for the next job
+const NEXT_JOB_REFRESH: Duration = Duration::from_secs(60);
+
+/// Messages received from the broker
+static EVENTS: AtomicU64 = AtomicU64::new(0);
+/// Times the broker connection was lost and retried
+static RECONNECTS: AtomicU64 = AtomicU64::new(0);
+/// API responses by status class 1xx to 5xx, then requests that got no response
+static HTTP: [AtomicU64; 6] = [
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+];
+const HTTP_CLASSES: [&str; 6] = ["1xx", "2xx", "3xx", "4xx", "5xx", "error"];
+
+/// Values that are only known once something has reported them
+struct Gauges {
+    next_job: Option<DateTime<Utc>>,
+    elevation: Option<f64>,
+}
+
+static GAUGES: Mutex<Gauges> = Mutex::new(Gauges {
+    next_job: None,
+    elevation: None,
+});
+
+fn gauges() -> std::sync::MutexGuard<'static, Gauges> {
+    GAUGES.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+pub fn event() {
+    EVENTS.fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn reconnect() {
+    RECONNECTS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// The antenna elevation the station last reported, in degrees
+pub fn set_elevation(degrees: f64) {
+    gauges().elevation = Some(degrees);
+}
+
+/// Counts API responses by status class, passing every report on to the observer it wraps
+pub struct Counting<O> {
+    inner: O,
+}
+
+impl<O: Observer> Counting<O> {
+    pub fn new(inner: O) -> Self {
+        Self { inner }
+    }
+}
+
+impl<O: Observer> Observer for Counting<O> {
+    fn on_client_ready(&self, base_url: &str) {
+        self.inner.on_client_ready(base_url);
+    }
+
+    fn on_job_submit(&self, url: &str, hold: Option<&str>) {
+        self.inner.on_job_submit(url, hold);
+    }
+
+    fn on_request_start(&self, method: &Method, path: &str) {
+        self.inner.on_request_start(method, path);
+    }
+
+    fn on_request_end(
+        &self,
+        method: &Method,
+        path: &str,
+        status: Option<StatusCode>,
+        elapsed: Duration,
+    ) {
+        let class = status.map_or(5, |s| (s.as_u16() / 100).clamp(1, 5) as usize - 1);
+        HTTP[class].fetch_add(1, Ordering::Relaxed);
+        self.inner.on_request_end(method, path, status, elapsed);
+    }
+
+    fn on_request_id(&self, method: &Method, path: &str, request_id: &str) {
+        self.inner.on_request_id(method, path, request_id);
+    }
+
+    fn on_retry(&self, attempt: u32, reason: &str) {
+        self.inner.on_retry(attempt, reason);
+    }
+
+    fn on_download_progress(&self, path: &str, received: u64, total: Option<u64>) {
+        self.inner.on_download_progress(path, received, total);
+    }
+
+    fn on_batch_progress(&self, done: usize, total: usize) {
+        self.inner.on_batch_progress(done, total);
+    }
+}
+
+/// Everything recorded so far, in the Prometheus text format
+fn render() -> String {
+    let mut out = String::new();
+    let mut metric = |name: &str, kind: &str, help: &str, samples: &[(String, String)]| {
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+        let _ = writeln!(out, "# TYPE {} {}", name, kind);
+        for (labels, value) in samples {
+            let _ = writeln!(out, "{}{} {}", name, labels, value);
+        }
+    };
+    let count = |counter: &AtomicU64| counter.load(Ordering::Relaxed).to_string();
+
+    metric(
+        "rustar_events_received_total",
+        "counter",
+        "Messages received from the broker",
+        &[(String::new(), count(&EVENTS))],
+    );
+    metric(
+        "rustar_reconnects_total",
+        "counter",
+        "Broker connections lost and retried",
+        &[(String::new(), count(&RECONNECTS))],
+    );
+    let http: Vec<(String, String)> = HTTP_CLASSES
+        .iter()
+        .zip(&HTTP)
+        .map(|(class, counter)| (format!("{{class=\"{}\"}}", class), count(counter)))
+        .collect();
+    metric(
+        "rustar_http_requests_total",
+        "counter",
+        "API requests by response status class (error: no response)",
+        &http,
+    );
+
+    let gauges = gauges();
+    let sample = |value: Option<f64>| {
+        value
+            .map(|value| vec![(String::new(), value.to_string())])
+            .unwrap_or_default()
+    };
+    metric(
+        "rustar_seconds_to_next_job",
+        "gauge",
+        "Seconds until the next scheduled job starts",
+        &sample(
+            gauges
+                .next_job
+                .map(|start| (start - clock::now()).num_seconds().max(0) as f64),
+        ),
+    );
+    metric(
+        "rustar_pass_elevation_degrees",
+        "gauge",
+        "Antenna elevation last reported by the station",
+        &sample(gauges.elevation),
+    );
+    out
+}
+
+/// Keep the next-job gauge current; failures leave the last value in place
+async fn track_next_job(client: ApiClient) {
+    loop {
+        if let Ok(jobs) = client.list_jobs().await {
+            let now = clock::now();
+            gauges().next_job = jobs
+                .iter()
+                .map(|job| job.job.start)
+                .filter(|start| *start > now)
+                .min();
+        }
+        tokio::time::sleep(NEXT_JOB_REFRESH).await;
+    }
+}
+
+/// A running metrics endpoint. Dropping it stops the listener and the next-job tracker,
+/// so the endpoint goes away with the command that started it.
+pub struct Exporter {
+    shutdown: Option<oneshot::Sender<()>>,
+    tracker: Option<JoinHandle<()>>,
+}
+
+impl Drop for Exporter {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        if let Some(tracker) = &self.tracker {
+            tracker.abort();
+        }
+    }
+}
+
+/// Serve `GET /metrics` on `address` while the returned exporter lives. A bind failure
+/// is a warning: the command runs on without metrics.
+pub async fn listen(address: SocketAddr, client: Option<ApiClient>) -> Option<Exporter> {
+    let listener = match tokio::net::TcpListener::bind(address).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!(
+                "⚠️ Metrics disabled: could not listen on {}: {}",
+                address, e
+            );
+            return None;
+        }
+    };
+    eprintln!("📈 Metrics on http://{}/metrics", address);
+
+    let (shutdown, stop) = oneshot::channel::<()>();
+    let app = Router::new().route("/metrics", get(|| async { render() }));
+    tokio::spawn(async move {
+        let _ = axum::serve(listener, app)
+            .with_graceful_shutdown(async {
+                let _ = stop.await;
+            })
+            .await;
+    });
+    Some(Exporter {
+        shutdown: Some(shutdown),
+        tracker: client.map(|client| tokio::spawn(track_next_job(client))),
+    })
+}
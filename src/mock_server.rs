@@ -0,0 +1,621 @@
+use crate::catalog;
+use crate::client::{
+    self, CatalogEntry, Identity, JobDTO, JobRequestDTO, SatelliteDTO, StationInfo,
+};
+use crate::clock;
+use crate::error::{CliError, ResultExt};
+use crate::satellite_id;
+use crate::schedule::TimeWindow;
+use crate::storage;
+use crate::tle;
+use crate::transcript;
+use crate::validation;
+use axum::extract::{Path as UrlPath, Query, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// Everything the mock remembers; written to `--state-file` after each change
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct MockState {
+    next_id: u64,
+    jobs: BTreeMap<u64, JobDTO>,
+    #[serde(default)]
+    satellites: BTreeMap<u32, SatelliteDTO>,
+    /// Cancelled jobs whose grace period ran out, answered with 410 Gone
+    #[serde(default)]
+    purged: BTreeSet<u64>,
+}
+
+/// How long a cancelled job can still be restored, as on the real server
+const PURGE_AFTER_HOURS: i64 = 24;
+
+impl MockState {
+    /// Drop the cancelled jobs whose grace period has run out
+    fn purge(&mut self) {
+        let cutoff = clock::now() - chrono::Duration::hours(PURGE_AFTER_HOURS);
+        let expired: Vec<u64> = self
+            .jobs
+            .iter()
+            .filter(|(_, job)| job.cancelled_at.is_some_and(|at| at <= cutoff))
+            .map(|(id, _)| *id)
+            .collect();
+        for id in expired {
+            self.jobs.remove(&id);
+            self.purged.insert(id);
+        }
+    }
+
+    /// The job `id` clashes with, among the ones that have not been cancelled
+    fn clash(&self, id: Option<u64>, window: TimeWindow) -> Option<(u64, &JobDTO)> {
+        self.jobs
+            .iter()
+            .find(|(other_id, other)| {
+                Some(**other_id) != id
+                    && !other.is_cancelled()
+                    && window.overlaps(&other.job.window())
+            })
+            .map(|(other_id, other)| (*other_id, other))
+    }
+
+    fn missing(&self, id: u64) -> Response {
+        if self.purged.contains(&id) {
+            error(StatusCode::GONE, format!("job {} has been purged", id))
+        } else {
+            error(StatusCode::NOT_FOUND, format!("job {} not found", id))
+        }
+    }
+}
+
+struct Shared {
+    state: Mutex<MockState>,
+    state_file: Option<PathBuf>,
+    started: Instant,
+    /// The station `GET /station` describes, when it is not the mock's own
+    station: Option<StationInfo>,
+    /// Added as the message of every answer to a change
+    note: Option<String>,
+}
+
+/// A station and schedule to serve in place of an empty mock, as `--demo` does
+pub struct Fixture {
+    pub station: StationInfo,
+    pub jobs: Vec<JobDTO>,
+    pub satellites: Vec<SatelliteDTO>,
+    /// Said in answer to every change, which lasts only as long as the server
+    pub note: String,
+}
+
+type AppState = Arc<Shared>;
+
+impl Shared {
+    /// Apply a change and persist it, answering with whatever the change produced
+    fn update<T>(&self, change: impl FnOnce(&mut MockState) -> T) -> T {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let result = change(&mut state);
+        if let Some(path) = &self.state_file {
+            let written = serde_json::to_string_pretty(&*state)
+                .map_err(|e| CliError::parse("mock server state", e))
+                .and_then(|contents| storage::write_atomic(path, contents));
+            if let Err(e) = written {
+                eprintln!("⚠️ {}", e.full_message());
+            }
+        }
+        result
+    }
+
+    fn read<T>(&self, read: impl FnOnce(&MockState) -> T) -> T {
+        read(&self.state.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+}
+
+impl Shared {
+    /// A success answer to a change, carrying the note when there is one
+    fn changed(&self, status: StatusCode, mut body: Value) -> Response {
+        if let Some(note) = &self.note {
+            body["message"] = json!(note);
+        }
+        (status, Json(body)).into_response()
+    }
+}
+
+fn error(status: StatusCode, message: impl Into<String>) -> Response {
+    (
+        status,
+        Json(json!({ "status": "error", "message": message.into() })),
+    )
+        .into_response()
+}
+
+/// A JSON body with an ETag, or 304 when the client already holds that version
+fn tagged<T: Serialize>(headers: &HeaderMap, value: &T) -> Response {
+    use std::hash::{Hash, Hasher};
+    let body = serde_json::to_string(value).unwrap_or_default();
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    body.hash(&mut hasher);
+    let etag = format!("\"{:016x}\"", hasher.finish());
+
+    let known = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.split(',').any(|tag| tag.trim() == etag));
+    if known {
+        return (StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response();
+    }
+    (
+        [
+            (header::ETAG, etag),
+            (header::CONTENT_TYPE, "application/json".to_string()),
+        ],
+        body,
+    )
+        .into_response()
+}
+
+async fn health() -> Json<serde_json::Value> {
+    Json(json!({ "status": "ok" }))
+}
+
+async fn station(State(app): State<AppState>) -> Json<StationInfo> {
+    let queue_depth = app.read(|s| s.jobs.len() as u64);
+    if let Some(station) = &app.station {
+        return Json(StationInfo {
+            uptime_seconds: Some(app.started.elapsed().as_secs()),
+            queue_depth: Some(queue_depth),
+            ..station.clone()
+        });
+    }
+    Json(StationInfo {
+        name: Some("rustar mock station".to_string()),
+        location: Some(json!({
+            "name": "Training ground",
+            "latitude": -34.6037,
+            "longitude": -58.3816,
+            "altitude_m": 25,
+        })),
+        antennas: vec![json!({ "name": "Mock UHF yagi" })],
+        azimuth: Some(0.0),
+        elevation: Some(90.0),
+        uptime_seconds: Some(app.started.elapsed().as_secs()),
+        queue_depth: Some(queue_depth),
+        disk_free_bytes: None,
+        polarizations: Some(vec!["RHCP".to_string(), "LHCP".to_string()]),
+        api_version: None,
+        scheduling_lead_seconds: None,
+        extra: BTreeMap::new(),
+    })
+}
+
+/// Everyone is the same operator: the mock server checks no credentials
+async fn whoami() -> Json<Identity> {
+    Json(Identity {
+        subject: Some("mock-operator".to_string()),
+        issuer: Some("rustar mock server".to_string()),
+        expires_at: None,
+        scopes: vec![
+            "jobs:read".to_string(),
+            "jobs:write".to_string(),
+            "catalog:write".to_string(),
+        ],
+        extra: BTreeMap::new(),
+    })
+}
+
+#[derive(Deserialize)]
+struct ListQuery {
+    #[serde(default)]
+    include_cancelled: bool,
+}
+
+/// Jobs in ID order; cancelled ones only with `?include_cancelled=true`
+async fn list_jobs(
+    State(app): State<AppState>,
+    Query(query): Query<ListQuery>,
+    headers: HeaderMap,
+) -> Response {
+    let jobs: Vec<JobDTO> = app.update(|s| {
+        s.purge();
+        s.jobs
+            .values()
+            .filter(|job| query.include_cancelled || !job.is_cancelled())
+            .cloned()
+            .collect()
+    });
+    tagged(&headers, &jobs)
+}
+
+async fn get_job(
+    State(app): State<AppState>,
+    UrlPath(id): UrlPath<u64>,
+    headers: HeaderMap,
+) -> Response {
+    app.update(|s| {
+        s.purge();
+        match s.jobs.get(&id) {
+            Some(job) => tagged(&headers, job),
+            None => s.missing(id),
+        }
+    })
+}
+
+/// Accept a job the way the station does: valid, in the future and not overlapping another
+async fn create_job(State(app): State<AppState>, body: String) -> Response {
+    let job: JobRequestDTO = match serde_json::from_str(&body) {
+        Ok(job) => job,
+        Err(e) => return error(StatusCode::UNPROCESSABLE_ENTITY, e.to_string()),
+    };
+    if let Some(problem) = validation::check_job(&job).into_iter().next() {
+        return error(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!("{}: {}", problem.field, problem.reason),
+        );
+    }
+    if job.end <= clock::now() {
+        return error(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!("window ends in the past ({})", job.end),
+        );
+    }
+
+    let app = &app;
+    app.update(|state| {
+        if let Some((other_id, other)) = state.clash(None, job.window()) {
+            return error(
+                StatusCode::CONFLICT,
+                format!(
+                    "window overlaps job {} ({} – {})",
+                    other_id, other.job.start, other.job.end
+                ),
+            );
+        }
+
+        state.next_id += 1;
+        let id = state.next_id;
+        state.jobs.insert(
+            id,
+            JobDTO {
+                id: Some(id.to_string()),
+                status: Some("scheduled".to_string()),
+                job,
+                cancelled_at: None,
+                cancel_reason: None,
+            },
+        );
+        app.changed(
+            StatusCode::CREATED,
+            json!({ "status": "created", "id": id }),
+        )
+    })
+}
+
+/// Change a job that has not started yet with a merge patch of its fields, a new window
+/// or anything else, under the same checks as a new one. Nested objects are replaced
+/// whole rather than merged; no client sends a part of one.
+async fn patch_job(
+    State(app): State<AppState>,
+    UrlPath(id): UrlPath<u64>,
+    Json(patch): Json<Value>,
+) -> Response {
+    let Value::Object(changes) = patch else {
+        return error(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "a merge patch must be a JSON object",
+        );
+    };
+    app.update(|state| {
+        let Some(stored) = state.jobs.get(&id).filter(|job| !job.is_cancelled()) else {
+            return state.missing(id);
+        };
+        if stored.job.start <= clock::now() {
+            return error(
+                StatusCode::CONFLICT,
+                format!("job {} has already started", id),
+            );
+        }
+        let mut fields = match serde_json::to_value(&stored.job) {
+            Ok(Value::Object(fields)) => fields,
+            _ => return error(StatusCode::INTERNAL_SERVER_ERROR, "job is not an object"),
+        };
+        for (key, change) in changes {
+            match change {
+                Value::Null => fields.remove(&key),
+                change => fields.insert(key, change),
+            };
+        }
+        let job: JobRequestDTO = match serde_json::from_value(Value::Object(fields)) {
+            Ok(job) => job,
+            Err(e) => return error(StatusCode::UNPROCESSABLE_ENTITY, e.to_string()),
+        };
+        if let Some(problem) = validation::check_job(&job).into_iter().next() {
+            return error(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                format!("{}: {}", problem.field, problem.reason),
+            );
+        }
+        if let Some((other_id, other)) = state.clash(Some(id), job.window()) {
+            return error(
+                StatusCode::CONFLICT,
+                format!(
+                    "window overlaps job {} ({} – {})",
+                    other_id, other.job.start, other.job.end
+                ),
+            );
+        }
+
+        if let Some(stored) = state.jobs.get_mut(&id) {
+            stored.job = job;
+        }
+        app.changed(StatusCode::OK, json!({ "status": "updated" }))
+    })
+}
+
+/// Cancel softly, as the real server does: the job is kept, marked cancelled, until
+/// it is purged a day later. Takes an optional `{"reason", "reason_text"}` body and
+/// echoes the reason back.
+async fn delete_job(
+    State(app): State<AppState>,
+    UrlPath(id): UrlPath<u64>,
+    body: Option<Json<Value>>,
+) -> Response {
+    let reason = body.and_then(|Json(body)| {
+        body.get("reason")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    });
+    app.update(|state| {
+        state.purge();
+        let Some(stored) = state.jobs.get_mut(&id).filter(|job| !job.is_cancelled()) else {
+            return state.missing(id);
+        };
+        stored.status = Some(client::CANCELLED.to_string());
+        stored.cancelled_at = Some(clock::now());
+        stored.cancel_reason = reason.clone();
+        match reason {
+            Some(reason) if app.note.is_none() => {
+                Json(json!({ "status": "cancelled", "message": format!("reason: {}", reason) }))
+                    .into_response()
+            }
+            _ => app.changed(StatusCode::OK, json!({ "status": "cancelled" })),
+        }
+    })
+}
+
+/// Bring back a cancelled job, unless another job has taken its window since
+async fn restore_job(State(app): State<AppState>, UrlPath(id): UrlPath<u64>) -> Response {
+    app.update(|state| {
+        state.purge();
+        let Some(stored) = state.jobs.get(&id) else {
+            return state.missing(id);
+        };
+        if !stored.is_cancelled() {
+            return error(StatusCode::CONFLICT, format!("job {} is not cancelled", id));
+        }
+        if let Some((other_id, other)) = state.clash(Some(id), stored.job.window()) {
+            return error(
+                StatusCode::CONFLICT,
+                format!(
+                    "window overlaps job {} ({} – {})",
+                    other_id, other.job.start, other.job.end
+                ),
+            );
+        }
+        if let Some(stored) = state.jobs.get_mut(&id) {
+            stored.status = Some("scheduled".to_string());
+            stored.cancelled_at = None;
+            stored.cancel_reason = None;
+        }
+        app.changed(StatusCode::OK, json!({ "status": "restored" }))
+    })
+}
+
+async fn create_satellite(
+    State(app): State<AppState>,
+    Json(mut entry): Json<SatelliteDTO>,
+) -> Response {
+    app.update(|state| {
+        if state.satellites.contains_key(&entry.norad_id) {
+            return error(
+                StatusCode::CONFLICT,
+                format!("NORAD {} is already in the catalog", entry.norad_id),
+            );
+        }
+        entry.updated_at = Some(clock::now());
+        state.satellites.insert(entry.norad_id, entry);
+        app.changed(StatusCode::CREATED, json!({ "status": "created" }))
+    })
+}
+
+async fn update_satellite(
+    State(app): State<AppState>,
+    UrlPath(norad_id): UrlPath<u32>,
+    Json(mut entry): Json<SatelliteDTO>,
+) -> Response {
+    app.update(|state| {
+        let Some(stored) = state.satellites.get(&norad_id) else {
+            return error(
+                StatusCode::NOT_FOUND,
+                format!("NORAD {} is not in the catalog", norad_id),
+            );
+        };
+        if crate::tle::epoch(&entry.tle.tle1) < crate::tle::epoch(&stored.tle.tle1) {
+            return error(
+                StatusCode::CONFLICT,
+                "the stored elements have a newer epoch",
+            );
+        }
+        entry.norad_id = norad_id;
+        entry.updated_at = Some(clock::now());
+        state.satellites.insert(norad_id, entry);
+        app.changed(StatusCode::OK, json!({ "status": "updated" }))
+    })
+}
+
+#[derive(Deserialize)]
+struct CatalogQuery {
+    #[serde(default)]
+    q: String,
+    limit: Option<usize>,
+}
+
+/// Stored satellites whose name, NORAD ID or designator matches `q`; the mock keeps no default frequencies
+async fn search_satellites(
+    State(app): State<AppState>,
+    Query(query): Query<CatalogQuery>,
+) -> Response {
+    let q = query.q.trim();
+    let found: Vec<CatalogEntry> = app.read(|s| {
+        s.satellites
+            .values()
+            .filter(|entry| {
+                catalog::matches(q, &entry.tle.tle0)
+                    || entry.norad_id.to_string().starts_with(q)
+                    || tle::intl_designator(&entry.tle.tle1).is_some_and(|d| {
+                        satellite_id::parse_designator(q).is_some_and(|wanted| d == wanted)
+                    })
+            })
+            .take(query.limit.unwrap_or(catalog::SEARCH_LIMIT))
+            .map(|entry| CatalogEntry {
+                norad_id: entry.norad_id,
+                name: entry.tle.tle0.clone(),
+                tle1: Some(entry.tle.tle1.clone()),
+                tle2: Some(entry.tle.tle2.clone()),
+                rx_frequency: None,
+                tx_frequency: None,
+                mode: None,
+                baud: None,
+            })
+            .collect()
+    });
+    Json(found).into_response()
+}
+
+async fn get_satellite(State(app): State<AppState>, UrlPath(norad_id): UrlPath<u32>) -> Response {
+    match app.read(|s| s.satellites.get(&norad_id).cloned()) {
+        Some(entry) => Json(entry).into_response(),
+        None => error(
+            StatusCode::NOT_FOUND,
+            format!("NORAD {} is not in the catalog", norad_id),
+        ),
+    }
+}
+
+/// A stored satellite's elements as the three lines a TLE source sends, so `tle_source`
+/// can point at the mock (`…/elements/{norad}`)
+async fn elements(State(app): State<AppState>, UrlPath(norad_id): UrlPath<u32>) -> Response {
+    match app.read(|s| s.satellites.get(&norad_id).cloned()) {
+        Some(entry) => format!(
+            "{}\n{}\n{}\n",
+            entry.tle.tle0, entry.tle.tle1, entry.tle.tle2
+        )
+        .into_response(),
+        None => (StatusCode::NOT_FOUND, "No GP data found").into_response(),
+    }
+}
+
+/// The mock API, with state loaded from `state_file` when it exists
+pub fn router(state_file: Option<PathBuf>) -> Result<Router, CliError> {
+    let state = match &state_file {
+        Some(path) if path.exists() => {
+            let contents = std::fs::read_to_string(path)
+                .with_context(|| format!("reading {}", path.display()))?;
+            serde_json::from_str(&contents)
+                .map_err(|e| CliError::parse(format!("mock server state {}", path.display()), e))?
+        }
+        _ => MockState::default(),
+    };
+
+    Ok(routes(Shared {
+        state: Mutex::new(state),
+        state_file,
+        started: Instant::now(),
+        station: None,
+        note: None,
+    }))
+}
+
+/// The mock API serving `fixture`, kept in memory only
+pub fn demo(fixture: Fixture) -> Router {
+    let state = MockState {
+        next_id: fixture
+            .jobs
+            .iter()
+            .filter_map(|job| job.id.as_deref()?.parse().ok())
+            .max()
+            .unwrap_or(0),
+        jobs: fixture
+            .jobs
+            .into_iter()
+            .filter_map(|job| Some((job.id.as_deref()?.parse().ok()?, job)))
+            .collect(),
+        satellites: fixture
+            .satellites
+            .into_iter()
+            .map(|entry| (entry.norad_id, entry))
+            .collect(),
+        purged: BTreeSet::new(),
+    };
+    routes(Shared {
+        state: Mutex::new(state),
+        state_file: None,
+        started: Instant::now(),
+        station: Some(fixture.station),
+        note: Some(fixture.note),
+    })
+}
+
+fn routes(shared: Shared) -> Router {
+    Router::new()
+        .route("/health", get(health))
+        .route("/station", get(station))
+        .route("/auth/me", get(whoami))
+        .route("/jobs", get(list_jobs).post(create_job))
+        .route(
+            "/jobs/{id}",
+            get(get_job).patch(patch_job).delete(delete_job),
+        )
+        .route("/jobs/{id}/restore", post(restore_job))
+        .route("/satellites", get(search_satellites).post(create_satellite))
+        .route("/satellites/{id}", get(get_satellite).put(update_satellite))
+        .route("/elements/{id}", get(elements))
+        .with_state(Arc::new(shared))
+}
+
+/// Serve the mock API on an already-bound listener until the process stops
+pub async fn serve(
+    listener: tokio::net::TcpListener,
+    state_file: Option<PathBuf>,
+) -> Result<(), CliError> {
+    let app = router(state_file)?;
+    transcript::stops_on_interrupt();
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await
+        .context("running the mock server")
+}
+
+/// Bind `127.0.0.1:port` and serve the mock API until Ctrl-C
+pub async fn run(port: u16, state_file: Option<PathBuf>) -> Result<(), CliError> {
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", port))
+        .await
+        .with_context(|| format!("binding port {}", port))?;
+    let address = listener.local_addr().context("reading the bound address")?;
+
+    println!("🧪 Mock ground station listening on http://{}", address);
+    if let Some(path) = &state_file {
+        println!("   State is kept in {}", path.display());
+    }
+    println!(
+        "   Point the CLI at it with `rustar-cli --base-url http://{} …` — Ctrl-C to stop",
+        address
+    );
+    serve(listener, state_file).await
+}
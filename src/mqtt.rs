@@ -0,0 +1,299 @@
+use crate::clock;
+use crate::error::CliError;
+use crate::metrics;
+use crate::reproducible;
+use crate::signal::SignalView;
+use crate::transcript;
+use crate::OutputFormat;
+use rumqttc::{AsyncClient, Event, MqttOptions, Packet, QoS, Transport};
+use serde::{Deserialize, Serialize};
+use std::io::IsTerminal;
+use std::time::Duration;
+
+/// Broker settings from the profile's `[mqtt]` table
+#[derive(Debug, Clone, Deserialize)]
+pub struct MqttSettings {
+    pub host: String,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    #[serde(default)]
+    pub tls: bool,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Prefix under which the station publishes its `job/` and `status/` topics
+    #[serde(default = "default_prefix")]
+    pub topic_prefix: String,
+}
+
+fn default_port() -> u16 {
+    1883
+}
+
+fn default_prefix() -> String {
+    "rustar".to_string()
+}
+
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Whether a message concerns the given job, judged by topic segment or payload field
+fn matches_job(topic: &str, payload: Option<&serde_json::Value>, job_id: &str) -> bool {
+    if topic.split('/').any(|segment| segment == job_id) {
+        return true;
+    }
+
+    payload
+        .and_then(|p| p.get("job_id").or_else(|| p.get("id")))
+        .map(|id| match id {
+            serde_json::Value::String(s) => s == job_id,
+            other => other.as_u64().is_some_and(|n| n.to_string() == job_id),
+        })
+        .unwrap_or(false)
+}
+
+/// Kind of event a message carries, for the `type` field of `--output ndjson` records
+fn classify(topic: &str, payload: Option<&serde_json::Value>) -> &'static str {
+    let has = |field: &str| payload.is_some_and(|p| p.get(field).is_some());
+    if topic.split('/').any(|segment| segment == "frame") || has("frame") {
+        "frame"
+    } else if (has("azimuth") && has("elevation")) || (has("az") && has("el")) {
+        "sample"
+    } else if has("status") || topic.split('/').any(|segment| segment == "status") {
+        "status"
+    } else {
+        "message"
+    }
+}
+
+/// States after which a job publishes nothing more
+fn is_terminal(status: &str) -> bool {
+    matches!(
+        status.to_ascii_lowercase().as_str(),
+        "completed" | "done" | "failed" | "cancelled" | "canceled" | "aborted"
+    )
+}
+
+/// One `--output ndjson` record, `{"ts", "type", "payload"}`, written and flushed at once
+/// so a pipe sees each event as it happens
+fn emit(kind: &str, payload: serde_json::Value) {
+    use std::io::Write;
+    #[derive(Serialize)]
+    struct Record<'a> {
+        ts: String,
+        #[serde(rename = "type")]
+        kind: &'a str,
+        payload: serde_json::Value,
+    }
+    let record = Record {
+        ts: clock::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+        kind,
+        payload,
+    };
+    let mut stdout = std::io::stdout().lock();
+    let _ = writeln!(
+        stdout,
+        "{}",
+        serde_json::to_string(&record).unwrap_or_default()
+    );
+    let _ = stdout.flush();
+}
+
+/// Print over the live signal line when there is one, so it stays at the bottom
+fn above(view: &mut Option<SignalView>, print: impl FnOnce()) {
+    match view {
+        Some(view) => view.above(print),
+        None => print(),
+    }
+}
+
+fn print_message(
+    topic: &str,
+    payload: &[u8],
+    json: Option<&serde_json::Value>,
+    output: OutputFormat,
+) {
+    match output {
+        OutputFormat::Json => println!("{}", String::from_utf8_lossy(payload)),
+        OutputFormat::Ndjson => emit(
+            classify(topic, json),
+            serde_json::json!({
+                "topic": topic,
+                "message": json
+                    .cloned()
+                    .unwrap_or_else(|| String::from_utf8_lossy(payload).into()),
+            }),
+        ),
+        OutputFormat::Text | OutputFormat::Csv => {
+            let timestamp = clock::now().format("%H:%M:%S%.3fZ");
+            match json {
+                Some(value) => println!(
+                    "[{}] {}\n{}",
+                    timestamp,
+                    topic,
+                    serde_json::to_string_pretty(value).unwrap_or_default()
+                ),
+                None => println!(
+                    "[{}] {} {}",
+                    timestamp,
+                    topic,
+                    String::from_utf8_lossy(payload)
+                ),
+            }
+        }
+    }
+}
+
+/// Subscribe to the station's job and status topics and print messages until Ctrl-C.
+/// Broker disconnects are retried with exponential backoff.
+/// With `--output ndjson`, connections, reconnects and the end of following are records too.
+pub async fn follow(
+    settings: &MqttSettings,
+    prefix: Option<&str>,
+    job_id: Option<&str>,
+    output: OutputFormat,
+    ascii: bool,
+) -> Result<(), CliError> {
+    transcript::stops_on_interrupt();
+    let prefix = prefix
+        .unwrap_or(&settings.topic_prefix)
+        .trim_end_matches('/');
+    let topics = [format!("{}/job/#", prefix), format!("{}/status/#", prefix)];
+
+    let client_id = format!("rustar-cli-{}", std::process::id());
+    let mut options = MqttOptions::new(client_id, &settings.host, settings.port);
+    options.set_keep_alive(Duration::from_secs(30));
+    if let Some(username) = &settings.username {
+        options.set_credentials(username, settings.password.as_deref().unwrap_or(""));
+    }
+    if settings.tls {
+        options.set_transport(Transport::tls_with_default_config());
+    }
+
+    let (client, mut eventloop) = AsyncClient::new(options, 16);
+    eprintln!(
+        "📡 Following {} on {}:{} (Ctrl-C to stop)",
+        topics.join(", "),
+        settings.host,
+        settings.port
+    );
+
+    let ndjson = output == OutputFormat::Ndjson;
+    // Last status the followed messages reported, for the closing record
+    let mut last_status: Option<String> = None;
+    let end = |last_status: &Option<String>| {
+        if ndjson {
+            emit(
+                "end",
+                serde_json::json!({
+                    "reason": "interrupted",
+                    "last_status": last_status,
+                    "terminal": last_status.as_deref().is_some_and(is_terminal),
+                }),
+            );
+        }
+    };
+
+    // Only a person watching text output gets the live line
+    let mut view = (output == OutputFormat::Text
+        && reproducible::live(std::io::stdout().is_terminal()))
+    .then(|| SignalView::new(ascii));
+
+    let mut backoff = Duration::from_secs(1);
+    loop {
+        let event = tokio::select! {
+            _ = tokio::signal::ctrl_c() => {
+                // Finish the live line and show the cursor before anything else is printed
+                drop(view.take());
+                eprintln!("\n👋 Disconnecting");
+                let _ = client.disconnect().await;
+                end(&last_status);
+                return Ok(());
+            }
+            event = eventloop.poll() => event,
+        };
+
+        match event {
+            Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                backoff = Duration::from_secs(1);
+                if ndjson {
+                    emit(
+                        "connected",
+                        serde_json::json!({
+                            "host": settings.host,
+                            "port": settings.port,
+                            "topics": topics,
+                        }),
+                    );
+                }
+                // Subscriptions do not survive a clean-session reconnect
+                for topic in &topics {
+                    client
+                        .subscribe(topic.as_str(), QoS::AtLeastOnce)
+                        .await
+                        .map_err(|e| {
+                            CliError::Configuration(format!("MQTT subscribe failed: {}", e))
+                        })?;
+                }
+            }
+            Ok(Event::Incoming(Packet::Publish(publish))) => {
+                metrics::event();
+                let json = serde_json::from_slice::<serde_json::Value>(&publish.payload).ok();
+                if let Some(job_id) = job_id {
+                    if !matches_job(&publish.topic, json.as_ref(), job_id) {
+                        continue;
+                    }
+                }
+                if let Some(elevation) = json
+                    .as_ref()
+                    .and_then(|p| p.get("elevation").or_else(|| p.get("el")))
+                    .and_then(serde_json::Value::as_f64)
+                {
+                    metrics::set_elevation(elevation);
+                }
+                if let Some(status) = json
+                    .as_ref()
+                    .and_then(|p| p.get("status"))
+                    .and_then(|s| s.as_str())
+                {
+                    last_status = Some(status.to_string());
+                }
+                let shown = match (&mut view, &json) {
+                    (Some(view), Some(payload)) => view.update(payload),
+                    _ => false,
+                };
+                if !shown {
+                    above(&mut view, || {
+                        print_message(&publish.topic, &publish.payload, json.as_ref(), output)
+                    });
+                }
+            }
+            Ok(_) => {}
+            Err(e) => {
+                metrics::reconnect();
+                above(&mut view, || {
+                    eprintln!(
+                        "⚠️ Broker connection lost ({}); reconnecting in {}s",
+                        e,
+                        backoff.as_secs()
+                    )
+                });
+                if ndjson {
+                    emit(
+                        "reconnecting",
+                        serde_json::json!({
+                            "error": e.to_string(),
+                            "retry_in_seconds": backoff.as_secs(),
+                        }),
+                    );
+                }
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {
+                        end(&last_status);
+                        return Ok(());
+                    }
+                    _ = tokio::time::sleep(backoff) => {}
+                }
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            }
+        }
+    }
+}
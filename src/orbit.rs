@@ -0,0 +1,265 @@
+use crate::client::TleData;
+use crate::error::CliError;
+use crate::location::Location;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// WGS84 equatorial radius in km and flattening
+const EARTH_RADIUS_KM: f64 = 6378.137;
+const EARTH_FLATTENING: f64 = 1.0 / 298.257_223_563;
+/// Earth rotation rate in rad/s
+const EARTH_ROTATION: f64 = 7.292_115e-5;
+const SPEED_OF_LIGHT_KM_S: f64 = 299_792.458;
+
+/// Where the satellite appears from the station at one instant
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct LookAngle {
+    pub at: DateTime<Utc>,
+    /// Degrees clockwise from true north
+    pub azimuth_deg: f64,
+    /// Degrees above the horizon
+    pub elevation_deg: f64,
+    pub range_km: f64,
+    /// Positive while the satellite moves away
+    pub range_rate_km_s: f64,
+}
+
+impl LookAngle {
+    /// Frequency heard at the station for a downlink sent at `hz`
+    pub fn downlink(&self, hz: f64) -> f64 {
+        hz * (1.0 - self.range_rate_km_s / SPEED_OF_LIGHT_KM_S)
+    }
+
+    /// Frequency to transmit for the satellite to hear `hz`
+    pub fn uplink(&self, hz: f64) -> f64 {
+        hz / (1.0 - self.range_rate_km_s / SPEED_OF_LIGHT_KM_S)
+    }
+}
+
+/// Where a second element set puts the satellite relative to the first at one instant,
+/// in the first one's radial / along-track / cross-track frame, km
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Separation {
+    pub at: DateTime<Utc>,
+    pub total_km: f64,
+    /// Positive when the second is ahead
+    pub along_track_km: f64,
+    /// Positive when the second is higher
+    pub radial_km: f64,
+    pub cross_track_km: f64,
+    /// How long the satellite takes to cover the along-track gap
+    pub along_track_s: f64,
+}
+
+/// An element set ready for SGP4 propagation
+pub struct Satellite {
+    pub name: String,
+    pub tle: TleData,
+    elements: sgp4::Elements,
+    constants: sgp4::Constants,
+}
+
+type Vector = [f64; 3];
+
+fn dot(a: Vector, b: Vector) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+/// Station position in Earth-fixed coordinates, km
+fn station_ecef(station: &Location) -> Vector {
+    let (lat, lon) = (
+        station.latitude.to_radians(),
+        station.longitude.to_radians(),
+    );
+    let e2 = EARTH_FLATTENING * (2.0 - EARTH_FLATTENING);
+    let n = EARTH_RADIUS_KM / (1.0 - e2 * lat.sin().powi(2)).sqrt();
+    let h = station.altitude_m / 1000.0;
+    [
+        (n + h) * lat.cos() * lon.cos(),
+        (n + h) * lat.cos() * lon.sin(),
+        (n * (1.0 - e2) + h) * lat.sin(),
+    ]
+}
+
+impl Satellite {
+    pub fn from_tle(tle: &TleData) -> Result<Self, CliError> {
+        let elements = sgp4::Elements::from_tle(
+            Some(tle.tle0.clone()),
+            tle.tle1.as_bytes(),
+            tle.tle2.as_bytes(),
+        )
+        .map_err(|e| CliError::parse(format!("element set for {}", tle.tle0), e.to_string()))?;
+        let constants = sgp4::Constants::from_elements(&elements)
+            .map_err(|e| CliError::parse(format!("orbit of {}", tle.tle0), e.to_string()))?;
+        Ok(Self {
+            name: tle.tle0.clone(),
+            tle: tle.clone(),
+            elements,
+            constants,
+        })
+    }
+
+    pub fn epoch(&self) -> DateTime<Utc> {
+        self.elements.datetime.and_utc()
+    }
+
+    /// Position (km) and velocity (km/s) in the TEME frame at `at`
+    fn teme(&self, at: DateTime<Utc>) -> Result<(Vector, Vector), CliError> {
+        let minutes = (at - self.epoch()).num_milliseconds() as f64 / 60_000.0;
+        let prediction = self
+            .constants
+            .propagate(sgp4::MinutesSinceEpoch(minutes))
+            .map_err(|e| {
+                CliError::validation(
+                    "tle",
+                    format!("{} cannot be propagated to {}: {}", self.name, at, e),
+                )
+            })?;
+        Ok((prediction.position, prediction.velocity))
+    }
+
+    /// Azimuth, elevation, range and range rate as seen from `station`
+    pub fn look_angle(&self, station: &Location, at: DateTime<Utc>) -> Result<LookAngle, CliError> {
+        let (position, velocity) = self.teme(at)?;
+        let (position, velocity) = earth_fixed(at, position, velocity);
+        Ok(topocentric(station, at, position, velocity))
+    }
+
+    /// The mean elements as parsed from the TLE
+    pub fn elements(&self) -> &sgp4::Elements {
+        &self.elements
+    }
+
+    /// Where `other` puts the satellite at `at`, relative to where this element set does
+    pub fn separation(&self, other: &Satellite, at: DateTime<Utc>) -> Result<Separation, CliError> {
+        let (position, velocity) = self.teme(at)?;
+        let (theirs, _) = other.teme(at)?;
+        let offset = [
+            theirs[0] - position[0],
+            theirs[1] - position[1],
+            theirs[2] - position[2],
+        ];
+        let unit = |v: Vector| {
+            let length = dot(v, v).sqrt();
+            [v[0] / length, v[1] / length, v[2] / length]
+        };
+        let radial = unit(position);
+        let normal = unit([
+            position[1] * velocity[2] - position[2] * velocity[1],
+            position[2] * velocity[0] - position[0] * velocity[2],
+            position[0] * velocity[1] - position[1] * velocity[0],
+        ]);
+        let along = [
+            normal[1] * radial[2] - normal[2] * radial[1],
+            normal[2] * radial[0] - normal[0] * radial[2],
+            normal[0] * radial[1] - normal[1] * radial[0],
+        ];
+        let along_track_km = dot(offset, along);
+        Ok(Separation {
+            at,
+            total_km: dot(offset, offset).sqrt(),
+            along_track_km,
+            radial_km: dot(offset, radial),
+            cross_track_km: dot(offset, normal),
+            along_track_s: along_track_km / dot(velocity, velocity).sqrt(),
+        })
+    }
+
+    /// Whether the Earth hides the Sun from the satellite, using a cylindrical shadow
+    pub fn in_shadow(&self, at: DateTime<Utc>) -> Result<bool, CliError> {
+        let (position, _) = self.teme(at)?;
+        let sun = sun::direction(at);
+        let along = dot(position, sun);
+        let across = dot(position, position) - along * along;
+        Ok(along < 0.0 && across.sqrt() < EARTH_RADIUS_KM)
+    }
+}
+
+/// TEME to Earth-fixed: rotate by sidereal time and remove the Earth's rotation
+fn earth_fixed(at: DateTime<Utc>, position: Vector, velocity: Vector) -> (Vector, Vector) {
+    let theta = sgp4::iau_epoch_to_sidereal_time(sgp4::julian_years_since_j2000(&at.naive_utc()));
+    let (sin, cos) = theta.sin_cos();
+    let [x, y, z] = position;
+    let [vx, vy, vz] = velocity;
+    let position = [cos * x + sin * y, -sin * x + cos * y, z];
+    let velocity = [
+        cos * vx + sin * vy + EARTH_ROTATION * position[1],
+        -sin * vx + cos * vy - EARTH_ROTATION * position[0],
+        vz,
+    ];
+    (position, velocity)
+}
+
+/// Look angle of an Earth-fixed position and velocity from `station`
+fn topocentric(
+    station: &Location,
+    at: DateTime<Utc>,
+    position: Vector,
+    velocity: Vector,
+) -> LookAngle {
+    let site = station_ecef(station);
+    let rho = [
+        position[0] - site[0],
+        position[1] - site[1],
+        position[2] - site[2],
+    ];
+
+    let (lat, lon) = (
+        station.latitude.to_radians(),
+        station.longitude.to_radians(),
+    );
+    let south = dot(
+        rho,
+        [lat.sin() * lon.cos(), lat.sin() * lon.sin(), -lat.cos()],
+    );
+    let east = dot(rho, [-lon.sin(), lon.cos(), 0.0]);
+    let up = dot(
+        rho,
+        [lat.cos() * lon.cos(), lat.cos() * lon.sin(), lat.sin()],
+    );
+
+    let range_km = dot(rho, rho).sqrt();
+    LookAngle {
+        at,
+        azimuth_deg: east.atan2(-south).to_degrees().rem_euclid(360.0),
+        elevation_deg: (up / range_km).asin().to_degrees(),
+        range_km,
+        range_rate_km_s: dot(rho, velocity) / range_km,
+    }
+}
+
+/// Elevation of the Sun's centre above the station's horizon, in degrees
+pub fn sun_elevation(station: &Location, at: DateTime<Utc>) -> f64 {
+    let sun = sun::direction(at);
+    let far = [
+        sun[0] * sun::ASTRONOMICAL_UNIT_KM,
+        sun[1] * sun::ASTRONOMICAL_UNIT_KM,
+        sun[2] * sun::ASTRONOMICAL_UNIT_KM,
+    ];
+    let (position, velocity) = earth_fixed(at, far, [0.0; 3]);
+    topocentric(station, at, position, velocity).elevation_deg
+}
+
+/// Low-precision solar position from the Astronomical Almanac, good to about 0.01°
+mod sun {
+    use super::Vector;
+    use chrono::{DateTime, TimeZone, Utc};
+
+    pub const ASTRONOMICAL_UNIT_KM: f64 = 149_597_870.7;
+
+    /// Unit vector towards the Sun in the equatorial frame of date
+    pub fn direction(at: DateTime<Utc>) -> Vector {
+        let j2000 = Utc.with_ymd_and_hms(2000, 1, 1, 12, 0, 0).unwrap();
+        let days = (at - j2000).num_milliseconds() as f64 / 86_400_000.0;
+        let mean_longitude = (280.460 + 0.985_647_4 * days).to_radians();
+        let anomaly = (357.528 + 0.985_600_3 * days).to_radians();
+        let longitude =
+            mean_longitude + (1.915 * anomaly.sin() + 0.020 * (2.0 * anomaly).sin()).to_radians();
+        let obliquity = (23.439 - 0.000_000_4 * days).to_radians();
+        [
+            longitude.cos(),
+            obliquity.cos() * longitude.sin(),
+            obliquity.sin() * longitude.sin(),
+        ]
+    }
+}
@@ -0,0 +1,77 @@
+use crate::error::{CliError, ResultExt};
+use crate::storage;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Where a command's document goes. Status text always goes to stderr,
+/// so stdout carries nothing but the document.
+#[derive(Debug, Clone)]
+pub enum OutputTarget {
+    Stdout,
+    File { path: PathBuf, force: bool },
+}
+
+impl OutputTarget {
+    /// No path, or "-", means stdout
+    pub fn new(path: Option<PathBuf>, force: bool) -> Self {
+        match path {
+            Some(path) if path != Path::new("-") => OutputTarget::File { path, force },
+            _ => OutputTarget::Stdout,
+        }
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            OutputTarget::Stdout => None,
+            OutputTarget::File { path, .. } => Some(path),
+        }
+    }
+
+    /// Refuse to replace an existing file without `--force`; call before doing the work
+    pub fn check(&self) -> Result<(), CliError> {
+        match self {
+            OutputTarget::File { path, force: false } if path.exists() => {
+                Err(CliError::validation(
+                    "output file",
+                    format!(
+                        "{} already exists (pass --force to overwrite it)",
+                        path.display()
+                    ),
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Write the whole document, reporting its size on stderr when it went to a file.
+    /// Replaces the file unconditionally; `check` is what enforces `--force`.
+    pub fn write(&self, contents: impl AsRef<[u8]>) -> Result<(), CliError> {
+        let contents = contents.as_ref();
+        match self {
+            OutputTarget::Stdout => {
+                let mut stdout = std::io::stdout().lock();
+                stdout
+                    .write_all(contents)
+                    .and_then(|()| stdout.flush())
+                    .context("writing to stdout")
+            }
+            OutputTarget::File { path, .. } => {
+                storage::write_atomic(path, contents)?;
+                eprintln!("💾 Wrote {} bytes to {}", contents.len(), path.display());
+                Ok(())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable output
+    Text,
+    /// Machine-readable JSON
+    Json,
+    /// Comma-separated values, for tabular commands
+    Csv,
+    /// One compact JSON object per line, for long lists piped into jq
+    Ndjson,
+}
@@ -0,0 +1,301 @@
+use crate::clock;
+use crate::error::CliError;
+use crate::examples::Example;
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+
+/// Date part of every datetime input, as a chrono format and as shown to people
+pub const DATE_FORMAT: (&str, &str) = ("%Y-%m-%d", "YYYY-MM-DD");
+/// Accepted times of day, tried in order; seconds may carry any fraction
+pub const TIME_FORMATS: &[(&str, &str)] = &[("%H:%M", "HH:MM"), ("%H:%M:%S%.f", "HH:MM:SS[.fff]")];
+/// What may join the date and the time in a single-field datetime
+pub const DATETIME_SEPARATORS: [char; 2] = [' ', 'T'];
+/// Duration units, their length in milliseconds and what they are called
+pub const DURATION_UNITS: &[(&str, i64, &str)] = &[
+    ("ms", 1, "milliseconds"),
+    ("s", 1_000, "seconds"),
+    ("m", 60_000, "minutes"),
+    ("h", 3_600_000, "hours"),
+    ("d", 86_400_000, "days"),
+];
+
+/// Split a trailing UTC offset ("Z", "+05:30", "-0300") off a time of day; none means UTC
+fn split_offset(time_str: &str) -> Result<(&str, FixedOffset), CliError> {
+    let utc = FixedOffset::east_opt(0).expect("zero is a valid offset");
+    if let Some(time) = time_str
+        .strip_suffix('Z')
+        .or_else(|| time_str.strip_suffix('z'))
+    {
+        return Ok((time.trim_end(), utc));
+    }
+    let Some(at) = time_str.rfind(['+', '-']) else {
+        return Ok((time_str, utc));
+    };
+    let (time, offset) = time_str.split_at(at);
+    let offset = offset.parse::<FixedOffset>().map_err(|e| {
+        CliError::parse(format!("UTC offset '{}' (expected Z or ±HH:MM)", offset), e)
+    })?;
+    Ok((time.trim_end(), offset))
+}
+
+/// Parse user-friendly date/time format to a UTC DateTime. The time may end in a UTC
+/// offset and carry fractional seconds; both are honoured, not dropped.
+pub fn user_datetime(date_str: &str, time_str: &str) -> Result<DateTime<Utc>, CliError> {
+    let (date_format, date_shown) = DATE_FORMAT;
+    let date = NaiveDate::parse_from_str(date_str, date_format).map_err(|e| {
+        CliError::parse(format!("date '{}' (expected {})", date_str, date_shown), e)
+    })?;
+
+    let (time_str, offset) = split_offset(time_str)?;
+    let time = TIME_FORMATS
+        .iter()
+        .find_map(|(format, _)| NaiveTime::parse_from_str(time_str, format).ok())
+        .ok_or_else(|| {
+            let shown: Vec<&str> = TIME_FORMATS.iter().map(|(_, shown)| *shown).collect();
+            CliError::parse(
+                format!("time '{}'", time_str),
+                format!(
+                    "expected {}, optionally followed by Z or ±HH:MM",
+                    shown.join(" or ")
+                ),
+            )
+        })?;
+
+    let local = NaiveDateTime::new(date, time);
+    let datetime = offset
+        .from_local_datetime(&local)
+        .single()
+        .expect("a fixed offset maps every local time to one instant");
+    Ok(datetime.with_timezone(&Utc))
+}
+
+/// `--at`: "now", "now" plus or minus a duration ("now+24h"), or a single-field datetime
+pub fn instant(input: &str) -> Result<DateTime<Utc>, CliError> {
+    let input = input.trim();
+    let Some(offset) = input.strip_prefix("now") else {
+        return datetime(input);
+    };
+    let now = clock::now();
+    match offset.trim_start().split_at_checked(1) {
+        None => Ok(now),
+        Some(("+", span)) => Ok(now + duration(span)?),
+        Some(("-", span)) => Ok(now - duration(span)?),
+        Some(_) => Err(CliError::parse(
+            format!("time '{}'", input),
+            "expected now, now+DURATION or now-DURATION",
+        )),
+    }
+}
+
+/// Parse a single-field datetime: RFC 3339, or the prompt formats joined by a space or 'T'
+pub fn datetime(input: &str) -> Result<DateTime<Utc>, CliError> {
+    let input = input.trim();
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(input) {
+        return Ok(datetime.with_timezone(&Utc));
+    }
+
+    let (date, time) = input.split_once(DATETIME_SEPARATORS).ok_or_else(|| {
+        CliError::parse(
+            format!("datetime '{}'", input),
+            format!(
+                "expected '{} {}[:SS]' or RFC 3339",
+                DATE_FORMAT.1, TIME_FORMATS[0].1
+            ),
+        )
+    })?;
+    user_datetime(date.trim(), time.trim())
+}
+
+/// Parse a duration made of unit-suffixed parts ("90s", "15m", "1h30m", "2d", "500ms")
+pub fn duration(input: &str) -> Result<chrono::Duration, CliError> {
+    let trimmed = input.trim();
+    let error =
+        |reason: &str| CliError::parse(format!("duration '{}'", trimmed), reason.to_string());
+    let unit_names = || {
+        let names: Vec<&str> = DURATION_UNITS.iter().map(|(unit, _, _)| *unit).collect();
+        names.join(", ")
+    };
+
+    let mut total = chrono::Duration::zero();
+    let mut rest = trimmed;
+    if rest.is_empty() {
+        return Err(error("duration is empty"));
+    }
+
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        if digits == 0 {
+            return Err(error("expected a number before each unit"));
+        }
+        let value: i64 = rest[..digits]
+            .parse()
+            .map_err(|_| error("number is too large"))?;
+        rest = &rest[digits..];
+
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let unit = &rest[..unit_len];
+        if unit.is_empty() {
+            return Err(error(&format!("missing unit (use {})", unit_names())));
+        }
+        let millis = match DURATION_UNITS.iter().find(|(name, _, _)| *name == unit) {
+            Some((_, millis, _)) => value
+                .checked_mul(*millis)
+                .ok_or_else(|| error("number is too large"))?,
+            None => {
+                return Err(error(&format!(
+                    "unknown unit '{}' (use {})",
+                    unit,
+                    unit_names()
+                )))
+            }
+        };
+        total += chrono::Duration::milliseconds(millis);
+        rest = &rest[unit_len..];
+    }
+
+    Ok(total)
+}
+
+/// Parse one sweep axis written as "start-end/step" in degrees ("0-360/30")
+pub fn sweep_axis(input: &str) -> Result<(f64, f64, f64), CliError> {
+    let trimmed = input.trim();
+    let error = || {
+        CliError::parse(
+            format!("sweep '{}'", trimmed),
+            "expected start-end/step in degrees, e.g. 0-360/30",
+        )
+    };
+
+    let (range, step) = trimmed.split_once('/').ok_or_else(error)?;
+    let (start, end) = range.split_once('-').ok_or_else(error)?;
+    let number = |text: &str| text.trim().parse::<f64>().map_err(|_| error());
+    Ok((number(start)?, number(end)?, number(step)?))
+}
+
+/// An angle in degrees, as typed at the fixed-pointing prompts
+pub fn degrees(input: &str) -> Result<f64, CliError> {
+    let trimmed = input.trim();
+    let number = trimmed.strip_suffix('°').unwrap_or(trimmed).trim();
+    number
+        .parse::<f64>()
+        .ok()
+        .filter(|degrees| degrees.is_finite())
+        .ok_or_else(|| {
+            CliError::parse(
+                format!("angle '{}'", trimmed),
+                "expected a number of degrees, e.g. 123.4",
+            )
+        })
+}
+
+/// `examples datetimes`
+pub const DATETIME_EXAMPLES: &[Example] = &[
+    Example {
+        note: "UTC wall-clock time; quote it for the space",
+        input: Some("2026-10-20 12:00"),
+        command: "rustar-cli predict --norad-id 25544 --from \"2026-10-20 12:00\"",
+    },
+    Example {
+        note: "Seconds, with a T in place of the space",
+        input: Some("2026-10-20T12:15:30"),
+        command: "rustar-cli look-angles --tle iss.tle --start 2026-10-20T12:15:30 --end 2026-10-20T12:25:00",
+    },
+    Example {
+        note: "Any fraction of a second is kept, to the millisecond the server takes",
+        input: Some("2026-10-20 12:15:30.250"),
+        command: "rustar-cli stats --from \"2026-10-20 12:15:30.250\"",
+    },
+    Example {
+        note: "A UTC offset after the time; the instant is converted to UTC",
+        input: Some("2026-10-20 14:00+02:00"),
+        command: "rustar-cli import iss.sat --start \"2026-10-20 14:00+02:00\" --duration 12m",
+    },
+    Example {
+        note: "RFC 3339, as other programs write it",
+        input: Some("2026-10-20T12:00:00Z"),
+        command: "rustar-cli audit --since 2026-10-20T12:00:00Z",
+    },
+    Example {
+        note: "Relative to now, where a flag takes WHEN",
+        input: Some("now+24h"),
+        command: "rustar-cli tle compare cache:25544 25544 --at now+24h",
+    },
+];
+
+/// `examples durations`
+pub const DURATION_EXAMPLES: &[Example] = &[
+    Example {
+        note: "Minutes",
+        input: Some("15m"),
+        command: "rustar-cli quick --satellite iss --within 15m",
+    },
+    Example {
+        note: "Parts add up, without spaces between them",
+        input: Some("1h30m"),
+        command: "rustar-cli jobs next --within 1h30m",
+    },
+    Example {
+        note: "Days",
+        input: Some("30d"),
+        command: "rustar-cli purge --older-than 30d --dry-run",
+    },
+    Example {
+        note: "Seconds, here the gap after the job this one follows",
+        input: Some("120s"),
+        command: "rustar-cli jobs add --after 42 --gap 120s",
+    },
+    Example {
+        note: "Milliseconds, for fine steps",
+        input: Some("500ms"),
+        command: "rustar-cli look-angles 42 --step 500ms",
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(text: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(text)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn fractions_and_offsets_are_kept_in_every_spelling() {
+        let cases = [
+            ("2026-10-20T12:15:00.123Z", "2026-10-20T12:15:00.123Z"),
+            ("2026-10-20T12:15:00.000001Z", "2026-10-20T12:15:00.000001Z"),
+            ("2026-10-20T17:45:00+05:30", "2026-10-20T12:15:00Z"),
+            ("2026-10-20T17:45:00.123+05:30", "2026-10-20T12:15:00.123Z"),
+            ("2026-10-20T12:15:00Z", "2026-10-20T12:15:00Z"),
+            ("2026-10-20 12:15:00.123Z", "2026-10-20T12:15:00.123Z"),
+            ("2026-10-20 12:15:00.000001", "2026-10-20T12:15:00.000001Z"),
+            ("2026-10-20 17:45:00.123+05:30", "2026-10-20T12:15:00.123Z"),
+            ("2026-10-20T17:45+05:30", "2026-10-20T12:15:00Z"),
+        ];
+        for (input, expected) in cases {
+            let at = datetime(input).unwrap();
+            assert_eq!(at, utc(expected), "{}", input);
+            let written = at.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true);
+            assert_eq!(datetime(&written).unwrap(), at, "{}", written);
+        }
+    }
+
+    #[test]
+    fn the_wire_keeps_milliseconds_and_drops_finer_digits() {
+        let wire = |input: &str| crate::schedule::format(datetime(input).unwrap());
+        assert_eq!(wire("2026-10-20T12:15:00.123Z"), "2026-10-20T12:15:00.123Z");
+        assert_eq!(wire("2026-10-20T12:15:00.000001Z"), "2026-10-20T12:15:00Z");
+        assert_eq!(
+            wire("2026-10-20T17:45:00.123+05:30"),
+            "2026-10-20T12:15:00.123Z"
+        );
+        assert_eq!(wire("2026-10-20T12:15:00Z"), "2026-10-20T12:15:00Z");
+        let sent = wire("2026-10-20T12:15:00.123456Z");
+        assert_eq!(datetime(&sent).unwrap(), utc("2026-10-20T12:15:00.123Z"));
+    }
+}
@@ -0,0 +1,135 @@
+use crate::config::{self, Resolved};
+use crate::error::CliError;
+use crate::predict::Pass;
+use crate::quiet_hours::Window;
+use crate::stats::{self, DayBoundary};
+use serde::Deserialize;
+
+/// A pass this far outside the preferred hours gets nothing for its time of day
+const TIME_OF_DAY_FALLOFF_MIN: f64 = 120.0;
+
+/// How passes are scored, from the profile's `[pass_scoring]` table. The weights are
+/// relative: only their ratio matters.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct PassScoring {
+    /// Weight of the highest elevation, full marks at 90°
+    pub elevation_weight: f64,
+    /// Weight of the time above the mask, full marks at `full_duration_minutes`
+    pub duration_weight: f64,
+    /// Weight of the time of day of the highest point; used only with `preferred_hours`
+    pub time_of_day_weight: f64,
+    /// Usable time worth full marks for duration, in minutes
+    pub full_duration_minutes: f64,
+    /// Time of day passes are best taken, in the profile's timezone, e.g. "08:00-20:00";
+    /// passes lose the time-of-day marks over the two hours either side
+    pub preferred_hours: Option<String>,
+}
+
+impl Default for PassScoring {
+    fn default() -> Self {
+        Self {
+            elevation_weight: 3.0,
+            duration_weight: 1.0,
+            time_of_day_weight: 1.0,
+            full_duration_minutes: 10.0,
+            preferred_hours: None,
+        }
+    }
+}
+
+/// The profile's scoring, checked and ready to apply
+#[derive(Debug, Clone)]
+pub struct Scorer {
+    scoring: PassScoring,
+    preferred: Option<Window>,
+    zone: DayBoundary,
+}
+
+impl Scorer {
+    /// The profile's `pass_scoring`, with time of day taken in its `timezone`
+    pub fn new(resolved: &Resolved) -> Result<Self, CliError> {
+        let profile = &resolved.profile;
+        let invalid = |key: &str, reason: String| {
+            CliError::Configuration(format!(
+                "profiles.{}.{} in {}: {}",
+                resolved.profile_name.value,
+                key,
+                config::config_path().display(),
+                reason
+            ))
+        };
+        let scoring = profile.pass_scoring.clone();
+        for (key, value) in [
+            ("elevation_weight", scoring.elevation_weight),
+            ("duration_weight", scoring.duration_weight),
+            ("time_of_day_weight", scoring.time_of_day_weight),
+        ] {
+            if !(value.is_finite() && value >= 0.0) {
+                return Err(invalid(
+                    &format!("pass_scoring.{}", key),
+                    format!("{} is not a weight of 0 or more", value),
+                ));
+            }
+        }
+        if !(scoring.full_duration_minutes.is_finite() && scoring.full_duration_minutes > 0.0) {
+            return Err(invalid(
+                "pass_scoring.full_duration_minutes",
+                format!(
+                    "{} is not a positive number of minutes",
+                    scoring.full_duration_minutes
+                ),
+            ));
+        }
+        let preferred = scoring
+            .preferred_hours
+            .as_deref()
+            .map(Window::parse)
+            .transpose()
+            .map_err(|reason| invalid("pass_scoring.preferred_hours", reason))?;
+        let weights = scoring.elevation_weight
+            + scoring.duration_weight
+            + preferred.map_or(0.0, |_| scoring.time_of_day_weight);
+        if weights == 0.0 {
+            return Err(invalid(
+                "pass_scoring",
+                "every weight in use is 0, so no pass would score".to_string(),
+            ));
+        }
+        let zone = match &profile.timezone {
+            Some(zone) => {
+                stats::parse_timezone(zone).map_err(|reason| invalid("timezone", reason))?
+            }
+            None => DayBoundary::Local,
+        };
+        Ok(Self {
+            scoring,
+            preferred,
+            zone,
+        })
+    }
+
+    /// How good `pass` is to schedule, 0 to 100. A pass the mask hides entirely scores 0.
+    pub fn score(&self, pass: &Pass) -> u8 {
+        if pass.aos.is_none() {
+            return 0;
+        }
+        let elevation = (pass.max_elevation_deg / 90.0).clamp(0.0, 1.0);
+        let usable_minutes = pass.usable_duration().num_milliseconds() as f64 / 60_000.0;
+        let duration = (usable_minutes / self.scoring.full_duration_minutes).clamp(0.0, 1.0);
+
+        let mut parts = vec![
+            (self.scoring.elevation_weight, elevation),
+            (self.scoring.duration_weight, duration),
+        ];
+        if let Some(preferred) = self.preferred {
+            let time = self.zone.wall_clock(pass.max_elevation_at).time();
+            let outside = preferred.distance(time).num_seconds() as f64 / 60.0;
+            let time_of_day = (1.0 - outside / TIME_OF_DAY_FALLOFF_MIN).clamp(0.0, 1.0);
+            parts.push((self.scoring.time_of_day_weight, time_of_day));
+        }
+        let weights: f64 = parts.iter().map(|(weight, _)| weight).sum();
+        let marks: f64 = parts.iter().map(|(weight, part)| weight * part).sum();
+        (marks / weights * 100.0).round() as u8
+    }
+}
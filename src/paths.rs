@@ -0,0 +1,227 @@
+use crate::error::{CliError, ResultExt};
+use crate::transcript::Recorded;
+use directories::ProjectDirs;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Overrides for the three base directories
+pub const CONFIG_DIR_ENV: &str = "RUSTAR_CONFIG_DIR";
+pub const CACHE_DIR_ENV: &str = "RUSTAR_CACHE_DIR";
+pub const STATE_DIR_ENV: &str = "RUSTAR_STATE_DIR";
+
+/// Left in the state directory once the operator said no to moving the legacy files
+const MIGRATION_DECLINED: &str = "legacy-migration-declined";
+
+/// Files that used to live under `~/.rustar/`, named relative to it
+enum Legacy {
+    Config,
+    Queue,
+    TleCache,
+}
+
+impl Legacy {
+    const ALL: [Legacy; 3] = [Legacy::Config, Legacy::Queue, Legacy::TleCache];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Legacy::Config => "config.toml",
+            Legacy::Queue => "queue.json",
+            Legacy::TleCache => "tle",
+        }
+    }
+
+    fn destination(&self) -> PathBuf {
+        match self {
+            Legacy::Config => config_file(),
+            Legacy::Queue => queue_file(),
+            Legacy::TleCache => tle_cache_dir(),
+        }
+    }
+}
+
+fn project_dirs() -> Option<ProjectDirs> {
+    ProjectDirs::from("", "", "rustar")
+}
+
+/// Set by `--demo`: the three directories live under it, whatever the environment says
+static SCRATCH: OnceLock<PathBuf> = OnceLock::new();
+
+/// Keep configuration, cache and state under `root` for the rest of the run
+pub fn use_scratch(root: &Path) {
+    let _ = SCRATCH.set(root.to_path_buf());
+}
+
+fn scratch(kind: &str) -> Option<PathBuf> {
+    SCRATCH.get().map(|root| root.join(kind))
+}
+
+fn from_env(var: &str) -> Option<PathBuf> {
+    std::env::var_os(var)
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Last resort when the platform reports no home directory
+fn fallback(kind: &str) -> PathBuf {
+    PathBuf::from(".rustar").join(kind)
+}
+
+/// `$XDG_CONFIG_HOME/rustar` or the platform equivalent
+pub fn config_dir() -> PathBuf {
+    scratch("config")
+        .or_else(|| from_env(CONFIG_DIR_ENV))
+        .or_else(|| project_dirs().map(|d| d.config_dir().to_path_buf()))
+        .unwrap_or_else(|| fallback("config"))
+}
+
+/// `$XDG_CACHE_HOME/rustar` or the platform equivalent
+pub fn cache_dir() -> PathBuf {
+    scratch("cache")
+        .or_else(|| from_env(CACHE_DIR_ENV))
+        .or_else(|| project_dirs().map(|d| d.cache_dir().to_path_buf()))
+        .unwrap_or_else(|| fallback("cache"))
+}
+
+/// `$XDG_STATE_HOME/rustar`; platforms without a state directory use local data
+pub fn state_dir() -> PathBuf {
+    scratch("state")
+        .or_else(|| from_env(STATE_DIR_ENV))
+        .or_else(|| {
+            project_dirs().map(|d| {
+                d.state_dir()
+                    .unwrap_or_else(|| d.data_local_dir())
+                    .to_path_buf()
+            })
+        })
+        .unwrap_or_else(|| fallback("state"))
+}
+
+pub fn config_file() -> PathBuf {
+    config_dir().join("config.toml")
+}
+
+/// One cached `<norad>.tle` file per satellite
+pub fn tle_cache_dir() -> PathBuf {
+    cache_dir().join("tle")
+}
+
+pub fn queue_file() -> PathBuf {
+    state_dir().join("queue.json")
+}
+
+/// Campaign names to the IDs of the jobs submitted under them
+pub fn campaigns_file() -> PathBuf {
+    state_dir().join("campaigns.json")
+}
+
+/// The latest jobs submitted for each satellite, for the summary's anomaly hints
+pub fn baselines_file() -> PathBuf {
+    state_dir().join("baselines.json")
+}
+
+/// Job IDs to the jobs they were scheduled to start after
+pub fn chains_file() -> PathBuf {
+    state_dir().join("chains.json")
+}
+
+/// Earlier answers to the satellite and frequency prompts
+pub fn prompt_history_file() -> PathBuf {
+    state_dir().join("prompt_history.json")
+}
+
+/// One receipt per accepted submission, chained by hash
+pub fn receipts_dir() -> PathBuf {
+    state_dir().join("receipts")
+}
+
+/// Unfinished `jobs add` sessions, one batch file each
+pub fn drafts_dir() -> PathBuf {
+    state_dir().join("drafts")
+}
+
+/// Where everything lived before the XDG layout
+fn legacy_dir() -> Option<PathBuf> {
+    directories::BaseDirs::new().map(|d| d.home_dir().join(".rustar"))
+}
+
+/// Move a file or directory, copying when a rename cannot cross filesystems
+fn move_path(from: &Path, to: &Path) -> Result<(), CliError> {
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("creating {}", parent.display()))?;
+    }
+    if std::fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+
+    if from.is_dir() {
+        std::fs::create_dir_all(to).with_context(|| format!("creating {}", to.display()))?;
+        let entries =
+            std::fs::read_dir(from).with_context(|| format!("reading {}", from.display()))?;
+        for entry in entries {
+            let entry = entry.with_context(|| format!("reading {}", from.display()))?;
+            move_path(&entry.path(), &to.join(entry.file_name()))?;
+        }
+        std::fs::remove_dir(from).with_context(|| format!("removing {}", from.display()))
+    } else {
+        std::fs::copy(from, to)
+            .with_context(|| format!("copying {} to {}", from.display(), to.display()))?;
+        std::fs::remove_file(from).with_context(|| format!("removing {}", from.display()))
+    }
+}
+
+/// Offer to move files from `~/.rustar/` into the XDG locations.
+/// Only asks on a terminal, and only until the operator has answered once.
+pub fn migrate_legacy() -> Result<(), CliError> {
+    let Some(legacy) = legacy_dir() else {
+        return Ok(());
+    };
+    let pending: Vec<(PathBuf, PathBuf)> = Legacy::ALL
+        .iter()
+        .map(|item| (legacy.join(item.name()), item.destination()))
+        .filter(|(from, to)| from.exists() && !to.exists())
+        .collect();
+    let declined = state_dir().join(MIGRATION_DECLINED);
+    if pending.is_empty() || declined.exists() {
+        return Ok(());
+    }
+
+    if !std::io::stdin().is_terminal() {
+        eprintln!(
+            "📦 Found files from an older version in {}; run rustar-cli in a terminal to move them",
+            legacy.display()
+        );
+        return Ok(());
+    }
+
+    eprintln!(
+        "📦 Files from an older version were found in {}:",
+        legacy.display()
+    );
+    for (from, to) in &pending {
+        eprintln!("   {} → {}", from.display(), to.display());
+    }
+    let migrate = inquire::Confirm::new("Move them to the new locations?")
+        .with_default(true)
+        .prompt()
+        .recorded("Move them to the new locations?")?;
+
+    if !migrate {
+        std::fs::create_dir_all(state_dir())
+            .with_context(|| format!("creating {}", state_dir().display()))?;
+        std::fs::write(&declined, "").with_context(|| format!("writing {}", declined.display()))?;
+        eprintln!(
+            "⏭️ Leaving them in place; delete {} to be asked again",
+            declined.display()
+        );
+        return Ok(());
+    }
+
+    for (from, to) in &pending {
+        move_path(from, to).with_context(|| format!("migrating {}", from.display()))?;
+    }
+    let _ = std::fs::remove_dir(&legacy);
+    eprintln!("✅ Moved {} item(s)", pending.len());
+    Ok(())
+}
@@ -0,0 +1,177 @@
+//! Where an invocation's time goes, phase by phase. Code that may be slow opens a `Span`
+//! for its phase; `--profile-timing` prints the totals at the end and `--verbose` notes
+//! each span as it closes. With neither, a span is an atomic load and nothing more.
+
+use crate::OutputFormat;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// The parts of a command that are timed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Phase {
+    /// Reading the config file, env file and flags into the settings in force
+    Config,
+    /// Finding an element set, in the cache or at a public source
+    Tle,
+    /// Pass predictions
+    Prediction,
+    /// One request to the station's API
+    Http,
+    /// Laying out tables for the terminal
+    Render,
+}
+
+impl Phase {
+    fn name(self) -> &'static str {
+        match self {
+            Phase::Config => "config",
+            Phase::Tle => "tle",
+            Phase::Prediction => "prediction",
+            Phase::Http => "http",
+            Phase::Render => "render",
+        }
+    }
+}
+
+/// `--profile-timing`: print the breakdown at the end
+static PROFILE: AtomicBool = AtomicBool::new(false);
+/// `--verbose`: note each span as it closes
+static VERBOSE: AtomicBool = AtomicBool::new(false);
+/// `--output json`: the breakdown is printed as JSON
+static JSON: AtomicBool = AtomicBool::new(false);
+/// Since when the command is timed; each command of the shell starts its own
+static STARTED: Mutex<Option<Instant>> = Mutex::new(None);
+static SPANS: Mutex<Vec<(Phase, Duration)>> = Mutex::new(Vec::new());
+
+/// `--profile-timing`, `--verbose` and `--output`, as early as the arguments are parsed
+pub fn set(profile: bool, verbose: bool, output: OutputFormat) {
+    PROFILE.store(profile, Ordering::Relaxed);
+    VERBOSE.store(verbose, Ordering::Relaxed);
+    JSON.store(output == OutputFormat::Json, Ordering::Relaxed);
+    *STARTED.lock().unwrap_or_else(|e| e.into_inner()) = Some(Instant::now());
+}
+
+/// A phase under way, recorded when dropped; `detail` says which one in verbose notes
+pub struct Span {
+    phase: Phase,
+    detail: String,
+    started: Instant,
+}
+
+/// Time `phase` until the span is dropped; `detail` is only worked out when someone
+/// will see it
+pub fn span(phase: Phase, detail: impl FnOnce() -> String) -> Option<Span> {
+    let verbose = VERBOSE.load(Ordering::Relaxed);
+    if !verbose && !PROFILE.load(Ordering::Relaxed) {
+        return None;
+    }
+    Some(Span {
+        phase,
+        detail: match verbose {
+            true => detail(),
+            false => String::new(),
+        },
+        started: Instant::now(),
+    })
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        let took = self.started.elapsed();
+        if VERBOSE.load(Ordering::Relaxed) {
+            eprintln!(
+                "⏱️ {} {}: {:.1} ms",
+                self.phase.name(),
+                self.detail,
+                took.as_secs_f64() * 1000.0
+            );
+        }
+        SPANS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push((self.phase, took));
+    }
+}
+
+/// One phase's share of the invocation
+#[derive(Debug, Serialize)]
+struct Total {
+    phase: Phase,
+    calls: usize,
+    ms: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct Breakdown {
+    total_ms: f64,
+    phases: Vec<Total>,
+}
+
+fn ms(duration: Duration) -> f64 {
+    (duration.as_secs_f64() * 1_000_000.0).round() / 1000.0
+}
+
+/// The phases in the order they first ran, with their calls and time added up
+fn breakdown() -> Breakdown {
+    let spans = std::mem::take(&mut *SPANS.lock().unwrap_or_else(|e| e.into_inner()));
+    let mut totals: Vec<(Phase, usize, Duration)> = Vec::new();
+    for (phase, took) in spans {
+        match totals.iter_mut().find(|(seen, _, _)| *seen == phase) {
+            Some((_, calls, time)) => {
+                *calls += 1;
+                *time += took;
+            }
+            None => totals.push((phase, 1, took)),
+        }
+    }
+    Breakdown {
+        total_ms: ms(STARTED
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .replace(Instant::now())
+            .map(|started| started.elapsed())
+            .unwrap_or_default()),
+        phases: totals
+            .into_iter()
+            .map(|(phase, calls, time)| Total {
+                phase,
+                calls,
+                ms: ms(time),
+            })
+            .collect(),
+    }
+}
+
+/// Print the breakdown with `--profile-timing`: a table on stderr, or with `--output json`
+/// one object under a `timing` key, on stderr too so stdout stays the command's document
+pub fn finish() {
+    if !PROFILE.load(Ordering::Relaxed) {
+        return;
+    }
+    let breakdown = breakdown();
+    if JSON.load(Ordering::Relaxed) {
+        eprintln!("{}", serde_json::json!({ "timing": breakdown }));
+        return;
+    }
+    eprintln!("⏱️ Phases of this run, {:.1} ms in all", breakdown.total_ms);
+    eprintln!(
+        "  {:<12}{:>6}{:>12}{:>7}",
+        "PHASE", "CALLS", "TIME", "SHARE"
+    );
+    for total in &breakdown.phases {
+        let share = match breakdown.total_ms > 0.0 {
+            true => total.ms / breakdown.total_ms * 100.0,
+            false => 0.0,
+        };
+        eprintln!(
+            "  {:<12}{:>6}{:>9.1} ms{:>6.0}%",
+            total.phase.name(),
+            total.calls,
+            total.ms,
+            share
+        );
+    }
+}
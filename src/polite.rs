@@ -0,0 +1,177 @@
+//! Polite use of the public element-set sources, which block clients that hammer them.
+//! Every download of elements goes through `get`: it takes a turn from its host's token
+//! bucket, shares one request between concurrent fetches of the same URL, and makes no
+//! request at all under `--tle-offline`.
+
+use crate::error::CliError;
+use std::collections::BTreeMap;
+use std::sync::{Mutex, OnceLock};
+use tokio::sync::watch;
+use tokio::time::{Duration, Instant};
+
+/// Requests a minute to one element-set host, unless the profile says otherwise
+pub const DEFAULT_REQUESTS_PER_MINUTE: u32 = 20;
+
+/// Requests a host gets at once before the rate applies
+const BURST: f64 = 3.0;
+
+struct Settings {
+    requests_per_minute: u32,
+    offline: bool,
+    verbose: bool,
+}
+
+static SETTINGS: OnceLock<Settings> = OnceLock::new();
+
+/// The profile's `tle_requests_per_minute` (at least one), `--tle-offline` and `--verbose`
+pub fn set(requests_per_minute: Option<u32>, offline: bool, verbose: bool) {
+    let _ = SETTINGS.set(Settings {
+        requests_per_minute: requests_per_minute
+            .unwrap_or(DEFAULT_REQUESTS_PER_MINUTE)
+            .max(1),
+        offline,
+        verbose,
+    });
+}
+
+fn settings() -> &'static Settings {
+    SETTINGS.get_or_init(|| Settings {
+        requests_per_minute: DEFAULT_REQUESTS_PER_MINUTE,
+        offline: false,
+        verbose: false,
+    })
+}
+
+/// `--tle-offline`: element sets come from the cache or not at all
+pub fn offline() -> bool {
+    settings().offline
+}
+
+/// Report limiter activity with `--verbose`
+fn note(message: impl AsRef<str>) {
+    if settings().verbose {
+        eprintln!("🐢 {}", message.as_ref());
+    }
+}
+
+/// Turns left to one host, refilled at the configured rate
+struct Bucket {
+    tokens: f64,
+    refilled: Instant,
+}
+
+/// How long until `host` may be sent a request, taking that turn. Turns are handed out
+/// in order, so a bucket in debt makes each later caller wait longer.
+fn take_turn(host: &str) -> Duration {
+    static BUCKETS: Mutex<BTreeMap<String, Bucket>> = Mutex::new(BTreeMap::new());
+    let rate = f64::from(settings().requests_per_minute) / 60.0;
+    let now = Instant::now();
+    let mut buckets = BUCKETS.lock().unwrap_or_else(|e| e.into_inner());
+    let bucket = buckets.entry(host.to_string()).or_insert(Bucket {
+        tokens: BURST,
+        refilled: now,
+    });
+    let elapsed = now.duration_since(bucket.refilled).as_secs_f64();
+    bucket.tokens = (bucket.tokens + elapsed * rate).min(BURST);
+    bucket.refilled = now;
+    bucket.tokens -= 1.0;
+    match bucket.tokens < 0.0 {
+        true => Duration::from_secs_f64(-bucket.tokens / rate),
+        false => Duration::ZERO,
+    }
+}
+
+/// What a request ended with, as the fetches sharing it see it
+type Shared = Option<Result<String, String>>;
+
+static IN_FLIGHT: Mutex<BTreeMap<String, watch::Receiver<Shared>>> = Mutex::new(BTreeMap::new());
+
+/// Takes a finished or abandoned request out of `IN_FLIGHT`, so the next fetch of its URL
+/// makes a new one
+struct InFlight<'a> {
+    url: &'a str,
+}
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        IN_FLIGHT
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(self.url);
+    }
+}
+
+/// Whether a fetch makes the request or waits for one already on its way
+enum Role {
+    Lead(watch::Sender<Shared>),
+    Follow(watch::Receiver<Shared>),
+}
+
+/// The body at `url`, an element-set source, for the elements of `what`
+pub async fn get(url: &str, what: &str) -> Result<String, CliError> {
+    if offline() {
+        return Err(CliError::Offline {
+            what: what.to_string(),
+        });
+    }
+    loop {
+        let role = {
+            let mut in_flight = IN_FLIGHT.lock().unwrap_or_else(|e| e.into_inner());
+            match in_flight.get(url) {
+                Some(receiver) => Role::Follow(receiver.clone()),
+                None => {
+                    let (sender, receiver) = watch::channel(None);
+                    in_flight.insert(url.to_string(), receiver);
+                    Role::Lead(sender)
+                }
+            }
+        };
+        match role {
+            Role::Follow(mut receiver) => {
+                note(format!("Sharing the request already on its way to {}", url));
+                // A request given up on its way ends the wait empty; this fetch makes its own
+                let outcome = receiver
+                    .wait_for(Option::is_some)
+                    .await
+                    .ok()
+                    .and_then(|outcome| outcome.clone());
+                let Some(outcome) = outcome else {
+                    continue;
+                };
+                return outcome.map_err(|message| CliError::Coalesced {
+                    url: url.to_string(),
+                    message,
+                });
+            }
+            Role::Lead(sender) => {
+                let _in_flight = InFlight { url };
+                let body = download(url).await;
+                let _ = sender.send(Some(match &body {
+                    Ok(body) => Ok(body.clone()),
+                    Err(e) => Err(e.full_message()),
+                }));
+                return body;
+            }
+        }
+    }
+}
+
+/// One request to `url`, once its host has a turn free
+async fn download(url: &str) -> Result<String, CliError> {
+    if let Some(host) = reqwest::Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+    {
+        let wait = take_turn(&host);
+        if !wait.is_zero() {
+            note(format!(
+                "Waiting {:.1}s for a turn at {} ({} requests a minute)",
+                wait.as_secs_f64(),
+                host,
+                settings().requests_per_minute
+            ));
+            tokio::time::sleep(wait).await;
+        }
+    }
+    Ok(reqwest::get(url).await?.error_for_status()?.text().await?)
+}
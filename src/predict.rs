@@ -0,0 +1,472 @@
+use crate::client::TleData;
+use crate::clock;
+use crate::error::CliError;
+use crate::humanize;
+use crate::location::Location;
+use crate::mask::ElevationMask;
+use crate::orbit::{self, Satellite};
+use crate::pass_score::Scorer;
+use crate::phases::{self, Phase};
+use crate::schedule::TimeWindow;
+use crate::table::{Column, Table};
+use crate::transcript::Recorded;
+use crate::OutputFormat;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+/// Coarse search step; short enough not to miss a low LEO pass
+const SEARCH_STEP_S: i64 = 20;
+/// Step used inside a pass when looking for mask crossings and the highest point
+const PASS_STEP_S: i64 = 5;
+/// Crossings are refined until the bracket is this narrow
+const CROSSING_PRECISION_MS: i64 = 500;
+
+/// The station counts as dark once the Sun is this far below the horizon (end of civil twilight)
+const CIVIL_TWILIGHT_DEG: f64 = -6.0;
+
+/// A [start, end] stretch of time
+pub type Interval = (DateTime<Utc>, DateTime<Utc>);
+
+/// How the merged pass table is ordered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum PassOrder {
+    /// Chronologically by AOS
+    Time,
+    /// Highest pass first
+    Elevation,
+    /// Best-scoring pass first
+    Score,
+}
+
+/// Which passes `show` lists, and how
+pub struct Listing {
+    /// Include passes the mask hides entirely, and each pass's rise/set and masked time
+    pub show_mask: bool,
+    pub order: PassOrder,
+    /// Leave out passes scoring below this
+    pub min_score: Option<u8>,
+    /// Keep only this many of the best-scoring passes
+    pub best: Option<u32>,
+}
+
+/// One pass over the station
+#[derive(Debug, Clone, Serialize)]
+pub struct Pass {
+    pub satellite: String,
+    /// Elements the pass was computed from, for scheduling it
+    #[serde(skip)]
+    pub tle: TleData,
+    /// Where the satellite rises above and sets below the flat minimum elevation
+    pub rise: DateTime<Utc>,
+    pub set: DateTime<Utc>,
+    /// First and last moment above both the minimum elevation and the mask
+    pub aos: Option<DateTime<Utc>>,
+    pub los: Option<DateTime<Utc>>,
+    pub aos_azimuth_deg: Option<f64>,
+    pub los_azimuth_deg: Option<f64>,
+    pub max_elevation_deg: f64,
+    pub max_elevation_at: DateTime<Utc>,
+    /// Intervals above the mask; more than one when an obstruction cuts the pass in two
+    pub usable: Vec<Interval>,
+    /// Share of rise to set the satellite spends in the Earth's shadow, 0 to 1
+    pub shadow_fraction: f64,
+    /// Sun elevation at the station at the highest point of the pass
+    pub sun_elevation_deg: f64,
+    /// Whether the station is past civil twilight at the highest point
+    pub station_dark: bool,
+    /// Other satellites whose usable window overlaps this one
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub conflicts_with: Vec<String>,
+    /// 0 to 100 by the profile's `pass_scoring`; set once the passes are listed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<u8>,
+}
+
+impl Pass {
+    pub fn usable_duration(&self) -> Duration {
+        self.usable
+            .iter()
+            .fold(Duration::zero(), |total, (start, end)| {
+                total + (*end - *start)
+            })
+    }
+
+    /// Time above the minimum elevation but hidden by the mask
+    pub fn masked_duration(&self) -> Duration {
+        (self.set - self.rise) - self.usable_duration()
+    }
+
+    /// "dark", "twilight" or "day" at the station
+    pub fn sky(&self) -> &'static str {
+        if self.station_dark {
+            "dark"
+        } else if self.sun_elevation_deg < 0.0 {
+            "twilight"
+        } else {
+            "day"
+        }
+    }
+}
+
+/// Everything the search needs besides the satellite
+pub struct Horizon<'a> {
+    pub station: &'a Location,
+    pub min_elevation_deg: f64,
+    pub mask: &'a ElevationMask,
+}
+
+/// Intervals of [from, to) where `margin` is non-negative, refined by bisection
+fn intervals(
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    step: Duration,
+    margin: impl Fn(DateTime<Utc>) -> Result<f64, CliError>,
+) -> Result<Vec<Interval>, CliError> {
+    let crossing = |mut below: DateTime<Utc>, mut above: DateTime<Utc>| {
+        while (above - below).num_milliseconds().abs() > CROSSING_PRECISION_MS {
+            let middle = below + (above - below) / 2;
+            if margin(middle)? >= 0.0 {
+                above = middle;
+            } else {
+                below = middle;
+            }
+        }
+        Ok::<_, CliError>(above)
+    };
+
+    let mut found = Vec::new();
+    let mut previous = (from, margin(from)? >= 0.0);
+    let mut start = previous.1.then_some(from);
+    let mut at = from;
+    while at < to {
+        at = (at + step).min(to);
+        let inside = margin(at)? >= 0.0;
+        match (previous.1, inside) {
+            (false, true) => start = Some(crossing(previous.0, at)?),
+            (true, false) => {
+                if let Some(start) = start.take() {
+                    found.push((start, crossing(at, previous.0)?));
+                }
+            }
+            _ => {}
+        }
+        previous = (at, inside);
+    }
+    if let Some(start) = start {
+        found.push((start, to));
+    }
+    Ok(found)
+}
+
+/// Passes starting in [from, to), with AOS/LOS and usable time taken against the mask
+pub fn passes(
+    satellite: &Satellite,
+    horizon: &Horizon,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<Pass>, CliError> {
+    let _span = phases::span(Phase::Prediction, || {
+        format!(
+            "{} to {}",
+            humanize::timestamp(from),
+            humanize::timestamp(to)
+        )
+    });
+    let elevation = |at| {
+        satellite
+            .look_angle(horizon.station, at)
+            .map(|look| look.elevation_deg)
+    };
+    let above_mask = |at| {
+        let look = satellite.look_angle(horizon.station, at)?;
+        let limit = horizon
+            .min_elevation_deg
+            .max(horizon.mask.min_elevation(look.azimuth_deg));
+        Ok(look.elevation_deg - limit)
+    };
+
+    let windows = intervals(from, to, Duration::seconds(SEARCH_STEP_S), |at| {
+        Ok(elevation(at)? - horizon.min_elevation_deg)
+    })?;
+
+    let mut found = Vec::new();
+    for (rise, set) in windows {
+        let usable = if horizon.mask.is_empty() {
+            vec![(rise, set)]
+        } else {
+            intervals(rise, set, Duration::seconds(PASS_STEP_S), above_mask)?
+        };
+
+        let mut highest = (rise, elevation(rise)?);
+        let (mut samples, mut shadowed) = (1, u32::from(satellite.in_shadow(rise)?));
+        let mut at = rise;
+        while at < set {
+            at = (at + Duration::seconds(PASS_STEP_S)).min(set);
+            let el = elevation(at)?;
+            if el > highest.1 {
+                highest = (at, el);
+            }
+            samples += 1;
+            shadowed += u32::from(satellite.in_shadow(at)?);
+        }
+        let sun_elevation_deg = orbit::sun_elevation(horizon.station, highest.0);
+
+        let azimuth = |at| {
+            satellite
+                .look_angle(horizon.station, at)
+                .map(|look| look.azimuth_deg)
+        };
+        let aos = usable.first().map(|(start, _)| *start);
+        let los = usable.last().map(|(_, end)| *end);
+        found.push(Pass {
+            satellite: satellite.name.clone(),
+            tle: satellite.tle.clone(),
+            rise,
+            set,
+            aos,
+            los,
+            aos_azimuth_deg: aos.map(azimuth).transpose()?,
+            los_azimuth_deg: los.map(azimuth).transpose()?,
+            max_elevation_deg: highest.1,
+            max_elevation_at: highest.0,
+            usable,
+            shadow_fraction: f64::from(shadowed) / f64::from(samples),
+            sun_elevation_deg,
+            station_dark: sun_elevation_deg < CIVIL_TWILIGHT_DEG,
+            conflicts_with: Vec::new(),
+            score: None,
+        });
+    }
+    Ok(found)
+}
+
+/// Mark passes of different satellites whose usable windows overlap
+fn flag_conflicts(passes: &mut [Pass]) {
+    for i in 0..passes.len() {
+        let (Some(aos), Some(los)) = (passes[i].aos, passes[i].los) else {
+            continue;
+        };
+        let mut names: Vec<String> = passes
+            .iter()
+            .filter(|other| other.satellite != passes[i].satellite)
+            .filter(|other| match (other.aos, other.los) {
+                (Some(start), Some(end)) => {
+                    TimeWindow::new(aos, los).overlaps(&TimeWindow::new(start, end))
+                }
+                _ => false,
+            })
+            .map(|other| other.satellite.clone())
+            .collect();
+        names.sort();
+        names.dedup();
+        passes[i].conflicts_with = names;
+    }
+}
+
+fn score(pass: &Pass) -> String {
+    pass.score
+        .map_or_else(|| "—".to_string(), |score| score.to_string())
+}
+
+fn print_table(passes: &[Pass], show_mask: bool, several: bool) {
+    let mut columns = vec![
+        Column::time("AOS (UTC)"),
+        Column::time("LOS (UTC)"),
+        Column::new("DURATION"),
+        Column::new("MAX EL"),
+        Column::new("SCORE"),
+        Column::new("AZ"),
+        Column::new("IN SHADOW").from(100),
+        Column::new("STATION").from(90),
+        Column::new("WHEN"),
+    ];
+    if show_mask {
+        columns.extend([
+            Column::time("RISE → SET").from(120),
+            Column::new("LOST TO MASK").from(110),
+        ]);
+    }
+    if several {
+        columns.insert(0, Column::name("SATELLITE"));
+        columns.push(Column::name("CONFLICTS"));
+    }
+    let now = clock::now();
+    let mut table = Table::new(columns);
+    for p in passes {
+        let mut row = match (p.aos, p.los) {
+            (Some(aos), Some(los)) => vec![
+                aos.format("%Y-%m-%d %H:%M:%S").to_string(),
+                los.format("%H:%M:%S").to_string(),
+                humanize::duration(p.usable_duration()),
+                format!("{:.1}°", p.max_elevation_deg),
+                score(p),
+                format!(
+                    "{:.0}° → {:.0}°",
+                    p.aos_azimuth_deg.unwrap_or_default(),
+                    p.los_azimuth_deg.unwrap_or_default()
+                ),
+                format!("{:.0}%", p.shadow_fraction * 100.0),
+                p.sky().to_string(),
+                humanize::relative_to(aos, los, now),
+            ],
+            _ => vec![
+                "masked".to_string(),
+                "—".to_string(),
+                "0s".to_string(),
+                format!("{:.1}°", p.max_elevation_deg),
+                score(p),
+                "—".to_string(),
+                format!("{:.0}%", p.shadow_fraction * 100.0),
+                p.sky().to_string(),
+                humanize::relative_to(p.rise, p.set, now),
+            ],
+        };
+        if show_mask {
+            let total = (p.set - p.rise).num_seconds().max(1) as f64;
+            row.push(format!(
+                "{} → {}",
+                p.rise.format("%H:%M:%S"),
+                p.set.format("%H:%M:%S")
+            ));
+            row.push(format!(
+                "{} ({:.0}%)",
+                humanize::duration(p.masked_duration()),
+                p.masked_duration().num_seconds() as f64 * 100.0 / total
+            ));
+        }
+        if several {
+            row.insert(0, p.satellite.clone());
+            row.push(match p.conflicts_with.as_slice() {
+                [] => String::new(),
+                names => format!("⚠️ {}", names.join(", ")),
+            });
+        }
+        table.row(row);
+    }
+    table.print();
+}
+
+/// Predict passes of every satellite, merged into one table. Returns the listed passes.
+pub fn show(
+    tles: &[TleData],
+    horizon: &Horizon,
+    from: DateTime<Utc>,
+    hours: u32,
+    listing: &Listing,
+    scorer: &Scorer,
+    output: OutputFormat,
+) -> Result<Vec<Pass>, CliError> {
+    let show_mask = listing.show_mask;
+    let to = from + Duration::hours(hours.into());
+    let mut found = Vec::new();
+    let mut names = Vec::new();
+    for tle in tles {
+        let satellite = Satellite::from_tle(tle)?;
+        found.extend(passes(&satellite, horizon, from, to)?);
+        names.push(satellite.name);
+    }
+    // A pass entirely behind the mask is only worth listing when asked about the mask
+    if !show_mask {
+        found.retain(|p| p.aos.is_some());
+    }
+    found.sort_by_key(|p| p.aos.unwrap_or(p.rise));
+    // Conflicts are with every pass, including those the selection below leaves out
+    flag_conflicts(&mut found);
+    for pass in &mut found {
+        pass.score = Some(scorer.score(pass));
+    }
+    let predicted = found.len();
+    if let Some(min_score) = listing.min_score {
+        found.retain(|p| p.score >= Some(min_score));
+    }
+    if let Some(best) = listing.best {
+        // Stable, so equal scores keep the earlier pass
+        found.sort_by_key(|p| std::cmp::Reverse(p.score));
+        found.truncate(best as usize);
+        found.sort_by_key(|p| p.aos.unwrap_or(p.rise));
+    }
+    let left_out = predicted - found.len();
+    match listing.order {
+        PassOrder::Time => {}
+        PassOrder::Elevation => {
+            found.sort_by(|a, b| b.max_elevation_deg.total_cmp(&a.max_elevation_deg))
+        }
+        PassOrder::Score => found.sort_by_key(|p| std::cmp::Reverse(p.score)),
+    }
+
+    if output == OutputFormat::Json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&found).map_err(|e| CliError::parse("passes", e))?
+        );
+        return Ok(found);
+    }
+
+    let names = names.join(", ");
+    if found.is_empty() && left_out > 0 {
+        println!(
+            "📭 None of the {} pass(es) of {} in the next {} hour(s) made the selection",
+            left_out, names, hours
+        );
+        return Ok(found);
+    }
+    if found.is_empty() {
+        println!("📭 No passes of {} in the next {} hour(s)", names, hours);
+        return Ok(found);
+    }
+    println!(
+        "🛰️ {} pass(es) of {} from {} over {}",
+        found.len(),
+        names,
+        humanize::timestamp(from),
+        humanize::duration(to - from)
+    );
+    print_table(&found, show_mask, tles.len() > 1);
+    if left_out > 0 {
+        println!(
+            "🔎 {} lower-scoring pass(es) left out by --min-score or --best",
+            left_out
+        );
+    }
+    let conflicts = found
+        .iter()
+        .filter(|p| !p.conflicts_with.is_empty())
+        .count();
+    if conflicts > 0 {
+        println!(
+            "⚠️ {} pass(es) overlap a pass of another satellite; only one can be tracked",
+            conflicts
+        );
+    }
+    Ok(found)
+}
+
+/// Let the operator choose one usable pass to schedule
+pub fn pick(passes: &[Pass]) -> Result<Option<Pass>, CliError> {
+    let usable: Vec<&Pass> = passes.iter().filter(|p| p.aos.is_some()).collect();
+    if usable.is_empty() {
+        return Ok(None);
+    }
+    let options: Vec<String> = usable
+        .iter()
+        .map(|p| {
+            format!(
+                "{}  {} → {}  max {:.1}°  score {}{}",
+                p.satellite,
+                p.aos.unwrap_or(p.rise).format("%Y-%m-%d %H:%M:%S"),
+                p.los.unwrap_or(p.set).format("%H:%M:%S"),
+                p.max_elevation_deg,
+                score(p),
+                if p.conflicts_with.is_empty() {
+                    ""
+                } else {
+                    "  ⚠️ conflict"
+                }
+            )
+        })
+        .collect();
+    let choice = inquire::Select::new("🎯 Pass to schedule:", options)
+        .raw_prompt()
+        .recorded("🎯 Pass to schedule:")?;
+    Ok(Some(usable[choice.index].clone()))
+}
@@ -0,0 +1,146 @@
+use crate::humanize;
+use crate::reproducible;
+use reqwest::{Method, StatusCode};
+use std::io::{IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// Bodies smaller than this finish before a progress line would be worth drawing
+const DRAW_AFTER_BYTES: u64 = 256 * 1024;
+
+/// What the client reports as it works, for spinners, progress bars and logs. Every
+/// method defaults to doing nothing. Observers must not panic; `ApiClient` catches a
+/// panic so the request carries on, but the report it was making is lost.
+pub trait Observer: Send + Sync {
+    /// A client was built to talk to `base_url`
+    fn on_client_ready(&self, _base_url: &str) {}
+
+    /// A job is about to be submitted to `url`, or the hold `hold` confirmed there
+    fn on_job_submit(&self, _url: &str, _hold: Option<&str>) {}
+
+    /// A request is about to be sent
+    fn on_request_start(&self, _method: &Method, _path: &str) {}
+
+    /// The response headers arrived, or the request failed without any (`status` is None)
+    fn on_request_end(
+        &self,
+        _method: &Method,
+        _path: &str,
+        _status: Option<StatusCode>,
+        _elapsed: Duration,
+    ) {
+    }
+
+    /// The server named the request it answered, in an `X-Request-Id` header
+    fn on_request_id(&self, _method: &Method, _path: &str, _request_id: &str) {}
+
+    /// A request is being sent again after a failure, `attempt` counting from 2
+    fn on_retry(&self, _attempt: u32, _reason: &str) {}
+
+    /// Part of a response body has arrived; `total` when the server said how long it is
+    fn on_download_progress(&self, _path: &str, _received: u64, _total: Option<u64>) {}
+
+    /// Entries of a batch submission handled so far, successful or not
+    fn on_batch_progress(&self, _done: usize, _total: usize) {}
+}
+
+/// The observer of a client nobody watches
+pub struct Silent;
+
+impl Observer for Silent {}
+
+/// What the CLI shows: a live download line on a terminal, and request outcomes,
+/// retries and batch counts with `--verbose`
+pub struct Terminal {
+    verbose: bool,
+    live: bool,
+    /// A progress line is on screen and must be cleared before anything else is written
+    drawn: AtomicBool,
+}
+
+impl Terminal {
+    pub fn new(verbose: bool) -> Self {
+        Self {
+            verbose,
+            live: reproducible::live(std::io::stderr().is_terminal()),
+            drawn: AtomicBool::new(false),
+        }
+    }
+
+    fn clear(&self) {
+        if self.drawn.swap(false, Ordering::Relaxed) {
+            eprint!("\r\x1b[2K");
+        }
+    }
+}
+
+impl Observer for Terminal {
+    fn on_client_ready(&self, base_url: &str) {
+        eprintln!("🌐 API Client initialized: {}", base_url);
+    }
+
+    fn on_job_submit(&self, url: &str, hold: Option<&str>) {
+        self.clear();
+        match hold {
+            Some(hold) => eprintln!("🚀 Confirming hold {} at: {}", hold, url),
+            None => eprintln!("🚀 Submitting job to: {}", url),
+        }
+    }
+
+    fn on_request_end(
+        &self,
+        method: &Method,
+        path: &str,
+        status: Option<StatusCode>,
+        elapsed: Duration,
+    ) {
+        self.clear();
+        if self.verbose {
+            let outcome = status.map_or("no response".to_string(), |s| s.to_string());
+            eprintln!(
+                "↩️ {} {}: {} after {} ms",
+                method,
+                path,
+                outcome,
+                elapsed.as_millis()
+            );
+        }
+    }
+
+    fn on_retry(&self, attempt: u32, reason: &str) {
+        self.clear();
+        if self.verbose {
+            eprintln!("🔁 Attempt {}: {}", attempt, reason);
+        }
+    }
+
+    fn on_download_progress(&self, path: &str, received: u64, total: Option<u64>) {
+        if !self.live || received < DRAW_AFTER_BYTES {
+            return;
+        }
+        let done = total.is_some_and(|total| received >= total);
+        if done {
+            self.clear();
+            return;
+        }
+        let amount = match total {
+            Some(total) => format!(
+                "{} of {} ({}%)",
+                humanize::bytes(received),
+                humanize::bytes(total),
+                received * 100 / total.max(1)
+            ),
+            None => humanize::bytes(received),
+        };
+        eprint!("\r\x1b[2K⬇️ {}: {}", path, amount);
+        let _ = std::io::stderr().flush();
+        self.drawn.store(true, Ordering::Relaxed);
+    }
+
+    fn on_batch_progress(&self, done: usize, total: usize) {
+        self.clear();
+        if self.verbose {
+            eprintln!("📦 {} of {} entries handled", done, total);
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use crate::error::CliError;
+use crate::reproducible;
+use crate::transcript::Recorded;
+use crate::tty;
+use inquire::Text;
+use std::io::IsTerminal;
+
+/// Red on a terminal that allows colour, plain otherwise
+fn red(text: &str) -> String {
+    if reproducible::styled(std::io::stderr().is_terminal()) {
+        format!("\x1b[1;31m{}\x1b[0m", text)
+    } else {
+        text.to_string()
+    }
+}
+
+/// Hold a mutating command against a protected profile until the operator names the
+/// profile: typed at a prompt, or given as `--confirm-protected` for automation.
+/// `--yes` does not count; it answers the command's own questions, not this one.
+pub fn check(
+    command: &str,
+    profile: &str,
+    base_url: &str,
+    confirmed: Option<&str>,
+) -> Result<(), CliError> {
+    eprintln!(
+        "🔒 {} targets the protected profile {} at {}",
+        command,
+        red(profile),
+        red(base_url)
+    );
+    let mismatch = |given: &str| {
+        CliError::validation(
+            "confirmation",
+            format!(
+                "'{}' is not the protected profile '{}'; nothing was changed",
+                given.trim(),
+                profile
+            ),
+        )
+    };
+
+    if let Some(given) = confirmed {
+        return if given.trim() == profile {
+            Ok(())
+        } else {
+            Err(mismatch(given))
+        };
+    }
+    let flag = format!("--confirm-protected {}", profile);
+    tty::require(command, &[&flag])?;
+    let question = format!("Type {} to go ahead:", profile);
+    let typed = Text::new(&question).prompt().recorded(&question)?;
+    if typed.trim() == profile {
+        Ok(())
+    } else {
+        Err(mismatch(&typed))
+    }
+}
@@ -0,0 +1,250 @@
+use crate::audit;
+use crate::clock;
+use crate::error::{CliError, ResultExt};
+use crate::history;
+use crate::humanize;
+use crate::paths;
+use crate::queue;
+use crate::table::{Column, Table};
+use crate::tty;
+use crate::OutputFormat;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Local stores `purge` can thin out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Target {
+    /// Answers remembered by the satellite and frequency prompts
+    History,
+    /// Cached element sets, HTTP responses and weather
+    Cache,
+    /// Entries of the audit log
+    Audit,
+    /// Queued jobs whose start has passed, whatever --older-than says
+    Queue,
+}
+
+impl Target {
+    pub const ALL: [Target; 4] = [Target::History, Target::Cache, Target::Audit, Target::Queue];
+
+    fn name(self) -> &'static str {
+        match self {
+            Target::History => "prompt history",
+            Target::Cache => "cache",
+            Target::Audit => "audit log",
+            Target::Queue => "offline queue",
+        }
+    }
+
+    fn path(self) -> PathBuf {
+        match self {
+            Target::History => paths::prompt_history_file(),
+            Target::Cache => paths::cache_dir(),
+            Target::Audit => audit::log_path(),
+            Target::Queue => queue::queue_path(),
+        }
+    }
+
+    /// Count what goes, and remove it when `apply`; each store locks its file as its other
+    /// writers do, and recounts under the lock
+    fn purge(
+        self,
+        cutoff: DateTime<Utc>,
+        now: DateTime<Utc>,
+        apply: bool,
+    ) -> Result<Removal, CliError> {
+        match self {
+            Target::History => history::purge(cutoff, apply),
+            Target::Cache => purge_cache(&self.path(), cutoff, apply),
+            Target::Audit => audit::purge(cutoff, apply),
+            Target::Queue => queue::purge(now, apply),
+        }
+    }
+}
+
+/// What purging one store removes, or removed
+#[derive(Debug, Default, Serialize)]
+pub struct Removal {
+    pub entries: usize,
+    pub bytes: u64,
+    /// The entries one by one, for stores whose entries are worth naming
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub dropped: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct StoreReport {
+    store: Target,
+    path: PathBuf,
+    #[serde(flatten)]
+    removal: Removal,
+}
+
+/// Cached files last written before `cutoff`. Cache writes replace whole files, so each
+/// removal is atomic on its own.
+fn purge_cache(dir: &Path, cutoff: DateTime<Utc>, apply: bool) -> Result<Removal, CliError> {
+    let mut removal = Removal::default();
+    let Ok(listing) = std::fs::read_dir(dir) else {
+        return Ok(removal);
+    };
+    for entry in listing {
+        let entry = entry.with_context(|| format!("listing {}", dir.display()))?;
+        let path = entry.path();
+        let metadata = entry
+            .metadata()
+            .with_context(|| format!("reading {}", path.display()))?;
+        if metadata.is_dir() {
+            let inner = purge_cache(&path, cutoff, apply)?;
+            removal.entries += inner.entries;
+            removal.bytes += inner.bytes;
+            continue;
+        }
+        let written = metadata.modified().ok().map(DateTime::<Utc>::from);
+        if written.is_none_or(|written| written >= cutoff) {
+            continue;
+        }
+        if apply {
+            match std::fs::remove_file(&path) {
+                Ok(()) => {}
+                // Cleared by another process meanwhile
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e).with_context(|| format!("removing {}", path.display())),
+            }
+        }
+        removal.entries += 1;
+        removal.bytes += metadata.len();
+    }
+    Ok(removal)
+}
+
+fn survey(
+    targets: &[Target],
+    cutoff: DateTime<Utc>,
+    now: DateTime<Utc>,
+    apply: bool,
+) -> Result<Vec<StoreReport>, CliError> {
+    targets
+        .iter()
+        .map(|target| {
+            Ok(StoreReport {
+                store: *target,
+                path: target.path(),
+                removal: target
+                    .purge(cutoff, now, apply)
+                    .with_context(|| format!("purging the {}", target.name()))?,
+            })
+        })
+        .collect()
+}
+
+fn print_table(reports: &[StoreReport]) {
+    let mut table = Table::new(vec![
+        Column::new("STORE"),
+        Column::new("ENTRIES"),
+        Column::new("SIZE"),
+        Column::new("PATH"),
+    ]);
+    for report in reports {
+        table.row(vec![
+            report.store.name().to_string(),
+            humanize::count(report.removal.entries),
+            humanize::bytes(report.removal.bytes),
+            report.path.display().to_string(),
+        ]);
+    }
+    table.print();
+    for report in reports.iter().filter(|r| !r.removal.dropped.is_empty()) {
+        println!("\nFrom the {}:", report.store.name());
+        for dropped in &report.removal.dropped {
+            println!("  - {}", dropped);
+        }
+    }
+}
+
+fn totals(reports: &[StoreReport]) -> (usize, u64) {
+    reports.iter().fold((0, 0), |(entries, bytes), r| {
+        (entries + r.removal.entries, bytes + r.removal.bytes)
+    })
+}
+
+fn print_json(
+    reports: &[StoreReport],
+    cutoff: DateTime<Utc>,
+    removed: bool,
+) -> Result<(), CliError> {
+    let document = serde_json::json!({
+        "cutoff": cutoff,
+        "removed": removed,
+        "stores": reports,
+    });
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&document).map_err(|e| CliError::parse("purge report", e))?
+    );
+    Ok(())
+}
+
+/// `purge`: report what is older than `older_than` in each of `targets`, and remove it once
+/// confirmed. With `dry_run` nothing is locked for longer than the count, or changed.
+pub fn run(
+    targets: &[Target],
+    older_than: chrono::Duration,
+    yes: bool,
+    dry_run: bool,
+    output: OutputFormat,
+) -> Result<(), CliError> {
+    let now = clock::now();
+    let cutoff = now - older_than;
+    let preview = survey(targets, cutoff, now, false)?;
+    let (entries, bytes) = totals(&preview);
+
+    if output != OutputFormat::Json {
+        println!(
+            "🧹 Local state older than {} (before {}):",
+            humanize::duration(older_than),
+            cutoff.format("%Y-%m-%d %H:%M UTC")
+        );
+        print_table(&preview);
+    }
+    if entries == 0 || dry_run {
+        if output == OutputFormat::Json {
+            return print_json(&preview, cutoff, false);
+        }
+        if entries == 0 {
+            println!("\n✨ Nothing to purge");
+        } else {
+            println!("\nDry run: nothing was removed");
+        }
+        return Ok(());
+    }
+
+    let question = format!(
+        "Remove {} entr{} ({})?",
+        humanize::count(entries),
+        if entries == 1 { "y" } else { "ies" },
+        humanize::bytes(bytes)
+    );
+    if !tty::confirm("purge", &question, yes)? {
+        if output == OutputFormat::Json {
+            return print_json(&preview, cutoff, false);
+        }
+        println!("Nothing removed");
+        return Ok(());
+    }
+
+    let removed = survey(targets, cutoff, now, true)?;
+    if output == OutputFormat::Json {
+        return print_json(&removed, cutoff, true);
+    }
+    let (entries, bytes) = totals(&removed);
+    println!(
+        "🗑️ Removed {} entr{} ({}) from {} store(s)",
+        humanize::count(entries),
+        if entries == 1 { "y" } else { "ies" },
+        humanize::bytes(bytes),
+        removed.iter().filter(|r| r.removal.entries > 0).count()
+    );
+    Ok(())
+}
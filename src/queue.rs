@@ -0,0 +1,106 @@
+use crate::client::JobRequestDTO;
+use crate::error::CliError;
+use crate::humanize;
+use crate::paths;
+use crate::purge::Removal;
+use crate::schema::{Format, Store};
+use crate::storage;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::path::PathBuf;
+
+/// `queue.json` layouts: version 1 was a bare array of jobs
+pub const STORE: Store = Store {
+    name: "offline queue",
+    format: Format::Json,
+    migrations: &[wrap_jobs],
+};
+
+fn wrap_jobs(jobs: Value) -> Result<Value, String> {
+    if !jobs.is_array() {
+        return Err("expected an array of jobs".to_string());
+    }
+    Ok(json!({ "jobs": jobs }))
+}
+
+#[derive(Deserialize)]
+struct Layout {
+    jobs: Vec<JobRequestDTO>,
+}
+
+/// Location of the offline queue file
+pub fn queue_path() -> PathBuf {
+    paths::queue_file()
+}
+
+/// Load every queued job, treating a missing file as an empty queue
+pub fn load() -> Result<Vec<JobRequestDTO>, CliError> {
+    Ok(STORE
+        .load::<Layout>(&queue_path())?
+        .map(|layout| layout.jobs)
+        .unwrap_or_default())
+}
+
+/// Replace the queue contents; callers hold the queue lock
+fn store(jobs: &[JobRequestDTO]) -> Result<(), CliError> {
+    STORE.write(&queue_path(), &json!({ "jobs": jobs }))
+}
+
+/// Append a job to the queue, returning the queue file path
+pub async fn enqueue(job: &JobRequestDTO) -> Result<PathBuf, CliError> {
+    let path = queue_path();
+    let _lock = storage::lock_async(&path).await?;
+    let mut jobs = load()?;
+    jobs.push(job.clone());
+    store(&jobs)?;
+    Ok(path)
+}
+
+/// After a flush of the first `taken` jobs, put back the ones that failed.
+/// Jobs queued by another process meanwhile stay behind them; returns the queue length.
+pub async fn settle(taken: usize, failed: Vec<JobRequestDTO>) -> Result<usize, CliError> {
+    let _lock = storage::lock_async(&queue_path()).await?;
+    let mut current = load()?;
+    let added = current.split_off(taken.min(current.len()));
+    let jobs = [failed, added].concat();
+    store(&jobs)?;
+    Ok(jobs.len())
+}
+
+/// "ISS from 2026-10-14 12:00:00 UTC to 12:10:00 UTC (10m)", as the queue lists a job
+pub fn describe(job: &JobRequestDTO) -> String {
+    format!(
+        "{} from {} to {} ({})",
+        job.label(),
+        humanize::timestamp(job.start),
+        job.end.format("%H:%M:%S UTC"),
+        humanize::window(job.start, job.end)
+    )
+}
+
+/// `purge`: drop the queued jobs whose start has passed by `now`, which the station would
+/// refuse anyway. Only counts unless `apply`.
+pub fn purge(now: DateTime<Utc>, apply: bool) -> Result<Removal, CliError> {
+    let path = queue_path();
+    let Ok(metadata) = std::fs::metadata(&path) else {
+        return Ok(Removal::default());
+    };
+    let _lock = storage::lock(&path)?;
+    let jobs = STORE
+        .peek::<Layout>(&path)?
+        .map(|layout| layout.jobs)
+        .unwrap_or_default();
+    let (started, waiting): (Vec<_>, Vec<_>) = jobs.into_iter().partition(|job| job.start < now);
+    let removal = Removal {
+        entries: started.len(),
+        bytes: metadata
+            .len()
+            .saturating_sub(STORE.size(&json!({ "jobs": waiting }))?),
+        dropped: started.iter().map(describe).collect(),
+    };
+    if apply && !started.is_empty() {
+        store(&waiting)?;
+    }
+    Ok(removal)
+}
@@ -0,0 +1,274 @@
+use crate::audit;
+use crate::baseline;
+use crate::capabilities;
+use crate::client::{ApiClient, JobType, TleData};
+use crate::clock;
+use crate::error::{CliError, ResultExt};
+use crate::findings;
+use crate::frequency::Frequency;
+use crate::hooks::HookRunner;
+use crate::humanize;
+use crate::job_request::JobRequestBuilder;
+use crate::latency;
+use crate::location::Location;
+use crate::maintenance;
+use crate::mask::ElevationMask;
+use crate::orbit::Satellite;
+use crate::predict::{self, Horizon, Pass};
+use crate::quiet_hours;
+use crate::regulatory::TxLimits;
+use crate::rotator;
+use crate::satellite_id::SatelliteId;
+use crate::satellites::{self, Preset};
+use crate::submit_window;
+use crate::tle;
+use crate::tty::RawMode;
+use chrono::{DateTime, Duration, SubsecRound, Utc};
+use crossterm::event::{self, Event, KeyEventKind};
+use std::io::{IsTerminal, Write};
+use std::time::Instant;
+
+/// How soon a pass must start when neither the profile nor `--within` says
+pub const DEFAULT_HORIZON_MINUTES: u64 = 10;
+/// Seconds the operator has to abort before the job is sent
+const COUNTDOWN_S: u64 = 5;
+/// A pass that rose this long ago still counts as in progress
+const PASS_LOOKBACK_MIN: i64 = 30;
+/// Searched past the horizon, so a pass starting just inside it is seen to its LOS
+const PASS_LENGTH_MAX_MIN: i64 = 60;
+
+/// Where a hot submission stands before the countdown
+pub struct Station<'a> {
+    pub client: &'a ApiClient,
+    pub hooks: &'a HookRunner,
+    pub location: Location,
+    pub mask: &'a ElevationMask,
+    pub limits: &'a TxLimits,
+}
+
+/// How to fetch the elements of `id` ahead of time, for the errors saying they are missing
+fn fetch_command(id: &SatelliteId) -> String {
+    match id {
+        SatelliteId::NoradId(norad_id) => format!("rustar-cli predict --norad-id {}", norad_id),
+        SatelliteId::IntlDesignator(designator) => {
+            format!("rustar-cli predict --intl-designator {}", designator)
+        }
+        SatelliteId::Name(name) => format!("rustar-cli predict --alias-group {}", name),
+    }
+}
+
+/// The alias's element set from the TLE cache, without touching the network
+fn cached_elements(name: &str, preset: &Preset) -> Result<TleData, CliError> {
+    let id = preset.id().ok_or_else(|| {
+        CliError::validation(
+            "satellite",
+            format!(
+                "alias '{}' has no NORAD ID or designator; set one with rustar-cli sat set-freq {} --norad-id <ID>",
+                name, name
+            ),
+        )
+    })?;
+    let (tle, age) = tle::cached(&id).ok_or_else(|| {
+        CliError::validation(
+            "satellite",
+            format!(
+                "no cached element set for {}; fetch one ahead of time with {}",
+                id,
+                fetch_command(&id)
+            ),
+        )
+    })?;
+    if age >= tle::CACHE_MAX_AGE {
+        eprintln!(
+            "⚠️ The cached elements for {} are {}h old; refresh them with {}",
+            id,
+            age.as_secs() / 3600,
+            fetch_command(&id)
+        );
+    }
+    Ok(TleData {
+        tle0: name.to_string(),
+        ..tle
+    })
+}
+
+/// RX and TX from the presets: a linear transponder pairs the uplink with the downlink
+fn preset_frequencies(name: &str, preset: &Preset) -> Result<(Frequency, Frequency), CliError> {
+    if let Some(passband) = &preset.transponder {
+        let downlink = preset
+            .rx_frequency
+            .filter(|hz| passband.uplink_for(*hz).is_ok())
+            .unwrap_or_else(|| passband.downlink_centre());
+        return Ok((downlink, passband.uplink_for(downlink)?));
+    }
+    let rx = preset.rx_frequency.ok_or_else(|| {
+        CliError::validation(
+            "rx_frequency",
+            format!(
+                "alias '{}' has no RX frequency; set one with rustar-cli sat set-freq {} --rx <FREQ>",
+                name, name
+            ),
+        )
+    })?;
+    Ok((rx, preset.tx_frequency.unwrap_or(Frequency::ZERO)))
+}
+
+/// The pass in progress at `now`, or else the first one starting within `within`
+fn imminent_pass(
+    tle: &TleData,
+    horizon: &Horizon,
+    now: DateTime<Utc>,
+    within: Duration,
+) -> Result<Option<Pass>, CliError> {
+    let satellite = Satellite::from_tle(tle)?;
+    let passes = predict::passes(
+        &satellite,
+        horizon,
+        now - Duration::minutes(PASS_LOOKBACK_MIN),
+        now + within + Duration::minutes(PASS_LENGTH_MAX_MIN),
+    )?;
+    Ok(passes.into_iter().find(|pass| match (pass.aos, pass.los) {
+        (Some(aos), Some(los)) => los > now && aos <= now + within,
+        _ => false,
+    }))
+}
+
+/// Count down on one line; false when a key was pressed to abort. Without a terminal
+/// there is no key to press, and only Ctrl-C stops it.
+fn countdown() -> Result<bool, CliError> {
+    let interactive = std::io::stdin().is_terminal() && std::io::stdout().is_terminal();
+    let Some(raw) = interactive.then(RawMode::enable).flatten() else {
+        println!("⏳ Submitting in {}s (Ctrl-C aborts)", COUNTDOWN_S);
+        std::thread::sleep(std::time::Duration::from_secs(COUNTDOWN_S));
+        return Ok(true);
+    };
+    let mut stdout = std::io::stdout();
+    for left in (1..=COUNTDOWN_S).rev() {
+        let _ = write!(stdout, "\r⏳ Submitting in {}s (any key aborts) ", left);
+        let _ = stdout.flush();
+        let deadline = Instant::now() + std::time::Duration::from_secs(1);
+        while let Some(timeout) = deadline.checked_duration_since(Instant::now()) {
+            if !event::poll(timeout).context("reading the keyboard")? {
+                break;
+            }
+            if let Event::Key(key) = event::read().context("reading the keyboard")? {
+                if key.kind == KeyEventKind::Press {
+                    let _ = write!(stdout, "\r\n");
+                    return Ok(false);
+                }
+            }
+        }
+    }
+    // Raw mode needs the carriage return spelled out
+    let _ = write!(stdout, "\r\n");
+    drop(raw);
+    Ok(true)
+}
+
+/// Schedule the alias's pass in progress, or the one starting within `within`, from
+/// now to LOS. Everything comes from the alias book and the TLE cache, the station
+/// defaults stand for the antenna, and a countdown stands for the confirmation.
+pub async fn run(station: Station<'_>, alias: &str, within: Duration) -> Result<(), CliError> {
+    let book = satellites::AliasBook::load()?;
+    let (name, preset) = satellites::lookup(&book, alias)?;
+    let tle = cached_elements(&name, &preset)?;
+    let (rx_frequency, tx_frequency) = preset_frequencies(&name, &preset)?;
+
+    let horizon = Horizon {
+        station: &station.location,
+        min_elevation_deg: 0.0,
+        mask: station.mask,
+    };
+    let now = clock::now();
+    let Some(pass) = imminent_pass(&tle, &horizon, now, within)? else {
+        return Err(CliError::validation(
+            "pass",
+            format!(
+                "no pass of {} is in progress or starts within {}; schedule a later one with rustar-cli predict --pick or rustar-cli jobs add --satellite {}",
+                name,
+                humanize::duration(within),
+                alias
+            ),
+        ));
+    };
+    let (Some(aos), Some(los)) = (pass.aos, pass.los) else {
+        unreachable!("only passes with an AOS and LOS are picked");
+    };
+
+    // The window opens when the countdown ends, in the whole seconds the station schedules in
+    let start = (now + Duration::seconds(COUNTDOWN_S as i64)).trunc_subsecs(0);
+    let end = los.trunc_subsecs(0);
+    let job = JobRequestBuilder::new(start, end, JobType::Tracking { tle })
+        .rx_frequency(rx_frequency)
+        .rx_offset(preset.rx_offset_hz)
+        .tx_frequency(tx_frequency)
+        .mode(preset.mode.clone())
+        .baud(preset.baud)
+        .tx_limits(station.limits)
+        .build()?;
+    if let Some(problem) = submit_window::check(job.start) {
+        return Err(problem.into());
+    }
+    quiet_hours::review(&[&job])?;
+    rotator::review(&[&job]).await?;
+    capabilities::review(station.client, &[&job]).await?;
+    if let Some(warning) = latency::check(station.client, job.start).await {
+        eprintln!("⏱️ {}", warning);
+    }
+    let windows = maintenance::windows(station.client).await;
+    if let Some(clash) = maintenance::intersecting(&windows, job.window()).first() {
+        return Err(CliError::Conflict {
+            message: Some(format!(
+                "the pass overlaps maintenance ({})",
+                maintenance::describe(clash)
+            )),
+            hint: Some(format!(
+                "only a person can schedule into maintenance; use rustar-cli jobs add --satellite {}",
+                alias
+            )),
+        });
+    }
+
+    let when = if aos <= now {
+        format!("in progress since {}", aos.format("%H:%M:%S UTC"))
+    } else {
+        format!("AOS {}", humanize::until(aos, now))
+    };
+    println!(
+        "🛰️ {} pass {}, max {:.1}°, LOS {}",
+        name,
+        when,
+        pass.max_elevation_deg,
+        los.format("%H:%M:%S UTC")
+    );
+    println!(
+        "📻 {} → {}, {}",
+        start.format("%H:%M:%S"),
+        end.format("%H:%M:%S UTC"),
+        preset.describe()
+    );
+    if let Some(rx) = job.describe_rx_offset() {
+        println!("📻 Applying the preset's receive offset: {}", rx);
+    }
+    if !countdown()? {
+        println!("🛑 Job not submitted");
+        return Ok(());
+    }
+
+    let response = station
+        .client
+        .add_job(&job)
+        .await
+        .with_context(|| format!("while scheduling {}", job.label()))?;
+    println!("✅ Job submitted successfully: {}", response.status());
+    if let Some(message) = &response.message {
+        println!("   {}", message);
+    }
+    let warned = findings::server(None, &response.warnings);
+    if let Some(id) = &response.id {
+        audit::job(id);
+    }
+    baseline::remember(&job).await;
+    station.hooks.after_submit(&job, &response).await;
+    warned
+}
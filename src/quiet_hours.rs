@@ -0,0 +1,267 @@
+use crate::audit;
+use crate::client::JobRequestDTO;
+use crate::config::{self, Resolved};
+use crate::error::CliError;
+use crate::stats::{self, DayBoundary};
+use crate::transcript::{self, Recorded};
+use crate::tty;
+use crate::validation::{Problem, Rule};
+use chrono::{DateTime, Duration, NaiveTime, Utc};
+use inquire::Text;
+use std::sync::OnceLock;
+
+/// What the operator types to transmit through quiet hours
+const CONFIRMATION: &str = "override";
+
+/// One daily span of quiet hours in station time, past midnight when `end` is not after `start`
+#[derive(Debug, Clone, Copy)]
+pub struct Window {
+    start: NaiveTime,
+    end: NaiveTime,
+}
+
+impl Window {
+    /// "22:00-06:00"
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let time = |part: &str| {
+            NaiveTime::parse_from_str(part.trim(), "%H:%M")
+                .map_err(|_| format!("'{}' is not a time of day like 22:00", part.trim()))
+        };
+        let (start, end) = text
+            .split_once('-')
+            .ok_or_else(|| format!("'{}' is not a span of the day like 22:00-06:00", text))?;
+        let window = Self {
+            start: time(start)?,
+            end: time(end)?,
+        };
+        if window.start == window.end {
+            return Err(format!("'{}' starts and ends at the same time", text));
+        }
+        Ok(window)
+    }
+
+    fn wraps(self) -> bool {
+        self.end <= self.start
+    }
+
+    /// How far `time` is from the span, 0 inside it
+    pub fn distance(self, time: NaiveTime) -> Duration {
+        let inside = match self.wraps() {
+            true => time >= self.start || time < self.end,
+            false => time >= self.start && time < self.end,
+        };
+        if inside {
+            return Duration::zero();
+        }
+        let day = Duration::days(1);
+        let forward = |from: NaiveTime, to: NaiveTime| {
+            let gap = to - from;
+            if gap < Duration::zero() {
+                gap + day
+            } else {
+                gap
+            }
+        };
+        forward(time, self.start).min(forward(self.end, time))
+    }
+}
+
+/// Times of day no uplink may be keyed, as the station's licence or neighbours require
+#[derive(Debug, Clone)]
+pub struct QuietHours {
+    windows: Vec<Window>,
+    zone: DayBoundary,
+    rule: Rule,
+    overriding: bool,
+}
+
+/// Set once from the profile and `--override-quiet-hours`
+static QUIET_HOURS: OnceLock<QuietHours> = OnceLock::new();
+
+impl QuietHours {
+    /// The profile's `quiet_hours`, in its `timezone`. Unlike days in stats this ignores
+    /// `--utc-only`, which changes how times are shown, not when the station must be quiet.
+    pub fn new(resolved: &Resolved, overriding: bool) -> Result<Option<Self>, CliError> {
+        let profile = &resolved.profile;
+        if profile.quiet_hours.is_empty() {
+            return Ok(None);
+        }
+        let invalid = |key: &str, reason: String| {
+            CliError::Configuration(format!(
+                "profiles.{}.{} in {}: {}",
+                resolved.profile_name.value,
+                key,
+                config::config_path().display(),
+                reason
+            ))
+        };
+        let windows = profile
+            .quiet_hours
+            .iter()
+            .map(|text| Window::parse(text))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|reason| invalid("quiet_hours", reason))?;
+        let zone = match &profile.timezone {
+            Some(zone) => {
+                stats::parse_timezone(zone).map_err(|reason| invalid("timezone", reason))?
+            }
+            None => DayBoundary::Local,
+        };
+        Ok(Some(Self {
+            windows,
+            zone,
+            rule: Rule::profile(
+                "quiet hours",
+                "quiet_hours",
+                profile.quiet_hours.join(", "),
+                true,
+            )
+            .overridden_by("--override-quiet-hours"),
+            overriding,
+        }))
+    }
+
+    /// The parts of `[start, end)` inside quiet hours, earliest first, touching spans joined
+    fn overlaps(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+        let mut spans = Vec::new();
+        // A window that wraps begins the evening before the first day the job touches
+        let mut date = self.zone.date(start).pred_opt();
+        let last = self.zone.date(end);
+        while let Some(day) = date.filter(|day| *day <= last) {
+            for window in &self.windows {
+                let closes_on = match window.wraps() {
+                    true => day.succ_opt(),
+                    false => Some(day),
+                };
+                let opens = self.zone.at(day, window.start);
+                let closes = closes_on.and_then(|closes_on| self.zone.at(closes_on, window.end));
+                // A time skipped by a clock change has nothing to intersect
+                let (Some(opens), Some(closes)) = (opens, closes) else {
+                    continue;
+                };
+                let (from, to) = (opens.max(start), closes.min(end));
+                if from < to {
+                    spans.push((from, to));
+                }
+            }
+            date = day.succ_opt();
+        }
+        spans.sort();
+        let mut joined: Vec<(DateTime<Utc>, DateTime<Utc>)> = Vec::new();
+        for (from, to) in spans {
+            match joined.last_mut() {
+                Some(last) if from <= last.1 => last.1 = last.1.max(to),
+                _ => joined.push((from, to)),
+            }
+        }
+        joined
+    }
+
+    /// "2025-09-01 22:30 to 23:10 station time (2025-09-01 20:30 to 21:10 UTC)"
+    fn describe(&self, (from, to): (DateTime<Utc>, DateTime<Utc>)) -> String {
+        let utc = format!(
+            "{} to {} UTC",
+            from.format("%Y-%m-%d %H:%M"),
+            to.format("%H:%M")
+        );
+        match self.zone {
+            DayBoundary::Utc => utc,
+            zone => format!(
+                "{} to {} station time ({})",
+                zone.wall_clock(from).format("%Y-%m-%d %H:%M"),
+                zone.wall_clock(to).format("%H:%M"),
+                utc
+            ),
+        }
+    }
+
+    fn overlap(&self, job: &JobRequestDTO) -> Option<String> {
+        let spans = self.overlaps(job.start, job.end);
+        (!spans.is_empty()).then(|| {
+            spans
+                .into_iter()
+                .map(|span| self.describe(span))
+                .collect::<Vec<_>>()
+                .join(", ")
+        })
+    }
+}
+
+pub fn set(quiet_hours: QuietHours) {
+    let _ = QUIET_HOURS.set(quiet_hours);
+}
+
+/// Why `job` may not transmit when it does: the part of its window in quiet hours.
+/// Receive-only jobs are never held up.
+pub fn check(job: &JobRequestDTO) -> Option<Problem> {
+    let quiet = QUIET_HOURS.get()?;
+    if job.tx_frequency.is_zero() || quiet.overriding {
+        return None;
+    }
+    let overlap = quiet.overlap(job)?;
+    Some(
+        Problem::new(
+            "window",
+            format!(
+                "the job transmits during quiet hours, {}; move it, or pass \
+                 --override-quiet-hours to transmit anyway",
+                overlap
+            ),
+        )
+        .breaking(quiet.rule.clone()),
+    )
+}
+
+/// The note for a receive-only `job` in quiet hours, which goes ahead regardless
+pub fn receiving(job: &JobRequestDTO) -> Option<String> {
+    if !job.tx_frequency.is_zero() {
+        return None;
+    }
+    let overlap = QUIET_HOURS.get()?.overlap(job)?;
+    Some(format!(
+        "Receive-only, so allowed in quiet hours: {}",
+        overlap
+    ))
+}
+
+/// Before `jobs` are submitted with `--override-quiet-hours`: have the operator type the
+/// confirmation for the ones that transmit in quiet hours, recording each in the audit log
+pub fn review(jobs: &[&JobRequestDTO]) -> Result<(), CliError> {
+    let Some(quiet) = QUIET_HOURS.get() else {
+        return Ok(());
+    };
+    let transmitting: Vec<String> = jobs
+        .iter()
+        .filter(|job| !job.tx_frequency.is_zero())
+        .filter_map(|job| quiet.overlap(job))
+        .collect();
+    // Without the override `check` has refused these already
+    if transmitting.is_empty() || !quiet.overriding {
+        return Ok(());
+    }
+    for overlap in &transmitting {
+        eprintln!("🌙 Transmits during quiet hours: {}", overlap);
+        transcript::warning(format!("Transmits during quiet hours: {}", overlap));
+    }
+    tty::require("--override-quiet-hours", &[])?;
+    let question = format!("Type {} to transmit during quiet hours:", CONFIRMATION);
+    let typed = Text::new(&question).prompt().recorded(&question)?;
+    if typed.trim() != CONFIRMATION {
+        return Err(CliError::validation(
+            "confirmation",
+            format!(
+                "'{}' is not '{}'; nothing was submitted",
+                typed.trim(),
+                CONFIRMATION
+            ),
+        ));
+    }
+    for overlap in &transmitting {
+        audit::quiet_hours_override(overlap);
+    }
+    Ok(())
+}
@@ -0,0 +1,516 @@
+//! Submission receipts, for showing an auditor what was submitted and when. Each
+//! accepted submission leaves a numbered file with the request in canonical form, the
+//! server's answer, the request ID and the times, and the hash of the receipt before it,
+//! so a receipt removed, changed or put out of order breaks the chain `receipts verify`
+//! walks. A profile may also have each receipt signed with a minisign key.
+//!
+//! Writing a receipt never fails a submission: the job is on the station either way, so
+//! anything that goes wrong is a warning, printed where it cannot be missed.
+
+use crate::client::{ApiResponse, JobRequestDTO};
+use crate::clock;
+use crate::error::{CliError, ResultExt};
+use crate::humanize;
+use crate::paths;
+use crate::reproducible;
+use crate::storage;
+use crate::OutputFormat;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// The `previous` of the first receipt in a chain
+pub const GENESIS: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// How long the signing or verifying tool may take before it is abandoned
+const SIGNER_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Layout of the receipt files; changing what is hashed needs a new one
+const FORMAT: u32 = 1;
+
+/// The profile's `receipt_signing`: a minisign secret key to sign each receipt with, and
+/// the public key `receipts verify` checks the signatures against
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Signing {
+    pub secret_key: Option<PathBuf>,
+    pub public_key: Option<PathBuf>,
+    /// The minisign executable ("minisign" when unset)
+    pub command: Option<String>,
+}
+
+impl Signing {
+    fn command(&self) -> &str {
+        self.command.as_deref().unwrap_or("minisign")
+    }
+}
+
+/// What every receipt of this invocation records besides the submission
+#[derive(Debug, Clone)]
+pub struct Settings {
+    pub base_url: String,
+    pub profile: String,
+    pub signing: Signing,
+}
+
+/// This invocation's settings; none when the profile turns receipts off
+static SETTINGS: OnceLock<Option<Settings>> = OnceLock::new();
+
+/// Write receipts under `settings`, unless the profile's `job_receipts` is false
+pub fn set(enabled: bool, settings: Settings) {
+    let _ = SETTINGS.set(enabled.then_some(settings));
+}
+
+/// One accepted submission, linked to the receipt before it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Receipt {
+    pub format: u32,
+    /// Place in the chain, from 1
+    pub sequence: u64,
+    /// When the request was sent, and when the server's answer was in
+    pub submitted_at: DateTime<Utc>,
+    pub answered_at: DateTime<Utc>,
+    pub cli_version: String,
+    pub base_url: String,
+    pub profile: String,
+    /// The server's `X-Request-Id` for the submission, when it sent one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub job_id: Option<String>,
+    /// The body as sent, with its keys sorted
+    pub request: Value,
+    /// Everything the server answered
+    pub response: Value,
+    /// `hash` of the receipt before this one, `GENESIS` for the first
+    pub previous: String,
+    /// SHA-256 of the receipt's canonical JSON without this field, in hex
+    pub hash: String,
+}
+
+/// The newest receipt's place and hash, so removing it from the end is noticed too
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Head {
+    sequence: u64,
+    hash: String,
+}
+
+fn head_path(dir: &Path) -> PathBuf {
+    dir.join("head.json")
+}
+
+fn receipt_path(dir: &Path, sequence: u64) -> PathBuf {
+    dir.join(format!("{:06}.json", sequence))
+}
+
+fn signature_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".minisig");
+    PathBuf::from(name)
+}
+
+/// `value` with the keys of every object sorted, so equal documents serialize alike
+pub fn canonical(value: &Value) -> Value {
+    match value {
+        Value::Object(object) => {
+            let mut keys: Vec<&String> = object.keys().collect();
+            keys.sort();
+            let mut sorted = Map::new();
+            for key in keys {
+                sorted.insert(key.clone(), canonical(&object[key]));
+            }
+            Value::Object(sorted)
+        }
+        Value::Array(items) => Value::Array(items.iter().map(canonical).collect()),
+        other => other.clone(),
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// SHA-256 over the compact canonical JSON of `receipt` with its `hash` left out
+fn digest(receipt: &Receipt) -> Result<String, CliError> {
+    let mut value = serde_json::to_value(receipt).map_err(|e| CliError::parse("a receipt", e))?;
+    if let Value::Object(object) = &mut value {
+        object.remove("hash");
+    }
+    let text =
+        serde_json::to_string(&canonical(&value)).map_err(|e| CliError::parse("a receipt", e))?;
+    Ok(hex(ring::digest::digest(
+        &ring::digest::SHA256,
+        text.as_bytes(),
+    )
+    .as_ref()))
+}
+
+/// Numbers of the receipts in `dir`, in order
+fn sequences(dir: &Path) -> Result<Vec<u64>, CliError> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("reading {}", dir.display())),
+    };
+    let mut found: Vec<u64> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            name.strip_suffix(".json")?.parse().ok()
+        })
+        .collect();
+    found.sort_unstable();
+    Ok(found)
+}
+
+fn load(path: &Path) -> Result<Receipt, CliError> {
+    let contents =
+        std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    serde_json::from_str(&contents)
+        .map_err(|e| CliError::parse(format!("receipt {}", path.display()), e))
+}
+
+/// Write the receipt for `job`, submitted at `submitted_at` and answered with `response`.
+/// Failures are warnings.
+pub async fn write(
+    job: &JobRequestDTO,
+    response: &ApiResponse,
+    request_id: Option<&str>,
+    submitted_at: DateTime<Utc>,
+) {
+    let Some(Some(settings)) = SETTINGS.get() else {
+        return;
+    };
+    let written = (|| {
+        let dir = paths::receipts_dir();
+        let _lock = storage::lock(&dir.join("chain"))?;
+        let (sequence, previous) = match sequences(&dir)?.last() {
+            Some(&last) => (last + 1, load(&receipt_path(&dir, last))?.hash),
+            None => (1, GENESIS.to_string()),
+        };
+        let mut receipt = Receipt {
+            format: FORMAT,
+            sequence,
+            submitted_at,
+            answered_at: clock::now(),
+            cli_version: env!("CARGO_PKG_VERSION").to_string(),
+            base_url: settings.base_url.clone(),
+            profile: settings.profile.clone(),
+            request_id: request_id.map(str::to_string),
+            job_id: response.id.clone(),
+            request: canonical(&serde_json::to_value(job).unwrap_or_default()),
+            response: serde_json::to_value(response).unwrap_or_default(),
+            previous,
+            hash: String::new(),
+        };
+        receipt.hash = digest(&receipt)?;
+        let path = receipt_path(&dir, sequence);
+        let contents = serde_json::to_string_pretty(&receipt)
+            .map_err(|e| CliError::parse("the receipt", e))?;
+        storage::write_atomic(&path, contents + "\n")?;
+        let head = Head {
+            sequence,
+            hash: receipt.hash.clone(),
+        };
+        let head = serde_json::to_string_pretty(&head).map_err(|e| CliError::parse("head", e))?;
+        storage::write_atomic(&head_path(&dir), head + "\n")?;
+        Ok::<_, CliError>(path)
+    })();
+    match written {
+        Ok(path) => {
+            if let Some(secret_key) = &settings.signing.secret_key {
+                if let Err(e) = sign(&settings.signing, secret_key, &path).await {
+                    eprintln!(
+                        "⚠️⚠️ The receipt {} was written but not signed: {}",
+                        path.display(),
+                        e.full_message()
+                    );
+                }
+            }
+        }
+        Err(e) => eprintln!(
+            "⚠️⚠️ No receipt was written for the {} just submitted: {}. The job is scheduled; \
+             keep the output above as the record of it.",
+            job.label(),
+            e.full_message()
+        ),
+    }
+}
+
+/// Run the minisign command with `args`, for up to `SIGNER_TIMEOUT`, without a terminal
+/// to ask for a password on
+async fn minisign(signing: &Signing, args: &[&std::ffi::OsStr]) -> Result<(), CliError> {
+    let command = signing.command();
+    let child = tokio::process::Command::new(command)
+        .args(args)
+        .envs(reproducible::child_env().iter().copied())
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .with_context(|| format!("running {}", command))?;
+    let output = tokio::time::timeout(SIGNER_TIMEOUT, child.wait_with_output())
+        .await
+        .map_err(|_| {
+            CliError::Configuration(format!(
+                "{} did not finish within {}; a key with a password cannot be used here",
+                command,
+                humanize::duration(chrono::Duration::from_std(SIGNER_TIMEOUT).unwrap_or_default())
+            ))
+        })?
+        .with_context(|| format!("waiting for {}", command))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let said = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    Err(CliError::Configuration(format!(
+        "{} failed ({}){}",
+        command,
+        output.status,
+        if said.is_empty() {
+            String::new()
+        } else {
+            format!(": {}", said)
+        }
+    )))
+}
+
+/// Sign the receipt at `path` into `<path>.minisig`, written whole or not at all
+async fn sign(signing: &Signing, secret_key: &Path, path: &Path) -> Result<(), CliError> {
+    let signature = signature_path(path);
+    let mut temp = signature.as_os_str().to_os_string();
+    temp.push(format!(".tmp{}", std::process::id()));
+    let temp = PathBuf::from(temp);
+    let result = minisign(
+        signing,
+        &[
+            "-S".as_ref(),
+            "-s".as_ref(),
+            secret_key.as_os_str(),
+            "-m".as_ref(),
+            path.as_os_str(),
+            "-x".as_ref(),
+            temp.as_os_str(),
+        ],
+    )
+    .await
+    .and_then(|()| {
+        std::fs::rename(&temp, &signature)
+            .with_context(|| format!("writing {}", signature.display()))
+    });
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp);
+    }
+    result
+}
+
+/// Where a chain stops holding
+#[derive(Debug, Clone, Serialize)]
+struct Break {
+    sequence: u64,
+    file: String,
+    problem: String,
+}
+
+/// What `receipts verify` found
+#[derive(Debug, Clone, Serialize)]
+struct Verification {
+    dir: String,
+    receipts: usize,
+    signatures_checked: usize,
+    unsigned: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    broken: Option<Break>,
+}
+
+/// The first place the chain in `dir` does not hold, with the receipts and signatures
+/// checked before it
+async fn walk(dir: &Path, signing: Option<&Signing>) -> Result<Verification, CliError> {
+    let mut verification = Verification {
+        dir: dir.display().to_string(),
+        receipts: 0,
+        signatures_checked: 0,
+        unsigned: 0,
+        broken: None,
+    };
+    let found = sequences(dir)?;
+    let broken = |sequence: u64, problem: String| Break {
+        sequence,
+        file: receipt_path(dir, sequence).display().to_string(),
+        problem,
+    };
+    let mut previous = GENESIS.to_string();
+    for (expected, &sequence) in (1..).zip(&found) {
+        if sequence != expected {
+            verification.broken = Some(broken(
+                expected,
+                match sequence - expected {
+                    1 => format!("receipt {} is missing", expected),
+                    n => format!("receipts {} to {} are missing", expected, expected + n - 1),
+                },
+            ));
+            return Ok(verification);
+        }
+        let path = receipt_path(dir, sequence);
+        let receipt = match load(&path) {
+            Ok(receipt) => receipt,
+            Err(e) => {
+                verification.broken = Some(broken(
+                    sequence,
+                    format!("it cannot be read: {}", e.full_message()),
+                ));
+                return Ok(verification);
+            }
+        };
+        let problem = if receipt.sequence != sequence {
+            Some(format!(
+                "it says it is receipt {}, so it was renamed or copied",
+                receipt.sequence
+            ))
+        } else if digest(&receipt)? != receipt.hash {
+            Some(
+                "its contents no longer match its hash: it was changed after it was written"
+                    .to_string(),
+            )
+        } else if receipt.previous != previous {
+            Some(format!(
+                "it does not follow receipt {}: one of the two was replaced",
+                sequence - 1
+            ))
+        } else {
+            None
+        };
+        if let Some(problem) = problem {
+            verification.broken = Some(broken(sequence, problem));
+            return Ok(verification);
+        }
+        if let Some(signing) = signing {
+            let signature = signature_path(&path);
+            if signature.exists() {
+                if let Err(e) = check_signature(signing, &path, &signature).await {
+                    verification.broken = Some(broken(
+                        sequence,
+                        format!("its signature does not verify: {}", e.full_message()),
+                    ));
+                    return Ok(verification);
+                }
+                verification.signatures_checked += 1;
+            } else {
+                verification.unsigned += 1;
+            }
+        }
+        previous = receipt.hash;
+        verification.receipts += 1;
+    }
+
+    let head: Option<Head> = match std::fs::read_to_string(head_path(dir)) {
+        Ok(contents) => serde_json::from_str(&contents).ok(),
+        Err(_) => None,
+    };
+    let last = found.last().copied().unwrap_or(0);
+    match head {
+        Some(head) if head.sequence > last => {
+            verification.broken = Some(broken(
+                last + 1,
+                match head.sequence - last {
+                    1 => format!("the newest receipt, {}, was removed", head.sequence),
+                    _ => format!(
+                        "the newest receipts, {} to {}, were removed",
+                        last + 1,
+                        head.sequence
+                    ),
+                },
+            ));
+        }
+        Some(head) if head.sequence == last && head.hash != previous => {
+            verification.broken = Some(broken(
+                last,
+                "it is not the receipt last written: it was replaced".to_string(),
+            ));
+        }
+        None if last > 0 => {
+            verification.broken = Some(broken(
+                last,
+                format!(
+                    "{} is missing or unreadable, so receipts removed from the end would go unseen",
+                    head_path(dir).display()
+                ),
+            ));
+        }
+        _ => {}
+    }
+    Ok(verification)
+}
+
+async fn check_signature(signing: &Signing, path: &Path, signature: &Path) -> Result<(), CliError> {
+    let Some(public_key) = &signing.public_key else {
+        return Ok(());
+    };
+    minisign(
+        signing,
+        &[
+            "-V".as_ref(),
+            "-q".as_ref(),
+            "-p".as_ref(),
+            public_key.as_os_str(),
+            "-m".as_ref(),
+            path.as_os_str(),
+            "-x".as_ref(),
+            signature.as_os_str(),
+        ],
+    )
+    .await
+}
+
+/// `receipts verify`: walk the chain in `dir` and report the first break. Ok(true) when
+/// it breaks, for the exit status.
+pub async fn verify(dir: &Path, signing: &Signing, output: OutputFormat) -> Result<bool, CliError> {
+    let checks_signatures = signing.public_key.is_some().then_some(signing);
+    let verification = walk(dir, checks_signatures).await?;
+    let broken = verification.broken.is_some();
+    if output == OutputFormat::Json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&json!(verification))
+                .map_err(|e| CliError::parse("the verification", e))?
+        );
+        return Ok(broken);
+    }
+
+    match &verification.broken {
+        Some(at) => {
+            println!(
+                "❌ The receipt chain in {} breaks at receipt {}:",
+                verification.dir, at.sequence
+            );
+            println!("   {}", at.problem);
+            println!("   ({})", at.file);
+            println!(
+                "   {} receipt(s) before it hold.",
+                humanize::count(verification.receipts)
+            );
+        }
+        None if verification.receipts == 0 => {
+            println!("📭 No receipts in {}", verification.dir);
+        }
+        None => {
+            println!(
+                "✅ {} receipt(s) in {} form an unbroken chain",
+                humanize::count(verification.receipts),
+                verification.dir
+            );
+        }
+    }
+    if checks_signatures.is_some() {
+        println!(
+            "   🔏 {} signature(s) verified, {} receipt(s) unsigned",
+            humanize::count(verification.signatures_checked),
+            humanize::count(verification.unsigned)
+        );
+    }
+    Ok(broken)
+}
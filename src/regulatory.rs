@@ -0,0 +1,235 @@
+use crate::client::JobRequestDTO;
+use crate::frequency::{self, Frequency};
+use crate::humanize;
+use crate::validation::{Problem, Rule};
+use serde::Deserialize;
+use std::sync::OnceLock;
+
+/// Lowest frequency the station hardware can tune (1 MHz)
+pub const MIN_FREQUENCY: Frequency = Frequency::from_hz(1_000_000);
+/// Highest frequency the station hardware can tune (30 GHz)
+pub const MAX_FREQUENCY: Frequency = Frequency::from_hz(30_000_000_000);
+/// Largest receive offset accepted when the profile sets no `max_rx_offset_hz` (50 kHz)
+pub const DEFAULT_MAX_RX_OFFSET: Frequency = Frequency::from_hz(50_000);
+
+/// The profile's `max_rx_offset_hz`, set once
+static MAX_RX_OFFSET: OnceLock<Option<u64>> = OnceLock::new();
+
+/// The profile's `max_rx_offset_hz`
+pub fn set_max_rx_offset(max: Option<u64>) {
+    let _ = MAX_RX_OFFSET.set(max);
+}
+
+/// What the station licence allows on the uplink, from the profile's `[tx_limits]` table
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(default)]
+pub struct TxLimits {
+    /// Highest permitted uplink power, in dBm EIRP
+    pub max_power_dbm: Option<f64>,
+    /// Largest share of a window the uplink may be keyed, 0 to 1
+    pub max_duty_cycle: Option<f64>,
+    /// Power given to TX jobs that do not set one
+    pub default_power_dbm: Option<f64>,
+}
+
+pub fn check_frequency(field: &str, hz: Frequency, optional: bool) -> Option<Problem> {
+    if optional && hz.is_zero() {
+        return None;
+    }
+    if !(MIN_FREQUENCY..=MAX_FREQUENCY).contains(&hz) {
+        return Some(
+            Problem::new(
+                field,
+                format!(
+                    "{} Hz is outside the supported range {}–{} Hz",
+                    hz.hz(),
+                    MIN_FREQUENCY.hz(),
+                    MAX_FREQUENCY.hz()
+                ),
+            )
+            .breaking(Rule::builtin(
+                "supported frequency range",
+                format!("{}–{}", MIN_FREQUENCY, MAX_FREQUENCY),
+            )),
+        );
+    }
+    None
+}
+
+/// A receive offset within the profile's bound, on a job that listens at all
+pub fn check_rx_offset(job: &JobRequestDTO) -> Option<Problem> {
+    let offset = job.rx_offset_hz?;
+    if job.rx_frequency.is_zero() {
+        return Some(Problem::new(
+            "rx_offset_hz",
+            "set on a job with no RX frequency",
+        ));
+    }
+    check_offset(offset)
+}
+
+/// Why `offset` is further off a published downlink than the profile allows
+pub fn check_offset(offset: i64) -> Option<Problem> {
+    let set = MAX_RX_OFFSET.get().copied().flatten();
+    let max = set.map_or(DEFAULT_MAX_RX_OFFSET, Frequency::from_hz);
+    if offset.unsigned_abs() <= max.hz() {
+        return None;
+    }
+    Some(
+        Problem::new(
+            "rx_offset_hz",
+            format!(
+                "{} is further off the published downlink than ±{}",
+                frequency::describe_offset(offset),
+                humanize::frequency_step(max.hz())
+            ),
+        )
+        .breaking(Rule::profile(
+            "receive offset",
+            "max_rx_offset_hz",
+            format!("±{}", humanize::frequency_step(max.hz())),
+            set.is_some(),
+        )),
+    )
+}
+
+/// "25%", "12.5%"
+fn percent(fraction: f64) -> String {
+    format!("{}%", (fraction * 1000.0).round() / 10.0)
+}
+
+/// `--duty-cycle`: a percentage ("25%") or a fraction ("0.25")
+pub fn parse_duty_cycle(input: &str) -> Result<f64, String> {
+    let trimmed = input.trim();
+    let (number, scale) = match trimmed.strip_suffix('%') {
+        Some(number) => (number.trim(), 100.0),
+        None => (trimmed, 1.0),
+    };
+    let value = number
+        .parse::<f64>()
+        .map_err(|e| format!("'{}' is not a number: {}", trimmed, e))?
+        / scale;
+    if !(value > 0.0 && value <= 1.0) {
+        return Err(format!(
+            "'{}' must be a fraction up to 1 or a percentage up to 100%",
+            trimmed
+        ));
+    }
+    Ok(value)
+}
+
+/// Power and duty cycle on their own: sensible values, and only on jobs that transmit
+pub fn check_transmit(job: &JobRequestDTO) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    if job.tx_frequency.is_zero() {
+        for (field, set) in [
+            ("tx_power_dbm", job.tx_power_dbm.is_some()),
+            ("tx_duty_cycle", job.tx_duty_cycle.is_some()),
+        ] {
+            if set {
+                problems.push(Problem::new(field, "set on a job with no TX frequency"));
+            }
+        }
+        return problems;
+    }
+    if let Some(power) = job.tx_power_dbm {
+        if !power.is_finite() {
+            problems.push(Problem::new("tx_power_dbm", "must be a finite number"));
+        }
+    }
+    if let Some(duty) = job.tx_duty_cycle {
+        if !(duty > 0.0 && duty <= 1.0) {
+            problems.push(Problem::new(
+                "tx_duty_cycle",
+                format!("{} is outside (0, 1]", duty),
+            ));
+        }
+    }
+    problems
+}
+
+/// Give a TX job without an explicit power the profile default
+pub fn apply_defaults(job: &mut JobRequestDTO, limits: &TxLimits) {
+    if !job.tx_frequency.is_zero() && job.tx_power_dbm.is_none() {
+        job.tx_power_dbm = limits.default_power_dbm;
+    }
+}
+
+/// Check a TX job against the licence limits, quoting the limit it exceeds
+pub fn check_limits(job: &JobRequestDTO, limits: &TxLimits) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    if job.tx_frequency.is_zero() {
+        return problems;
+    }
+    if let Some(max) = limits.max_power_dbm {
+        let rule = Rule::profile(
+            "licensed uplink power",
+            "tx_limits.max_power_dbm",
+            format!("{} dBm EIRP", max),
+            true,
+        );
+        match job.tx_power_dbm {
+            Some(power) if power > max => problems.push(Problem::new(
+                "tx_power_dbm",
+                format!(
+                    "{} dBm exceeds the licensed maximum of {} dBm EIRP",
+                    power, max
+                ),
+            )
+            .breaking(rule)),
+            Some(_) => {}
+            None => problems.push(Problem::new(
+                "tx_power_dbm",
+                format!(
+                    "must be set: the licence caps the uplink at {} dBm EIRP (or set tx_limits.default_power_dbm)",
+                    max
+                ),
+            )
+            .breaking(rule)),
+        }
+    }
+    if let Some(max) = limits.max_duty_cycle {
+        // An uplink without a duty cycle is keyed for the whole window
+        let duty = job.tx_duty_cycle.unwrap_or(1.0);
+        if duty > max {
+            problems.push(
+                Problem::new(
+                    "tx_duty_cycle",
+                    format!(
+                        "{}{} exceeds the licensed maximum of {}",
+                        percent(duty),
+                        if job.tx_duty_cycle.is_none() {
+                            " (not set, so continuous)"
+                        } else {
+                            ""
+                        },
+                        percent(max)
+                    ),
+                )
+                .breaking(Rule::profile(
+                    "licensed duty cycle",
+                    "tx_limits.max_duty_cycle",
+                    percent(max),
+                    true,
+                )),
+            );
+        }
+    }
+    problems
+}
+
+/// "TX 437.5 MHz at 30 dBm, 25% duty cycle", for jobs that transmit
+pub fn describe_transmit(job: &JobRequestDTO) -> Option<String> {
+    if job.tx_frequency.is_zero() {
+        return None;
+    }
+    let power = match job.tx_power_dbm {
+        Some(power) => format!("{} dBm", power),
+        None => "station default power".to_string(),
+    };
+    let duty = match job.tx_duty_cycle {
+        Some(duty) => format!("{} duty cycle", percent(duty)),
+        None => "continuous".to_string(),
+    };
+    Some(format!("TX {} at {}, {}", job.tx_frequency, power, duty))
+}
@@ -0,0 +1,437 @@
+//! Pass reports: `report` puts one job's definition, what the station says it produced, the
+//! pass geometry predicted for its window and any notes into a single document, as
+//! Markdown, JSON or a team's own template. A source that cannot be had (a server without
+//! results, a profile without a station position) leaves its section out with a note
+//! saying why; only the job itself is required.
+
+use crate::client::{ApiClient, JobDTO, JobRequestDTO, JobResults, JobType};
+use crate::clock;
+use crate::error::{CliError, ResultExt};
+use crate::humanize;
+use crate::location::Location;
+use crate::mask::ElevationMask;
+use crate::orbit::Satellite;
+use crate::output::OutputTarget;
+use crate::predict::{self, Horizon};
+use crate::text;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt::Write as _;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ReportFormat {
+    /// Headed sections, for pasting into a wiki or a mail
+    Markdown,
+    /// One object with every section; the same names a template uses
+    Json,
+}
+
+/// Everything the report says, in the shape `--format json` writes and templates name
+#[derive(Debug, Serialize)]
+struct Report {
+    generated_at: DateTime<Utc>,
+    job: JobSection,
+    /// Null when the server has no results for the job
+    outcome: Option<JobResults>,
+    /// Null when there is no pass to predict, or nowhere to predict it from
+    geometry: Option<Geometry>,
+    notes: Vec<String>,
+    /// Sections left out, each with why
+    omitted: Vec<String>,
+}
+
+/// Names a template can start a placeholder with
+const SECTIONS: [&str; 6] = [
+    "generated_at",
+    "job",
+    "outcome",
+    "geometry",
+    "notes",
+    "omitted",
+];
+
+#[derive(Debug, Serialize)]
+struct JobSection {
+    id: String,
+    status: Option<String>,
+    /// The satellite, or the kind of job when nothing is tracked
+    label: String,
+    #[serde(flatten)]
+    job: JobRequestDTO,
+}
+
+/// The pass as predicted over the job's window, against the profile's mask
+#[derive(Debug, Serialize)]
+struct Geometry {
+    max_elevation_deg: f64,
+    max_elevation_at: DateTime<Utc>,
+    /// First and last moment above the mask within the window
+    aos: Option<DateTime<Utc>>,
+    los: Option<DateTime<Utc>>,
+    above_mask_seconds: i64,
+}
+
+/// Where the report's pieces come from besides the server
+pub struct Sources<'a> {
+    /// The station position, or why the profile gives none
+    pub station: Result<Location, String>,
+    pub mask: &'a ElevationMask,
+    /// Given with --notes, before whatever the server keeps
+    pub notes: Option<String>,
+}
+
+/// `report`: gather job `id`'s report and write it to `output` as `format`, or through
+/// the template at `template`
+pub async fn run(
+    client: &ApiClient,
+    id: &str,
+    sources: Sources<'_>,
+    format: ReportFormat,
+    template: Option<&Path>,
+    output: &OutputTarget,
+) -> Result<(), CliError> {
+    output.check()?;
+    // Read first, so a missing template is not found out after the requests
+    let template = template
+        .map(|path| {
+            std::fs::read_to_string(path)
+                .with_context(|| format!("reading the template {}", path.display()))
+        })
+        .transpose()?;
+
+    let value = client.job_json(id).await?;
+    if value.is_null() {
+        return Err(CliError::NotFound {
+            message: Some(format!("the server returned no body for job {}", id)),
+        });
+    }
+    let stored: JobDTO = serde_json::from_value(value.clone())
+        .map_err(|e| CliError::parse(format!("job {}", id), e))?;
+
+    let mut omitted = Vec::new();
+    let outcome = client.job_results(id).await?;
+    if outcome.is_none() {
+        omitted.push(format!(
+            "outcome: the server keeps no results for job {}",
+            id
+        ));
+    }
+    let geometry = match geometry(&stored, &sources) {
+        Ok(geometry) => Some(geometry),
+        Err(why) => {
+            omitted.push(format!("predicted pass: {}", why));
+            None
+        }
+    };
+    let notes = sources
+        .notes
+        .into_iter()
+        .chain(value["notes"].as_str().map(str::to_string))
+        .chain(outcome.as_ref().and_then(|outcome| outcome.notes.clone()))
+        .filter(|note| !note.trim().is_empty())
+        .collect();
+
+    let report = Report {
+        generated_at: clock::now(),
+        job: JobSection {
+            id: id.to_string(),
+            status: stored.status,
+            label: stored.job.label().to_string(),
+            job: stored.job,
+        },
+        outcome,
+        geometry,
+        notes,
+        omitted,
+    };
+    for why in &report.omitted {
+        eprintln!("ℹ️ Left out of the report: {}", why);
+    }
+
+    let document = match (template, format) {
+        (Some(template), _) => {
+            let value =
+                serde_json::to_value(&report).map_err(|e| CliError::parse("the report", e))?;
+            render(&template, &value)?
+        }
+        (None, ReportFormat::Json) => {
+            let mut text = serde_json::to_string_pretty(&report)
+                .map_err(|e| CliError::parse("the report", e))?;
+            text.push('\n');
+            text
+        }
+        (None, ReportFormat::Markdown) => markdown(&report),
+    };
+    output.write(document)
+}
+
+/// The pass over the job's window, or why there is none to give
+fn geometry(stored: &JobDTO, sources: &Sources) -> Result<Geometry, String> {
+    let tle = match &stored.job.job_type {
+        JobType::Tracking { tle } => tle,
+        JobType::Pointing { pointing } => {
+            return Err(format!(
+                "the job holds the antenna at az {}°, el {}°, with no pass to predict",
+                pointing.azimuth, pointing.elevation
+            ))
+        }
+        other => return Err(format!("a {} job follows no satellite", other.name())),
+    };
+    let station = sources.station.as_ref().map_err(Clone::clone)?;
+    let satellite = Satellite::from_tle(tle).map_err(|e| e.full_message())?;
+    let horizon = Horizon {
+        station,
+        min_elevation_deg: 0.0,
+        mask: sources.mask,
+    };
+    let passes = predict::passes(&satellite, &horizon, stored.job.start, stored.job.end)
+        .map_err(|e| e.full_message())?;
+    let highest = passes
+        .iter()
+        .max_by(|a, b| a.max_elevation_deg.total_cmp(&b.max_elevation_deg))
+        .ok_or_else(|| format!("{} stays below the horizon for the whole window", tle.tle0))?;
+    Ok(Geometry {
+        max_elevation_deg: highest.max_elevation_deg,
+        max_elevation_at: highest.max_elevation_at,
+        aos: passes.iter().find_map(|pass| pass.aos),
+        los: passes.iter().rev().find_map(|pass| pass.los),
+        above_mask_seconds: passes
+            .iter()
+            .fold(Duration::zero(), |total, pass| {
+                total + pass.usable_duration()
+            })
+            .num_seconds(),
+    })
+}
+
+fn markdown(report: &Report) -> String {
+    let job = &report.job.job;
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "# Pass report: {}\n",
+        text::terminal(&report.job.label)
+    );
+    let _ = writeln!(
+        out,
+        "Job {}{} · generated {}\n",
+        report.job.id,
+        match &report.job.status {
+            Some(status) => format!(" · {}", status),
+            None => String::new(),
+        },
+        humanize::timestamp(report.generated_at)
+    );
+
+    out.push_str("## Job\n\n");
+    let _ = writeln!(out, "- Type: {}", job.job_type.name());
+    let _ = writeln!(
+        out,
+        "- Window: {} to {} ({})",
+        humanize::timestamp(job.start),
+        humanize::timestamp(job.end),
+        humanize::duration(job.end - job.start)
+    );
+    let _ = writeln!(out, "- Downlink: {}", job.rx_frequency);
+    if !job.tx_frequency.is_zero() {
+        let _ = writeln!(out, "- Uplink: {}", job.tx_frequency);
+    }
+    match (&job.mode, job.baud) {
+        (Some(mode), Some(baud)) => {
+            let _ = writeln!(out, "- Mode: {} at {} baud", mode, baud);
+        }
+        (Some(mode), None) => {
+            let _ = writeln!(out, "- Mode: {}", mode);
+        }
+        _ => {}
+    }
+    if let Some(polarization) = job.polarization {
+        let _ = writeln!(out, "- Polarization: {}", polarization);
+    }
+    if let Some(campaign) = &job.campaign {
+        let _ = writeln!(out, "- Campaign: {}", campaign);
+    }
+
+    if let Some(outcome) = &report.outcome {
+        out.push_str("\n## Outcome\n\n");
+        if let Some(frames) = outcome.frames_decoded {
+            let _ = writeln!(
+                out,
+                "- Frames decoded: {}",
+                humanize::count(frames as usize)
+            );
+        }
+        match outcome.recordings.len() {
+            0 => out.push_str("- Recordings: none\n"),
+            _ => {
+                let total: u64 = outcome.recordings.iter().filter_map(|r| r.size_bytes).sum();
+                let _ = writeln!(out, "- Recordings: {}", humanize::bytes(total));
+                for recording in &outcome.recordings {
+                    let _ = writeln!(
+                        out,
+                        "  - `{}`: {}",
+                        recording.name,
+                        match recording.size_bytes {
+                            Some(size) => humanize::bytes(size),
+                            None => "size not reported".to_string(),
+                        }
+                    );
+                }
+            }
+        }
+        for (key, value) in &outcome.extra {
+            let _ = writeln!(out, "- {}: {}", key, scalar(value));
+        }
+    }
+
+    if let Some(geometry) = &report.geometry {
+        out.push_str("\n## Predicted pass\n\n");
+        let _ = writeln!(
+            out,
+            "- Max elevation: {:.1}° at {}",
+            geometry.max_elevation_deg,
+            humanize::timestamp(geometry.max_elevation_at)
+        );
+        let _ = writeln!(
+            out,
+            "- Above the mask: {}",
+            humanize::duration(Duration::seconds(geometry.above_mask_seconds))
+        );
+        if let (Some(aos), Some(los)) = (geometry.aos, geometry.los) {
+            let _ = writeln!(
+                out,
+                "- AOS to LOS: {} to {}",
+                humanize::timestamp(aos),
+                humanize::timestamp(los)
+            );
+        }
+    }
+
+    if !report.notes.is_empty() {
+        out.push_str("\n## Notes\n\n");
+        for note in &report.notes {
+            let _ = writeln!(out, "- {}", note);
+        }
+    }
+
+    if !report.omitted.is_empty() {
+        out.push_str("\n## Left out\n\n");
+        for why in &report.omitted {
+            let _ = writeln!(out, "- _{}_", why);
+        }
+    }
+    out
+}
+
+/// `template` with every `{{section.field}}` replaced by that value of the report.
+/// Values of a section left out, and fields the server did not send, come out empty;
+/// a placeholder naming no section is refused, as it can only be a typo.
+fn render(template: &str, report: &Value) -> Result<String, CliError> {
+    let mut out = String::new();
+    let mut rest = template;
+    while let Some(open) = rest.find("{{") {
+        out.push_str(&rest[..open]);
+        let after = &rest[open + 2..];
+        let Some(close) = after.find("}}") else {
+            return Err(CliError::validation(
+                "template",
+                format!(
+                    "'{{{{{}' is never closed",
+                    after.lines().next().unwrap_or("")
+                ),
+            ));
+        };
+        out.push_str(&placeholder(report, after[..close].trim())?);
+        rest = &after[close + 2..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn placeholder(report: &Value, key: &str) -> Result<String, CliError> {
+    let mut parts = key.split('.');
+    let section = parts.next().unwrap_or_default();
+    let Some(mut value) = report.get(section).filter(|_| SECTIONS.contains(&section)) else {
+        return Err(CliError::validation(
+            "template",
+            format!(
+                "{{{{{}}}}} names nothing in the report; placeholders start with {}",
+                key,
+                SECTIONS.join(", ")
+            ),
+        ));
+    };
+    for part in parts {
+        value = match value {
+            Value::Array(items) => part.parse::<usize>().ok().and_then(|i| items.get(i)),
+            other => other.get(part),
+        }
+        .unwrap_or(&Value::Null);
+    }
+    Ok(match value {
+        Value::Array(items) if items.iter().all(Value::is_string) => items
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join("\n"),
+        other => scalar(other),
+    })
+}
+
+/// A value as text: strings bare, nothing for null, anything else as JSON
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> Value {
+        serde_json::json!({
+            "job": { "id": "7", "label": "ISS (ZARYA)", "tle": { "tle0": "ISS (ZARYA)" } },
+            "outcome": null,
+            "geometry": { "max_elevation_deg": 54.2 },
+            "notes": ["low noise floor", "rotator lagged at AOS"],
+            "omitted": [],
+        })
+    }
+
+    #[test]
+    fn placeholders_take_the_report_values() {
+        let text = render(
+            "{{job.label}} (job {{ job.id }}) peaked at {{geometry.max_elevation_deg}}°\n{{notes}}",
+            &report(),
+        )
+        .expect("a valid template");
+        assert_eq!(
+            text,
+            "ISS (ZARYA) (job 7) peaked at 54.2°\nlow noise floor\nrotator lagged at AOS"
+        );
+        assert_eq!(
+            render("{{job.tle.tle0}} / {{notes.1}}", &report()).expect("valid"),
+            "ISS (ZARYA) / rotator lagged at AOS"
+        );
+    }
+
+    #[test]
+    fn a_section_left_out_renders_empty() {
+        assert_eq!(
+            render("frames: {{outcome.frames_decoded}}.", &report()).expect("valid"),
+            "frames: ."
+        );
+    }
+
+    #[test]
+    fn unknown_sections_and_unclosed_placeholders_are_refused() {
+        let message = |template: &str| render(template, &report()).expect_err(template).to_string();
+        assert!(message("{{jbo.id}}").contains("{{jbo.id}} names nothing"));
+        assert!(message("{{job.id").contains("never closed"));
+    }
+}
@@ -0,0 +1,120 @@
+//! `--reproducible`: output that reads the same wherever and whenever a command runs,
+//! for golden tests and scripts that diff it. Times are absolute and in UTC, lines lose
+//! their emoji markers, nothing is coloured, drawn live or cut to the terminal, child
+//! processes get the C locale, and lists come in a fixed order: jobs by start, then ID.
+//! With `RUSTAR_FAKE_NOW` (see `clock`) the current time is fixed as well.
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Environment variable that turns the mode on, as `--reproducible` does
+pub const ENV: &str = "RUSTAR_REPRODUCIBLE";
+
+/// What child processes get in the mode
+const C_LOCALE: &[(&str, &str)] = &[("LC_ALL", "C"), ("LANG", "C")];
+
+/// Set once from `--reproducible`
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Turn the mode on before anything is printed or any hook is started
+pub fn set(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Variables to give a child process, such as a hook, the editor or a signing tool: the
+/// C locale in the mode, nothing otherwise. The CLI's own environment is never changed,
+/// since the runtime's threads may be reading it.
+pub fn child_env() -> &'static [(&'static str, &'static str)] {
+    if enabled() {
+        C_LOCALE
+    } else {
+        &[]
+    }
+}
+
+/// Whether output to a stream may be redrawn in place: it is a terminal, and the mode is off
+pub fn live(terminal: bool) -> bool {
+    terminal && !enabled()
+}
+
+/// Whether escape codes may be written to a stream: it is live and NO_COLOR is unset
+pub fn styled(terminal: bool) -> bool {
+    live(terminal) && std::env::var_os("NO_COLOR").is_none()
+}
+
+/// Whether `c` is part of an emoji such as the ones that open this CLI's lines
+fn marker(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x1F000..=0x1FAFF
+            | 0x2600..=0x27BF
+            | 0x2B00..=0x2BFF
+            | 0x231A..=0x231B
+            | 0x2328
+            | 0x23CF
+            | 0x23E9..=0x23F3
+            | 0x23F8..=0x23FA
+            | 0x2139
+            | 0xFE0F
+            | 0x200D
+            | 0x20E3
+    )
+}
+
+/// `text` with the emoji, and the space after it, taken off the front of each line. The
+/// indentation stays, and so does anything after the first word.
+pub fn plain(text: &str) -> String {
+    text.split('\n')
+        .map(|line| {
+            let words = line.trim_start_matches(' ');
+            let indent = &line[..line.len() - words.len()];
+            let rest = words.trim_start_matches(marker);
+            if rest.len() == words.len() {
+                line.to_string()
+            } else {
+                format!("{}{}", indent, rest.strip_prefix(' ').unwrap_or(rest))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// What `println!` prints, throughout the crate
+pub fn println(args: fmt::Arguments) {
+    if enabled() {
+        std::println!("{}", plain(&args.to_string()));
+    } else {
+        std::println!("{}", args);
+    }
+}
+
+/// What `eprintln!` prints, throughout the crate
+pub fn eprintln(args: fmt::Arguments) {
+    if enabled() {
+        std::eprintln!("{}", plain(&args.to_string()));
+    } else {
+        std::eprintln!("{}", args);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_the_marker_opening_a_line_is_taken_off() {
+        assert_eq!(plain("📋 3 job(s)"), "3 job(s)");
+        assert_eq!(plain("⚠️ Stale elements"), "Stale elements");
+        assert_eq!(plain("   🛰️ ISS pass"), "   ISS pass");
+        assert_eq!(
+            plain("✅ Done\n  [1] 12:00 – 12:10 ✅"),
+            "Done\n  [1] 12:00 – 12:10 ✅"
+        );
+        assert_eq!(plain("  ID  TYPE"), "  ID  TYPE");
+        assert_eq!(plain(""), "");
+    }
+}
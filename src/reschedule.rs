@@ -0,0 +1,207 @@
+use crate::audit;
+use crate::client::{ApiClient, JobDTO, JobType};
+use crate::clock;
+use crate::diff;
+use crate::error::{CliError, ResultExt};
+use crate::humanize;
+use crate::job_request::JobRequestBuilder;
+use crate::location::Location;
+use crate::maintenance;
+use crate::mask::ElevationMask;
+use crate::orbit::Satellite;
+use crate::predict::{self, Horizon};
+use crate::quiet_hours;
+use crate::schedule::{self, TimeWindow};
+use crate::submit_window;
+use crate::tle;
+use crate::OutputFormat;
+use chrono::{DateTime, Duration, SubsecRound, Utc};
+
+/// How far before the current start `--to-next-pass` looks, so a pass that moved earlier is found
+const PASS_LOOKBACK_MIN: i64 = 30;
+/// How far ahead `--to-next-pass` searches
+const PASS_SEARCH_HOURS: i64 = 24;
+
+/// What to do with the job's window
+pub enum Change {
+    /// Move both ends by a signed amount
+    Shift(Duration),
+    /// Set one or both ends; a lone start keeps the job's length
+    Set {
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    },
+    /// Use the next pass of the job's satellite over the station
+    NextPass {
+        station: Location,
+        mask: ElevationMask,
+    },
+}
+
+/// Signed duration such as "+90s", "-2m" or "1h30m"
+pub fn parse_shift(input: &str) -> Result<Duration, CliError> {
+    let trimmed = input.trim();
+    let (negative, magnitude) = match trimmed.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, trimmed.strip_prefix('+').unwrap_or(trimmed)),
+    };
+    let magnitude = crate::parse::duration(magnitude)
+        .with_context(|| format!("reading shift '{}'", trimmed))?;
+    Ok(if negative { -magnitude } else { magnitude })
+}
+
+/// The first usable pass of the job's satellite around or after its current start
+async fn next_pass(
+    job: &JobDTO,
+    station: &Location,
+    mask: &ElevationMask,
+) -> Result<(DateTime<Utc>, DateTime<Utc>), CliError> {
+    let JobType::Tracking { tle: stored } = &job.job.job_type else {
+        return Err(CliError::validation(
+            "job",
+            format!(
+                "a {} job has no satellite to follow; use --shift or --start/--end",
+                job.job.job_type.name()
+            ),
+        ));
+    };
+    // Prefer fresh elements; the stored ones are what the job was planned with
+    let current = match tle::norad_id(&stored.tle1) {
+        Some(norad_id) => tle::resolve(norad_id).await?,
+        None => stored.clone(),
+    };
+    let satellite = Satellite::from_tle(&current)?;
+    let horizon = Horizon {
+        station,
+        min_elevation_deg: 0.0,
+        mask,
+    };
+    let from = (job.job.start - Duration::minutes(PASS_LOOKBACK_MIN)).max(clock::now());
+    let to = from + Duration::hours(PASS_SEARCH_HOURS);
+    predict::passes(&satellite, &horizon, from, to)?
+        .into_iter()
+        .find_map(|pass| pass.aos.zip(pass.los))
+        .ok_or_else(|| {
+            CliError::validation(
+                "job",
+                format!(
+                    "{} has no usable pass in the {}h after {}",
+                    satellite.name,
+                    PASS_SEARCH_HOURS,
+                    humanize::timestamp(from)
+                ),
+            )
+        })
+}
+
+/// Fetch a job, move it as asked and send the new window to the server, then read it
+/// back to show what the server now holds
+pub async fn reschedule(
+    client: &ApiClient,
+    id: &str,
+    change: Change,
+    output: OutputFormat,
+) -> Result<(), CliError> {
+    let value = client.job_json(id).await?;
+    if value.is_null() {
+        return Err(CliError::NotFound {
+            message: Some(format!("the server returned no body for job {}", id)),
+        });
+    }
+    let job: JobDTO = serde_json::from_value(value.clone())
+        .map_err(|e| CliError::parse(format!("job {}", id), e))?;
+
+    let now = clock::now();
+    if job.job.start <= now {
+        return Err(CliError::validation(
+            "job",
+            format!(
+                "job {} started at {} and can no longer be rescheduled",
+                id,
+                humanize::timestamp(job.job.start)
+            ),
+        ));
+    }
+
+    let (old_start, old_end) = (job.job.start, job.job.end);
+    let (start, end) = match change {
+        Change::Shift(by) => (old_start + by, old_end + by),
+        Change::Set { start, end } => match (start, end) {
+            (Some(start), Some(end)) => (start, end),
+            (Some(start), None) => (start, start + (old_end - old_start)),
+            (None, Some(end)) => (old_start, end),
+            (None, None) => (old_start, old_end),
+        },
+        Change::NextPass { station, mask } => {
+            let (aos, los) = next_pass(&job, &station, &mask).await?;
+            (aos.trunc_subsecs(0), los.trunc_subsecs(0))
+        }
+    };
+    if (start, end) == (old_start, old_end) {
+        println!("✅ Job {} already runs in that window", id);
+        return Ok(());
+    }
+    if start <= now {
+        return Err(CliError::validation(
+            "start",
+            format!("{} is in the past", humanize::timestamp(start)),
+        ));
+    }
+
+    let mut moved = job.job.clone();
+    moved.start = start;
+    moved.end = end;
+    let moved = JobRequestBuilder::from(moved).build()?;
+    if let Some(problem) = submit_window::check(start) {
+        return Err(problem.into());
+    }
+    quiet_hours::review(&[&moved])?;
+
+    let others = client.list_jobs().await?;
+    if let Some(other) = others.iter().find(|other| {
+        other.id.as_deref() != Some(id) && TimeWindow::new(start, end).overlaps(&other.job.window())
+    }) {
+        return Err(CliError::Conflict {
+            message: Some(format!(
+                "the new window overlaps job {} ({}, {} – {})",
+                other.id.as_deref().unwrap_or("?"),
+                other.job.label(),
+                humanize::timestamp(other.job.start),
+                other.job.end.format("%H:%M:%S UTC")
+            )),
+            hint: Some("pick another window, or move that job first".to_string()),
+        });
+    }
+    if !maintenance::confirm_window(client, TimeWindow::new(start, end)).await? {
+        println!("🛑 Job {} left unchanged", id);
+        return Ok(());
+    }
+
+    let text = output != OutputFormat::Json;
+    if text {
+        println!("🔁 Rescheduling job {} ({})", id, moved.label());
+        println!(
+            "   start {} → {}",
+            humanize::timestamp(old_start),
+            humanize::timestamp(start)
+        );
+        println!(
+            "   end   {} → {}",
+            humanize::timestamp(old_end),
+            humanize::timestamp(end)
+        );
+    }
+    let response = client.reschedule_job(id, start, end).await?;
+    audit::job(id);
+    audit::previous(
+        id,
+        serde_json::json!({ "start": old_start, "end": old_end }),
+    );
+    if text {
+        println!("✅ Job rescheduled: {}", response.status());
+    }
+    let mut expected = value.clone();
+    expected["start"] = schedule::format(start).into();
+    expected["end"] = schedule::format(end).into();
+    diff::report_change(client, id, &value, &expected, &["start", "end"], output).await
+}
@@ -0,0 +1,83 @@
+use crate::audit;
+use crate::client::{ApiClient, JobDTO};
+use crate::clock;
+use crate::error::CliError;
+use crate::humanize;
+
+/// A 410 from the server, which no longer holds the job at all
+fn purged(error: CliError) -> CliError {
+    if error.status() == Some(410) {
+        CliError::Gone {
+            message: Some("this job has been permanently purged".to_string()),
+        }
+    } else {
+        error
+    }
+}
+
+/// Bring back a cancelled job. The slot is checked here first: another job may have
+/// taken it since the cancellation, and the server's refusal would say less.
+pub async fn restore(client: &ApiClient, id: &str) -> Result<(), CliError> {
+    let value = client.job_json(id).await.map_err(purged)?;
+    if value.is_null() {
+        return Err(CliError::NotFound {
+            message: Some(format!("the server returned no body for job {}", id)),
+        });
+    }
+    let job: JobDTO =
+        serde_json::from_value(value).map_err(|e| CliError::parse(format!("job {}", id), e))?;
+
+    if !job.is_cancelled() {
+        return Err(CliError::validation(
+            "job",
+            format!(
+                "job {} is not cancelled (status {})",
+                id,
+                job.status.as_deref().unwrap_or("unknown")
+            ),
+        ));
+    }
+    if job.job.start <= clock::now() {
+        return Err(CliError::validation(
+            "job",
+            format!(
+                "job {} was due to start at {} and can no longer run",
+                id,
+                humanize::timestamp(job.job.start)
+            ),
+        ));
+    }
+
+    let others = client.list_jobs().await?;
+    if let Some(other) = others.iter().find(|other| {
+        other.id.as_deref() != Some(id)
+            && !other.is_cancelled()
+            && job.job.window().overlaps(&other.job.window())
+    }) {
+        return Err(CliError::Conflict {
+            message: Some(format!(
+                "the slot has been taken by job {} ({}, {} – {})",
+                other.id.as_deref().unwrap_or("?"),
+                other.job.label(),
+                humanize::timestamp(other.job.start),
+                other.job.end.format("%H:%M:%S UTC")
+            )),
+            hint: Some(
+                "cancel or move that job first, or add this one again in another window"
+                    .to_string(),
+            ),
+        });
+    }
+
+    println!(
+        "♻️ Restoring job {} ({}, {} – {})",
+        id,
+        job.job.label(),
+        humanize::timestamp(job.job.start),
+        job.job.end.format("%H:%M:%S UTC")
+    );
+    let response = client.restore_job(id).await.map_err(purged)?;
+    audit::job(id);
+    println!("✅ Job restored: {}", response.status());
+    Ok(())
+}
@@ -0,0 +1,173 @@
+use crate::client::JobRequestDTO;
+use crate::error::{CliError, ResultExt};
+use crate::rotctld::Rotctld;
+use crate::transcript;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// How long a probe waits when the profile does not say
+const DEFAULT_TIMEOUT_SECONDS: u64 = 3;
+
+/// What a failed check does to jobs that transmit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OnFailure {
+    /// Say so, and submit anyway
+    #[default]
+    Warn,
+    /// Submit none of them
+    Block,
+}
+
+/// The profile's `[rotator_check]`: how to tell the rotator controller is there before
+/// a job transmits, e.g. `rotator_check = { rotctld = "localhost:4533" }`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct RotatorCheck {
+    /// A rotctld to ask for the position, as host:port
+    pub rotctld: Option<String>,
+    /// A station-local URL that answers 2xx while the rotator is attached
+    pub health_url: Option<String>,
+    pub on_failure: OnFailure,
+    /// How long each probe waits, in seconds (3 when unset)
+    pub timeout_seconds: Option<u64>,
+}
+
+impl RotatorCheck {
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_seconds.unwrap_or(DEFAULT_TIMEOUT_SECONDS))
+    }
+}
+
+/// What one probe found
+#[derive(Debug, Clone, Serialize)]
+pub struct Status {
+    /// What was probed: "rotctld at localhost:4533"
+    pub target: String,
+    pub ok: bool,
+    /// The position or answer, or why the probe failed
+    pub detail: String,
+}
+
+/// Set once from the selected profile
+static CHECK: OnceLock<RotatorCheck> = OnceLock::new();
+/// The selected profile's probes, made once however many jobs are submitted
+static PROBED: tokio::sync::OnceCell<Vec<Status>> = tokio::sync::OnceCell::const_new();
+
+/// The profile's `[rotator_check]`, when it has one
+pub fn set(check: Option<&RotatorCheck>) {
+    if let Some(check) = check {
+        let _ = CHECK.set(check.clone());
+    }
+}
+
+async fn ask_rotctld(address: &str, timeout: Duration) -> Result<String, CliError> {
+    let position = Rotctld::connect(address, timeout)
+        .await?
+        .position()
+        .await
+        .with_context(|| format!("asking rotctld at {} for the position", address))?;
+    Ok(format!(
+        "pointing at azimuth {:.1}°, elevation {:.1}°",
+        position.azimuth, position.elevation
+    ))
+}
+
+async fn ask_health_url(url: &str, timeout: Duration) -> Result<String, CliError> {
+    let client = reqwest::Client::builder().timeout(timeout).build()?;
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| CliError::transport(e, None))
+        .with_context(|| format!("checking {}", url))?;
+    Ok(format!("answered {}", response.status()))
+}
+
+/// Every probe `check` configures; none when it configures neither
+pub async fn probe(check: &RotatorCheck) -> Vec<Status> {
+    let timeout = check.timeout();
+    let mut found = Vec::new();
+    if let Some(address) = &check.rotctld {
+        let result = ask_rotctld(address, timeout).await;
+        found.push(status(format!("rotctld at {}", address), result));
+    }
+    if let Some(url) = &check.health_url {
+        let result = ask_health_url(url, timeout).await;
+        found.push(status(format!("health URL {}", url), result));
+    }
+    found
+}
+
+fn status(target: String, result: Result<String, CliError>) -> Status {
+    match result {
+        Ok(detail) => Status {
+            target,
+            ok: true,
+            detail,
+        },
+        Err(e) => Status {
+            target,
+            ok: false,
+            detail: e.full_message(),
+        },
+    }
+}
+
+/// The selected profile's probes, for `doctor`; none without a `[rotator_check]`
+pub async fn selected() -> Vec<Status> {
+    match CHECK.get() {
+        Some(check) => PROBED.get_or_init(|| probe(check)).await.clone(),
+        None => Vec::new(),
+    }
+}
+
+/// Before `jobs` are submitted under `check`: when any of them transmits, probe the
+/// rotator and warn about a failure, or refuse with it when the check blocks
+pub async fn review_with(check: &RotatorCheck, jobs: &[&JobRequestDTO]) -> Result<(), CliError> {
+    if jobs.iter().all(|job| job.tx_frequency.is_zero()) {
+        return Ok(());
+    }
+    let probed = probe(check).await;
+    judge(check.on_failure, &probed)
+}
+
+/// `review_with` the selected profile's check, probing at most once per invocation
+pub async fn review(jobs: &[&JobRequestDTO]) -> Result<(), CliError> {
+    let Some(check) = CHECK.get() else {
+        return Ok(());
+    };
+    if jobs.iter().all(|job| job.tx_frequency.is_zero()) {
+        return Ok(());
+    }
+    let probed = PROBED.get_or_init(|| probe(check)).await;
+    judge(check.on_failure, probed)
+}
+
+fn judge(on_failure: OnFailure, probed: &[Status]) -> Result<(), CliError> {
+    let Some(failed) = probed.iter().find(|status| !status.ok) else {
+        return Ok(());
+    };
+    match on_failure {
+        OnFailure::Warn => {
+            let warning = format!(
+                "The rotator check failed ({}): {}; the job transmits without knowing the \
+                 antenna can be pointed",
+                failed.target, failed.detail
+            );
+            eprintln!("⚠️ {}", warning);
+            transcript::warning(&warning);
+            Ok(())
+        }
+        OnFailure::Block => Err(CliError::validation(
+            "rotator",
+            format!(
+                "the check of {} failed: {}; rotator_check.on_failure = \"block\" holds back \
+                 jobs that transmit",
+                failed.target, failed.detail
+            ),
+        )),
+    }
+}
@@ -0,0 +1,233 @@
+//! The part of hamlib's rotctld network protocol the CLI needs: one command per line,
+//! answered by its values one per line, or by `RPRT <code>` when it fails
+
+use crate::error::{CliError, ResultExt};
+use std::io::{Error, ErrorKind};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+
+/// Where the rotator points, in degrees
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Position {
+    pub azimuth: f64,
+    pub elevation: f64,
+}
+
+/// What hamlib's negative `RPRT` codes mean, for the ones a rotator sends
+fn meaning(code: i32) -> &'static str {
+    match code.abs() {
+        1 => "invalid parameter",
+        2 => "invalid configuration",
+        4 => "not implemented",
+        5 => "the rotator did not answer in time",
+        6 => "I/O error talking to the rotator",
+        7 => "internal hamlib error",
+        8 => "protocol error talking to the rotator",
+        9 => "command rejected by the rotator",
+        11 => "function not available",
+        _ => "hamlib error",
+    }
+}
+
+/// An open connection to rotctld; every exchange gives up after `timeout`
+pub struct Rotctld {
+    stream: BufReader<TcpStream>,
+    timeout: Duration,
+}
+
+impl Rotctld {
+    pub async fn connect(address: &str, timeout: Duration) -> Result<Self, CliError> {
+        let stream = within(timeout, TcpStream::connect(address))
+            .await
+            .with_context(|| format!("connecting to rotctld at {}", address))?;
+        Ok(Self {
+            stream: BufReader::new(stream),
+            timeout,
+        })
+    }
+
+    /// Send `command` and read the `lines` values it answers with. `RPRT 0` alone is a
+    /// success with no values; any other code is the command failing.
+    pub async fn command(&mut self, command: &str, lines: usize) -> Result<Vec<String>, CliError> {
+        let timeout = self.timeout;
+        within(
+            timeout,
+            self.stream
+                .get_mut()
+                .write_all(format!("{}\n", command).as_bytes()),
+        )
+        .await?;
+        let mut values = Vec::with_capacity(lines);
+        while values.len() < lines {
+            let mut line = String::new();
+            if within(timeout, self.stream.read_line(&mut line)).await? == 0 {
+                return Err(Error::new(
+                    ErrorKind::UnexpectedEof,
+                    "rotctld closed the connection before answering",
+                )
+                .into());
+            }
+            let line = line.trim();
+            match report(line) {
+                Some(0) => break,
+                Some(code) => {
+                    return Err(Error::other(format!("RPRT {} ({})", code, meaning(code))).into())
+                }
+                None => values.push(line.to_string()),
+            }
+        }
+        Ok(values)
+    }
+
+    /// `p`: where the rotator points now
+    pub async fn position(&mut self) -> Result<Position, CliError> {
+        let lines = self.command("p", 2).await?;
+        parse_position(&lines)
+    }
+}
+
+/// The code of an `RPRT` line
+fn report(line: &str) -> Option<i32> {
+    line.strip_prefix("RPRT")?.trim().parse().ok()
+}
+
+/// The two lines `p` answers with: azimuth, then elevation
+pub fn parse_position(lines: &[String]) -> Result<Position, CliError> {
+    let [azimuth, elevation] = lines else {
+        return Err(CliError::validation(
+            "rotctld position",
+            format!(
+                "expected azimuth and elevation, got {} line(s)",
+                lines.len()
+            ),
+        ));
+    };
+    let degrees = |name: &str, text: &str| {
+        text.parse::<f64>()
+            .ok()
+            .filter(|value| value.is_finite())
+            .ok_or_else(|| {
+                CliError::validation(
+                    "rotctld position",
+                    format!("{} '{}' is not a number of degrees", name, text),
+                )
+            })
+    };
+    Ok(Position {
+        azimuth: degrees("azimuth", azimuth)?,
+        elevation: degrees("elevation", elevation)?,
+    })
+}
+
+/// `io`, given up on as timed out after `timeout`
+async fn within<T>(
+    timeout: Duration,
+    io: impl std::future::Future<Output = std::io::Result<T>>,
+) -> Result<T, CliError> {
+    match tokio::time::timeout(timeout, io).await {
+        Ok(result) => Ok(result?),
+        Err(_) => Err(Error::new(
+            ErrorKind::TimedOut,
+            format!("no answer within {:.1}s", timeout.as_secs_f64()),
+        )
+        .into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncReadExt;
+    use tokio::net::TcpListener;
+
+    const TIMEOUT: Duration = Duration::from_millis(500);
+
+    /// A rotctld on a free port that reads one command and answers with `reply`, or
+    /// says nothing when `reply` is None
+    async fn server(reply: Option<&'static str>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut command = [0u8; 16];
+            let _ = socket.read(&mut command).await;
+            match reply {
+                Some(reply) => socket.write_all(reply.as_bytes()).await.unwrap(),
+                None => tokio::time::sleep(TIMEOUT * 4).await,
+            }
+        });
+        address
+    }
+
+    async fn position(reply: Option<&'static str>) -> Result<Position, CliError> {
+        let address = server(reply).await;
+        Rotctld::connect(&address, TIMEOUT).await?.position().await
+    }
+
+    #[tokio::test]
+    async fn reads_the_position() {
+        let position = position(Some("180.500000\n45.000000\n")).await.unwrap();
+        assert_eq!(
+            position,
+            Position {
+                azimuth: 180.5,
+                elevation: 45.0
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn a_failure_report_is_an_error_with_its_meaning() {
+        let error = position(Some("RPRT -5\n")).await.unwrap_err();
+        assert!(
+            error
+                .full_message()
+                .contains("RPRT -5 (the rotator did not answer in time)"),
+            "{}",
+            error.full_message()
+        );
+    }
+
+    #[tokio::test]
+    async fn silence_times_out() {
+        let error = position(None).await.unwrap_err();
+        assert!(
+            error.full_message().contains("no answer within 0.5s"),
+            "{}",
+            error.full_message()
+        );
+    }
+
+    #[tokio::test]
+    async fn a_short_answer_is_reported() {
+        let error = position(Some("180.0\n")).await.unwrap_err();
+        assert!(
+            error.full_message().contains("closed the connection"),
+            "{}",
+            error.full_message()
+        );
+    }
+
+    #[tokio::test]
+    async fn nobody_listening_names_the_address() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        drop(listener);
+        let error = Rotctld::connect(&address, TIMEOUT).await.err().unwrap();
+        assert!(
+            error.full_message().contains(&address),
+            "{}",
+            error.full_message()
+        );
+    }
+
+    #[test]
+    fn positions_must_be_two_numbers() {
+        let lines = |text: &[&str]| text.iter().map(|l| l.to_string()).collect::<Vec<_>>();
+        assert!(parse_position(&lines(&["12.5", "-3"])).is_ok());
+        assert!(parse_position(&lines(&["12.5"])).is_err());
+        assert!(parse_position(&lines(&["north", "10"])).is_err());
+        assert!(parse_position(&lines(&["NaN", "10"])).is_err());
+    }
+}
@@ -0,0 +1,157 @@
+use std::fmt;
+
+/// Letters Alpha-5 catalog numbers start with, worth 10 to 33: I and O are skipped
+/// so they cannot be read as 1 and 0
+const ALPHA5_LETTERS: &str = "ABCDEFGHJKLMNPQRSTUVWXYZ";
+
+/// Largest catalog number the five TLE columns can hold in Alpha-5 ("Z9999")
+pub const MAX_ALPHA5: u32 = 339_999;
+
+/// How the operator named a satellite: by catalog number, by international (COSPAR)
+/// designator, or by anything else, which is taken as a name or alias
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SatelliteId {
+    NoradId(u32),
+    /// Canonical four-digit-year form, "1998-067A"
+    IntlDesignator(String),
+    Name(String),
+}
+
+impl SatelliteId {
+    /// Tell the three forms apart: "25544" and "A0001" are catalog numbers, "98067A" and
+    /// "1998-067A" designators, and everything else a name
+    pub fn detect(input: &str) -> Self {
+        let input = input.trim();
+        if let Some(norad_id) = parse_norad_id(input) {
+            SatelliteId::NoradId(norad_id)
+        } else if let Some(designator) = parse_designator(input) {
+            SatelliteId::IntlDesignator(designator)
+        } else {
+            SatelliteId::Name(input.to_string())
+        }
+    }
+
+    /// Whether `line1` of an element set is for this satellite; names never match a line
+    pub fn matches_line(&self, line1: &str) -> bool {
+        match self {
+            SatelliteId::NoradId(norad_id) => {
+                crate::tle::norad_id(line1).is_some_and(|id| id == *norad_id)
+            }
+            SatelliteId::IntlDesignator(designator) => {
+                crate::tle::intl_designator(line1).is_some_and(|d| d == *designator)
+            }
+            SatelliteId::Name(_) => false,
+        }
+    }
+}
+
+impl fmt::Display for SatelliteId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SatelliteId::NoradId(norad_id) => write!(f, "{}", norad_label(*norad_id)),
+            SatelliteId::IntlDesignator(designator) => write!(f, "{}", designator),
+            SatelliteId::Name(name) => write!(f, "'{}'", name),
+        }
+    }
+}
+
+/// A catalog number as typed: plain digits, or Alpha-5 (a letter for the hundred
+/// thousands, then four digits)
+pub fn parse_norad_id(input: &str) -> Option<u32> {
+    let input = input.trim();
+    if !input.is_empty() && input.chars().all(|c| c.is_ascii_digit()) {
+        return input.parse().ok();
+    }
+    let mut chars = input.chars();
+    let letter = chars.next()?.to_ascii_uppercase();
+    let rest = chars.as_str();
+    if rest.len() != 4 || !rest.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let high = ALPHA5_LETTERS.find(letter)? as u32 + 10;
+    Some(high * 10_000 + rest.parse::<u32>().ok()?)
+}
+
+/// A catalog number as the five TLE columns write it: zero-padded digits below 100000,
+/// Alpha-5 above, and none past what Alpha-5 reaches
+pub fn to_alpha5(norad_id: u32) -> Option<String> {
+    if norad_id < 100_000 {
+        return Some(format!("{:05}", norad_id));
+    }
+    if norad_id > MAX_ALPHA5 {
+        return None;
+    }
+    let letter = ALPHA5_LETTERS
+        .chars()
+        .nth((norad_id / 10_000 - 10) as usize)?;
+    Some(format!("{}{:04}", letter, norad_id % 10_000))
+}
+
+/// An international designator in either spelling, "98067A" as TLE line 1 carries it or
+/// "1998-067A", returned in the second form. Two-digit years follow the NORAD convention.
+pub fn parse_designator(input: &str) -> Option<String> {
+    let input = input.trim().to_ascii_uppercase();
+    let (year, rest) = match input.split_once('-') {
+        Some((year, rest)) if year.len() == 4 => (year.parse::<u32>().ok()?, rest),
+        Some(_) => return None,
+        None => {
+            let two: u32 = input.get(..2)?.parse().ok()?;
+            let year = if two >= 57 { 1900 + two } else { 2000 + two };
+            (year, input.get(2..)?)
+        }
+    };
+    let (launch, piece) = rest.split_at(rest.find(|c: char| !c.is_ascii_digit())?);
+    let piece_ok = (1..=3).contains(&piece.len()) && piece.chars().all(|c| c.is_ascii_uppercase());
+    if launch.len() != 3 || !piece_ok || !(1957..=2056).contains(&year) {
+        return None;
+    }
+    Some(format!("{}-{}{}", year, launch, piece))
+}
+
+/// "NORAD 25544", with the Alpha-5 spelling TLEs use once the number no longer fits five
+/// digits: "NORAD 100001 (A0001)"
+fn norad_label(norad_id: u32) -> String {
+    match to_alpha5(norad_id) {
+        Some(alpha5) if norad_id >= 100_000 => format!("NORAD {} ({})", norad_id, alpha5),
+        _ => format!("NORAD {}", norad_id),
+    }
+}
+
+/// "NORAD 25544 · 1998-067A", or just the catalog number when the designator is unknown
+pub fn describe(norad_id: u32, designator: Option<&str>) -> String {
+    match designator {
+        Some(designator) => format!("{} · {}", norad_label(norad_id), designator),
+        None => norad_label(norad_id),
+    }
+}
+
+/// clap value parser for flags that take a catalog number, Alpha-5 included
+pub fn norad_id_arg(input: &str) -> Result<u32, String> {
+    parse_norad_id(input).ok_or_else(|| {
+        format!(
+            "'{}' is not a NORAD catalog number (digits, or Alpha-5 like A0001)",
+            input
+        )
+    })
+}
+
+/// clap value parser for flags that take an international designator
+pub fn designator_arg(input: &str) -> Result<String, String> {
+    parse_designator(input).ok_or_else(|| {
+        format!(
+            "'{}' is not an international designator (like 1998-067A or 98067A)",
+            input
+        )
+    })
+}
+
+/// clap value parser for flags that take either a catalog number or a designator
+pub fn catalog_id_arg(input: &str) -> Result<SatelliteId, String> {
+    match SatelliteId::detect(input) {
+        SatelliteId::Name(_) => Err(format!(
+            "'{}' is neither a NORAD catalog number nor an international designator",
+            input
+        )),
+        id => Ok(id),
+    }
+}
@@ -0,0 +1,430 @@
+use crate::error::CliError;
+use crate::frequency::{self, Frequency};
+use crate::paths;
+use crate::satellite_id::{self, SatelliteId};
+use crate::schema::{Format, Store, VERSION_KEY};
+use crate::storage;
+use crate::text;
+use crate::transponder::Passband;
+use crate::OutputFormat;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// What the operator keeps for one satellite alias
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Preset {
+    /// Catalog number, for fetching the element set when the alias is used
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub norad_id: Option<u32>,
+    /// International designator ("1998-067A"), for fetching the element set when the alias
+    /// has no catalog number
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub intl_designator: Option<String>,
+    /// Downlink the station listens on, in Hz
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rx_frequency: Option<Frequency>,
+    /// How far off its published downlink the satellite transmits, in signed Hz; jobs for
+    /// the alias listen that much off the downlink
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rx_offset_hz: Option<i64>,
+    /// Uplink, in Hz
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_frequency: Option<Frequency>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub baud: Option<u32>,
+    /// Linear transponder passband; jobs add then asks for the downlink only and pairs the uplink
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transponder: Option<Passband>,
+}
+
+impl Preset {
+    /// What the element set is fetched by: the catalog number, else the designator
+    pub fn id(&self) -> Option<SatelliteId> {
+        self.norad_id.map(SatelliteId::NoradId).or_else(|| {
+            self.intl_designator
+                .clone()
+                .map(SatelliteId::IntlDesignator)
+        })
+    }
+
+    /// "RX 145.825 MHz, AFSK, 1200 baud", listing only what is set
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        match (self.norad_id, &self.intl_designator) {
+            (Some(norad_id), designator) => {
+                parts.push(satellite_id::describe(norad_id, designator.as_deref()))
+            }
+            (None, Some(designator)) => parts.push(designator.clone()),
+            (None, None) => {}
+        }
+        match (self.rx_frequency, self.rx_offset_hz) {
+            (Some(rx), Some(offset)) => {
+                parts.push(format!("RX {} {}", rx, frequency::describe_offset(offset)))
+            }
+            (Some(rx), None) => parts.push(format!("RX {}", rx)),
+            (None, Some(offset)) => {
+                parts.push(format!("RX offset {}", frequency::describe_offset(offset)))
+            }
+            (None, None) => {}
+        }
+        if let Some(tx) = self.tx_frequency {
+            parts.push(format!("TX {}", tx));
+        }
+        if let Some(mode) = &self.mode {
+            parts.push(mode.clone());
+        }
+        if let Some(baud) = self.baud {
+            parts.push(format!("{} baud", baud));
+        }
+        if let Some(transponder) = &self.transponder {
+            parts.push(format!("transponder {}", transponder.describe()));
+        }
+        if parts.is_empty() {
+            "no presets".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+/// Table of the alias book holding the groups, so no alias can take its name
+const GROUPS_KEY: &str = "groups";
+
+/// `satellites.toml` layouts; version 1 is the first to carry a `version` key
+pub const STORE: Store = Store {
+    name: "alias book",
+    format: Format::Toml,
+    migrations: &[],
+};
+
+/// Satellite aliases by name, kept in `satellites.toml` next to the config file
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct AliasBook {
+    #[serde(flatten)]
+    entries: BTreeMap<String, Preset>,
+    /// Group name to members: aliases, other groups, bare NORAD IDs or designators
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    groups: BTreeMap<String, Vec<String>>,
+}
+
+pub fn book_path() -> PathBuf {
+    paths::config_dir().join("satellites.toml")
+}
+
+impl AliasBook {
+    /// Read the alias book; a missing file is an empty book
+    pub fn load() -> Result<Self, CliError> {
+        Ok(STORE.load(&book_path())?.unwrap_or_default())
+    }
+
+    /// Hold the alias book against other rustar processes until the guard is dropped;
+    /// take it before `load` when the book is going to be saved
+    pub fn lock() -> Result<storage::FileLock, CliError> {
+        storage::lock(&book_path())
+    }
+
+    pub fn save(&self) -> Result<PathBuf, CliError> {
+        let path = book_path();
+        STORE.write(&path, self)?;
+        Ok(path)
+    }
+
+    /// Every alias with its preset, in name order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Preset)> {
+        self.entries
+            .iter()
+            .map(|(name, preset)| (name.as_str(), preset))
+    }
+
+    /// The entry for `alias`, matched without regard to case or normalization form
+    pub fn get(&self, alias: &str) -> Option<(&str, &Preset)> {
+        self.entries
+            .iter()
+            .find(|(name, _)| text::same_name(name, alias))
+            .map(|(name, preset)| (name.as_str(), preset))
+    }
+
+    /// The entry for `alias`, created under that name when missing
+    pub fn entry(&mut self, alias: &str) -> Result<(String, &mut Preset), CliError> {
+        if alias.eq_ignore_ascii_case(GROUPS_KEY) || self.group_name(alias).is_some() {
+            return Err(CliError::validation(
+                "alias",
+                format!("'{}' is already used for groups", alias),
+            ));
+        }
+        if alias.eq_ignore_ascii_case(VERSION_KEY) {
+            return Err(CliError::validation(
+                "alias",
+                format!("'{}' is reserved for the file's layout version", alias),
+            ));
+        }
+        let name = self
+            .get(alias)
+            .map(|(name, _)| name.to_string())
+            .unwrap_or_else(|| alias.to_string());
+        Ok((name.clone(), self.entries.entry(name).or_default()))
+    }
+
+    /// Stored spelling of a group name, matched without regard to case or normalization
+    /// form
+    fn group_name(&self, group: &str) -> Option<&str> {
+        self.groups
+            .keys()
+            .find(|name| text::same_name(name, group))
+            .map(String::as_str)
+    }
+
+    /// Satellites a group (or a single alias) stands for, by catalog number or designator,
+    /// in order and without repeats.
+    /// Groups may contain groups; a group that ends up containing itself is an error.
+    pub fn expand(&self, name: &str) -> Result<Vec<SatelliteId>, CliError> {
+        let mut found = Vec::new();
+        self.expand_into(name, &mut Vec::new(), &mut found)?;
+        Ok(found)
+    }
+
+    fn expand_into(
+        &self,
+        name: &str,
+        path: &mut Vec<String>,
+        found: &mut Vec<SatelliteId>,
+    ) -> Result<(), CliError> {
+        let mut push = |id: SatelliteId| {
+            if !found.contains(&id) {
+                found.push(id);
+            }
+        };
+        let name = match SatelliteId::detect(name) {
+            SatelliteId::Name(name) => name,
+            id => {
+                push(id);
+                return Ok(());
+            }
+        };
+        let name = name.as_str();
+        if let Some(group) = self.group_name(name) {
+            if path.iter().any(|seen| seen == group) {
+                path.push(group.to_string());
+                return Err(CliError::validation(
+                    "group",
+                    format!(
+                        "groups refer to each other in a cycle: {}",
+                        path.join(" → ")
+                    ),
+                ));
+            }
+            path.push(group.to_string());
+            for member in &self.groups[group] {
+                self.expand_into(member, path, found)?;
+            }
+            path.pop();
+            return Ok(());
+        }
+        match self.get(name) {
+            Some((alias, preset)) => match preset.id() {
+                Some(id) => {
+                    push(id);
+                    Ok(())
+                }
+                None => Err(CliError::validation(
+                    "alias",
+                    format!(
+                        "'{}' has no NORAD ID (set one with rustar-cli sat set-freq {} --norad-id N)",
+                        alias, alias
+                    ),
+                )),
+            },
+            None => Err(CliError::validation(
+                "group",
+                format!(
+                    "'{}' is not a group, an alias, a NORAD ID or an international designator",
+                    name
+                ),
+            )),
+        }
+    }
+}
+
+/// Add members to a group, creating it; refused when the result would not expand
+pub fn group_add(group: &str, members: &[String]) -> Result<(), CliError> {
+    let _lock = AliasBook::lock()?;
+    let mut book = AliasBook::load()?;
+    if book.get(group).is_some() || !matches!(SatelliteId::detect(group), SatelliteId::Name(_)) {
+        return Err(CliError::validation(
+            "group",
+            format!(
+                "'{}' is already a satellite alias, a NORAD ID or a designator",
+                group
+            ),
+        ));
+    }
+    let name = book.group_name(group).unwrap_or(group).to_string();
+    let entry = book.groups.entry(name.clone()).or_default();
+    for member in members {
+        if !entry.iter().any(|m| m.eq_ignore_ascii_case(member)) {
+            entry.push(member.clone());
+        }
+    }
+    let expanded = book.expand(&name)?;
+    let path = book.save()?;
+    println!(
+        "👥 {}: {} ({} satellite(s), saved in {})",
+        name,
+        book.groups[&name].join(", "),
+        expanded.len(),
+        path.display()
+    );
+    Ok(())
+}
+
+/// Remove members from a group; the group goes away with its last member
+pub fn group_remove(group: &str, members: &[String]) -> Result<(), CliError> {
+    let _lock = AliasBook::lock()?;
+    let mut book = AliasBook::load()?;
+    let name = book
+        .group_name(group)
+        .ok_or_else(|| CliError::validation("group", format!("no group '{}'", group)))?
+        .to_string();
+    let entry = book.groups.entry(name.clone()).or_default();
+    let before = entry.len();
+    entry.retain(|m| !members.iter().any(|r| r.eq_ignore_ascii_case(m)));
+    let removed = before - entry.len();
+    if entry.is_empty() {
+        book.groups.remove(&name);
+        book.save()?;
+        println!(
+            "👥 Removed {} member(s); group {} is now empty and deleted",
+            removed, name
+        );
+    } else {
+        book.save()?;
+        println!(
+            "👥 Removed {} member(s); {}: {}",
+            removed,
+            name,
+            book.groups[&name].join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Show the groups with their members and what they expand to
+pub fn group_list(output: OutputFormat) -> Result<(), CliError> {
+    let book = AliasBook::load()?;
+    let expanded: BTreeMap<&str, Result<Vec<SatelliteId>, String>> = book
+        .groups
+        .keys()
+        .map(|name| {
+            (
+                name.as_str(),
+                book.expand(name).map_err(|e| e.full_message()),
+            )
+        })
+        .collect();
+
+    if output == OutputFormat::Json {
+        let document: Vec<serde_json::Value> = book
+            .groups
+            .iter()
+            .map(|(name, members)| match &expanded[name.as_str()] {
+                Ok(ids) => {
+                    let (norad_ids, designators): (Vec<u32>, Vec<&str>) =
+                        ids.iter()
+                            .fold((Vec::new(), Vec::new()), |(mut n, mut d), id| {
+                                match id {
+                                    SatelliteId::NoradId(norad_id) => n.push(*norad_id),
+                                    SatelliteId::IntlDesignator(designator) => d.push(designator),
+                                    SatelliteId::Name(_) => {}
+                                }
+                                (n, d)
+                            });
+                    serde_json::json!({
+                        "group": name,
+                        "members": members,
+                        "norad_ids": norad_ids,
+                        "intl_designators": designators,
+                    })
+                }
+                Err(error) => serde_json::json!({
+                    "group": name,
+                    "members": members,
+                    "error": error,
+                }),
+            })
+            .collect();
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&document).map_err(|e| CliError::parse("groups", e))?
+        );
+        return Ok(());
+    }
+
+    if book.groups.is_empty() {
+        println!("📭 No satellite groups in {}", book_path().display());
+        return Ok(());
+    }
+    for (name, members) in &book.groups {
+        let expansion = match &expanded[name.as_str()] {
+            Ok(ids) => ids
+                .iter()
+                .map(|id| match id {
+                    SatelliteId::NoradId(norad_id) => norad_id.to_string(),
+                    id => id.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(", "),
+            Err(error) => format!("⚠️ {}", error),
+        };
+        println!("  👥 {}: {} → {}", name, members.join(", "), expansion);
+    }
+    Ok(())
+}
+
+/// The alias named on the command line, or an error listing the known ones
+pub fn lookup(book: &AliasBook, alias: &str) -> Result<(String, Preset), CliError> {
+    book.get(alias)
+        .map(|(name, preset)| (name.to_string(), preset.clone()))
+        .ok_or_else(|| {
+            let known: Vec<&str> = book.entries.keys().map(String::as_str).collect();
+            CliError::validation(
+                "satellite",
+                if known.is_empty() {
+                    format!(
+                        "no alias '{}'; the alias book is empty (add one with rustar-cli sat set-freq)",
+                        alias
+                    )
+                } else {
+                    format!("no alias '{}' (known: {})", alias, known.join(", "))
+                },
+            )
+        })
+}
+
+/// Print every alias with its presets
+pub fn list(output: OutputFormat) -> Result<(), CliError> {
+    let book = AliasBook::load()?;
+    if output == OutputFormat::Json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&book).map_err(|e| CliError::parse("alias book", e))?
+        );
+        return Ok(());
+    }
+    if book.entries.is_empty() {
+        println!("📭 No satellite aliases in {}", book_path().display());
+        return Ok(());
+    }
+    println!(
+        "📒 {} alias(es) in {}",
+        book.entries.len(),
+        book_path().display()
+    );
+    for (name, preset) in &book.entries {
+        println!("  {}: {}", name, preset.describe());
+    }
+    Ok(())
+}
@@ -0,0 +1,159 @@
+use chrono::{DateTime, SecondsFormat, SubsecRound, Utc};
+
+/// Sub-second digits the server keeps: job times are stored to the millisecond
+const SERVER_DIGITS: u16 = 3;
+
+/// `at` as the server will store it, finer digits dropped
+pub fn to_server_precision(at: DateTime<Utc>) -> DateTime<Utc> {
+    at.trunc_subsecs(SERVER_DIGITS)
+}
+
+/// A job, maintenance or pass window. Windows are half-open `[start, end)`, matching the
+/// server's conflict check, so a job ending at 12:15:00 and one starting at 12:15:00 are
+/// back to back, not overlapping. Both ends are held at the server's millisecond
+/// precision, so no conflict is found in digits the server would throw away.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeWindow {
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+}
+
+impl TimeWindow {
+    pub fn new(start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        Self {
+            start: to_server_precision(start),
+            end: to_server_precision(end),
+        }
+    }
+
+    pub fn start(&self) -> DateTime<Utc> {
+        self.start
+    }
+
+    pub fn end(&self) -> DateTime<Utc> {
+        self.end
+    }
+
+    /// Whether the two windows share any time
+    pub fn overlaps(&self, other: &TimeWindow) -> bool {
+        self.start < other.end && other.start < self.end
+    }
+
+    /// A window that does not end after it starts holds no time at all
+    pub fn is_empty(&self) -> bool {
+        self.end <= self.start
+    }
+}
+
+/// Job times on the wire: RFC 3339 at the server's millisecond precision. Finer digits,
+/// from a shifted clone or a computed pass, are cut here rather than left for the server
+/// to drop; times read back are kept exactly as sent.
+pub mod wire_time {
+    use chrono::{DateTime, Utc};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    /// `at` to the millisecond and no finer. That precision is the contract of the wire
+    /// format, since the server stores no more: digits beyond it are cut, not rounded.
+    pub fn serialize<S: Serializer>(at: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&super::format(*at))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<DateTime<Utc>, D::Error> {
+        DateTime::<Utc>::deserialize(deserializer)
+    }
+}
+
+/// "2026-10-20T12:15:00Z", or "2026-10-20T12:15:00.250Z" when the milliseconds matter
+pub fn format(at: DateTime<Utc>) -> String {
+    to_server_precision(at).to_rfc3339_opts(SecondsFormat::AutoSi, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{instant, CASES};
+    use chrono::Duration;
+    use proptest::prelude::*;
+
+    fn at(text: &str) -> DateTime<Utc> {
+        text.parse().expect("an RFC 3339 time")
+    }
+
+    /// Up to a day, to the millisecond
+    fn length() -> impl Strategy<Value = Duration> {
+        (0..86_400_000i64).prop_map(Duration::milliseconds)
+    }
+
+    #[test]
+    fn back_to_back_jobs_do_not_overlap() {
+        let first = TimeWindow::new(at("2026-10-20T12:00:00Z"), at("2026-10-20T12:15:00Z"));
+        let second = TimeWindow::new(at("2026-10-20T12:15:00Z"), at("2026-10-20T12:30:00Z"));
+        assert!(!first.overlaps(&second));
+        assert!(!second.overlaps(&first));
+
+        let touching = TimeWindow::new(at("2026-10-20T12:15:00Z"), at("2026-10-20T12:15:00Z"));
+        assert!(touching.is_empty());
+        assert!(!touching.overlaps(&first) && !touching.overlaps(&second));
+    }
+
+    #[test]
+    fn digits_past_the_millisecond_make_no_overlap() {
+        let first = TimeWindow::new(
+            at("2026-10-20T12:00:00Z"),
+            at("2026-10-20T12:15:00.000900Z"),
+        );
+        let second = TimeWindow::new(at("2026-10-20T12:15:00Z"), at("2026-10-20T12:30:00Z"));
+        assert!(!first.overlaps(&second));
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(CASES))]
+
+        #[test]
+        fn adjacent_windows_never_overlap(start in instant(), first in length(), second in length()) {
+            let left = TimeWindow::new(start, start + first);
+            let right = TimeWindow::new(start + first, start + first + second);
+            prop_assert!(!left.overlaps(&right));
+            prop_assert!(!right.overlaps(&left));
+        }
+
+        #[test]
+        fn a_nested_window_overlaps_unless_it_is_empty(
+            start in instant(),
+            before in length(),
+            inner in length(),
+            after in length(),
+        ) {
+            let outer = TimeWindow::new(start, start + before + inner + after);
+            let nested = TimeWindow::new(start + before, start + before + inner);
+            prop_assert_eq!(nested.overlaps(&outer), !nested.is_empty());
+            prop_assert_eq!(outer.overlaps(&nested), !nested.is_empty());
+        }
+
+        #[test]
+        fn a_window_overlaps_itself_unless_it_is_empty(start in instant(), span in length()) {
+            let window = TimeWindow::new(start, start + span);
+            prop_assert_eq!(window.overlaps(&window), !window.is_empty());
+            prop_assert_eq!(window.is_empty(), span.is_zero());
+        }
+
+        #[test]
+        fn overlapping_is_symmetric(a in instant(), a_span in length(), b_offset in -86_400_000..86_400_000i64, b_span in length()) {
+            let b = a + Duration::milliseconds(b_offset);
+            let first = TimeWindow::new(a, a + a_span);
+            let second = TimeWindow::new(b, b + b_span);
+            prop_assert_eq!(first.overlaps(&second), second.overlaps(&first));
+        }
+
+        #[test]
+        fn an_empty_window_overlaps_nothing(start in instant(), back in length(), other in instant(), span in length()) {
+            let empty = TimeWindow::new(start, start - back);
+            let window = TimeWindow::new(other, other + span);
+            prop_assert!(empty.is_empty());
+            prop_assert!(!empty.overlaps(&window));
+            prop_assert!(!window.overlaps(&empty));
+        }
+    }
+}
@@ -0,0 +1,335 @@
+use crate::error::{CliError, ResultExt};
+use crate::rotator;
+use crate::storage;
+use crate::transcript::{self, Transcript};
+use crate::OutputFormat;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// Top-level key holding a document's layout version; a document without one is version 1
+pub const VERSION_KEY: &str = "version";
+
+/// Rewrites a document of one version into the layout of the next
+pub type Migration = fn(Value) -> Result<Value, String>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Toml,
+}
+
+/// A file the CLI writes, and the steps that bring its older layouts up to date
+pub struct Store {
+    /// As it reads in messages: "offline queue"
+    pub name: &'static str,
+    pub format: Format,
+    /// `migrations[0]` turns version 1 into 2, and so on
+    pub migrations: &'static [Migration],
+}
+
+impl Store {
+    /// The version this CLI writes: one past the last migration
+    pub fn current(&self) -> u32 {
+        self.migrations.len() as u32 + 1
+    }
+
+    fn parse(&self, path: &Path, contents: &str) -> Result<Value, CliError> {
+        let what = || format!("{} {}", self.name, path.display());
+        match self.format {
+            Format::Json => serde_json::from_str(contents).map_err(|e| CliError::parse(what(), e)),
+            Format::Toml => {
+                let table: toml::Table =
+                    toml::from_str(contents).map_err(|e| CliError::parse(what(), e))?;
+                serde_json::to_value(table).map_err(|e| CliError::parse(what(), e))
+            }
+        }
+    }
+
+    fn render(&self, document: &Value) -> Result<String, CliError> {
+        match self.format {
+            Format::Json => {
+                serde_json::to_string_pretty(document).map_err(|e| CliError::parse(self.name, e))
+            }
+            Format::Toml => {
+                toml::to_string_pretty(document).map_err(|e| CliError::parse(self.name, e))
+            }
+        }
+    }
+
+    /// The version a document declares
+    fn version_of(&self, path: &Path, document: &Value) -> Result<u32, CliError> {
+        match document.get(VERSION_KEY) {
+            None => Ok(1),
+            Some(version) => version
+                .as_u64()
+                .and_then(|v| u32::try_from(v).ok())
+                .filter(|v| *v >= 1)
+                .ok_or_else(|| {
+                    CliError::Configuration(format!(
+                        "{} {} has an unreadable {} field: {}",
+                        self.name,
+                        path.display(),
+                        VERSION_KEY,
+                        version
+                    ))
+                }),
+        }
+    }
+
+    fn too_new(&self, path: &Path, found: u32) -> CliError {
+        CliError::Configuration(format!(
+            "{} {} is version {}, written by a newer rustar-cli; this one reads up to version {}. \
+             Upgrade rustar-cli, or move the file aside to start afresh",
+            self.name,
+            path.display(),
+            found,
+            self.current()
+        ))
+    }
+
+    /// `contents` read from `path`, with the version it declares
+    fn parse_versioned(&self, path: &Path, contents: &str) -> Result<(Value, u32), CliError> {
+        let document = self.parse(path, contents)?;
+        let found = self.version_of(path, &document)?;
+        if found > self.current() {
+            return Err(self.too_new(path, found));
+        }
+        Ok((document, found))
+    }
+
+    /// The file at `path` as written, with the version it declares; `None` when missing
+    fn read_raw(&self, path: &Path) -> Result<Option<(Value, u32)>, CliError> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("reading {} {}", self.name, path.display()))?;
+        self.parse_versioned(path, &contents).map(Some)
+    }
+
+    /// The version a copy of the file declares, such as one kept in a backup; `origin`
+    /// names the copy in messages
+    pub fn version(&self, origin: &Path, contents: &str) -> Result<u32, CliError> {
+        self.parse_versioned(origin, contents)
+            .map(|(_, found)| found)
+    }
+
+    /// A copy of the file brought up to the current layout in memory, version field
+    /// included; nothing on disk changes
+    pub fn upgrade(&self, origin: &Path, contents: &str) -> Result<Value, CliError> {
+        let (document, found) = self.parse_versioned(origin, contents)?;
+        self.migrate(origin, document, found, origin)
+    }
+
+    /// Bring a version `found` document up to the current layout in memory; `original`
+    /// names where the file as it was can be found, for the error
+    fn migrate(
+        &self,
+        path: &Path,
+        mut document: Value,
+        found: u32,
+        original: &Path,
+    ) -> Result<Value, CliError> {
+        for (step, migrate) in self.migrations.iter().enumerate().skip(found as usize - 1) {
+            document = migrate(document).map_err(|reason| {
+                CliError::Configuration(format!(
+                    "upgrading {} {} from version {}: {} (the original is in {})",
+                    self.name,
+                    path.display(),
+                    step + 1,
+                    reason,
+                    original.display()
+                ))
+            })?;
+        }
+        stamp(&mut document, self.current());
+        Ok(document)
+    }
+
+    /// Read the file at `path` in the current layout, upgrading it in place first when it
+    /// is older (the original is kept next to it as `<name>.v<N>.bak`). `None` when missing.
+    pub fn read(&self, path: &Path) -> Result<Option<Value>, CliError> {
+        let Some((document, found)) = self.read_raw(path)? else {
+            return Ok(None);
+        };
+        if found == self.current() {
+            return Ok(Some(document));
+        }
+
+        let backup = backup_path(path, found);
+        std::fs::copy(path, &backup)
+            .with_context(|| format!("backing up {} to {}", path.display(), backup.display()))?;
+        let document = self.migrate(path, document, found, &backup)?;
+        storage::write_atomic(path, self.render(&document)?)
+            .with_context(|| format!("saving the upgraded {}", self.name))?;
+        eprintln!(
+            "📦 Upgraded {} {} from version {} to {} (original kept as {})",
+            self.name,
+            path.display(),
+            found,
+            self.current(),
+            backup.display()
+        );
+        Ok(Some(document))
+    }
+
+    /// `read`, then the upgraded document as `T`; the version field is not passed on
+    pub fn load<T: DeserializeOwned>(&self, path: &Path) -> Result<Option<T>, CliError> {
+        self.read(path)?
+            .map(|mut document| {
+                if let Some(fields) = document.as_object_mut() {
+                    fields.remove(VERSION_KEY);
+                }
+                serde_json::from_value(document)
+                    .map_err(|e| CliError::parse(format!("{} {}", self.name, path.display()), e))
+            })
+            .transpose()
+    }
+
+    /// `load` without upgrading the file on disk: for previews that promise to change nothing
+    pub fn peek<T: DeserializeOwned>(&self, path: &Path) -> Result<Option<T>, CliError> {
+        self.read_raw(path)?
+            .map(|(document, found)| {
+                let mut document = self.migrate(path, document, found, path)?;
+                if let Some(fields) = document.as_object_mut() {
+                    fields.remove(VERSION_KEY);
+                }
+                serde_json::from_value(document)
+                    .map_err(|e| CliError::parse(format!("{} {}", self.name, path.display()), e))
+            })
+            .transpose()
+    }
+
+    /// Size in bytes of the file `write` would produce for `contents`
+    pub fn size<T: Serialize>(&self, contents: &T) -> Result<u64, CliError> {
+        let mut document =
+            serde_json::to_value(contents).map_err(|e| CliError::parse(self.name, e))?;
+        stamp(&mut document, self.current());
+        Ok(self.render(&document)?.len() as u64)
+    }
+
+    /// Write `contents` in the current layout, version field included; callers hold the lock
+    pub fn write<T: Serialize>(&self, path: &Path, contents: &T) -> Result<(), CliError> {
+        let mut document =
+            serde_json::to_value(contents).map_err(|e| CliError::parse(self.name, e))?;
+        stamp(&mut document, self.current());
+        storage::write_atomic(path, self.render(&document)?)
+            .with_context(|| format!("saving the {}", self.name))
+    }
+
+    /// What `doctor` says about the file, without changing it
+    fn inspect(&self, path: &Path) -> Report {
+        let (found, status) = match std::fs::read_to_string(path) {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => (None, "not created yet".into()),
+            Err(e) => (None, format!("unreadable: {}", e)),
+            Ok(contents) => match self
+                .parse(path, &contents)
+                .and_then(|document| self.version_of(path, &document))
+            {
+                Err(e) => (None, format!("unreadable: {}", e.full_message())),
+                Ok(found) if found > self.current() => {
+                    (Some(found), "newer than this rustar-cli".into())
+                }
+                Ok(found) if found < self.current() => (Some(found), "upgraded on next use".into()),
+                Ok(found) => (Some(found), "current".into()),
+            },
+        };
+        Report {
+            store: self.name,
+            path: path.to_path_buf(),
+            version: found,
+            current: self.current(),
+            ok: !status.starts_with("unreadable") && !status.starts_with("newer"),
+            status,
+        }
+    }
+}
+
+/// Set the version field of a document about to be written
+fn stamp(document: &mut Value, version: u32) {
+    if let Some(fields) = document.as_object_mut() {
+        fields.insert(VERSION_KEY.to_string(), version.into());
+    }
+}
+
+/// `queue.json` → `queue.json.v1.bak`
+fn backup_path(path: &Path, version: u32) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".v{}.bak", version));
+    path.with_file_name(name)
+}
+
+#[derive(Serialize)]
+struct Report {
+    store: &'static str,
+    path: PathBuf,
+    version: Option<u32>,
+    current: u32,
+    status: String,
+    #[serde(skip)]
+    ok: bool,
+}
+
+/// `doctor`: the layout version of every local store, then what the rotator probes of the
+/// profile found. Returns how many stores and probes need attention.
+pub fn doctor(
+    stores: &[(&Store, PathBuf)],
+    rotator: &[rotator::Status],
+    transcript: Option<(&Path, &Transcript)>,
+    output: OutputFormat,
+) -> Result<usize, CliError> {
+    let reports: Vec<Report> = stores
+        .iter()
+        .map(|(store, path)| store.inspect(path))
+        .collect();
+    let problems = reports.iter().filter(|r| !r.ok).count()
+        + rotator.iter().filter(|status| !status.ok).count();
+
+    if output == OutputFormat::Json {
+        let mut document = serde_json::json!({ "stores": reports, "rotator": rotator });
+        if let Some((_, transcript)) = transcript {
+            document["transcript"] = serde_json::to_value(transcript)
+                .map_err(|e| CliError::parse("the transcript", e))?;
+        }
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&document).map_err(|e| CliError::parse("stores", e))?
+        );
+        return Ok(problems);
+    }
+
+    println!("🩺 Local stores");
+    let width = reports.iter().map(|r| r.store.len()).max().unwrap_or(0);
+    for report in &reports {
+        let version = match report.version {
+            Some(version) => format!("v{} of v{}", version, report.current),
+            None => format!("v{}", report.current),
+        };
+        println!(
+            "  {} {:<width$}  {:<10}  {}  {}",
+            if report.ok { "✅" } else { "❌" },
+            report.store,
+            version,
+            report.status,
+            report.path.display(),
+            width = width
+        );
+    }
+    if !rotator.is_empty() {
+        println!("\n🧭 Rotator");
+        for status in rotator {
+            println!(
+                "  {} {}: {}",
+                if status.ok { "✅" } else { "❌" },
+                status.target,
+                status.detail
+            );
+        }
+    }
+    if let Some((path, transcript)) = transcript {
+        transcript::print(path, transcript);
+    }
+    Ok(problems)
+}
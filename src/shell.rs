@@ -0,0 +1,165 @@
+//! `shell`: commands typed at a prompt one after another, against one configuration and
+//! one API client. Each command returns its exit status to the prompt instead of leaving
+//! the process, so its failure is only its own.
+
+use crate::audit;
+use crate::error::{CliError, ResultExt};
+use crate::history::{self, Prompt};
+use crate::tty::{self, RawMode};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use std::future::Future;
+use std::io::Write;
+
+pub const PROMPT: &str = "rustar> ";
+
+/// Exit status of a command stopped with Ctrl-C, as other shells report it
+pub const EXIT_INTERRUPTED: i32 = 130;
+
+/// Run one command to its end and return its exit status. Ctrl-C stops it; a command
+/// that waits for Ctrl-C itself sees it first and finishes as usual.
+pub async fn run_command(command: impl Future<Output = i32>) -> i32 {
+    tokio::select! {
+        biased;
+        status = command => status,
+        _ = tokio::signal::ctrl_c() => {
+            eprintln!("\n🛑 Interrupted");
+            EXIT_INTERRUPTED
+        }
+    }
+}
+
+/// What came from the prompt
+pub enum Input {
+    Line(String),
+    /// Ctrl-C: the line is dropped
+    Interrupted,
+    /// Ctrl-D on an empty line, or the end of stdin
+    Closed,
+}
+
+/// Reads commands, with the arrows going through earlier ones
+pub struct LineEditor {
+    /// Oldest first
+    history: Vec<String>,
+}
+
+impl LineEditor {
+    pub fn new() -> Self {
+        let mut history = history::earlier(Prompt::ShellCommand);
+        history.reverse();
+        Self { history }
+    }
+
+    /// The next command: edited on the terminal, or the next line of stdin when it is
+    /// not one
+    pub fn read(&mut self) -> Result<Input, CliError> {
+        let raw = match tty::interactive() {
+            true => RawMode::enable(),
+            false => None,
+        };
+        let Some(raw) = raw else {
+            let mut line = String::new();
+            return match std::io::stdin()
+                .read_line(&mut line)
+                .context("reading stdin")?
+            {
+                0 => Ok(Input::Closed),
+                _ => Ok(Input::Line(line)),
+            };
+        };
+        let mut stderr = std::io::stderr();
+        let mut line: Vec<char> = Vec::new();
+        let mut cursor = 0;
+        // Which earlier command the arrows are on; the end is the line being typed
+        let mut browsing = self.history.len();
+        let mut typed: Vec<char> = Vec::new();
+        let input = loop {
+            draw(&mut stderr, &line, cursor);
+            let Event::Key(key) = event::read().context("reading the keyboard")? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            let control = key.modifiers.contains(KeyModifiers::CONTROL);
+            match key.code {
+                KeyCode::Enter => break Input::Line(line.iter().collect()),
+                KeyCode::Char('c') if control => break Input::Interrupted,
+                KeyCode::Char('d') if control && line.is_empty() => break Input::Closed,
+                KeyCode::Char('a') if control => cursor = 0,
+                KeyCode::Char('e') if control => cursor = line.len(),
+                KeyCode::Char('u') if control => {
+                    line.drain(..cursor);
+                    cursor = 0;
+                }
+                KeyCode::Char(c) if !control => {
+                    line.insert(cursor, c);
+                    cursor += 1;
+                }
+                KeyCode::Backspace if cursor > 0 => {
+                    cursor -= 1;
+                    line.remove(cursor);
+                }
+                KeyCode::Delete if cursor < line.len() => {
+                    line.remove(cursor);
+                }
+                KeyCode::Left => cursor = cursor.saturating_sub(1),
+                KeyCode::Right => cursor = (cursor + 1).min(line.len()),
+                KeyCode::Home => cursor = 0,
+                KeyCode::End => cursor = line.len(),
+                KeyCode::Up if browsing > 0 => {
+                    if browsing == self.history.len() {
+                        typed = line;
+                    }
+                    browsing -= 1;
+                    line = self.history[browsing].chars().collect();
+                    cursor = line.len();
+                }
+                KeyCode::Down if browsing < self.history.len() => {
+                    browsing += 1;
+                    line = match self.history.get(browsing) {
+                        Some(earlier) => earlier.chars().collect(),
+                        None => typed.clone(),
+                    };
+                    cursor = line.len();
+                }
+                _ => {}
+            }
+        };
+        // Raw mode needs the carriage return spelled out
+        let end = match input {
+            Input::Interrupted => "^C",
+            _ => "",
+        };
+        let _ = write!(stderr, "{}\r\n", end);
+        drop(raw);
+        if let Input::Line(line) = &input {
+            self.remember(line);
+        }
+        Ok(input)
+    }
+
+    /// Keep `line` for the arrows, and for the next shell unless it carries a secret
+    fn remember(&mut self, line: &str) {
+        let line = line.trim();
+        if line.is_empty() {
+            return;
+        }
+        self.history.retain(|earlier| earlier != line);
+        self.history.push(line.to_string());
+        let words: Vec<String> = line.split_whitespace().map(str::to_string).collect();
+        if audit::redact(words.clone()) == words {
+            history::record(Prompt::ShellCommand, line);
+        }
+    }
+}
+
+fn draw(out: &mut impl Write, line: &[char], cursor: usize) {
+    let text: String = line.iter().collect();
+    let _ = write!(out, "\r{}{}\x1b[K", PROMPT, text);
+    let back = line.len() - cursor;
+    if back > 0 {
+        let _ = write!(out, "\x1b[{}D", back);
+    }
+    let _ = out.flush();
+}
@@ -0,0 +1,165 @@
+use crate::reproducible;
+use std::collections::VecDeque;
+use std::io::Write;
+
+/// Samples shown in the sparkline
+const WINDOW: usize = 60;
+const BLOCKS: &[char] = &['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+const ASCII_BARS: &[char] = &['_', '.', '-', '~', '=', '+', '*', '#'];
+/// Payload fields read as signal strength, in order of preference, with their unit
+const SIGNAL_FIELDS: &[(&str, &str, &str)] = &[
+    ("snr", "SNR", "dB"),
+    ("snr_db", "SNR", "dB"),
+    ("rssi", "RSSI", "dBm"),
+    ("rssi_dbm", "RSSI", "dBm"),
+];
+
+/// Whether the locale promises a UTF-8 terminal; never with `--reproducible`, which
+/// draws as the C locale would
+fn unicode_terminal() -> bool {
+    !reproducible::enabled()
+        && ["LC_ALL", "LC_CTYPE", "LANG"]
+            .iter()
+            .find_map(|var| std::env::var(var).ok().filter(|v| !v.is_empty()))
+            .is_some_and(|locale| {
+                let locale = locale.to_ascii_lowercase();
+                locale.contains("utf-8") || locale.contains("utf8")
+            })
+}
+
+fn number(payload: &serde_json::Value, field: &str) -> Option<f64> {
+    payload.get(field).and_then(serde_json::Value::as_f64)
+}
+
+/// One-line live view of signal strength and pointing for `mqtt follow`, redrawn in place.
+/// It starts with the first message carrying a signal field; until then nothing changes.
+pub struct SignalView {
+    samples: VecDeque<f64>,
+    label: &'static str,
+    unit: &'static str,
+    peak: Option<f64>,
+    pointing: Option<(f64, f64)>,
+    /// Plain bars, labels and units, for terminals without Unicode
+    ascii: bool,
+    /// The line is on screen and the cursor hidden
+    drawn: bool,
+}
+
+impl SignalView {
+    /// `ascii` forces plain bars; they are also used when the locale is not UTF-8
+    pub fn new(ascii: bool) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(WINDOW),
+            label: "",
+            unit: "",
+            peak: None,
+            pointing: None,
+            ascii: ascii || !unicode_terminal(),
+            drawn: false,
+        }
+    }
+
+    /// Take in a message; true when it went into the view and needs no printing of its own
+    pub fn update(&mut self, payload: &serde_json::Value) -> bool {
+        let pointing = number(payload, "azimuth")
+            .zip(number(payload, "elevation"))
+            .or_else(|| number(payload, "az").zip(number(payload, "el")));
+        if pointing.is_some() {
+            self.pointing = pointing;
+        }
+
+        let signal = SIGNAL_FIELDS
+            .iter()
+            .find_map(|&(field, label, unit)| Some((number(payload, field)?, label, unit)));
+        match signal {
+            Some((value, label, unit)) => {
+                if self.samples.len() == WINDOW {
+                    self.samples.pop_front();
+                }
+                self.samples.push_back(value);
+                self.peak = Some(self.peak.map_or(value, |peak| peak.max(value)));
+                (self.label, self.unit) = (label, unit);
+            }
+            // Pointing alone only updates a view that is already showing
+            None if pointing.is_none() || self.samples.is_empty() => return false,
+            None => {}
+        }
+        self.draw();
+        true
+    }
+
+    fn sparkline(&self) -> String {
+        let low = self.samples.iter().copied().fold(f64::INFINITY, f64::min);
+        let high = self
+            .samples
+            .iter()
+            .copied()
+            .fold(f64::NEG_INFINITY, f64::max);
+        let bars = if self.ascii { ASCII_BARS } else { BLOCKS };
+        let top = bars.len() - 1;
+        self.samples
+            .iter()
+            .map(|&value| {
+                let level = if high > low {
+                    ((value - low) / (high - low) * top as f64).round() as usize
+                } else {
+                    top / 2
+                };
+                bars[level.min(top)]
+            })
+            .collect()
+    }
+
+    fn draw(&mut self) {
+        let Some(&current) = self.samples.back() else {
+            return;
+        };
+        let (prefix, degrees) = if self.ascii {
+            ("signal", " deg")
+        } else {
+            ("📶", "°")
+        };
+        let pointing = self
+            .pointing
+            .map(|(az, el)| format!("  az {:.1}{} el {:.1}{}", az, degrees, el, degrees))
+            .unwrap_or_default();
+        let mut stdout = std::io::stdout().lock();
+        // Hide the cursor while the line is live; `Drop` shows it again
+        let hide = if self.drawn { "" } else { "\x1b[?25l" };
+        let _ = write!(
+            stdout,
+            "{}\r\x1b[2K{} {} {} {:.1} {} (peak {:.1}){}",
+            hide,
+            prefix,
+            self.sparkline(),
+            self.label,
+            current,
+            self.unit,
+            self.peak.unwrap_or(current),
+            pointing
+        );
+        let _ = stdout.flush();
+        self.drawn = true;
+    }
+
+    /// Print something above the live line: clear it, run `print`, then draw it again
+    pub fn above(&mut self, print: impl FnOnce()) {
+        if self.drawn {
+            print!("\r\x1b[2K");
+            let _ = std::io::stdout().flush();
+        }
+        print();
+        if self.drawn {
+            self.draw();
+        }
+    }
+}
+
+impl Drop for SignalView {
+    fn drop(&mut self) {
+        if self.drawn {
+            print!("\n\x1b[?25h");
+            let _ = std::io::stdout().flush();
+        }
+    }
+}
@@ -0,0 +1,366 @@
+use crate::client::{ApiClient, JobDTO, JobRequestDTO, JobType};
+use crate::error::{CliError, ResultExt};
+use crate::humanize;
+use crate::input_schema;
+use crate::location::Location;
+use crate::mask::ElevationMask;
+use crate::orbit::{LookAngle, Satellite};
+use crate::predict::{self, Horizon};
+use crate::tty::RawMode;
+use chrono::{DateTime, Duration, Utc};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use std::io::{IsTerminal, Write};
+use std::path::Path;
+use std::time::Instant;
+
+/// Fastest replay; beyond this the rows scroll by faster than anyone can read
+const MAX_SPEED: f64 = 3600.0;
+/// Passes that rose before the window opens still decide where AOS falls
+const PASS_LOOKBACK_MIN: i64 = 30;
+
+/// How the rehearsal is paced
+pub struct Pace {
+    /// Simulated seconds per real second
+    pub speed: f64,
+    /// Simulated time between rows
+    pub step: Duration,
+    /// Countdown before the window opens
+    pub lead: Duration,
+}
+
+/// "60x" or "60": how many times faster than real time to replay
+pub fn parse_speed(input: &str) -> Result<f64, CliError> {
+    let trimmed = input.trim();
+    let number = trimmed
+        .strip_suffix(['x', 'X'])
+        .unwrap_or(trimmed)
+        .trim()
+        .parse::<f64>()
+        .map_err(|e| CliError::parse(format!("speed '{}'", trimmed), e))?;
+    if !(number > 0.0 && number <= MAX_SPEED) {
+        return Err(CliError::validation(
+            "speed",
+            format!("must be above 0 and at most {}x", MAX_SPEED),
+        ));
+    }
+    Ok(number)
+}
+
+/// A scheduled job, fetched to rehearse it
+pub async fn fetch(client: &ApiClient, id: &str) -> Result<JobRequestDTO, CliError> {
+    let value = client.job_json(id).await?;
+    if value.is_null() {
+        return Err(CliError::NotFound {
+            message: Some(format!("the server returned no body for job {}", id)),
+        });
+    }
+    let job: JobDTO =
+        serde_json::from_value(value).map_err(|e| CliError::parse(format!("job {}", id), e))?;
+    Ok(job.job)
+}
+
+/// A job definition from a file, checked against the job schema before it is read
+pub fn read_file(path: &Path) -> Result<JobRequestDTO, CliError> {
+    let value = read_value(path)?;
+    if let Some(problem) = input_schema::check_job(&value).into_iter().next() {
+        return Err(problem.into());
+    }
+    serde_json::from_value(value)
+        .map_err(|e| CliError::parse(format!("job in {}", path.display()), e))
+}
+
+/// The job object in a file: the whole file, or the one entry of a batch file
+pub fn read_value(path: &Path) -> Result<serde_json::Value, CliError> {
+    let contents =
+        std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    let value: serde_json::Value = serde_json::from_str(&contents)
+        .map_err(|e| CliError::parse(format!("job file {}", path.display()), e))?;
+    match value {
+        serde_json::Value::Array(mut jobs) if jobs.len() == 1 => Ok(jobs.remove(0)),
+        serde_json::Value::Array(jobs) => Err(CliError::validation(
+            "file",
+            format!(
+                "{} holds {} jobs; simulate rehearses one at a time",
+                path.display(),
+                jobs.len()
+            ),
+        )),
+        other => Ok(other),
+    }
+}
+
+/// Prints the timeline and waits between rows, pausing on the space bar when interactive
+struct Player {
+    speed: f64,
+    raw: Option<RawMode>,
+}
+
+impl Player {
+    fn new(speed: f64) -> Self {
+        let interactive = std::io::stdin().is_terminal() && std::io::stdout().is_terminal();
+        Self {
+            speed,
+            raw: if interactive { RawMode::enable() } else { None },
+        }
+    }
+
+    /// Print one line; raw mode needs the carriage return spelled out
+    fn line(&self, text: impl AsRef<str>) {
+        let mut stdout = std::io::stdout().lock();
+        let end = if self.raw.is_some() { "\r\n" } else { "\n" };
+        let _ = write!(stdout, "{}{}", text.as_ref(), end);
+        let _ = stdout.flush();
+    }
+
+    /// Let `simulated` time pass; false once the user asked to stop
+    fn wait(&self, simulated: Duration) -> Result<bool, CliError> {
+        let real = simulated.to_std().unwrap_or_default().div_f64(self.speed);
+        let mut deadline = Instant::now() + real;
+        if self.raw.is_none() {
+            std::thread::sleep(real);
+            return Ok(true);
+        }
+
+        let mut paused_at: Option<Instant> = None;
+        loop {
+            let now = Instant::now();
+            if paused_at.is_none() && now >= deadline {
+                return Ok(true);
+            }
+            let timeout = match paused_at {
+                Some(_) => std::time::Duration::from_secs(3600),
+                None => deadline - now,
+            };
+            if !event::poll(timeout).context("reading the keyboard")? {
+                continue;
+            }
+            let Event::Key(key) = event::read().context("reading the keyboard")? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char(' ') => match paused_at.take() {
+                    Some(since) => {
+                        deadline += since.elapsed();
+                        self.line("▶️ Resumed");
+                    }
+                    None => {
+                        paused_at = Some(Instant::now());
+                        self.line("⏸️ Paused (space to resume, q to stop)");
+                    }
+                },
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(false),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    return Ok(false)
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// "T-04:50" / "T+01:10" relative to the window opening
+fn clock(at: DateTime<Utc>, start: DateTime<Utc>) -> String {
+    let offset = at - start;
+    let seconds = offset.num_seconds().abs();
+    format!(
+        "T{}{:02}:{:02}",
+        if offset < Duration::zero() { '-' } else { '+' },
+        seconds / 60,
+        seconds % 60
+    )
+}
+
+fn row(job: &JobRequestDTO, look: &LookAngle) -> String {
+    let mut text = format!(
+        "  {}  {}  az {:6.1}° el {:5.1}°",
+        look.at.format("%H:%M:%SZ"),
+        clock(look.at, job.start),
+        look.azimuth_deg,
+        look.elevation_deg
+    );
+    if look.elevation_deg < 0.0 {
+        text.push_str("  (below the horizon)");
+        return text;
+    }
+    text.push_str(&format!(
+        "  range {:6.0} km  RX {}",
+        look.range_km,
+        humanize::frequency(look.downlink(job.rx_frequency.as_f64()))
+    ));
+    if !job.tx_frequency.is_zero() {
+        text.push_str(&format!(
+            "  TX {}",
+            humanize::frequency(look.uplink(job.tx_frequency.as_f64()))
+        ));
+    }
+    text
+}
+
+/// "AFSK, 1200 baud", or what the station falls back to
+fn decoder(job: &JobRequestDTO) -> String {
+    match (&job.mode, job.baud) {
+        (Some(mode), Some(baud)) => format!("{}, {} baud", mode, baud),
+        (Some(mode), None) => mode.clone(),
+        (None, Some(baud)) => format!("station default mode, {} baud", baud),
+        (None, None) => "station default mode".to_string(),
+    }
+}
+
+/// Replay a job's pass on the local clock: countdown, pointing and Doppler-corrected
+/// frequencies at every step, and where the decoder would change state.
+/// Nothing is sent to the station.
+pub fn rehearse(
+    job: &JobRequestDTO,
+    station: &Location,
+    mask: &ElevationMask,
+    pace: Pace,
+) -> Result<(), CliError> {
+    if let JobType::Pointing { pointing } = &job.job_type {
+        println!(
+            "🎯 {} holds the antenna at az {}°, el {}° from {} to {}: nothing moves and no Doppler applies, so there is nothing to rehearse",
+            job.label(),
+            pointing.azimuth,
+            pointing.elevation,
+            humanize::timestamp(job.start),
+            humanize::timestamp(job.end)
+        );
+        return Ok(());
+    }
+    let JobType::Tracking { tle } = &job.job_type else {
+        return Err(CliError::validation(
+            "job",
+            format!(
+                "a {} job has no satellite pass to rehearse",
+                job.job_type.name()
+            ),
+        ));
+    };
+    if pace.step.num_milliseconds() < crate::look_angles::MIN_STEP_MS {
+        return Err(CliError::validation(
+            "step",
+            format!("must be at least {}ms", crate::look_angles::MIN_STEP_MS),
+        ));
+    }
+    let satellite = Satellite::from_tle(tle)?;
+    let horizon = Horizon {
+        station,
+        min_elevation_deg: 0.0,
+        mask,
+    };
+    let begin = job.start - pace.lead;
+    let passes = predict::passes(
+        &satellite,
+        &horizon,
+        job.start - Duration::minutes(PASS_LOOKBACK_MIN),
+        job.end,
+    )?;
+
+    // Decoder state changes, in order
+    let mut events = vec![
+        (
+            job.start,
+            format!(
+                "▶️ Window opens: RX tuned to {} with {}{}",
+                job.rx_frequency,
+                decoder(job),
+                job.polarization
+                    .map(|p| format!(", {} feed", p.code()))
+                    .unwrap_or_default()
+            ),
+        ),
+        (
+            job.end,
+            match job.post_pass_action {
+                Some(action) => format!("⏹️ Window closes: decoder stopped, then {}", action),
+                None => "⏹️ Window closes: decoder stopped".to_string(),
+            },
+        ),
+    ];
+    for pass in passes.iter().filter(|p| p.set > job.start) {
+        if let (Some(aos), Some(azimuth)) = (pass.aos, pass.aos_azimuth_deg) {
+            if aos > job.start {
+                events.push((
+                    aos,
+                    format!(
+                        "🛰️ AOS at az {:.0}°: {} starts decoding",
+                        azimuth,
+                        decoder(job)
+                    ),
+                ));
+            } else {
+                events.push((
+                    job.start,
+                    "🛰️ Already above the horizon: decoding from the start".to_string(),
+                ));
+            }
+        }
+        if (job.start..=job.end).contains(&pass.max_elevation_at) {
+            events.push((
+                pass.max_elevation_at,
+                format!("⛰️ Highest point: el {:.1}°", pass.max_elevation_deg),
+            ));
+        }
+        if let (Some(los), Some(azimuth)) = (pass.los, pass.los_azimuth_deg) {
+            if los < job.end {
+                events.push((
+                    los,
+                    format!(
+                        "🌅 LOS at az {:.0}°: decoder idles until the window closes",
+                        azimuth
+                    ),
+                ));
+            }
+        }
+    }
+    events.sort_by_key(|(at, _)| *at);
+    let first_aos = passes
+        .iter()
+        .filter_map(|p| p.aos)
+        .find(|aos| *aos >= begin && *aos <= job.end);
+
+    let player = Player::new(pace.speed);
+    player.line(format!(
+        "🎬 Rehearsing {} from {} to {} at {}x; nothing is sent to the station",
+        satellite.name,
+        humanize::timestamp(job.start),
+        humanize::timestamp(job.end),
+        pace.speed
+    ));
+    if player.raw.is_some() {
+        player.line("   Space pauses and resumes, q stops");
+    }
+    if passes.is_empty() {
+        player.line("⚠️ The satellite does not rise above the horizon during this window");
+    }
+
+    let mut pending = events.into_iter().peekable();
+    let mut at = begin;
+    loop {
+        while let Some((_, text)) = pending.next_if(|(when, _)| *when <= at) {
+            player.line(text);
+        }
+        if at < job.start {
+            let countdown = match first_aos.filter(|aos| *aos > at) {
+                Some(aos) => format!("AOS in {}", humanize::duration(aos - at)),
+                None => format!("window opens in {}", humanize::duration(job.start - at)),
+            };
+            player.line(format!("⏳ {}  {}", clock(at, job.start), countdown));
+        } else {
+            player.line(row(job, &satellite.look_angle(station, at)?));
+        }
+        if at >= job.end {
+            break;
+        }
+        let next = (at + pace.step).min(job.end);
+        if !player.wait(next - at)? {
+            player.line("⏹️ Rehearsal stopped");
+            return Ok(());
+        }
+        at = next;
+    }
+    player.line("🏁 Rehearsal finished");
+    Ok(())
+}
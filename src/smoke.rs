@@ -0,0 +1,550 @@
+//! `smoke-test`: the checklist run by hand after standing up a station server, as one
+//! command. A short receive-only job goes through its whole life on the server, each step
+//! through the client methods the other commands use, so a pass says they work there.
+
+use crate::cancel::Reason;
+use crate::client::{ApiClient, JobDTO, JobType, JobUpdate, Version};
+use crate::clock;
+use crate::error::CliError;
+use crate::frequency::Frequency;
+use crate::job_request::JobRequestBuilder;
+use crate::progress::Observer;
+use crate::tle;
+use crate::OutputFormat;
+use chrono::{DateTime, Duration, SubsecRound, Utc};
+use reqwest::{Method, StatusCode};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// Steps of the sequence, for the "[3/9]" of each line
+const STEPS: usize = 9;
+/// Least time between submitting the job and its start, when the station asks for less
+const LEAD_MINUTES: i64 = 5;
+/// Length of the job's window
+const WINDOW_MINUTES: i64 = 2;
+/// The downlink the job listens on, and where the update moves it
+const RX: Frequency = Frequency::from_hz(437_800_000);
+const RX_UPDATED: Frequency = Frequency::from_hz(437_801_000);
+
+/// One API request of the run as the observer saw it
+#[derive(Debug, Clone, Serialize)]
+pub struct Exchange {
+    method: String,
+    path: String,
+    /// None when no response arrived
+    status: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    request_id: Option<String>,
+}
+
+/// Notes every request with the ID the server gave it, passing each report on to the
+/// observer it wraps
+pub struct Recorder<O> {
+    inner: O,
+    exchanges: Mutex<Vec<Exchange>>,
+}
+
+impl<O: Observer> Recorder<O> {
+    pub fn new(inner: O) -> Self {
+        Self {
+            inner,
+            exchanges: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// The requests since the last call
+    fn take(&self) -> Vec<Exchange> {
+        std::mem::take(&mut *self.exchanges.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+}
+
+impl<O: Observer> Observer for Recorder<O> {
+    fn on_client_ready(&self, base_url: &str) {
+        self.inner.on_client_ready(base_url);
+    }
+
+    fn on_job_submit(&self, url: &str, hold: Option<&str>) {
+        self.inner.on_job_submit(url, hold);
+    }
+
+    fn on_request_start(&self, method: &Method, path: &str) {
+        self.inner.on_request_start(method, path);
+    }
+
+    fn on_request_end(
+        &self,
+        method: &Method,
+        path: &str,
+        status: Option<StatusCode>,
+        elapsed: std::time::Duration,
+    ) {
+        self.exchanges
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(Exchange {
+                method: method.to_string(),
+                path: path.to_string(),
+                status: status.map(|s| s.as_u16()),
+                request_id: None,
+            });
+        self.inner.on_request_end(method, path, status, elapsed);
+    }
+
+    fn on_request_id(&self, method: &Method, path: &str, request_id: &str) {
+        // Reported right after the end of the same request
+        if let Some(last) = self
+            .exchanges
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .last_mut()
+            .filter(|last| last.method == method.as_str() && last.path == path)
+        {
+            last.request_id = Some(request_id.to_string());
+        }
+        self.inner.on_request_id(method, path, request_id);
+    }
+
+    fn on_retry(&self, attempt: u32, reason: &str) {
+        self.inner.on_retry(attempt, reason);
+    }
+
+    fn on_download_progress(&self, path: &str, received: u64, total: Option<u64>) {
+        self.inner.on_download_progress(path, received, total);
+    }
+
+    fn on_batch_progress(&self, done: usize, total: usize) {
+        self.inner.on_batch_progress(done, total);
+    }
+}
+
+/// One step as reported
+#[derive(Debug, Serialize)]
+struct Step {
+    name: &'static str,
+    passed: bool,
+    ms: f64,
+    /// What the step saw, when it passed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detail: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    requests: Vec<Exchange>,
+    /// The body the step sent, and the last one it received
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sent: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    received: Option<Value>,
+}
+
+/// The steps so far, each printed as it ends in text output
+struct Run<'a, O> {
+    recorder: &'a Recorder<O>,
+    output: OutputFormat,
+    steps: Vec<Step>,
+    started: Instant,
+    sent: Option<Value>,
+    received: Option<Value>,
+}
+
+impl<O: Observer> Run<'_, O> {
+    /// Start a step: what earlier ones left behind is not its own
+    fn begin(&mut self) {
+        self.recorder.take();
+        self.started = Instant::now();
+        self.sent = None;
+        self.received = None;
+    }
+
+    fn sending(&mut self, body: &impl Serialize) {
+        self.sent = serde_json::to_value(body).ok();
+    }
+
+    fn receiving(&mut self, body: &impl Serialize) {
+        self.received = serde_json::to_value(body).ok();
+    }
+
+    /// Record how the step went; a failure ends the run with its error
+    fn end(
+        &mut self,
+        name: &'static str,
+        result: Result<String, CliError>,
+    ) -> Result<(), CliError> {
+        let elapsed = self.started.elapsed();
+        let step = Step {
+            name,
+            passed: result.is_ok(),
+            ms: (elapsed.as_secs_f64() * 1_000_000.0).round() / 1000.0,
+            detail: result.as_ref().ok().cloned(),
+            error: result.as_ref().err().map(CliError::full_message),
+            requests: self.recorder.take(),
+            sent: self.sent.take(),
+            received: self.received.take(),
+        };
+        if self.output != OutputFormat::Json {
+            print(self.steps.len() + 1, &step);
+        }
+        self.steps.push(step);
+        result.map(drop)
+    }
+}
+
+fn print(number: usize, step: &Step) {
+    let head = format!("[{}/{}] {} ({:.0} ms)", number, STEPS, step.name, step.ms);
+    match &step.detail {
+        Some(detail) => println!("✅ {}: {}", head, detail),
+        None => {
+            println!("❌ {}", head);
+            for exchange in &step.requests {
+                let status = exchange
+                    .status
+                    .map_or("no response".to_string(), |s| s.to_string());
+                match &exchange.request_id {
+                    Some(id) => println!(
+                        "   {} {} → {}, request id {}",
+                        exchange.method, exchange.path, status, id
+                    ),
+                    None => println!(
+                        "   {} {} → {}, no request id",
+                        exchange.method, exchange.path, status
+                    ),
+                }
+            }
+            for (label, body) in [("sent", &step.sent), ("received", &step.received)] {
+                if let Some(body) = body {
+                    println!("   {}: {}", label, body);
+                }
+            }
+        }
+    }
+}
+
+/// A job's own fields out of what the server holds
+fn job(value: &Value, id: &str) -> Result<JobDTO, CliError> {
+    serde_json::from_value(value.clone()).map_err(|e| CliError::parse(format!("job {}", id), e))
+}
+
+fn mismatch(field: &str, sent: impl std::fmt::Display, got: impl std::fmt::Display) -> CliError {
+    CliError::validation(
+        field,
+        format!("the server holds {} where {} was sent", got, sent),
+    )
+}
+
+/// "12:05:00–12:07:00 UTC"
+fn window(start: DateTime<Utc>, end: DateTime<Utc>) -> String {
+    format!(
+        "{}–{} UTC",
+        start.format("%H:%M:%S"),
+        end.format("%H:%M:%S")
+    )
+}
+
+/// The whole sequence against `client`, which reports its requests to `recorder`: stop at
+/// the first step that fails, cancelling the job by then submitted when `cleanup` says
+/// so. Refused outright on a protected profile; it creates jobs there.
+pub async fn run<O: Observer>(
+    client: &ApiClient,
+    recorder: &Recorder<O>,
+    (profile, protected): (&str, bool),
+    cleanup: bool,
+    output: OutputFormat,
+) -> Result<(), CliError> {
+    if protected {
+        return Err(CliError::Configuration(format!(
+            "profile {} is protected; smoke-test submits and cancels a job, so it only runs against unprotected profiles",
+            profile
+        )));
+    }
+    let started = Instant::now();
+    if output != OutputFormat::Json {
+        println!(
+            "🧪 Smoke test of {} (profile {})",
+            client.base_url(),
+            profile
+        );
+    }
+    let mut run = Run {
+        recorder,
+        output,
+        steps: Vec::new(),
+        started,
+        sent: None,
+        received: None,
+    };
+    let mut submitted = None;
+    let result = sequence(client, &mut run, &mut submitted).await;
+
+    let left = match (&result, submitted) {
+        (Err(_), Some(id)) if cleanup => {
+            let reason = reason();
+            match client.cancel_job(&id, Some(&reason), None).await {
+                Ok(_) => {
+                    if output != OutputFormat::Json {
+                        println!("🧹 Job {} cancelled (--cleanup)", id);
+                    }
+                    None
+                }
+                Err(e) => {
+                    eprintln!("⚠️ Could not cancel job {}: {}", id, e.full_message());
+                    Some(id)
+                }
+            }
+        }
+        (Err(_), Some(id)) => {
+            if output != OutputFormat::Json {
+                println!(
+                    "   Job {} is left on the server for a look; cancel it with rustar-cli jobs cancel {}, or pass --cleanup",
+                    id, id
+                );
+            }
+            Some(id)
+        }
+        _ => None,
+    };
+
+    let passed = result.is_ok();
+    match output {
+        OutputFormat::Json => println!(
+            "{}",
+            json!({
+                "passed": passed,
+                "base_url": client.base_url(),
+                "profile": profile,
+                "left_job_id": left,
+                "steps": run.steps,
+                "total_ms": started.elapsed().as_millis() as u64,
+            })
+        ),
+        _ if passed => println!(
+            "🎉 Smoke test passed: {} steps in {:.1} s",
+            STEPS,
+            started.elapsed().as_secs_f64()
+        ),
+        _ => {}
+    }
+    result
+}
+
+fn reason() -> Reason {
+    Reason {
+        reason: "other".to_string(),
+        reason_text: Some("rustar-cli smoke-test".to_string()),
+    }
+}
+
+async fn sequence<O: Observer>(
+    client: &ApiClient,
+    run: &mut Run<'_, O>,
+    submitted: &mut Option<String>,
+) -> Result<(), CliError> {
+    run.begin();
+    let result = client
+        .ping()
+        .await
+        .map(|()| "the API answers /health".to_string());
+    run.end("health check", result)?;
+
+    run.begin();
+    let mut lead = Duration::minutes(LEAD_MINUTES);
+    let result = match client.capabilities().await {
+        Ok(capabilities) => {
+            run.receiving(&json!({
+                "min_lead_seconds": capabilities.min_lead_seconds,
+                "max_pending_jobs": capabilities.max_pending_jobs,
+            }));
+            if let Some(seconds) = capabilities.min_lead_seconds {
+                lead = lead.max(Duration::seconds(seconds as i64) + Duration::minutes(1));
+            }
+            let limits: Vec<String> = [
+                capabilities
+                    .min_lead_seconds
+                    .map(|s| format!("jobs at least {}s ahead", s)),
+                capabilities
+                    .max_pending_jobs
+                    .map(|n| format!("at most {} pending", n)),
+            ]
+            .into_iter()
+            .flatten()
+            .collect();
+            Ok(match limits.is_empty() {
+                true => "no scheduler limits given".to_string(),
+                false => limits.join(", "),
+            })
+        }
+        // Servers before the endpoint schedule without announcing limits
+        Err(e) if e.status() == Some(404) => {
+            Ok("not offered by this server; no limits assumed".to_string())
+        }
+        Err(e) => Err(e),
+    };
+    run.end("capabilities", result)?;
+
+    run.begin();
+    let start = (clock::now() + lead).trunc_subsecs(0);
+    let result = async {
+        let tle = tle::parse_text(tle::EXAMPLE_FILE, "ISS (ZARYA)")
+            .map_err(|e| CliError::parse("the bundled element set", e))?;
+        let job = JobRequestBuilder::new(
+            start,
+            start + Duration::minutes(WINDOW_MINUTES),
+            JobType::Tracking { tle },
+        )
+        .rx_frequency(RX)
+        .build()?;
+        run.sending(&*job);
+        let response = client.add_job(&job).await?;
+        run.receiving(&response);
+        let id = response.id.clone().ok_or_else(|| {
+            CliError::validation(
+                "response",
+                "the server accepted the job without naming its ID, so it cannot be followed",
+            )
+        })?;
+        *submitted = Some(id.clone());
+        Ok((job, id))
+    }
+    .await;
+    let (sent, id) = match result {
+        Ok((job, id)) => {
+            let detail = format!(
+                "job {}, {} on {}, receive only",
+                id,
+                window(job.start, job.end),
+                job.rx_frequency
+            );
+            run.end("submit a job", Ok(detail))?;
+            (job, id)
+        }
+        Err(e) => return run.end("submit a job", Err(e)),
+    };
+
+    run.begin();
+    let result = client.list_jobs().await.and_then(|jobs| {
+        match jobs
+            .iter()
+            .any(|job| job.id.as_deref() == Some(id.as_str()))
+        {
+            true => Ok(format!("job {} among {} listed", id, jobs.len())),
+            false => Err(CliError::NotFound {
+                message: Some(format!(
+                    "job {} is not among the {} jobs listed",
+                    id,
+                    jobs.len()
+                )),
+            }),
+        }
+    });
+    run.end("find it in the job list", result)?;
+
+    run.begin();
+    let mut version: Option<Version> = None;
+    let mut stored = Value::Null;
+    let result = async {
+        let fetched = client.job_versioned(&id).await?;
+        run.receiving(&fetched.value);
+        let held = job(&fetched.value, &id)?;
+        if (held.job.start, held.job.end) != (sent.start, sent.end) {
+            return Err(mismatch(
+                "window",
+                window(sent.start, sent.end),
+                window(held.job.start, held.job.end),
+            ));
+        }
+        if held.job.rx_frequency != sent.rx_frequency {
+            return Err(mismatch(
+                "rx_frequency",
+                sent.rx_frequency,
+                held.job.rx_frequency,
+            ));
+        }
+        version = fetched.version;
+        stored = fetched.value;
+        Ok(format!(
+            "window and frequency as sent{}",
+            match &version {
+                Some(_) => ", with a version to change it against",
+                None => "",
+            }
+        ))
+    }
+    .await;
+    run.end("get it back", result)?;
+
+    run.begin();
+    let result = async {
+        let how = client.job_update(&id).await?;
+        let body = match how {
+            JobUpdate::MergePatch => json!({ "rx_frequency": RX_UPDATED.hz() }),
+            JobUpdate::Replace => {
+                let mut whole = stored.clone();
+                whole["rx_frequency"] = json!(RX_UPDATED.hz());
+                whole
+            }
+        };
+        run.sending(&body);
+        let response = client.update_job(&id, how, &body, version.as_ref()).await?;
+        run.receiving(&response);
+        Ok(format!(
+            "rx_frequency {} → {} by {}",
+            RX,
+            RX_UPDATED,
+            match how {
+                JobUpdate::MergePatch => "merge patch",
+                JobUpdate::Replace => "replacing the job",
+            }
+        ))
+    }
+    .await;
+    run.end("update one field", result)?;
+
+    run.begin();
+    let result = async {
+        let fetched = client.job_versioned(&id).await?;
+        run.receiving(&fetched.value);
+        let held = job(&fetched.value, &id)?;
+        match held.job.rx_frequency == RX_UPDATED {
+            true => Ok(format!("the server holds {}", RX_UPDATED)),
+            false => Err(mismatch("rx_frequency", RX_UPDATED, held.job.rx_frequency)),
+        }
+    }
+    .await;
+    run.end("check the update", result)?;
+
+    run.begin();
+    let reason = reason();
+    run.sending(&reason);
+    let result = client
+        .cancel_job(&id, Some(&reason), None)
+        .await
+        .map(|response| {
+            run.receiving(&response);
+            format!("job {} {}", id, response.status())
+        });
+    run.end("cancel it", result)?;
+    *submitted = None;
+
+    run.begin();
+    let result = match client.job_versioned(&id).await {
+        Ok(fetched) => {
+            run.receiving(&fetched.value);
+            job(&fetched.value, &id).and_then(|held| match held.is_cancelled() {
+                true => Ok("marked cancelled".to_string()),
+                false => Err(CliError::validation(
+                    "status",
+                    format!(
+                        "job {} is still {} after its cancellation",
+                        id,
+                        held.status.as_deref().unwrap_or("there")
+                    ),
+                )),
+            })
+        }
+        Err(e) if matches!(e.status(), Some(404 | 410)) => {
+            Ok("the server no longer holds it".to_string())
+        }
+        Err(e) => Err(e),
+    };
+    run.end("check the cancellation", result)
+}
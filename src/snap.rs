@@ -0,0 +1,149 @@
+use crate::humanize;
+use chrono::{DateTime, Duration, Timelike, Utc};
+use serde::Deserialize;
+use std::sync::OnceLock;
+
+/// Which way a time off the grid is moved
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    /// To the grid point at or before it
+    Floor,
+    /// To the grid point at or after it
+    Ceil,
+    /// To the closer grid point; halfway goes later
+    #[default]
+    Nearest,
+}
+
+impl Direction {
+    fn name(self) -> &'static str {
+        match self {
+            Direction::Floor => "floor",
+            Direction::Ceil => "ceil",
+            Direction::Nearest => "nearest",
+        }
+    }
+}
+
+/// The grid the station scheduler keeps job times on, from the profile's `[snap]` table
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(default)]
+pub struct SnapPolicy {
+    /// Spacing of the grid; unset or 0 sends times as entered
+    pub granularity_seconds: Option<u64>,
+    pub direction: Direction,
+}
+
+/// A grid in force for this invocation
+#[derive(Debug, Clone, Copy)]
+struct Grid {
+    step: Duration,
+    direction: Direction,
+}
+
+/// Set once from the profile and `--no-snap`; none sends times as entered
+static GRID: OnceLock<Option<Grid>> = OnceLock::new();
+
+/// The profile's `[snap]`, unless `--no-snap` was given
+pub fn set(policy: &SnapPolicy, disabled: bool) {
+    let grid = policy
+        .granularity_seconds
+        .filter(|seconds| *seconds > 0 && !disabled)
+        .and_then(|seconds| i64::try_from(seconds).ok())
+        .and_then(Duration::try_seconds)
+        .map(|step| Grid {
+            step,
+            direction: policy.direction,
+        });
+    let _ = GRID.set(grid);
+}
+
+fn current() -> Option<Grid> {
+    GRID.get().copied().flatten()
+}
+
+/// "10s grid (nearest)" for the grid in force, or "none" when times are sent as entered
+pub fn policy() -> String {
+    match current() {
+        Some(grid) => format!(
+            "{} grid ({})",
+            humanize::duration(grid.step),
+            grid.direction.name()
+        ),
+        None => "none".to_string(),
+    }
+}
+
+/// `at` moved onto the grid of `step` counted from the Unix epoch. Works in nanoseconds,
+/// so an input with a fraction of a second is moved like any other; one already on the
+/// grid is returned unchanged whatever the direction.
+pub fn round(at: DateTime<Utc>, step: Duration, direction: Direction) -> DateTime<Utc> {
+    let Some(step) = step.num_nanoseconds().filter(|n| *n > 0).map(i128::from) else {
+        return at;
+    };
+    let nanos = i128::from(at.timestamp()) * 1_000_000_000 + i128::from(at.nanosecond());
+    let floor = nanos - nanos.rem_euclid(step);
+    let snapped = match direction {
+        _ if floor == nanos => nanos,
+        Direction::Floor => floor,
+        Direction::Ceil => floor + step,
+        Direction::Nearest if (nanos - floor) * 2 >= step => floor + step,
+        Direction::Nearest => floor,
+    };
+    let (seconds, nanos) = (
+        snapped.div_euclid(1_000_000_000),
+        snapped.rem_euclid(1_000_000_000),
+    );
+    i64::try_from(seconds)
+        .ok()
+        .and_then(|seconds| DateTime::from_timestamp(seconds, nanos as u32))
+        .unwrap_or(at)
+}
+
+/// A job window on this invocation's grid. A window the grid would close up keeps one
+/// step after its start, so a short job is not turned into an empty one.
+pub fn window(start: DateTime<Utc>, end: DateTime<Utc>) -> (DateTime<Utc>, DateTime<Utc>) {
+    let Some(grid) = current() else {
+        return (start, end);
+    };
+    let snapped_start = round(start, grid.step, grid.direction);
+    let snapped_end = round(end, grid.step, grid.direction);
+    match snapped_end <= snapped_start && end > start {
+        true => (snapped_start, snapped_start + grid.step),
+        false => (snapped_start, snapped_end),
+    }
+}
+
+/// "12:15:03.250", with the fraction only when there is one
+fn clock(at: DateTime<Utc>) -> String {
+    match at.nanosecond() {
+        0 => at.format("%H:%M:%S").to_string(),
+        _ => at.format("%H:%M:%S%.3f").to_string(),
+    }
+}
+
+/// What snapping did to an entered window, when it moved either end
+pub fn describe(
+    entered: (DateTime<Utc>, DateTime<Utc>),
+    snapped: (DateTime<Utc>, DateTime<Utc>),
+) -> Option<String> {
+    if entered == snapped {
+        return None;
+    }
+    let grid = current()?;
+    let moved: Vec<String> = [
+        ("start", entered.0, snapped.0),
+        ("end", entered.1, snapped.1),
+    ]
+    .into_iter()
+    .filter(|(_, from, to)| from != to)
+    .map(|(end, from, to)| format!("{} {} → {} UTC", end, clock(from), clock(to)))
+    .collect();
+    Some(format!(
+        "Snapped to the station's {} grid ({}): {}; --no-snap sends the times as entered",
+        humanize::duration(grid.step),
+        grid.direction.name(),
+        moved.join(", ")
+    ))
+}
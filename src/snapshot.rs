@@ -0,0 +1,321 @@
+//! Job snapshots: what went into each submission, kept with the audit entry of the command
+//! that made it. `history show` prints one, and `history replay` checks its exact body
+//! against the rules in force now, to tell whether the station would still take it.
+
+use crate::audit;
+use crate::client::{ApiResponse, JobRequestDTO, JobType};
+use crate::clock;
+use crate::error::{CliError, ResultExt};
+use crate::humanize;
+use crate::job_request::JobRequestBuilder;
+use crate::regulatory::TxLimits;
+use crate::snap;
+use crate::tle;
+use crate::validation;
+use crate::OutputFormat;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::{Mutex, OnceLock};
+
+/// Largest snapshot kept, serialized; past it the server's answer is left out first,
+/// then the request
+pub const MAX_BYTES: usize = 16 * 1024;
+
+/// One submission as it was made
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub taken_at: DateTime<Utc>,
+    pub cli_version: String,
+    /// The job's ID, when the server reported one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub job_id: Option<String>,
+    pub label: String,
+    /// The body exactly as sent
+    pub request: Value,
+    /// Everything the server answered
+    pub response: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tle: Option<Elements>,
+    pub settings: Settings,
+    /// "response" and "request", for the parts left out to stay under `MAX_BYTES`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dropped: Vec<String>,
+}
+
+/// The element set of a tracking job and where it came from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Elements {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub norad_id: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub epoch: Option<DateTime<Utc>>,
+    #[serde(flatten)]
+    pub origin: Origin,
+}
+
+/// Where an element set came from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "source", rename_all = "lowercase")]
+pub enum Origin {
+    /// Typed in, or read from a file or a bundle
+    Manual,
+    /// Downloaded from a public source for this invocation
+    Network { url: String },
+    /// Read from the TLE cache, written this long before
+    Cache { age_seconds: u64 },
+}
+
+impl Origin {
+    fn describe(&self) -> String {
+        match self {
+            Origin::Manual => "entered by hand or from a file".to_string(),
+            Origin::Network { url } => format!("downloaded from {}", url),
+            Origin::Cache { age_seconds } => format!(
+                "from the cache, {} old",
+                humanize::duration(chrono::Duration::seconds(*age_seconds as i64))
+            ),
+        }
+    }
+}
+
+/// The configuration that shaped the job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Settings {
+    pub base_url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    /// The grid job times were snapped to, "none" when sent as entered
+    pub rounding: String,
+}
+
+/// This invocation's settings; none when the profile turns snapshots off
+static SETTINGS: OnceLock<Option<Settings>> = OnceLock::new();
+
+/// Where each element set this invocation resolved came from, by its first line
+static ORIGINS: Mutex<BTreeMap<String, Origin>> = Mutex::new(BTreeMap::new());
+
+/// Snapshot submissions under `settings`, unless the profile's `job_snapshots` is false
+pub fn set(enabled: bool, settings: Settings) {
+    let _ = SETTINGS.set(enabled.then_some(settings));
+}
+
+/// Note where the element set with first line `tle1` came from; element sets never
+/// noted were entered by hand
+pub fn element_origin(tle1: &str, origin: Origin) {
+    ORIGINS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(tle1.to_string(), origin);
+}
+
+fn elements(job: &JobRequestDTO) -> Option<Elements> {
+    let JobType::Tracking { tle } = &job.job_type else {
+        return None;
+    };
+    let origin = ORIGINS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&tle.tle1)
+        .cloned()
+        .unwrap_or(Origin::Manual);
+    Some(Elements {
+        norad_id: tle::norad_id(&tle.tle1),
+        epoch: tle::epoch(&tle.tle1),
+        origin,
+    })
+}
+
+fn size(snapshot: &Snapshot) -> usize {
+    serde_json::to_string(snapshot).map_or(0, |text| text.len())
+}
+
+/// Keep `job` as sent and the server's `response` with the running command's audit entry
+pub fn record(job: &JobRequestDTO, response: &ApiResponse) {
+    let Some(Some(settings)) = SETTINGS.get() else {
+        return;
+    };
+    let mut snapshot = Snapshot {
+        taken_at: clock::now(),
+        cli_version: env!("CARGO_PKG_VERSION").to_string(),
+        job_id: response.id.clone(),
+        label: job.label().to_string(),
+        request: serde_json::to_value(job).unwrap_or_default(),
+        response: serde_json::to_value(response).unwrap_or_default(),
+        tle: elements(job),
+        settings: settings.clone(),
+        dropped: Vec::new(),
+    };
+    if size(&snapshot) > MAX_BYTES {
+        snapshot.response = Value::Null;
+        snapshot.dropped.push("response".to_string());
+    }
+    if size(&snapshot) > MAX_BYTES {
+        snapshot.request = Value::Null;
+        snapshot.dropped.push("request".to_string());
+    }
+    audit::snapshot(snapshot);
+}
+
+/// Every snapshot in the audit log, most recent first, with the entry it belongs to
+fn all() -> Result<Vec<(audit::Entry, Snapshot)>, CliError> {
+    Ok(audit::read()?
+        .into_iter()
+        .rev()
+        .flat_map(|entry| {
+            entry
+                .snapshots
+                .iter()
+                .rev()
+                .map(|snapshot| (entry.clone(), snapshot.clone()))
+                .collect::<Vec<_>>()
+        })
+        .collect())
+}
+
+/// Snapshot `n`, counting from 1 for the most recent
+fn nth(n: usize) -> Result<(audit::Entry, Snapshot), CliError> {
+    if n == 0 {
+        return Err(CliError::validation(
+            "n",
+            "snapshots are numbered from 1, the most recent",
+        ));
+    }
+    let mut snapshots = all()?;
+    let count = snapshots.len();
+    if n > count {
+        return Err(CliError::NotFound {
+            message: Some(format!(
+                "there is no snapshot {}; the audit log holds {} snapshot(s)",
+                n,
+                humanize::count(count)
+            )),
+        });
+    }
+    Ok(snapshots.swap_remove(n - 1))
+}
+
+/// "job 3, ISS (ZARYA)", or the label alone for a job the server gave no ID
+fn title(snapshot: &Snapshot) -> String {
+    match &snapshot.job_id {
+        Some(id) => format!("job {}, {}", id, snapshot.label.trim()),
+        None => snapshot.label.trim().to_string(),
+    }
+}
+
+/// `history list`: every snapshot, most recent first, numbered for show and replay
+pub fn list(output: OutputFormat) -> Result<(), CliError> {
+    let snapshots = all()?;
+    if output == OutputFormat::Json {
+        let list: Vec<&Snapshot> = snapshots.iter().map(|(_, snapshot)| snapshot).collect();
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&list).map_err(|e| CliError::parse("snapshots", e))?
+        );
+        return Ok(());
+    }
+    if snapshots.is_empty() {
+        println!("📭 No job snapshots in {}", audit::log_path().display());
+        return Ok(());
+    }
+    println!("📸 {} job snapshot(s), most recent first", snapshots.len());
+    for (n, (entry, snapshot)) in snapshots.iter().enumerate() {
+        println!(
+            "  {:>3}. {}  {}  ({}, {}@{})",
+            n + 1,
+            snapshot.taken_at.format("%Y-%m-%d %H:%M:%S"),
+            title(snapshot),
+            entry.command,
+            entry.user,
+            entry.profile
+        );
+    }
+    Ok(())
+}
+
+fn pretty(value: &Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+}
+
+/// `history show`: snapshot `n`; `full` adds the request and the server's answer
+pub fn show(n: usize, full: bool, output: OutputFormat) -> Result<(), CliError> {
+    let (entry, snapshot) = nth(n)?;
+    if output == OutputFormat::Json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&snapshot).map_err(|e| CliError::parse("snapshot", e))?
+        );
+        return Ok(());
+    }
+    println!("📸 Snapshot {}: {}", n, title(&snapshot));
+    println!(
+        "   taken {} by rustar-cli {} ({}, {}@{})",
+        humanize::timestamp(snapshot.taken_at),
+        snapshot.cli_version,
+        entry.command,
+        entry.user,
+        entry.profile
+    );
+    println!(
+        "   server {}, timezone {}, rounding {}",
+        snapshot.settings.base_url,
+        snapshot.settings.timezone.as_deref().unwrap_or("utc"),
+        snapshot.settings.rounding
+    );
+    if let Some(elements) = &snapshot.tle {
+        let satellite = elements
+            .norad_id
+            .map(|id| format!("NORAD {}", id))
+            .unwrap_or_else(|| "elements".to_string());
+        let epoch = elements
+            .epoch
+            .map(|epoch| format!(", epoch {}", humanize::timestamp(epoch)))
+            .unwrap_or_default();
+        println!("   {}{}, {}", satellite, epoch, elements.origin.describe());
+    }
+    if !snapshot.dropped.is_empty() {
+        println!(
+            "   ✂️ Left out to stay under {}: {}",
+            humanize::bytes(MAX_BYTES as u64),
+            snapshot.dropped.join(", ")
+        );
+    }
+    if full {
+        println!("   request:\n{}", pretty(&snapshot.request));
+        println!("   response:\n{}", pretty(&snapshot.response));
+    }
+    Ok(())
+}
+
+/// `history replay`: whether the exact body of snapshot `n` passes today's client-side
+/// checks. The server's own checks are not run.
+pub fn replay(n: usize, limits: &TxLimits) -> Result<(), CliError> {
+    let (_, snapshot) = nth(n)?;
+    if snapshot.request.is_null() {
+        return Err(CliError::validation(
+            "request",
+            format!(
+                "snapshot {} was kept without its request, which was over {}",
+                n,
+                humanize::bytes(MAX_BYTES as u64)
+            ),
+        ));
+    }
+    let job: JobRequestDTO = serde_json::from_value(snapshot.request.clone())
+        .map_err(|e| CliError::parse(format!("the request of snapshot {}", n), e))?;
+    let entered = (job.start, job.end);
+    let snapped = snap::window(job.start, job.end);
+    let builder = JobRequestBuilder::from(job).tx_limits(limits);
+    validation::into_result(builder.problems())
+        .with_context(|| format!("replaying snapshot {} ({})", n, title(&snapshot)))?;
+    println!(
+        "✅ Snapshot {} ({}) would still be accepted today: it passes every client-side check",
+        n,
+        title(&snapshot)
+    );
+    if let Some(moved) = snap::describe(entered, snapped) {
+        println!("   {}", moved);
+    }
+    Ok(())
+}
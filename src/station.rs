@@ -0,0 +1,216 @@
+use crate::client::{ApiClient, Polarization, StationInfo};
+use crate::error::CliError;
+use crate::humanize;
+use crate::metrics;
+use crate::text;
+use crate::transcript;
+use crate::validation::Problem;
+use crate::OutputFormat;
+use clap::ValueEnum;
+use std::time::Duration;
+
+/// One-line rendering of an arbitrary JSON value
+fn format_value(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Null => "—".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn format_location(location: &serde_json::Value) -> String {
+    let field = |names: &[&str]| {
+        names
+            .iter()
+            .find_map(|name| location.get(*name).and_then(|v| v.as_f64()))
+    };
+    match (
+        field(&["latitude", "lat"]),
+        field(&["longitude", "lon", "lng"]),
+    ) {
+        (Some(lat), Some(lon)) => {
+            let mut text = format!("{:.4}°, {:.4}°", lat, lon);
+            if let Some(alt) = field(&["altitude_m", "altitude", "alt"]) {
+                text.push_str(&format!(" ({:.0} m)", alt));
+            }
+            if let Some(name) = location.get("name").and_then(|v| v.as_str()) {
+                text = format!("{} — {}", name, text);
+            }
+            text
+        }
+        _ => format_value(location),
+    }
+}
+
+fn format_antenna(antenna: &serde_json::Value) -> String {
+    antenna
+        .get("name")
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+        .unwrap_or_else(|| format_value(antenna))
+}
+
+/// Detail view of the station status, unknown fields listed after the known ones
+fn render(info: &StationInfo) {
+    println!(
+        "🛰️ Station: {}",
+        info.name.as_deref().unwrap_or("(unnamed)")
+    );
+
+    let mut rows: Vec<(String, String)> = Vec::new();
+    if let Some(location) = &info.location {
+        rows.push(("Location".into(), format_location(location)));
+    }
+    if !info.antennas.is_empty() {
+        let names: Vec<String> = info.antennas.iter().map(format_antenna).collect();
+        rows.push(("Antennas".into(), names.join(", ")));
+    }
+    if let Some(polarizations) = &info.polarizations {
+        rows.push(("Polarizations".into(), polarizations.join(", ")));
+    }
+    if info.azimuth.is_some() || info.elevation.is_some() {
+        let angle = |v: Option<f64>| v.map(|a| format!("{:.1}°", a)).unwrap_or("—".into());
+        rows.push((
+            "Az / El".into(),
+            format!("{} / {}", angle(info.azimuth), angle(info.elevation)),
+        ));
+    }
+    if let Some(uptime) = info.uptime_seconds {
+        rows.push((
+            "Uptime".into(),
+            humanize::duration(chrono::Duration::seconds(uptime as i64)),
+        ));
+    }
+    if let Some(depth) = info.queue_depth {
+        rows.push(("Queue depth".into(), depth.to_string()));
+    }
+    if let Some(free) = info.disk_free_bytes {
+        rows.push(("Disk free".into(), humanize::bytes(free)));
+    }
+    if let Some(version) = &info.api_version {
+        rows.push(("API version".into(), version.clone()));
+    }
+    if let Some(lead) = info.scheduling_lead_seconds {
+        rows.push((
+            "Scheduling lead".into(),
+            humanize::duration(chrono::Duration::seconds(lead as i64)),
+        ));
+    }
+    for (key, value) in &info.extra {
+        rows.push((key.clone(), format_value(value)));
+    }
+
+    let width = rows.iter().map(|(k, _)| text::width(k)).max().unwrap_or(0);
+    for (key, value) in rows {
+        println!("  {} : {}", text::pad(&key, width), text::terminal(&value));
+    }
+}
+
+async fn fetch(client: &ApiClient) -> Result<StationInfo, CliError> {
+    match client.station_info().await {
+        Err(e) if e.status() == Some(404) => Err(CliError::Unsupported(
+            "this server does not expose station info".to_string(),
+        )),
+        other => other,
+    }
+}
+
+/// The polarizations `info` lists, leaving out names this CLI does not know
+pub fn supported_polarizations(info: &StationInfo) -> Option<Vec<Polarization>> {
+    info.polarizations.as_ref().map(|names| {
+        names
+            .iter()
+            .filter_map(|name| Polarization::from_str(name, true).ok())
+            .collect()
+    })
+}
+
+/// Polarizations the station can switch to; None when it does not say, so any may be asked for
+pub async fn polarizations(client: &ApiClient) -> Option<Vec<Polarization>> {
+    match client.station_info().await {
+        Ok(info) => supported_polarizations(&info),
+        Err(e) if e.status() == Some(404) => None,
+        Err(e) => {
+            eprintln!(
+                "⚠️ Could not check the station's polarizations: {}",
+                e.full_message()
+            );
+            None
+        }
+    }
+}
+
+/// A polarization the station did not list as supported
+pub fn check_polarization(
+    polarization: Polarization,
+    supported: &[Polarization],
+) -> Option<Problem> {
+    if supported.contains(&polarization) {
+        return None;
+    }
+    let names: Vec<&str> = supported.iter().map(Polarization::code).collect();
+    Some(Problem::new(
+        "polarization",
+        format!(
+            "{} is not supported by the station (it offers {})",
+            polarization.code(),
+            if names.is_empty() {
+                "none".to_string()
+            } else {
+                names.join(", ")
+            }
+        ),
+    ))
+}
+
+fn print(info: &StationInfo, output: OutputFormat) -> Result<(), CliError> {
+    match output {
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(info).map_err(|e| CliError::parse("station info", e))?
+        ),
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::Ndjson => render(info),
+    }
+    Ok(())
+}
+
+/// Show the station status once, or keep refreshing it until Ctrl-C
+pub async fn show(
+    client: &ApiClient,
+    output: OutputFormat,
+    watch: Option<Duration>,
+) -> Result<(), CliError> {
+    let Some(interval) = watch else {
+        return print(&fetch(client).await?, output);
+    };
+    transcript::stops_on_interrupt();
+
+    loop {
+        let info = fetch(client).await;
+        if output == OutputFormat::Text {
+            // Clear the screen so the view updates in place
+            print!("\x1b[2J\x1b[H");
+        }
+        match info {
+            Ok(info) => {
+                if let Some(elevation) = info.elevation {
+                    metrics::set_elevation(elevation);
+                }
+                print(&info, output)?
+            }
+            Err(e @ CliError::Unsupported(_)) => return Err(e),
+            Err(e) => eprintln!("⚠️ {}", e.full_message()),
+        }
+        if output == OutputFormat::Text {
+            println!(
+                "\n🔄 Refreshing every {}s — Ctrl-C to stop",
+                interval.as_secs()
+            );
+        }
+
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+            _ = tokio::time::sleep(interval) => {}
+        }
+    }
+}
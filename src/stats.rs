@@ -0,0 +1,281 @@
+use crate::client::{ApiClient, JobDTO};
+use crate::error::{CliError, ResultExt};
+use crate::humanize;
+use crate::text;
+use crate::OutputFormat;
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// What jobs are counted together
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupBy {
+    /// Satellite name, or the kind of job for calibration and test jobs
+    Satellite,
+    /// Calendar day the job started on
+    Day,
+    /// Job status reported by the server
+    Status,
+}
+
+/// Where one day ends and the next begins when grouping by day
+#[derive(Debug, Clone, Copy)]
+pub enum DayBoundary {
+    Utc,
+    Local,
+    Fixed(FixedOffset),
+}
+
+impl GroupBy {
+    fn header(self) -> &'static str {
+        match self {
+            GroupBy::Satellite => "satellite",
+            GroupBy::Day => "day",
+            GroupBy::Status => "status",
+        }
+    }
+}
+
+impl DayBoundary {
+    /// Local midnight unless `--utc-only` asked to stay in UTC
+    pub fn default_for_display() -> Self {
+        if humanize::utc_only() {
+            DayBoundary::Utc
+        } else {
+            DayBoundary::Local
+        }
+    }
+
+    /// The calendar date `at` falls on in this zone
+    pub fn date(self, at: DateTime<Utc>) -> NaiveDate {
+        self.wall_clock(at).date()
+    }
+
+    /// What a clock in this zone reads at `at`
+    pub fn wall_clock(self, at: DateTime<Utc>) -> NaiveDateTime {
+        match self {
+            DayBoundary::Utc => at.naive_utc(),
+            DayBoundary::Local => at.with_timezone(&Local).naive_local(),
+            DayBoundary::Fixed(offset) => at.with_timezone(&offset).naive_local(),
+        }
+    }
+
+    /// Midnight starting `date` in this zone, as UTC
+    fn midnight(self, date: NaiveDate) -> Option<DateTime<Utc>> {
+        self.at(date, NaiveTime::MIN)
+    }
+
+    /// The wall-clock `time` of `date` in this zone, as UTC; the earlier of a time a
+    /// clock change repeats, none for one it skips
+    pub fn at(self, date: NaiveDate, time: NaiveTime) -> Option<DateTime<Utc>> {
+        let naive = date.and_time(time);
+        Some(match self {
+            DayBoundary::Utc => Utc.from_utc_datetime(&naive),
+            DayBoundary::Local => Local
+                .from_local_datetime(&naive)
+                .earliest()?
+                .with_timezone(&Utc),
+            DayBoundary::Fixed(offset) => offset
+                .from_local_datetime(&naive)
+                .single()?
+                .with_timezone(&Utc),
+        })
+    }
+}
+
+/// Parse `--timezone`: "utc", "local" or a fixed offset such as "+02:00" / "-0300"
+pub fn parse_timezone(input: &str) -> Result<DayBoundary, String> {
+    match input.trim().to_lowercase().as_str() {
+        "utc" | "z" => return Ok(DayBoundary::Utc),
+        "local" => return Ok(DayBoundary::Local),
+        _ => {}
+    }
+    DateTime::parse_from_str(
+        &format!("2000-01-01 00:00 {}", input.trim()),
+        "%Y-%m-%d %H:%M %z",
+    )
+    .map(|d| DayBoundary::Fixed(*d.offset()))
+    .map_err(|_| {
+        format!(
+            "'{}' is not a timezone (use utc, local or an offset like +02:00)",
+            input
+        )
+    })
+}
+
+/// A `--from`/`--to` bound: a bare date means midnight in the grouping zone
+pub fn parse_bound(
+    input: &str,
+    boundary: DayBoundary,
+    flag: &str,
+) -> Result<DateTime<Utc>, CliError> {
+    if let Ok(date) = NaiveDate::parse_from_str(input.trim(), "%Y-%m-%d") {
+        return boundary.midnight(date).ok_or_else(|| {
+            CliError::validation(flag, format!("midnight of {} does not exist here", date))
+        });
+    }
+    crate::parse::datetime(input).with_context(|| format!("reading {}", flag))
+}
+
+/// Jobs and tracked time of one group
+#[derive(Debug, Default, Serialize)]
+struct Tally {
+    jobs: u64,
+    minutes: f64,
+}
+
+impl Tally {
+    fn add(&mut self, job: &JobDTO) {
+        self.jobs += 1;
+        self.minutes += (job.job.end - job.job.start).num_seconds().max(0) as f64 / 60.0;
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct GroupRow {
+    group: String,
+    #[serde(flatten)]
+    tally: Tally,
+}
+
+#[derive(Debug, Serialize)]
+struct Summary {
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    group_by: GroupBy,
+    groups: Vec<GroupRow>,
+    total: Tally,
+}
+
+fn group_key(job: &JobDTO, group_by: GroupBy, boundary: DayBoundary) -> String {
+    match group_by {
+        GroupBy::Satellite => job.job.label().to_string(),
+        GroupBy::Day => boundary.date(job.job.start).to_string(),
+        GroupBy::Status => job.status.clone().unwrap_or_else(|| "unknown".to_string()),
+    }
+}
+
+fn print_text(summary: &Summary) {
+    let header = summary.group_by.header().to_uppercase();
+    let width = summary
+        .groups
+        .iter()
+        .map(|g| text::width(&g.group))
+        .chain([header.len(), "TOTAL".len()])
+        .max()
+        .unwrap_or(0);
+
+    let range = match (summary.from, summary.to) {
+        (None, None) => "all jobs".to_string(),
+        (from, to) => format!(
+            "{} → {}",
+            from.map(|f| f.to_string()).unwrap_or_else(|| "…".into()),
+            to.map(|t| t.to_string()).unwrap_or_else(|| "…".into())
+        ),
+    };
+    println!("📊 Job statistics ({})", range);
+
+    println!(
+        "  {:<width$}  {:>6}  {:>10}",
+        header,
+        "JOBS",
+        "MINUTES",
+        width = width
+    );
+    for row in &summary.groups {
+        println!(
+            "  {}  {:>6}  {:>10.1}",
+            text::pad(&row.group, width),
+            row.tally.jobs,
+            row.tally.minutes
+        );
+    }
+    println!(
+        "  {:<width$}  {:>6}  {:>10.1}",
+        "TOTAL",
+        summary.total.jobs,
+        summary.total.minutes,
+        width = width
+    );
+}
+
+fn print_csv(summary: &Summary) -> Result<(), CliError> {
+    let mut writer = csv::Writer::from_writer(std::io::stdout());
+    let error = |e: csv::Error| CliError::parse("statistics as CSV", e);
+    writer
+        .write_record([summary.group_by.header(), "jobs", "minutes"])
+        .map_err(error)?;
+    for row in &summary.groups {
+        writer
+            .write_record([
+                row.group.clone(),
+                row.tally.jobs.to_string(),
+                format!("{:.1}", row.tally.minutes),
+            ])
+            .map_err(error)?;
+    }
+    writer.flush().context("writing CSV")
+}
+
+/// Count jobs and tracked minutes per group over an optional start-time range
+pub async fn show(
+    client: &ApiClient,
+    output: OutputFormat,
+    from: Option<&str>,
+    to: Option<&str>,
+    group_by: GroupBy,
+    boundary: DayBoundary,
+) -> Result<(), CliError> {
+    let from = from
+        .map(|f| parse_bound(f, boundary, "--from"))
+        .transpose()?;
+    let to = to.map(|t| parse_bound(t, boundary, "--to")).transpose()?;
+    if let (Some(from), Some(to)) = (from, to) {
+        if to <= from {
+            return Err(CliError::validation(
+                "--to",
+                format!("{} is not after --from {}", to, from),
+            ));
+        }
+    }
+
+    let mut groups: BTreeMap<String, Tally> = BTreeMap::new();
+    let mut total = Tally::default();
+    let mut jobs = client.stream_jobs(false).await?;
+    while let Some(job) = jobs.next().await? {
+        let start = job.job.start;
+        if from.is_some_and(|f| start < f) || to.is_some_and(|t| start >= t) {
+            continue;
+        }
+        groups
+            .entry(group_key(&job, group_by, boundary))
+            .or_default()
+            .add(&job);
+        total.add(&job);
+    }
+
+    let summary = Summary {
+        from,
+        to,
+        group_by,
+        groups: groups
+            .into_iter()
+            .map(|(group, tally)| GroupRow { group, tally })
+            .collect(),
+        total,
+    };
+
+    match output {
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&summary).map_err(|e| CliError::parse("statistics", e))?
+        ),
+        OutputFormat::Csv => print_csv(&summary)?,
+        OutputFormat::Text | OutputFormat::Ndjson if summary.total.jobs == 0 => {
+            println!("📭 No jobs in the requested range")
+        }
+        OutputFormat::Text | OutputFormat::Ndjson => print_text(&summary),
+    }
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use crate::error::{CliError, ResultExt};
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// How long to wait for another process to release a state file before giving up
+const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+const LOCK_RETRY: Duration = Duration::from_millis(50);
+
+/// Exclusive hold on a state file for a read-modify-write cycle, released on drop
+pub struct FileLock {
+    _file: File,
+}
+
+/// `queue.json` is guarded by `queue.json.lock`, so replacing the file keeps the lock valid
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+fn create_parent(path: &Path) -> Result<(), CliError> {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => std::fs::create_dir_all(parent)
+            .with_context(|| format!("creating {}", parent.display())),
+        _ => Ok(()),
+    }
+}
+
+/// The lock file guarding `path`, opened but not yet locked
+fn lock_file(path: &Path) -> Result<(File, PathBuf), CliError> {
+    create_parent(path)?;
+    let lock_path = sibling(path, ".lock");
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)
+        .with_context(|| format!("opening {}", lock_path.display()))?;
+    Ok((file, lock_path))
+}
+
+/// One try at the lock on `file`: whether it was taken, or the error once `deadline` has passed
+fn try_lock(
+    path: &Path,
+    lock_path: &Path,
+    file: &File,
+    deadline: Instant,
+) -> Result<bool, CliError> {
+    match file.try_lock() {
+        Ok(()) => Ok(true),
+        Err(TryLockError::WouldBlock) if Instant::now() < deadline => Ok(false),
+        Err(TryLockError::WouldBlock) => Err(CliError::Locked {
+            path: path.to_path_buf(),
+        }),
+        Err(TryLockError::Error(e)) => {
+            Err(e).with_context(|| format!("locking {}", lock_path.display()))
+        }
+    }
+}
+
+/// Lock `path` against other rustar processes, waiting up to `LOCK_TIMEOUT`. The wait
+/// blocks the thread, which suits code that blocks anyway, such as the prompts; async
+/// code takes `lock_async`.
+pub fn lock(path: &Path) -> Result<FileLock, CliError> {
+    let (file, lock_path) = lock_file(path)?;
+    let deadline = Instant::now() + LOCK_TIMEOUT;
+    while !try_lock(path, &lock_path, &file, deadline)? {
+        std::thread::sleep(LOCK_RETRY);
+    }
+    Ok(FileLock { _file: file })
+}
+
+/// `lock` for async code: the wait sleeps on the runtime's timer, so the other tasks of
+/// the worker thread carry on meanwhile
+pub async fn lock_async(path: &Path) -> Result<FileLock, CliError> {
+    let (file, lock_path) = lock_file(path)?;
+    let deadline = Instant::now() + LOCK_TIMEOUT;
+    while !try_lock(path, &lock_path, &file, deadline)? {
+        tokio::time::sleep(LOCK_RETRY).await;
+    }
+    Ok(FileLock { _file: file })
+}
+
+/// Replace `path` with `contents` so readers see the old file or the new one, never half of it
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> Result<(), CliError> {
+    create_parent(path)?;
+    let temp = sibling(path, &format!(".tmp{}", std::process::id()));
+    let written = (|| {
+        let mut file = File::create(&temp)?;
+        // Keep the mode of the file being replaced, e.g. a config only its owner may read
+        if let Ok(metadata) = std::fs::metadata(path) {
+            file.set_permissions(metadata.permissions())?;
+        }
+        file.write_all(contents.as_ref())?;
+        file.sync_all()?;
+        std::fs::rename(&temp, path)
+    })();
+    if written.is_err() {
+        let _ = std::fs::remove_file(&temp);
+    }
+    written.with_context(|| format!("writing {}", path.display()))
+}
@@ -0,0 +1,116 @@
+use crate::clock;
+use crate::humanize;
+use crate::validation::{Problem, Rule};
+use chrono::{DateTime, Duration, Utc};
+use std::sync::OnceLock;
+
+/// How far ahead a job may start when neither the profile nor `--max-horizon` says
+pub const DEFAULT_MAX_HORIZON_DAYS: u64 = 30;
+/// How long ago a job may have started, for one typed in the minute it begins
+pub const DEFAULT_MAX_PAST_MINUTES: u64 = 5;
+
+/// Start times accepted without question, around the moment of submission. Catches
+/// typos like 2052 for 2025, which would otherwise be scheduled without complaint.
+#[derive(Debug, Clone)]
+pub struct SubmitWindow {
+    ahead: Duration,
+    behind: Duration,
+    ahead_rule: Rule,
+    behind_rule: Rule,
+}
+
+/// Set once from the profile and `--max-horizon`
+static WINDOW: OnceLock<SubmitWindow> = OnceLock::new();
+
+impl SubmitWindow {
+    /// The profile's `max_horizon_days` and `max_past_minutes`; `--max-horizon` wins
+    pub fn new(
+        max_horizon_days: Option<u64>,
+        max_past_minutes: Option<u64>,
+        max_horizon: Option<Duration>,
+    ) -> Self {
+        let days = max_horizon_days.unwrap_or(DEFAULT_MAX_HORIZON_DAYS);
+        let minutes = max_past_minutes.unwrap_or(DEFAULT_MAX_PAST_MINUTES);
+        let ahead = max_horizon.unwrap_or_else(|| {
+            i64::try_from(days)
+                .ok()
+                .and_then(Duration::try_days)
+                .unwrap_or(Duration::MAX)
+        });
+        let behind = i64::try_from(minutes)
+            .ok()
+            .and_then(Duration::try_minutes)
+            .unwrap_or(Duration::MAX);
+        let ahead_rule = Rule::profile(
+            "submit horizon",
+            "max_horizon_days",
+            humanize::duration(ahead),
+            max_horizon_days.is_some(),
+        )
+        .overridden_by("--max-horizon");
+        Self {
+            ahead,
+            behind,
+            ahead_rule: match max_horizon {
+                Some(_) => ahead_rule.set_by_flag("--max-horizon"),
+                None => ahead_rule,
+            },
+            behind_rule: Rule::profile(
+                "latest start in the past",
+                "max_past_minutes",
+                humanize::duration(behind),
+                max_past_minutes.is_some(),
+            ),
+        }
+    }
+}
+
+pub fn set(window: SubmitWindow) {
+    let _ = WINDOW.set(window);
+}
+
+fn current() -> SubmitWindow {
+    WINDOW
+        .get()
+        .cloned()
+        .unwrap_or_else(|| SubmitWindow::new(None, None, None))
+}
+
+/// Why `start` is outside the submit window: the time given, the limit it breaks and
+/// how to move the limit when the time is what was meant
+pub fn check(start: DateTime<Utc>) -> Option<Problem> {
+    let window = current();
+    let ahead = start - clock::now();
+    if ahead > window.ahead {
+        return Some(
+            Problem::new(
+                "start",
+                format!(
+                    "{} is {} from now, beyond the {} submit horizon; if that is what you meant, \
+                 pass --max-horizon {}d or raise max_horizon_days in the profile",
+                    humanize::timestamp(start),
+                    humanize::duration(ahead),
+                    humanize::duration(window.ahead),
+                    ahead.num_days() + 1
+                ),
+            )
+            .breaking(window.ahead_rule),
+        );
+    }
+    if -ahead > window.behind {
+        return Some(
+            Problem::new(
+                "start",
+                format!(
+                    "{} is {} in the past, more than the {} allowed; if that is what you meant, \
+                 raise max_past_minutes in the profile",
+                    humanize::timestamp(start),
+                    humanize::duration(-ahead),
+                    humanize::duration(window.behind)
+                ),
+            )
+            .breaking(window.behind_rule),
+        );
+    }
+    None
+}
@@ -0,0 +1,380 @@
+//! `jobs sweep`: back-to-back receive-only jobs stepping across a band, for hunting a
+//! downlink no one has published. The whole plan is shown and checked against the
+//! satellite's pass and the station's maintenance before the jobs go out as one batch,
+//! under one campaign.
+
+use crate::batch;
+use crate::client::{ApiClient, JobType, TleData};
+use crate::clock;
+use crate::error::CliError;
+use crate::findings::Checks;
+use crate::frequency::Frequency;
+use crate::hooks::HookRunner;
+use crate::humanize;
+use crate::job_request::JobRequestBuilder;
+use crate::location::Location;
+use crate::maintenance;
+use crate::mask::ElevationMask;
+use crate::orbit::Satellite;
+use crate::predict::{self, Horizon, Interval};
+use crate::regulatory::TxLimits;
+use crate::satellite_id::SatelliteId;
+use crate::satellites;
+use crate::schedule::TimeWindow;
+use crate::tle;
+use crate::tty;
+use chrono::{DateTime, Duration, SubsecRound, Utc};
+use std::path::Path;
+
+/// Most jobs one sweep may submit when the profile does not say
+pub const DEFAULT_MAX_JOBS: usize = 48;
+/// A sweep without `--start` opens no sooner than this, so its first job is still ahead
+/// when the batch reaches the station
+const LEAD_S: i64 = 120;
+/// How far ahead the next pass is looked for when no `--start` is given
+const PASS_SEARCH_HOURS: i64 = 48;
+
+/// What the operator asked to sweep
+pub struct Request<'a> {
+    /// Alias, NORAD catalog number or international designator, as typed
+    pub satellite: &'a str,
+    pub from: Frequency,
+    pub to: Frequency,
+    pub step: Frequency,
+    pub dwell: Duration,
+    pub start: Option<DateTime<Utc>>,
+    pub campaign: Option<String>,
+    /// Answer the confirmation
+    pub yes: bool,
+    /// Show the plan and stop
+    pub dry_run: bool,
+}
+
+/// Where the sweep is submitted and what it is held to
+pub struct Station<'a> {
+    pub client: &'a ApiClient,
+    pub hooks: &'a HookRunner,
+    /// None when the profile does not say where the station is; the pass is then not
+    /// checked
+    pub location: Option<Location>,
+    pub mask: &'a ElevationMask,
+    pub limits: &'a TxLimits,
+    pub checks: &'a Checks,
+    /// The profile's `sweep_max_jobs`
+    pub max_jobs: usize,
+}
+
+/// One job of the plan
+struct Step {
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    frequency: Frequency,
+    /// Whether the satellite is above the station's horizon and mask for the whole job;
+    /// always true when the pass is not checked
+    visible: bool,
+}
+
+/// Frequencies from `from` up to `to` in `step`s, `to` included when a step lands on it.
+/// The job count is checked against `max_jobs` before any is listed.
+fn frequencies(
+    from: Frequency,
+    to: Frequency,
+    step: Frequency,
+    max_jobs: usize,
+) -> Result<Vec<Frequency>, CliError> {
+    if step.is_zero() {
+        return Err(CliError::validation("step", "must be more than 0 Hz"));
+    }
+    let Some(span) = to.checked_sub(from).filter(|span| !span.is_zero()) else {
+        return Err(CliError::validation(
+            "to",
+            format!(
+                "{} is not above --from {}",
+                humanize::frequency(to.as_f64()),
+                humanize::frequency(from.as_f64())
+            ),
+        ));
+    };
+    let count = span.hz() / step.hz() + 1;
+    if count > max_jobs as u64 {
+        return Err(CliError::validation(
+            "step",
+            format!(
+                "the sweep would be {} jobs, more than the {} sweep_max_jobs allows; take a \
+                 wider --step or a narrower range, or raise sweep_max_jobs in the profile",
+                count, max_jobs
+            ),
+        ));
+    }
+    Ok((0..count)
+        .map(|i| Frequency::from_hz(from.hz() + i * step.hz()))
+        .collect())
+}
+
+/// The elements of an alias, named after it, or of a catalog number or designator
+async fn elements(satellite: &str) -> Result<TleData, CliError> {
+    let book = satellites::AliasBook::load()?;
+    let id = SatelliteId::detect(satellite);
+    if book.get(satellite).is_none() && !matches!(id, SatelliteId::Name(_)) {
+        return tle::resolve_id(id).await;
+    }
+    let (name, preset) = satellites::lookup(&book, satellite)?;
+    let id = preset.id().ok_or_else(|| {
+        CliError::validation(
+            "satellite",
+            format!(
+                "alias '{}' has no NORAD ID or designator; set one with rustar-cli sat set-freq {} --norad-id <ID>",
+                name, name
+            ),
+        )
+    })?;
+    Ok(TleData {
+        tle0: name,
+        ..tle::resolve_id(id).await?
+    })
+}
+
+/// `at`, or the next whole second after it: a job starting there misses none of what
+/// follows `at`
+fn ceil_second(at: DateTime<Utc>) -> DateTime<Utc> {
+    let whole = at.trunc_subsecs(0);
+    match whole < at {
+        true => whole + Duration::seconds(1),
+        false => whole,
+    }
+}
+
+/// Where the satellite is usable from the station over `[from, to)`
+fn usable(
+    satellite: &Satellite,
+    horizon: &Horizon,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<Interval>, CliError> {
+    Ok(predict::passes(satellite, horizon, from, to)?
+        .into_iter()
+        .flat_map(|pass| pass.usable)
+        .collect())
+}
+
+/// The start of the satellite's next usable pass, no sooner than the lead
+fn next_pass(satellite: &Satellite, horizon: &Horizon) -> Result<DateTime<Utc>, CliError> {
+    let from = clock::now() + Duration::seconds(LEAD_S);
+    let to = from + Duration::hours(PASS_SEARCH_HOURS);
+    usable(satellite, horizon, from, to)?
+        .first()
+        .map(|(aos, _)| ceil_second(*aos))
+        .ok_or_else(|| {
+            CliError::validation(
+                "start",
+                format!(
+                    "{} makes no pass over the station in the next {}h; give a --start",
+                    satellite.name, PASS_SEARCH_HOURS
+                ),
+            )
+        })
+}
+
+fn print_plan(name: &str, request: &Request, steps: &[Step], campaign: &str) {
+    let (Some(first), Some(last)) = (steps.first(), steps.last()) else {
+        return;
+    };
+    println!(
+        "📡 Sweep of {} from {} to {} in {} steps",
+        name,
+        humanize::frequency(first.frequency.as_f64()),
+        humanize::frequency(last.frequency.as_f64()),
+        humanize::frequency_step(request.step.hz())
+    );
+    println!(
+        "   {} job(s) of {}, back to back: {} of airtime, {} – {}",
+        steps.len(),
+        humanize::duration(request.dwell),
+        humanize::duration(last.end - first.start),
+        humanize::timestamp(first.start),
+        last.end.format("%H:%M:%S UTC")
+    );
+    println!("   Campaign {}", campaign);
+    for (index, step) in steps.iter().enumerate() {
+        println!(
+            "  [{}] {} – {}  {}{}",
+            index,
+            step.start.format("%H:%M:%S"),
+            step.end.format("%H:%M:%S UTC"),
+            humanize::frequency(step.frequency.as_f64()),
+            match step.visible {
+                true => "",
+                false => "  (below the horizon)",
+            }
+        );
+    }
+}
+
+/// Plan the sweep, show it, check it, and once confirmed submit it as a batch. Returns
+/// the number of jobs that failed, as a batch run does.
+pub async fn run(station: Station<'_>, request: Request<'_>) -> Result<usize, CliError> {
+    if request.dwell < Duration::seconds(1) {
+        return Err(CliError::validation("dwell", "must be at least 1s"));
+    }
+    let frequencies = frequencies(request.from, request.to, request.step, station.max_jobs)?;
+    let tle = elements(request.satellite).await?;
+    let satellite = Satellite::from_tle(&tle)?;
+    let name = tle.tle0.trim().to_string();
+
+    let horizon = station.location.as_ref().map(|location| Horizon {
+        station: location,
+        min_elevation_deg: 0.0,
+        mask: station.mask,
+    });
+    let start = match (request.start, &horizon) {
+        (Some(start), _) => start,
+        (None, Some(horizon)) => next_pass(&satellite, horizon)?,
+        (None, None) => {
+            return Err(CliError::validation(
+                "start",
+                "the profile does not say where the station is, so there is no pass to start \
+                 at; give a --start, or set the position with rustar-cli config set-location",
+            ))
+        }
+    };
+    let end = start + request.dwell * frequencies.len() as i32;
+
+    let passes = match &horizon {
+        Some(horizon) => Some(usable(&satellite, horizon, start, end)?),
+        None => {
+            eprintln!(
+                "⚠️ The profile does not say where the station is, so the sweep is not \
+                 checked against the pass"
+            );
+            None
+        }
+    };
+    let steps: Vec<Step> = frequencies
+        .into_iter()
+        .enumerate()
+        .map(|(i, frequency)| {
+            let start = start + request.dwell * i as i32;
+            let end = start + request.dwell;
+            let visible = passes
+                .as_ref()
+                .is_none_or(|passes| passes.iter().any(|(aos, los)| *aos <= start && end <= *los));
+            Step {
+                start,
+                end,
+                frequency,
+                visible,
+            }
+        })
+        .collect();
+
+    let campaign = request.campaign.clone().unwrap_or_else(|| {
+        format!(
+            "sweep-{}-{}",
+            request.satellite.trim(),
+            start.format("%Y%m%d-%H%M")
+        )
+    });
+    print_plan(&name, &request, &steps, &campaign);
+
+    let hidden = steps.iter().filter(|step| !step.visible).count();
+    if hidden == steps.len() {
+        return Err(CliError::validation(
+            "start",
+            format!(
+                "{} – {} covers no pass of {} over the station",
+                humanize::timestamp(start),
+                end.format("%H:%M:%S UTC"),
+                name
+            ),
+        ));
+    }
+    if hidden > 0 {
+        eprintln!(
+            "⚠️ {} of the {} jobs fall outside the pass, where the station hears nothing",
+            hidden,
+            steps.len()
+        );
+    }
+    if request.dry_run {
+        eprintln!("🧪 Dry run: {} job(s) not submitted", steps.len());
+        return Ok(0);
+    }
+
+    if !maintenance::confirm_window(station.client, TimeWindow::new(start, end)).await? {
+        println!("🛑 Sweep not submitted");
+        return Ok(0);
+    }
+    let question = format!(
+        "Submit {} jobs, {} of airtime?",
+        steps.len(),
+        humanize::duration(end - start)
+    );
+    if !tty::confirm("jobs sweep", &question, request.yes)? {
+        println!("🛑 Sweep not submitted");
+        return Ok(0);
+    }
+
+    let values = steps
+        .iter()
+        .map(|step| {
+            let job = JobRequestBuilder::new(
+                step.start,
+                step.end,
+                JobType::Tracking { tle: tle.clone() },
+            )
+            .rx_frequency(step.frequency)
+            .campaign(Some(campaign.clone()))
+            .into_draft();
+            serde_json::to_value(&job).map_err(|e| CliError::parse("sweep job", e))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let source = format!("sweep of {}", name);
+    batch::submit_entries(
+        station.client,
+        station.hooks,
+        Path::new(&source),
+        values,
+        None,
+        false,
+        station.limits,
+        station.checks,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hz(hz: u64) -> Frequency {
+        Frequency::from_hz(hz)
+    }
+
+    #[test]
+    fn steps_run_from_the_bottom_to_the_top_of_the_band() {
+        let steps = frequencies(hz(435_000_000), hz(435_100_000), hz(25_000), 48).unwrap();
+        assert_eq!(
+            steps,
+            [
+                435_000_000,
+                435_025_000,
+                435_050_000,
+                435_075_000,
+                435_100_000
+            ]
+            .map(hz)
+        );
+        // A top the steps do not land on is not passed
+        let steps = frequencies(hz(435_000_000), hz(435_060_000), hz(25_000), 48).unwrap();
+        assert_eq!(steps.last(), Some(&hz(435_050_000)));
+    }
+
+    #[test]
+    fn a_band_upside_down_empty_or_too_finely_cut_is_refused() {
+        assert!(frequencies(hz(438_000_000), hz(435_000_000), hz(25_000), 48).is_err());
+        assert!(frequencies(hz(435_000_000), hz(435_000_000), hz(25_000), 48).is_err());
+        assert!(frequencies(hz(435_000_000), hz(438_000_000), Frequency::ZERO, 48).is_err());
+        let error = frequencies(hz(435_000_000), hz(438_000_000), hz(25_000), 48).unwrap_err();
+        assert!(error.to_string().contains("121 jobs"), "{}", error);
+    }
+}
@@ -0,0 +1,259 @@
+use crate::phases::{self, Phase};
+use crate::reproducible;
+use crate::text;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static WIDE: AtomicBool = AtomicBool::new(false);
+
+/// Narrowest a name column is cut to before the table is left to wrap
+const NAME_MIN_WIDTH: usize = 10;
+/// Indent before the first column
+const INDENT: usize = 2;
+/// Space between columns
+const GAP: usize = 2;
+
+/// `--wide`: print every column in full, whatever the terminal width
+pub fn set_wide(wide: bool) {
+    WIDE.store(wide, Ordering::Relaxed);
+}
+
+/// Columns available to a table: `COLUMNS` when set, else the terminal's width.
+/// None means full output: `--wide`, `--reproducible`, or stdout is not a terminal.
+pub fn width() -> Option<usize> {
+    if WIDE.load(Ordering::Relaxed) || reproducible::enabled() {
+        return None;
+    }
+    if let Some(columns) = std::env::var("COLUMNS")
+        .ok()
+        .and_then(|c| c.trim().parse().ok())
+    {
+        return Some(columns);
+    }
+    if !std::io::stdout().is_terminal() {
+        return None;
+    }
+    crossterm::terminal::size()
+        .ok()
+        .map(|(columns, _)| columns as usize)
+}
+
+/// Every "HH:MM:SS" in `text` as "HH:MM", fractions of a second included
+fn drop_seconds(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let digits = |at: usize| chars.get(at).is_some_and(|c| c.is_ascii_digit());
+    let clock = |at: usize| {
+        [0, 1, 3, 4, 6, 7].iter().all(|&i| digits(at + i))
+            && chars.get(at + 2) == Some(&':')
+            && chars.get(at + 5) == Some(&':')
+    };
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+    while i < chars.len() {
+        if clock(i) {
+            out.extend(&chars[i..i + 5]);
+            i += 8;
+            if chars.get(i) == Some(&'.') && digits(i + 1) {
+                i += 1;
+                while digits(i) {
+                    i += 1;
+                }
+            }
+        } else {
+            out.push(chars[i]);
+            i += 1;
+        }
+    }
+    out
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Plain,
+    /// Cut with an ellipsis when the table does not fit
+    Name,
+    /// Loses its seconds when the table does not fit
+    Time,
+}
+
+/// A table column: its header, how it gives way on a narrow terminal, and the width
+/// below which it is left out
+#[derive(Debug, Clone)]
+pub struct Column {
+    header: &'static str,
+    kind: Kind,
+    shown_from: usize,
+}
+
+impl Column {
+    pub fn new(header: &'static str) -> Self {
+        Self {
+            header,
+            kind: Kind::Plain,
+            shown_from: 0,
+        }
+    }
+
+    /// A free-form name, cut with an ellipsis before the table wraps
+    pub fn name(header: &'static str) -> Self {
+        Self {
+            kind: Kind::Name,
+            ..Self::new(header)
+        }
+    }
+
+    /// Timestamps, shown to the minute before any name is cut
+    pub fn time(header: &'static str) -> Self {
+        Self {
+            kind: Kind::Time,
+            ..Self::new(header)
+        }
+    }
+
+    /// Leave the column out on terminals narrower than `columns`
+    pub fn from(self, columns: usize) -> Self {
+        Self {
+            shown_from: columns,
+            ..self
+        }
+    }
+}
+
+/// Rows under a header, aligned, fitted to the terminal. Out of room, a table first
+/// leaves out the columns whose breakpoint is above the width, then shows times to the
+/// minute, then cuts names; whatever still does not fit wraps.
+#[derive(Debug, Clone)]
+pub struct Table {
+    columns: Vec<Column>,
+    rows: Vec<Vec<String>>,
+    /// Indexes of the rows printed dimmed
+    dimmed: Vec<usize>,
+}
+
+impl Table {
+    pub fn new(columns: Vec<Column>) -> Self {
+        Self {
+            columns,
+            rows: Vec::new(),
+            dimmed: Vec::new(),
+        }
+    }
+
+    /// One cell per column, in column order
+    pub fn row(&mut self, cells: Vec<String>) {
+        self.rows.push(cells);
+    }
+
+    /// A row for something no longer in effect, greyed out on terminals that show colour
+    pub fn dimmed_row(&mut self, cells: Vec<String>) {
+        self.dimmed.push(self.rows.len());
+        self.row(cells);
+    }
+
+    pub fn print(&self) {
+        let _span = phases::span(Phase::Render, || format!("{} row(s)", self.rows.len()));
+        let dim = reproducible::styled(std::io::stdout().is_terminal());
+        for (i, line) in self.render(width()).into_iter().enumerate() {
+            // Line 0 is the header
+            if dim && i > 0 && self.dimmed.contains(&(i - 1)) {
+                println!("\x1b[2m{}\x1b[0m", line);
+            } else {
+                println!("{}", line);
+            }
+        }
+    }
+
+    /// The lines `print` would print, without the dimming, for views that lay out a whole
+    /// screen before drawing it
+    pub fn lines(&self) -> Vec<String> {
+        self.render(width())
+    }
+
+    /// The table's lines fitted to `width` columns, or in full without one
+    fn render(&self, width: Option<usize>) -> Vec<String> {
+        let limit = width.unwrap_or(usize::MAX);
+        let shown: Vec<usize> = (0..self.columns.len())
+            .filter(|&i| self.columns[i].shown_from <= limit)
+            .collect();
+        let columns: Vec<&Column> = shown.iter().map(|&i| &self.columns[i]).collect();
+        let mut rows: Vec<Vec<String>> = self
+            .rows
+            .iter()
+            .map(|row| {
+                shown
+                    .iter()
+                    .map(|&i| row.get(i).cloned().unwrap_or_default())
+                    .collect()
+            })
+            .collect();
+
+        let widths = |rows: &[Vec<String>]| -> Vec<usize> {
+            columns
+                .iter()
+                .enumerate()
+                .map(|(i, column)| {
+                    rows.iter()
+                        .map(|row| text::width(&row[i]))
+                        .fold(text::width(column.header), usize::max)
+                })
+                .collect()
+        };
+        let total = |widths: &[usize]| {
+            INDENT + widths.iter().sum::<usize>() + GAP * widths.len().saturating_sub(1)
+        };
+
+        let mut column_widths = widths(&rows);
+        if total(&column_widths) > limit {
+            for row in &mut rows {
+                for (cell, column) in row.iter_mut().zip(&columns) {
+                    if column.kind == Kind::Time {
+                        *cell = drop_seconds(cell);
+                    }
+                }
+            }
+            column_widths = widths(&rows);
+        }
+        let excess = total(&column_widths).saturating_sub(limit);
+        if excess > 0 {
+            let names: Vec<usize> = (0..columns.len())
+                .filter(|&i| columns[i].kind == Kind::Name)
+                .collect();
+            let spare: usize = names
+                .iter()
+                .map(|&i| column_widths[i].saturating_sub(NAME_MIN_WIDTH))
+                .sum();
+            // Each name column gives up its share of the excess, none below the minimum
+            for &i in &names {
+                let give = column_widths[i].saturating_sub(NAME_MIN_WIDTH);
+                let cut = if spare == 0 {
+                    0
+                } else {
+                    (excess * give).div_ceil(spare).min(give)
+                };
+                column_widths[i] -= cut;
+                for row in &mut rows {
+                    row[i] = text::truncate(&row[i], column_widths[i]);
+                }
+            }
+        }
+
+        let line = |cells: Vec<&str>| {
+            let padded: Vec<String> = cells
+                .iter()
+                .zip(&column_widths)
+                .map(|(cell, &width)| text::pad(cell, width))
+                .collect();
+            format!(
+                "{}{}",
+                " ".repeat(INDENT),
+                padded.join(&" ".repeat(GAP)).trim_end()
+            )
+        };
+        let mut lines = vec![line(columns.iter().map(|c| c.header).collect())];
+        lines.extend(
+            rows.iter()
+                .map(|row| line(row.iter().map(String::as_str).collect())),
+        );
+        lines
+    }
+}
@@ -0,0 +1,352 @@
+//! `jobs tail`: an always-on view of the station for a wall display. The jobs that just
+//! ended, the ones running and the ones coming up, the clocks ticking every second. The
+//! schedule is followed through the server's job events where it has them and polled
+//! where it does not; an outage greys the view out instead of ending it.
+
+use crate::client::{ApiClient, JobDTO, JobEvents};
+use crate::clock;
+use crate::error::CliError;
+use crate::event_stream::Event;
+use crate::humanize;
+use crate::reproducible;
+use crate::table::{Column, Table};
+use crate::transcript;
+use crate::OutputFormat;
+use chrono::{DateTime, SubsecRound, Utc};
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::io::{IsTerminal, Write};
+use std::time::Duration;
+
+/// Width of the progress bar of a running job
+const BAR_WIDTH: usize = 20;
+
+/// What `jobs tail` shows and how often
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// Between polls of the job list, when the server sends no job events
+    pub interval: Duration,
+    /// Jobs that ended, most recent first
+    pub recent: usize,
+    /// Jobs still to start, soonest first
+    pub upcoming: usize,
+    /// Stop after this many refreshes of the job list; until Ctrl-C without
+    pub count: Option<usize>,
+}
+
+/// A `frames` event: how many frames a running job has received so far
+#[derive(Debug, Deserialize)]
+struct Frames {
+    #[serde(alias = "id")]
+    job_id: serde_json::Value,
+    frames: u64,
+}
+
+/// The schedule as last read, and how the reading went
+#[derive(Debug, Default)]
+struct View {
+    jobs: Vec<JobDTO>,
+    /// When the job list was last read
+    updated: Option<DateTime<Utc>>,
+    /// Why the last read failed, while the station is out of reach
+    failure: Option<String>,
+    /// Frames received by each running job, from the job events
+    frames: HashMap<String, u64>,
+    /// Whether job events are coming in, so no polling is needed
+    live: bool,
+}
+
+/// The three sections of the view at `now`: jobs that ended, most recent first; running
+/// jobs; jobs still to start, soonest first. Cancelled jobs are left out.
+fn sections<'a>(
+    jobs: &'a [JobDTO],
+    now: DateTime<Utc>,
+    options: &Options,
+) -> (Vec<&'a JobDTO>, Vec<&'a JobDTO>, Vec<&'a JobDTO>) {
+    let active: Vec<&JobDTO> = jobs.iter().filter(|job| !job.is_cancelled()).collect();
+    let mut recent: Vec<&JobDTO> = active
+        .iter()
+        .copied()
+        .filter(|job| job.job.end <= now)
+        .collect();
+    recent.sort_by(|a, b| b.job.end.cmp(&a.job.end).then_with(|| a.id.cmp(&b.id)));
+    recent.truncate(options.recent);
+    let mut running: Vec<&JobDTO> = active
+        .iter()
+        .copied()
+        .filter(|job| job.job.start <= now && now < job.job.end)
+        .collect();
+    running.sort_by(|a, b| JobDTO::schedule_order(a, b));
+    let mut upcoming: Vec<&JobDTO> = active
+        .iter()
+        .copied()
+        .filter(|job| job.job.start > now)
+        .collect();
+    upcoming.sort_by(|a, b| JobDTO::schedule_order(a, b));
+    upcoming.truncate(options.upcoming);
+    (recent, running, upcoming)
+}
+
+fn id(job: &JobDTO) -> String {
+    job.id.clone().unwrap_or_else(|| "—".to_string())
+}
+
+/// "[██████░░░░]" for the part of a job's window behind it
+fn bar(job: &JobDTO, now: DateTime<Utc>) -> String {
+    let total = (job.job.end - job.job.start).num_seconds().max(1);
+    let done = (now - job.job.start).num_seconds().clamp(0, total);
+    let filled = (done as usize * BAR_WIDTH) / total as usize;
+    format!("[{}{}]", "█".repeat(filled), "░".repeat(BAR_WIDTH - filled))
+}
+
+/// The whole screen at `now`, greyed out when the schedule could not be read
+fn render(view: &View, now: DateTime<Utc>, options: &Options) -> String {
+    let (recent, running, upcoming) = sections(&view.jobs, now, options);
+    let mut lines = vec![format!(
+        "📺 Station activity at {} ({})",
+        humanize::timestamp(now.trunc_subsecs(0)),
+        match view.live {
+            true => "live from the server's job events".to_string(),
+            false => format!("polling every {}s", options.interval.as_secs()),
+        }
+    )];
+
+    lines.push(String::new());
+    lines.push("✅ Recently ended".to_string());
+    if recent.is_empty() {
+        lines.push("  (none)".to_string());
+    } else {
+        let mut table = Table::new(vec![
+            Column::new("ID"),
+            Column::name("SATELLITE"),
+            Column::new("ENDED"),
+            Column::new("OUTCOME"),
+        ]);
+        for job in recent {
+            table.row(vec![
+                id(job),
+                job.job.label().to_string(),
+                humanize::relative_to(job.job.start, job.job.end, now),
+                job.status.clone().unwrap_or_else(|| "—".to_string()),
+            ]);
+        }
+        lines.extend(table.lines());
+    }
+
+    lines.push(String::new());
+    lines.push("▶️ Running now".to_string());
+    if running.is_empty() {
+        lines.push("  (nothing)".to_string());
+    }
+    for job in running {
+        let mut line = format!(
+            "  {} {}  {} elapsed, {} left  {}",
+            id(job),
+            job.job.label(),
+            humanize::duration(now - job.job.start),
+            humanize::duration(job.job.end - now),
+            bar(job, now)
+        );
+        if let Some(frames) = job.id.as_ref().and_then(|id| view.frames.get(id)) {
+            line.push_str(&format!("  {} frames", humanize::count(*frames as usize)));
+        }
+        lines.push(line);
+    }
+
+    lines.push(String::new());
+    lines.push("⏭️ Coming up".to_string());
+    if upcoming.is_empty() {
+        lines.push("  (nothing scheduled)".to_string());
+    } else {
+        let mut table = Table::new(vec![
+            Column::new("ID"),
+            Column::name("SATELLITE"),
+            Column::time("START (UTC)"),
+            Column::new("IN"),
+        ]);
+        for job in upcoming {
+            table.row(vec![
+                id(job),
+                job.job.label().to_string(),
+                job.job.start.format("%Y-%m-%d %H:%M:%S").to_string(),
+                humanize::duration(job.job.start - now),
+            ]);
+        }
+        lines.extend(table.lines());
+    }
+
+    if let Some(failure) = &view.failure {
+        lines.push(String::new());
+        lines.push(format!("⚠️ The station cannot be reached: {}", failure));
+        lines.push(
+            match view
+                .updated
+                .map(|updated| (now - updated).num_seconds().max(0))
+            {
+                Some(1) => "   last updated 1 second ago".to_string(),
+                Some(seconds) => format!("   last updated {} seconds ago", seconds),
+                None => "   not updated yet".to_string(),
+            },
+        );
+    }
+    lines.push(String::new());
+    lines.push("Ctrl-C to stop".to_string());
+
+    let text = lines.join("\n");
+    let grey = view.failure.is_some() && colour();
+    match grey {
+        true => format!("\x1b[2m{}\x1b[0m", text),
+        false => text,
+    }
+}
+
+/// Whether the view may use escape codes: stdout is a terminal and NO_COLOR is unset
+fn colour() -> bool {
+    reproducible::styled(std::io::stdout().is_terminal())
+}
+
+/// Whether the view is redrawn in place, every second
+fn live() -> bool {
+    reproducible::live(std::io::stdout().is_terminal())
+}
+
+/// Draw the view in place on a terminal; elsewhere each frame follows the last
+fn draw(view: &View, options: &Options) {
+    let screen = render(view, clock::now(), options);
+    let mut stdout = std::io::stdout().lock();
+    if live() {
+        let _ = write!(stdout, "\x1b[2J\x1b[H");
+    }
+    let _ = writeln!(stdout, "{}\n", screen);
+    let _ = stdout.flush();
+}
+
+/// One refresh as a JSON line, for `--output json`
+fn document(view: &View, options: &Options) -> serde_json::Value {
+    let (recent, running, upcoming) = sections(&view.jobs, clock::now(), options);
+    json!({
+        "updated_at": view.updated,
+        "error": view.failure,
+        "live": view.live,
+        "recent": recent,
+        "running": running,
+        "upcoming": upcoming,
+        "frames": view.frames,
+    })
+}
+
+/// Read the job list into the view; a failure keeps what was shown and says why
+async fn refresh(client: &ApiClient, view: &mut View) {
+    match client.list_jobs().await {
+        Ok(jobs) => {
+            view.jobs = jobs;
+            view.updated = Some(clock::now());
+            view.failure = None;
+        }
+        Err(e) => view.failure = Some(e.full_message()),
+    }
+}
+
+/// The next job event, or never while there is no stream
+async fn next_event(events: &mut Option<JobEvents>) -> Result<Option<Event>, CliError> {
+    match events {
+        Some(events) => events.next().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// A stream of terminal resizes, where the platform sends them
+#[cfg(unix)]
+fn resizes() -> Option<tokio::signal::unix::Signal> {
+    tokio::signal::unix::signal(tokio::signal::unix::SignalKind::window_change()).ok()
+}
+
+#[cfg(not(unix))]
+fn resizes() -> Option<()> {
+    None
+}
+
+/// The next resize, or never where there are none
+#[cfg(unix)]
+async fn resized(signal: &mut Option<tokio::signal::unix::Signal>) {
+    match signal {
+        Some(signal) => {
+            signal.recv().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(not(unix))]
+async fn resized(_: &mut Option<()>) {
+    std::future::pending().await
+}
+
+/// Follow the station until Ctrl-C, or for `options.count` refreshes
+pub async fn run(
+    client: &ApiClient,
+    options: &Options,
+    output: OutputFormat,
+) -> Result<(), CliError> {
+    transcript::stops_on_interrupt();
+    let text = output != OutputFormat::Json;
+    let mut view = View::default();
+    let mut events: Option<JobEvents> = None;
+    let mut resize = resizes();
+    let mut refreshes = 0;
+    let mut due = true;
+    let mut next_poll = tokio::time::Instant::now();
+
+    loop {
+        if due || (events.is_none() && tokio::time::Instant::now() >= next_poll) {
+            due = false;
+            if events.is_none() {
+                // Not offered, or the stream went away: poll, and look again next time
+                events = client.job_events().await.ok().flatten();
+            }
+            view.live = events.is_some();
+            refresh(client, &mut view).await;
+            next_poll = tokio::time::Instant::now() + options.interval;
+            refreshes += 1;
+            match text {
+                true => draw(&view, options),
+                false => println!("{}", document(&view, options)),
+            }
+            if options.count.is_some_and(|count| refreshes >= count) {
+                return Ok(());
+            }
+        } else if text && live() {
+            draw(&view, options);
+        }
+
+        // The clocks tick every second on a terminal; otherwise only the schedule is followed
+        let tick = match (text && live(), events.is_some()) {
+            (true, _) => Duration::from_secs(1),
+            (false, true) => options.interval,
+            (false, false) => next_poll.saturating_duration_since(tokio::time::Instant::now()),
+        };
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+            _ = tokio::time::sleep(tick) => {}
+            _ = resized(&mut resize) => {}
+            event = next_event(&mut events) => match event {
+                Ok(Some(event)) if event.name == "frames" => {
+                    if let Ok(frames) = serde_json::from_str::<Frames>(&event.data) {
+                        let id = match frames.job_id {
+                            serde_json::Value::String(id) => id,
+                            other => other.to_string(),
+                        };
+                        view.frames.insert(id, frames.frames);
+                    }
+                }
+                Ok(Some(_)) => due = true,
+                // Polled from here on, at most once an interval, until a stream is offered again
+                Ok(None) | Err(_) => {
+                    events = None;
+                    view.live = false;
+                }
+            },
+        }
+    }
+}
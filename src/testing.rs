@@ -0,0 +1,286 @@
+//! Generators of valid input for the hand-written parsers, and the property suites that
+//! run on them. Built by `cargo test`, and by `--features testing` for other harnesses;
+//! nothing in the binary itself calls the generators.
+#![cfg_attr(not(test), allow(dead_code))]
+
+use crate::parse::DURATION_UNITS;
+use crate::tle;
+use chrono::{DateTime, FixedOffset, Utc};
+use proptest::prelude::*;
+
+/// Cases per property; enough to reach the corners of each format in a few seconds
+pub const CASES: u32 = 256;
+
+/// The end of 2099, as seconds since the epoch
+const LATEST: i64 = 4_102_444_799;
+
+/// Unit-suffixed parts of a duration, as `parse::duration` reads them joined
+pub fn duration_parts() -> impl Strategy<Value = Vec<(i64, &'static str)>> {
+    let unit = proptest::sample::select(
+        DURATION_UNITS
+            .iter()
+            .map(|(unit, _, _)| *unit)
+            .collect::<Vec<_>>(),
+    );
+    proptest::collection::vec((0..10_000i64, unit), 1..5)
+}
+
+/// Length in milliseconds of one duration part
+pub fn part_millis(value: i64, unit: &str) -> i64 {
+    let (_, millis, _) = DURATION_UNITS
+        .iter()
+        .find(|(name, _, _)| *name == unit)
+        .expect("a known unit");
+    value * millis
+}
+
+/// The shortest text `parse::duration` reads back as `duration`, e.g. "1h30m15s"
+pub fn duration_text(duration: chrono::Duration) -> String {
+    let mut left = duration.num_milliseconds();
+    let mut text = String::new();
+    for (unit, millis, _) in DURATION_UNITS.iter().rev() {
+        if left >= *millis {
+            text.push_str(&format!("{}{}", left / millis, unit));
+            left %= millis;
+        }
+    }
+    if text.is_empty() {
+        text.push_str("0s");
+    }
+    text
+}
+
+/// An instant between 1970 and 2100, to the millisecond
+pub fn instant() -> impl Strategy<Value = DateTime<Utc>> {
+    (0..=LATEST, 0..1000u32).prop_map(|(seconds, millis)| {
+        DateTime::from_timestamp(seconds, millis * 1_000_000).expect("an instant in range")
+    })
+}
+
+/// A UTC offset in whole minutes, as far as offsets go
+pub fn offset() -> impl Strategy<Value = FixedOffset> {
+    (-14 * 60..=14 * 60).prop_map(|minutes: i32| {
+        FixedOffset::east_opt(minutes * 60).expect("an offset within a day")
+    })
+}
+
+/// Up to 100 GHz, in whole hertz
+pub fn frequency_hz() -> impl Strategy<Value = u64> {
+    0..=100_000_000_000u64
+}
+
+/// Degrees below `max`, whole minutes and seconds to the tenth
+pub fn dms(max: u32) -> impl Strategy<Value = (u32, u32, f64)> {
+    (0..max, 0..60u32, 0..600u32).prop_map(|(d, m, tenths)| (d, m, f64::from(tenths) / 10.0))
+}
+
+/// A data line numbered `number` with a valid checksum: digits and the other characters
+/// element sets use, in no particular layout
+pub fn tle_line(number: char) -> impl Strategy<Value = String> {
+    "[0-9 .+A-Z-]{66}".prop_map(move |body| {
+        let mut line = format!("{} {}", number, body);
+        line.push_str(&tle::checksum(&line).to_string());
+        line
+    })
+}
+
+/// Text that starts like a data line and runs to about its length, with one character of
+/// any kind put in near the end, for code that slices lines by column
+pub fn near_line() -> impl Strategy<Value = String> {
+    ("[ -~]{58,68}", any::<char>(), "[ -~]{0,4}")
+        .prop_map(|(before, odd, after)| format!("1 {}{}{}", before, odd, after))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frequency::Frequency;
+    use crate::humanize::{self, FrequencyUnit};
+    use crate::location;
+    use crate::parse;
+    use chrono::{Datelike, NaiveDate};
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(CASES))]
+
+        #[test]
+        fn duration_parts_add_up(parts in duration_parts()) {
+            let text: String = parts.iter().map(|(value, unit)| format!("{}{}", value, unit)).collect();
+            let expected: i64 = parts.iter().map(|(value, unit)| part_millis(*value, unit)).sum();
+            prop_assert_eq!(parse::duration(&text).unwrap().num_milliseconds(), expected);
+        }
+
+        #[test]
+        fn duration_round_trips(millis in 0..1_000_000_000_000i64) {
+            let duration = chrono::Duration::milliseconds(millis);
+            let text = duration_text(duration);
+            prop_assert_eq!(parse::duration(&text).unwrap(), duration, "{}", text);
+        }
+
+        #[test]
+        fn duration_units_agree(n in 0..1_000_000i64) {
+            let hours = parse::duration(&format!("{}h", n)).unwrap();
+            prop_assert_eq!(parse::duration(&format!("{}m", n * 60)).unwrap(), hours);
+            prop_assert_eq!(parse::duration(&format!("{}s", n * 3600)).unwrap(), hours);
+            prop_assert_eq!(parse::duration(&format!("{}ms", n * 3_600_000)).unwrap(), hours);
+        }
+
+        #[test]
+        fn durations_never_panic(input in "\\PC{0,16}|[0-9a-z ]{0,24}") {
+            let _ = parse::duration(&input);
+            let _ = parse::instant(&input);
+        }
+
+        #[test]
+        fn rfc3339_round_trips(at in instant()) {
+            prop_assert_eq!(parse::datetime(&at.to_rfc3339()).unwrap(), at);
+        }
+
+        #[test]
+        fn prompt_datetimes_keep_their_offset(at in instant(), offset in offset()) {
+            let local = at.with_timezone(&offset);
+            let date = local.format("%Y-%m-%d").to_string();
+            let time = local.format("%H:%M:%S%.3f%:z").to_string();
+            prop_assert_eq!(parse::user_datetime(&date, &time).unwrap(), at);
+            prop_assert_eq!(parse::datetime(&format!("{} {}", date, time)).unwrap(), at);
+            prop_assert_eq!(parse::datetime(&format!("{}T{}", date, time)).unwrap(), at);
+        }
+
+        #[test]
+        fn minutes_alone_mean_the_start_of_the_minute(at in instant()) {
+            let date = at.format("%Y-%m-%d").to_string();
+            let minute = parse::user_datetime(&date, &at.format("%H:%M").to_string()).unwrap();
+            let seconds = parse::user_datetime(&date, &at.format("%H:%M:00Z").to_string()).unwrap();
+            prop_assert_eq!(minute, seconds);
+        }
+
+        #[test]
+        fn datetimes_never_panic(input in "\\PC{0,32}|[0-9:+TZ. -]{0,32}") {
+            let _ = parse::datetime(&input);
+            let _ = parse::user_datetime(&input, &input);
+        }
+
+        #[test]
+        fn hertz_round_trip(hz in frequency_hz()) {
+            prop_assert_eq!(Frequency::parse(&hz.to_string()).unwrap().hz(), hz);
+        }
+
+        #[test]
+        fn frequency_suffixes_agree(khz in 0..100_000_000u64) {
+            let hz = Frequency::parse(&(khz * 1000).to_string()).unwrap();
+            prop_assert_eq!(Frequency::parse(&format!("{}k", khz)).unwrap(), hz);
+            prop_assert_eq!(Frequency::parse(&format!("{} kHz", khz)).unwrap(), hz);
+            prop_assert_eq!(Frequency::parse(&format!("{}K", khz)).unwrap(), hz);
+        }
+
+        #[test]
+        fn decimal_megahertz_match_kilohertz(mhz in 0..100_000u64, thousandths in 0..1000u64) {
+            let decimal = Frequency::parse(&format!("{}.{:03}M", mhz, thousandths)).unwrap();
+            let khz = Frequency::parse(&format!("{}k", mhz * 1000 + thousandths)).unwrap();
+            prop_assert_eq!(decimal, khz);
+        }
+
+        #[test]
+        fn shown_frequencies_read_back(hz in 0..1_000_000_000u64) {
+            let shown = Frequency::from_hz(hz).to_string();
+            prop_assert_eq!(Frequency::parse(&shown).unwrap().hz(), hz, "{}", shown);
+        }
+
+        #[test]
+        fn every_unit_shows_the_exact_hertz(hz in 0..40_000_000_000u64) {
+            for unit in [FrequencyUnit::Auto, FrequencyUnit::Hz, FrequencyUnit::Khz, FrequencyUnit::Mhz] {
+                let shown = humanize::frequency_in(hz as f64, unit);
+                prop_assert_eq!(Frequency::parse(&shown).unwrap().hz(), hz, "{}", shown);
+            }
+        }
+
+        #[test]
+        fn frequencies_never_panic(input in "\\PC{0,16}|[0-9.kKMGgHz -]{0,16}") {
+            let _ = Frequency::parse(&input);
+        }
+
+        #[test]
+        fn decimal_coordinates_round_trip(lat in -90.0..=90.0f64, lon in -180.0..=180.0f64) {
+            let parsed = location::parse(&format!("{},{}", lat, lon)).unwrap();
+            prop_assert_eq!((parsed.latitude, parsed.longitude), (lat, lon));
+        }
+
+        #[test]
+        fn dms_reads_as_degrees(
+            (lat_d, lat_m, lat_s) in dms(90),
+            (lon_d, lon_m, lon_s) in dms(180),
+            south: bool,
+            west: bool,
+        ) {
+            let text = format!(
+                "{}°{}'{:.1}\"{} {}°{}'{:.1}\"{}",
+                lat_d, lat_m, lat_s, if south { 'S' } else { 'N' },
+                lon_d, lon_m, lon_s, if west { 'W' } else { 'E' },
+            );
+            let degrees = |d: u32, m: u32, s: f64| f64::from(d) + f64::from(m) / 60.0 + s / 3600.0;
+            let parsed = location::parse(&text).unwrap();
+            let latitude = degrees(lat_d, lat_m, lat_s) * if south { -1.0 } else { 1.0 };
+            let longitude = degrees(lon_d, lon_m, lon_s) * if west { -1.0 } else { 1.0 };
+            prop_assert!((parsed.latitude - latitude).abs() < 1e-9, "{}", text);
+            prop_assert!((parsed.longitude - longitude).abs() < 1e-9, "{}", text);
+        }
+
+        #[test]
+        fn coordinates_never_panic(input in "\\PC{0,32}|[0-9°'\" .,NSEWm-]{0,32}") {
+            let _ = location::parse(&input);
+        }
+
+        #[test]
+        fn sweeps_round_trip(start in 0.0..360.0f64, end in 0.0..360.0f64, step in 0.0..90.0f64) {
+            let text = format!("{}-{}/{}", start, end, step);
+            prop_assert_eq!(parse::sweep_axis(&text).unwrap(), (start, end, step));
+        }
+
+        #[test]
+        fn checksummed_lines_pass(line in tle_line('1')) {
+            prop_assert_eq!(tle::check_line(&line, '1'), None);
+        }
+
+        #[test]
+        fn a_changed_digit_fails_the_checksum(
+            line in tle_line('2'),
+            pick: proptest::sample::Index,
+            step in 1..10u32,
+        ) {
+            let digits: Vec<usize> = (2..68).filter(|&i| line.as_bytes()[i].is_ascii_digit()).collect();
+            prop_assume!(!digits.is_empty());
+            let column = digits[pick.index(digits.len())];
+            let old = char::from(line.as_bytes()[column]).to_digit(10).unwrap();
+            let new = char::from_digit((old + step) % 10, 10).unwrap();
+            let mut changed = line.clone();
+            changed.replace_range(column..=column, &new.to_string());
+            let reason = tle::check_line(&changed, '2');
+            prop_assert!(reason.as_deref().is_some_and(|r| r.contains("checksum")), "{:?}", reason);
+        }
+
+        #[test]
+        fn epochs_read_as_their_day(
+            line in tle_line('1'),
+            year in 1957..=2056i32,
+            day in 1..=365u32,
+            fraction in 0..100_000_000u32,
+        ) {
+            let field = format!("{:02}{:03}.{:08}", year % 100, day, fraction);
+            let mut line = line;
+            line.replace_range(18..32, &field);
+            let start = NaiveDate::from_yo_opt(year, day).unwrap().and_hms_opt(0, 0, 0).unwrap().and_utc();
+            let expected = start + chrono::Duration::milliseconds(i64::from(fraction) * 864 / 1000);
+            let epoch = tle::epoch(&line).unwrap();
+            prop_assert_eq!(epoch.year(), year);
+            prop_assert!((epoch - expected).num_milliseconds().abs() <= 1, "{} vs {}", epoch, expected);
+        }
+
+        #[test]
+        fn line_fields_never_panic(line in near_line()) {
+            let _ = tle::check_line(&line, '1');
+            let _ = tle::epoch(&line);
+            let _ = tle::norad_id(&line);
+            let _ = tle::identity(&line);
+        }
+    }
+}
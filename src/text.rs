@@ -0,0 +1,126 @@
+//! Names as catalogs and operators write them: accented, combining, CJK, right-to-left,
+//! emoji, or hundreds of characters long. Terminal layout goes by the columns a name
+//! takes on screen, not by its bytes or characters, and cuts it between graphemes;
+//! comparisons go by its NFC form, so "Café" typed and "Café" pasted are the same name.
+
+use icu_normalizer::ComposingNormalizerBorrowed;
+use std::borrow::Cow;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Characters that reorder or embed the text after them (U+202A–U+202E, U+2066–U+2069),
+/// which would carry a name's direction into the columns beside it
+fn is_bidi_control(c: char) -> bool {
+    matches!(c, '\u{202a}'..='\u{202e}' | '\u{2066}'..='\u{2069}')
+}
+
+/// `text` safe to lay out on one terminal line: control characters, line breaks among
+/// them, become spaces, and direction overrides are left out
+pub fn terminal(text: &str) -> Cow<'_, str> {
+    if !text.chars().any(|c| c.is_control() || is_bidi_control(c)) {
+        return Cow::Borrowed(text);
+    }
+    Cow::Owned(
+        text.chars()
+            .filter(|&c| !is_bidi_control(c))
+            .map(|c| if c.is_control() { ' ' } else { c })
+            .collect(),
+    )
+}
+
+/// Columns `text` takes on a terminal: two for CJK and most emoji, none for combining
+/// marks
+pub fn width(text: &str) -> usize {
+    terminal(text).width()
+}
+
+/// `text` cut to `width` columns, the last of them an ellipsis when anything was cut.
+/// A grapheme is kept whole or left out, so no accent loses its letter.
+pub fn truncate(text: &str, width: usize) -> String {
+    let text = terminal(text);
+    if text.width() <= width {
+        return text.into_owned();
+    }
+    let room = width.saturating_sub(1);
+    let mut kept = String::new();
+    let mut used = 0;
+    for grapheme in text.graphemes(true) {
+        let columns = grapheme.width();
+        if used + columns > room {
+            break;
+        }
+        kept.push_str(grapheme);
+        used += columns;
+    }
+    format!("{}…", kept)
+}
+
+/// `text` followed by spaces to fill `width` columns; wider text is left as it is
+pub fn pad(text: &str, width: usize) -> String {
+    let text = terminal(text);
+    let fill = width.saturating_sub(text.width());
+    format!("{}{}", text, " ".repeat(fill))
+}
+
+/// `text` in Unicode normalization form C, composed as most keyboards type it
+pub fn nfc(text: &str) -> Cow<'_, str> {
+    ComposingNormalizerBorrowed::new_nfc().normalize(text)
+}
+
+/// Whether two names are the same once normalized, whatever their case or surrounding
+/// spaces
+pub fn same_name(a: &str, b: &str) -> bool {
+    nfc(a.trim()).to_lowercase() == nfc(b.trim()).to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wide_and_combining_characters_are_measured_as_shown() {
+        assert_eq!(width("ひまわり"), 8);
+        assert_eq!(width("Cafe\u{301}"), 4);
+        assert_eq!(width("🛰️ SAT"), 6);
+        assert_eq!(width("line\nbreak"), 10);
+    }
+
+    #[test]
+    fn truncation_keeps_graphemes_whole_and_fits_the_width() {
+        assert_eq!(truncate("NOAA 19", 10), "NOAA 19");
+        assert_eq!(truncate("Cafe\u{301} Cafe\u{301}", 5), "Cafe\u{301}…");
+        assert_eq!(truncate("ひまわり8号", 6), "ひま…");
+        for name in ["ひまわり8号", "👩‍🚀👩‍🚀👩‍🚀", "ÅÅÅÅÅ", "WX-3 (DEB) [unconfirmed]"]
+        {
+            for columns in 1..12 {
+                assert!(
+                    width(&truncate(name, columns)) <= columns,
+                    "{} at {}",
+                    name,
+                    columns
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn padding_fills_columns_not_characters() {
+        assert_eq!(pad("ひま", 6), "ひま  ");
+        assert_eq!(pad("Cafe\u{301}", 6), "Cafe\u{301}  ");
+        assert_eq!(pad("too long", 3), "too long");
+    }
+
+    #[test]
+    fn direction_overrides_and_controls_stay_out_of_the_line() {
+        assert_eq!(terminal("\u{202e}evil\u{202c} sat"), "evil sat");
+        assert_eq!(terminal("two\tparts"), "two parts");
+        assert!(matches!(terminal("plain"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn names_compare_once_normalized() {
+        assert!(same_name("Cafe\u{301}", "Café"));
+        assert!(same_name(" ESTCube-2 ", "estcube-2"));
+        assert!(!same_name("Café", "Cafe"));
+    }
+}
@@ -0,0 +1,219 @@
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+/// Set once from `--timing`: report how long every API request took
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static RECORDER: Mutex<Recorder> = Mutex::new(Recorder::new());
+
+/// What one request cost, from the moment it was sent to the end of its body
+#[derive(Debug, Clone)]
+struct Sample {
+    /// DNS and connection set-up, when the request had to open a connection
+    connection: Option<Connection>,
+    ttfb: Duration,
+    total: Duration,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Connection {
+    dns: Duration,
+    /// TCP connect and, for https, the TLS handshake; reqwest reports them as one step
+    connect: Duration,
+}
+
+struct Recorder {
+    /// Set-up measured since the last request finished, claimed by the next one
+    dns: Option<Duration>,
+    connect: Option<Duration>,
+    samples: Vec<Sample>,
+}
+
+impl Recorder {
+    const fn new() -> Self {
+        Self {
+            dns: None,
+            connect: None,
+            samples: Vec::new(),
+        }
+    }
+}
+
+fn recorder() -> std::sync::MutexGuard<'static, Recorder> {
+    RECORDER.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+fn ms(duration: Duration) -> String {
+    format!("{:.1} ms", duration.as_secs_f64() * 1000.0)
+}
+
+/// Times one request; the sample is recorded and printed when it is dropped,
+/// which callers arrange to happen once the body has been read
+pub struct Timer {
+    label: String,
+    started: Instant,
+    ttfb: Option<Duration>,
+}
+
+/// A timer for a request about to be sent, when `--timing` is on
+pub fn start(method: &reqwest::Method, path: &str) -> Option<Timer> {
+    enabled().then(|| Timer {
+        label: format!("{} {}", method, path),
+        started: Instant::now(),
+        ttfb: None,
+    })
+}
+
+impl Timer {
+    /// The response headers have arrived
+    pub fn headers(&mut self) {
+        self.ttfb.get_or_insert_with(|| self.started.elapsed());
+    }
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        let total = self.started.elapsed();
+        let mut recorder = recorder();
+        // Requests run one after another, so set-up since the last one belongs to this one
+        let connection = match (recorder.dns.take(), recorder.connect.take()) {
+            (None, None) => None,
+            (dns, connect) => {
+                let dns = dns.unwrap_or_default();
+                Some(Connection {
+                    dns,
+                    connect: connect.unwrap_or_default().saturating_sub(dns),
+                })
+            }
+        };
+        let sample = Sample {
+            connection,
+            ttfb: self.ttfb.unwrap_or(total),
+            total,
+        };
+        let setup = match sample.connection {
+            Some(c) => format!(
+                "new connection (dns {}, connect+tls {})",
+                ms(c.dns),
+                ms(c.connect)
+            ),
+            None => "reused connection".to_string(),
+        };
+        eprintln!(
+            "⏱️ {}: {}, ttfb {}, total {}",
+            self.label,
+            setup,
+            ms(sample.ttfb),
+            ms(sample.total)
+        );
+        recorder.samples.push(sample);
+    }
+}
+
+/// Print the totals for the invocation; nothing when `--timing` is off or nothing was sent
+pub fn finish() {
+    if !enabled() {
+        return;
+    }
+    let samples = std::mem::take(&mut recorder().samples);
+    if samples.is_empty() {
+        return;
+    }
+    let connections: Vec<Connection> = samples.iter().filter_map(|s| s.connection).collect();
+    let average =
+        |durations: &[Duration]| durations.iter().sum::<Duration>() / durations.len().max(1) as u32;
+    let largest = |durations: &[Duration]| durations.iter().max().copied().unwrap_or_default();
+    let ttfb: Vec<Duration> = samples.iter().map(|s| s.ttfb).collect();
+    let total: Vec<Duration> = samples.iter().map(|s| s.total).collect();
+    let dns: Vec<Duration> = connections.iter().map(|c| c.dns).collect();
+    let connect: Vec<Duration> = connections.iter().map(|c| c.connect).collect();
+
+    eprintln!(
+        "⏱️ {} request(s) over {} new connection(s), {} reused",
+        samples.len(),
+        connections.len(),
+        samples.len() - connections.len()
+    );
+    if !connections.is_empty() {
+        eprintln!(
+            "   dns avg {}, connect+tls avg {} (per new connection)",
+            ms(average(&dns)),
+            ms(average(&connect))
+        );
+    }
+    eprintln!(
+        "   ttfb avg {}, max {}; total avg {}, max {}, sum {}",
+        ms(average(&ttfb)),
+        ms(largest(&ttfb)),
+        ms(average(&total)),
+        ms(largest(&total)),
+        ms(total.iter().sum())
+    );
+}
+
+/// System resolver that records how long each lookup took
+pub struct TimedResolver;
+
+impl Resolve for TimedResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let started = Instant::now();
+            let addrs: Vec<_> = tokio::net::lookup_host((name.as_str(), 0)).await?.collect();
+            *recorder().dns.get_or_insert_default() += started.elapsed();
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Connector layer recording how long opening a connection took, DNS included
+#[derive(Clone)]
+pub struct ConnectTiming;
+
+impl<S> tower_layer::Layer<S> for ConnectTiming {
+    type Service = TimedConnect<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TimedConnect { inner }
+    }
+}
+
+#[derive(Clone)]
+pub struct TimedConnect<S> {
+    inner: S,
+}
+
+impl<S, R> tower_service::Service<R> for TimedConnect<S>
+where
+    S: tower_service::Service<R>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: R) -> Self::Future {
+        let connecting = self.inner.call(request);
+        Box::pin(async move {
+            let started = Instant::now();
+            let connection = connecting.await;
+            *recorder().connect.get_or_insert_default() += started.elapsed();
+            connection
+        })
+    }
+}
@@ -0,0 +1,530 @@
+use crate::client::TleData;
+use crate::clock;
+use crate::error::{self, CliError, ResultExt};
+use crate::examples::Example;
+use crate::paths;
+use crate::phases::{self, Phase};
+use crate::polite;
+use crate::satellite_id::{self, SatelliteId};
+use crate::snapshot::{self, Origin};
+use crate::storage;
+use chrono::{DateTime, NaiveDate, Utc};
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Number of characters in a TLE data line, checksum digit included
+pub const LINE_LENGTH: usize = 69;
+
+/// Modulo-10 checksum over the first 68 columns: digits count as their value, '-' as 1
+pub fn checksum(line: &str) -> u32 {
+    line.chars()
+        .take(LINE_LENGTH - 1)
+        .map(|c| match c {
+            '0'..='9' => c.to_digit(10).unwrap_or(0),
+            '-' => 1,
+            _ => 0,
+        })
+        .sum::<u32>()
+        % 10
+}
+
+/// Check the structure of one data line, returning a reason when it is malformed
+pub fn check_line(line: &str, number: char) -> Option<String> {
+    if line.len() < LINE_LENGTH {
+        return Some(format!(
+            "expected {} characters, got {}; a line cut short was often wrapped on the way",
+            LINE_LENGTH,
+            line.len()
+        ));
+    }
+    if line.len() != LINE_LENGTH {
+        return Some(format!(
+            "expected {} characters, got {}",
+            LINE_LENGTH,
+            line.len()
+        ));
+    }
+    if !line.starts_with(&format!("{} ", number)) {
+        return Some(format!("line must start with '{} '", number));
+    }
+
+    let expected = checksum(line);
+    // By byte: a line of the right length with a wide character in it must not panic
+    match char::from(line.as_bytes()[LINE_LENGTH - 1]).to_digit(10) {
+        Some(found) if found == expected => None,
+        Some(found) => Some(format!(
+            "checksum mismatch (line says {}, computed {})",
+            found, expected
+        )),
+        None => Some("last column must be the checksum digit".to_string()),
+    }
+}
+
+/// NORAD catalog number from columns 3–7 of a data line
+pub fn catalog_number(line: &str) -> Option<&str> {
+    line.get(2..7).map(str::trim)
+}
+
+/// NORAD catalog number of a data line as a number, so "00005" and "5" compare equal
+/// and Alpha-5 numbers ("A0001") read as what they stand for
+pub fn norad_id(line: &str) -> Option<u32> {
+    satellite_id::parse_norad_id(catalog_number(line)?)
+}
+
+/// "NORAD 25544 · 1998-067A" for the satellite line 1 describes
+pub fn identity(line1: &str) -> Option<String> {
+    Some(satellite_id::describe(
+        norad_id(line1)?,
+        intl_designator(line1).as_deref(),
+    ))
+}
+
+/// International designator from columns 10–17 of line 1, as "1998-067A"; none when
+/// the columns are blank, as they are for some analyst objects
+pub fn intl_designator(line1: &str) -> Option<String> {
+    satellite_id::parse_designator(line1.get(9..17)?)
+}
+
+/// Validate a complete element set, returning (field, reason) for each problem
+pub fn validate(tle: &TleData) -> Vec<(&'static str, String)> {
+    let mut problems = Vec::new();
+
+    if tle.tle0.trim().is_empty() {
+        problems.push(("tle.tle0", "satellite name is empty".to_string()));
+    }
+    if let Some(reason) = check_line(&tle.tle1, '1') {
+        problems.push(("tle.tle1", reason));
+    }
+    if let Some(reason) = check_line(&tle.tle2, '2') {
+        problems.push(("tle.tle2", reason));
+    }
+
+    if problems.is_empty() && catalog_number(&tle.tle1) != catalog_number(&tle.tle2) {
+        problems.push((
+            "tle.tle2",
+            "catalog number does not match line 1".to_string(),
+        ));
+    }
+
+    problems
+}
+
+/// Epoch of an element set from columns 19–32 of line 1 (two-digit year, fractional day)
+pub fn epoch(line1: &str) -> Option<DateTime<Utc>> {
+    let year: i32 = line1.get(18..20)?.trim().parse().ok()?;
+    let day: f64 = line1.get(20..32)?.trim().parse().ok()?;
+    // Two-digit years follow the NORAD convention: 57–99 are 1900s, the rest 2000s
+    let year = if year >= 57 { 1900 + year } else { 2000 + year };
+
+    let new_year = NaiveDate::from_yo_opt(year, 1)?.and_hms_opt(0, 0, 0)?;
+    let offset = chrono::Duration::milliseconds(((day - 1.0) * 86_400_000.0).round() as i64);
+    Some(DateTime::from_naive_utc_and_offset(new_year + offset, Utc))
+}
+
+/// Named fields of an element set, in the order they appear in the lines
+pub fn fields(tle: &TleData) -> Vec<(&'static str, String)> {
+    let column = |line: &str, range: std::ops::Range<usize>| {
+        line.get(range).map(str::trim).unwrap_or("").to_string()
+    };
+    let (line1, line2) = (tle.tle1.as_str(), tle.tle2.as_str());
+
+    vec![
+        ("name", tle.tle0.trim().to_string()),
+        ("catalog number", column(line1, 2..7)),
+        ("classification", column(line1, 7..8)),
+        ("international designator", column(line1, 9..17)),
+        (
+            "epoch",
+            epoch(line1)
+                .map(|e| e.format("%Y-%m-%d %H:%M:%S%.3f UTC").to_string())
+                .unwrap_or_else(|| column(line1, 18..32)),
+        ),
+        ("mean motion dot", column(line1, 33..43)),
+        ("mean motion ddot", column(line1, 44..52)),
+        ("bstar", column(line1, 53..61)),
+        ("element set number", column(line1, 64..68)),
+        ("inclination", column(line2, 8..16)),
+        ("right ascension", column(line2, 17..25)),
+        ("eccentricity", format!("0.{}", column(line2, 26..33))),
+        ("argument of perigee", column(line2, 34..42)),
+        ("mean anomaly", column(line2, 43..51)),
+        ("mean motion", column(line2, 52..63)),
+        ("revolution number", column(line2, 63..68)),
+    ]
+}
+
+/// Read a two- or three-line element set file, named after the file when it has no name line
+pub fn read_file(path: &Path) -> Result<TleData, CliError> {
+    let text =
+        std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    let fallback = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+    parse_text(&text, fallback)
+        .map_err(|reason| CliError::parse(format!("TLE file {}", path.display()), reason))
+}
+
+/// Environment variable overriding where element sets are fetched from
+pub const SOURCE_ENV: &str = "TLE_SOURCE_URL";
+
+/// Where element sets are fetched from; `{norad}` is replaced by the catalog number
+pub const DEFAULT_TLE_SOURCE: &str =
+    "https://celestrak.org/NORAD/elements/gp.php?CATNR={norad}&FORMAT=TLE";
+
+/// The resolved `tle_source` of this run
+static SOURCE: OnceLock<String> = OnceLock::new();
+
+/// Fetch element sets from the resolved `tle_source`, whichever layer it came from
+pub fn use_source(template: &str) {
+    let _ = SOURCE.set(template.to_string());
+}
+
+/// Environment variable overriding where element sets are fetched by designator
+pub const DESIGNATOR_SOURCE_ENV: &str = "TLE_DESIGNATOR_SOURCE_URL";
+
+/// Where element sets are fetched by international designator; `{designator}` is
+/// replaced by its "1998-067A" form
+pub const DEFAULT_TLE_DESIGNATOR_SOURCE: &str =
+    "https://celestrak.org/NORAD/elements/gp.php?INTDES={designator}&FORMAT=TLE";
+
+/// Cached element sets younger than this are used without asking the network
+pub const CACHE_MAX_AGE: std::time::Duration = std::time::Duration::from_secs(12 * 60 * 60);
+
+/// Whether a line is one of the two element lines, by its leading "1 " or "2 "
+fn is_element_line(line: &str) -> bool {
+    line.starts_with("1 ") || line.starts_with("2 ")
+}
+
+/// An element set from a name and two element lines as people paste them: surrounding
+/// whitespace and the "0 " Space-Track puts before names are dropped, and lines given
+/// as 2 then 1 are put back in order. Columns inside a line are left as they are.
+pub fn assemble(name: &str, first: &str, second: &str) -> TleData {
+    let name = name.trim();
+    let name = name.strip_prefix("0 ").map(str::trim_start).unwrap_or(name);
+    let (mut line1, mut line2) = (first.trim(), second.trim());
+    if line1.starts_with("2 ") && line2.starts_with("1 ") {
+        std::mem::swap(&mut line1, &mut line2);
+    }
+    TleData {
+        tle0: name.to_string(),
+        tle1: line1.to_string(),
+        tle2: line2.to_string(),
+    }
+}
+
+/// Why an element line followed by `next` looks wrapped: it is too short, and the rest
+/// of it arrived as a line of its own
+fn wrapped(number: usize, line: &str, next: Option<&&str>) -> Option<String> {
+    let next = next.filter(|next| !is_element_line(next))?;
+    (line.len() < LINE_LENGTH && line.len() + next.len() <= LINE_LENGTH + 1).then(|| {
+        format!(
+            "element line {} has {} of its {} characters and is followed by '{}': \
+             it looks wrapped, as email clients and some editors do to long lines; \
+             join the pieces back into one line",
+            number,
+            line.len(),
+            LINE_LENGTH,
+            next
+        )
+    })
+}
+
+/// Parse a two- or three-line element set, using `fallback_name` when there is no name line.
+/// Of several sets, the first is read. Tolerates what `assemble` does; refuses wrapped lines.
+pub fn parse_text(text: &str, fallback_name: &str) -> Result<TleData, String> {
+    let lines: Vec<&str> = text
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect();
+    let first = lines
+        .iter()
+        .position(|line| is_element_line(line))
+        .ok_or("expected a two- or three-line element set: no line starts with '1 ' or '2 '")?;
+    let name = match first {
+        0 => fallback_name,
+        _ => lines[first - 1],
+    };
+    let rest = &lines[first..];
+    if let Some(reason) = wrapped(1, rest[0], rest.get(1)) {
+        return Err(reason);
+    }
+    let Some(second) = rest.get(1).filter(|line| is_element_line(line)) else {
+        return Err("expected a two- or three-line element set: only one element line".into());
+    };
+    if let Some(reason) = wrapped(2, second, rest.get(2)) {
+        return Err(reason);
+    }
+    Ok(assemble(name, rest[0], second))
+}
+
+fn read_cache(norad_id: u32) -> Option<(TleData, std::time::Duration)> {
+    let path = paths::tle_cache_dir().join(format!("{}.tle", norad_id));
+    let age = std::fs::metadata(&path)
+        .and_then(|m| m.modified())
+        .ok()
+        .map(clock::age)?;
+    let text = std::fs::read_to_string(&path).ok()?;
+    parse_text(&text, &norad_id.to_string())
+        .ok()
+        .map(|tle| (tle, age))
+}
+
+/// Element set for a catalog number or designator from the cache alone, with its age.
+/// The cache is keyed by catalog number, so a designator is looked for in every entry.
+pub fn cached(id: &SatelliteId) -> Option<(TleData, std::time::Duration)> {
+    match id {
+        SatelliteId::NoradId(norad_id) => read_cache(*norad_id),
+        SatelliteId::IntlDesignator(_) => std::fs::read_dir(paths::tle_cache_dir())
+            .ok()?
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                let norad_id = path.file_stem()?.to_str()?.parse().ok()?;
+                (path.extension()? == "tle").then(|| read_cache(norad_id))?
+            })
+            .find(|(tle, _)| id.matches_line(&tle.tle1)),
+        SatelliteId::Name(_) => None,
+    }
+}
+
+fn write_cache(norad_id: u32, tle: &TleData) -> Result<(), CliError> {
+    let path = paths::tle_cache_dir().join(format!("{}.tle", norad_id));
+    storage::write_atomic(&path, format!("{}\n{}\n{}\n", tle.tle0, tle.tle1, tle.tle2))
+}
+
+/// Download the current element set for a catalog number
+async fn fetch(norad_id: u32) -> Result<TleData, CliError> {
+    let template = SOURCE.get().map_or(DEFAULT_TLE_SOURCE, String::as_str);
+    let url = template.replace("{norad}", &norad_id.to_string());
+    download(&url, &SatelliteId::NoradId(norad_id)).await
+}
+
+/// Download the element set at `url`, named after `id` when the response has no name line
+async fn download(url: &str, id: &SatelliteId) -> Result<TleData, CliError> {
+    let fallback = match id {
+        SatelliteId::NoradId(norad_id) => norad_id.to_string(),
+        SatelliteId::IntlDesignator(designator) | SatelliteId::Name(designator) => {
+            designator.clone()
+        }
+    };
+    let text = polite::get(url, &id.to_string()).await?;
+    let tle = parse_text(&text, &fallback)
+        .map_err(|reason| CliError::parse(format!("TLE for {} from {}", id, url), reason))?;
+
+    if let Some((field, reason)) = validate(&tle).into_iter().next() {
+        return Err(CliError::validation(field, reason));
+    }
+    snapshot::element_origin(
+        &tle.tle1,
+        Origin::Network {
+            url: url.to_string(),
+        },
+    );
+    Ok(tle)
+}
+
+/// `tle` as read from the cache, written `age` before; noted for job snapshots
+fn cached_copy(tle: &TleData, age: std::time::Duration) -> TleData {
+    snapshot::element_origin(
+        &tle.tle1,
+        Origin::Cache {
+            age_seconds: age.as_secs(),
+        },
+    );
+    tle.clone()
+}
+
+/// The job's element set fetched again, keeping its name; unchanged when it names no satellite
+pub async fn refreshed(tle: &TleData) -> Result<TleData, CliError> {
+    let Some(norad_id) = norad_id(&tle.tle1) else {
+        return Ok(tle.clone());
+    };
+    Ok(TleData {
+        tle0: tle.tle0.clone(),
+        ..resolve(norad_id).await?
+    })
+}
+
+/// The cached copy when it is fresh, or of any age under `--tle-offline`
+fn from_cache(cached: &Option<(TleData, std::time::Duration)>, what: &str) -> Option<TleData> {
+    let (tle, age) = cached.as_ref()?;
+    if *age < CACHE_MAX_AGE {
+        return Some(cached_copy(tle, *age));
+    }
+    if !polite::offline() {
+        return None;
+    }
+    eprintln!(
+        "📦 Using the cached elements for {} from {}h ago, as --tle-offline keeps to the cache",
+        what,
+        age.as_secs() / 3600
+    );
+    Some(cached_copy(tle, *age))
+}
+
+/// Element set for a catalog number: a fresh cache entry, else the network,
+/// else a stale cache entry with a warning
+pub async fn resolve(norad_id: u32) -> Result<TleData, CliError> {
+    let _span = phases::span(Phase::Tle, || format!("NORAD {}", norad_id));
+    let cached = read_cache(norad_id);
+    if let Some(tle) = from_cache(&cached, &format!("NORAD {}", norad_id)) {
+        return Ok(tle);
+    }
+
+    match fetch(norad_id).await {
+        Ok(tle) => {
+            if let Err(e) = write_cache(norad_id, &tle) {
+                error::report("Could not cache TLE", &e);
+            }
+            Ok(tle)
+        }
+        Err(e) => match cached {
+            Some((tle, age)) => {
+                eprintln!(
+                    "⚠️ Could not refresh TLE for NORAD {} ({}); using cached copy from {}h ago",
+                    norad_id,
+                    e.full_message(),
+                    age.as_secs() / 3600
+                );
+                Ok(cached_copy(&tle, age))
+            }
+            None => Err(e).with_context(|| format!("while fetching TLE for {}", norad_id)),
+        },
+    }
+}
+
+/// Download the current element set for a designator, checking it is for that object
+async fn fetch_designator(designator: &str) -> Result<TleData, CliError> {
+    let template = std::env::var(DESIGNATOR_SOURCE_ENV)
+        .unwrap_or_else(|_| DEFAULT_TLE_DESIGNATOR_SOURCE.to_string());
+    let url = template.replace("{designator}", designator);
+    let id = SatelliteId::IntlDesignator(designator.to_string());
+    let tle = download(&url, &id)
+        .await
+        .with_context(|| format!("while fetching TLE for {}", designator))?;
+    if !id.matches_line(&tle.tle1) {
+        return Err(CliError::validation(
+            "tle.tle1",
+            format!("{} answered with elements for another object", url),
+        ));
+    }
+    Ok(tle)
+}
+
+/// The element set the source publishes now, whatever the cache holds; the cache is
+/// left as it is
+pub async fn fetch_id(id: &SatelliteId) -> Result<TleData, CliError> {
+    let _span = phases::span(Phase::Tle, || id.to_string());
+    match id {
+        SatelliteId::NoradId(norad_id) => fetch(*norad_id)
+            .await
+            .with_context(|| format!("while fetching TLE for {}", norad_id)),
+        SatelliteId::IntlDesignator(designator) => fetch_designator(designator).await,
+        SatelliteId::Name(name) => Err(CliError::validation(
+            "satellite",
+            format!(
+                "'{}' is not a NORAD catalog number or an international designator",
+                name
+            ),
+        )),
+    }
+}
+
+/// Fetch the element set the source publishes now and cache it, whatever the cache held;
+/// returns the cached copy it replaced, if any
+pub async fn refresh(id: &SatelliteId) -> Result<(Option<TleData>, TleData), CliError> {
+    let tle = fetch_id(id).await?;
+    let norad_id = norad_id(&tle.tle1).ok_or_else(|| {
+        CliError::validation("tle.tle1", "the element set carries no catalog number")
+    })?;
+    let replaced = read_cache(norad_id).map(|(tle, _)| tle);
+    write_cache(norad_id, &tle)?;
+    Ok((replaced, tle))
+}
+
+/// Element set for an international designator, as `resolve` finds one for a catalog
+/// number (the cache is keyed by catalog number, so the answer is cached under the one
+/// it carries)
+async fn resolve_designator(designator: &str) -> Result<TleData, CliError> {
+    let _span = phases::span(Phase::Tle, || designator.to_string());
+    let cached = cached(&SatelliteId::IntlDesignator(designator.to_string()));
+    if let Some(tle) = from_cache(&cached, designator) {
+        return Ok(tle);
+    }
+    match fetch_designator(designator).await {
+        Ok(tle) => {
+            if let Some(norad_id) = norad_id(&tle.tle1) {
+                if let Err(e) = write_cache(norad_id, &tle) {
+                    error::report("Could not cache TLE", &e);
+                }
+            }
+            Ok(tle)
+        }
+        Err(e) => match cached {
+            Some((tle, age)) => {
+                eprintln!(
+                    "⚠️ Could not refresh TLE for {} ({}); using cached copy from {}h ago",
+                    designator,
+                    e.full_message(),
+                    age.as_secs() / 3600
+                );
+                Ok(cached_copy(&tle, age))
+            }
+            None => Err(e),
+        },
+    }
+}
+
+/// Element set for a catalog number or a designator; names have no element set of their
+/// own and are an error here
+pub async fn resolve_id(id: SatelliteId) -> Result<TleData, CliError> {
+    match id {
+        SatelliteId::NoradId(norad_id) => resolve(norad_id).await,
+        SatelliteId::IntlDesignator(designator) => resolve_designator(&designator).await,
+        SatelliteId::Name(name) => Err(CliError::validation(
+            "satellite",
+            format!(
+                "'{}' is not a NORAD catalog number or an international designator",
+                name
+            ),
+        )),
+    }
+}
+
+/// The iss.tle of `examples tle`
+pub const EXAMPLE_FILE: &str = "ISS (ZARYA)
+1 25544U 98067A   08264.51782528 -.00002182  00000-0 -11606-4 0  2927
+2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.72125391563537
+";
+
+/// `examples tle`
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        note: "A catalog number: the cached element set while it is fresh, else a fetched one",
+        input: Some("25544"),
+        command: "rustar-cli predict --norad-id 25544",
+    },
+    Example {
+        note: "Alpha-5 catalog numbers, past 99999",
+        input: Some("A0001"),
+        command: "rustar-cli sat set-freq newsat --norad-id A0001 --rx 437.2M",
+    },
+    Example {
+        note: "An international designator, in either form",
+        input: Some("98067A"),
+        command: "rustar-cli predict --intl-designator 98067A",
+    },
+    Example {
+        note: "A two- or three-line file, such as iss.tle above",
+        input: None,
+        command: "rustar-cli predict --tle-file iss.tle --hours 48",
+    },
+    Example {
+        note: "How far the cached copy has drifted from a fresh one",
+        input: None,
+        command: "rustar-cli tle compare cache:25544 25544",
+    },
+    Example {
+        note: "Fetch from another source; {norad} is replaced by the catalog number",
+        input: None,
+        command: "TLE_SOURCE_URL='https://tle.example.org/{norad}.txt' rustar-cli predict --norad-id 25544",
+    },
+];
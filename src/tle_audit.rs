@@ -0,0 +1,325 @@
+//! `jobs audit-tles`: the element sets of upcoming tracking jobs aged to the start of each
+//! job, since a set that is fine when the job is scheduled can be days too old by its pass.
+//! With `--refresh` the jobs found are brought up to date from the TLE source, one change
+//! per job. Made for cron: the exit status says whether any job is left too old.
+
+use crate::audit;
+use crate::client::{ApiClient, JobDTO, JobType, JobUpdate, TleData};
+use crate::clock;
+use crate::error::CliError;
+use crate::humanize;
+use crate::satellite_id::SatelliteId;
+use crate::table::{Column, Table};
+use crate::tle;
+use crate::OutputFormat;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Where a job stands once audited
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Status {
+    /// Too old at the pass, and left as it is (no `--refresh`)
+    Stale,
+    /// Sent fresh elements that are young enough at the pass
+    Refreshed,
+    /// Sent the newest elements the source has, which are still too old at the pass
+    StillStale,
+    /// No fresher elements could be had: no catalog number, or the source failed or has
+    /// nothing newer
+    Unresolved,
+    /// Fresh elements were found but the server did not take the change
+    Failed,
+}
+
+impl Status {
+    fn label(self) -> &'static str {
+        match self {
+            Status::Stale => "stale",
+            Status::Refreshed => "refreshed",
+            Status::StillStale => "still stale",
+            Status::Unresolved => "unresolved",
+            Status::Failed => "failed",
+        }
+    }
+
+    /// Whether the job still goes to its pass with elements too old
+    fn left_stale(self) -> bool {
+        self != Status::Refreshed
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Audited {
+    id: String,
+    satellite: String,
+    norad_id: Option<u32>,
+    start: DateTime<Utc>,
+    /// Epoch of the elements the job held; none when it cannot be read
+    epoch: Option<DateTime<Utc>>,
+    /// How old those elements are at the start of the job
+    age_at_pass_seconds: Option<i64>,
+    status: Status,
+    /// Epoch of the elements sent with `--refresh`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    new_epoch: Option<DateTime<Utc>>,
+    /// Why the job could not be refreshed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+}
+
+impl Audited {
+    fn age(&self) -> String {
+        self.age_at_pass_seconds.map_or_else(
+            || "unknown".to_string(),
+            |seconds| humanize::duration(Duration::seconds(seconds)),
+        )
+    }
+}
+
+/// Upcoming tracking jobs, each with its elements, soonest first
+fn upcoming(jobs: Vec<JobDTO>, now: DateTime<Utc>) -> Vec<(String, DateTime<Utc>, TleData)> {
+    let mut found: Vec<_> = jobs
+        .into_iter()
+        .filter(|job| !job.is_cancelled() && job.job.start > now)
+        .filter_map(|job| match job.job.job_type {
+            JobType::Tracking { tle } => Some((job.id?, job.job.start, tle)),
+            _ => None,
+        })
+        .collect();
+    found.sort_by_key(|(_, start, _)| *start);
+    found
+}
+
+/// `jobs audit-tles`: list the upcoming jobs whose elements will be older than `max_age`
+/// at their start, and with `refresh` send each of them fresh ones. Returns whether any
+/// job is left too old.
+pub async fn run(
+    client: &ApiClient,
+    max_age: Duration,
+    refresh: bool,
+    output: OutputFormat,
+) -> Result<bool, CliError> {
+    let now = clock::now();
+    let jobs = upcoming(client.list_jobs().await?, now);
+    let checked = jobs.len();
+    let mut audited: Vec<Audited> = jobs
+        .into_iter()
+        .filter_map(|(id, start, tle)| {
+            let epoch = tle::epoch(&tle.tle1);
+            let age = epoch.map(|epoch| start - epoch);
+            if age.is_some_and(|age| age <= max_age) {
+                return None;
+            }
+            Some(Audited {
+                id,
+                satellite: tle.tle0.trim().to_string(),
+                norad_id: tle::norad_id(&tle.tle1),
+                start,
+                epoch,
+                age_at_pass_seconds: age.map(|age| age.num_seconds()),
+                status: Status::Stale,
+                new_epoch: None,
+                reason: epoch
+                    .is_none()
+                    .then(|| "the epoch of its elements cannot be read".to_string()),
+            })
+        })
+        .collect();
+
+    if refresh && !audited.is_empty() {
+        refresh_all(client, &mut audited, max_age).await;
+    }
+
+    if output == OutputFormat::Json {
+        let document = serde_json::json!({
+            "checked": checked,
+            "max_age_at_pass_seconds": max_age.num_seconds(),
+            "jobs": audited,
+        });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&document).map_err(|e| CliError::parse("TLE audit", e))?
+        );
+    } else {
+        print(&audited, checked, max_age, refresh);
+    }
+    Ok(audited.iter().any(|job| job.status.left_stale()))
+}
+
+/// Fetch fresh elements once per satellite and send them to every job found with it
+async fn refresh_all(client: &ApiClient, audited: &mut [Audited], max_age: Duration) {
+    let mut fetched: BTreeMap<u32, Result<TleData, String>> = BTreeMap::new();
+    for norad_id in audited.iter().filter_map(|job| job.norad_id) {
+        if fetched.contains_key(&norad_id) {
+            continue;
+        }
+        let result = tle::refresh(&SatelliteId::NoradId(norad_id))
+            .await
+            .map(|(_, tle)| tle)
+            .map_err(|e| e.full_message());
+        fetched.insert(norad_id, result);
+    }
+
+    for job in audited.iter_mut() {
+        let Some(norad_id) = job.norad_id else {
+            job.status = Status::Unresolved;
+            job.reason = Some("its elements carry no catalog number to fetch by".to_string());
+            continue;
+        };
+        let fresh = match &fetched[&norad_id] {
+            Ok(fresh) => fresh,
+            Err(e) => {
+                job.status = Status::Unresolved;
+                job.reason = Some(e.clone());
+                continue;
+            }
+        };
+        let new_epoch = tle::epoch(&fresh.tle1);
+        let newer = match (new_epoch, job.epoch) {
+            (Some(new), Some(old)) => new > old,
+            (new, None) => new.is_some(),
+            (None, Some(_)) => false,
+        };
+        if !newer {
+            job.status = Status::Unresolved;
+            job.reason = Some(format!(
+                "the source has nothing newer than epoch {}",
+                job.epoch
+                    .map_or_else(|| "-".to_string(), humanize::timestamp)
+            ));
+            continue;
+        }
+        match send(client, &job.id, fresh).await {
+            Ok(()) => {
+                job.new_epoch = new_epoch;
+                job.status = match new_epoch.is_some_and(|epoch| job.start - epoch <= max_age) {
+                    true => Status::Refreshed,
+                    false => Status::StillStale,
+                };
+            }
+            Err(e) => {
+                job.status = Status::Failed;
+                job.reason = Some(e.full_message());
+            }
+        }
+    }
+}
+
+/// Give job `id` the elements `fresh`, keeping the name it was scheduled under. Sent
+/// against the version read, so a job changed meanwhile is left for the next run.
+async fn send(client: &ApiClient, id: &str, fresh: &TleData) -> Result<(), CliError> {
+    let stored = client.job_versioned(id).await?;
+    let Some(held) = stored.value.get("tle") else {
+        return Err(CliError::validation(
+            "job",
+            format!("job {} no longer tracks a satellite", id),
+        ));
+    };
+    let name = held["tle0"].as_str().unwrap_or(&fresh.tle0).to_string();
+    let tle = serde_json::json!({ "tle0": name, "tle1": fresh.tle1, "tle2": fresh.tle2 });
+    let how = client.job_update(id).await?;
+    let body = match how {
+        JobUpdate::MergePatch => serde_json::json!({ "tle": tle }),
+        JobUpdate::Replace => {
+            let mut whole = stored.value.clone();
+            whole["tle"] = tle;
+            whole
+        }
+    };
+    let previous = serde_json::json!({ "tle": held.clone() });
+    match client
+        .update_job(id, how, &body, stored.version.as_ref())
+        .await
+    {
+        Ok(_) => {
+            audit::job(id);
+            audit::previous(id, previous);
+            Ok(())
+        }
+        Err(e) if e.is_stale() => Err(CliError::validation(
+            "job",
+            format!("job {} was changed on the server meanwhile; run again", id),
+        )),
+        Err(e) => Err(e),
+    }
+}
+
+fn shown(epoch: Option<DateTime<Utc>>) -> String {
+    epoch.map_or_else(
+        || "-".to_string(),
+        |epoch| epoch.format("%Y-%m-%d %H:%M").to_string(),
+    )
+}
+
+fn print(audited: &[Audited], checked: usize, max_age: Duration, refresh: bool) {
+    let limit = humanize::duration(max_age);
+    if audited.is_empty() {
+        println!(
+            "✅ The elements of all {} upcoming tracking job(s) are within {} at pass time",
+            checked, limit
+        );
+        return;
+    }
+    let mut columns = vec![
+        Column::new("ID"),
+        Column::new("SATELLITE"),
+        Column::new("START"),
+        Column::new("EPOCH"),
+        Column::new("AGE AT PASS"),
+    ];
+    if refresh {
+        columns.push(Column::new("NEW EPOCH"));
+        columns.push(Column::new("STATUS"));
+    }
+    let mut table = Table::new(columns);
+    for job in audited {
+        let mut row = vec![
+            job.id.clone(),
+            job.satellite.clone(),
+            job.start.format("%Y-%m-%d %H:%M").to_string(),
+            shown(job.epoch),
+            job.age(),
+        ];
+        if refresh {
+            row.push(shown(job.new_epoch));
+            row.push(job.status.label().to_string());
+        }
+        table.row(row);
+    }
+    table.print();
+
+    let unresolved: Vec<&Audited> = audited
+        .iter()
+        .filter(|job| matches!(job.status, Status::Unresolved | Status::Failed))
+        .collect();
+    if !unresolved.is_empty() {
+        println!("\n⚠️ Could not refresh:");
+        for job in &unresolved {
+            println!(
+                "  - job {} ({}): {}",
+                job.id,
+                job.satellite,
+                job.reason.as_deref().unwrap_or("no reason given")
+            );
+        }
+    }
+    let left = audited.iter().filter(|job| job.status.left_stale()).count();
+    match refresh {
+        false => println!(
+            "\n🕰️ {} of {} upcoming tracking job(s) will fly elements older than {}; \
+             --refresh sends fresh ones",
+            audited.len(),
+            checked,
+            limit
+        ),
+        true => println!(
+            "\n🛰️ {} of {} job(s) refreshed; {} left with elements older than {}",
+            audited.len() - left,
+            audited.len(),
+            left,
+            limit
+        ),
+    }
+}
@@ -0,0 +1,245 @@
+use crate::client::TleData;
+use crate::error::{CliError, ResultExt};
+use crate::humanize;
+use crate::orbit::{Satellite, Separation};
+use crate::satellite_id::SatelliteId;
+use crate::table::{Column, Table};
+use crate::tle;
+use crate::OutputFormat;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Where one side of a comparison comes from
+#[derive(Debug, Clone)]
+pub enum Source {
+    Stdin,
+    /// The cached copy, however old
+    Cache(SatelliteId),
+    /// What the TLE source publishes now; the cache is not touched
+    Fetch(SatelliteId),
+    File(PathBuf),
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Source::Stdin => write!(f, "stdin"),
+            Source::Cache(id) => write!(f, "the cache ({})", id),
+            Source::Fetch(id) => write!(f, "the TLE source ({})", id),
+            Source::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// clap value parser: "-" for stdin, "cache:ID", an existing file, or a catalog number
+/// or designator to fetch
+pub fn parse_source(input: &str) -> Result<Source, String> {
+    let catalog_id = |id: &str| match SatelliteId::detect(id) {
+        SatelliteId::Name(_) => None,
+        id => Some(id),
+    };
+    if input == "-" {
+        return Ok(Source::Stdin);
+    }
+    if let Some(id) = input.strip_prefix("cache:") {
+        return catalog_id(id).map(Source::Cache).ok_or_else(|| {
+            format!(
+                "'{}' is neither a NORAD catalog number nor an international designator",
+                id
+            )
+        });
+    }
+    let path = PathBuf::from(input);
+    if path.is_file() {
+        return Ok(Source::File(path));
+    }
+    catalog_id(input).map(Source::Fetch).ok_or_else(|| {
+        format!(
+            "'{}' is not a file, and not a NORAD catalog number or designator to fetch",
+            input
+        )
+    })
+}
+
+async fn load(source: &Source) -> Result<TleData, CliError> {
+    match source {
+        Source::Stdin => {
+            let text = std::io::read_to_string(std::io::stdin())
+                .context("reading an element set from stdin")?;
+            tle::parse_text(&text, "stdin").map_err(|reason| CliError::parse("stdin", reason))
+        }
+        Source::Cache(id) => tle::cached(id).map(|(tle, _)| tle).ok_or_else(|| {
+            CliError::validation(
+                "tle",
+                format!(
+                    "no cached element set for {}; rustar-cli predict --norad-id fetches one",
+                    id
+                ),
+            )
+        }),
+        Source::Fetch(id) => tle::fetch_id(id).await,
+        Source::File(path) => tle::read_file(path),
+    }
+}
+
+/// One mean element in both sets, and how far it moved
+#[derive(Debug, Serialize)]
+struct Change {
+    field: &'static str,
+    a: f64,
+    b: f64,
+    /// B minus A, in the field's unit
+    delta: f64,
+    unit: &'static str,
+}
+
+fn changes(a: &Satellite, b: &Satellite) -> Vec<Change> {
+    let (ea, eb) = (a.elements(), b.elements());
+    let change = |field, a: f64, b: f64, unit| Change {
+        field,
+        a,
+        b,
+        delta: b - a,
+        unit,
+    };
+    vec![
+        change("mean motion", ea.mean_motion, eb.mean_motion, "rev/day"),
+        change("inclination", ea.inclination, eb.inclination, "°"),
+        change(
+            "right ascension",
+            ea.right_ascension,
+            eb.right_ascension,
+            "°",
+        ),
+        change("eccentricity", ea.eccentricity, eb.eccentricity, ""),
+        change("B*", ea.drag_term, eb.drag_term, "1/earth radii"),
+    ]
+}
+
+/// "+0.000123", with enough digits for the field's usual size
+fn signed(value: f64, field: &str) -> String {
+    match field {
+        "eccentricity" => format!("{:+.7}", value),
+        "B*" => format!("{:+.4e}", value),
+        "mean motion" => format!("{:+.8}", value),
+        _ => format!("{:+.4}", value),
+    }
+}
+
+fn plain(value: f64, field: &str) -> String {
+    signed(value, field).trim_start_matches('+').to_string()
+}
+
+/// `tle compare`: how far apart two element sets are, field by field and in position at `at`
+pub async fn run(
+    a: Source,
+    b: Source,
+    at: DateTime<Utc>,
+    force: bool,
+    output: OutputFormat,
+) -> Result<(), CliError> {
+    if matches!((&a, &b), (Source::Stdin, Source::Stdin)) {
+        return Err(CliError::validation(
+            "input",
+            "only one of the element sets can come from stdin",
+        ));
+    }
+    let (tle_a, tle_b) = (load(&a).await?, load(&b).await?);
+    let (sat_a, sat_b) = (Satellite::from_tle(&tle_a)?, Satellite::from_tle(&tle_b)?);
+    let (norad_a, norad_b) = (sat_a.elements().norad_id, sat_b.elements().norad_id);
+    if norad_a != norad_b && !force {
+        return Err(CliError::validation(
+            "norad_id",
+            format!(
+                "A is NORAD {} and B is NORAD {}; pass --force to compare different objects",
+                norad_a, norad_b
+            ),
+        ));
+    }
+    let changes = changes(&sat_a, &sat_b);
+    let separation = sat_a.separation(&sat_b, at)?;
+
+    if output == OutputFormat::Json {
+        let side = |source: &Source, tle: &TleData, sat: &Satellite| {
+            serde_json::json!({
+                "source": source.to_string(),
+                "tle": tle,
+                "epoch": sat.epoch(),
+            })
+        };
+        let document = serde_json::json!({
+            "a": side(&a, &tle_a, &sat_a),
+            "b": side(&b, &tle_b, &sat_b),
+            "epoch_delta_s": (sat_b.epoch() - sat_a.epoch()).num_milliseconds() as f64 / 1000.0,
+            "changes": changes,
+            "separation": separation,
+        });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&document)
+                .map_err(|e| CliError::parse("comparison", e))?
+        );
+        return Ok(());
+    }
+
+    for (label, source, tle, sat) in [("A", &a, &tle_a, &sat_a), ("B", &b, &tle_b, &sat_b)] {
+        println!(
+            "🛰️ {}: {} from {}, epoch {}",
+            label,
+            tle.tle0,
+            source,
+            sat.epoch().format("%Y-%m-%d %H:%M:%S UTC")
+        );
+    }
+    let mut table = Table::new(vec![
+        Column::new("FIELD"),
+        Column::new("A"),
+        Column::new("B"),
+        Column::new("CHANGE"),
+    ]);
+    let epoch_delta = sat_b.epoch() - sat_a.epoch();
+    table.row(vec![
+        "epoch".to_string(),
+        sat_a.epoch().format("%Y-%m-%d %H:%M:%S").to_string(),
+        sat_b.epoch().format("%Y-%m-%d %H:%M:%S").to_string(),
+        format!(
+            "{}{}",
+            if epoch_delta < chrono::Duration::zero() {
+                "-"
+            } else {
+                "+"
+            },
+            humanize::duration(epoch_delta)
+        ),
+    ]);
+    for change in &changes {
+        table.row(vec![
+            change.field.to_string(),
+            plain(change.a, change.field),
+            plain(change.b, change.field),
+            match change.unit {
+                "" | "°" => format!("{}{}", signed(change.delta, change.field), change.unit),
+                unit => format!("{} {}", signed(change.delta, change.field), unit),
+            },
+        ]);
+    }
+    table.print();
+    print_separation(&separation);
+    Ok(())
+}
+
+fn print_separation(separation: &Separation) {
+    println!(
+        "\n📍 At {} the two put the satellite {:.1} km apart:",
+        separation.at.format("%Y-%m-%d %H:%M UTC"),
+        separation.total_km
+    );
+    println!(
+        "   {:+.1} km along track ({:+.1} s), {:+.1} km radial, {:+.1} km cross track",
+        separation.along_track_km,
+        separation.along_track_s,
+        separation.radial_km,
+        separation.cross_track_km
+    );
+}
@@ -0,0 +1,216 @@
+use crate::client::TleData;
+use crate::error::{CliError, ResultExt};
+use crate::satellite_id::SatelliteId;
+use crate::satellites::{self, AliasBook};
+use crate::table::{Column, Table};
+use crate::tle;
+use crate::OutputFormat;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+/// Element sets fetched at once; each host also has its rate limit (see `polite`)
+const IN_FLIGHT: usize = 4;
+
+/// One satellite to refresh: what it is fetched by, and the aliases naming it
+struct Target {
+    id: SatelliteId,
+    aliases: Vec<String>,
+}
+
+impl Target {
+    fn label(&self) -> String {
+        match self.aliases.is_empty() {
+            true => self.id.to_string(),
+            false => self.aliases.join(", "),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Refreshed {
+    satellite: String,
+    id: String,
+    name: String,
+    /// Epoch of the element set the cache held before, when it held one
+    old_epoch: Option<DateTime<Utc>>,
+    new_epoch: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize)]
+struct Failed {
+    satellite: String,
+    id: String,
+    error: String,
+}
+
+/// Every alias of the book with an element set to fetch, or the members of `group`;
+/// aliases that share a satellite are fetched once. Aliases without a catalog number or
+/// designator are reported and left out.
+fn targets(book: &AliasBook, group: Option<&str>) -> Result<Vec<Target>, CliError> {
+    let aliases_of = |id: &SatelliteId| {
+        book.iter()
+            .filter(|(_, preset)| preset.id().as_ref() == Some(id))
+            .map(|(name, _)| name.to_string())
+            .collect::<Vec<_>>()
+    };
+    let ids = match group {
+        Some(group) => book.expand(group)?,
+        None => {
+            let mut ids: Vec<SatelliteId> = Vec::new();
+            for (name, preset) in book.iter() {
+                match preset.id() {
+                    Some(id) if !ids.contains(&id) => ids.push(id),
+                    Some(_) => {}
+                    None => eprintln!(
+                        "⏭️ {} has no NORAD ID or designator to fetch by; skipped",
+                        name
+                    ),
+                }
+            }
+            ids
+        }
+    };
+    Ok(ids
+        .into_iter()
+        .map(|id| Target {
+            aliases: aliases_of(&id),
+            id,
+        })
+        .collect())
+}
+
+/// The cached element set a fetch replaced, if any, and the fetched one
+type Fetched = Result<(Option<TleData>, TleData), CliError>;
+
+/// Fetch every target, `IN_FLIGHT` at a time, in the order given
+async fn fetch_all(targets: &[Target]) -> Vec<Fetched> {
+    let permits = Arc::new(Semaphore::new(IN_FLIGHT));
+    let mut tasks = JoinSet::new();
+    for (index, target) in targets.iter().enumerate() {
+        let permits = Arc::clone(&permits);
+        let id = target.id.clone();
+        tasks.spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            (index, tle::refresh(&id).await)
+        });
+    }
+    let mut results: Vec<Option<Fetched>> = targets.iter().map(|_| None).collect();
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok((index, result)) => results[index] = Some(result),
+            // Nothing aborts the tasks, so only a panic ends one early
+            Err(e) => std::panic::resume_unwind(e.into_panic()),
+        }
+    }
+    results.into_iter().flatten().collect()
+}
+
+fn epoch(tle: &TleData) -> Option<DateTime<Utc>> {
+    tle::epoch(&tle.tle1)
+}
+
+fn shown(epoch: Option<DateTime<Utc>>) -> String {
+    epoch.map_or_else(
+        || "-".to_string(),
+        |epoch| epoch.format("%Y-%m-%d %H:%M").to_string(),
+    )
+}
+
+/// `tle refresh-all`: fetch a fresh element set for every alias, or every member of
+/// `group`, into the cache. Fails only when no fetch succeeded.
+pub async fn run(group: Option<&str>, output: OutputFormat) -> Result<(), CliError> {
+    let book = AliasBook::load()?;
+    let targets = targets(&book, group)?;
+    if targets.is_empty() {
+        if output == OutputFormat::Json {
+            println!("{}", serde_json::json!({ "refreshed": [], "failed": [] }));
+        } else {
+            println!(
+                "📭 No satellites to refresh in {}",
+                satellites::book_path().display()
+            );
+        }
+        return Ok(());
+    }
+    if output != OutputFormat::Json {
+        eprintln!(
+            "🔄 Refreshing {} element set(s), {} at a time",
+            targets.len(),
+            IN_FLIGHT
+        );
+    }
+
+    let mut refreshed = Vec::new();
+    let mut failed = Vec::new();
+    let mut first_error = None;
+    for (target, result) in targets.iter().zip(fetch_all(&targets).await) {
+        match result {
+            Ok((replaced, tle)) => refreshed.push(Refreshed {
+                satellite: target.label(),
+                id: target.id.to_string(),
+                name: tle.tle0.clone(),
+                old_epoch: replaced.as_ref().and_then(epoch),
+                new_epoch: epoch(&tle),
+            }),
+            Err(e) => {
+                failed.push(Failed {
+                    satellite: target.label(),
+                    id: target.id.to_string(),
+                    error: e.full_message(),
+                });
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+
+    if output == OutputFormat::Json {
+        let document = serde_json::json!({ "refreshed": refreshed, "failed": failed });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&document)
+                .map_err(|e| CliError::parse("refresh report", e))?
+        );
+    } else {
+        if !refreshed.is_empty() {
+            let mut table = Table::new(vec![
+                Column::new("SATELLITE"),
+                Column::new("ID"),
+                Column::new("OLD EPOCH"),
+                Column::new("NEW EPOCH"),
+            ]);
+            for row in &refreshed {
+                table.row(vec![
+                    row.satellite.clone(),
+                    row.id.clone(),
+                    shown(row.old_epoch),
+                    shown(row.new_epoch),
+                ]);
+            }
+            table.print();
+        }
+        if !failed.is_empty() {
+            println!("\n⚠️ Could not refresh:");
+            for row in &failed {
+                println!("  - {} ({}): {}", row.satellite, row.id, row.error);
+            }
+        }
+        println!(
+            "\n🛰️ {} of {} element set(s) refreshed",
+            refreshed.len(),
+            targets.len()
+        );
+    }
+
+    match (refreshed.is_empty(), first_error) {
+        (true, Some(e)) => Err(e).with_context(|| {
+            format!(
+                "none of the {} element set(s) could be fetched",
+                targets.len()
+            )
+        }),
+        _ => Ok(()),
+    }
+}
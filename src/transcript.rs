@@ -0,0 +1,375 @@
+//! `--transcript FILE`: the whole session as one JSON document for a support request —
+//! the prompts and answers, warnings and errors, the jobs sent, every API request with
+//! its request ID, and how it ended. Nothing here may fail or stop the command.
+
+use crate::audit;
+use crate::clock;
+use crate::error::{CliError, ResultExt};
+use crate::storage;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Layout of the document, under the usual `version` key
+const VERSION: u32 = 1;
+
+/// Events kept before the rest are only counted, so a long batch cannot fill the disk
+const MAX_EVENTS: usize = 10_000;
+
+/// What happened, in order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Event {
+    /// A question on the terminal and what was typed or picked
+    Prompt {
+        at: DateTime<Utc>,
+        prompt: String,
+        answer: String,
+    },
+    Warning {
+        at: DateTime<Utc>,
+        text: String,
+    },
+    Error {
+        at: DateTime<Utc>,
+        text: String,
+    },
+    /// A job, or a change to one, as it was sent to the server
+    Job {
+        at: DateTime<Utc>,
+        job: serde_json::Value,
+    },
+    /// One API request; no status when no response arrived
+    Http {
+        at: DateTime<Utc>,
+        method: String,
+        path: String,
+        status: Option<u16>,
+        request_id: Option<String>,
+        elapsed_ms: u64,
+    },
+}
+
+/// How the session ended
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Outcome {
+    pub exit_code: i32,
+    /// "success", "failed", "interrupted", or the panic message
+    pub result: String,
+}
+
+/// The document written to the transcript file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Transcript {
+    pub version: u32,
+    pub cli_version: String,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    /// The command line, with secret-looking values redacted
+    pub arguments: Vec<String>,
+    pub profile: Option<String>,
+    pub events: Vec<Event>,
+    /// Events past the cap, left out
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub dropped_events: usize,
+    pub outcome: Option<Outcome>,
+    /// False until the session has ended and the whole document was written; a file
+    /// still saying false was cut short
+    pub complete: bool,
+}
+
+fn is_zero(count: &usize) -> bool {
+    *count == 0
+}
+
+struct Recording {
+    path: PathBuf,
+    transcript: Transcript,
+}
+
+/// The running session's transcript, when one was asked for
+static CURRENT: Mutex<Option<Recording>> = Mutex::new(None);
+/// Set by commands that stop by themselves on Ctrl-C and then end as usual
+static STOPS_ON_INTERRUPT: AtomicBool = AtomicBool::new(false);
+
+fn current() -> std::sync::MutexGuard<'static, Option<Recording>> {
+    CURRENT.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// A file name for the profile's `transcript_dir`, unique to this invocation
+pub fn file_name() -> String {
+    format!(
+        "rustar-cli-{}-{}.json",
+        clock::now().format("%Y%m%dT%H%M%SZ"),
+        std::process::id()
+    )
+}
+
+/// Start recording to `path`; the first call wins. The file is written at once, marked
+/// incomplete, so a session that dies without finishing still leaves a trace.
+pub fn start(path: &Path) {
+    let mut current = current();
+    if current.is_some() {
+        return;
+    }
+    let recording = Recording {
+        path: path.to_path_buf(),
+        transcript: Transcript {
+            version: VERSION,
+            cli_version: env!("CARGO_PKG_VERSION").to_string(),
+            started_at: clock::now(),
+            finished_at: None,
+            arguments: audit::redact(std::env::args().skip(1)),
+            profile: None,
+            events: Vec::new(),
+            dropped_events: 0,
+            outcome: None,
+            complete: false,
+        },
+    };
+    if let Err(e) = write(&recording) {
+        eprintln!("⚠️ Not recording a transcript: {}", e.full_message());
+        return;
+    }
+    *current = Some(recording);
+    drop(current);
+
+    // A panic still gets its transcript, then the usual message
+    let report = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        finish_with(101, info.to_string());
+        report(info);
+    }));
+    // Ctrl-C outside a prompt ends the process; write the transcript first
+    tokio::spawn(async {
+        while tokio::signal::ctrl_c().await.is_ok() {
+            if STOPS_ON_INTERRUPT.load(Ordering::Relaxed) {
+                warning("Interrupted with Ctrl-C");
+                continue;
+            }
+            finish_with(130, "interrupted".to_string());
+            std::process::exit(130);
+        }
+    });
+}
+
+/// The command handles Ctrl-C itself, so it is noted instead of ending the process
+pub fn stops_on_interrupt() {
+    STOPS_ON_INTERRUPT.store(true, Ordering::Relaxed);
+}
+
+/// Whether a transcript is being recorded
+pub fn recording() -> bool {
+    current().is_some()
+}
+
+/// The profile the session runs with
+pub fn use_profile(name: &str) {
+    if let Some(recording) = current().as_mut() {
+        recording.transcript.profile = Some(name.to_string());
+    }
+}
+
+fn record(event: impl FnOnce(DateTime<Utc>) -> Event) {
+    if let Some(recording) = current().as_mut() {
+        let transcript = &mut recording.transcript;
+        if transcript.events.len() < MAX_EVENTS {
+            transcript.events.push(event(clock::now()));
+        } else {
+            transcript.dropped_events += 1;
+        }
+    }
+}
+
+/// The answer to `prompt`; answers to questions about secrets are not written down
+pub fn answered(prompt: &str, answer: &dyn Display) {
+    let answer = match audit::is_secret_name(prompt) {
+        true => audit::REDACTED.to_string(),
+        false => answer.to_string(),
+    };
+    record(|at| Event::Prompt {
+        at,
+        prompt: prompt.trim().to_string(),
+        answer,
+    });
+}
+
+/// A warning as it was shown, without its emoji
+pub fn warning(text: impl Display) {
+    record(|at| Event::Warning {
+        at,
+        text: text.to_string(),
+    });
+}
+
+pub fn error(text: &str) {
+    record(|at| Event::Error {
+        at,
+        text: text.to_string(),
+    });
+}
+
+/// A job about to be sent
+pub fn job(job: &impl Serialize) {
+    if !recording() {
+        return;
+    }
+    if let Ok(job) = serde_json::to_value(job) {
+        record(|at| Event::Job { at, job });
+    }
+}
+
+pub fn http(
+    method: &reqwest::Method,
+    path: &str,
+    status: Option<reqwest::StatusCode>,
+    request_id: Option<&str>,
+    elapsed: Duration,
+) {
+    record(|at| Event::Http {
+        at,
+        method: method.to_string(),
+        path: path.to_string(),
+        status: status.map(|s| s.as_u16()),
+        request_id: request_id.map(str::to_string),
+        elapsed_ms: u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX),
+    });
+}
+
+/// Inquire answers recorded as they come back, or the prompt being given up
+pub trait Recorded: Sized {
+    fn recorded(self, prompt: &str) -> Self;
+}
+
+impl<T: Display> Recorded for Result<T, inquire::InquireError> {
+    fn recorded(self, prompt: &str) -> Self {
+        match &self {
+            Ok(answer) => answered(prompt, answer),
+            Err(e) => answered(prompt, &format!("<{}>", e)),
+        }
+        self
+    }
+}
+
+fn write(recording: &Recording) -> Result<(), CliError> {
+    let mut contents = serde_json::to_string_pretty(&recording.transcript)
+        .map_err(|e| CliError::parse("the transcript", e))?;
+    contents.push('\n');
+    storage::write_atomic(&recording.path, contents)
+}
+
+fn finish_with(code: i32, result: String) {
+    // A panic while the lock is held must not deadlock the hook
+    let Ok(mut current) = CURRENT.try_lock() else {
+        return;
+    };
+    let Some(mut recording) = current.take() else {
+        return;
+    };
+    recording.transcript.finished_at = Some(clock::now());
+    recording.transcript.outcome = Some(Outcome {
+        exit_code: code,
+        result,
+    });
+    recording.transcript.complete = true;
+    match write(&recording) {
+        Ok(()) => {}
+        Err(e) => eprintln!(
+            "⚠️ The transcript {} could not be completed and stays marked incomplete: {}",
+            recording.path.display(),
+            e.full_message()
+        ),
+    }
+}
+
+/// Write the transcript with the session's exit status
+pub fn finish(code: i32) {
+    let result = match code {
+        crate::error::EXIT_SUCCESS => "success",
+        130 => "interrupted",
+        _ => "failed",
+    };
+    finish_with(code, result.to_string());
+}
+
+/// Finishes the transcript when `main` returns without calling `exit`
+pub struct Guard;
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        finish(crate::error::EXIT_SUCCESS);
+    }
+}
+
+/// A transcript file, for `doctor --attach-transcript`
+pub fn read(path: &Path) -> Result<Transcript, CliError> {
+    let text =
+        std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    serde_json::from_str(&text)
+        .map_err(|e| CliError::parse(format!("transcript {}", path.display()), e))
+}
+
+/// The transcript for a person to go through, one line per event
+pub fn print(path: &Path, transcript: &Transcript) {
+    println!("\n📼 Transcript {}", path.display());
+    println!(
+        "  rustar-cli {} {}, profile {}, started {}",
+        transcript.cli_version,
+        transcript.arguments.join(" "),
+        transcript.profile.as_deref().unwrap_or("unknown"),
+        transcript.started_at.format("%Y-%m-%d %H:%M:%S UTC")
+    );
+    for event in &transcript.events {
+        let (at, line) = match event {
+            Event::Prompt { at, prompt, answer } => (at, format!("❓ {} {}", prompt, answer)),
+            Event::Warning { at, text } => (at, format!("⚠️ {}", text)),
+            Event::Error { at, text } => (at, format!("❌ {}", text)),
+            Event::Job { at, job } => (at, format!("📦 Job sent: {}", job)),
+            Event::Http {
+                at,
+                method,
+                path,
+                status,
+                request_id,
+                elapsed_ms,
+            } => {
+                let status = match status {
+                    Some(status) => status.to_string(),
+                    None => "no response".to_string(),
+                };
+                let id = match request_id {
+                    Some(id) => format!(", request id {}", id),
+                    None => String::new(),
+                };
+                (
+                    at,
+                    format!(
+                        "🌐 {} {} → {} in {} ms{}",
+                        method, path, status, elapsed_ms, id
+                    ),
+                )
+            }
+        };
+        println!("  {}  {}", at.format("%H:%M:%S%.3f"), line);
+    }
+    if transcript.dropped_events > 0 {
+        println!(
+            "  … {} more event(s) were left out",
+            transcript.dropped_events
+        );
+    }
+    match (&transcript.outcome, transcript.complete) {
+        (Some(outcome), true) => println!(
+            "  🏁 {} (exit status {})",
+            outcome.result, outcome.exit_code
+        ),
+        _ => println!(
+            "  ⚠️ Incomplete: the session ended before its transcript could be finished \
+             (killed, or the file could not be written)"
+        ),
+    }
+}
@@ -0,0 +1,124 @@
+use crate::error::{CliError, ResultExt};
+use crate::frequency::Frequency;
+use crate::validation::Problem;
+use serde::{Deserialize, Serialize};
+
+/// Passband edges of a linear transponder, in Hz. Each point of the downlink band
+/// pairs with one point of the uplink band; an inverting transponder maps the
+/// bottom of one onto the top of the other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Passband {
+    pub downlink_low: Frequency,
+    pub downlink_high: Frequency,
+    pub uplink_low: Frequency,
+    pub uplink_high: Frequency,
+    #[serde(default)]
+    pub inverting: bool,
+}
+
+/// "145.9M-145.93M": a band's edges, lowest first
+pub fn parse_band(input: &str) -> Result<(Frequency, Frequency), CliError> {
+    let trimmed = input.trim();
+    let (low, high) = trimmed.split_once('-').ok_or_else(|| {
+        CliError::parse(
+            format!("band '{}'", trimmed),
+            "expected low-high, e.g. 145.9M-145.93M",
+        )
+    })?;
+    let low = Frequency::parse(low).with_context(|| format!("reading band '{}'", trimmed))?;
+    let high = Frequency::parse(high).with_context(|| format!("reading band '{}'", trimmed))?;
+    if low >= high {
+        return Err(CliError::validation(
+            "band",
+            format!("'{}' must go from the lower edge to the higher", trimmed),
+        ));
+    }
+    Ok((low, high))
+}
+
+impl Passband {
+    pub fn new(
+        downlink: (Frequency, Frequency),
+        uplink: (Frequency, Frequency),
+        inverting: bool,
+    ) -> Self {
+        Self {
+            downlink_low: downlink.0,
+            downlink_high: downlink.1,
+            uplink_low: uplink.0,
+            uplink_high: uplink.1,
+            inverting,
+        }
+    }
+
+    pub fn downlink_centre(&self) -> Frequency {
+        self.downlink_low.midpoint(self.downlink_high)
+    }
+
+    /// The uplink paired with `downlink`: the same offset into the uplink band,
+    /// counted from the top edge when the transponder inverts
+    pub fn uplink_for(&self, downlink: Frequency) -> Result<Frequency, Problem> {
+        if !(self.downlink_low..=self.downlink_high).contains(&downlink) {
+            return Err(Problem::new(
+                "rx_frequency",
+                format!(
+                    "{} is outside the transponder passband {}–{}",
+                    downlink, self.downlink_low, self.downlink_high
+                ),
+            ));
+        }
+        let offset = downlink
+            .checked_sub(self.downlink_low)
+            .expect("the downlink is inside the passband");
+        let uplink = if self.inverting {
+            self.uplink_high.checked_sub(offset)
+        } else {
+            self.uplink_low.checked_add(offset)
+        };
+        uplink.ok_or_else(|| {
+            Problem::new(
+                "tx_frequency",
+                format!(
+                    "{} has no uplink: the uplink band {}–{} is too narrow",
+                    downlink, self.uplink_low, self.uplink_high
+                ),
+            )
+        })
+    }
+
+    /// A downlink entered as a frequency, or as "+10k" / "-5k" from the passband centre
+    pub fn parse_downlink(&self, input: &str) -> Result<Frequency, CliError> {
+        let trimmed = input.trim();
+        let offset = |rest: &str| {
+            Frequency::parse(rest).with_context(|| format!("reading offset '{}'", trimmed))
+        };
+        let out_of_range = || {
+            CliError::validation(
+                "downlink",
+                format!("'{}' takes the downlink out of range", trimmed),
+            )
+        };
+        let centre = self.downlink_centre();
+        match trimmed.as_bytes().first() {
+            Some(b'+') => centre
+                .checked_add(offset(&trimmed[1..])?)
+                .ok_or_else(out_of_range),
+            Some(b'-') => centre
+                .checked_sub(offset(&trimmed[1..])?)
+                .ok_or_else(out_of_range),
+            _ => Frequency::parse(trimmed),
+        }
+    }
+
+    /// "downlink 145.9–145.93 MHz, uplink 435.1–435.13 MHz, inverting"
+    pub fn describe(&self) -> String {
+        format!(
+            "downlink {}–{}, uplink {}–{}{}",
+            self.downlink_low,
+            self.downlink_high,
+            self.uplink_low,
+            self.uplink_high,
+            if self.inverting { ", inverting" } else { "" }
+        )
+    }
+}
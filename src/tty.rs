@@ -0,0 +1,54 @@
+use crate::error::CliError;
+use crate::transcript::Recorded;
+use std::io::IsTerminal;
+
+/// Whether prompts can run: answers come from stdin and inquire draws on stderr
+pub fn interactive() -> bool {
+    std::io::stdin().is_terminal() && std::io::stderr().is_terminal()
+}
+
+/// Fail before `command` prompts when there is no terminal, naming what to pass instead
+pub fn require(command: &str, instead: &[&str]) -> Result<(), CliError> {
+    if interactive() {
+        return Ok(());
+    }
+    Err(CliError::NoTerminal {
+        command: command.to_string(),
+        instead: match instead {
+            [] => None,
+            [only] => Some(format!("to run it without prompts, use {}", only)),
+            [rest @ .., last] => Some(format!(
+                "to run it without prompts, use {} or {}",
+                rest.join(", "),
+                last
+            )),
+        },
+    })
+}
+
+/// Ask a yes/no question, defaulting to no; `--yes` answers it, and is required without a terminal
+pub fn confirm(command: &str, question: &str, yes: bool) -> Result<bool, CliError> {
+    if yes {
+        return Ok(true);
+    }
+    require(command, &["--yes"])?;
+    Ok(inquire::Confirm::new(question)
+        .with_default(false)
+        .prompt()
+        .recorded(question)?)
+}
+
+/// Raw terminal mode for reading single keys, restored when dropped
+pub struct RawMode;
+
+impl RawMode {
+    pub fn enable() -> Option<Self> {
+        crossterm::terminal::enable_raw_mode().ok().map(|_| RawMode)
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = crossterm::terminal::disable_raw_mode();
+    }
+}
@@ -0,0 +1,236 @@
+use crate::audit::{self, Entry};
+use crate::client::{ApiClient, JobDTO};
+use crate::clock;
+use crate::error::{self, CliError};
+use crate::humanize;
+use crate::jobs;
+use crate::reproducible;
+use crate::tty;
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+
+/// How far back `undo` reaches when the profile does not say
+pub const DEFAULT_WINDOW_MINUTES: u64 = 10;
+/// Commands whose jobs undo cancels
+const CREATES: &[&str] = &[
+    "add-job",
+    "import",
+    "import-handoff",
+    "predict --pick",
+    "quick",
+    "queue flush",
+    "campaign clone",
+    "jobs sweep",
+];
+
+/// What `reschedule` recorded about the window it replaced
+#[derive(Deserialize)]
+struct Window {
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+}
+
+fn not_possible(reason: String) -> CliError {
+    CliError::Conflict {
+        message: Some(format!("nothing to undo: {}", reason)),
+        hint: None,
+    }
+}
+
+/// "add-job at 14:02:11 UTC (3m ago)"; without the age under `--reproducible`
+fn describe(entry: &Entry, now: DateTime<Utc>) -> String {
+    let at = humanize::timestamp(entry.timestamp);
+    match reproducible::enabled() {
+        true => format!("{} at {}", entry.command, at),
+        false => format!(
+            "{} at {} ({} ago)",
+            entry.command,
+            at,
+            humanize::duration(now - entry.timestamp)
+        ),
+    }
+}
+
+/// "2025-10-02 12:00:00 UTC – 12:15:00 UTC"
+fn span(start: DateTime<Utc>, end: DateTime<Utc>) -> String {
+    format!(
+        "{} – {}",
+        humanize::timestamp(start),
+        end.format("%H:%M:%S UTC")
+    )
+}
+
+/// The most recent mutating action by this user on this profile. Failed attempts and
+/// undos that changed no job are passed over: there is nothing of theirs to reverse.
+fn latest(profile: &str) -> Result<Entry, CliError> {
+    let user = audit::username();
+    let idle = |entry: &Entry| {
+        entry.job_ids.is_empty() && (entry.outcome != "success" || entry.command == "undo")
+    };
+    audit::read()?
+        .into_iter()
+        .rev()
+        .find(|entry| entry.user == user && entry.profile == profile && !idle(entry))
+        .ok_or_else(|| {
+            not_possible(format!(
+                "the audit log has no actions by {} on profile {}",
+                user, profile
+            ))
+        })
+}
+
+/// A job as the server has it now, or why undo cannot touch it
+async fn current(client: &ApiClient, id: &str, now: DateTime<Utc>) -> Result<JobDTO, String> {
+    let value = match client.job_json(id).await {
+        Ok(value) if !value.is_null() => value,
+        Ok(_) => return Err(format!("job {} is gone from the server", id)),
+        Err(e) if e.status() == Some(404) => {
+            return Err(format!("job {} is gone from the server", id))
+        }
+        Err(e) => {
+            return Err(format!(
+                "job {} could not be read: {}",
+                id,
+                e.full_message()
+            ))
+        }
+    };
+    let job: JobDTO = serde_json::from_value(value)
+        .map_err(|e| format!("job {} could not be decoded: {}", id, e))?;
+    if job.job.start <= now {
+        return Err(format!(
+            "job {} ({}) started {} ago",
+            id,
+            job.job.label(),
+            humanize::duration(now - job.job.start)
+        ));
+    }
+    Ok(job)
+}
+
+/// Reverse the most recent submission or reschedule, if it is recent enough and its
+/// jobs have not started. Returns the number of jobs that could not be restored.
+pub async fn run(
+    client: &ApiClient,
+    profile: &str,
+    window_minutes: u64,
+    yes: bool,
+) -> Result<usize, CliError> {
+    let now = clock::now();
+    let entry = latest(profile)?;
+    let what = describe(&entry, now);
+
+    if entry.command == "undo" {
+        return Err(not_possible(format!(
+            "the last action was already an undo, {}",
+            what
+        )));
+    }
+    let reschedule = matches!(entry.command.as_str(), "reschedule" | "rebase-job");
+    if !reschedule && !CREATES.contains(&entry.command.as_str()) {
+        return Err(not_possible(format!(
+            "the last action was {}; undo only reverses job submissions and reschedules",
+            what
+        )));
+    }
+    let window = Duration::minutes(window_minutes as i64);
+    if now - entry.timestamp > window {
+        return Err(not_possible(format!(
+            "the last action was {}, and undo only reaches back {} (undo_window_minutes)",
+            what,
+            humanize::duration(window)
+        )));
+    }
+    if entry.job_ids.is_empty() {
+        return Err(not_possible(format!(
+            "the last action, {}, did not change any job",
+            what
+        )));
+    }
+
+    let mut targets = Vec::new();
+    let mut blocked = 0;
+    for id in &entry.job_ids {
+        match current(client, id, now).await {
+            Ok(job) => targets.push((id.clone(), job)),
+            Err(reason) => {
+                eprintln!("⏭️ Cannot undo: {}", reason);
+                blocked += 1;
+            }
+        }
+    }
+    if targets.is_empty() {
+        return Err(not_possible(format!(
+            "none of the jobs from {} can be changed any more",
+            what
+        )));
+    }
+
+    let jobs: Vec<JobDTO> = targets.iter().map(|(_, job)| job.clone()).collect();
+    if reschedule {
+        println!("↩️ Undoing {}: restoring the previous window of", what);
+        jobs::print_table(&jobs);
+        let mut restores = Vec::new();
+        for (id, job) in &targets {
+            let before = entry
+                .previous
+                .get(id)
+                .and_then(|fields| Window::deserialize(fields).ok());
+            match before {
+                Some(before) => {
+                    println!(
+                        "   {}: {} → {}",
+                        id,
+                        span(job.job.start, job.job.end),
+                        span(before.start, before.end)
+                    );
+                    restores.push((id, before));
+                }
+                None => {
+                    eprintln!(
+                        "⏭️ Cannot undo: the audit log has no earlier window for job {}",
+                        id
+                    );
+                    blocked += 1;
+                }
+            }
+        }
+        if restores.is_empty() || !tty::confirm("undo", "Restore these windows?", yes)? {
+            println!("🛑 Nothing changed");
+            return Ok(blocked);
+        }
+        for (id, before) in restores {
+            match client.reschedule_job(id, before.start, before.end).await {
+                Ok(response) => {
+                    audit::job(id);
+                    println!("✅ Job {} restored: {}", id, response.status());
+                }
+                Err(e) => {
+                    error::report(&format!("Could not restore job {}", id), &e);
+                    blocked += 1;
+                }
+            }
+        }
+        return Ok(blocked);
+    }
+
+    println!("↩️ Undoing {}: these job(s) will be cancelled", what);
+    jobs::print_table(&jobs);
+    if !tty::confirm("undo", &format!("Cancel {} job(s)?", targets.len()), yes)? {
+        println!("🛑 Nothing cancelled");
+        return Ok(blocked);
+    }
+    for (id, job) in &targets {
+        match client.cancel_job(id, None, None).await {
+            Ok(response) => {
+                audit::job(id);
+                println!("✅ Job {} ({}): {}", id, job.job.label(), response.status());
+            }
+            Err(e) => {
+                error::report(&format!("Could not cancel job {}", id), &e);
+                blocked += 1;
+            }
+        }
+    }
+    Ok(blocked)
+}
@@ -0,0 +1,230 @@
+use crate::audit;
+use crate::client::{ApiClient, JobDTO, JobRequestDTO, JobUpdate, Versioned};
+use crate::clock;
+use crate::conflict::{self, OnConflict};
+use crate::diff;
+use crate::error::CliError;
+use crate::humanize;
+use crate::input_schema;
+use crate::job_request::{JobRequest, JobRequestBuilder};
+use crate::maintenance;
+use crate::quiet_hours;
+use crate::regulatory::TxLimits;
+use crate::submit_window;
+use crate::validation;
+use crate::OutputFormat;
+use serde_json::{Map, Value};
+
+/// A `--patch` argument: a JSON object whose keys are all job fields. Checked before
+/// anything is fetched, so a script with a typo fails at once.
+fn parse(patch: &str) -> Result<Map<String, Value>, CliError> {
+    let Value::Object(patch) =
+        serde_json::from_str(patch).map_err(|e| CliError::parse("--patch", e))?
+    else {
+        return Err(CliError::validation(
+            "patch",
+            "must be a JSON object of the fields to change, e.g. '{\"rx_frequency\": 145825000}'",
+        ));
+    };
+    if patch.is_empty() {
+        return Err(CliError::validation("patch", "changes nothing"));
+    }
+    let fields = input_schema::job_fields();
+    let unknown: Vec<&str> = patch
+        .keys()
+        .filter(|key| !fields.contains(*key))
+        .map(String::as_str)
+        .collect();
+    if !unknown.is_empty() {
+        return Err(CliError::validation(
+            "patch",
+            format!(
+                "{} {} not a job field; a job has {}",
+                unknown.join(", "),
+                if unknown.len() == 1 { "is" } else { "are" },
+                fields.into_iter().collect::<Vec<_>>().join(", ")
+            ),
+        ));
+    }
+    Ok(patch)
+}
+
+/// Apply `patch` to `target` as RFC 7386 says: objects merge key by key, a null removes
+/// the key, anything else replaces the value
+fn merge(target: &mut Value, patch: &Value) {
+    let Value::Object(changes) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    let Value::Object(fields) = target else {
+        unreachable!("made an object above");
+    };
+    for (key, change) in changes {
+        if change.is_null() {
+            fields.remove(key);
+        } else {
+            merge(fields.entry(key).or_insert(Value::Null), change);
+        }
+    }
+}
+
+/// The fields of `updated` that make a job, put through every check a new job goes through
+fn check(updated: &Value, limits: &TxLimits) -> Result<JobRequest, CliError> {
+    // The server's own fields (id, status and the like) are not the schema's business
+    let fields = input_schema::job_fields();
+    let own: Map<String, Value> = updated
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter(|(key, _)| fields.contains(*key))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    let own = Value::Object(own);
+    validation::into_result(input_schema::check_job(&own))?;
+    let dto: JobRequestDTO =
+        serde_json::from_value(own).map_err(|e| CliError::parse("the patched job", e))?;
+    let checked = JobRequestBuilder::from(dto).tx_limits(limits).build()?;
+    if let Some(problem) = submit_window::check(checked.start) {
+        return Err(problem.into());
+    }
+    quiet_hours::review(&[&checked])?;
+    Ok(checked)
+}
+
+/// `jobs update --patch`: change some fields of a job that has not started. The patched
+/// job goes through every check a new one does before the change is sent, and is read
+/// back afterwards to show what the server made of it. The change is sent against the
+/// version of the job it was made on; when someone changed the job in between,
+/// `on_conflict` or the operator decides how to go on.
+pub async fn update(
+    client: &ApiClient,
+    id: &str,
+    patch: &str,
+    limits: &TxLimits,
+    on_conflict: Option<OnConflict>,
+    output: OutputFormat,
+) -> Result<(), CliError> {
+    let patch = parse(patch)?;
+    let Versioned {
+        value: mut stored,
+        mut version,
+    } = client.job_versioned(id).await?;
+    if stored.is_null() {
+        return Err(CliError::NotFound {
+            message: Some(format!("the server returned no body for job {}", id)),
+        });
+    }
+    let job: JobDTO = serde_json::from_value(stored.clone())
+        .map_err(|e| CliError::parse(format!("job {}", id), e))?;
+    if job.job.start <= clock::now() {
+        return Err(CliError::validation(
+            "job",
+            format!(
+                "job {} started at {} and can no longer be changed",
+                id,
+                humanize::timestamp(job.job.start)
+            ),
+        ));
+    }
+
+    let patched = |stored: &Value| {
+        let mut updated = stored.clone();
+        merge(&mut updated, &Value::Object(patch.clone()));
+        updated
+    };
+    let mut updated = patched(&stored);
+    let changed = |stored: &Value, updated: &Value| -> Vec<String> {
+        patch
+            .keys()
+            .filter(|key| stored.get(key.as_str()) != updated.get(key.as_str()))
+            .cloned()
+            .collect()
+    };
+    if changed(&stored, &updated).is_empty() {
+        println!("✅ Job {} already has those values", id);
+        return Ok(());
+    }
+
+    let checked = check(&updated, limits)?;
+    let moved = (checked.start, checked.end) != (job.job.start, job.job.end);
+    if moved && !maintenance::confirm_window(client, checked.window()).await? {
+        println!("🛑 Job {} left unchanged", id);
+        return Ok(());
+    }
+
+    let how = client.job_update(id).await?;
+    let text = output != OutputFormat::Json;
+    if text {
+        println!("✏️ Updating job {} ({})", id, checked.label());
+        let shown = |value: Option<&Value>| match value {
+            Some(value) => value.to_string(),
+            None => "(unset)".to_string(),
+        };
+        for key in changed(&stored, &updated) {
+            println!(
+                "   {} {} → {}",
+                key,
+                shown(stored.get(key.as_str())),
+                shown(updated.get(key.as_str()))
+            );
+        }
+    }
+    let mut attempts = 0;
+    let response = loop {
+        attempts += 1;
+        let body = match how {
+            JobUpdate::MergePatch => Value::Object(patch.clone()),
+            JobUpdate::Replace => updated.clone(),
+        };
+        match client.update_job(id, how, &body, version.as_ref()).await {
+            Ok(response) => break response,
+            Err(e) if e.is_stale() => {}
+            Err(e) => return Err(e),
+        }
+        let theirs = client.job_versioned(id).await?;
+        conflict::show(id, &stored, &updated, &theirs.value, output);
+        if attempts == conflict::ATTEMPTS {
+            return Err(conflict::aborted(id, attempts));
+        }
+        match conflict::resolve(id, on_conflict)? {
+            OnConflict::Abort => return Err(conflict::aborted(id, attempts)),
+            OnConflict::Theirs => {
+                println!("↩️ Job {} left as the server now holds it", id);
+                return Ok(());
+            }
+            OnConflict::Mine => {
+                updated = patched(&theirs.value);
+                if changed(&theirs.value, &updated).is_empty() {
+                    println!("✅ Job {} on the server already has those values", id);
+                    return Ok(());
+                }
+                check(&updated, limits)?;
+                (stored, version) = (theirs.value, theirs.version);
+                if text {
+                    println!("🔁 Sending the change again on top of the newer version");
+                }
+            }
+        }
+    };
+    let changed = changed(&stored, &updated);
+    audit::job(id);
+    audit::previous(
+        id,
+        changed
+            .iter()
+            .map(|key| {
+                let before = stored.get(key.as_str()).cloned().unwrap_or(Value::Null);
+                (key.clone(), before)
+            })
+            .collect::<Map<_, _>>()
+            .into(),
+    );
+    if text {
+        println!("✅ Job updated: {}", response.status());
+    }
+    let requested: Vec<&str> = patch.keys().map(String::as_str).collect();
+    diff::report_change(client, id, &stored, &updated, &requested, output).await
+}
@@ -0,0 +1,288 @@
+use crate::client::{JobRequestDTO, JobType, SweepPattern};
+use crate::config::{self, Origin};
+use crate::error::CliError;
+use crate::regulatory::{self, check_frequency};
+use crate::tle;
+use serde::Serialize;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+static EXPLAIN: AtomicBool = AtomicBool::new(false);
+/// Profile the configured limits were read from
+static PROFILE: OnceLock<String> = OnceLock::new();
+
+/// `--explain`: report where the limit behind each validation failure was set
+pub fn set_explain(explain: bool) {
+    EXPLAIN.store(explain, Ordering::Relaxed);
+}
+
+pub fn explaining() -> bool {
+    EXPLAIN.load(Ordering::Relaxed)
+}
+
+/// Name the profile that profile-held limits come from
+pub fn use_profile(name: &str) {
+    let _ = PROFILE.set(name.to_string());
+}
+
+/// A limit jobs are held to and where it was set: the setting, its source and the flag
+/// that moves it for one run
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Rule {
+    pub name: &'static str,
+    /// The limit in force, as the CLI shows it
+    pub limit: String,
+    /// Setting that holds the limit; none for limits built into the CLI
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<&'static str>,
+    pub origin: Origin,
+    /// Profile the setting was read from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// Flag that overrides the limit for one invocation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flag: Option<&'static str>,
+}
+
+impl Rule {
+    /// A limit the CLI enforces with no setting to change it
+    pub fn builtin(name: &'static str, limit: impl Into<String>) -> Self {
+        Self {
+            name,
+            limit: limit.into(),
+            key: None,
+            origin: Origin::Default,
+            profile: None,
+            flag: None,
+        }
+    }
+
+    /// A limit held by profile setting `key`: read from the config file when `set`,
+    /// else the default
+    pub fn profile(
+        name: &'static str,
+        key: &'static str,
+        limit: impl Into<String>,
+        set: bool,
+    ) -> Self {
+        Self {
+            key: Some(key),
+            origin: if set {
+                Origin::ConfigFile(config::config_path())
+            } else {
+                Origin::Default
+            },
+            profile: PROFILE.get().cloned().filter(|_| set),
+            ..Self::builtin(name, limit)
+        }
+    }
+
+    /// A limit the server reports at `endpoint`; only the station can change it
+    pub fn server(name: &'static str, endpoint: String, limit: impl Into<String>) -> Self {
+        Self {
+            origin: Origin::Server(endpoint),
+            ..Self::builtin(name, limit)
+        }
+    }
+
+    /// `flag` overrides the limit for one run
+    pub fn overridden_by(self, flag: &'static str) -> Self {
+        Self {
+            flag: Some(flag),
+            ..self
+        }
+    }
+
+    /// The limit came from `flag` on this command line
+    pub fn set_by_flag(self, flag: &'static str) -> Self {
+        Self {
+            origin: Origin::Flag(flag.to_string()),
+            profile: None,
+            ..self.overridden_by(flag)
+        }
+    }
+}
+
+/// "submit horizon 30d, from max_horizon_days in profile 'default' (config file …);
+/// --max-horizon overrides it for one run"
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.name, self.limit)?;
+        match (&self.origin, self.key) {
+            (Origin::Flag(flag), _) => write!(f, ", from {} on this command line", flag)?,
+            (Origin::Default, Some(key)) => write!(f, ", the default: {} is not set", key)?,
+            (Origin::Default, None) => write!(f, ", built into the CLI")?,
+            (origin, Some(key)) => {
+                write!(f, ", from {}", key)?;
+                if let Some(profile) = &self.profile {
+                    write!(f, " in profile '{}'", profile)?;
+                }
+                write!(f, " ({})", origin)?;
+            }
+            (origin, None) => write!(f, " ({})", origin)?,
+        }
+        match self.flag {
+            Some(flag) if !matches!(self.origin, Origin::Flag(_)) => {
+                write!(f, "; {} overrides it for one run", flag)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A single rule violation in a job definition
+#[derive(Debug, Clone)]
+pub struct Problem {
+    pub field: String,
+    pub reason: String,
+    /// The configured or built-in limit the job breaks, when it breaks one
+    pub rule: Option<Box<Rule>>,
+}
+
+impl Problem {
+    pub fn new(field: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            reason: reason.into(),
+            rule: None,
+        }
+    }
+
+    /// The problem is breaking `rule`
+    pub fn breaking(self, rule: Rule) -> Self {
+        Self {
+            rule: Some(Box::new(rule)),
+            ..self
+        }
+    }
+}
+
+/// One error for all of `problems`: the problem itself when there is one, else every
+/// problem joined under "job" with the rules each breaks
+pub fn into_result(mut problems: Vec<Problem>) -> Result<(), CliError> {
+    match problems.len() {
+        0 => Ok(()),
+        1 => Err(problems.remove(0).into()),
+        _ => Err(CliError::Validation {
+            field: "job".to_string(),
+            reason: problems
+                .iter()
+                .map(|p| format!("{}: {}", p.field, p.reason))
+                .collect::<Vec<_>>()
+                .join("; "),
+            rules: problems
+                .into_iter()
+                .filter_map(|p| p.rule.map(|rule| *rule))
+                .collect(),
+        }),
+    }
+}
+
+impl From<Problem> for CliError {
+    fn from(problem: Problem) -> Self {
+        CliError::Validation {
+            field: problem.field,
+            reason: problem.reason,
+            rules: problem.rule.into_iter().map(|rule| *rule).collect(),
+        }
+    }
+}
+
+/// Azimuth stays within one turn, elevation between the horizon and zenith
+fn check_sweep(sweep: &SweepPattern) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    for (axis, start, end, step, max) in [
+        (
+            "azimuth",
+            sweep.az_start,
+            sweep.az_end,
+            sweep.az_step,
+            360.0,
+        ),
+        (
+            "elevation",
+            sweep.el_start,
+            sweep.el_end,
+            sweep.el_step,
+            90.0,
+        ),
+    ] {
+        let field = format!("sweep.{}", axis);
+        if !(0.0..=max).contains(&start) || !(0.0..=max).contains(&end) {
+            problems.push(Problem::new(
+                &field,
+                format!("{}°–{}° is outside 0–{}°", start, end, max),
+            ));
+        } else if end < start {
+            problems.push(Problem::new(
+                &field,
+                format!("ends at {}°, before its start {}°", end, start),
+            ));
+        }
+        if !step.is_finite() || step <= 0.0 {
+            problems.push(Problem::new(&field, "step must be a positive angle"));
+        }
+    }
+    problems
+}
+
+/// An azimuth within one turn, north included and not counted twice
+pub fn check_azimuth(field: &str, azimuth: f64) -> Option<Problem> {
+    (!(0.0..360.0).contains(&azimuth)).then(|| {
+        Problem::new(
+            field,
+            format!("{}° is outside 0–360° (360° itself is 0°)", azimuth),
+        )
+    })
+}
+
+/// An elevation between the horizon and zenith
+pub fn check_elevation(field: &str, elevation: f64) -> Option<Problem> {
+    (!(0.0..=90.0).contains(&elevation))
+        .then(|| Problem::new(field, format!("{}° is outside 0–90°", elevation)))
+}
+
+/// Check one job on its own: window ordering, the parameters of its job type,
+/// frequency ranges and transmit settings. Licence limits need the profile; see
+/// `regulatory::check_limits`.
+pub fn check_job(job: &JobRequestDTO) -> Vec<Problem> {
+    let mut problems = Vec::new();
+
+    if job.window().is_empty() {
+        problems.push(Problem::new(
+            "end",
+            format!("{} is not after the start {}", job.end, job.start),
+        ));
+    }
+
+    match &job.job_type {
+        JobType::Tracking { tle } => {
+            problems.extend(
+                tle::validate(tle)
+                    .into_iter()
+                    .map(|(field, reason)| Problem::new(field, reason)),
+            );
+            problems.extend(check_frequency("rx_frequency", job.rx_frequency, false));
+        }
+        JobType::Calibration { sweep } => {
+            problems.extend(check_sweep(sweep));
+            // A sweep may log signal strength, but it does not have to listen at all
+            problems.extend(check_frequency("rx_frequency", job.rx_frequency, true));
+        }
+        JobType::Pointing { pointing } => {
+            problems.extend(check_azimuth("pointing.azimuth", pointing.azimuth));
+            problems.extend(check_elevation("pointing.elevation", pointing.elevation));
+            problems.extend(check_frequency("rx_frequency", job.rx_frequency, false));
+        }
+        JobType::Test => {
+            problems.extend(check_frequency("rx_frequency", job.rx_frequency, false));
+        }
+    }
+    // A zero uplink frequency marks a receive-only job
+    problems.extend(check_frequency("tx_frequency", job.tx_frequency, true));
+    problems.extend(regulatory::check_rx_offset(job));
+    problems.extend(regulatory::check_transmit(job));
+
+    problems
+}
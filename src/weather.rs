@@ -0,0 +1,196 @@
+use crate::client::JobRequestDTO;
+use crate::clock;
+use crate::frequency::Frequency;
+use crate::location::Location;
+use crate::paths;
+use crate::storage;
+use chrono::{DateTime, Duration, DurationRound, NaiveDateTime, Utc};
+use serde::Deserialize;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+
+/// Open-Meteo's hourly forecast for the station, in UTC
+pub const DEFAULT_FORECAST_URL: &str = "https://api.open-meteo.com/v1/forecast?\
+     latitude={latitude}&longitude={longitude}\
+     &hourly=precipitation_probability,cloud_cover&timezone=UTC";
+
+/// Forecasts younger than this are used without asking the provider again
+const CACHE_MAX_AGE: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+/// The forecast is a courtesy; a slow provider must not hold up scheduling
+const FETCH_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Forecast provider and the limits above which a job gets a rain-fade warning, from the
+/// profile's `[weather]` table
+#[derive(Debug, Clone, Deserialize)]
+pub struct WeatherSettings {
+    /// Hourly forecast URL; `{latitude}` and `{longitude}` are replaced by the station's
+    #[serde(default = "default_url")]
+    pub url: String,
+    /// Jobs receiving or transmitting at or above this frequency are checked
+    #[serde(default = "default_min_frequency")]
+    pub min_frequency: Frequency,
+    /// Warn when the chance of precipitation in the window exceeds this, in percent
+    #[serde(default = "default_max_precipitation")]
+    pub max_precipitation_probability: f64,
+    /// Warn when cloud cover in the window exceeds this, in percent
+    #[serde(default = "default_max_cloud_cover")]
+    pub max_cloud_cover: f64,
+}
+
+fn default_url() -> String {
+    DEFAULT_FORECAST_URL.to_string()
+}
+
+fn default_min_frequency() -> Frequency {
+    // S band and up, where rain fade starts to matter
+    Frequency::from_hz(2_000_000_000)
+}
+
+fn default_max_precipitation() -> f64 {
+    40.0
+}
+
+fn default_max_cloud_cover() -> f64 {
+    80.0
+}
+
+/// Hourly series as Open-Meteo sends them; hours without a value are null
+#[derive(Debug, Deserialize)]
+struct Forecast {
+    hourly: Hourly,
+}
+
+#[derive(Debug, Deserialize)]
+struct Hourly {
+    time: Vec<String>,
+    #[serde(default)]
+    precipitation_probability: Vec<Option<f64>>,
+    #[serde(default)]
+    cloud_cover: Vec<Option<f64>>,
+}
+
+/// The worst of each series over the hours a window touches
+#[derive(Debug, Default)]
+struct Outlook {
+    precipitation_probability: Option<f64>,
+    cloud_cover: Option<f64>,
+}
+
+impl Forecast {
+    fn outlook(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Outlook {
+        let first_hour = start.duration_trunc(Duration::hours(1)).unwrap_or(start);
+        let mut outlook = Outlook::default();
+        let worst =
+            |current: Option<f64>, value: Option<&Option<f64>>| match value.copied().flatten() {
+                Some(value) => Some(current.map_or(value, |current| current.max(value))),
+                None => current,
+            };
+        for (index, time) in self.hourly.time.iter().enumerate() {
+            let Ok(hour) = NaiveDateTime::parse_from_str(time, "%Y-%m-%dT%H:%M") else {
+                continue;
+            };
+            let hour = hour.and_utc();
+            if hour < first_hour || hour >= end {
+                continue;
+            }
+            outlook.precipitation_probability = worst(
+                outlook.precipitation_probability,
+                self.hourly.precipitation_probability.get(index),
+            );
+            outlook.cloud_cover = worst(outlook.cloud_cover, self.hourly.cloud_cover.get(index));
+        }
+        outlook
+    }
+}
+
+/// Forecast checks for one station, built once the profile has both settings and a location
+pub struct Lookout {
+    settings: WeatherSettings,
+    url: String,
+}
+
+impl Lookout {
+    pub fn new(settings: WeatherSettings, station: Location) -> Self {
+        let url = settings
+            .url
+            .replace("{latitude}", &station.latitude.to_string())
+            .replace("{longitude}", &station.longitude.to_string());
+        Self { settings, url }
+    }
+
+    /// A warning line when the forecast for the job's window is past the profile's limits.
+    /// Low-frequency jobs are not checked, and any failure to get a forecast is silent.
+    pub async fn warning(&self, job: &JobRequestDTO) -> Option<String> {
+        if job.rx_frequency.max(job.tx_frequency) < self.settings.min_frequency {
+            return None;
+        }
+        let outlook = self.forecast().await?.outlook(job.start, job.end);
+        let mut reasons = Vec::new();
+        if let Some(chance) = outlook
+            .precipitation_probability
+            .filter(|chance| *chance > self.settings.max_precipitation_probability)
+        {
+            reasons.push(format!("{:.0}% chance of precipitation", chance));
+        }
+        if let Some(cover) = outlook
+            .cloud_cover
+            .filter(|cover| *cover > self.settings.max_cloud_cover)
+        {
+            reasons.push(format!("{:.0}% cloud cover", cover));
+        }
+        if reasons.is_empty() {
+            return None;
+        }
+        Some(format!(
+            "Forecast for the window: {}; expect rain fade at {}",
+            reasons.join(", "),
+            job.rx_frequency.max(job.tx_frequency)
+        ))
+    }
+
+    fn cache_path(&self) -> PathBuf {
+        let mut hasher = DefaultHasher::new();
+        self.url.hash(&mut hasher);
+        paths::cache_dir()
+            .join("weather")
+            .join(format!("{:016x}.json", hasher.finish()))
+    }
+
+    /// The cached forecast while it is fresh, else the provider's
+    async fn forecast(&self) -> Option<Forecast> {
+        let path = self.cache_path();
+        let fresh = std::fs::metadata(&path)
+            .and_then(|m| m.modified())
+            .ok()
+            .map(clock::age)
+            .is_some_and(|age| age < CACHE_MAX_AGE);
+        if fresh {
+            if let Some(forecast) = std::fs::read_to_string(&path)
+                .ok()
+                .and_then(|text| serde_json::from_str(&text).ok())
+            {
+                return Some(forecast);
+            }
+        }
+
+        let client = reqwest::Client::builder()
+            .timeout(FETCH_TIMEOUT)
+            .build()
+            .ok()?;
+        let text = client
+            .get(&self.url)
+            .send()
+            .await
+            .ok()?
+            .error_for_status()
+            .ok()?
+            .text()
+            .await
+            .ok()?;
+        let forecast = serde_json::from_str(&text).ok()?;
+        let _ = storage::write_atomic(&path, text);
+        Some(forecast)
+    }
+}
@@ -0,0 +1,103 @@
+use crate::client::{ApiClient, Identity};
+use crate::clock;
+use crate::error::CliError;
+use crate::humanize;
+use crate::OutputFormat;
+use serde::Serialize;
+
+/// The identity together with where it was asked for, as `--output json` prints it
+#[derive(Serialize)]
+struct Report<'a> {
+    profile: &'a str,
+    base_url: &'a str,
+    identity: &'a Identity,
+}
+
+/// "expires in 3h 12m (2026-10-14 18:00:00 UTC)", or how long ago it already did
+fn expiry(identity: &Identity) -> String {
+    let Some(at) = identity.expires_at else {
+        return "never (the server gave no expiry)".to_string();
+    };
+    let left = at - clock::now();
+    if left.num_seconds() > 0 {
+        format!(
+            "in {} ({})",
+            humanize::duration(left),
+            humanize::timestamp(at)
+        )
+    } else {
+        format!(
+            "{} ago ({})",
+            humanize::duration(left),
+            humanize::timestamp(at)
+        )
+    }
+}
+
+fn render(report: &Report) {
+    let identity = report.identity;
+    println!(
+        "👤 {}",
+        identity.subject.as_deref().unwrap_or("(no subject given)")
+    );
+
+    let mut rows: Vec<(String, String)> = vec![
+        ("Profile".into(), report.profile.to_string()),
+        ("Server".into(), report.base_url.to_string()),
+    ];
+    if let Some(issuer) = &identity.issuer {
+        rows.push(("Issuer".into(), issuer.clone()));
+    }
+    rows.push(("Expires".into(), expiry(identity)));
+    rows.push((
+        "Scopes".into(),
+        if identity.scopes.is_empty() {
+            "—".to_string()
+        } else {
+            identity.scopes.join(", ")
+        },
+    ));
+    for (key, value) in &identity.extra {
+        let value = match value {
+            serde_json::Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        rows.push((key.clone(), value));
+    }
+
+    let width = rows.iter().map(|(key, _)| key.len()).max().unwrap_or(0);
+    for (key, value) in rows {
+        println!("  {:<width$} : {}", key, value, width = width);
+    }
+}
+
+/// Ask the server who it takes this client to be, and show that with the profile and
+/// server it was asked through
+pub async fn show(
+    client: &ApiClient,
+    profile: &str,
+    base_url: &str,
+    output: OutputFormat,
+) -> Result<(), CliError> {
+    let identity = match client.whoami().await {
+        Err(e) if e.status() == Some(404) => {
+            return Err(CliError::Unsupported(
+                "this server does not report identities (GET /auth/me)".to_string(),
+            ))
+        }
+        other => other?,
+    };
+    let report = Report {
+        profile,
+        base_url,
+        identity: &identity,
+    };
+    match output {
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&report).map_err(|e| CliError::parse("identity", e))?
+        ),
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::Ndjson => render(&report),
+    }
+    Ok(())
+}
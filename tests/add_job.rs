@@ -0,0 +1,139 @@
+//! `jobs add` answered through a terminal, against a scripted station
+
+mod common;
+
+use common::{add_loopback, created, created_after, start_date, station, Sandbox, BACKSPACE};
+use std::time::Duration;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, ResponseTemplate};
+
+#[tokio::test]
+async fn submits_a_loopback_test() {
+    let api = station().await;
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(created(7))
+        .expect(1)
+        .mount(&api)
+        .await;
+
+    let sandbox = Sandbox::new();
+    let mut terminal = sandbox.spawn(&api, &["jobs", "add"]);
+    add_loopback(&mut terminal, &start_date());
+    terminal.expect("Job submitted successfully");
+    let (_, code) = terminal.finish();
+    assert_eq!(code, 0);
+
+    let requests = api.received_requests().await.unwrap_or_default();
+    let submitted = requests
+        .iter()
+        .find(|r| r.method.as_str() == "POST")
+        .expect("a job was posted");
+    let body: serde_json::Value = submitted.body_json().expect("the job is JSON");
+    assert_eq!(body["job_type"], "test");
+    assert_eq!(body["rx_frequency"], 437_500_000.0);
+}
+
+#[tokio::test]
+async fn asks_again_for_an_impossible_date() {
+    let api = station().await;
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(created(7))
+        .expect(1)
+        .mount(&api)
+        .await;
+
+    let sandbox = Sandbox::new();
+    let mut terminal = sandbox.spawn(&api, &["jobs", "add"]);
+    let typo = "2026-13-45";
+    terminal
+        .expect("Job type:")
+        .keys(common::DOWN)
+        .keys(common::DOWN)
+        .keys(common::ENTER)
+        .answer("Start date:", typo)
+        // The prompt stays open with the reason, holding what was typed
+        .expect("could not parse date")
+        .keys(&BACKSPACE.repeat(typo.len()))
+        .keys(&start_date())
+        .keys(common::ENTER)
+        .answer("Start time:", "12:10")
+        .answer("Duration", "10m")
+        .answer("Test frequency", "437.5M")
+        .expect("Job submitted successfully");
+    let (_, code) = terminal.finish();
+    assert_eq!(code, 0);
+}
+
+#[tokio::test]
+async fn reports_a_conflicting_window() {
+    let api = station().await;
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(ResponseTemplate::new(409).set_body_json(serde_json::json!({
+            "status": "error",
+            "message": "window overlaps job 3",
+        })))
+        .expect(1)
+        .mount(&api)
+        .await;
+
+    let sandbox = Sandbox::new();
+    let mut terminal = sandbox.spawn(&api, &["jobs", "add"]);
+    add_loopback(&mut terminal, &start_date());
+    terminal.expect("window overlaps job 3");
+    let (output, code) = terminal.finish();
+    assert_ne!(code, 0);
+    assert!(!output.contains("Job submitted successfully"));
+}
+
+#[tokio::test]
+async fn reports_a_server_error_without_retrying() {
+    let api = station().await;
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(ResponseTemplate::new(500).set_body_json(serde_json::json!({
+            "status": "error",
+            "message": "scheduler unavailable",
+        })))
+        .expect(1)
+        .mount(&api)
+        .await;
+
+    let sandbox = Sandbox::new();
+    let mut terminal = sandbox.spawn(&api, &["jobs", "add"]);
+    add_loopback(&mut terminal, &start_date());
+    let (output, code) = terminal.finish();
+    assert_ne!(code, 0);
+    assert!(output.contains("scheduler unavailable"), "{}", output);
+    assert!(!output.contains("What now?"), "{}", output);
+}
+
+#[tokio::test]
+async fn offers_a_retry_when_the_station_is_slow() {
+    let api = station().await;
+    // The first answer comes after the client gave up; the retry gets a prompt one
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(created_after(7, Duration::from_secs(3)))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&api)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(created(8))
+        .mount(&api)
+        .await;
+
+    let sandbox = Sandbox::new();
+    let mut terminal = sandbox.spawn(&api, &["--timeout", "1", "jobs", "add"]);
+    add_loopback(&mut terminal, &start_date());
+    terminal
+        .expect("What now?")
+        .keys(common::ENTER)
+        .expect("Job submitted successfully");
+    let (_, code) = terminal.finish();
+    assert_eq!(code, 0);
+}
@@ -0,0 +1,84 @@
+//! Profile aliases and the default command, expanded before the arguments are parsed
+
+mod common;
+
+use common::{cli, configure, station, Sandbox};
+use predicates::prelude::PredicateBooleanExt;
+use predicates::str::contains;
+
+#[tokio::test]
+async fn an_alias_runs_its_expansion_and_says_so_with_verbose() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    configure(
+        &sandbox,
+        "alias.stores = \"doctor\"\nalias.check = \"stores\"\n",
+    );
+
+    cli(&sandbox, &api, &["--verbose", "check"])
+        .assert()
+        .success()
+        .stderr(contains(
+            "Alias check of profile default expands to: stores",
+        ))
+        .stderr(contains(
+            "Alias stores of profile default expands to: doctor",
+        ))
+        .stdout(contains("Local stores"));
+}
+
+#[tokio::test]
+async fn no_arguments_run_the_default_command() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    configure(
+        &sandbox,
+        "default_command = \"alias list\"\nalias.up = \"jobs list --timeline\"\n",
+    );
+
+    cli(&sandbox, &api, &[])
+        .assert()
+        .success()
+        .stdout(contains("up"))
+        .stdout(contains("jobs list --timeline"))
+        .stdout(contains("With no command: alias list"));
+}
+
+#[tokio::test]
+async fn an_alias_named_like_a_command_is_refused_when_the_config_loads() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    configure(&sandbox, "alias.doctor = \"jobs list\"\n");
+
+    cli(&sandbox, &api, &["alias", "list"])
+        .assert()
+        .failure()
+        .stderr(contains("alias.doctor has the name of a built-in command"));
+}
+
+#[tokio::test]
+async fn aliases_that_loop_are_reported() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    configure(&sandbox, "alias.ping = \"pong\"\nalias.pong = \"ping\"\n");
+
+    cli(&sandbox, &api, &["ping"])
+        .assert()
+        .failure()
+        .stderr(contains(
+            "alias loop in profile 'default': ping → pong → ping",
+        ));
+}
+
+#[tokio::test]
+async fn built_in_commands_are_never_expanded() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    configure(&sandbox, "alias.stores = \"doctor\"\n");
+
+    cli(&sandbox, &api, &["--verbose", "alias", "list"])
+        .assert()
+        .success()
+        .stderr(contains("expands to").not())
+        .stdout(contains("stores"));
+}
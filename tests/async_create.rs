@@ -0,0 +1,207 @@
+//! Servers that create jobs later: a 202 Accepted with a Location is followed until the
+//! job is created or rejected, and reported as a synchronous answer would be
+
+mod common;
+
+use common::{cli, created, Sandbox};
+use predicates::prelude::PredicateBooleanExt;
+use predicates::str::contains;
+use serde_json::Value;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// A batch of one loopback test, with the arguments that submit it and report on it
+fn batch(sandbox: &Sandbox) -> Vec<String> {
+    let start = chrono::Utc::now() + chrono::Duration::days(2);
+    let file = sandbox.path().join("jobs.json");
+    let job = serde_json::json!({
+        "job_type": "test",
+        "start": start.to_rfc3339(),
+        "end": (start + chrono::Duration::minutes(10)).to_rfc3339(),
+        "rx_frequency": 437_500_000,
+        "tx_frequency": 0,
+    });
+    std::fs::write(&file, Value::Array(vec![job]).to_string()).expect("writing the batch");
+    [
+        "jobs",
+        "add",
+        "--batch",
+        &file.display().to_string(),
+        "--report",
+        &sandbox.path().join("report.json").display().to_string(),
+    ]
+    .map(str::to_string)
+    .to_vec()
+}
+
+fn args(batch: &[String], more: &[&str]) -> Vec<String> {
+    batch
+        .iter()
+        .cloned()
+        .chain(more.iter().map(|s| s.to_string()))
+        .collect()
+}
+
+/// The outcome of the one entry in the batch report
+fn entry(sandbox: &Sandbox) -> Value {
+    let report = std::fs::read_to_string(sandbox.path().join("report.json")).expect("a report");
+    let report: Value = serde_json::from_str(&report).expect("a JSON report");
+    report["entries"][0].clone()
+}
+
+/// A server that accepts submissions to create them at `/jobs/queue/9`
+async fn accepting() -> MockServer {
+    let api = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(
+            ResponseTemplate::new(202)
+                .insert_header("Location", "/jobs/queue/9")
+                .set_body_json(serde_json::json!({ "status": "accepted" })),
+        )
+        .mount(&api)
+        .await;
+    api
+}
+
+async fn polls(api: &MockServer) -> usize {
+    api.received_requests()
+        .await
+        .unwrap_or_default()
+        .iter()
+        .filter(|r| r.method.as_str() == "GET" && r.url.path() == "/jobs/queue/9")
+        .count()
+}
+
+fn run(sandbox: &Sandbox, api: &MockServer, args: &[String]) -> assert_cmd::assert::Assert {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    cli(sandbox, api, &args).assert()
+}
+
+#[tokio::test]
+async fn a_synchronous_answer_is_taken_as_it_is() {
+    let api = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(created(4))
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+
+    run(&sandbox, &api, &batch(&sandbox))
+        .success()
+        .stderr(contains("waiting up to").not());
+    let entry = entry(&sandbox);
+    assert_eq!(entry["outcome"], "created", "{}", entry);
+    assert_eq!(entry["job_id"], "4", "{}", entry);
+}
+
+#[tokio::test]
+async fn an_accepted_job_is_followed_until_it_is_created() {
+    let api = accepting().await;
+    Mock::given(method("GET"))
+        .and(path("/jobs/queue/9"))
+        .respond_with(ResponseTemplate::new(202).set_body_json(serde_json::json!({
+            "status": "processing",
+        })))
+        .up_to_n_times(1)
+        .mount(&api)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/jobs/queue/9"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "status": "created",
+            "id": 17,
+        })))
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+
+    run(&sandbox, &api, &batch(&sandbox))
+        .success()
+        .stderr(contains("waiting up to 60s"))
+        .stderr(contains("✅ [0]"));
+    let entry = entry(&sandbox);
+    assert_eq!(entry["outcome"], "created", "{}", entry);
+    assert_eq!(entry["job_id"], "17", "{}", entry);
+    assert_eq!(polls(&api).await, 2);
+}
+
+#[tokio::test]
+async fn a_job_rejected_later_fails_as_a_rejection_would() {
+    let api = accepting().await;
+    Mock::given(method("GET"))
+        .and(path("/jobs/queue/9"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "status": "rejected",
+            "message": "the window overlaps job 12",
+        })))
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+
+    run(&sandbox, &api, &batch(&sandbox))
+        .failure()
+        .stderr(contains("422"))
+        .stderr(contains("the window overlaps job 12"));
+    let entry = entry(&sandbox);
+    assert_eq!(entry["outcome"], "failed", "{}", entry);
+    assert_eq!(entry["status"], 422, "{}", entry);
+}
+
+#[tokio::test]
+async fn no_wait_answers_with_where_to_follow_the_job() {
+    let api = accepting().await;
+    let sandbox = Sandbox::new();
+
+    run(&sandbox, &api, &args(&batch(&sandbox), &["--no-wait"]))
+        .success()
+        .stderr(contains(format!(
+            "accepted; follow it at {}/jobs/queue/9",
+            api.uri()
+        )));
+    let entry = entry(&sandbox);
+    assert_eq!(entry["outcome"], "created", "{}", entry);
+    assert_eq!(entry["status"], "accepted", "{}", entry);
+    assert_eq!(
+        entry["location"],
+        format!("{}/jobs/queue/9", api.uri()),
+        "{}",
+        entry
+    );
+    assert_eq!(polls(&api).await, 0);
+}
+
+#[tokio::test]
+async fn a_job_still_pending_at_the_deadline_is_not_submitted_again() {
+    let api = accepting().await;
+    Mock::given(method("GET"))
+        .and(path("/jobs/queue/9"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "status": "pending",
+        })))
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+
+    run(
+        &sandbox,
+        &api,
+        &args(&batch(&sandbox), &["--wait-timeout", "2s"]),
+    )
+    .failure()
+    .stderr(contains("had not created it after 2s"))
+    .stderr(contains(format!("{}/jobs/queue/9", api.uri())))
+    .stderr(contains("jobs list before submitting it again"));
+    let entry = entry(&sandbox);
+    assert_eq!(entry["kind"], "pending", "{}", entry);
+    assert!(polls(&api).await >= 1);
+    let posts = api
+        .received_requests()
+        .await
+        .unwrap_or_default()
+        .iter()
+        .filter(|r| r.method.as_str() == "POST")
+        .count();
+    assert_eq!(posts, 1);
+}
@@ -0,0 +1,335 @@
+//! `backup export` and `backup import` between two sandboxes standing in for the old
+//! laptop and the new one
+
+mod common;
+
+use common::{cli, Sandbox};
+use predicates::prelude::PredicateBooleanExt;
+use predicates::str::contains;
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use wiremock::MockServer;
+
+const CONFIG: &str = "default_profile = \"lab\"\n\
+    [profiles.lab]\nbase_url = \"http://lab.example\"\n\
+    [profiles.lab.mqtt]\nhost = \"broker.example\"\nusername = \"op\"\npassword = \"hunter2\"\n\
+    [[profiles.lab.hooks]]\ntype = \"webhook\"\nurl = \"http://hooks.example\"\n\
+    [profiles.lab.hooks.headers]\nAuthorization = \"Bearer abc\"\n";
+const ALIASES: &str = "version = 1\n[iss]\nnorad_id = 25544\nrx_frequency = 145800000\n\
+    [groups]\nleo = [\"iss\"]\n";
+const AUDIT_LINE: &str =
+    "{\"timestamp\":\"2026-01-02T03:04:05Z\",\"user\":\"op\",\"profile\":\"lab\",\
+    \"command\":\"add-job\",\"arguments\":[],\"job_ids\":[\"7\"],\"outcome\":\"success\"}";
+
+fn config_file(sandbox: &Sandbox) -> PathBuf {
+    sandbox.path().join("config/rustar/config.toml")
+}
+
+fn alias_book(sandbox: &Sandbox) -> PathBuf {
+    sandbox.path().join("config/rustar/satellites.toml")
+}
+
+fn history_file(sandbox: &Sandbox) -> PathBuf {
+    sandbox.path().join("state/rustar/prompt_history.json")
+}
+
+fn audit_log(sandbox: &Sandbox) -> PathBuf {
+    sandbox.path().join("state/rustar/audit.jsonl")
+}
+
+fn write(path: PathBuf, contents: &str) {
+    std::fs::create_dir_all(path.parent().expect("a parent")).expect("creating the directory");
+    std::fs::write(path, contents).expect("writing a local store");
+}
+
+fn read(path: PathBuf) -> String {
+    std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("reading {}: {}", path.display(), e))
+}
+
+fn toml(path: PathBuf) -> toml::Table {
+    toml::from_str(&read(path)).expect("a TOML file")
+}
+
+/// The old laptop: a profile with secrets, an alias book, some history and an audit line
+fn old_laptop() -> Sandbox {
+    let sandbox = Sandbox::new();
+    write(config_file(&sandbox), CONFIG);
+    write(alias_book(&sandbox), ALIASES);
+    write(
+        history_file(&sandbox),
+        &json!({
+            "version": 2,
+            "prompts": { "satellite_name": [{ "value": "ISS (ZARYA)", "profile": "lab" }] },
+        })
+        .to_string(),
+    );
+    write(audit_log(&sandbox), &format!("{}\n", AUDIT_LINE));
+    sandbox
+}
+
+/// Export from `sandbox` to `backup.tar.gz` in it
+fn export(sandbox: &Sandbox, api: &MockServer, extra: &[&str]) -> PathBuf {
+    let file = sandbox.path().join("backup.tar.gz");
+    let mut args = vec!["backup", "export", file.to_str().expect("a UTF-8 path")];
+    args.extend(extra);
+    cli(sandbox, api, &args).assert().success();
+    file
+}
+
+#[tokio::test]
+async fn everything_comes_back_on_a_new_machine() {
+    let api = MockServer::start().await;
+    let old = old_laptop();
+    let file = export(&old, &api, &[]);
+    let new = Sandbox::new();
+
+    cli(&new, &api, &["backup", "import", file.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(contains("config file: restored"))
+        .stdout(contains("alias book: restored"))
+        .stdout(contains("prompt history: restored"))
+        .stdout(contains("audit log: restored"));
+    assert_eq!(read(config_file(&new)), CONFIG);
+    assert_eq!(read(alias_book(&new)), ALIASES);
+    assert_eq!(read(history_file(&new)), read(history_file(&old)));
+    // The import itself is the log's latest entry
+    let log = read(audit_log(&new));
+    let lines: Vec<&str> = log.lines().collect();
+    assert_eq!(lines[0], AUDIT_LINE);
+    assert!(lines[1].contains("\"backup import\""), "{}", log);
+}
+
+#[tokio::test]
+async fn no_secrets_leaves_out_passwords_and_authorization_headers() {
+    let api = MockServer::start().await;
+    let old = old_laptop();
+    let file = sandbox_export_without_secrets(&old, &api);
+    let new = Sandbox::new();
+
+    cli(
+        &new,
+        &api,
+        &[
+            "--output",
+            "json",
+            "backup",
+            "import",
+            file.to_str().unwrap(),
+        ],
+    )
+    .assert()
+    .success()
+    .stdout(contains("\"secrets\": false"));
+    let config = read(config_file(&new));
+    assert!(
+        !config.contains("hunter2") && !config.contains("Bearer"),
+        "{}",
+        config
+    );
+    assert!(config.contains("username = \"op\""), "{}", config);
+    assert!(config.contains("http://hooks.example"), "{}", config);
+}
+
+fn sandbox_export_without_secrets(sandbox: &Sandbox, api: &MockServer) -> PathBuf {
+    let file = sandbox.path().join("backup.tar.gz");
+    cli(
+        sandbox,
+        api,
+        &[
+            "backup",
+            "export",
+            "--no-secrets",
+            file.to_str().expect("a UTF-8 path"),
+        ],
+    )
+    .assert()
+    .success()
+    .stderr(contains("Left out 2 secret setting(s) of the config file"));
+    file
+}
+
+#[tokio::test]
+async fn merge_adds_what_is_missing_and_keeps_what_is_here() {
+    let api = MockServer::start().await;
+    let old = old_laptop();
+    let file = export(&old, &api, &[]);
+    let new = Sandbox::new();
+    write(
+        config_file(&new),
+        "default_profile = \"home\"\n[profiles.home]\nbase_url = \"http://home.example\"\n\
+         [profiles.lab]\nbase_url = \"http://lab.local\"\n",
+    );
+    write(
+        alias_book(&new),
+        "version = 1\n[iss]\nnorad_id = 25544\nrx_frequency = 437800000\n\
+         [noaa19]\nnorad_id = 33591\n[groups]\nweather = [\"noaa19\"]\n",
+    );
+    let mine = AUDIT_LINE.replace("2026-01-02", "2026-03-01");
+    write(audit_log(&new), &format!("{}\n{}\n", mine, AUDIT_LINE));
+
+    cli(
+        &new,
+        &api,
+        &[
+            "backup",
+            "import",
+            file.to_str().unwrap(),
+            "--config",
+            "merge",
+            "--aliases",
+            "merge",
+            "--history",
+            "merge",
+            "--audit",
+            "merge",
+        ],
+    )
+    .assert()
+    .success()
+    .stdout(contains("alias book: merged"));
+
+    let config = toml(config_file(&new));
+    assert_eq!(config["default_profile"].as_str(), Some("home"));
+    assert_eq!(
+        config["profiles"]["lab"]["base_url"].as_str(),
+        Some("http://lab.local")
+    );
+    assert_eq!(
+        config["profiles"]["home"]["base_url"].as_str(),
+        Some("http://home.example")
+    );
+
+    let book = toml(alias_book(&new));
+    assert_eq!(book["iss"]["rx_frequency"].as_integer(), Some(437_800_000));
+    assert_eq!(book["noaa19"]["norad_id"].as_integer(), Some(33591));
+    assert!(book["groups"].get("leo").is_some() && book["groups"].get("weather").is_some());
+
+    // The shared line once, in time order, before this machine's later one
+    let log = read(audit_log(&new));
+    let lines: Vec<&str> = log.lines().collect();
+    assert_eq!(lines[..2], [AUDIT_LINE, mine.as_str()]);
+}
+
+#[tokio::test]
+async fn skip_and_overwrite_keep_or_replace_this_machines_copy() {
+    let api = MockServer::start().await;
+    let old = old_laptop();
+    let file = export(&old, &api, &[]);
+    let new = Sandbox::new();
+    let here = "[profiles.home]\nbase_url = \"http://home.example\"\n";
+    write(config_file(&new), here);
+    write(
+        alias_book(&new),
+        "version = 1\n[noaa19]\nnorad_id = 33591\n",
+    );
+
+    cli(
+        &new,
+        &api,
+        &[
+            "backup",
+            "import",
+            file.to_str().unwrap(),
+            "--config",
+            "skip",
+            "--aliases",
+            "overwrite",
+        ],
+    )
+    .assert()
+    .success()
+    .stdout(contains("config file: skipped"))
+    .stdout(contains("alias book: overwritten"));
+    assert_eq!(read(config_file(&new)), here);
+    assert_eq!(read(alias_book(&new)), ALIASES);
+}
+
+#[tokio::test]
+async fn an_older_layout_is_upgraded_on_the_way_in() {
+    let api = MockServer::start().await;
+    let old = Sandbox::new();
+    // Version 1 of the prompt history: bare strings, no profile
+    write(
+        history_file(&old),
+        &json!({ "satellite_name": ["ISS (ZARYA)", "NOAA 19"] }).to_string(),
+    );
+    let file = export(&old, &api, &[]);
+    let new = Sandbox::new();
+
+    cli(&new, &api, &["backup", "import", file.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(contains(
+            "The prompt history is version 1; it is upgraded to version 2 on the way in",
+        ));
+    let history: Value = serde_json::from_str(&read(history_file(&new))).expect("JSON history");
+    assert_eq!(history["version"], 2);
+    assert_eq!(
+        history["prompts"]["satellite_name"],
+        json!([{ "value": "ISS (ZARYA)" }, { "value": "NOAA 19" }])
+    );
+}
+
+#[tokio::test]
+async fn a_question_without_a_terminal_fails_before_anything_is_written() {
+    let api = MockServer::start().await;
+    let old = old_laptop();
+    let file = export(&old, &api, &[]);
+    let new = Sandbox::new();
+    let here = "[profiles.home]\nbase_url = \"http://home.example\"\n";
+    write(config_file(&new), here);
+    write(
+        alias_book(&new),
+        "version = 1\n[noaa19]\nnorad_id = 33591\n",
+    );
+
+    cli(
+        &new,
+        &api,
+        &[
+            "backup",
+            "import",
+            file.to_str().unwrap(),
+            "--config",
+            "overwrite",
+        ],
+    )
+    .assert()
+    .failure()
+    .stderr(contains("--aliases <skip|overwrite|merge>"));
+    // The config file was to be overwritten, but nothing is written until every
+    // category is settled
+    assert_eq!(read(config_file(&new)), here);
+    assert!(!history_file(&new).exists());
+}
+
+#[tokio::test]
+async fn a_damaged_archive_is_refused() {
+    let api = MockServer::start().await;
+    let old = old_laptop();
+    let file = export(&old, &api, &[]);
+    let bytes = std::fs::read(&file).expect("reading the backup");
+    std::fs::write(&file, &bytes[..bytes.len() / 2]).expect("cutting the backup short");
+    let new = Sandbox::new();
+
+    cli(&new, &api, &["backup", "import", file.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(contains("Restoring the backup failed"))
+        .stderr(contains("cut short"));
+    assert!(!config_file(&new).exists());
+}
+
+#[tokio::test]
+async fn an_existing_file_is_not_replaced_without_force() {
+    let api = MockServer::start().await;
+    let old = old_laptop();
+    let file = export(&old, &api, &[]);
+
+    cli(&old, &api, &["backup", "export", file.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(contains("already exists (pass --force to overwrite it)"))
+        .stderr(contains("Backed up").not());
+}
@@ -0,0 +1,129 @@
+//! Tracking jobs whose frequencies sit in a band allocated the other way round: the
+//! allocation table behind the warning, and the confirmation it asks for
+
+mod common;
+
+use common::{created, start_date, station, Sandbox, ENTER};
+use wiremock::matchers::{method, path};
+use wiremock::Mock;
+
+const GEOSAT: &str = "GEOSAT
+1 36516U 10016A   26287.50000000 -.00000276  00000-0  00000+0 0  9994
+2 36516   0.0300  90.0000 0002000  10.0000 100.0000  1.00270000 50004
+";
+
+/// An alias with `rx` as its RX preset, its elements in the TLE cache
+fn prepare(sandbox: &Sandbox, api: &impl common::Api, rx: &str) {
+    assert_cmd::Command::from_std(sandbox.command(
+        api,
+        &["sat", "set-freq", "geo", "--norad-id", "36516", "--rx", rx],
+    ))
+    .assert()
+    .success();
+    let dir = sandbox.path().join("cache/rustar/tle");
+    std::fs::create_dir_all(&dir).expect("creating the TLE cache");
+    std::fs::write(dir.join("36516.tle"), GEOSAT).expect("writing the cached elements");
+}
+
+/// `jobs add` for the alias, up to the point the job is checked, with `tx` as the uplink
+fn add_tracking(
+    sandbox: &Sandbox,
+    api: &impl common::Api,
+    tx: &str,
+    extra: &[&str],
+) -> common::Terminal {
+    let mut args = vec![
+        "jobs",
+        "add",
+        "--satellite",
+        "geo",
+        "--polarization",
+        "rhcp",
+        "--post-pass-action",
+        "park",
+    ];
+    args.extend_from_slice(extra);
+    let mut terminal = sandbox.spawn(api, &args);
+    let date = start_date();
+    terminal
+        .answer("Start date:", &date)
+        .answer("Start time:", "12:00")
+        .answer("End date:", &date)
+        .answer("End time:", "12:10")
+        .expect("RX frequency")
+        .keys(ENTER)
+        .answer("TX frequency", tx);
+    terminal
+}
+
+async fn expect_jobs(api: &wiremock::MockServer, times: u64) {
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(created(7))
+        .expect(times)
+        .mount(api)
+        .await;
+}
+
+#[tokio::test]
+async fn a_downlink_in_an_uplink_band_asks_first() {
+    let api = station().await;
+    expect_jobs(&api, 0).await;
+    let sandbox = Sandbox::new();
+    prepare(&sandbox, &api, "401.65M");
+
+    let mut terminal = add_tracking(&sandbox, &api, "0", &[]);
+    terminal
+        .expect("RX 401.65 MHz is in 401 MHz")
+        .expect("meteorological-satellite data collection")
+        .expect("allocated Earth-to-space")
+        .answer("Keep these frequencies?", "n");
+    let (output, code) = terminal.finish();
+    assert!(output.contains("Job not submitted"), "{}", output);
+    assert_eq!(code, 0);
+}
+
+#[tokio::test]
+async fn an_uplink_in_a_downlink_band_is_named() {
+    let api = station().await;
+    expect_jobs(&api, 1).await;
+    let sandbox = Sandbox::new();
+    prepare(&sandbox, &api, "145.8M");
+
+    let mut terminal = add_tracking(&sandbox, &api, "137.1M", &[]);
+    terminal
+        .expect("TX 137.1 MHz is in 137 MHz")
+        .expect("allocated space-to-Earth")
+        .answer("Keep these frequencies?", "y")
+        .expect("Job submitted successfully");
+    let (_, code) = terminal.finish();
+    assert_eq!(code, 0);
+}
+
+#[tokio::test]
+async fn amateur_satellite_bands_draw_no_hint() {
+    let api = station().await;
+    expect_jobs(&api, 1).await;
+    let sandbox = Sandbox::new();
+    prepare(&sandbox, &api, "437.5M");
+
+    let mut terminal = add_tracking(&sandbox, &api, "145.9M", &[]);
+    terminal.expect("Job submitted successfully");
+    let (output, code) = terminal.finish();
+    assert!(!output.contains("Keep these frequencies"), "{}", output);
+    assert_eq!(code, 0);
+}
+
+#[tokio::test]
+async fn no_band_hints_skips_the_check() {
+    let api = station().await;
+    expect_jobs(&api, 1).await;
+    let sandbox = Sandbox::new();
+    prepare(&sandbox, &api, "401.65M");
+
+    let mut terminal = add_tracking(&sandbox, &api, "0", &["--no-band-hints"]);
+    terminal.expect("Job submitted successfully");
+    let (output, code) = terminal.finish();
+    assert!(!output.contains("allocated Earth-to-space"), "{}", output);
+    assert_eq!(code, 0);
+}
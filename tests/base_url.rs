@@ -0,0 +1,174 @@
+//! Base URLs beyond host and port: IPv6 literals, path prefixes and trailing slashes reach
+//! the server as configured, and one that cannot work is refused with what is wrong
+
+mod common;
+
+use common::{cli, created, Sandbox};
+use predicates::str::contains;
+use serde_json::Value;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// A base URL given as it is, for the server `api` runs on
+struct At(String);
+
+impl common::Api for At {
+    fn uri(&self) -> String {
+        self.0.clone()
+    }
+}
+
+/// A station listening on the IPv6 loopback
+async fn ipv6_station() -> MockServer {
+    let listener = std::net::TcpListener::bind("[::1]:0").expect("binding the IPv6 loopback");
+    MockServer::builder().listener(listener).start().await
+}
+
+/// Serve the job list and take jobs under `prefix`
+async fn mount(api: &MockServer, prefix: &str) {
+    Mock::given(method("GET"))
+        .and(path(format!("{}/jobs", prefix)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+        .mount(api)
+        .await;
+    Mock::given(method("POST"))
+        .and(path(format!("{}/jobs", prefix)))
+        .respond_with(created(4))
+        .mount(api)
+        .await;
+}
+
+fn batch(sandbox: &Sandbox) -> String {
+    let start = chrono::Utc::now() + chrono::Duration::days(2);
+    let file = sandbox.path().join("jobs.json");
+    let job = serde_json::json!({
+        "job_type": "test",
+        "start": start.to_rfc3339(),
+        "end": (start + chrono::Duration::minutes(10)).to_rfc3339(),
+        "rx_frequency": 437_500_000,
+        "tx_frequency": 0,
+    });
+    std::fs::write(&file, Value::Array(vec![job]).to_string()).expect("writing the batch");
+    file.display().to_string()
+}
+
+async fn paths(api: &MockServer) -> Vec<String> {
+    api.received_requests()
+        .await
+        .unwrap_or_default()
+        .iter()
+        .map(|r| format!("{} {}", r.method, r.url.path()))
+        .collect()
+}
+
+#[tokio::test]
+async fn an_ipv6_literal_reaches_the_server() {
+    let api = ipv6_station().await;
+    mount(&api, "").await;
+    let sandbox = Sandbox::new();
+    assert!(api.uri().starts_with("http://[::1]:"), "{}", api.uri());
+
+    cli(&sandbox, &api, &["jobs", "list"]).assert().success();
+    cli(
+        &sandbox,
+        &api,
+        &["jobs", "add", "--batch", &batch(&sandbox)],
+    )
+    .assert()
+    .success();
+    let seen = paths(&api).await;
+    assert!(seen.contains(&"GET /jobs".to_string()), "{:?}", seen);
+    assert!(seen.contains(&"POST /jobs".to_string()), "{:?}", seen);
+}
+
+#[tokio::test]
+async fn an_ipv6_literal_with_a_path_prefix_keeps_both() {
+    let api = ipv6_station().await;
+    mount(&api, "/groundstation/api").await;
+    let sandbox = Sandbox::new();
+    let base = At(format!("{}/groundstation/api/", api.uri()));
+
+    cli(
+        &sandbox,
+        &base,
+        &["jobs", "add", "--batch", &batch(&sandbox)],
+    )
+    .assert()
+    .success()
+    .stderr(contains(format!(
+        "Submitting job to: {}/groundstation/api/jobs",
+        api.uri()
+    )));
+    let seen = paths(&api).await;
+    assert!(
+        seen.contains(&"POST /groundstation/api/jobs".to_string()),
+        "{:?}",
+        seen
+    );
+}
+
+#[tokio::test]
+async fn a_path_prefix_is_kept_with_or_without_a_trailing_slash() {
+    for slash in ["", "/"] {
+        let api = MockServer::start().await;
+        mount(&api, "/groundstation/api").await;
+        let sandbox = Sandbox::new();
+        let base = At(format!("{}/groundstation/api{}", api.uri(), slash));
+
+        cli(&sandbox, &base, &["jobs", "list"]).assert().success();
+        cli(
+            &sandbox,
+            &base,
+            &["jobs", "add", "--batch", &batch(&sandbox)],
+        )
+        .assert()
+        .success();
+        let seen = paths(&api).await;
+        assert!(
+            seen.iter().all(|p| p.contains(" /groundstation/api/")),
+            "{:?}",
+            seen
+        );
+        assert!(!seen.iter().any(|p| p.contains("//")), "{:?}", seen);
+    }
+}
+
+#[tokio::test]
+async fn the_base_url_is_shown_as_parsed() {
+    let api = MockServer::start().await;
+    let sandbox = Sandbox::new();
+
+    cli(
+        &sandbox,
+        &At("http://[FD00::12]:3000/groundstation/api/".to_string()),
+        &["config", "list"],
+    )
+    .assert()
+    .success()
+    .stdout(contains("http://[fd00::12]:3000/groundstation/api"));
+    assert!(paths(&api).await.is_empty());
+}
+
+#[test]
+fn an_unusable_base_url_names_the_value_and_the_problem() {
+    let sandbox = Sandbox::new();
+    for (url, problem) in [
+        (
+            "http://[fd00::12:3000",
+            "IPv6 address in brackets is not valid",
+        ),
+        ("http://fd00::12:3000", "goes in brackets"),
+        (
+            "groundstation.local:3000",
+            "the scheme is groundstation.local",
+        ),
+        ("http://host:70000", "the port is not a number"),
+    ] {
+        cli(&sandbox, &At(url.to_string()), &["jobs", "list"])
+            .assert()
+            .failure()
+            .stderr(contains(format!("base URL '{}'", url)))
+            .stderr(contains(problem))
+            .stderr(contains("flag --base-url"));
+    }
+}
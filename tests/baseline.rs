@@ -0,0 +1,94 @@
+//! Anomaly hints: the summary before an interactive submission points out values that
+//! differ from the earlier jobs for the same satellite, once there are three of them
+
+mod common;
+
+use common::{add_loopback, cli, created, start_date, station, Sandbox};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer};
+
+async fn accepting() -> MockServer {
+    let api = station().await;
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(created(4))
+        .mount(&api)
+        .await;
+    api
+}
+
+/// Submit `count` loopback tests on 145.8 MHz as a batch
+fn earlier_jobs(sandbox: &Sandbox, api: &MockServer, count: usize) {
+    let start = chrono::Utc::now() + chrono::Duration::days(3);
+    let jobs: Vec<serde_json::Value> = (0..count)
+        .map(|n| {
+            let start = start + chrono::Duration::hours(n as i64);
+            serde_json::json!({
+                "job_type": "test",
+                "start": start.to_rfc3339(),
+                "end": (start + chrono::Duration::minutes(10)).to_rfc3339(),
+                "rx_frequency": 145_800_000,
+                "tx_frequency": 0,
+            })
+        })
+        .collect();
+    let file = sandbox.path().join("jobs.json");
+    std::fs::write(&file, serde_json::Value::from(jobs).to_string())
+        .expect("writing the batch file");
+    cli(
+        sandbox,
+        api,
+        &["jobs", "add", "--batch", file.to_str().unwrap()],
+    )
+    .assert()
+    .success();
+}
+
+/// Add a loopback test on 437.5 MHz at the prompts and return what the terminal showed
+/// after the last answer
+fn add_interactively(sandbox: &Sandbox, api: &MockServer, flags: &[&str]) -> String {
+    let mut args = flags.to_vec();
+    args.extend(["jobs", "add"]);
+    let mut terminal = sandbox.spawn(api, &args);
+    add_loopback(&mut terminal, &start_date());
+    let (screen, code) = terminal.finish();
+    assert_eq!(code, 0, "{}", screen);
+    assert!(screen.contains("Job submitted successfully"), "{}", screen);
+    screen
+}
+
+#[tokio::test]
+async fn a_frequency_unlike_the_earlier_jobs_is_pointed_out() {
+    let api = accepting().await;
+    let sandbox = Sandbox::new();
+    earlier_jobs(&sandbox, &api, 3);
+
+    let screen = add_interactively(&sandbox, &api, &[]);
+    // The terminal's bytes are read one to a character, so the dash is left out
+    assert!(screen.contains("RX 437.5 MHz"), "{}", screen);
+    assert!(
+        screen.contains("the previous 3 jobs used 145.8 MHz"),
+        "{}",
+        screen
+    );
+}
+
+#[tokio::test]
+async fn two_earlier_jobs_give_no_hints() {
+    let api = accepting().await;
+    let sandbox = Sandbox::new();
+    earlier_jobs(&sandbox, &api, 2);
+
+    let screen = add_interactively(&sandbox, &api, &[]);
+    assert!(!screen.contains("the previous"), "{}", screen);
+}
+
+#[tokio::test]
+async fn the_hints_can_be_turned_off() {
+    let api = accepting().await;
+    let sandbox = Sandbox::new();
+    earlier_jobs(&sandbox, &api, 3);
+
+    let screen = add_interactively(&sandbox, &api, &["--no-anomaly-hints"]);
+    assert!(!screen.contains("the previous"), "{}", screen);
+}
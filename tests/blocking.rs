@@ -0,0 +1,105 @@
+//! `BlockingApiClient` against the library's mock station: the async client's requests,
+//! checks and errors, called from plain synchronous code
+
+mod common;
+
+use chrono::{Duration, SubsecRound, Utc};
+use common::{Api, LibraryStation};
+use rustar_cli::blocking::BlockingApiClient;
+use rustar_cli::client::{ApiClient, JobType, Timeouts};
+use rustar_cli::error::CliError;
+use rustar_cli::frequency::Frequency;
+use rustar_cli::job_request::{JobRequest, JobRequestBuilder};
+
+fn client(uri: &str) -> BlockingApiClient {
+    let timeouts = Timeouts {
+        request: std::time::Duration::from_secs(5),
+        explicit: true,
+        idle: std::time::Duration::from_secs(5),
+    };
+    let client = ApiClient::builder(uri, timeouts)
+        .build()
+        .expect("an API client");
+    BlockingApiClient::new(client).expect("a blocking client")
+}
+
+/// A ten-minute loopback test starting `hours` from now
+fn loopback(hours: i64) -> JobRequest {
+    let start = Utc::now().trunc_subsecs(0) + Duration::hours(hours);
+    JobRequestBuilder::new(start, start + Duration::minutes(10), JobType::Test)
+        .rx_frequency(Frequency::from_hz(437_500_000))
+        .build()
+        .expect("a valid job")
+}
+
+#[test]
+fn a_job_added_is_listed_and_fetched() {
+    let station = LibraryStation::start(None);
+    let api = client(&station.uri());
+
+    api.ping().expect("the station answers");
+    let created = api.add_job(&loopback(24)).expect("the job is accepted");
+    let id = created.id.expect("an ID for the job");
+
+    let jobs = api.list_jobs().expect("a job list");
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0].id.as_deref(), Some(id.as_str()));
+    assert_eq!(api.job_json(&id).expect("the job")["job_type"], "test");
+}
+
+#[test]
+fn an_overlapping_job_is_the_same_conflict_the_async_client_reports() {
+    let station = LibraryStation::start(None);
+    let api = client(&station.uri());
+
+    api.add_job(&loopback(24))
+        .expect("the first job is accepted");
+    let error = api
+        .add_job(&loopback(24))
+        .expect_err("the overlap is refused");
+    assert!(matches!(error, CliError::Conflict { .. }), "{:?}", error);
+    assert_eq!(api.list_jobs().expect("a job list").len(), 1);
+}
+
+#[test]
+fn a_cancelled_job_can_be_restored() {
+    let station = LibraryStation::start(None);
+    let api = client(&station.uri());
+    let id = api
+        .add_job(&loopback(24))
+        .expect("the job is accepted")
+        .id
+        .expect("an ID for the job");
+
+    api.cancel_job(&id, None, None)
+        .expect("the job is cancelled");
+    assert!(api.list_jobs().expect("a job list").is_empty());
+    api.restore_job(&id).expect("the job is restored");
+    assert_eq!(api.list_jobs().expect("a job list").len(), 1);
+}
+
+#[test]
+fn the_station_describes_itself() {
+    let station = LibraryStation::start(None);
+    let api = client(&station.uri());
+
+    let info = api.station_info().expect("station info");
+    assert!(info.name.is_some(), "{:?}", info);
+}
+
+#[test]
+fn a_station_that_is_not_there_is_refused_with_the_async_hint() {
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("a free port")
+        .port();
+    let api = client(&format!("http://127.0.0.1:{}", port));
+
+    let error = api.list_jobs().expect_err("nothing listens there");
+    let hint = error.hint().unwrap_or_default();
+    assert!(
+        hint.contains(&format!("running on port {} of 127.0.0.1", port)),
+        "{:?}",
+        error
+    );
+}
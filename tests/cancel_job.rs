@@ -0,0 +1,90 @@
+//! `jobs cancel`: nothing is deleted until the operator says so
+
+mod common;
+
+use common::{loopback_job, station, Sandbox, ENTER};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// A station holding job 7, with deletes expected `deletes` times
+async fn holding_job(deletes: u64) -> MockServer {
+    let api = station().await;
+    Mock::given(method("GET"))
+        .and(path("/jobs/7"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(loopback_job("7")))
+        .mount(&api)
+        .await;
+    Mock::given(method("DELETE"))
+        .and(path("/jobs/7"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "status": "cancelled",
+        })))
+        .expect(deletes)
+        .mount(&api)
+        .await;
+    api
+}
+
+#[tokio::test]
+async fn cancels_once_confirmed() {
+    let api = holding_job(1).await;
+    let sandbox = Sandbox::new();
+    let mut terminal = sandbox.spawn(&api, &["jobs", "cancel", "7"]);
+    terminal
+        .answer("Cancel 1 job(s)?", "y")
+        .expect("Reason for cancelling:")
+        .keys(ENTER)
+        .answer("Details:", "");
+    let (output, code) = terminal.finish();
+    assert_eq!(code, 0, "{}", output);
+    assert!(
+        output.contains("7 (loopback test): cancelled"),
+        "{}",
+        output
+    );
+}
+
+#[tokio::test]
+async fn keeps_the_job_when_declined() {
+    let api = holding_job(0).await;
+    let sandbox = Sandbox::new();
+    let mut terminal = sandbox.spawn(&api, &["jobs", "cancel", "7"]);
+    terminal
+        .answer("Cancel 1 job(s)?", "n")
+        .expect("Nothing cancelled");
+    let (_, code) = terminal.finish();
+    assert_eq!(code, 0);
+}
+
+#[test]
+fn asks_for_yes_without_a_terminal() {
+    let runtime = tokio::runtime::Runtime::new().expect("starting a runtime");
+    let api = runtime.block_on(holding_job(0));
+    let sandbox = Sandbox::new();
+    let mut command =
+        assert_cmd::Command::from_std(sandbox.command(&api, &["jobs", "cancel", "7"]));
+    command
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("--yes"));
+    drop(runtime);
+}
+
+#[test]
+fn points_the_old_name_at_the_new_one() {
+    let runtime = tokio::runtime::Runtime::new().expect("starting a runtime");
+    let api = runtime.block_on(async {
+        let api = station().await;
+        Mock::given(method("GET"))
+            .and(path("/jobs"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+            .mount(&api)
+            .await;
+        api
+    });
+    let sandbox = Sandbox::new();
+    assert_cmd::Command::from_std(sandbox.command(&api, &["list-jobs"]))
+        .assert()
+        .stderr(predicates::str::contains("use rustar-cli jobs list"));
+    drop(runtime);
+}
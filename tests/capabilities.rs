@@ -0,0 +1,146 @@
+//! Scheduler limits from `GET /station/capabilities`: the lead time a job needs and the
+//! cap on pending jobs, checked before anything is sent
+
+mod common;
+
+use common::{add_loopback, cli, created, loopback_job, start_date, Sandbox};
+use predicates::prelude::PredicateBooleanExt;
+use predicates::str::contains;
+use serde_json::{json, Value};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// A station describing itself as `info`, reporting `capabilities` when given, and
+/// taking `jobs` submissions
+async fn station(info: Value, capabilities: Option<Value>, jobs: u64) -> MockServer {
+    let api = common::station().await;
+    // Answers before the shared empty description
+    Mock::given(method("GET"))
+        .and(path("/station"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(info))
+        .with_priority(1)
+        .mount(&api)
+        .await;
+    if let Some(capabilities) = capabilities {
+        Mock::given(method("GET"))
+            .and(path("/station/capabilities"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(capabilities))
+            .mount(&api)
+            .await;
+    }
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(created(1))
+        .expect(jobs)
+        .mount(&api)
+        .await;
+    api
+}
+
+/// `count` receive-only loopback tests, the first starting `lead` from now
+fn write_batch(sandbox: &Sandbox, lead: chrono::Duration, count: i64) -> String {
+    let first = chrono::Utc::now() + lead;
+    let jobs: Vec<Value> = (0..count)
+        .map(|i| {
+            let start = first + chrono::Duration::minutes(20 * i);
+            json!({
+                "job_type": "test",
+                "start": start.to_rfc3339(),
+                "end": (start + chrono::Duration::minutes(10)).to_rfc3339(),
+                "rx_frequency": 437_500_000,
+                "tx_frequency": 0,
+            })
+        })
+        .collect();
+    let file = sandbox.path().join("jobs.json");
+    std::fs::write(&file, Value::from(jobs).to_string()).expect("writing the batch file");
+    file.display().to_string()
+}
+
+#[tokio::test]
+async fn a_job_inside_the_lead_time_is_refused_with_the_requirement() {
+    let api = station(json!({}), Some(json!({ "min_lead_seconds": 60 })), 0).await;
+    let sandbox = Sandbox::new();
+    let file = write_batch(&sandbox, chrono::Duration::seconds(30), 1);
+
+    cli(
+        &sandbox,
+        &api,
+        &["--explain", "jobs", "add", "--batch", &file],
+    )
+    .assert()
+    .failure()
+    .stderr(contains("the station accepts jobs at least 60s"))
+    .stderr(contains("scheduling lead 60s (reported by the server at"))
+    .stderr(contains("/station/capabilities)"));
+}
+
+#[tokio::test]
+async fn entries_past_the_free_slots_are_refused() {
+    let api = station(
+        json!({ "queue_depth": 48 }),
+        Some(json!({ "max_pending_jobs": 50 })),
+        0,
+    )
+    .await;
+    let sandbox = Sandbox::new();
+    let file = write_batch(&sandbox, chrono::Duration::days(2), 3);
+
+    cli(&sandbox, &api, &["jobs", "add", "--batch", &file])
+        .assert()
+        .failure()
+        .stderr(contains("Station queue 48/50"))
+        .stderr(contains(
+            "[2] queue: the station queue is full: 48 of at most 50 jobs are pending",
+        ))
+        .stderr(contains("[1] queue").not());
+}
+
+#[tokio::test]
+async fn the_listed_jobs_count_when_the_station_gives_no_depth() {
+    let api = station(json!({}), Some(json!({ "max_pending_jobs": 2 })), 0).await;
+    Mock::given(method("GET"))
+        .and(path("/jobs"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(json!([loopback_job("1"), loopback_job("2")])),
+        )
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+    let file = write_batch(&sandbox, chrono::Duration::days(3), 1);
+
+    cli(&sandbox, &api, &["jobs", "add", "--batch", &file])
+        .assert()
+        .failure()
+        .stderr(contains("2 of at most 2 jobs are pending"));
+}
+
+#[tokio::test]
+async fn the_summary_shows_the_queue() {
+    let api = station(
+        json!({ "queue_depth": 48 }),
+        Some(json!({ "max_pending_jobs": 50, "min_lead_seconds": 60 })),
+        1,
+    )
+    .await;
+    let sandbox = Sandbox::new();
+    let mut terminal = sandbox.spawn(&api, &["jobs", "add"]);
+    add_loopback(&mut terminal, &start_date());
+    terminal
+        .expect("Station queue 48/50")
+        .expect("Job submitted successfully");
+    let (_, code) = terminal.finish();
+    assert_eq!(code, 0);
+}
+
+#[tokio::test]
+async fn without_the_endpoint_nothing_changes() {
+    let api = station(json!({ "queue_depth": 500 }), None, 1).await;
+    let sandbox = Sandbox::new();
+    let file = write_batch(&sandbox, chrono::Duration::seconds(30), 1);
+
+    cli(&sandbox, &api, &["jobs", "add", "--batch", &file])
+        .assert()
+        .success()
+        .stderr(contains("Station queue").not());
+}
@@ -0,0 +1,227 @@
+//! Chained jobs: `jobs add --after` starting a job when another ends, and `jobs rebase`
+//! moving one back behind the job it follows
+
+mod common;
+
+use common::{cli, created, loopback_job, station, Sandbox};
+use predicates::str::contains;
+use serde_json::{json, Value};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn at(value: &Value) -> chrono::DateTime<chrono::Utc> {
+    chrono::DateTime::parse_from_rfc3339(value.as_str().expect("a timestamp"))
+        .expect("an RFC 3339 timestamp")
+        .to_utc()
+}
+
+/// Within the second a server-bound timestamp is kept to
+fn assert_near(actual: chrono::DateTime<chrono::Utc>, expected: chrono::DateTime<chrono::Utc>) {
+    assert!(
+        (actual - expected).num_milliseconds().abs() < 1000,
+        "{} is not {}",
+        actual,
+        expected
+    );
+}
+
+async fn serves(api: &MockServer, id: &str, job: &Value) {
+    Mock::given(method("GET"))
+        .and(path(format!("/jobs/{}", id)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(job))
+        .mount(api)
+        .await;
+}
+
+fn chains_file(sandbox: &Sandbox) -> std::path::PathBuf {
+    sandbox.path().join("state/rustar/chains.json")
+}
+
+fn links(sandbox: &Sandbox) -> Value {
+    let contents = std::fs::read_to_string(chains_file(sandbox)).expect("reading chains.json");
+    serde_json::from_str::<Value>(&contents).expect("chains.json is JSON")["links"].clone()
+}
+
+/// Job 8 moved by `jobs rebase`: read, then read back, with the move accepted
+async fn movable(api: &MockServer, job: &Value) {
+    serves(api, "8", job).await;
+    Mock::given(method("GET"))
+        .and(path("/jobs"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+        .mount(api)
+        .await;
+    Mock::given(method("PATCH"))
+        .and(path("/jobs/8"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "status": "updated" })))
+        .expect(1)
+        .mount(api)
+        .await;
+}
+
+async fn patched_start(api: &MockServer) -> chrono::DateTime<chrono::Utc> {
+    let requests = api.received_requests().await.unwrap_or_default();
+    let patch = requests
+        .iter()
+        .find(|r| r.method.as_str() == "PATCH")
+        .expect("the job was moved");
+    let body: Value = patch.body_json().expect("the move is JSON");
+    at(&body["start"])
+}
+
+#[tokio::test]
+async fn add_after_starts_the_gap_after_the_job_ends_and_skips_the_start_prompt() {
+    let api = station().await;
+    let first = loopback_job("7");
+    serves(&api, "7", &first).await;
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(created(8))
+        .expect(1)
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+
+    let mut terminal = sandbox.spawn(&api, &["jobs", "add", "--after", "7", "--gap", "120s"]);
+    terminal
+        .expect("after job 7 (loopback test) ends")
+        .expect("Job type:")
+        .keys(common::DOWN)
+        .keys(common::DOWN)
+        .keys(common::ENTER)
+        .answer("Duration", "5m")
+        .answer("Test frequency", "437.5M")
+        .expect("Job submitted successfully");
+    let (output, code) = terminal.finish();
+    assert_eq!(code, 0);
+    assert!(!output.contains("Start date:"), "{}", output);
+
+    let requests = api.received_requests().await.unwrap_or_default();
+    let posted = requests
+        .iter()
+        .find(|r| r.method.as_str() == "POST")
+        .expect("a job was posted");
+    let body: Value = posted.body_json().expect("the job is JSON");
+    assert_near(
+        at(&body["start"]),
+        at(&first["end"]) + chrono::Duration::minutes(2),
+    );
+    assert_eq!(
+        links(&sandbox),
+        json!({ "8": { "after": "7", "gap_seconds": 120 } })
+    );
+}
+
+#[tokio::test]
+async fn add_after_a_job_that_has_ended_is_refused() {
+    let api = station().await;
+    let mut first = loopback_job("7");
+    let end = chrono::Utc::now() - chrono::Duration::hours(1);
+    first["start"] = (end - chrono::Duration::minutes(10)).to_rfc3339().into();
+    first["end"] = end.to_rfc3339().into();
+    serves(&api, "7", &first).await;
+    let sandbox = Sandbox::new();
+
+    let mut terminal = sandbox.spawn(&api, &["jobs", "add", "--after", "7"]);
+    terminal.expect("so a job after it would start in the past");
+    let (output, code) = terminal.finish();
+    assert_ne!(code, 0);
+    assert!(!output.contains("Job type:"), "{}", output);
+}
+
+#[tokio::test]
+async fn rebase_moves_the_job_behind_the_other_and_records_the_link() {
+    let api = station().await;
+    let first = loopback_job("7");
+    serves(&api, "7", &first).await;
+    let mut second = loopback_job("8");
+    second["start"] = (at(&first["end"]) + chrono::Duration::hours(3))
+        .to_rfc3339()
+        .into();
+    second["end"] = (at(&first["end"]) + chrono::Duration::hours(4))
+        .to_rfc3339()
+        .into();
+    movable(&api, &second).await;
+    let sandbox = Sandbox::new();
+
+    cli(
+        &sandbox,
+        &api,
+        &["jobs", "rebase", "8", "--after", "7", "--gap", "90s"],
+    )
+    .assert()
+    .success()
+    .stdout(contains("job 8 moves to start 1m 30s later"))
+    .stdout(contains("Job rescheduled: updated"));
+    assert_near(
+        patched_start(&api).await,
+        at(&first["end"]) + chrono::Duration::seconds(90),
+    );
+    assert_eq!(
+        links(&sandbox),
+        json!({ "8": { "after": "7", "gap_seconds": 90 } })
+    );
+}
+
+#[tokio::test]
+async fn rebase_keeps_the_gap_the_job_was_chained_with() {
+    let api = station().await;
+    let first = loopback_job("7");
+    serves(&api, "7", &first).await;
+    let mut second = loopback_job("8");
+    second["start"] = (at(&first["end"]) + chrono::Duration::hours(3))
+        .to_rfc3339()
+        .into();
+    second["end"] = (at(&first["end"]) + chrono::Duration::hours(4))
+        .to_rfc3339()
+        .into();
+    movable(&api, &second).await;
+    let sandbox = Sandbox::new();
+    std::fs::create_dir_all(sandbox.path().join("state/rustar"))
+        .expect("creating the state directory");
+    std::fs::write(
+        chains_file(&sandbox),
+        json!({ "version": 1, "links": { "8": { "after": "7", "gap_seconds": 300 } } }).to_string(),
+    )
+    .expect("writing chains.json");
+
+    cli(&sandbox, &api, &["jobs", "rebase", "8", "--after", "7"])
+        .assert()
+        .success();
+    assert_near(
+        patched_start(&api).await,
+        at(&first["end"]) + chrono::Duration::minutes(5),
+    );
+}
+
+#[tokio::test]
+async fn circular_chains_are_refused_before_any_request() {
+    let api = MockServer::start().await;
+    let sandbox = Sandbox::new();
+    std::fs::create_dir_all(sandbox.path().join("state/rustar"))
+        .expect("creating the state directory");
+    std::fs::write(
+        chains_file(&sandbox),
+        json!({
+            "version": 1,
+            "links": {
+                "7": { "after": "6", "gap_seconds": 0 },
+                "6": { "after": "8", "gap_seconds": 0 },
+            },
+        })
+        .to_string(),
+    )
+    .expect("writing chains.json");
+
+    cli(&sandbox, &api, &["jobs", "rebase", "8", "--after", "7"])
+        .assert()
+        .failure()
+        .stderr(contains("Failed to rebase job"))
+        .stderr(contains(
+            "job 8 would start after itself: 8 → after 7 → after 6 → after 8",
+        ));
+    cli(&sandbox, &api, &["jobs", "rebase", "8", "--after", "8"])
+        .assert()
+        .failure()
+        .stderr(contains("job 8 would start after itself: 8 → after 8"));
+    assert!(api.received_requests().await.unwrap_or_default().is_empty());
+}
@@ -0,0 +1,324 @@
+//! Shared pieces of the end-to-end tests: an isolated home for the binary, a scripted
+//! API, and a pseudo-terminal so the inquire prompts can be answered like a person would.
+
+// Each test crate uses its own share of the helpers
+#![allow(dead_code)]
+
+use rexpect::session::PtySession;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::time::Duration;
+use tempfile::TempDir;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// How long a test waits for expected output before failing
+const EXPECT_TIMEOUT_MS: u64 = 15_000;
+
+/// Environment the binary would otherwise pick up from the developer's machine
+const INHERITED: &[&str] = &[
+    "API_BASE_URL",
+    "COLUMNS",
+    "API_TIMEOUT_SECONDS",
+    "API_IDLE_TIMEOUT_SECONDS",
+    "EDITOR",
+    "RUSTAR_FAKE_NOW",
+    "RUSTAR_PROFILE",
+    "RUSTAR_REPRODUCIBLE",
+    "TLE_SOURCE_URL",
+    "TLE_DESIGNATOR_SOURCE_URL",
+    "VISUAL",
+];
+
+/// Keys as the terminal sends them
+pub const UP: &str = "\x1b[A";
+pub const DOWN: &str = "\x1b[B";
+pub const ENTER: &str = "\r";
+pub const BACKSPACE: &str = "\x7f";
+
+/// An API the binary can be pointed at
+pub trait Api {
+    fn uri(&self) -> String;
+}
+
+impl Api for MockServer {
+    fn uri(&self) -> String {
+        MockServer::uri(self)
+    }
+}
+
+/// The CLI's own `mock-server`, run as a child process on a free port, with its state
+/// kept in `state_file`
+pub struct MockStation {
+    child: Child,
+    uri: String,
+}
+
+impl MockStation {
+    pub fn start(state_file: &Path) -> Self {
+        let mut child = Command::new(assert_cmd::cargo::cargo_bin("rustar-cli"))
+            .args(["mock-server", "--port", "0", "--state-file"])
+            .arg(state_file)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("starting the mock server");
+        let stdout = child.stdout.take().expect("the mock server's output");
+        let mut stdout = BufReader::new(stdout);
+        let mut first = String::new();
+        stdout
+            .read_line(&mut first)
+            .expect("reading the mock server's address");
+        // It keeps printing; a closed pipe would make it exit
+        std::thread::spawn(move || std::io::copy(&mut stdout, &mut std::io::sink()));
+        let uri = first
+            .split_whitespace()
+            .find(|word| word.starts_with("http://"))
+            .unwrap_or_else(|| panic!("no address in {:?}", first))
+            .to_string();
+        Self { child, uri }
+    }
+}
+
+impl Api for MockStation {
+    fn uri(&self) -> String {
+        self.uri.clone()
+    }
+}
+
+impl Drop for MockStation {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// The same mock station served in the test's own process by the library's
+/// `mock_server::serve`, on a free port and a thread of its own, until the test exits
+pub struct LibraryStation {
+    uri: String,
+}
+
+impl LibraryStation {
+    pub fn start(state_file: Option<PathBuf>) -> Self {
+        let (address, bound) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("starting a runtime for the mock server");
+            runtime.block_on(async move {
+                let listener = tokio::net::TcpListener::bind(("127.0.0.1", 0))
+                    .await
+                    .expect("binding a port for the mock server");
+                address
+                    .send(listener.local_addr().expect("the mock server's address"))
+                    .expect("handing over the address");
+                rustar_cli::mock_server::serve(listener, state_file)
+                    .await
+                    .expect("serving the mock API");
+            });
+        });
+        let address = bound.recv().expect("the mock server's address");
+        Self {
+            uri: format!("http://{}", address),
+        }
+    }
+}
+
+impl Api for LibraryStation {
+    fn uri(&self) -> String {
+        self.uri.clone()
+    }
+}
+
+/// A throwaway home: config, cache, state and history all land in a temporary directory
+pub struct Sandbox {
+    dir: TempDir,
+}
+
+impl Sandbox {
+    pub fn new() -> Self {
+        Self {
+            dir: TempDir::new().expect("creating the sandbox directory"),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// The binary against `api`, with nothing of the developer's setup leaking in
+    pub fn command(&self, api: &impl Api, args: &[&str]) -> Command {
+        let mut command = self.without_api(&["--base-url", &api.uri()]);
+        command.args(args);
+        command
+    }
+
+    /// The binary pointed at no API, for commands that bring their own, such as `--demo`
+    pub fn without_api(&self, args: &[&str]) -> Command {
+        let mut command = Command::new(assert_cmd::cargo::cargo_bin("rustar-cli"));
+        let home = self.path();
+        command
+            .args(args)
+            .current_dir(home)
+            .env("HOME", home)
+            .env("XDG_CONFIG_HOME", home.join("config"))
+            .env("XDG_CACHE_HOME", home.join("cache"))
+            .env("XDG_STATE_HOME", home.join("state"))
+            .env("RUSTAR_CONFIG_DIR", home.join("config/rustar"))
+            .env("RUSTAR_CACHE_DIR", home.join("cache/rustar"))
+            .env("RUSTAR_STATE_DIR", home.join("state/rustar"))
+            .env("NO_COLOR", "1")
+            .env("TERM", "xterm");
+        for name in INHERITED {
+            command.env_remove(name);
+        }
+        command
+    }
+
+    /// The binary on a pseudo-terminal, so it prompts as it does for a person
+    pub fn spawn(&self, api: &impl Api, args: &[&str]) -> Terminal {
+        self.spawn_command(self.command(api, args))
+    }
+
+    /// `command`, set up by `command()` and changed as the scenario needs, on a
+    /// pseudo-terminal
+    pub fn spawn_command(&self, command: Command) -> Terminal {
+        let options = rexpect::reader::Options {
+            timeout_ms: Some(EXPECT_TIMEOUT_MS),
+            strip_ansi_escape_codes: true,
+        };
+        let session = rexpect::session::spawn_with_options(command, options)
+            .expect("starting rustar-cli on a pseudo-terminal");
+        Terminal { session }
+    }
+}
+
+/// The binary against `api`, with `assert_cmd`'s assertions on how it ends
+pub fn cli(sandbox: &Sandbox, api: &impl Api, args: &[&str]) -> assert_cmd::Command {
+    assert_cmd::Command::from_std(sandbox.command(api, args))
+}
+
+/// Write `text` as the sandbox's whole config file
+pub fn write_config(sandbox: &Sandbox, text: &str) {
+    let dir = sandbox.path().join("config/rustar");
+    std::fs::create_dir_all(&dir).expect("creating the config directory");
+    std::fs::write(dir.join("config.toml"), text).expect("writing the config file");
+}
+
+/// A config file whose default profile has `profile`'s settings
+pub fn configure(sandbox: &Sandbox, profile: &str) {
+    write_config(sandbox, &format!("[profiles.default]\n{}", profile));
+}
+
+/// A TLE data line with the checksum digit the CLI expects added to its end
+pub fn checksummed(line: &str) -> String {
+    let mut line = line.to_string();
+    line.push_str(&rustar_cli::tle::checksum(&line).to_string());
+    line
+}
+
+/// The far end of the pseudo-terminal the binary runs on
+pub struct Terminal {
+    session: PtySession,
+}
+
+impl Terminal {
+    /// Wait until `text` is on screen
+    pub fn expect(&mut self, text: &str) -> &mut Self {
+        if let Err(e) = self.session.exp_string(text) {
+            panic!("waiting for {:?}: {}", text, e);
+        }
+        self
+    }
+
+    /// Type `keys` as they are; nothing is added
+    pub fn keys(&mut self, keys: &str) -> &mut Self {
+        self.session.send(keys).expect("writing to the terminal");
+        self.session.flush().expect("writing to the terminal");
+        self
+    }
+
+    /// Wait for `prompt`, then type `answer` and Enter
+    pub fn answer(&mut self, prompt: &str, answer: &str) -> &mut Self {
+        self.expect(prompt).keys(answer).keys(ENTER)
+    }
+
+    /// Everything up to the end of the output, then the exit code
+    pub fn finish(&mut self) -> (String, i32) {
+        let output = self
+            .session
+            .exp_eof()
+            .expect("waiting for rustar-cli to exit");
+        match self.session.process().wait() {
+            Ok(rexpect::process::wait::WaitStatus::Exited(_, code)) => (output, code),
+            other => panic!("rustar-cli did not exit normally: {:?}", other),
+        }
+    }
+}
+
+/// A station that takes any job: station info and maintenance answer, and POST /jobs is
+/// left to the scenario
+pub async fn station() -> MockServer {
+    let api = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/station"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+        .mount(&api)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/maintenance"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+        .mount(&api)
+        .await;
+    api
+}
+
+/// The server's answer to an accepted job
+pub fn created(id: u64) -> ResponseTemplate {
+    ResponseTemplate::new(201).set_body_json(serde_json::json!({
+        "status": "created",
+        "id": id,
+    }))
+}
+
+/// An accepted job that is answered only after `delay`
+pub fn created_after(id: u64, delay: Duration) -> ResponseTemplate {
+    created(id).set_delay(delay)
+}
+
+/// A loopback test as `GET /jobs/{id}` returns it
+pub fn loopback_job(id: &str) -> serde_json::Value {
+    let start = chrono::Utc::now() + chrono::Duration::days(2);
+    serde_json::json!({
+        "id": id,
+        "status": "scheduled",
+        "job_type": "test",
+        "start": start.to_rfc3339(),
+        "end": (start + chrono::Duration::minutes(10)).to_rfc3339(),
+        "rx_frequency": 437_500_000,
+        "tx_frequency": 0,
+    })
+}
+
+/// A start date inside the default submit window, as the date prompt takes it
+pub fn start_date() -> String {
+    (chrono::Utc::now() + chrono::Duration::days(2))
+        .format("%Y-%m-%d")
+        .to_string()
+}
+
+/// Answer the loopback add-job prompts with a valid job starting on `date`
+pub fn add_loopback(terminal: &mut Terminal, date: &str) {
+    terminal
+        .expect("Job type:")
+        .keys(DOWN)
+        .keys(DOWN)
+        .keys(ENTER)
+        .answer("Start date:", date)
+        .answer("Start time:", "12:10")
+        .answer("Duration", "10m")
+        .answer("Test frequency", "437.5M");
+}
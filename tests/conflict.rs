@@ -0,0 +1,267 @@
+//! Changes sent against the version of a job they were made on: the precondition headers,
+//! and what happens when someone else changed the job in between
+
+mod common;
+
+use common::{cli, loopback_job, station, Sandbox, DOWN, ENTER};
+use predicates::prelude::PredicateBooleanExt;
+use predicates::str::contains;
+use serde_json::Value;
+use wiremock::matchers::{body_json, header, method, path};
+use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+
+const PATCH: &str = r#"{"rx_frequency": 437525000}"#;
+
+/// GET /jobs/7 answered with `base` tagged "v1" once, then with `base` changed by `theirs`
+/// tagged "v2", as the server holds it once another operator changed it
+async fn versions(api: &MockServer, base: &Value, theirs: Value) {
+    let mut changed = base.clone();
+    for (key, value) in theirs.as_object().expect("changed fields") {
+        changed[key] = value.clone();
+    }
+    Mock::given(method("GET"))
+        .and(path("/jobs/7"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("ETag", "\"v1\"")
+                .set_body_json(base),
+        )
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(api)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/jobs/7"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("ETag", "\"v2\"")
+                .set_body_json(changed),
+        )
+        .mount(api)
+        .await;
+}
+
+/// `method` on /jobs/7 against version `etag`, answered with `status`, expected `times`
+async fn write(api: &MockServer, verb: &str, etag: &str, status: u16, times: u64) {
+    Mock::given(method(verb))
+        .and(path("/jobs/7"))
+        .and(header("If-Match", etag))
+        .respond_with(
+            ResponseTemplate::new(status).set_body_json(serde_json::json!({ "status": "done" })),
+        )
+        .expect(times)
+        .mount(api)
+        .await;
+}
+
+/// A station where another operator moved job 7's downlink to 437.55 MHz after it was
+/// fetched, answering the first change with 412
+async fn changed_underneath() -> MockServer {
+    let api = station().await;
+    versions(
+        &api,
+        &loopback_job("7"),
+        serde_json::json!({ "rx_frequency": 437_550_000 }),
+    )
+    .await;
+    write(&api, "PATCH", "\"v1\"", 412, 1).await;
+    api
+}
+
+#[tokio::test]
+async fn an_update_is_sent_against_the_version_fetched() {
+    let api = station().await;
+    versions(&api, &loopback_job("7"), serde_json::json!({})).await;
+    write(&api, "PATCH", "\"v1\"", 200, 1).await;
+    let sandbox = Sandbox::new();
+
+    cli(&sandbox, &api, &["jobs", "update", "7", "--patch", PATCH])
+        .assert()
+        .success()
+        .stdout(contains("Job updated: done"));
+}
+
+#[tokio::test]
+async fn without_an_etag_the_jobs_updated_at_is_the_version() {
+    let api = station().await;
+    let mut job = loopback_job("7");
+    job["updated_at"] = "2026-10-10T08:00:00.250Z".into();
+    Mock::given(method("GET"))
+        .and(path("/jobs/7"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(job))
+        .mount(&api)
+        .await;
+    Mock::given(method("PATCH"))
+        .and(path("/jobs/7"))
+        // An HTTP date has commas in it, which `header` would split on
+        .and(|request: &Request| {
+            request
+                .headers
+                .get("If-Unmodified-Since")
+                .is_some_and(|value| value == "Sat, 10 Oct 2026 08:00:00 GMT")
+        })
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+
+    cli(&sandbox, &api, &["jobs", "update", "7", "--patch", PATCH])
+        .assert()
+        .success();
+}
+
+#[tokio::test]
+async fn a_conflict_shows_all_three_versions_and_aborts_without_a_terminal() {
+    let api = changed_underneath().await;
+    let sandbox = Sandbox::new();
+
+    cli(&sandbox, &api, &["jobs", "update", "7", "--patch", PATCH])
+        .assert()
+        .failure()
+        .stdout(contains("1 field(s) differ, 1 changed on both sides"))
+        .stdout(contains("BASE"))
+        .stdout(contains("437500000"))
+        .stdout(contains("437525000"))
+        .stdout(contains("437550000"))
+        .stdout(contains("both"))
+        .stdout(contains("Job updated").not())
+        .stderr(contains("nothing was sent over it"))
+        .stderr(contains("--on-conflict"));
+}
+
+#[tokio::test]
+async fn mine_sends_the_change_again_on_the_newer_version() {
+    let api = changed_underneath().await;
+    Mock::given(method("PATCH"))
+        .and(path("/jobs/7"))
+        .and(header("If-Match", "\"v2\""))
+        .and(body_json(
+            serde_json::json!({ "rx_frequency": 437_525_000 }),
+        ))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+
+    cli(
+        &sandbox,
+        &api,
+        &[
+            "jobs",
+            "update",
+            "7",
+            "--patch",
+            PATCH,
+            "--on-conflict",
+            "mine",
+        ],
+    )
+    .assert()
+    .success()
+    .stdout(contains(
+        "Sending the change again on top of the newer version",
+    ))
+    .stdout(contains("Job updated"));
+}
+
+#[tokio::test]
+async fn theirs_leaves_the_job_alone() {
+    let api = changed_underneath().await;
+    let sandbox = Sandbox::new();
+
+    cli(
+        &sandbox,
+        &api,
+        &[
+            "jobs",
+            "update",
+            "7",
+            "--patch",
+            PATCH,
+            "--on-conflict",
+            "theirs",
+        ],
+    )
+    .assert()
+    .success()
+    .stdout(contains("Job 7 left as the server now holds it"))
+    .stdout(contains("Job updated").not());
+}
+
+#[tokio::test]
+async fn the_operator_picks_at_a_terminal() {
+    let api = changed_underneath().await;
+    let sandbox = Sandbox::new();
+
+    let mut terminal = sandbox.spawn(&api, &["jobs", "update", "7", "--patch", PATCH]);
+    terminal
+        .expect("How should job 7 go on?")
+        .keys(DOWN)
+        .keys(ENTER);
+    let (screen, code) = terminal.finish();
+    assert_eq!(code, 0, "{}", screen);
+    assert!(
+        screen.contains("left as the server now holds it"),
+        "{}",
+        screen
+    );
+}
+
+#[tokio::test]
+async fn a_cancellation_is_sent_against_the_version_listed() {
+    let api = station().await;
+    versions(
+        &api,
+        &loopback_job("7"),
+        serde_json::json!({ "tx_frequency": 145_800_000 }),
+    )
+    .await;
+    write(&api, "DELETE", "\"v1\"", 412, 1).await;
+    write(&api, "DELETE", "\"v2\"", 200, 1).await;
+    let sandbox = Sandbox::new();
+
+    cli(
+        &sandbox,
+        &api,
+        &[
+            "jobs",
+            "cancel",
+            "7",
+            "--yes",
+            "--reason",
+            "weather",
+            "--on-conflict",
+            "mine",
+        ],
+    )
+    .assert()
+    .success()
+    .stdout(contains("tx_frequency"))
+    .stdout(contains("them"))
+    .stdout(contains("7 (loopback test): done"));
+}
+
+#[tokio::test]
+async fn a_conflicting_cancellation_aborts_by_default() {
+    let api = station().await;
+    versions(
+        &api,
+        &loopback_job("7"),
+        serde_json::json!({ "tx_frequency": 145_800_000 }),
+    )
+    .await;
+    write(&api, "DELETE", "\"v1\"", 412, 1).await;
+    write(&api, "DELETE", "\"v2\"", 200, 0).await;
+    let sandbox = Sandbox::new();
+
+    cli(
+        &sandbox,
+        &api,
+        &["jobs", "cancel", "7", "--yes", "--reason", "weather"],
+    )
+    .assert()
+    .failure()
+    .stderr(contains("was changed on the server since it was fetched"));
+}
@@ -0,0 +1,154 @@
+//! Response bodies that are not the JSON asked for: the error tells the status, the
+//! content-type and the start of the body, and an empty success is an empty answer
+
+mod common;
+
+use chrono::{Duration, SubsecRound, Utc};
+use common::{station, Sandbox};
+use predicates::str::contains;
+use rustar_cli::client::{ApiClient, JobType, Timeouts};
+use rustar_cli::error::CliError;
+use rustar_cli::frequency::Frequency;
+use rustar_cli::job_request::{JobRequest, JobRequestBuilder};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const PROXY_PAGE: &str = "<html><head><title>502 Bad Gateway</title></head>\
+    <body><h1>Bad Gateway</h1><p>The proxy could not reach the station.</p></body></html>";
+
+fn client(api: &MockServer) -> ApiClient {
+    let timeouts = Timeouts {
+        request: std::time::Duration::from_secs(5),
+        explicit: true,
+        idle: std::time::Duration::from_secs(5),
+    };
+    ApiClient::builder(&api.uri(), timeouts)
+        .build()
+        .expect("an API client")
+}
+
+fn loopback() -> JobRequest {
+    let start = Utc::now().trunc_subsecs(0) + Duration::days(2);
+    JobRequestBuilder::new(start, start + Duration::minutes(10), JobType::Test)
+        .rx_frequency(Frequency::from_hz(437_500_000))
+        .build()
+        .expect("a valid job")
+}
+
+async fn answering(verb: &str, route: &str, response: ResponseTemplate) -> MockServer {
+    let api = station().await;
+    Mock::given(method(verb))
+        .and(path(route))
+        .respond_with(response)
+        .mount(&api)
+        .await;
+    api
+}
+
+/// The error under the context the client adds
+fn cause(error: CliError) -> CliError {
+    match error {
+        CliError::Context { source, .. } => cause(*source),
+        other => other,
+    }
+}
+
+fn html(status: u16) -> ResponseTemplate {
+    ResponseTemplate::new(status).set_body_raw(PROXY_PAGE, "text/html; charset=utf-8")
+}
+
+#[tokio::test]
+async fn an_html_page_names_its_status_content_type_and_first_characters() {
+    let api = answering("GET", "/jobs", html(200)).await;
+    match client(&api).list_jobs().await.map_err(cause) {
+        Err(CliError::Decode {
+            status,
+            content_type,
+            snippet,
+            ..
+        }) => {
+            assert_eq!(status.as_u16(), 200);
+            assert_eq!(content_type.as_deref(), Some("text/html; charset=utf-8"));
+            assert!(
+                snippet.starts_with("<html><head><title>502 Bad Gateway"),
+                "{}",
+                snippet
+            );
+        }
+        other => panic!("expected an undecodable body, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn a_long_body_is_cut_short_in_the_error() {
+    let page = format!("<html><body>{}</body></html>", "x".repeat(1000));
+    let api = answering(
+        "GET",
+        "/jobs",
+        ResponseTemplate::new(200).set_body_raw(page, "text/html"),
+    )
+    .await;
+    match client(&api).list_jobs().await.map_err(cause) {
+        Err(CliError::Decode { snippet, .. }) => {
+            assert_eq!(snippet.chars().count(), 301, "{}", snippet);
+            assert!(snippet.ends_with('…'), "{}", snippet);
+        }
+        other => panic!("expected an undecodable body, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn truncated_json_is_an_undecodable_body() {
+    let body = r#"{"status": "created", "id": 4"#;
+    let api = answering(
+        "POST",
+        "/jobs",
+        ResponseTemplate::new(201).set_body_raw(body, "application/json"),
+    )
+    .await;
+    match client(&api).add_job(&loopback()).await.map_err(cause) {
+        Err(CliError::Decode {
+            status,
+            content_type,
+            snippet,
+            source,
+        }) => {
+            assert_eq!(status.as_u16(), 201);
+            assert_eq!(content_type.as_deref(), Some("application/json"));
+            assert_eq!(snippet, body);
+            assert!(source.is_eof(), "{}", source);
+        }
+        other => panic!("expected an undecodable body, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn an_empty_success_is_the_default_answer() {
+    let api = answering("POST", "/jobs", ResponseTemplate::new(201)).await;
+    let answer = client(&api)
+        .add_job(&loopback())
+        .await
+        .expect("an empty body is accepted");
+    assert!(answer.status.is_none());
+    assert!(answer.message.is_none());
+    assert!(answer.id.is_none());
+    assert!(answer.warnings.is_empty());
+
+    let api = answering("GET", "/jobs", ResponseTemplate::new(200)).await;
+    assert!(client(&api).list_jobs().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn the_cli_prints_what_came_back() {
+    let api = answering("GET", "/jobs/7", html(200)).await;
+    let sandbox = Sandbox::new();
+    let file = sandbox.path().join("job.json");
+    std::fs::write(&file, "{}").expect("writing the job file");
+    let args = ["jobs", "diff", "7", "--file", file.to_str().unwrap()];
+    assert_cmd::Command::from_std(sandbox.command(&api, &args))
+        .assert()
+        .failure()
+        .stderr(contains("status 200 OK"))
+        .stderr(contains("content-type text/html; charset=utf-8"))
+        .stderr(contains("<title>502 Bad Gateway</title>"));
+}
@@ -0,0 +1,178 @@
+//! --demo: a built-in station with a seeded sample schedule, read through the ordinary
+//! client; changes pretend to succeed, marked DEMO, and leave nothing behind
+
+mod common;
+
+use common::Sandbox;
+use predicates::str::contains;
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+fn demo(sandbox: &Sandbox, args: &[&str]) -> assert_cmd::Command {
+    assert_cmd::Command::from_std(sandbox.without_api(&[&["--demo"], args].concat()))
+}
+
+fn jobs(sandbox: &Sandbox, seed: &str) -> Vec<Value> {
+    let output = demo(
+        sandbox,
+        &["--demo-seed", seed, "--output", "json", "jobs", "list"],
+    )
+    .assert()
+    .success()
+    .get_output()
+    .stdout
+    .clone();
+    match serde_json::from_slice(&output).expect("a JSON job list") {
+        Value::Array(jobs) => jobs,
+        other => panic!("not a list: {}", other),
+    }
+}
+
+#[test]
+fn the_sample_schedule_is_a_dozen_jobs_across_three_satellites() {
+    let sandbox = Sandbox::new();
+
+    let listed = jobs(&sandbox, "1");
+    assert_eq!(listed.len(), 12);
+    let satellites: BTreeSet<&str> = listed
+        .iter()
+        .filter_map(|job| job["tle"]["tle0"].as_str())
+        .collect();
+    assert_eq!(satellites.len(), 3, "{:?}", satellites);
+    assert!(satellites.contains("ISS (ZARYA)"), "{:?}", satellites);
+    let ids: Vec<&str> = listed.iter().filter_map(|job| job["id"].as_str()).collect();
+    assert_eq!(ids.first(), Some(&"1"));
+}
+
+#[test]
+fn a_seed_gives_the_same_data_every_time_and_another_seed_other_data() {
+    let sandbox = Sandbox::new();
+    let windows = |jobs: Vec<Value>| -> Vec<(Value, Value)> {
+        jobs.into_iter()
+            .map(|job| (job["start"].clone(), job["tle"]["tle2"].clone()))
+            .collect()
+    };
+
+    let first = windows(jobs(&sandbox, "1"));
+    assert_eq!(first, windows(jobs(&sandbox, "1")));
+    assert_ne!(first, windows(jobs(&sandbox, "2")));
+}
+
+#[test]
+fn the_station_is_marked_demo_with_two_antennas() {
+    let sandbox = Sandbox::new();
+
+    demo(&sandbox, &["station-info"])
+        .assert()
+        .success()
+        .stdout(contains("DEMO ground station"))
+        .stdout(contains("VHF crossed yagi, UHF yagi"))
+        .stderr(contains("🎭 DEMO — sample data (seed 1)"));
+}
+
+#[test]
+fn passes_are_predicted_from_the_demo_elements() {
+    let sandbox = Sandbox::new();
+
+    demo(
+        &sandbox,
+        &["predict", "--norad-id", "33591", "--hours", "24"],
+    )
+    .assert()
+    .success()
+    .stdout(contains("pass(es) of NOAA 19"));
+}
+
+#[test]
+fn the_operators_element_source_is_left_alone() {
+    let sandbox = Sandbox::new();
+
+    demo(
+        &sandbox,
+        &["predict", "--norad-id", "33591", "--hours", "24"],
+    )
+    .env("TLE_SOURCE_URL", "http://127.0.0.1:9/{norad}")
+    .assert()
+    .success()
+    .stdout(contains("pass(es) of NOAA 19"));
+}
+
+#[test]
+fn a_change_pretends_to_succeed_and_is_gone_afterwards() {
+    let sandbox = Sandbox::new();
+    let upcoming = jobs(&sandbox, "1")
+        .into_iter()
+        .find(|job| job["status"] == "scheduled")
+        .and_then(|job| job["id"].as_str().map(str::to_string))
+        .expect("an upcoming job");
+
+    demo(&sandbox, &["jobs", "cancel", &upcoming, "--yes"])
+        .assert()
+        .success()
+        .stderr(contains("jobs cancel only pretends"))
+        .stdout(contains("cancelled"));
+
+    // A new demo starts from the sample data again
+    let again = jobs(&sandbox, "1");
+    let job = again
+        .iter()
+        .find(|job| job["id"] == upcoming.as_str())
+        .expect("the job is still listed");
+    assert_eq!(job["status"], "scheduled");
+    // Nothing was recorded in the operator's own stores
+    assert!(!sandbox.path().join("state/rustar").exists());
+}
+
+#[test]
+fn a_submission_is_answered_as_a_demo() {
+    let sandbox = Sandbox::new();
+    let start = chrono::Utc::now() + chrono::Duration::days(5);
+    let file = sandbox.path().join("job.json");
+    std::fs::write(
+        &file,
+        serde_json::json!({
+            "job_type": "test",
+            "start": start.to_rfc3339(),
+            "end": (start + chrono::Duration::minutes(10)).to_rfc3339(),
+            "rx_frequency": 437_500_000,
+            "tx_frequency": 0,
+        })
+        .to_string(),
+    )
+    .expect("writing the template");
+
+    let mut command = sandbox.without_api(&[
+        "--demo",
+        "jobs",
+        "add",
+        "--edit",
+        "--template",
+        &file.display().to_string(),
+    ]);
+    command.env("VISUAL", "true");
+    let (output, code) = sandbox.spawn_command(command).finish();
+    assert_eq!(code, 0, "{}", output);
+    assert!(output.contains("Job submitted successfully"), "{}", output);
+    assert!(
+        output.contains("nothing was sent to a real station"),
+        "{}",
+        output
+    );
+}
+
+#[test]
+fn the_demo_takes_no_base_url() {
+    let sandbox = Sandbox::new();
+
+    demo(
+        &sandbox,
+        &["--base-url", "http://localhost:3000", "jobs", "list"],
+    )
+    .assert()
+    .code(2)
+    .stderr(contains("cannot be used with"));
+    assert_cmd::Command::from_std(sandbox.without_api(&["--demo-seed", "3", "jobs", "list"]))
+        .assert()
+        .code(2)
+        .stderr(contains("--demo"));
+}
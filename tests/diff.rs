@@ -0,0 +1,67 @@
+//! `jobs diff`: instants compare by time as the server stores them, not by spelling
+
+mod common;
+
+use common::{station, Sandbox};
+use predicates::str::contains;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn job(start: &str, end: &str) -> serde_json::Value {
+    serde_json::json!({
+        "job_type": "test",
+        "start": start,
+        "end": end,
+        "rx_frequency": 437_500_000,
+        "tx_frequency": 0,
+    })
+}
+
+/// A station holding job 7 from 12:15:00.123 to 12:25:00 UTC
+async fn holding_job() -> MockServer {
+    let api = station().await;
+    let mut held = job("2026-10-20T12:15:00.123Z", "2026-10-20T12:25:00Z");
+    held["id"] = "7".into();
+    held["status"] = "scheduled".into();
+    Mock::given(method("GET"))
+        .and(path("/jobs/7"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(held))
+        .mount(&api)
+        .await;
+    api
+}
+
+fn diff(sandbox: &Sandbox, api: &MockServer, local: serde_json::Value) -> assert_cmd::Command {
+    let file = sandbox.path().join("job.json");
+    std::fs::write(&file, local.to_string()).expect("writing the job file");
+    assert_cmd::Command::from_std(sandbox.command(
+        api,
+        &["jobs", "diff", "7", "--file", file.to_str().unwrap()],
+    ))
+}
+
+#[tokio::test]
+async fn offsets_and_dropped_digits_are_not_differences() {
+    let api = holding_job().await;
+    let sandbox = Sandbox::new();
+    let local = job(
+        "2026-10-20T17:45:00.123456+05:30",
+        "2026-10-20T09:25:00.000001-03:00",
+    );
+    diff(&sandbox, &api, local)
+        .assert()
+        .success()
+        .stdout(contains("matches"));
+}
+
+#[tokio::test]
+async fn a_millisecond_apart_is_a_difference() {
+    let api = holding_job().await;
+    let sandbox = Sandbox::new();
+    let local = job("2026-10-20T17:45:00.124+05:30", "2026-10-20T12:25:00Z");
+    diff(&sandbox, &api, local)
+        .assert()
+        .failure()
+        .stdout(contains("1 difference(s)"))
+        .stdout(contains("start"));
+}
@@ -0,0 +1,215 @@
+//! jobs download: recordings fetched to local files, resumed from a `.partial` file after
+//! a broken link, checked against the server's digest, and re-checked with --verify-only
+
+mod common;
+
+use common::{cli, Sandbox};
+use predicates::prelude::PredicateBooleanExt;
+use predicates::str::contains;
+use serde_json::json;
+use std::path::Path;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// The recording job 7 produced
+const RECORDING: &[u8] = b"IQ samples of the whole pass, sent in one piece or in two";
+const ETAG: &str = "\"v1\"";
+
+fn sha256(bytes: &[u8]) -> String {
+    ring::digest::digest(&ring::digest::SHA256, bytes)
+        .as_ref()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// A server that lists `pass.iq` among job 7's results; the file itself is up to the test
+async fn server() -> MockServer {
+    let api = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/jobs/7/results"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "recordings": [{ "name": "pass.iq", "size_bytes": RECORDING.len() }],
+        })))
+        .mount(&api)
+        .await;
+    api
+}
+
+/// The whole recording, as a server sends it with `digest` for a GET without a range
+fn whole(digest: &str) -> ResponseTemplate {
+    ResponseTemplate::new(200)
+        .insert_header("etag", ETAG)
+        .insert_header("x-checksum-sha256", digest)
+        .set_body_bytes(RECORDING)
+}
+
+/// What an earlier, broken-off run left in `dir`: the first `saved` bytes and the ETag
+fn broken_off(dir: &Path, api: &MockServer, saved: &[u8], etag: &str) {
+    std::fs::create_dir_all(dir).unwrap();
+    std::fs::write(dir.join("pass.iq.partial"), saved).unwrap();
+    std::fs::write(
+        dir.join("pass.iq.partial.json"),
+        json!({ "url": format!("{}/jobs/7/results/pass.iq", api.uri()), "etag": etag }).to_string(),
+    )
+    .unwrap();
+}
+
+fn download(sandbox: &Sandbox, api: &MockServer, extra: &[&str]) -> assert_cmd::Command {
+    cli(
+        sandbox,
+        api,
+        &[&["jobs", "download", "7", "--dir", "out"], extra].concat(),
+    )
+}
+
+#[tokio::test]
+async fn a_download_is_checked_against_the_digest_and_then_named() {
+    let api = server().await;
+    Mock::given(method("GET"))
+        .and(path("/jobs/7/results/pass.iq"))
+        .respond_with(whole(&sha256(RECORDING)))
+        .expect(1)
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+
+    download(&sandbox, &api, &[])
+        .assert()
+        .success()
+        .stdout(contains("pass.iq").and(contains("SHA-256 checked")));
+
+    let out = sandbox.path().join("out");
+    assert_eq!(std::fs::read(out.join("pass.iq")).unwrap(), RECORDING);
+    assert!(!out.join("pass.iq.partial").exists());
+    assert!(!out.join("pass.iq.partial.json").exists());
+}
+
+#[tokio::test]
+async fn a_broken_off_download_asks_only_for_the_rest() {
+    let api = server().await;
+    let (saved, rest) = RECORDING.split_at(20);
+    Mock::given(method("GET"))
+        .and(path("/jobs/7/results/pass.iq"))
+        .and(header("range", "bytes=20-"))
+        .and(header("if-range", ETAG))
+        .respond_with(
+            ResponseTemplate::new(206)
+                .insert_header("etag", ETAG)
+                .insert_header(
+                    "content-range",
+                    format!("bytes 20-{}/{}", RECORDING.len() - 1, RECORDING.len()).as_str(),
+                )
+                .insert_header("x-checksum-sha256", sha256(RECORDING).as_str())
+                .set_body_bytes(rest),
+        )
+        .expect(1)
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+    let out = sandbox.path().join("out");
+    broken_off(&out, &api, saved, ETAG);
+
+    download(&sandbox, &api, &[])
+        .assert()
+        .success()
+        .stdout(contains("resumed after 20.0 B").and(contains("SHA-256 checked")));
+
+    assert_eq!(std::fs::read(out.join("pass.iq")).unwrap(), RECORDING);
+    assert!(!out.join("pass.iq.partial").exists());
+}
+
+#[tokio::test]
+async fn a_recording_that_changed_since_is_fetched_whole() {
+    let api = server().await;
+    // The ETag no longer matches, so the server ignores the range and sends everything
+    Mock::given(method("GET"))
+        .and(path("/jobs/7/results/pass.iq"))
+        .and(header("if-range", "\"v0\""))
+        .respond_with(whole(&sha256(RECORDING)))
+        .expect(1)
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+    let out = sandbox.path().join("out");
+    broken_off(&out, &api, b"bytes of an older take", "\"v0\"");
+
+    download(&sandbox, &api, &[])
+        .assert()
+        .success()
+        .stdout(contains("resumed").not());
+
+    assert_eq!(std::fs::read(out.join("pass.iq")).unwrap(), RECORDING);
+}
+
+#[tokio::test]
+async fn a_download_that_does_not_match_its_digest_is_deleted() {
+    let api = server().await;
+    Mock::given(method("GET"))
+        .and(path("/jobs/7/results/pass.iq"))
+        .respond_with(whole(&sha256(b"something else")))
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+
+    download(&sandbox, &api, &[]).assert().code(1).stderr(
+        contains("does not match the server's SHA-256 digest")
+            .and(contains("run the command again")),
+    );
+
+    let out = sandbox.path().join("out");
+    assert!(!out.join("pass.iq").exists());
+    assert!(!out.join("pass.iq.partial").exists());
+    assert!(!out.join("pass.iq.partial.json").exists());
+}
+
+#[tokio::test]
+async fn verify_only_rehashes_the_files_without_fetching_them() {
+    let api = server().await;
+    // MD5 of RECORDING, in base64 as Content-MD5 carries it
+    let md5 = {
+        use base64::Engine;
+        use md5::Digest;
+        base64::engine::general_purpose::STANDARD.encode(md5::Md5::digest(RECORDING))
+    };
+    Mock::given(method("HEAD"))
+        .and(path("/jobs/7/results/pass.iq"))
+        .respond_with(ResponseTemplate::new(200).insert_header("content-md5", md5.as_str()))
+        .expect(2)
+        .mount(&api)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/jobs/7/results/pass.iq"))
+        .respond_with(whole(&sha256(RECORDING)))
+        .expect(0)
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+    let out = sandbox.path().join("out");
+    std::fs::create_dir_all(&out).unwrap();
+
+    std::fs::write(out.join("pass.iq"), RECORDING).unwrap();
+    download(&sandbox, &api, &["--verify-only"])
+        .assert()
+        .success()
+        .stdout(contains("matches the server's MD5"));
+
+    let mut damaged = RECORDING.to_vec();
+    damaged[3] ^= 0x20;
+    std::fs::write(out.join("pass.iq"), damaged).unwrap();
+    download(&sandbox, &api, &["--verify-only"])
+        .assert()
+        .code(1)
+        .stdout(contains("does not match the server's MD5"));
+}
+
+#[tokio::test]
+async fn a_recording_the_job_does_not_have_is_refused() {
+    let api = server().await;
+    let sandbox = Sandbox::new();
+
+    download(&sandbox, &api, &["--only", "other.iq"])
+        .assert()
+        .code(1)
+        .stderr(contains("job 7 has no recording named 'other.iq'"));
+}
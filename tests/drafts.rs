@@ -0,0 +1,267 @@
+//! Drafts of jobs add: the answers of a session that died halfway are kept, offered at the
+//! next jobs add with what they hold, resumed or discarded, and gone once the job is in
+
+mod common;
+
+use common::{cli, configure, created, start_date, station, Sandbox, DOWN, ENTER};
+use predicates::str::contains;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer};
+
+async fn accepting() -> MockServer {
+    let api = station().await;
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(created(12))
+        .mount(&api)
+        .await;
+    api
+}
+
+async fn submitted(api: &MockServer) -> Vec<Value> {
+    api.received_requests()
+        .await
+        .unwrap_or_default()
+        .iter()
+        .filter(|r| r.method.as_str() == "POST" && r.url.path() == "/jobs")
+        .map(|r| r.body_json().expect("the job is JSON"))
+        .collect()
+}
+
+fn drafts_dir(sandbox: &Sandbox) -> PathBuf {
+    sandbox.path().join("state/rustar/drafts")
+}
+
+fn drafts(sandbox: &Sandbox) -> Vec<PathBuf> {
+    match std::fs::read_dir(drafts_dir(sandbox)) {
+        Ok(entries) => entries.flatten().map(|entry| entry.path()).collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// A draft file holding `job`, saved `hours_ago`
+fn write_draft(sandbox: &Sandbox, name: &str, job: Value, hours_ago: u64) -> PathBuf {
+    let dir = drafts_dir(sandbox);
+    std::fs::create_dir_all(&dir).expect("creating the drafts directory");
+    let file = dir.join(name);
+    std::fs::write(&file, Value::Array(vec![job]).to_string()).expect("writing the draft");
+    let saved = std::time::SystemTime::now() - std::time::Duration::from_secs(hours_ago * 3600);
+    std::fs::File::options()
+        .write(true)
+        .open(&file)
+        .and_then(|f| f.set_modified(saved))
+        .expect("dating the draft");
+    file
+}
+
+/// A loopback test answered up to its start
+fn loopback_started() -> Value {
+    serde_json::json!({
+        "job_type": "test",
+        "start": format!("{}T12:10:00Z", start_date()),
+    })
+}
+
+/// Kill the session at the duration prompt of a loopback test, once its start is typed in
+fn die_at_duration(sandbox: &Sandbox, api: &MockServer) {
+    let mut terminal = sandbox.spawn(api, &["jobs", "add"]);
+    terminal
+        .expect("Job type:")
+        .keys(DOWN)
+        .keys(DOWN)
+        .keys(ENTER)
+        .answer("Start date:", &start_date())
+        .answer("Start time:", "12:10")
+        .expect("Duration");
+    // Dropping the terminal ends the process as a closed terminal would
+    drop(terminal);
+}
+
+fn read(file: &Path) -> Value {
+    serde_json::from_str(&std::fs::read_to_string(file).expect("reading the draft"))
+        .expect("the draft is JSON")
+}
+
+#[tokio::test]
+async fn a_session_that_dies_leaves_a_draft_that_resumes() {
+    let api = accepting().await;
+    let sandbox = Sandbox::new();
+    die_at_duration(&sandbox, &api);
+
+    let files = drafts(&sandbox);
+    assert_eq!(files.len(), 1, "{:?}", files);
+    let draft = read(&files[0]);
+    assert_eq!(draft, Value::Array(vec![loopback_started()]));
+
+    let mut terminal = sandbox.spawn(&api, &["jobs", "add"]);
+    terminal
+        .expect("Unfinished job saved")
+        .expect("loopback test")
+        .expect("job_type: test")
+        .expect("What about this draft?")
+        .keys(ENTER)
+        .expect("Resuming")
+        // Straight to the first question not answered
+        .answer("Duration", "10m")
+        .answer("Test frequency", "437.5M")
+        .expect("Job submitted successfully");
+    let (output, code) = terminal.finish();
+    assert_eq!(code, 0, "{}", output);
+    assert!(!output.contains("Job type:"), "{}", output);
+    assert!(!output.contains("Start date:"), "{}", output);
+
+    let jobs = submitted(&api).await;
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0]["job_type"], "test");
+    assert!(
+        jobs[0]["start"]
+            .as_str()
+            .is_some_and(|start| start.starts_with(&format!("{}T12:10:00", start_date()))),
+        "{}",
+        jobs[0]
+    );
+    assert!(drafts(&sandbox).is_empty(), "{:?}", drafts(&sandbox));
+}
+
+#[tokio::test]
+async fn a_draft_is_a_batch_file_of_one_job() {
+    let api = accepting().await;
+    let sandbox = Sandbox::new();
+    die_at_duration(&sandbox, &api);
+    let file = drafts(&sandbox).remove(0);
+
+    // Unfinished, it is refused for what is missing
+    cli(
+        &sandbox,
+        &api,
+        &["jobs", "add", "--batch", &file.display().to_string()],
+    )
+    .assert()
+    .failure()
+    .stderr(contains("end"));
+
+    // Finished by hand, it goes in as it is
+    let mut job = read(&file)[0].clone();
+    job["end"] = format!("{}T12:20:00Z", start_date()).into();
+    job["rx_frequency"] = 437_500_000.into();
+    job["tx_frequency"] = 0.into();
+    std::fs::write(&file, Value::Array(vec![job]).to_string()).expect("finishing the draft");
+    cli(
+        &sandbox,
+        &api,
+        &["jobs", "add", "--batch", &file.display().to_string()],
+    )
+    .assert()
+    .success();
+    assert_eq!(submitted(&api).await.len(), 1);
+}
+
+#[tokio::test]
+async fn a_discarded_draft_is_removed_and_the_flow_starts_afresh() {
+    let api = accepting().await;
+    let sandbox = Sandbox::new();
+    let file = write_draft(&sandbox, "earlier.json", loopback_started(), 1);
+
+    let mut terminal = sandbox.spawn(&api, &["jobs", "add"]);
+    terminal
+        .expect("What about this draft?")
+        .keys(DOWN)
+        .keys(ENTER);
+    common::add_loopback(&mut terminal, &start_date());
+    terminal.expect("Job submitted successfully");
+    let (output, code) = terminal.finish();
+    assert_eq!(code, 0, "{}", output);
+    assert!(!file.exists());
+    assert!(drafts(&sandbox).is_empty(), "{:?}", drafts(&sandbox));
+}
+
+#[tokio::test]
+async fn escape_gives_the_job_and_its_draft_up() {
+    let api = accepting().await;
+    let sandbox = Sandbox::new();
+
+    let mut terminal = sandbox.spawn(&api, &["jobs", "add"]);
+    terminal
+        .expect("Job type:")
+        .keys(DOWN)
+        .keys(DOWN)
+        .keys(ENTER)
+        .answer("Start date:", &start_date())
+        .answer("Start time:", "12:10")
+        .expect("Duration")
+        .keys("\x1b");
+    let (output, code) = terminal.finish();
+    assert_ne!(code, 0, "{}", output);
+    assert!(drafts(&sandbox).is_empty(), "{:?}", drafts(&sandbox));
+}
+
+#[tokio::test]
+async fn old_drafts_are_not_offered() {
+    let api = accepting().await;
+    let sandbox = Sandbox::new();
+    let file = write_draft(&sandbox, "old.json", loopback_started(), 100);
+
+    let mut terminal = sandbox.spawn(&api, &["jobs", "add"]);
+    common::add_loopback(&mut terminal, &start_date());
+    terminal.expect("Job submitted successfully");
+    let (output, code) = terminal.finish();
+    assert_eq!(code, 0, "{}", output);
+    assert!(!output.contains("What about this draft?"), "{}", output);
+    // Ignored, not removed
+    assert!(file.exists());
+}
+
+#[tokio::test]
+async fn the_profile_sets_how_old_a_draft_may_be() {
+    let api = accepting().await;
+    let sandbox = Sandbox::new();
+    configure(&sandbox, "draft_max_age_hours = 200\n");
+    write_draft(&sandbox, "old.json", loopback_started(), 100);
+
+    let mut terminal = sandbox.spawn(&api, &["jobs", "add"]);
+    terminal.expect("What about this draft?").keys("\x1b");
+    terminal.finish();
+}
+
+#[tokio::test]
+async fn several_drafts_are_picked_from() {
+    let api = accepting().await;
+    let sandbox = Sandbox::new();
+    let date = start_date();
+    write_draft(&sandbox, "loopback.json", loopback_started(), 2);
+    let sweep = write_draft(
+        &sandbox,
+        "sweep.json",
+        serde_json::json!({
+            "job_type": "calibration",
+            "start": format!("{}T14:00:00Z", date),
+            "end": format!("{}T14:30:00Z", date),
+        }),
+        1,
+    );
+
+    let mut terminal = sandbox.spawn(&api, &["jobs", "add"]);
+    terminal
+        .expect("Unfinished jobs:")
+        .expect("calibration sweep")
+        .expect("loopback test")
+        .expect("Start a new job")
+        // The most recent first
+        .keys(ENTER)
+        .expect("What about this draft?")
+        .keys(ENTER)
+        .answer("Azimuth sweep", "0-360/30")
+        .answer("Elevation sweep", "0-90/15")
+        .expect("Job submitted successfully");
+    let (output, code) = terminal.finish();
+    assert_eq!(code, 0, "{}", output);
+
+    let jobs = submitted(&api).await;
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0]["job_type"], "calibration");
+    assert!(!sweep.exists());
+    // The other draft is still there for later
+    assert_eq!(drafts(&sandbox).len(), 1);
+}
@@ -0,0 +1,191 @@
+//! `jobs add --edit`: the job written in an editor, played here by a script that saves
+//! prepared documents in turn
+
+mod common;
+
+use common::{created, start_date, station, Sandbox};
+use serde_json::json;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer};
+
+/// On its Nth run the editor keeps what it was given as seen-N.json, saves round-N.json
+/// over it when there is one, and exits with the status in status-N when there is one
+const EDITOR: &str = r#"#!/bin/sh
+n=$(($(cat rounds 2>/dev/null || echo 0) + 1))
+echo $n > rounds
+cp "$1" "seen-$n.json"
+if [ -f "round-$n.json" ]; then cp "round-$n.json" "$1"; fi
+if [ -f "status-$n" ]; then exit "$(cat "status-$n")"; fi
+"#;
+
+/// A sandbox whose editor is the script above
+fn sandbox() -> Sandbox {
+    let sandbox = Sandbox::new();
+    let script = sandbox.path().join("editor.sh");
+    std::fs::write(&script, EDITOR).expect("writing the editor script");
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755))
+        .expect("making the editor script executable");
+    sandbox
+}
+
+fn save(sandbox: &Sandbox, name: &str, contents: &str) {
+    std::fs::write(sandbox.path().join(name), contents).expect("writing an editor round");
+}
+
+fn seen(sandbox: &Sandbox, round: u32) -> String {
+    std::fs::read_to_string(sandbox.path().join(format!("seen-{}.json", round)))
+        .unwrap_or_else(|_| panic!("the editor did not run {} times", round))
+}
+
+/// A loopback test inside the submit window
+fn loopback() -> serde_json::Value {
+    let date = start_date();
+    json!({
+        "job_type": "test",
+        "start": format!("{}T12:10:00Z", date),
+        "end": format!("{}T12:20:00Z", date),
+        "rx_frequency": "437.5M",
+        "tx_frequency": 0,
+    })
+}
+
+async fn accepting() -> MockServer {
+    let api = station().await;
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(created(7))
+        .mount(&api)
+        .await;
+    api
+}
+
+/// `jobs add --edit` and `args`, with the script as $VISUAL
+fn edit(sandbox: &Sandbox, api: &MockServer, args: &[&str]) -> (String, i32) {
+    let mut command = sandbox.command(api, &[&["jobs", "add", "--edit"], args].concat());
+    command.env("VISUAL", Path::new(".").join("editor.sh"));
+    sandbox.spawn_command(command).finish()
+}
+
+async fn posted(api: &MockServer) -> Vec<serde_json::Value> {
+    api.received_requests()
+        .await
+        .unwrap_or_default()
+        .iter()
+        .filter(|r| r.method.as_str() == "POST" && r.url.path() == "/jobs")
+        .map(|r| r.body_json().expect("the job is JSON"))
+        .collect()
+}
+
+#[tokio::test]
+async fn submits_the_job_saved_in_the_editor() {
+    let api = accepting().await;
+    let sandbox = sandbox();
+    save(&sandbox, "round-1.json", &loopback().to_string());
+
+    let (output, code) = edit(&sandbox, &api, &[]);
+    assert_eq!(code, 0, "{}", output);
+    assert!(output.contains("Job submitted successfully"), "{}", output);
+    let jobs = posted(&api).await;
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0]["job_type"], "test");
+    assert_eq!(jobs[0]["rx_frequency"], 437_500_000.0);
+
+    // The first document is a commented tracking job with the prompts' defaults
+    let first = seen(&sandbox, 1);
+    assert!(
+        first.starts_with("# A job for rustar-cli jobs add"),
+        "{}",
+        first
+    );
+    assert!(first.contains("\"job_type\": \"tracking\""), "{}", first);
+    assert!(first.contains("145800000"), "{}", first);
+}
+
+#[tokio::test]
+async fn reopens_the_editor_with_the_problems_until_the_job_is_valid() {
+    let api = accepting().await;
+    let sandbox = sandbox();
+    let mut broken = loopback();
+    broken.as_object_mut().unwrap().remove("rx_frequency");
+    save(&sandbox, "round-1.json", &broken.to_string());
+    save(&sandbox, "round-2.json", &loopback().to_string());
+
+    let (output, code) = edit(&sandbox, &api, &[]);
+    assert_eq!(code, 0, "{}", output);
+    assert!(output.contains("reopening the editor"), "{}", output);
+    let second = seen(&sandbox, 2);
+    assert!(
+        second.starts_with("# ! The job is not valid yet"),
+        "{}",
+        second
+    );
+    assert!(second.contains("# !   /rx_frequency:"), "{}", second);
+    assert_eq!(posted(&api).await.len(), 1);
+}
+
+#[tokio::test]
+async fn an_editor_that_fails_submits_nothing() {
+    let api = accepting().await;
+    let sandbox = sandbox();
+    save(&sandbox, "round-1.json", &loopback().to_string());
+    save(&sandbox, "status-1", "1");
+
+    let (output, code) = edit(&sandbox, &api, &[]);
+    assert_eq!(code, 0, "{}", output);
+    assert!(
+        output.contains("the editor exited with status 1"),
+        "{}",
+        output
+    );
+    assert!(posted(&api).await.is_empty());
+}
+
+#[tokio::test]
+async fn an_empty_save_submits_nothing() {
+    let api = accepting().await;
+    let sandbox = sandbox();
+    save(&sandbox, "round-1.json", "# nothing to schedule today\n\n");
+
+    let (output, code) = edit(&sandbox, &api, &[]);
+    assert_eq!(code, 0, "{}", output);
+    assert!(
+        output.contains("the document was saved empty"),
+        "{}",
+        output
+    );
+    assert!(posted(&api).await.is_empty());
+}
+
+#[tokio::test]
+async fn a_template_and_flags_start_the_document() {
+    let api = accepting().await;
+    let sandbox = sandbox();
+    // A batch file of one job will do
+    save(&sandbox, "base.json", &json!([loopback()]).to_string());
+
+    let (output, code) = edit(
+        &sandbox,
+        &api,
+        &["--template", "base.json", "--campaign", "field-day"],
+    );
+    assert_eq!(code, 0, "{}", output);
+    let first = seen(&sandbox, 1);
+    assert!(first.contains("\"job_type\": \"test\""), "{}", first);
+    assert!(first.contains("\"campaign\": \"field-day\""), "{}", first);
+    let jobs = posted(&api).await;
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0]["campaign"], "field-day");
+}
+
+#[tokio::test]
+async fn without_an_editor_nothing_is_opened() {
+    let api = accepting().await;
+    let sandbox = sandbox();
+    let mut terminal = sandbox.spawn(&api, &["jobs", "add", "--edit"]);
+    terminal.expect("neither is set");
+    let (_, code) = terminal.finish();
+    assert_ne!(code, 0);
+    assert!(posted(&api).await.is_empty());
+}
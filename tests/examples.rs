@@ -0,0 +1,107 @@
+//! `examples`: the topics, the `help examples` spelling, and the pointers from command help
+
+mod common;
+
+use common::{cli, station, Sandbox};
+use predicates::prelude::PredicateBooleanExt;
+use predicates::str::contains;
+
+fn stdout(sandbox: &Sandbox, api: &impl common::Api, args: &[&str]) -> String {
+    let output = cli(sandbox, api, args)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    String::from_utf8(output).expect("UTF-8 output")
+}
+
+#[tokio::test]
+async fn every_topic_is_listed() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    let listed = stdout(&sandbox, &api, &["examples"]);
+    for topic in [
+        "datetimes",
+        "durations",
+        "frequencies",
+        "batch",
+        "tle",
+        "scripting",
+    ] {
+        assert!(listed.contains(topic), "{}", listed);
+    }
+}
+
+#[tokio::test]
+async fn help_examples_shows_the_topic() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    let direct = stdout(&sandbox, &api, &["examples", "frequencies"]);
+    assert!(direct.contains("437.5 MHz"), "{}", direct);
+    assert_eq!(
+        stdout(&sandbox, &api, &["help", "examples", "frequencies"]),
+        direct
+    );
+}
+
+#[tokio::test]
+async fn the_batch_topic_carries_its_file_as_json() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    let output = stdout(&sandbox, &api, &["--output", "json", "examples", "batch"]);
+    let shown: serde_json::Value = serde_json::from_str(&output).expect("JSON output");
+    assert_eq!(shown["file"]["name"], "jobs.json");
+    let contents = shown["file"]["contents"].as_str().expect("file contents");
+    let jobs: serde_json::Value = serde_json::from_str(contents).expect("a JSON batch");
+    assert_eq!(jobs[0]["job_type"], "tracking");
+    assert!(shown["examples"]
+        .as_array()
+        .expect("an array")
+        .iter()
+        .any(|example| example["command"] == "rustar-cli jobs add --batch jobs.json"));
+}
+
+#[tokio::test]
+async fn command_help_points_at_its_topics() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    cli(&sandbox, &api, &["jobs", "--help"])
+        .assert()
+        .success()
+        .stdout(contains("rustar-cli examples batch"));
+    cli(&sandbox, &api, &["predict", "--help"])
+        .assert()
+        .success()
+        .stdout(contains("rustar-cli examples tle"));
+}
+
+#[tokio::test]
+async fn a_deprecated_name_describes_only_itself() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    let help = stdout(&sandbox, &api, &["add-job", "--help"]);
+    assert!(help.starts_with("Deprecated: use `jobs add`"), "{}", help);
+    assert!(!help.contains("man pages"), "{}", help);
+}
+
+#[test]
+fn no_client_is_built_for_a_command_that_stays_local() {
+    let sandbox = Sandbox::new();
+    assert_cmd::Command::from_std(sandbox.without_api(&["examples", "datetimes"]))
+        .env("API_TIMEOUT_SECONDS", "abc")
+        .assert()
+        .success()
+        .stderr(contains("API Client initialized").not())
+        .stderr(contains("timeout").not());
+}
+
+#[tokio::test]
+async fn an_unknown_topic_is_refused_with_the_list() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    cli(&sandbox, &api, &["examples", "antennas"])
+        .assert()
+        .failure()
+        .stderr(contains("datetimes"));
+}
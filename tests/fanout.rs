@@ -0,0 +1,124 @@
+//! `jobs add --profiles`: one loopback test scheduled at two scripted stations, each named
+//! by its own profile
+
+mod common;
+
+use common::{add_loopback, created, start_date, write_config, Sandbox};
+use serde_json::{json, Value};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// A station describing itself as `info` and answering the one job it is sent with `answer`
+async fn station(info: Value, answer: ResponseTemplate) -> MockServer {
+    let api = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/station"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(info))
+        .mount(&api)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/maintenance"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+        .mount(&api)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(answer)
+        .expect(1)
+        .mount(&api)
+        .await;
+    api
+}
+
+/// Profiles `north` and `south` pointing at the two stations
+fn configure(sandbox: &Sandbox, north: &MockServer, south: &MockServer) {
+    write_config(
+        sandbox,
+        &format!(
+            "[profiles.north]\nbase_url = \"{}\"\n[profiles.south]\nbase_url = \"{}\"\n",
+            north.uri(),
+            south.uri()
+        ),
+    );
+}
+
+/// Add the loopback test at both stations and confirm the summary
+fn fan_out(sandbox: &Sandbox, north: &MockServer) -> (String, i32) {
+    let mut terminal = sandbox.spawn(north, &["jobs", "add", "--profiles", "north,south"]);
+    add_loopback(&mut terminal, &start_date());
+    terminal
+        .expect("The job goes to 2 stations")
+        .expect("(same window)")
+        .answer("Submit to all 2 stations?", "y");
+    terminal.finish()
+}
+
+#[tokio::test]
+async fn the_job_is_scheduled_at_every_station() {
+    let north = station(json!({}), created(7)).await;
+    let south = station(json!({}), created(8)).await;
+    let sandbox = Sandbox::new();
+    configure(&sandbox, &north, &south);
+
+    let (output, code) = fan_out(&sandbox, &north);
+    assert_eq!(code, 0, "{}", output);
+    assert!(output.contains("Fan-out results"), "{}", output);
+    assert!(output.contains("job 7 (created)"), "{}", output);
+    assert!(output.contains("job 8 (created)"), "{}", output);
+}
+
+#[tokio::test]
+async fn a_station_that_refuses_leaves_the_other_job_in_place() {
+    let north = station(json!({}), created(7)).await;
+    let south = station(
+        json!({}),
+        ResponseTemplate::new(409).set_body_json(json!({ "detail": "slot taken" })),
+    )
+    .await;
+    let sandbox = Sandbox::new();
+    configure(&sandbox, &north, &south);
+
+    let (output, code) = fan_out(&sandbox, &north);
+    assert_ne!(code, 0);
+    assert!(output.contains("job 7 (created)"), "{}", output);
+    assert!(output.contains("slot taken"), "{}", output);
+    // Nothing is cancelled at the station that took the job
+    let requests = north.received_requests().await.unwrap_or_default();
+    assert!(requests.iter().all(|r| r.method.as_str() != "DELETE"));
+}
+
+#[tokio::test]
+async fn stations_on_different_api_versions_are_flagged_before_submission() {
+    let north = station(json!({ "api_version": "2" }), created(7)).await;
+    let south = station(json!({ "api_version": "1" }), created(8)).await;
+    let sandbox = Sandbox::new();
+    configure(&sandbox, &north, &south);
+
+    let mut terminal = sandbox.spawn(&north, &["jobs", "add", "--profiles", "north,south"]);
+    add_loopback(&mut terminal, &start_date());
+    terminal
+        .expect("different API versions (north 2, south 1)")
+        .answer("Submit to all 2 stations?", "y")
+        .expect("Fan-out results");
+    let (output, code) = terminal.finish();
+    assert_eq!(code, 0, "{}", output);
+}
+
+#[tokio::test]
+async fn a_profile_without_a_base_url_is_refused_before_any_prompt() {
+    let north = MockServer::start().await;
+    let sandbox = Sandbox::new();
+    write_config(
+        &sandbox,
+        &format!(
+            "[profiles.north]\nbase_url = \"{}\"\n[profiles.south]\n",
+            north.uri()
+        ),
+    );
+
+    let mut terminal = sandbox.spawn(&north, &["jobs", "add", "--profiles", "north,south"]);
+    terminal.expect("profile south sets no base_url");
+    let (output, code) = terminal.finish();
+    assert_ne!(code, 0);
+    assert!(!output.contains("Job type:"), "{}", output);
+}
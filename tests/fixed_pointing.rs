@@ -0,0 +1,193 @@
+//! Jobs held at one azimuth/elevation instead of following an element set: the flags and
+//! the prompt that build them, their bounds, and the prediction features that step aside
+
+mod common;
+
+use common::{cli, created, start_date, station, Sandbox, DOWN, ENTER};
+use predicates::str::contains;
+use serde_json::Value;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn accepting() -> MockServer {
+    let api = station().await;
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(created(9))
+        .mount(&api)
+        .await;
+    api
+}
+
+async fn submitted(api: &MockServer) -> Vec<Value> {
+    api.received_requests()
+        .await
+        .unwrap_or_default()
+        .iter()
+        .filter(|r| r.method.as_str() == "POST" && r.url.path() == "/jobs")
+        .map(|r| r.body_json().expect("the job is JSON"))
+        .collect()
+}
+
+/// A fixed-pointing job as `GET /jobs/{id}` returns it
+fn pointing_job(id: &str) -> Value {
+    let start = chrono::Utc::now() + chrono::Duration::days(2);
+    serde_json::json!({
+        "id": id,
+        "status": "scheduled",
+        "job_type": "pointing",
+        "pointing": { "azimuth": 123.4, "elevation": 45.6, "name": "ES'HAIL-2" },
+        "start": start.to_rfc3339(),
+        "end": (start + chrono::Duration::minutes(30)).to_rfc3339(),
+        "rx_frequency": 10_489_750_000u64,
+        "tx_frequency": 0,
+    })
+}
+
+/// The prompts after the target, for a job from 12:00 to 12:30 on the test date
+fn answer_window_and_frequencies(terminal: &mut common::Terminal) {
+    let date = start_date();
+    terminal
+        .answer("Start date:", &date)
+        .answer("Start time:", "12:00")
+        .answer("End date:", &date)
+        .answer("End time:", "12:30")
+        .answer("RX frequency", "10489.75M")
+        .answer("TX frequency", "0");
+}
+
+#[tokio::test]
+async fn the_flags_submit_a_pointing_target() {
+    let api = accepting().await;
+    let sandbox = Sandbox::new();
+
+    let mut terminal = sandbox.spawn(
+        &api,
+        &[
+            "jobs",
+            "add",
+            "--fixed-az",
+            "123.4",
+            "--fixed-el",
+            "45.6",
+            "--fixed-name",
+            "QO-100",
+            "--polarization",
+            "rhcp",
+            "--post-pass-action",
+            "park",
+        ],
+    );
+    terminal.expect("fixed-pointing job at az 123.4");
+    answer_window_and_frequencies(&mut terminal);
+    terminal
+        .expect("Fixed pointing at az 123.4")
+        .expect("Job submitted successfully");
+    let (output, code) = terminal.finish();
+    assert_eq!(code, 0, "{}", output);
+    assert!(!output.contains("Job type:"), "{}", output);
+    assert!(!output.contains("TLE Line"), "{}", output);
+
+    let jobs = submitted(&api).await;
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0]["job_type"], "pointing");
+    assert_eq!(
+        jobs[0]["pointing"],
+        serde_json::json!({ "azimuth": 123.4, "elevation": 45.6, "name": "QO-100" })
+    );
+    assert!(jobs[0].get("tle").is_none(), "{}", jobs[0]);
+}
+
+#[tokio::test]
+async fn the_prompt_offers_fixed_pointing_and_checks_the_angles() {
+    let api = accepting().await;
+    let sandbox = Sandbox::new();
+
+    let mut terminal = sandbox.spawn(
+        &api,
+        &[
+            "jobs",
+            "add",
+            "--polarization",
+            "rhcp",
+            "--post-pass-action",
+            "park",
+        ],
+    );
+    terminal
+        .expect("Job type:")
+        .keys(ENTER)
+        .expect("Target:")
+        .keys(DOWN)
+        .keys(ENTER)
+        .answer("Azimuth", "360")
+        // North is 0, so a full turn is refused on the spot
+        .expect("is outside 0")
+        .keys(&common::BACKSPACE.repeat(3))
+        .keys("0")
+        .keys(ENTER)
+        .answer("Elevation", "90")
+        .answer("Target name", "");
+    answer_window_and_frequencies(&mut terminal);
+    terminal.expect("Job submitted successfully");
+    let (output, code) = terminal.finish();
+    assert_eq!(code, 0, "{}", output);
+
+    let jobs = submitted(&api).await;
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(
+        jobs[0]["pointing"],
+        serde_json::json!({ "azimuth": 0.0, "elevation": 90.0 })
+    );
+}
+
+#[tokio::test]
+async fn an_elevation_below_the_horizon_is_refused() {
+    let api = accepting().await;
+    let sandbox = Sandbox::new();
+    let start = chrono::Utc::now() + chrono::Duration::days(2);
+    let file = sandbox.path().join("jobs.json");
+    let job = serde_json::json!({
+        "job_type": "pointing",
+        "pointing": { "azimuth": 123.4, "elevation": -5.0 },
+        "start": start.to_rfc3339(),
+        "end": (start + chrono::Duration::minutes(30)).to_rfc3339(),
+        "rx_frequency": 10_489_750_000u64,
+        "tx_frequency": 0,
+    });
+    std::fs::write(&file, Value::Array(vec![job]).to_string()).expect("writing the batch");
+
+    cli(
+        &sandbox,
+        &api,
+        &["jobs", "add", "--batch", &file.display().to_string()],
+    )
+    .assert()
+    .failure()
+    .stderr(contains("pointing.elevation"));
+    assert!(submitted(&api).await.is_empty());
+}
+
+#[tokio::test]
+async fn simulate_and_look_angles_step_aside_for_a_fixed_pointing() {
+    let api = station().await;
+    Mock::given(method("GET"))
+        .and(path("/jobs/5"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(pointing_job("5")))
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+    cli(&sandbox, &api, &["config", "set-location", "25.3", "51.5"])
+        .assert()
+        .success();
+
+    cli(&sandbox, &api, &["simulate", "--job-id", "5"])
+        .assert()
+        .success()
+        .stdout(contains("nothing to rehearse"));
+    cli(&sandbox, &api, &["look-angles", "5"])
+        .assert()
+        .success()
+        .stdout("")
+        .stderr(contains("no look angles to work out"));
+}
@@ -0,0 +1,90 @@
+//! Frequency display: the profile's `frequency_display` and `--freq-unit` pick the unit
+//! frequencies are printed in, exactly to the hertz, while JSON stays in hertz
+
+mod common;
+
+use common::{cli, configure, station, Sandbox};
+use predicates::prelude::PredicateBooleanExt;
+use predicates::str::contains;
+
+/// Save an alias on `rx` hertz and return what `sat list` printed with `flags`
+fn listed(sandbox: &Sandbox, api: &impl common::Api, rx: &str, flags: &[&str]) -> String {
+    cli(sandbox, api, &["sat", "set-freq", "iss", "--rx", rx])
+        .assert()
+        .success();
+    let mut args = flags.to_vec();
+    args.extend(["sat", "list"]);
+    let output = cli(sandbox, api, &args)
+        .assert()
+        .success()
+        .get_output()
+        .clone();
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[tokio::test]
+async fn auto_keeps_three_to_six_digits_before_the_point() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    configure(&sandbox, "");
+
+    let shown = listed(&sandbox, &api, "145800000", &[]);
+    assert!(shown.contains("RX 145.8 MHz"), "{}", shown);
+    let shown = listed(&sandbox, &api, "14200000", &[]);
+    assert!(shown.contains("RX 14200 kHz"), "{}", shown);
+    let shown = listed(&sandbox, &api, "2400000000", &[]);
+    assert!(shown.contains("RX 2400 MHz"), "{}", shown);
+}
+
+#[tokio::test]
+async fn each_unit_shows_the_exact_hertz() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    configure(&sandbox, "");
+
+    for (unit, expected) in [
+        ("mhz", "RX 145.8 MHz"),
+        ("khz", "RX 145800 kHz"),
+        ("hz", "RX 145800000 Hz"),
+    ] {
+        let shown = listed(&sandbox, &api, "145800000", &["--freq-unit", unit]);
+        assert!(shown.contains(expected), "{}: {}", unit, shown);
+        assert!(!shown.contains("99999"), "{}: {}", unit, shown);
+    }
+    let shown = listed(&sandbox, &api, "437502100", &["--freq-unit", "mhz"]);
+    assert!(shown.contains("RX 437.5021 MHz"), "{}", shown);
+    let shown = listed(&sandbox, &api, "145800001", &["--freq-unit", "khz"]);
+    assert!(shown.contains("RX 145800.001 kHz"), "{}", shown);
+}
+
+#[tokio::test]
+async fn the_flag_wins_over_the_profile() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    configure(&sandbox, "frequency_display = \"khz\"\n");
+
+    let shown = listed(&sandbox, &api, "145800000", &[]);
+    assert!(shown.contains("RX 145800 kHz"), "{}", shown);
+    let shown = listed(&sandbox, &api, "145800000", &["--freq-unit", "mhz"]);
+    assert!(shown.contains("RX 145.8 MHz"), "{}", shown);
+}
+
+#[tokio::test]
+async fn json_output_stays_in_hertz() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    configure(&sandbox, "frequency_display = \"khz\"\n");
+
+    cli(
+        &sandbox,
+        &api,
+        &["sat", "set-freq", "iss", "--rx", "145.8M"],
+    )
+    .assert()
+    .success();
+    cli(&sandbox, &api, &["--output", "json", "sat", "list"])
+        .assert()
+        .success()
+        .stdout(contains("145800000"))
+        .stdout(contains("kHz").not());
+}
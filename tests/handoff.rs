@@ -0,0 +1,249 @@
+//! jobs handoff and import-handoff: one job carried to a partner station with where it
+//! came from, checked against the receiving station's sky, with aged elements fetched again
+
+mod common;
+
+use common::{checksummed, configure, created, loopback_job, station, Sandbox};
+use predicates::str::contains;
+use serde_json::{json, Value};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn cli(sandbox: &Sandbox, api: &MockServer, args: &[&str]) -> assert_cmd::Command {
+    let mut command = sandbox.command(api, args);
+    command.env(
+        "TLE_SOURCE_URL",
+        format!("{}/gp.php?CATNR={{norad}}", api.uri()),
+    );
+    assert_cmd::Command::from_std(command)
+}
+
+/// Element lines of a geostationary satellite with an epoch `age_days` ago
+fn geostationary(age_days: i64) -> (String, String) {
+    let epoch = chrono::Utc::now() - chrono::Duration::days(age_days);
+    (
+        checksummed(&format!(
+            "1 36516U 10016A   {}.50000000 -.00000276  00000-0  00000+0 0  999",
+            epoch.format("%y%j")
+        )),
+        checksummed("2 36516   0.0300  90.0000 0002000  10.0000 100.0000  1.0027000050000"),
+    )
+}
+
+/// A bundle for a tracking job two days from now, handed off `age_hours` ago with
+/// elements `elements_days` old
+fn bundle(sandbox: &Sandbox, age_hours: i64, elements_days: i64) -> String {
+    let (tle1, tle2) = geostationary(elements_days);
+    let start = chrono::Utc::now() + chrono::Duration::days(2);
+    let exported = chrono::Utc::now() - chrono::Duration::hours(age_hours);
+    let file = sandbox.path().join("bundle.json");
+    std::fs::write(
+        &file,
+        json!({
+            "kind": "rustar-handoff",
+            "format": 1,
+            "provenance": {
+                "station": "north",
+                "base_url": "http://north.example",
+                "profile": "default",
+                "job_id": "12",
+                "exported_at": exported.to_rfc3339(),
+                "cli_version": "0.1.0",
+            },
+            "job": {
+                "job_type": "tracking",
+                "tle": { "tle0": "GEOSAT", "tle1": tle1, "tle2": tle2 },
+                "start": start.to_rfc3339(),
+                "end": (start + chrono::Duration::minutes(10)).to_rfc3339(),
+                "rx_frequency": 1_690_000_000,
+                "tx_frequency": 0,
+            },
+        })
+        .to_string(),
+    )
+    .expect("writing the bundle");
+    file.display().to_string()
+}
+
+async fn accepting(api: &MockServer) {
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(created(3))
+        .expect(1)
+        .mount(api)
+        .await;
+}
+
+/// The element set the TLE source hands out: current, under the bundle's name
+async fn element_source(api: &MockServer) {
+    let (tle1, tle2) = geostationary(0);
+    Mock::given(method("GET"))
+        .and(path("/gp.php"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(format!("GEO 1\n{}\n{}\n", tle1, tle2)),
+        )
+        .mount(api)
+        .await;
+}
+
+async fn submitted(api: &MockServer) -> Value {
+    let requests = api.received_requests().await.unwrap_or_default();
+    let post = requests
+        .iter()
+        .find(|r| r.method.as_str() == "POST" && r.url.path() == "/jobs")
+        .expect("the submission");
+    serde_json::from_slice(&post.body).expect("a JSON job")
+}
+
+#[tokio::test]
+async fn a_handed_off_job_is_submitted_at_the_partner_station() {
+    let north = station().await;
+    let mut stored = loopback_job("12");
+    stored["campaign"] = json!("survey");
+    stored["notes"] = json!("Use the LHCP feed");
+    Mock::given(method("GET"))
+        .and(path("/jobs/12"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(stored))
+        .mount(&north)
+        .await;
+    let sandbox = Sandbox::new();
+    let file = sandbox.path().join("bundle.json");
+
+    cli(
+        &sandbox,
+        &north,
+        &["jobs", "handoff", "12", "--out", file.to_str().unwrap()],
+    )
+    .assert()
+    .success()
+    .stderr(contains("Handed off loopback test"));
+    let bundle: Value =
+        serde_json::from_str(&std::fs::read_to_string(&file).unwrap()).expect("a JSON bundle");
+    assert_eq!(bundle["kind"], "rustar-handoff");
+    assert_eq!(bundle["provenance"]["job_id"], "12");
+    assert_eq!(bundle["provenance"]["base_url"], north.uri());
+    assert_eq!(
+        bundle["provenance"]["cli_version"],
+        env!("CARGO_PKG_VERSION")
+    );
+    assert_eq!(bundle["notes"], "Use the LHCP feed");
+    assert!(bundle["job"].get("campaign").is_none(), "{}", bundle);
+
+    let south = station().await;
+    accepting(&south).await;
+    cli(
+        &sandbox,
+        &south,
+        &["jobs", "import-handoff", file.to_str().unwrap()],
+    )
+    .assert()
+    .success()
+    .stderr(contains(format!("Job 12 from {}", north.uri())))
+    .stderr(contains("📝 Use the LHCP feed"))
+    .stdout(contains("Job submitted successfully"));
+    assert_eq!(submitted(&south).await["rx_frequency"], 437_500_000.0);
+}
+
+#[tokio::test]
+async fn a_handoff_file_is_not_replaced_without_force() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    let file = sandbox.path().join("bundle.json");
+    std::fs::write(&file, "{}").unwrap();
+
+    cli(
+        &sandbox,
+        &api,
+        &["jobs", "handoff", "12", "--out", file.to_str().unwrap()],
+    )
+    .assert()
+    .failure()
+    .stderr(contains("already exists"));
+    assert_eq!(std::fs::read_to_string(&file).unwrap(), "{}");
+}
+
+#[tokio::test]
+async fn a_pass_this_station_cannot_see_is_flagged_and_refused_under_strict() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    // Geostationary satellites stay below the horizon this close to the pole
+    configure(&sandbox, "latitude = 89.0\nlongitude = 0.0\n");
+    let file = bundle(&sandbox, 1, 0);
+
+    cli(
+        &sandbox,
+        &api,
+        &["--strict", "jobs", "import-handoff", &file],
+    )
+    .assert()
+    .code(2)
+    .stderr(contains("✖ not-visible:"))
+    .stderr(contains("covers no pass of GEOSAT"));
+}
+
+#[tokio::test]
+async fn an_old_bundle_has_its_elements_fetched_again() {
+    let api = station().await;
+    element_source(&api).await;
+    accepting(&api).await;
+    let sandbox = Sandbox::new();
+    configure(&sandbox, "handoff_max_age_hours = 48\n");
+    let file = bundle(&sandbox, 72, 1);
+
+    cli(&sandbox, &api, &["jobs", "import-handoff", &file])
+        .assert()
+        .success()
+        .stderr(contains(
+            "Fetched fresh elements for GEOSAT, as the bundle is 3d old",
+        ));
+    let job = submitted(&api).await;
+    assert_eq!(job["tle"]["tle0"], "GEOSAT");
+    assert_eq!(job["tle"]["tle1"], geostationary(0).0);
+}
+
+#[tokio::test]
+async fn stale_elements_are_fetched_again_even_in_a_new_bundle() {
+    let api = station().await;
+    element_source(&api).await;
+    accepting(&api).await;
+    let sandbox = Sandbox::new();
+    let file = bundle(&sandbox, 1, 30);
+
+    cli(&sandbox, &api, &["jobs", "import-handoff", &file])
+        .assert()
+        .success()
+        .stderr(contains("as the elements are"));
+    assert_eq!(submitted(&api).await["tle"]["tle1"], geostationary(0).0);
+}
+
+#[tokio::test]
+async fn elements_that_cannot_be_fetched_are_kept_with_a_warning() {
+    let api = station().await;
+    accepting(&api).await;
+    let sandbox = Sandbox::new();
+    let file = bundle(&sandbox, 1, 30);
+
+    cli(&sandbox, &api, &["jobs", "import-handoff", &file])
+        .assert()
+        .success()
+        .stderr(contains("Keeping the bundle's elements"))
+        .stderr(contains("The elements for GEOSAT are"));
+    assert_eq!(submitted(&api).await["tle"]["tle1"], geostationary(30).0);
+}
+
+#[tokio::test]
+async fn a_file_that_is_not_a_bundle_is_refused() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    let file = sandbox.path().join("jobs.json");
+    std::fs::write(&file, loopback_job("12").to_string()).unwrap();
+
+    cli(
+        &sandbox,
+        &api,
+        &["jobs", "import-handoff", file.to_str().unwrap()],
+    )
+    .assert()
+    .failure()
+    .stderr(contains("is not a handoff bundle"));
+}
@@ -0,0 +1,90 @@
+//! `jobs add` on a station that holds the slot while the operator reviews the job
+
+mod common;
+
+use common::{add_loopback, created, start_date, station, Sandbox};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, ResponseTemplate};
+
+/// A hold on the slot for `seconds`
+fn held(id: &str, seconds: i64) -> ResponseTemplate {
+    ResponseTemplate::new(201).set_body_json(serde_json::json!({
+        "hold_id": id,
+        "expires_at": (chrono::Utc::now() + chrono::Duration::seconds(seconds)).to_rfc3339(),
+    }))
+}
+
+async fn expect_post(api: &wiremock::MockServer, at: &str, answer: ResponseTemplate, times: u64) {
+    Mock::given(method("POST"))
+        .and(path(at))
+        .respond_with(answer)
+        .expect(times)
+        .mount(api)
+        .await;
+}
+
+#[tokio::test]
+async fn an_accepted_hold_is_confirmed() {
+    let api = station().await;
+    expect_post(&api, "/jobs/reserve", held("h-1", 300), 1).await;
+    expect_post(&api, "/jobs/h-1/confirm", created(7), 1).await;
+    expect_post(&api, "/jobs", created(7), 0).await;
+
+    let sandbox = Sandbox::new();
+    let mut terminal = sandbox.spawn(&api, &["jobs", "add"]);
+    add_loopback(&mut terminal, &start_date());
+    terminal
+        .expect("Slot held until")
+        .expect("Schedule the job? [Y/n]")
+        .keys("y")
+        .expect("Job submitted successfully");
+    let (_, code) = terminal.finish();
+    assert_eq!(code, 0);
+}
+
+#[tokio::test]
+async fn a_declined_hold_is_released() {
+    let api = station().await;
+    expect_post(&api, "/jobs/reserve", held("h-1", 300), 1).await;
+    expect_post(&api, "/jobs/h-1/confirm", created(7), 0).await;
+    Mock::given(method("DELETE"))
+        .and(path("/jobs/h-1"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&api)
+        .await;
+
+    let sandbox = Sandbox::new();
+    let mut terminal = sandbox.spawn(&api, &["jobs", "add"]);
+    add_loopback(&mut terminal, &start_date());
+    terminal.expect("Schedule the job? [Y/n]").keys("n");
+    let (output, code) = terminal.finish();
+    assert!(output.contains("Hold h-1 released"), "{}", output);
+    assert!(output.contains("Job not submitted"), "{}", output);
+    assert_eq!(code, 0);
+}
+
+#[tokio::test]
+async fn a_hold_lapsing_under_review_is_taken_again() {
+    let api = station().await;
+    // The first hold lasts two seconds, the one taken again five minutes
+    Mock::given(method("POST"))
+        .and(path("/jobs/reserve"))
+        .respond_with(held("h-1", 2))
+        .up_to_n_times(1)
+        .mount(&api)
+        .await;
+    expect_post(&api, "/jobs/reserve", held("h-2", 300), 1).await;
+    expect_post(&api, "/jobs/h-2/confirm", created(7), 1).await;
+
+    let sandbox = Sandbox::new();
+    let mut terminal = sandbox.spawn(&api, &["jobs", "add"]);
+    add_loopback(&mut terminal, &start_date());
+    terminal
+        .expect("reserving it again")
+        .expect("Slot held again until")
+        .keys("y")
+        .expect("Job submitted successfully");
+    let (_, code) = terminal.finish();
+    assert_eq!(code, 0);
+}
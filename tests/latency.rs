@@ -0,0 +1,100 @@
+//! Latency warnings for batch jobs that start within the minute, against a station that asks
+//! for a scheduling lead or answers slowly
+
+mod common;
+
+use common::{cli, configure, created, Sandbox};
+use predicates::prelude::PredicateBooleanExt;
+use predicates::str::contains;
+use serde_json::json;
+use std::time::Duration;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// A station that reports `station` for `GET /station`, answering every read after `delay`,
+/// and takes the one job it is sent
+async fn station(station: serde_json::Value, delay: Duration) -> MockServer {
+    let api = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/station"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(station)
+                .set_delay(delay),
+        )
+        .mount(&api)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/maintenance"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!([]))
+                .set_delay(delay),
+        )
+        .mount(&api)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(created(1))
+        .expect(1)
+        .mount(&api)
+        .await;
+    api
+}
+
+/// A batch file with one loopback test starting `start` from now
+fn write_batch(sandbox: &Sandbox, start: chrono::Duration) -> String {
+    let start = chrono::Utc::now() + start;
+    let job = json!({
+        "job_type": "test",
+        "start": start.to_rfc3339(),
+        "end": (start + chrono::Duration::minutes(10)).to_rfc3339(),
+        "rx_frequency": 437_500_000,
+        "tx_frequency": 0,
+    });
+    let file = sandbox.path().join("jobs.json");
+    std::fs::write(&file, json!([job]).to_string()).expect("writing the batch file");
+    file.display().to_string()
+}
+
+#[tokio::test]
+async fn a_job_inside_the_station_lead_is_warned_about_and_still_submitted() {
+    let api = station(json!({ "scheduling_lead_seconds": 120 }), Duration::ZERO).await;
+    let sandbox = Sandbox::new();
+    let file = write_batch(&sandbox, chrono::Duration::seconds(60));
+
+    cli(&sandbox, &api, &["jobs", "add", "--batch", &file])
+        .assert()
+        .success()
+        .stderr(contains("[0] Job starts in"))
+        .stderr(contains("submission typically takes"))
+        .stderr(contains("station needs 2m lead"));
+}
+
+#[tokio::test]
+async fn a_slow_link_is_measured_against_the_profile_margin() {
+    let api = station(json!({}), Duration::from_millis(300)).await;
+    let sandbox = Sandbox::new();
+    configure(&sandbox, "latency_margin = 100\n");
+    let file = write_batch(&sandbox, chrono::Duration::seconds(20));
+
+    cli(&sandbox, &api, &["jobs", "add", "--batch", &file])
+        .assert()
+        .success()
+        .stderr(contains("round trips of 0.3s"))
+        .stderr(contains("lead").not());
+}
+
+#[tokio::test]
+async fn a_job_hours_away_is_not_checked() {
+    let api = station(json!({ "scheduling_lead_seconds": 120 }), Duration::ZERO).await;
+    let sandbox = Sandbox::new();
+    let file = write_batch(&sandbox, chrono::Duration::hours(2));
+
+    cli(&sandbox, &api, &["jobs", "add", "--batch", &file])
+        .assert()
+        .success()
+        .stderr(contains("Job starts in").not());
+    let requests = api.received_requests().await.unwrap_or_default();
+    assert!(requests.iter().all(|r| r.url.path() != "/station"));
+}
@@ -0,0 +1,68 @@
+//! The mock station served from the library by `mock_server::serve`, the way another
+//! suite or tool would embed it, with the CLI pointed at it
+
+mod common;
+
+use chrono::{Duration, SubsecRound, Utc};
+use common::{cli, LibraryStation, Sandbox};
+use predicates::str::contains;
+use serde_json::{json, Value};
+
+/// A ten-minute loopback test `minutes` after a start two days ahead
+fn loopback(minutes: i64) -> Value {
+    let start = Utc::now().trunc_subsecs(0) + Duration::days(2) + Duration::minutes(minutes);
+    json!({
+        "job_type": "test",
+        "start": start.to_rfc3339(),
+        "end": (start + Duration::minutes(10)).to_rfc3339(),
+        "rx_frequency": 437_500_000,
+        "tx_frequency": 0,
+    })
+}
+
+fn add(sandbox: &Sandbox, station: &LibraryStation, jobs: &[Value]) -> assert_cmd::assert::Assert {
+    let file = sandbox.path().join("jobs.json");
+    std::fs::write(&file, Value::from(jobs.to_vec()).to_string()).expect("writing the batch");
+    cli(
+        sandbox,
+        station,
+        &["jobs", "add", "--batch", &file.display().to_string()],
+    )
+    .assert()
+}
+
+#[test]
+fn jobs_added_are_listed_and_overlaps_are_refused() {
+    let sandbox = Sandbox::new();
+    let station = LibraryStation::start(None);
+
+    add(&sandbox, &station, &[loopback(0)]).success();
+    add(&sandbox, &station, &[loopback(5)])
+        .failure()
+        .stderr(contains("window overlaps job 1"));
+
+    let output = cli(&sandbox, &station, &["--output", "json", "jobs", "list"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let jobs: Vec<Value> = serde_json::from_slice(&output).expect("a JSON job list");
+    assert_eq!(jobs.len(), 1, "{:?}", jobs);
+    assert_eq!(jobs[0]["id"], "1");
+}
+
+#[test]
+fn the_state_file_keeps_the_jobs_for_the_next_server() {
+    let sandbox = Sandbox::new();
+    let state = sandbox.path().join("mock-state.json");
+    let station = LibraryStation::start(Some(state.clone()));
+
+    add(&sandbox, &station, &[loopback(0)]).success();
+
+    let again = LibraryStation::start(Some(state));
+    cli(&sandbox, &again, &["jobs", "list"])
+        .assert()
+        .success()
+        .stdout(contains("1 job(s)"));
+}
@@ -0,0 +1,82 @@
+//! Every command that prompts, run with stdin from /dev/null: it stops before asking
+//! anything, with the usage exit code and the flags that run it without prompts
+
+mod common;
+
+use common::Sandbox;
+use predicates::prelude::PredicateBooleanExt;
+use predicates::str::contains;
+use std::process::Stdio;
+
+/// The arguments, and the flags the refusal must name
+const PROMPTING: &[(&[&str], &[&str])] = &[
+    (
+        &["jobs", "add"],
+        &["--batch <FILE>", "--batch - (jobs on stdin)"],
+    ),
+    (
+        &["predict"],
+        &[
+            "--norad-id <ID>",
+            "--intl-designator <DESIGNATOR>",
+            "--alias-group <NAME>",
+            "--tle-file <FILE>",
+        ],
+    ),
+    (
+        &["predict", "--pick", "--norad-id", "25544"],
+        &["predict without --pick, then jobs add --batch with the chosen window"],
+    ),
+    (&["jobs", "cancel", "7"], &["--yes"]),
+    (
+        &[
+            "jobs",
+            "sweep",
+            "--satellite",
+            "25544",
+            "--from",
+            "437M",
+            "--to",
+            "437.1M",
+            "--step",
+            "25k",
+            "--dwell",
+            "60s",
+        ],
+        &["--yes", "--dry-run"],
+    ),
+    (&["campaign", "cancel", "hunt"], &["--yes"]),
+    (&["undo"], &["--yes"]),
+    (&["purge"], &["--yes", "--dry-run"]),
+];
+
+#[test]
+fn each_prompting_command_names_the_flags_that_replace_its_prompts() {
+    let sandbox = Sandbox::new();
+    for (args, flags) in PROMPTING {
+        let mut command =
+            sandbox.without_api(&[&["--base-url", "http://127.0.0.1:9"], *args].concat());
+        command.stdin(Stdio::null());
+        let mut assert = assert_cmd::Command::from_std(command)
+            .assert()
+            .code(2)
+            .stderr(contains(
+                "asks questions, but stdin or stderr is not a terminal",
+            ));
+        for flag in *flags {
+            assert = assert.stderr(contains(*flag));
+        }
+        // Refused before anything is contacted
+        assert.stderr(contains("HTTP request failed").not());
+    }
+}
+
+#[test]
+fn the_flags_it_names_run_the_command_without_the_refusal() {
+    let sandbox = Sandbox::new();
+    let mut command = sandbox.without_api(&["undo", "--yes"]);
+    command.stdin(Stdio::null());
+    assert_cmd::Command::from_std(command)
+        .assert()
+        .stderr(contains("asks questions").not());
+}
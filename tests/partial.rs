@@ -0,0 +1,270 @@
+//! Lists, batches and downloads cut short by a timeout: `jobs list --partial-ok` shows
+//! what arrived, a batch says which entries were scheduled and which may have been, and a
+//! download keeps the bytes it got and resumes from them
+
+mod common;
+
+use common::{created, loopback_job, Sandbox};
+use predicates::prelude::PredicateBooleanExt;
+use predicates::str::contains;
+use serde_json::{json, Value};
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::time::Duration;
+use wiremock::matchers::{method, path};
+use wiremock::Mock;
+
+/// A server that starts the job list with `jobs`, then goes quiet without ending it
+struct StallingStation {
+    uri: String,
+}
+
+impl StallingStation {
+    fn start(jobs: &[Value]) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("binding the station");
+        let uri = format!("http://{}", listener.local_addr().unwrap());
+        let mut body = String::from("[");
+        for job in jobs {
+            body.push_str(&job.to_string());
+            body.push(',');
+        }
+        std::thread::spawn(move || {
+            for mut socket in listener.incoming().flatten() {
+                let body = body.clone();
+                std::thread::spawn(move || {
+                    let mut request = [0u8; 4096];
+                    let _ = socket.read(&mut request);
+                    let head = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+                         Content-Length: {}\r\n\r\n",
+                        body.len() * 10
+                    );
+                    let _ = socket.write_all(head.as_bytes());
+                    let _ = socket.write_all(body.as_bytes());
+                    let _ = socket.flush();
+                    std::thread::sleep(Duration::from_secs(10));
+                });
+            }
+        });
+        Self { uri }
+    }
+}
+
+impl common::Api for StallingStation {
+    fn uri(&self) -> String {
+        self.uri.clone()
+    }
+}
+
+/// A recording a station sends only the first `sent` bytes of, then goes quiet; asked for
+/// the rest with a range, it sends that
+struct StallingRecording {
+    uri: String,
+}
+
+/// The recording job 7 produced
+const RECORDING: &[u8] = b"a recording long enough to break off in the middle of it";
+
+impl StallingRecording {
+    fn start(sent: usize) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("binding the station");
+        let uri = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for mut socket in listener.incoming().flatten() {
+                std::thread::spawn(move || {
+                    let mut request = [0u8; 4096];
+                    let read = socket.read(&mut request).unwrap_or(0);
+                    let request = String::from_utf8_lossy(&request[..read]).to_lowercase();
+                    let (head, body, stall) = if request.starts_with("get /jobs/7/results ") {
+                        let listing = json!({ "recordings": [{ "name": "pass.iq" }] });
+                        (
+                            "200 OK".to_string(),
+                            listing.to_string().into_bytes(),
+                            false,
+                        )
+                    } else if let Some(from) = request
+                        .lines()
+                        .find_map(|line| line.strip_prefix("range: bytes="))
+                        .and_then(|range| range.trim_end_matches('-').parse::<usize>().ok())
+                    {
+                        let head = format!(
+                            "206 Partial Content\r\nContent-Range: bytes {}-{}/{}",
+                            from,
+                            RECORDING.len() - 1,
+                            RECORDING.len()
+                        );
+                        (head, RECORDING[from..].to_vec(), false)
+                    } else {
+                        ("200 OK".to_string(), RECORDING.to_vec(), true)
+                    };
+                    let length = body.len();
+                    let head = format!(
+                        "HTTP/1.1 {}\r\nETag: \"v1\"\r\nContent-Length: {}\r\n\r\n",
+                        head, length
+                    );
+                    let _ = socket.write_all(head.as_bytes());
+                    let _ = socket.write_all(if stall { &body[..sent] } else { &body });
+                    let _ = socket.flush();
+                    if stall {
+                        std::thread::sleep(Duration::from_secs(10));
+                    }
+                });
+            }
+        });
+        Self { uri }
+    }
+}
+
+impl common::Api for StallingRecording {
+    fn uri(&self) -> String {
+        self.uri.clone()
+    }
+}
+
+fn list(sandbox: &Sandbox, station: &StallingStation, args: &[&str]) -> assert_cmd::Command {
+    let mut command = sandbox.command(station, &[&["jobs", "list"], args].concat());
+    command.env("API_IDLE_TIMEOUT_SECONDS", "1");
+    assert_cmd::Command::from_std(command)
+}
+
+#[test]
+fn a_stalled_list_fails_without_the_flag() {
+    let sandbox = Sandbox::new();
+    let station = StallingStation::start(&[loopback_job("1")]);
+
+    list(&sandbox, &station, &[])
+        .assert()
+        .code(1)
+        .stderr(contains("no data from the server for 1s"));
+}
+
+#[test]
+fn a_stalled_list_shows_what_arrived_and_says_it_is_incomplete() {
+    let sandbox = Sandbox::new();
+    let station = StallingStation::start(&[loopback_job("1"), loopback_job("2")]);
+
+    list(&sandbox, &station, &["--partial-ok"])
+        .assert()
+        .code(4)
+        .stdout(contains("📋 2 job(s) ⚠️ incomplete"))
+        .stderr(contains("Partial list: the server stopped sending it"))
+        .stderr(contains("no data from the server for 1s"));
+}
+
+#[test]
+fn a_stalled_ndjson_list_keeps_every_line_that_arrived() {
+    let sandbox = Sandbox::new();
+    let station = StallingStation::start(&[loopback_job("1"), loopback_job("2")]);
+
+    let output = list(&sandbox, &station, &["--partial-ok", "--output", "ndjson"])
+        .assert()
+        .code(4)
+        .get_output()
+        .stdout
+        .clone();
+    let lines: Vec<Value> = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("one job per line"))
+        .collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[1]["id"], "2");
+}
+
+#[tokio::test]
+async fn a_batch_entry_that_times_out_is_reported_as_unconfirmed() {
+    let api = common::station().await;
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(created(1))
+        .up_to_n_times(1)
+        .mount(&api)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(created(2).set_delay(Duration::from_secs(5)))
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+    let start = chrono::Utc::now() + chrono::Duration::days(2);
+    let jobs: Vec<Value> = (0..2)
+        .map(|i| {
+            let start = start + chrono::Duration::minutes(20 * i);
+            json!({
+                "job_type": "test",
+                "start": start.to_rfc3339(),
+                "end": (start + chrono::Duration::minutes(10)).to_rfc3339(),
+                "rx_frequency": 437_500_000,
+                "tx_frequency": 0,
+            })
+        })
+        .collect();
+    let file = sandbox.path().join("jobs.json");
+    std::fs::write(&file, Value::from(jobs).to_string()).unwrap();
+    let report = sandbox.path().join("report.json");
+
+    let mut command = sandbox.command(
+        &api,
+        &[
+            "jobs",
+            "add",
+            "--batch",
+            &file.display().to_string(),
+            "--report",
+            &report.display().to_string(),
+        ],
+    );
+    command.env("API_TIMEOUT_SECONDS", "1");
+    assert_cmd::Command::from_std(command)
+        .assert()
+        .failure()
+        .stderr(contains("✅ [0]"))
+        .stderr(contains("❓ [1]").and(contains("the station may have scheduled it")))
+        .stderr(contains("📊 1 submitted, 0 failed, 1 unconfirmed"))
+        .stderr(contains("Check `rustar-cli jobs list` for [1]"));
+
+    let report: Value = serde_json::from_str(&std::fs::read_to_string(&report).unwrap()).unwrap();
+    assert_eq!(report["entries"][0]["outcome"], "created");
+    assert_eq!(report["entries"][1]["outcome"], "unconfirmed");
+    let failed = sandbox.path().join("failed.json");
+    if failed.exists() {
+        let failed: Value =
+            serde_json::from_str(&std::fs::read_to_string(failed).unwrap()).unwrap();
+        assert_eq!(failed, json!([]));
+    }
+}
+
+#[test]
+fn a_stalled_download_keeps_what_arrived_and_the_next_run_resumes_from_it() {
+    let sandbox = Sandbox::new();
+    let station = StallingRecording::start(25);
+    let download = || {
+        let mut command = sandbox.command(&station, &["jobs", "download", "7", "--dir", "out"]);
+        command.env("API_IDLE_TIMEOUT_SECONDS", "1");
+        assert_cmd::Command::from_std(command)
+    };
+
+    download()
+        .assert()
+        .code(1)
+        .stderr(contains(format!(
+            "the download stopped after 25 bytes of {}",
+            RECORDING.len()
+        )))
+        .stderr(contains("no data from the server for 1s"))
+        .stderr(contains(
+            "run the same command again to fetch only the rest",
+        ));
+    let partial = sandbox.path().join("out/pass.iq.partial");
+    assert_eq!(std::fs::read(&partial).unwrap(), &RECORDING[..25]);
+
+    download()
+        .assert()
+        .success()
+        .stdout(contains("resumed after 25.0 B"));
+    assert_eq!(
+        std::fs::read(sandbox.path().join("out/pass.iq")).unwrap(),
+        RECORDING
+    );
+    assert!(!partial.exists());
+}
@@ -0,0 +1,226 @@
+//! Pass scores from the profile's `pass_scoring`, over a day of ISS passes above Buenos
+//! Aires, and the `--min-score` and `--best` selections built on them
+
+mod common;
+
+use common::{cli, station, write_config, Sandbox};
+use predicates::str::contains;
+use serde_json::Value;
+
+const ISS: &str = "ISS (ZARYA)
+1 25544U 98067A   25235.75642456  .00011222  00000+0  20339-3 0  9993
+2 25544  51.6355 332.1708 0003307 260.2831  99.7785 15.50129787525648
+";
+
+fn configure(sandbox: &Sandbox, pass_scoring: &str) {
+    write_config(
+        sandbox,
+        &format!(
+            "[profiles.default]\ntimezone = \"utc\"\n[profiles.default.pass_scoring]\n{}\n",
+            pass_scoring
+        ),
+    );
+}
+
+/// The passes of the day after the elements' epoch, predicted as JSON
+async fn passes(sandbox: &Sandbox, extra: &[&str]) -> Vec<Value> {
+    let api = station().await;
+    let file = sandbox.path().join("iss.tle");
+    std::fs::write(&file, ISS).expect("writing the element set");
+    let file = file.display().to_string();
+    let mut args = vec![
+        "--coords",
+        "-34.6,-58.4",
+        "--output",
+        "json",
+        "predict",
+        "--tle-file",
+        &file,
+        "--from",
+        "2025-08-24T00:00:00Z",
+        "--hours",
+        "24",
+    ];
+    args.extend(extra);
+    let output = cli(sandbox, &api, &args)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let passes: Value = serde_json::from_slice(&output).expect("JSON passes");
+    passes.as_array().expect("a list of passes").clone()
+}
+
+fn score(pass: &Value) -> u64 {
+    pass["score"].as_u64().expect("a score")
+}
+
+fn usable_minutes(pass: &Value) -> f64 {
+    let at = |value: &Value| {
+        chrono::DateTime::parse_from_rfc3339(value.as_str().expect("a timestamp"))
+            .expect("an RFC 3339 timestamp")
+    };
+    pass["usable"]
+        .as_array()
+        .expect("usable intervals")
+        .iter()
+        .map(|span| (at(&span[1]) - at(&span[0])).num_milliseconds() as f64 / 60_000.0)
+        .sum()
+}
+
+#[tokio::test]
+async fn the_default_weights_rank_a_high_pass_well_above_a_low_one() {
+    let sandbox = Sandbox::new();
+    let passes = passes(&sandbox, &[]).await;
+    assert!(passes.len() > 2);
+    let by_elevation = |pass: &Value| pass["max_elevation_deg"].as_f64().expect("an elevation");
+    let highest = passes
+        .iter()
+        .max_by(|a, b| by_elevation(a).total_cmp(&by_elevation(b)))
+        .expect("a pass");
+    let lowest = passes
+        .iter()
+        .min_by(|a, b| by_elevation(a).total_cmp(&by_elevation(b)))
+        .expect("a pass");
+    assert!(by_elevation(lowest) < 5.0);
+    assert!(score(highest) >= 80, "{}", highest);
+    assert!(score(lowest) <= 25, "{}", lowest);
+    assert!(passes.iter().all(|pass| score(pass) <= 100));
+}
+
+#[tokio::test]
+async fn elevation_alone_scores_the_share_of_90_degrees() {
+    let sandbox = Sandbox::new();
+    configure(&sandbox, "elevation_weight = 1\nduration_weight = 0");
+    for pass in passes(&sandbox, &[]).await {
+        let elevation = pass["max_elevation_deg"].as_f64().expect("an elevation");
+        assert_eq!(score(&pass), (elevation / 90.0 * 100.0).round() as u64);
+    }
+}
+
+#[tokio::test]
+async fn duration_alone_scores_the_share_of_full_marks_time() {
+    let sandbox = Sandbox::new();
+    configure(
+        &sandbox,
+        "elevation_weight = 0\nduration_weight = 1\nfull_duration_minutes = 20",
+    );
+    for pass in passes(&sandbox, &[]).await {
+        let expected = (usable_minutes(&pass) / 20.0 * 100.0).round() as u64;
+        assert_eq!(score(&pass), expected.min(100), "{}", pass);
+    }
+}
+
+#[tokio::test]
+async fn time_of_day_fades_over_two_hours_outside_the_preferred_span() {
+    let sandbox = Sandbox::new();
+    configure(
+        &sandbox,
+        "elevation_weight = 0\nduration_weight = 0\ntime_of_day_weight = 1\n\
+         preferred_hours = \"00:00-12:00\"",
+    );
+    for pass in passes(&sandbox, &[]).await {
+        let at = chrono::DateTime::parse_from_rfc3339(
+            pass["max_elevation_at"].as_str().expect("a timestamp"),
+        )
+        .expect("an RFC 3339 timestamp");
+        let minutes = f64::from(chrono::Timelike::hour(&at) * 60 + chrono::Timelike::minute(&at))
+            + f64::from(chrono::Timelike::second(&at)) / 60.0;
+        let expected = match minutes {
+            m if m < 720.0 => 100.0,
+            // Two hours after noon, or two hours before midnight, the marks are gone
+            m if m < 840.0 => (1.0 - (m - 720.0) / 120.0) * 100.0,
+            m if m < 1320.0 => 0.0,
+            m => (1.0 - (1440.0 - m) / 120.0) * 100.0,
+        };
+        let expected = expected.round() as i64;
+        assert!(
+            (score(&pass) as i64 - expected).abs() <= 1,
+            "{} scored {}, expected {}",
+            pass["max_elevation_at"],
+            score(&pass),
+            expected
+        );
+    }
+}
+
+#[tokio::test]
+async fn min_score_and_best_select_from_the_scored_passes() {
+    let sandbox = Sandbox::new();
+    let all = passes(&sandbox, &[]).await;
+    let mut scores: Vec<u64> = all.iter().map(score).collect();
+    scores.sort_unstable_by(|a, b| b.cmp(a));
+
+    let best = passes(&sandbox, &["--best", "2"]).await;
+    assert_eq!(best.len(), 2);
+    let mut kept: Vec<u64> = best.iter().map(score).collect();
+    kept.sort_unstable_by(|a, b| b.cmp(a));
+    assert_eq!(kept, scores[..2]);
+    // Still in time order
+    assert!(best[0]["aos"].as_str() < best[1]["aos"].as_str());
+
+    let threshold = scores[scores.len() / 2];
+    let above = passes(&sandbox, &["--min-score", &threshold.to_string()]).await;
+    assert_eq!(
+        above.len(),
+        scores.iter().filter(|score| **score >= threshold).count()
+    );
+    assert!(above.iter().all(|pass| score(pass) >= threshold));
+}
+
+#[tokio::test]
+async fn the_table_shows_the_score_and_what_was_left_out() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    let file = sandbox.path().join("iss.tle");
+    std::fs::write(&file, ISS).expect("writing the element set");
+
+    cli(
+        &sandbox,
+        &api,
+        &[
+            "--coords",
+            "-34.6,-58.4",
+            "predict",
+            "--tle-file",
+            &file.display().to_string(),
+            "--from",
+            "2025-08-24T00:00:00Z",
+            "--best",
+            "1",
+        ],
+    )
+    .assert()
+    .success()
+    .stdout(contains("SCORE"))
+    .stdout(contains("73.5°"))
+    .stdout(contains(
+        "lower-scoring pass(es) left out by --min-score or --best",
+    ));
+}
+
+#[tokio::test]
+async fn weights_that_leave_nothing_to_score_are_refused() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    configure(&sandbox, "elevation_weight = 0\nduration_weight = 0");
+    let file = sandbox.path().join("iss.tle");
+    std::fs::write(&file, ISS).expect("writing the element set");
+
+    cli(
+        &sandbox,
+        &api,
+        &[
+            "--coords",
+            "-34.6,-58.4",
+            "predict",
+            "--tle-file",
+            &file.display().to_string(),
+        ],
+    )
+    .assert()
+    .failure()
+    .stderr(contains("profiles.default.pass_scoring"))
+    .stderr(contains("every weight in use is 0"));
+}
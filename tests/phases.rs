@@ -0,0 +1,117 @@
+//! --profile-timing: the phases of a command added up at the end, as a table or as JSON,
+//! and each one noted as it ends under --verbose
+
+mod common;
+
+use common::{cli, created, station, Sandbox};
+use predicates::prelude::PredicateBooleanExt;
+use predicates::str::contains;
+use serde_json::Value;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn listing() -> MockServer {
+    let api = station().await;
+    Mock::given(method("GET"))
+        .and(path("/jobs"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+        .mount(&api)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(created(8))
+        .mount(&api)
+        .await;
+    api
+}
+
+fn write_batch(sandbox: &Sandbox) -> String {
+    let start = chrono::Utc::now() + chrono::Duration::days(2);
+    let file = sandbox.path().join("jobs.json");
+    std::fs::write(
+        &file,
+        serde_json::json!([{
+            "job_type": "test",
+            "start": start.to_rfc3339(),
+            "end": (start + chrono::Duration::minutes(10)).to_rfc3339(),
+            "rx_frequency": 437_500_000,
+            "tx_frequency": 0,
+        }])
+        .to_string(),
+    )
+    .expect("writing the batch file");
+    file.display().to_string()
+}
+
+#[tokio::test]
+async fn an_add_job_run_reports_its_phases_as_json() {
+    let api = listing().await;
+    let sandbox = Sandbox::new();
+    let file = write_batch(&sandbox);
+
+    let output = cli(
+        &sandbox,
+        &api,
+        &[
+            "--profile-timing",
+            "--output",
+            "json",
+            "jobs",
+            "add",
+            "--batch",
+            &file,
+        ],
+    )
+    .assert()
+    .success()
+    .get_output()
+    .stderr
+    .clone();
+    let stderr = String::from_utf8(output).unwrap();
+    let timing: Value = stderr
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .find_map(|value| value.get("timing").cloned())
+        .expect("a timing object on stderr");
+    let phases: Vec<&str> = timing["phases"]
+        .as_array()
+        .expect("a list of phases")
+        .iter()
+        .filter_map(|phase| phase["phase"].as_str())
+        .collect();
+    assert!(phases.contains(&"config"), "{}", timing);
+    assert!(phases.contains(&"http"), "{}", timing);
+    let http = timing["phases"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|phase| phase["phase"] == "http")
+        .unwrap();
+    assert!(http["calls"].as_u64().unwrap() >= 2, "{}", timing);
+    assert!(timing["total_ms"].as_f64().unwrap() > 0.0, "{}", timing);
+}
+
+#[tokio::test]
+async fn the_breakdown_is_a_table_in_text_output() {
+    let api = listing().await;
+    let sandbox = Sandbox::new();
+
+    cli(&sandbox, &api, &["--profile-timing", "jobs", "list"])
+        .assert()
+        .success()
+        .stderr(contains("Phases of this run"))
+        .stderr(contains("PHASE"))
+        .stderr(contains("http"));
+}
+
+#[tokio::test]
+async fn verbose_notes_each_phase_as_it_ends() {
+    let api = listing().await;
+    let sandbox = Sandbox::new();
+
+    cli(&sandbox, &api, &["--verbose", "jobs", "list"])
+        .assert()
+        .success()
+        .stderr(contains("http GET /jobs:"))
+        .stderr(contains("Phases of this run").not());
+}
@@ -0,0 +1,216 @@
+//! Polite element-set fetching: concurrent fetches of one URL share a request, each host
+//! gets a limited rate, and --tle-offline keeps every fetch to the cache
+
+mod common;
+
+use common::{checksummed, station, write_config, Sandbox};
+use predicates::prelude::PredicateBooleanExt;
+use predicates::str::contains;
+use std::time::Duration;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const ISS: &str = "ISS (ZARYA)
+1 25544U 98067A   25235.75642456  .00011222  00000+0  20339-3 0  9993
+2 25544  51.6355 332.1708 0003307 260.2831  99.7785 15.50129787525648
+";
+
+/// Catalog and designator lookups both sent to `source` at `at`
+fn cli(
+    sandbox: &Sandbox,
+    api: &impl common::Api,
+    source: &MockServer,
+    at: &str,
+    args: &[&str],
+) -> assert_cmd::Command {
+    let mut command = sandbox.command(api, args);
+    command
+        .env("TLE_SOURCE_URL", format!("{}{}", source.uri(), at))
+        .env(
+            "TLE_DESIGNATOR_SOURCE_URL",
+            format!("{}{}", source.uri(), at),
+        );
+    assert_cmd::Command::from_std(command)
+}
+
+fn configure(sandbox: &Sandbox, profile: &str) {
+    write_config(
+        sandbox,
+        &format!(
+            "[profiles.default]\nlatitude = -34.6\nlongitude = -58.4\n{}",
+            profile
+        ),
+    );
+}
+
+/// Put `tle` in the cache for `norad_id`, written `age` ago
+fn cache(sandbox: &Sandbox, norad_id: u32, tle: &str, age: Duration) {
+    let dir = sandbox.path().join("cache/rustar/tle");
+    std::fs::create_dir_all(&dir).expect("creating the TLE cache");
+    let file = dir.join(format!("{}.tle", norad_id));
+    std::fs::write(&file, tle).expect("caching an element set");
+    std::fs::File::options()
+        .write(true)
+        .open(&file)
+        .and_then(|file| file.set_modified(std::time::SystemTime::now() - age))
+        .expect("backdating the cache entry");
+}
+
+async fn requests(source: &MockServer) -> usize {
+    source.received_requests().await.unwrap_or_default().len()
+}
+
+#[tokio::test]
+async fn concurrent_fetches_of_one_url_share_a_request() {
+    let api = station().await;
+    let source = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/iss.txt"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string(ISS)
+                .set_delay(Duration::from_millis(500)),
+        )
+        .expect(1)
+        .mount(&source)
+        .await;
+    let sandbox = Sandbox::new();
+    configure(&sandbox, "");
+
+    cli(
+        &sandbox,
+        &api,
+        &source,
+        "/iss.txt",
+        &[
+            "--verbose",
+            "predict",
+            "--norad-id",
+            "25544",
+            "--intl-designator",
+            "1998-067A",
+        ],
+    )
+    .assert()
+    .success()
+    .stderr(contains("Sharing the request already on its way to"));
+}
+
+#[tokio::test]
+async fn offline_fetches_make_no_request_and_say_why() {
+    let api = station().await;
+    let source = MockServer::start().await;
+    let sandbox = Sandbox::new();
+    configure(&sandbox, "");
+
+    cli(
+        &sandbox,
+        &api,
+        &source,
+        "/gp.php?CATNR={norad}",
+        &["--tle-offline", "predict", "--norad-id", "25544"],
+    )
+    .assert()
+    .failure()
+    .stderr(contains("--tle-offline is set, so the elements for"))
+    .stderr(contains("tle refresh-all"));
+    assert_eq!(requests(&source).await, 0);
+}
+
+#[tokio::test]
+async fn offline_uses_a_cached_copy_of_any_age() {
+    let api = station().await;
+    let source = MockServer::start().await;
+    let sandbox = Sandbox::new();
+    configure(&sandbox, "");
+    cache(&sandbox, 25544, ISS, Duration::from_secs(3 * 24 * 3600));
+
+    cli(
+        &sandbox,
+        &api,
+        &source,
+        "/gp.php?CATNR={norad}",
+        &["--tle-offline", "predict", "--norad-id", "25544"],
+    )
+    .assert()
+    .success()
+    .stderr(contains(
+        "Using the cached elements for NORAD 25544 from 72h ago",
+    ));
+    assert_eq!(requests(&source).await, 0);
+}
+
+#[tokio::test]
+async fn a_fresh_cache_entry_is_used_before_any_request() {
+    let api = station().await;
+    let source = MockServer::start().await;
+    let sandbox = Sandbox::new();
+    configure(&sandbox, "");
+    cache(&sandbox, 25544, ISS, Duration::from_secs(60));
+
+    cli(
+        &sandbox,
+        &api,
+        &source,
+        "/iss.txt",
+        &["predict", "--intl-designator", "1998-067A"],
+    )
+    .assert()
+    .success();
+    assert_eq!(requests(&source).await, 0);
+}
+
+/// A geostationary element set under catalog number `norad_id`
+fn geostationary(norad_id: u32) -> String {
+    format!(
+        "GEO {}\n{}\n{}\n",
+        norad_id,
+        checksummed(&format!(
+            "1 {}U 10016A   25235.50000000 -.00000276  00000-0  00000+0 0  999",
+            norad_id
+        )),
+        checksummed(&format!(
+            "2 {}   0.0300  90.0000 0002000  10.0000 100.0000  1.0027000050000",
+            norad_id
+        ))
+    )
+}
+
+#[tokio::test]
+async fn requests_past_the_burst_wait_for_their_turn() {
+    let api = station().await;
+    let source = MockServer::start().await;
+    let ids = [40001, 40002, 40003, 40004];
+    for id in ids {
+        Mock::given(method("GET"))
+            .and(path("/gp.php"))
+            .and(query_param("CATNR", id.to_string()))
+            .respond_with(ResponseTemplate::new(200).set_body_string(geostationary(id)))
+            .expect(1)
+            .mount(&source)
+            .await;
+    }
+    let sandbox = Sandbox::new();
+    configure(&sandbox, "tle_requests_per_minute = 120\n");
+    let book: String = ids
+        .iter()
+        .map(|id| format!("[geo{}]\nnorad_id = {}\n", id, id))
+        .collect();
+    std::fs::write(
+        sandbox.path().join("config/rustar/satellites.toml"),
+        format!("version = 1\n{}", book),
+    )
+    .expect("writing the alias book");
+
+    cli(
+        &sandbox,
+        &api,
+        &source,
+        "/gp.php?CATNR={norad}",
+        &["--verbose", "tle", "refresh-all"],
+    )
+    .assert()
+    .success()
+    .stderr(contains("for a turn at 127.0.0.1 (120 requests a minute)"))
+    .stderr(contains("Sharing the request").not());
+}
@@ -0,0 +1,170 @@
+//! A protected profile: a changing command goes ahead only once the profile is named,
+//! typed at the prompt or given with --confirm-protected, and nothing reaches the
+//! station until it is
+
+mod common;
+
+use common::{cli, configure, loopback_job, station, Sandbox};
+use predicates::str::contains;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn protect(sandbox: &Sandbox) {
+    configure(sandbox, "protected = true\n");
+}
+
+/// A station holding job 7, with deletes expected `deletes` times
+async fn holding_job(deletes: u64) -> MockServer {
+    let api = station().await;
+    Mock::given(method("GET"))
+        .and(path("/jobs/7"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(loopback_job("7")))
+        .mount(&api)
+        .await;
+    Mock::given(method("DELETE"))
+        .and(path("/jobs/7"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "status": "cancelled",
+        })))
+        .expect(deletes)
+        .mount(&api)
+        .await;
+    api
+}
+
+async fn assert_untouched(api: &MockServer) {
+    let requests = api.received_requests().await.unwrap_or_default();
+    assert!(requests.is_empty(), "{:?}", requests);
+}
+
+#[tokio::test]
+async fn a_wrong_name_at_the_prompt_changes_nothing_and_the_right_one_goes_ahead() {
+    let api = holding_job(1).await;
+    let sandbox = Sandbox::new();
+    protect(&sandbox);
+
+    let mut terminal = sandbox.spawn(
+        &api,
+        &["jobs", "cancel", "7", "--yes", "--reason", "weather"],
+    );
+    terminal
+        .expect("targets the protected profile")
+        .answer("Type default to go ahead:", "staging");
+    let (output, code) = terminal.finish();
+    assert_ne!(code, 0, "{}", output);
+    assert!(
+        output.contains("'staging' is not the protected profile 'default'"),
+        "{}",
+        output
+    );
+    assert_untouched(&api).await;
+
+    let mut terminal = sandbox.spawn(
+        &api,
+        &["jobs", "cancel", "7", "--yes", "--reason", "weather"],
+    );
+    terminal.answer("Type default to go ahead:", "default");
+    let (output, code) = terminal.finish();
+    assert_eq!(code, 0, "{}", output);
+    assert!(output.contains("cancelled"), "{}", output);
+}
+
+#[tokio::test]
+async fn confirm_protected_with_the_profile_name_goes_ahead_without_a_prompt() {
+    let api = holding_job(1).await;
+    let sandbox = Sandbox::new();
+    protect(&sandbox);
+
+    cli(
+        &sandbox,
+        &api,
+        &[
+            "--confirm-protected",
+            "default",
+            "jobs",
+            "cancel",
+            "7",
+            "--yes",
+            "--reason",
+            "weather",
+        ],
+    )
+    .assert()
+    .success()
+    .stderr(contains("targets the protected profile default"));
+}
+
+#[tokio::test]
+async fn confirm_protected_with_another_name_is_refused_before_any_request() {
+    let api = holding_job(0).await;
+    let sandbox = Sandbox::new();
+    protect(&sandbox);
+
+    cli(
+        &sandbox,
+        &api,
+        &[
+            "--confirm-protected",
+            "staging",
+            "jobs",
+            "cancel",
+            "7",
+            "--yes",
+            "--reason",
+            "weather",
+        ],
+    )
+    .assert()
+    .failure()
+    .stderr(contains("'staging' is not the protected profile 'default'"));
+    assert_untouched(&api).await;
+}
+
+#[tokio::test]
+async fn yes_alone_does_not_confirm_a_protected_profile() {
+    let api = holding_job(0).await;
+    let sandbox = Sandbox::new();
+    protect(&sandbox);
+
+    cli(
+        &sandbox,
+        &api,
+        &["jobs", "cancel", "7", "--yes", "--reason", "weather"],
+    )
+    .assert()
+    .code(2)
+    .stderr(contains("--confirm-protected default"));
+    assert_untouched(&api).await;
+}
+
+#[tokio::test]
+async fn clearing_the_prompt_history_is_confirmed_and_audited_like_any_change() {
+    let api = MockServer::start().await;
+    let sandbox = Sandbox::new();
+    protect(&sandbox);
+    let audit_log = sandbox.path().join("state/rustar/audit.jsonl");
+
+    cli(
+        &sandbox,
+        &api,
+        &["--confirm-protected", "staging", "history", "clear-prompts"],
+    )
+    .assert()
+    .failure()
+    .stderr(contains("'staging' is not the protected profile 'default'"));
+
+    cli(
+        &sandbox,
+        &api,
+        &["--confirm-protected", "default", "history", "clear-prompts"],
+    )
+    .assert()
+    .success();
+    let audit = std::fs::read_to_string(&audit_log).expect("reading the audit log");
+    assert!(
+        audit.contains("\"command\":\"history clear-prompts\""),
+        "{}",
+        audit
+    );
+    assert_untouched(&api).await;
+}
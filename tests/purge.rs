@@ -0,0 +1,195 @@
+//! `purge` over a sandbox holding a little of everything, half of it past the cutoff
+
+mod common;
+
+use chrono::{Duration, Utc};
+use common::{cli, station, Sandbox};
+use predicates::str::contains;
+use std::path::PathBuf;
+
+fn loopback(start: chrono::DateTime<Utc>) -> serde_json::Value {
+    serde_json::json!({
+        "job_type": "test",
+        "start": start.to_rfc3339(),
+        "end": (start + Duration::minutes(10)).to_rfc3339(),
+        "rx_frequency": 437_500_000,
+        "tx_frequency": 0,
+    })
+}
+
+fn audit_entry(at: chrono::DateTime<Utc>, command: &str) -> String {
+    serde_json::json!({
+        "timestamp": at.to_rfc3339(),
+        "user": "operator",
+        "profile": "default",
+        "command": command,
+        "arguments": [],
+        "outcome": "success",
+    })
+    .to_string()
+}
+
+struct Files {
+    history: PathBuf,
+    queue: PathBuf,
+    audit: PathBuf,
+    old_tle: PathBuf,
+    new_tle: PathBuf,
+}
+
+/// One old and one recent entry in every store; the queue has a job that already started
+fn populate(sandbox: &Sandbox) -> Files {
+    let state = sandbox.path().join("state/rustar");
+    let tle = sandbox.path().join("cache/rustar/tle");
+    std::fs::create_dir_all(&state).expect("creating the state directory");
+    std::fs::create_dir_all(&tle).expect("creating the TLE cache");
+    let now = Utc::now();
+    let long_ago = now - Duration::days(200);
+
+    let files = Files {
+        history: state.join("prompt_history.json"),
+        queue: state.join("queue.json"),
+        audit: state.join("audit.jsonl"),
+        old_tle: tle.join("25544.tle"),
+        new_tle: tle.join("36516.tle"),
+    };
+    let history = serde_json::json!({
+        "version": 2,
+        "prompts": { "satellite_name": [
+            { "value": "NOAA 19", "at": now.to_rfc3339() },
+            { "value": "ISS", "at": long_ago.to_rfc3339() },
+        ]},
+    });
+    std::fs::write(&files.history, history.to_string()).expect("writing the prompt history");
+    let queue = serde_json::json!({
+        "version": 2,
+        "jobs": [loopback(now - Duration::hours(1)), loopback(now + Duration::days(1))],
+    });
+    std::fs::write(&files.queue, queue.to_string()).expect("writing the queue");
+    let audit = format!(
+        "{}\n{}\n",
+        audit_entry(long_ago, "add-job"),
+        audit_entry(now, "cancel-job")
+    );
+    std::fs::write(&files.audit, audit).expect("writing the audit log");
+    for path in [&files.old_tle, &files.new_tle] {
+        std::fs::write(path, "cached elements\n").expect("writing a cached element set");
+    }
+    std::fs::File::options()
+        .write(true)
+        .open(&files.old_tle)
+        .and_then(|file| file.set_modified(long_ago.into()))
+        .expect("backdating a cached element set");
+    files
+}
+
+fn read(path: &PathBuf) -> String {
+    std::fs::read_to_string(path).unwrap_or_default()
+}
+
+#[tokio::test]
+async fn a_dry_run_reports_and_changes_nothing() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    let files = populate(&sandbox);
+    let before: Vec<String> = [&files.history, &files.queue, &files.audit]
+        .map(read)
+        .to_vec();
+
+    cli(&sandbox, &api, &["purge", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(contains("prompt history"))
+        .stdout(contains("From the offline queue:"))
+        .stdout(contains("loopback test from"))
+        .stdout(contains("Dry run: nothing was removed"));
+
+    let after: Vec<String> = [&files.history, &files.queue, &files.audit]
+        .map(read)
+        .to_vec();
+    assert_eq!(before, after);
+    assert!(files.old_tle.exists());
+}
+
+#[tokio::test]
+async fn yes_removes_what_is_past_the_cutoff() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    let files = populate(&sandbox);
+
+    let output = cli(&sandbox, &api, &["--output", "json", "purge", "--yes"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let report: serde_json::Value = serde_json::from_slice(&output).expect("a JSON report");
+    assert_eq!(report["removed"], true);
+    let entries: Vec<(String, u64)> = report["stores"]
+        .as_array()
+        .expect("the stores")
+        .iter()
+        .map(|s| {
+            (
+                s["store"].as_str().unwrap_or_default().to_string(),
+                s["entries"].as_u64().unwrap_or_default(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        entries,
+        [
+            ("history".to_string(), 1),
+            ("cache".to_string(), 1),
+            ("audit".to_string(), 1),
+            ("queue".to_string(), 1)
+        ]
+    );
+
+    let history = read(&files.history);
+    assert!(
+        history.contains("NOAA 19") && !history.contains("ISS"),
+        "{}",
+        history
+    );
+    let queue: serde_json::Value = serde_json::from_str(&read(&files.queue)).expect("the queue");
+    assert_eq!(queue["jobs"].as_array().map(Vec::len), Some(1));
+    let audit = read(&files.audit);
+    assert!(
+        !audit.contains("add-job") && audit.contains("cancel-job"),
+        "{}",
+        audit
+    );
+    assert!(!files.old_tle.exists());
+    assert!(files.new_tle.exists());
+}
+
+#[tokio::test]
+async fn what_limits_the_stores_touched() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    let files = populate(&sandbox);
+    let history = read(&files.history);
+
+    cli(&sandbox, &api, &["purge", "--what", "queue,cache", "--yes"])
+        .assert()
+        .success()
+        .stdout(contains("Removed 2 entries"));
+
+    assert_eq!(read(&files.history), history);
+    assert!(read(&files.audit).contains("add-job"));
+    assert!(!files.old_tle.exists());
+}
+
+#[tokio::test]
+async fn without_a_terminal_yes_or_a_dry_run_is_required() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    let files = populate(&sandbox);
+
+    cli(&sandbox, &api, &["purge"])
+        .assert()
+        .failure()
+        .stderr(contains("--yes or --dry-run"));
+    assert!(files.old_tle.exists());
+}
@@ -0,0 +1,133 @@
+//! `quick` against a scripted station, with a geostationary satellite so a pass is always
+//! in progress over 0°N 0°E and never over 0°N 90°E
+
+mod common;
+
+use common::{cli, created, station, Sandbox};
+use predicates::str::contains;
+use wiremock::matchers::{method, path};
+use wiremock::Mock;
+
+const GEOSAT: &str = "GEOSAT
+1 36516U 10016A   26287.50000000 -.00000276  00000-0  00000+0 0  9994
+2 36516   0.0300  90.0000 0002000  10.0000 100.0000  1.00270000 50004
+";
+
+/// An alias for the satellite with an RX preset, and its elements in the TLE cache
+fn prepare(sandbox: &Sandbox, api: &impl common::Api, cached: bool) {
+    cli(
+        sandbox,
+        api,
+        &[
+            "sat",
+            "set-freq",
+            "geo",
+            "--norad-id",
+            "36516",
+            "--rx",
+            "437.5M",
+        ],
+    )
+    .assert()
+    .success();
+    if cached {
+        let dir = sandbox.path().join("cache/rustar/tle");
+        std::fs::create_dir_all(&dir).expect("creating the TLE cache");
+        std::fs::write(dir.join("36516.tle"), GEOSAT).expect("writing the cached elements");
+    }
+}
+
+#[tokio::test]
+async fn a_pass_in_progress_is_submitted_after_the_countdown() {
+    let api = station().await;
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(created(7))
+        .expect(1)
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+    prepare(&sandbox, &api, true);
+
+    cli(
+        &sandbox,
+        &api,
+        &["--coords", "0,0", "quick", "--satellite", "geo"],
+    )
+    .assert()
+    .success()
+    .stdout(contains("pass in progress"))
+    .stdout(contains("Submitting in 5s"))
+    .stdout(contains("Job submitted successfully"));
+
+    let requests = api.received_requests().await.unwrap_or_default();
+    let job: serde_json::Value = requests
+        .iter()
+        .find(|r| r.method.as_str() == "POST")
+        .map(|r| serde_json::from_slice(&r.body).expect("a JSON job"))
+        .expect("the submitted job");
+    assert_eq!(job["job_type"], "tracking");
+    assert_eq!(job["tle"]["tle0"], "geo");
+    assert_eq!(job["rx_frequency"], 437_500_000.0);
+}
+
+#[tokio::test]
+async fn a_key_during_the_countdown_aborts() {
+    let api = station().await;
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(created(7))
+        .expect(0)
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+    prepare(&sandbox, &api, true);
+
+    let mut terminal = sandbox.spawn(&api, &["--coords", "0,0", "quick", "--satellite", "geo"]);
+    terminal.expect("any key aborts").keys("x");
+    let (output, code) = terminal.finish();
+    assert!(output.contains("Job not submitted"), "{}", output);
+    assert_eq!(code, 0);
+}
+
+#[tokio::test]
+async fn no_imminent_pass_is_refused() {
+    let api = station().await;
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(created(7))
+        .expect(0)
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+    prepare(&sandbox, &api, true);
+
+    cli(
+        &sandbox,
+        &api,
+        &["--coords", "0,90", "quick", "--satellite", "geo"],
+    )
+    .assert()
+    .failure()
+    .stderr(contains(
+        "no pass of geo is in progress or starts within 10m",
+    ))
+    .stderr(contains("jobs add --satellite geo"));
+}
+
+#[tokio::test]
+async fn elements_missing_from_the_cache_are_named() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    prepare(&sandbox, &api, false);
+
+    cli(
+        &sandbox,
+        &api,
+        &["--coords", "0,0", "quick", "--satellite", "geo"],
+    )
+    .assert()
+    .failure()
+    .stderr(contains("no cached element set for NORAD 36516"))
+    .stderr(contains("predict --norad-id 36516"));
+}
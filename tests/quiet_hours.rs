@@ -0,0 +1,196 @@
+//! Quiet hours from the profile, checked against batch jobs two days from now: windows that
+//! wrap midnight, jobs that cross midnight or the edge of a window, and the override
+
+mod common;
+
+use common::{cli, created, station, write_config, Sandbox};
+use predicates::prelude::PredicateBooleanExt;
+use predicates::str::contains;
+use wiremock::matchers::{method, path};
+use wiremock::Mock;
+
+fn configure(sandbox: &Sandbox, quiet_hours: &str, timezone: &str) {
+    write_config(
+        sandbox,
+        &format!(
+            "[profiles.default]\nquiet_hours = [{}]\ntimezone = \"{}\"\n",
+            quiet_hours, timezone
+        ),
+    );
+}
+
+/// "HH:MM" two days from now, or three with `next_day`, in UTC
+fn at(time: &str, next_day: bool) -> String {
+    let days = if next_day { 3 } else { 2 };
+    format!(
+        "{}T{}:00Z",
+        (chrono::Utc::now() + chrono::Duration::days(days)).format("%Y-%m-%d"),
+        time
+    )
+}
+
+/// A test job from `start` to `end`, transmitting when `tx` is set
+fn job(start: String, end: String, tx: bool) -> serde_json::Value {
+    serde_json::json!({
+        "job_type": "test",
+        "start": start,
+        "end": end,
+        "rx_frequency": 437_500_000,
+        "tx_frequency": if tx { 145_800_000 } else { 0 },
+    })
+}
+
+fn write_batch(sandbox: &Sandbox, job: serde_json::Value) -> String {
+    let file = sandbox.path().join("jobs.json");
+    std::fs::write(&file, serde_json::json!([job]).to_string()).expect("writing the batch file");
+    file.display().to_string()
+}
+
+async fn expect_jobs(api: &wiremock::MockServer, times: u64) {
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(created(1))
+        .expect(times)
+        .mount(api)
+        .await;
+}
+
+#[tokio::test]
+async fn a_transmitting_job_in_quiet_hours_is_refused_with_the_overlap() {
+    let api = station().await;
+    expect_jobs(&api, 0).await;
+    let sandbox = Sandbox::new();
+    configure(&sandbox, "\"22:00-06:00\"", "+02:00");
+    let file = write_batch(&sandbox, job(at("19:30", false), at("20:30", false), true));
+
+    cli(&sandbox, &api, &["jobs", "add", "--batch", &file])
+        .assert()
+        .failure()
+        .stderr(contains("transmits during quiet hours"))
+        .stderr(contains("22:00 to 22:30 station time"))
+        .stderr(contains("20:00 to 20:30 UTC)"))
+        .stderr(contains("--override-quiet-hours"));
+}
+
+#[tokio::test]
+async fn a_receive_only_job_in_quiet_hours_goes_ahead_with_a_note() {
+    let api = station().await;
+    expect_jobs(&api, 1).await;
+    let sandbox = Sandbox::new();
+    configure(&sandbox, "\"22:00-06:00\"", "utc");
+    let file = write_batch(&sandbox, job(at("23:00", false), at("23:30", false), false));
+
+    cli(&sandbox, &api, &["jobs", "add", "--batch", &file])
+        .assert()
+        .success()
+        .stderr(contains("Receive-only, so allowed in quiet hours"))
+        .stderr(contains("23:00 to 23:30 UTC"));
+}
+
+#[tokio::test]
+async fn a_window_wrapping_midnight_catches_the_morning_side() {
+    let api = station().await;
+    expect_jobs(&api, 0).await;
+    let sandbox = Sandbox::new();
+    configure(&sandbox, "\"22:00-06:00\"", "utc");
+    // The quiet hours began the evening before the day the job is on
+    let file = write_batch(&sandbox, job(at("05:50", false), at("06:20", false), true));
+
+    cli(&sandbox, &api, &["jobs", "add", "--batch", &file])
+        .assert()
+        .failure()
+        .stderr(contains(format!(
+            "{} 05:50 to 06:00 UTC",
+            &at("05:50", false)[..10]
+        )));
+}
+
+#[tokio::test]
+async fn a_job_crossing_midnight_meets_quiet_hours_on_the_next_day() {
+    let api = station().await;
+    expect_jobs(&api, 0).await;
+    let sandbox = Sandbox::new();
+    configure(&sandbox, "\"01:00-03:00\"", "utc");
+    let file = write_batch(&sandbox, job(at("23:50", false), at("01:10", true), true));
+
+    cli(&sandbox, &api, &["jobs", "add", "--batch", &file])
+        .assert()
+        .failure()
+        .stderr(contains(format!(
+            "{} 01:00 to 01:10 UTC",
+            &at("01:00", true)[..10]
+        )));
+}
+
+#[tokio::test]
+async fn a_job_ending_where_quiet_hours_begin_is_clear() {
+    let api = station().await;
+    expect_jobs(&api, 1).await;
+    let sandbox = Sandbox::new();
+    configure(&sandbox, "\"08:00-09:00\", \"22:00-06:00\"", "utc");
+    let file = write_batch(&sandbox, job(at("21:30", false), at("22:00", false), true));
+
+    cli(&sandbox, &api, &["jobs", "add", "--batch", &file])
+        .assert()
+        .success()
+        .stderr(contains("quiet hours").not());
+}
+
+#[tokio::test]
+async fn the_override_needs_a_terminal_to_type_the_confirmation() {
+    let api = station().await;
+    expect_jobs(&api, 0).await;
+    let sandbox = Sandbox::new();
+    configure(&sandbox, "\"22:00-06:00\"", "utc");
+    let file = write_batch(&sandbox, job(at("23:00", false), at("23:30", false), true));
+
+    cli(
+        &sandbox,
+        &api,
+        &["--override-quiet-hours", "jobs", "add", "--batch", &file],
+    )
+    .assert()
+    .failure()
+    .stderr(contains("Transmits during quiet hours: "))
+    .stderr(contains("not a terminal"));
+}
+
+#[tokio::test]
+async fn a_confirmed_override_is_submitted_and_audited() {
+    let api = station().await;
+    expect_jobs(&api, 1).await;
+    let sandbox = Sandbox::new();
+    configure(&sandbox, "\"22:00-06:00\"", "utc");
+    let file = write_batch(&sandbox, job(at("23:00", false), at("23:30", false), true));
+
+    let mut terminal = sandbox.spawn(
+        &api,
+        &["--override-quiet-hours", "jobs", "add", "--batch", &file],
+    );
+    terminal.answer("Type override to transmit during quiet hours:", "override");
+    let (output, code) = terminal.finish();
+    assert_eq!(code, 0, "{}", output);
+
+    let audit = std::fs::read_to_string(sandbox.path().join("state/rustar/audit.jsonl"))
+        .expect("reading the audit log");
+    let entry: serde_json::Value =
+        serde_json::from_str(audit.lines().last().expect("an audit entry")).expect("JSON");
+    assert_eq!(
+        entry["quiet_hours_overrides"][0],
+        format!("{} 23:00 to 23:30 UTC", &at("23:00", false)[..10])
+    );
+}
+
+#[tokio::test]
+async fn a_malformed_span_is_reported_with_the_setting() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    configure(&sandbox, "\"22:00 to 06:00\"", "utc");
+    let file = write_batch(&sandbox, job(at("12:00", false), at("12:30", false), true));
+
+    cli(&sandbox, &api, &["jobs", "add", "--batch", &file])
+        .assert()
+        .failure()
+        .stderr(contains("profiles.default.quiet_hours"))
+        .stderr(contains("like 22:00-06:00"));
+}
@@ -0,0 +1,295 @@
+//! Read-only terminals: every command in the tree is either changing or reading, the
+//! changing ones are refused before anything reaches the station, and the reading ones
+//! are left alone
+
+mod common;
+
+use common::{cli, configure, station, write_config, Sandbox};
+use predicates::prelude::PredicateBooleanExt;
+use predicates::str::contains;
+use std::collections::BTreeSet;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, ResponseTemplate};
+
+/// Every command that changes something, with arguments it would otherwise run with
+const CHANGING: &[(&str, &[&str])] = &[
+    ("jobs add", &["jobs", "add", "--batch", "jobs.json"]),
+    (
+        "jobs reschedule",
+        &["jobs", "reschedule", "7", "--shift", "+1m"],
+    ),
+    ("jobs update", &["jobs", "update", "7", "--patch", "{}"]),
+    ("jobs cancel", &["jobs", "cancel", "7", "--yes"]),
+    ("jobs restore", &["jobs", "restore", "7"]),
+    ("jobs rebase", &["jobs", "rebase", "8", "--after", "7"]),
+    (
+        "jobs import-handoff",
+        &["jobs", "import-handoff", "bundle.json"],
+    ),
+    ("import", &["import", "passes.json"]),
+    (
+        "predict --pick",
+        &["predict", "--pick", "--tle-file", "iss.tle"],
+    ),
+    ("quick", &["quick", "--satellite", "iss"]),
+    ("tle push", &["tle", "push", "--file", "iss.tle"]),
+    (
+        "sat set-freq",
+        &["sat", "set-freq", "iss", "--rx", "145.8M"],
+    ),
+    ("sat group add", &["sat", "group", "add", "leo", "iss"]),
+    (
+        "sat group remove",
+        &["sat", "group", "remove", "leo", "iss"],
+    ),
+    ("init", &["init", "--non-interactive"]),
+    ("config set-location", &["config", "set-location", "0", "0"]),
+    ("undo", &["undo", "--yes"]),
+    (
+        "campaign cancel",
+        &["campaign", "cancel", "survey", "--yes"],
+    ),
+    (
+        "campaign clone",
+        &["campaign", "clone", "survey", "--shift", "7d"],
+    ),
+    ("queue flush", &["queue", "flush"]),
+    ("history clear-prompts", &["history", "clear-prompts"]),
+    ("purge", &["purge", "--yes"]),
+    ("backup import", &["backup", "import", "backup.tar.gz"]),
+    ("smoke-test", &["smoke-test"]),
+    (
+        "jobs sweep",
+        &[
+            "jobs",
+            "sweep",
+            "--satellite",
+            "iss",
+            "--from",
+            "435M",
+            "--to",
+            "438M",
+            "--step",
+            "25k",
+            "--dwell",
+            "60s",
+            "--yes",
+        ],
+    ),
+    (
+        "jobs audit-tles --refresh",
+        &["jobs", "audit-tles", "--refresh"],
+    ),
+];
+
+/// Every command that only reads; import, predict and purge read without --pick or with
+/// --dry-run, and the shell checks each command typed into it
+const READING: &[&str] = &[
+    "jobs list",
+    "jobs next",
+    "jobs tail",
+    "jobs diff",
+    "jobs handoff",
+    "jobs download",
+    "jobs audit-tles",
+    "jobs sweep",
+    "import",
+    "stats",
+    "station-info",
+    "whoami",
+    "tle pull",
+    "tle compare",
+    "tle refresh-all",
+    "predict",
+    "look-angles",
+    "report",
+    "export coordination",
+    "simulate",
+    "sat list",
+    "sat group list",
+    "mock-server",
+    "mqtt follow",
+    "config list",
+    "config resolve",
+    "campaign list",
+    "campaign show",
+    "queue list",
+    "history list",
+    "history show",
+    "history replay",
+    "receipts verify",
+    "audit",
+    "alias list",
+    "doctor",
+    "schema",
+    "examples",
+    "purge",
+    "backup export",
+    "shell",
+];
+
+/// Subcommand names listed under "Commands:" in the help of `path`
+fn subcommands(sandbox: &Sandbox, api: &impl common::Api, path: &[String]) -> Vec<String> {
+    let mut args: Vec<&str> = path.iter().map(String::as_str).collect();
+    args.push("--help");
+    let output = cli(sandbox, api, &args)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let help = String::from_utf8(output).expect("UTF-8 help");
+    help.lines()
+        .skip_while(|line| *line != "Commands:")
+        .skip(1)
+        .take_while(|line| !line.trim().is_empty())
+        .filter_map(|line| line.split_whitespace().next())
+        .filter(|name| *name != "help")
+        .map(str::to_string)
+        .collect()
+}
+
+/// Every command of the tree that runs something, as "jobs add"
+fn leaves(sandbox: &Sandbox, api: &impl common::Api, path: Vec<String>) -> Vec<String> {
+    let children = subcommands(sandbox, api, &path);
+    if children.is_empty() {
+        return vec![path.join(" ")];
+    }
+    children
+        .into_iter()
+        .flat_map(|child| {
+            let mut path = path.clone();
+            path.push(child);
+            leaves(sandbox, api, path)
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn every_command_in_the_tree_is_classified() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+
+    let tree: BTreeSet<String> = leaves(&sandbox, &api, Vec::new()).into_iter().collect();
+    let classified: BTreeSet<String> = CHANGING
+        .iter()
+        .map(|(name, _)| {
+            name.trim_end_matches(" --pick")
+                .trim_end_matches(" --refresh")
+                .to_string()
+        })
+        .chain(READING.iter().map(|name| name.to_string()))
+        .collect();
+    assert_eq!(
+        tree.difference(&classified).collect::<Vec<_>>(),
+        Vec::<&String>::new(),
+        "commands neither changing nor reading"
+    );
+    assert_eq!(
+        classified.difference(&tree).collect::<Vec<_>>(),
+        Vec::<&String>::new(),
+        "classified commands that no longer exist"
+    );
+}
+
+#[tokio::test]
+async fn changing_commands_are_refused_before_any_request() {
+    let api = wiremock::MockServer::start().await;
+    let sandbox = Sandbox::new();
+
+    for (name, args) in CHANGING {
+        let mut args = args.to_vec();
+        args.insert(0, "--read-only");
+        cli(&sandbox, &api, &args)
+            .assert()
+            .failure()
+            .stderr(contains(format!(
+                "Not allowed: {} changes the schedule or this CLI's records, and this \
+                 terminal is read-only (--read-only)",
+                name
+            )))
+            .stderr(contains("API Client initialized").not());
+    }
+    let requests = api.received_requests().await.unwrap_or_default();
+    assert!(requests.is_empty(), "{:?}", requests);
+    assert!(!sandbox.path().join("config/rustar/config.toml").exists());
+}
+
+#[tokio::test]
+async fn the_profile_setting_refuses_as_the_flag_does() {
+    let api = wiremock::MockServer::start().await;
+    let sandbox = Sandbox::new();
+    write_config(
+        &sandbox,
+        "default_profile = \"lobby\"\n[profiles.lobby]\nread_only = true\n",
+    );
+
+    cli(&sandbox, &api, &["jobs", "cancel", "7", "--yes"])
+        .assert()
+        .failure()
+        .stderr(contains("jobs cancel changes the schedule"))
+        .stderr(contains("(profile lobby sets read_only)"))
+        .stderr(contains("make changes from an operator terminal"));
+    assert!(api.received_requests().await.unwrap_or_default().is_empty());
+}
+
+#[tokio::test]
+async fn reading_commands_run_as_usual() {
+    let api = station().await;
+    Mock::given(method("GET"))
+        .and(path("/jobs"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+        .expect(1)
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+
+    cli(&sandbox, &api, &["--read-only", "jobs", "list"])
+        .assert()
+        .success();
+    cli(&sandbox, &api, &["--read-only", "sat", "list"])
+        .assert()
+        .success();
+    cli(&sandbox, &api, &["--read-only", "purge", "--dry-run"])
+        .assert()
+        .success();
+    cli(&sandbox, &api, &["--read-only", "config", "list"])
+        .assert()
+        .success()
+        .stderr(contains("read-only").not());
+}
+
+#[test]
+fn the_check_leaves_the_env_file_to_the_configuration_load() {
+    let sandbox = Sandbox::new();
+    configure(&sandbox, "base_url = \"http://127.0.0.1:9\"\n");
+    std::fs::write(
+        sandbox.path().join("config/rustar/.env"),
+        "API_BASE_URL=http://127.0.0.1:8\n",
+    )
+    .unwrap();
+
+    assert_cmd::Command::from_std(sandbox.without_api(&["-v", "queue", "flush"]))
+        .assert()
+        .success()
+        .stderr(contains("(1 variable(s) applied)"))
+        .stderr(contains("using http://127.0.0.1:8 (env file"));
+}
+
+#[tokio::test]
+async fn a_read_only_profile_chosen_in_the_env_file_refuses() {
+    let api = wiremock::MockServer::start().await;
+    let sandbox = Sandbox::new();
+    write_config(&sandbox, "[profiles.lobby]\nread_only = true\n");
+    std::fs::write(
+        sandbox.path().join("config/rustar/.env"),
+        "RUSTAR_PROFILE=lobby\n",
+    )
+    .unwrap();
+
+    cli(&sandbox, &api, &["jobs", "cancel", "7", "--yes"])
+        .assert()
+        .failure()
+        .stderr(contains("(profile lobby sets read_only)"));
+    assert!(api.received_requests().await.unwrap_or_default().is_empty());
+}
@@ -0,0 +1,283 @@
+//! Receipts of accepted submissions: one file each, chained by hash so `receipts verify`
+//! finds a receipt removed, changed or replaced, optionally signed, and never in the way
+//! of the submission itself
+
+mod common;
+
+use common::{cli, created, station, write_config, Sandbox};
+use predicates::prelude::PredicateBooleanExt;
+use predicates::str::contains;
+use serde_json::{json, Value};
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer};
+
+fn receipts(sandbox: &Sandbox) -> PathBuf {
+    sandbox.path().join("state/rustar/receipts")
+}
+
+fn receipt(sandbox: &Sandbox, sequence: u64) -> Value {
+    let path = receipts(sandbox).join(format!("{:06}.json", sequence));
+    let contents = std::fs::read_to_string(&path).expect("a receipt");
+    serde_json::from_str(&contents).expect("a JSON receipt")
+}
+
+/// A station that takes jobs as job 7, with a request ID on its answer
+async fn taking() -> MockServer {
+    let api = station().await;
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(created(7).insert_header("X-Request-Id", "req-abc-123"))
+        .mount(&api)
+        .await;
+    api
+}
+
+/// Submit a batch of `count` loopback tests
+fn submit(sandbox: &Sandbox, api: &MockServer, count: usize) -> assert_cmd::assert::Assert {
+    let start = chrono::Utc::now() + chrono::Duration::days(2);
+    let jobs: Vec<Value> = (0..count)
+        .map(|i| {
+            let start = start + chrono::Duration::hours(i as i64);
+            json!({
+                "job_type": "test",
+                "start": start.to_rfc3339(),
+                "end": (start + chrono::Duration::minutes(10)).to_rfc3339(),
+                "rx_frequency": 437_500_000,
+                "tx_frequency": 0,
+            })
+        })
+        .collect();
+    let file = sandbox.path().join("jobs.json");
+    std::fs::write(&file, Value::Array(jobs).to_string()).expect("writing the batch");
+    cli(
+        sandbox,
+        api,
+        &["jobs", "add", "--batch", &file.display().to_string()],
+    )
+    .assert()
+}
+
+fn verify(sandbox: &Sandbox, api: &MockServer) -> assert_cmd::assert::Assert {
+    cli(sandbox, api, &["receipts", "verify"]).assert()
+}
+
+/// A config file whose default profile, `lab`, has `profile`'s settings
+fn configure_lab(sandbox: &Sandbox, profile: &str) {
+    write_config(
+        sandbox,
+        &format!("default_profile = \"lab\"\n\n[profiles.lab]\n{}\n", profile),
+    );
+}
+
+#[tokio::test]
+async fn each_submission_leaves_a_receipt_linked_to_the_one_before() {
+    let api = taking().await;
+    let sandbox = Sandbox::new();
+
+    submit(&sandbox, &api, 2).success();
+
+    let first = receipt(&sandbox, 1);
+    let second = receipt(&sandbox, 2);
+    assert_eq!(first["sequence"], 1);
+    assert_eq!(first["previous"], "0".repeat(64));
+    assert_eq!(second["previous"], first["hash"]);
+    assert_eq!(first["request_id"], "req-abc-123");
+    assert_eq!(first["job_id"], "7");
+    assert_eq!(first["base_url"], api.uri());
+    assert_eq!(
+        first["request"]["rx_frequency"].as_f64(),
+        Some(437_500_000.0)
+    );
+    assert_eq!(first["response"]["status"], "created");
+    let keys: Vec<&String> = first["request"]
+        .as_object()
+        .expect("a request")
+        .keys()
+        .collect();
+    let mut sorted = keys.clone();
+    sorted.sort();
+    assert_eq!(keys, sorted, "the request keys are in canonical order");
+    assert!(first["submitted_at"].is_string() && first["answered_at"].is_string());
+
+    verify(&sandbox, &api)
+        .success()
+        .stdout(contains("2 receipt(s)"))
+        .stdout(contains("form an unbroken chain"));
+}
+
+#[tokio::test]
+async fn a_changed_receipt_breaks_the_chain_where_it_was_changed() {
+    let api = taking().await;
+    let sandbox = Sandbox::new();
+    submit(&sandbox, &api, 3).success();
+
+    let path = receipts(&sandbox).join("000002.json");
+    let mut changed = receipt(&sandbox, 2);
+    changed["request"]["rx_frequency"] = json!(145_800_000);
+    std::fs::write(&path, changed.to_string()).expect("changing the receipt");
+
+    verify(&sandbox, &api)
+        .code(6)
+        .stdout(contains("breaks at receipt 2"))
+        .stdout(contains("changed after it was written"))
+        .stdout(contains("1 receipt(s) before it hold"));
+}
+
+#[tokio::test]
+async fn a_receipt_rewritten_with_a_fresh_hash_no_longer_follows_the_one_before() {
+    let api = taking().await;
+    let sandbox = Sandbox::new();
+    submit(&sandbox, &api, 2).success();
+
+    // Replaced by a receipt of its own chain: a valid hash, but not linked to receipt 1
+    let other = Sandbox::new();
+    submit(&other, &api, 2).success();
+    std::fs::copy(
+        receipts(&other).join("000002.json"),
+        receipts(&sandbox).join("000002.json"),
+    )
+    .expect("replacing the receipt");
+
+    verify(&sandbox, &api)
+        .code(6)
+        .stdout(contains("breaks at receipt 2"));
+}
+
+#[tokio::test]
+async fn removed_receipts_are_noticed_in_the_middle_and_at_the_end() {
+    let api = taking().await;
+    let sandbox = Sandbox::new();
+    submit(&sandbox, &api, 3).success();
+
+    std::fs::remove_file(receipts(&sandbox).join("000003.json")).expect("removing a receipt");
+    verify(&sandbox, &api)
+        .code(6)
+        .stdout(contains("the newest receipt, 3, was removed"));
+
+    std::fs::remove_file(receipts(&sandbox).join("000001.json")).expect("removing a receipt");
+    verify(&sandbox, &api)
+        .code(6)
+        .stdout(contains("breaks at receipt 1"))
+        .stdout(contains("receipt 1 is missing"));
+}
+
+#[tokio::test]
+async fn verify_reports_as_json_for_scripts() {
+    let api = taking().await;
+    let sandbox = Sandbox::new();
+    submit(&sandbox, &api, 1).success();
+    std::fs::remove_file(receipts(&sandbox).join("head.json")).expect("removing the head");
+
+    let output = cli(&sandbox, &api, &["--output", "json", "receipts", "verify"])
+        .assert()
+        .code(6)
+        .get_output()
+        .stdout
+        .clone();
+    let report: Value = serde_json::from_slice(&output).expect("a JSON report");
+    assert_eq!(report["receipts"], 1, "{}", report);
+    assert_eq!(report["broken"]["sequence"], 1, "{}", report);
+}
+
+#[tokio::test]
+async fn a_receipt_that_cannot_be_written_is_a_loud_warning_not_a_failure() {
+    let api = taking().await;
+    let sandbox = Sandbox::new();
+    let state = sandbox.path().join("state/rustar");
+    std::fs::create_dir_all(&state).expect("creating the state directory");
+    // A file where the receipts directory should be
+    std::fs::write(state.join("receipts"), "").expect("blocking the receipts directory");
+
+    submit(&sandbox, &api, 1)
+        .success()
+        .stderr(contains("No receipt was written"))
+        .stderr(contains("The job is scheduled"));
+}
+
+#[tokio::test]
+async fn receipts_can_be_turned_off() {
+    let api = taking().await;
+    let sandbox = Sandbox::new();
+    configure_lab(&sandbox, "job_receipts = false");
+
+    submit(&sandbox, &api, 1).success();
+    assert!(!receipts(&sandbox).exists());
+}
+
+/// A stand-in for minisign: signs with the message's checksum and verifies by comparing
+const SIGNER: &str = r#"#!/bin/sh
+mode=$1; shift
+while [ $# -gt 0 ]; do
+  case $1 in
+    -m) message=$2; shift 2 ;;
+    -x) signature=$2; shift 2 ;;
+    *) shift ;;
+  esac
+done
+case $mode in
+  -S) cksum < "$message" > "$signature" ;;
+  -V) [ "$(cksum < "$message")" = "$(cat "$signature")" ] || { echo "Signature verification failed" >&2; exit 1; } ;;
+esac
+"#;
+
+fn signer(dir: &Path, script: &str) -> String {
+    let path = dir.join("minisign.sh");
+    std::fs::write(&path, script).expect("writing the signer");
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
+        .expect("making the signer executable");
+    path.display().to_string()
+}
+
+#[tokio::test]
+async fn signed_receipts_have_their_signatures_checked() {
+    let api = taking().await;
+    let sandbox = Sandbox::new();
+    let command = signer(sandbox.path(), SIGNER);
+    configure_lab(
+        &sandbox,
+        &format!(
+            "receipt_signing = {{ secret_key = \"receipts.key\", public_key = \"receipts.pub\", \
+             command = \"{}\" }}",
+            command
+        ),
+    );
+
+    submit(&sandbox, &api, 2).success();
+    assert!(receipts(&sandbox).join("000001.json.minisig").exists());
+    verify(&sandbox, &api)
+        .success()
+        .stdout(contains("2 signature(s) verified, 0 receipt(s) unsigned"));
+
+    std::fs::write(receipts(&sandbox).join("000002.json.minisig"), "forged\n")
+        .expect("forging a signature");
+    verify(&sandbox, &api)
+        .code(6)
+        .stdout(contains("breaks at receipt 2"))
+        .stdout(contains("its signature does not verify"));
+}
+
+#[tokio::test]
+async fn a_signer_that_fails_leaves_the_receipt_unsigned_with_a_warning() {
+    let api = taking().await;
+    let sandbox = Sandbox::new();
+    let command = signer(
+        sandbox.path(),
+        "#!/bin/sh\necho 'wrong password' >&2\nexit 1\n",
+    );
+    configure_lab(
+        &sandbox,
+        &format!(
+            "receipt_signing = {{ secret_key = \"receipts.key\", command = \"{}\" }}",
+            command
+        ),
+    );
+
+    submit(&sandbox, &api, 1)
+        .success()
+        .stderr(contains("was written but not signed"))
+        .stderr(contains("wrong password"))
+        .stderr(contains("No receipt was written").not());
+    assert!(receipts(&sandbox).join("000001.json").exists());
+}
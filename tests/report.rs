@@ -0,0 +1,254 @@
+//! report: one job's definition, results, predicted pass and notes in a single document,
+//! with sections whose source is missing left out and said so
+
+mod common;
+
+use common::{cli, loopback_job, Sandbox};
+use predicates::prelude::PredicateBooleanExt;
+use predicates::str::contains;
+use serde_json::Value;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const ISS: &str = "ISS (ZARYA)
+1 25544U 98067A   25235.75642456  .00011222  00000+0  20339-3 0  9993
+2 25544  51.6355 332.1708 0003307 260.2831  99.7785 15.50129787525648
+";
+
+const COORDS: &str = "-34.6,-58.4";
+
+/// The window of the first ISS pass over Buenos Aires on the day after the epoch
+fn pass_window(sandbox: &Sandbox, api: &MockServer) -> (String, String) {
+    let file = sandbox.path().join("iss.tle");
+    std::fs::write(&file, ISS).expect("writing the element set");
+    let output = cli(
+        sandbox,
+        api,
+        &[
+            "--coords",
+            COORDS,
+            "--output",
+            "json",
+            "predict",
+            "--tle-file",
+            &file.display().to_string(),
+            "--from",
+            "2025-08-24T00:00:00Z",
+            "--hours",
+            "24",
+        ],
+    )
+    .assert()
+    .success()
+    .get_output()
+    .stdout
+    .clone();
+    let passes: Value = serde_json::from_slice(&output).expect("JSON passes");
+    let pass = &passes[0];
+    (
+        pass["rise"].as_str().expect("a rise time").to_string(),
+        pass["set"].as_str().expect("a set time").to_string(),
+    )
+}
+
+/// A server holding tracking job 7 over `window`, with results when `results` is given
+async fn server(window: (String, String), results: Option<Value>) -> MockServer {
+    let api = MockServer::start().await;
+    let mut lines = ISS.lines();
+    let job = serde_json::json!({
+        "id": 7,
+        "status": "completed",
+        "job_type": "tracking",
+        "tle": {
+            "tle0": lines.next(),
+            "tle1": lines.next(),
+            "tle2": lines.next(),
+        },
+        "start": window.0,
+        "end": window.1,
+        "rx_frequency": 145_800_000,
+        "tx_frequency": 0,
+        "mode": "AFSK",
+        "baud": 1200,
+        "notes": "Antenna swapped the day before",
+    });
+    Mock::given(method("GET"))
+        .and(path("/jobs/7"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(job))
+        .mount(&api)
+        .await;
+    if let Some(results) = results {
+        Mock::given(method("GET"))
+            .and(path("/jobs/7/results"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(results))
+            .mount(&api)
+            .await;
+    }
+    api
+}
+
+fn results() -> Value {
+    serde_json::json!({
+        "frames_decoded": 1342,
+        "recordings": [
+            { "name": "iq.sigmf-data", "size_bytes": 52_428_800 },
+            { "name": "audio.wav", "size_bytes": 1_048_576 },
+        ],
+        "notes": "Strong signal past culmination",
+    })
+}
+
+#[tokio::test]
+async fn every_section_comes_together_in_markdown() {
+    let sandbox = Sandbox::new();
+    let api = server(
+        pass_window(&sandbox, &MockServer::start().await),
+        Some(results()),
+    )
+    .await;
+
+    cli(
+        &sandbox,
+        &api,
+        &["--coords", COORDS, "report", "7", "--notes", "Clear sky"],
+    )
+    .assert()
+    .success()
+    .stdout(contains("# Pass report: ISS (ZARYA)"))
+    .stdout(contains("Job 7 · completed"))
+    .stdout(contains("- Mode: AFSK at 1200 baud"))
+    .stdout(contains("- Frames decoded: 1,342"))
+    .stdout(contains("  - `iq.sigmf-data`: 50.0 MiB"))
+    .stdout(contains("## Predicted pass"))
+    .stdout(contains("- Max elevation:"))
+    .stdout(contains("- Clear sky"))
+    .stdout(contains("- Antenna swapped the day before"))
+    .stdout(contains("- Strong signal past culmination"))
+    .stdout(contains("## Left out").not());
+}
+
+#[tokio::test]
+async fn json_holds_the_same_sections() {
+    let sandbox = Sandbox::new();
+    let window = pass_window(&sandbox, &MockServer::start().await);
+    let api = server(window.clone(), Some(results())).await;
+
+    let output = cli(
+        &sandbox,
+        &api,
+        &["--coords", COORDS, "report", "7", "--format", "json"],
+    )
+    .assert()
+    .success()
+    .get_output()
+    .stdout
+    .clone();
+    let report: Value = serde_json::from_slice(&output).expect("one JSON document");
+    assert_eq!(report["job"]["id"], "7");
+    assert_eq!(report["job"]["label"], "ISS (ZARYA)");
+    assert_eq!(report["outcome"]["frames_decoded"], 1342);
+    assert_eq!(report["outcome"]["recordings"][1]["name"], "audio.wav");
+    let max = report["geometry"]["max_elevation_deg"]
+        .as_f64()
+        .expect("a max elevation");
+    assert!(max > 0.0 && max <= 90.0, "{}", report);
+    let above = report["geometry"]["above_mask_seconds"]
+        .as_i64()
+        .expect("a time above the mask");
+    let length = chrono::DateTime::parse_from_rfc3339(&window.1).expect("the set time")
+        - chrono::DateTime::parse_from_rfc3339(&window.0).expect("the rise time");
+    assert!(above > 0 && above <= length.num_seconds(), "{}", report);
+    assert_eq!(report["omitted"], serde_json::json!([]));
+}
+
+#[tokio::test]
+async fn missing_sources_are_left_out_with_a_note() {
+    let sandbox = Sandbox::new();
+    let api = server(pass_window(&sandbox, &MockServer::start().await), None).await;
+
+    cli(&sandbox, &api, &["report", "7"])
+        .assert()
+        .success()
+        .stdout(contains("# Pass report: ISS (ZARYA)"))
+        .stdout(contains("## Outcome").not())
+        .stdout(contains("## Predicted pass").not())
+        .stdout(contains("## Left out"))
+        .stdout(contains("the server keeps no results for job 7"))
+        .stdout(contains("the profile gives no station position"))
+        .stderr(contains("Left out of the report"));
+}
+
+#[tokio::test]
+async fn a_job_without_a_satellite_has_no_pass_to_predict() {
+    let api = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/jobs/3"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(loopback_job("3")))
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+
+    cli(&sandbox, &api, &["--coords", COORDS, "report", "3"])
+        .assert()
+        .success()
+        .stdout(contains("# Pass report: loopback test"))
+        .stdout(contains("a test job follows no satellite"));
+}
+
+#[tokio::test]
+async fn a_template_lays_the_report_out_and_goes_to_a_file() {
+    let sandbox = Sandbox::new();
+    let api = server(
+        pass_window(&sandbox, &MockServer::start().await),
+        Some(results()),
+    )
+    .await;
+    let template = sandbox.path().join("report.txt");
+    std::fs::write(
+        &template,
+        "{{job.label}} / job {{ job.id }}: {{outcome.frames_decoded}} frames, \
+         first recording {{outcome.recordings.0.name}}\n{{notes}}\n",
+    )
+    .expect("writing the template");
+    let target = sandbox.path().join("out.txt");
+
+    cli(
+        &sandbox,
+        &api,
+        &[
+            "--coords",
+            COORDS,
+            "report",
+            "7",
+            "--template",
+            &template.display().to_string(),
+            "--output-file",
+            &target.display().to_string(),
+        ],
+    )
+    .assert()
+    .success()
+    .stdout("");
+    assert_eq!(
+        std::fs::read_to_string(&target).expect("reading the report"),
+        "ISS (ZARYA) / job 7: 1342 frames, first recording iq.sigmf-data\n\
+         Antenna swapped the day before\nStrong signal past culmination\n"
+    );
+}
+
+#[tokio::test]
+async fn a_template_naming_no_section_is_refused() {
+    let sandbox = Sandbox::new();
+    let api = server(pass_window(&sandbox, &MockServer::start().await), None).await;
+    let template = sandbox.path().join("report.txt");
+    std::fs::write(&template, "{{jbo.label}}\n").expect("writing the template");
+
+    cli(
+        &sandbox,
+        &api,
+        &["report", "7", "--template", &template.display().to_string()],
+    )
+    .assert()
+    .failure()
+    .stderr(contains("{{jbo.label}} names nothing in the report"));
+}
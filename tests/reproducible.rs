@@ -0,0 +1,123 @@
+//! --reproducible with RUSTAR_FAKE_NOW: the demo station's output, byte for byte, against
+//! the files in tests/golden. Run with RUSTAR_UPDATE_GOLDEN=1 to write them afresh.
+
+mod common;
+
+use common::Sandbox;
+use predicates::str::contains;
+use std::path::PathBuf;
+
+const NOW: &str = "2026-03-01T12:00:00Z";
+
+fn cli(sandbox: &Sandbox, args: &[&str]) -> assert_cmd::Command {
+    let mut command = sandbox.without_api(&[&["--demo", "--reproducible"], args].concat());
+    command.env("RUSTAR_FAKE_NOW", NOW);
+    assert_cmd::Command::from_std(command)
+}
+
+fn stdout(command: &mut assert_cmd::Command) -> String {
+    let output = command.assert().success().get_output().stdout.clone();
+    String::from_utf8(output).expect("UTF-8 output")
+}
+
+/// `output` is what tests/golden/`name` holds
+fn golden(name: &str, output: &str) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(name);
+    if std::env::var_os("RUSTAR_UPDATE_GOLDEN").is_some() {
+        std::fs::write(&path, output).expect("writing the golden file");
+        return;
+    }
+    let expected = std::fs::read_to_string(&path).expect("reading the golden file");
+    assert!(
+        output == expected,
+        "{} differs from the output:\n{}",
+        path.display(),
+        output
+    );
+}
+
+#[test]
+fn the_job_list_is_the_golden_one() {
+    let sandbox = Sandbox::new();
+    golden(
+        "jobs_list.txt",
+        &stdout(&mut cli(&sandbox, &["jobs", "list"])),
+    );
+}
+
+#[test]
+fn the_timeline_is_the_golden_one() {
+    let sandbox = Sandbox::new();
+    golden(
+        "jobs_timeline.txt",
+        &stdout(&mut cli(&sandbox, &["jobs", "list", "--timeline"])),
+    );
+}
+
+#[test]
+fn ndjson_is_sorted_like_the_table() {
+    let sandbox = Sandbox::new();
+    golden(
+        "jobs_list.ndjson",
+        &stdout(&mut cli(&sandbox, &["--output", "ndjson", "jobs", "list"])),
+    );
+}
+
+#[test]
+fn passes_are_the_golden_ones() {
+    let sandbox = Sandbox::new();
+    golden(
+        "predict.txt",
+        &stdout(&mut cli(&sandbox, &["predict", "--norad-id", "33591"])),
+    );
+}
+
+#[test]
+fn the_terminal_width_time_zone_and_locale_change_nothing() {
+    let sandbox = Sandbox::new();
+    let mut command = cli(&sandbox, &["jobs", "list"]);
+    command
+        .env("COLUMNS", "60")
+        .env("TZ", "America/Argentina/Buenos_Aires")
+        .env("LANG", "de_DE.UTF-8")
+        .env("LC_ALL", "de_DE.UTF-8");
+    golden("jobs_list.txt", &stdout(&mut command));
+}
+
+#[test]
+fn the_environment_variable_turns_it_on_as_the_flag_does() {
+    let sandbox = Sandbox::new();
+    let mut command = sandbox.without_api(&["--demo", "jobs", "list"]);
+    command
+        .env("RUSTAR_FAKE_NOW", NOW)
+        .env("RUSTAR_REPRODUCIBLE", "1");
+    golden(
+        "jobs_list.txt",
+        &stdout(&mut assert_cmd::Command::from_std(command)),
+    );
+}
+
+#[test]
+fn without_it_the_list_speaks_of_times_relative_to_the_fake_now() {
+    let sandbox = Sandbox::new();
+    let mut command = sandbox.without_api(&["--demo", "--utc-only", "jobs", "list"]);
+    command.env("RUSTAR_FAKE_NOW", NOW);
+    assert_cmd::Command::from_std(command)
+        .assert()
+        .success()
+        .stdout(contains("ended 1 day ago"))
+        .stdout(contains("starts in 10h 54m"));
+}
+
+#[test]
+fn a_fake_now_that_is_no_time_is_refused() {
+    let sandbox = Sandbox::new();
+    let mut command = sandbox.without_api(&["--demo", "jobs", "list"]);
+    command.env("RUSTAR_FAKE_NOW", "yesterday");
+    assert_cmd::Command::from_std(command)
+        .assert()
+        .code(2)
+        .stderr(contains("RUSTAR_FAKE_NOW 'yesterday'"));
+}
@@ -0,0 +1,129 @@
+//! `jobs reschedule` reading the job back afterwards, against a server that keeps
+//! job windows to the whole minute
+
+mod common;
+
+use chrono::{DurationRound, Timelike};
+use common::{cli, loopback_job, station, Sandbox};
+use predicates::str::contains;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, ResponseTemplate};
+
+/// GET /jobs/7 answered with `before` once and `after` from then on, with no other jobs
+/// on the schedule and the move accepted
+async fn server(api: &wiremock::MockServer, before: serde_json::Value, after: serde_json::Value) {
+    Mock::given(method("GET"))
+        .and(path("/jobs/7"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(before))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(api)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/jobs/7"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(after))
+        .expect(1)
+        .mount(api)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/jobs"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+        .mount(api)
+        .await;
+    Mock::given(method("PATCH"))
+        .and(path("/jobs/7"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({ "status": "updated" })),
+        )
+        .expect(1)
+        .mount(api)
+        .await;
+}
+
+/// Three days from now at half past a minute, and the whole minute before it
+fn requested_start() -> (chrono::DateTime<chrono::Utc>, chrono::DateTime<chrono::Utc>) {
+    let minute = (chrono::Utc::now() + chrono::Duration::days(3))
+        .duration_trunc(chrono::Duration::minutes(1))
+        .expect("a whole minute");
+    (minute + chrono::Duration::seconds(30), minute)
+}
+
+#[tokio::test]
+async fn the_stored_window_is_listed_and_rounding_is_pointed_out() {
+    let api = station().await;
+    let before = loopback_job("7");
+    let (start, rounded) = requested_start();
+    let mut after = before.clone();
+    after["start"] = rounded.to_rfc3339().into();
+    after["end"] = (rounded + chrono::Duration::minutes(10))
+        .to_rfc3339()
+        .into();
+    server(&api, before, after).await;
+    let sandbox = Sandbox::new();
+
+    cli(
+        &sandbox,
+        &api,
+        &["jobs", "reschedule", "7", "--start", &start.to_rfc3339()],
+    )
+    .assert()
+    .success()
+    .stdout(contains("Job rescheduled: updated"))
+    .stdout(contains("Job 7 now holds end: "))
+    .stdout(contains(format!(
+        "→ {}",
+        rounded.format("%Y-%m-%d %H:%M:%S")
+    )))
+    .stdout(contains(format!(
+        "(requested → stored): end: {:02}:{:02}:30 → {:02}:{:02}:00, start: {:02}:{:02}:30 → {:02}:{:02}:00",
+        (start + chrono::Duration::minutes(10)).hour(),
+        (start + chrono::Duration::minutes(10)).minute(),
+        (rounded + chrono::Duration::minutes(10)).hour(),
+        (rounded + chrono::Duration::minutes(10)).minute(),
+        start.hour(),
+        start.minute(),
+        rounded.hour(),
+        rounded.minute(),
+    )));
+}
+
+#[tokio::test]
+async fn a_server_storing_what_was_sent_gets_no_warning() {
+    let api = station().await;
+    let before = loopback_job("7");
+    let (start, _) = requested_start();
+    let mut after = before.clone();
+    after["start"] = start.to_rfc3339().into();
+    after["end"] = (start + chrono::Duration::minutes(10)).to_rfc3339().into();
+    server(&api, before, after).await;
+    let sandbox = Sandbox::new();
+
+    let output = cli(
+        &sandbox,
+        &api,
+        &[
+            "--output",
+            "json",
+            "jobs",
+            "reschedule",
+            "7",
+            "--start",
+            &start.to_rfc3339(),
+        ],
+    )
+    .assert()
+    .success()
+    .get_output()
+    .stdout
+    .clone();
+    let report: serde_json::Value = serde_json::from_slice(&output).expect("a JSON report");
+    let changed: Vec<&str> = report["changes"]
+        .as_array()
+        .expect("the changes")
+        .iter()
+        .map(|change| change["path"].as_str().expect("a path"))
+        .collect();
+    assert_eq!(changed, ["end", "start"]);
+    assert_eq!(report["normalized"], serde_json::json!([]));
+    assert_eq!(report["after"]["start"], start.to_rfc3339());
+}
@@ -0,0 +1,118 @@
+//! Cancelled jobs against the CLI's own mock server, which keeps them for a day
+
+mod common;
+
+use chrono::{Duration, Utc};
+use common::{cli, MockStation, Sandbox};
+use predicates::str::contains;
+use serde_json::json;
+use std::path::PathBuf;
+
+/// A loopback test `days` from now; `cancelled` hours ago when given
+fn job(id: u64, days: i64, cancelled: Option<i64>) -> serde_json::Value {
+    let start = Utc::now() + Duration::days(days);
+    let mut job = json!({
+        "id": id.to_string(),
+        "status": "scheduled",
+        "job_type": "test",
+        "start": start.to_rfc3339(),
+        "end": (start + Duration::minutes(10)).to_rfc3339(),
+        "rx_frequency": 437_500_000.0,
+        "tx_frequency": 0.0,
+    });
+    if let Some(hours) = cancelled {
+        job["status"] = json!("cancelled");
+        job["cancelled_at"] = json!((Utc::now() - Duration::hours(hours)).to_rfc3339());
+        job["cancel_reason"] = json!("weather");
+    }
+    job
+}
+
+/// A mock station state file holding `jobs`
+fn seed(sandbox: &Sandbox, jobs: &[serde_json::Value]) -> PathBuf {
+    let path = sandbox.path().join("mock-state.json");
+    let by_id: serde_json::Map<String, serde_json::Value> = jobs
+        .iter()
+        .map(|job| {
+            (
+                job["id"].as_str().unwrap_or_default().to_string(),
+                job.clone(),
+            )
+        })
+        .collect();
+    let state = json!({ "next_id": jobs.len(), "jobs": by_id });
+    std::fs::write(&path, state.to_string()).expect("writing the mock state");
+    path
+}
+
+#[test]
+fn cancelled_jobs_are_listed_on_request_and_restored() {
+    let sandbox = Sandbox::new();
+    let station = MockStation::start(&seed(&sandbox, &[job(1, 2, None)]));
+
+    cli(
+        &sandbox,
+        &station,
+        &["jobs", "cancel", "1", "--yes", "--reason", "weather"],
+    )
+    .assert()
+    .success();
+    cli(&sandbox, &station, &["jobs", "list"])
+        .assert()
+        .success()
+        .stdout(contains("No jobs scheduled"));
+    cli(&sandbox, &station, &["jobs", "list", "--include-cancelled"])
+        .assert()
+        .success()
+        .stdout(contains("CANCELLED (UTC)"))
+        .stdout(contains("cancelled"))
+        .stdout(contains("weather"));
+
+    cli(&sandbox, &station, &["jobs", "restore", "1"])
+        .assert()
+        .success()
+        .stdout(contains("Job restored"));
+    cli(&sandbox, &station, &["jobs", "list"])
+        .assert()
+        .success()
+        .stdout(contains("scheduled"));
+}
+
+#[test]
+fn a_taken_slot_is_refused_before_asking_the_server() {
+    let sandbox = Sandbox::new();
+    let mut taken = job(2, 2, None);
+    taken["start"] = job(1, 2, Some(1))["start"].clone();
+    let station = MockStation::start(&seed(&sandbox, &[job(1, 2, Some(1)), taken]));
+
+    cli(&sandbox, &station, &["jobs", "restore", "1"])
+        .assert()
+        .failure()
+        .stderr(contains("the slot has been taken by job 2"));
+    cli(&sandbox, &station, &["jobs", "list", "--include-cancelled"])
+        .assert()
+        .success()
+        .stdout(contains("cancelled"));
+}
+
+#[test]
+fn a_purged_job_is_reported_as_gone() {
+    let sandbox = Sandbox::new();
+    let station = MockStation::start(&seed(&sandbox, &[job(1, 2, Some(30))]));
+
+    cli(&sandbox, &station, &["jobs", "restore", "1"])
+        .assert()
+        .failure()
+        .stderr(contains("this job has been permanently purged"));
+}
+
+#[test]
+fn only_cancelled_jobs_can_be_restored() {
+    let sandbox = Sandbox::new();
+    let station = MockStation::start(&seed(&sandbox, &[job(1, 2, None)]));
+
+    cli(&sandbox, &station, &["jobs", "restore", "1"])
+        .assert()
+        .failure()
+        .stderr(contains("job 1 is not cancelled"));
+}
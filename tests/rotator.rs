@@ -0,0 +1,162 @@
+//! The profile's rotator_check: a rotctld played by a thread of the test, or a health URL,
+//! probed by doctor and before batch jobs that transmit
+
+mod common;
+
+use common::{cli, created, station, write_config, Sandbox};
+use predicates::prelude::PredicateBooleanExt;
+use predicates::str::contains;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn configure(sandbox: &Sandbox, check: &str) {
+    write_config(
+        sandbox,
+        &format!("[profiles.default.rotator_check]\n{}\n", check),
+    );
+}
+
+/// A rotctld answering every `p` it is sent with `reply`, at the address returned
+fn rotctld(reply: &'static str) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("binding the fake rotctld");
+    let address = listener.local_addr().unwrap().to_string();
+    std::thread::spawn(move || {
+        for socket in listener.incoming().flatten() {
+            let mut reader = BufReader::new(socket.try_clone().expect("a socket"));
+            let mut command = String::new();
+            while reader.read_line(&mut command).unwrap_or(0) > 0 {
+                let _ = (&socket).write_all(reply.as_bytes());
+                command.clear();
+            }
+        }
+    });
+    address
+}
+
+/// An address nothing listens on
+fn unplugged() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("binding a free port");
+    listener.local_addr().unwrap().to_string()
+}
+
+fn write_batch(sandbox: &Sandbox, tx: bool) -> String {
+    let start = chrono::Utc::now() + chrono::Duration::days(2);
+    let job = serde_json::json!({
+        "job_type": "test",
+        "start": start.to_rfc3339(),
+        "end": (start + chrono::Duration::minutes(10)).to_rfc3339(),
+        "rx_frequency": 437_500_000,
+        "tx_frequency": if tx { 145_800_000 } else { 0 },
+    });
+    let file = sandbox.path().join("jobs.json");
+    std::fs::write(&file, serde_json::json!([job]).to_string()).expect("writing the batch file");
+    file.display().to_string()
+}
+
+async fn expect_jobs(api: &MockServer, times: u64) {
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(created(1))
+        .expect(times)
+        .mount(api)
+        .await;
+}
+
+#[tokio::test]
+async fn doctor_reports_where_the_rotator_points() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    let address = rotctld("180.000000\n45.500000\n");
+    configure(&sandbox, &format!("rotctld = \"{}\"", address));
+
+    cli(&sandbox, &api, &["doctor"])
+        .assert()
+        .success()
+        .stdout(contains(format!("rotctld at {}", address)))
+        .stdout(contains("azimuth 180.0°, elevation 45.5°"));
+}
+
+#[tokio::test]
+async fn doctor_fails_with_the_probe_error() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    let address = rotctld("RPRT -6\n");
+    configure(&sandbox, &format!("rotctld = \"{}\"", address));
+
+    cli(&sandbox, &api, &["doctor"])
+        .assert()
+        .failure()
+        .stdout(contains("RPRT -6 (I/O error talking to the rotator)"));
+}
+
+#[tokio::test]
+async fn doctor_checks_the_health_url() {
+    let api = station().await;
+    Mock::given(method("GET"))
+        .and(path("/rotator/health"))
+        .respond_with(ResponseTemplate::new(503))
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+    configure(
+        &sandbox,
+        &format!("health_url = \"{}/rotator/health\"", api.uri()),
+    );
+
+    cli(&sandbox, &api, &["--output", "json", "doctor"])
+        .assert()
+        .failure()
+        .stdout(contains("\"ok\": false"))
+        .stdout(contains("503"));
+}
+
+#[tokio::test]
+async fn a_blocking_check_holds_back_jobs_that_transmit() {
+    let api = station().await;
+    expect_jobs(&api, 0).await;
+    let sandbox = Sandbox::new();
+    configure(
+        &sandbox,
+        &format!("rotctld = \"{}\"\non_failure = \"block\"", unplugged()),
+    );
+    let file = write_batch(&sandbox, true);
+
+    cli(&sandbox, &api, &["jobs", "add", "--batch", &file])
+        .assert()
+        .failure()
+        .stderr(contains("connecting to rotctld at"))
+        .stderr(contains("holds back jobs that transmit"));
+}
+
+#[tokio::test]
+async fn a_failed_check_only_warns_by_default() {
+    let api = station().await;
+    expect_jobs(&api, 1).await;
+    let sandbox = Sandbox::new();
+    configure(&sandbox, &format!("rotctld = \"{}\"", unplugged()));
+    let file = write_batch(&sandbox, true);
+
+    cli(&sandbox, &api, &["jobs", "add", "--batch", &file])
+        .assert()
+        .success()
+        .stderr(contains("The rotator check failed"));
+}
+
+#[tokio::test]
+async fn receive_only_jobs_are_not_held_back() {
+    let api = station().await;
+    expect_jobs(&api, 1).await;
+    let sandbox = Sandbox::new();
+    configure(
+        &sandbox,
+        &format!("rotctld = \"{}\"\non_failure = \"block\"", unplugged()),
+    );
+    let file = write_batch(&sandbox, false);
+
+    cli(&sandbox, &api, &["jobs", "add", "--batch", &file])
+        .assert()
+        .success()
+        .stderr(contains("rotator").not());
+}
@@ -0,0 +1,152 @@
+//! Receive offsets for satellites that transmit off their published downlink: given with
+//! --rx-offset or carried by an alias preset, added to the RX frequency and shown
+
+mod common;
+
+use common::{add_loopback, cli, configure, created, start_date, station, Sandbox};
+use predicates::str::contains;
+use serde_json::Value;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer};
+
+const GEOSAT: &str = "GEOSAT
+1 36516U 10016A   26287.50000000 -.00000276  00000-0  00000+0 0  9994
+2 36516   0.0300  90.0000 0002000  10.0000 100.0000  1.00270000 50004
+";
+
+async fn accepting() -> MockServer {
+    let api = station().await;
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(created(7))
+        .mount(&api)
+        .await;
+    api
+}
+
+async fn submitted(api: &MockServer) -> Value {
+    let requests = api.received_requests().await.unwrap_or_default();
+    requests
+        .iter()
+        .find(|r| r.method.as_str() == "POST" && r.url.path() == "/jobs")
+        .map(|r| serde_json::from_slice(&r.body).expect("a JSON job"))
+        .expect("the submitted job")
+}
+
+#[tokio::test]
+async fn the_flag_moves_the_downlink_and_the_summary_shows_the_sum() {
+    let api = accepting().await;
+    let sandbox = Sandbox::new();
+
+    let mut terminal = sandbox.spawn(&api, &["jobs", "add", "--rx-offset", "-500"]);
+    add_loopback(&mut terminal, &start_date());
+    terminal
+        .expect("RX 437.5 MHz -500 Hz")
+        .expect("437.4995 MHz")
+        .expect("Job submitted successfully");
+    let (_, code) = terminal.finish();
+    assert_eq!(code, 0);
+
+    let job = submitted(&api).await;
+    assert_eq!(job["rx_frequency"], 437_499_500.0);
+    assert_eq!(job["rx_offset_hz"], -500);
+}
+
+#[tokio::test]
+async fn an_alias_preset_applies_its_offset_with_a_notice() {
+    let api = accepting().await;
+    let sandbox = Sandbox::new();
+    cli(
+        &sandbox,
+        &api,
+        &[
+            "sat",
+            "set-freq",
+            "geo",
+            "--norad-id",
+            "36516",
+            "--rx",
+            "437.5M",
+            "--rx-offset",
+            "+2.1k",
+        ],
+    )
+    .assert()
+    .success()
+    .stdout(contains("RX 437.5 MHz +2.1 kHz"));
+    let dir = sandbox.path().join("cache/rustar/tle");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("36516.tle"), GEOSAT).unwrap();
+
+    cli(
+        &sandbox,
+        &api,
+        &["--coords", "0,0", "quick", "--satellite", "geo"],
+    )
+    .assert()
+    .success()
+    .stdout(contains(
+        "Applying the preset's receive offset: RX 437.5 MHz +2.1 kHz → 437.5021 MHz",
+    ));
+
+    let job = submitted(&api).await;
+    assert_eq!(job["rx_frequency"], 437_502_100.0);
+    assert_eq!(job["rx_offset_hz"], 2100);
+}
+
+#[tokio::test]
+async fn offsets_past_the_bound_are_refused_unless_the_profile_allows_them() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    let set = |offset: &str| {
+        cli(
+            &sandbox,
+            &api,
+            &[
+                "--explain",
+                "sat",
+                "set-freq",
+                "wide",
+                "--rx-offset",
+                offset,
+            ],
+        )
+        .assert()
+    };
+
+    set("+60k")
+        .failure()
+        .stderr(contains(
+            "+60 kHz is further off the published downlink than ±50 kHz",
+        ))
+        .stderr(contains("receive offset ±50 kHz"));
+
+    configure(&sandbox, "max_rx_offset_hz = 100000\n");
+    set("+60k").success().stdout(contains("RX offset +60 kHz"));
+}
+
+#[tokio::test]
+async fn a_batch_entry_is_held_to_the_bound() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    let start = chrono::Utc::now() + chrono::Duration::days(2);
+    let job = serde_json::json!([{
+        "job_type": "test",
+        "start": start.to_rfc3339(),
+        "end": (start + chrono::Duration::minutes(10)).to_rfc3339(),
+        "rx_frequency": 437_500_000,
+        "rx_offset_hz": -75_000,
+        "tx_frequency": 0,
+    }]);
+    let file = sandbox.path().join("jobs.json");
+    std::fs::write(&file, job.to_string()).unwrap();
+
+    cli(
+        &sandbox,
+        &api,
+        &["jobs", "add", "--batch", &file.display().to_string()],
+    )
+    .assert()
+    .failure()
+    .stderr(contains("rx_offset_hz: -75 kHz is further off"));
+}
@@ -0,0 +1,104 @@
+//! `schema` and the checks it backs: a misspelt field is named by its JSON pointer,
+//! before anything reaches the station
+
+mod common;
+
+use common::{cli, created, station, Sandbox};
+use predicates::str::contains;
+use wiremock::matchers::{method, path};
+use wiremock::Mock;
+
+fn write_batch(sandbox: &Sandbox, jobs: serde_json::Value) -> String {
+    let file = sandbox.path().join("jobs.json");
+    std::fs::write(&file, jobs.to_string()).expect("writing the batch file");
+    file.display().to_string()
+}
+
+fn loopback(start: &str, end: &str) -> serde_json::Value {
+    serde_json::json!({
+        "job_type": "test",
+        "start": start,
+        "end": end,
+        "rx_frequency": 437_500_000,
+        "tx_frequency": 0,
+    })
+}
+
+#[tokio::test]
+async fn prints_a_schema_refusing_unknown_fields() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    let output = cli(&sandbox, &api, &["schema", "job"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let schema: serde_json::Value = serde_json::from_slice(&output).expect("the schema is JSON");
+    assert_eq!(schema["unevaluatedProperties"], false);
+    assert_eq!(schema["properties"]["rx_freq"]["deprecated"], true);
+}
+
+#[tokio::test]
+async fn a_valid_batch_passes() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    let mut legacy = loopback("2026-10-21T12:00:00Z", "2026-10-21T12:10:00Z");
+    legacy["rx_freq"] = legacy["rx_frequency"].take();
+    legacy
+        .as_object_mut()
+        .expect("an object")
+        .remove("rx_frequency");
+    let file = write_batch(
+        &sandbox,
+        serde_json::json!([
+            loopback("2026-10-20T12:00:00Z", "2026-10-20T12:10:00Z"),
+            legacy
+        ]),
+    );
+
+    cli(&sandbox, &api, &["schema", "--validate-only", &file])
+        .assert()
+        .success()
+        .stdout(contains("2 job(s), all valid"));
+}
+
+#[tokio::test]
+async fn validate_only_names_each_field_at_fault() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    let mut misspelt = loopback("2026-10-20T12:00:00Z", "2026-10-20T12:10:00Z");
+    misspelt["polarisation"] = "RHCP".into();
+    let mut parked = loopback("2026-10-21T12:00:00Z", "2026-10-21T12:10:00Z");
+    parked["post_pass_action"] = "parked".into();
+    let file = write_batch(&sandbox, serde_json::json!([misspelt, parked]));
+
+    cli(&sandbox, &api, &["schema", "--validate-only", &file])
+        .assert()
+        .code(1)
+        .stderr(contains("2 of 2 job(s) have problems"))
+        .stderr(contains("[0] /polarisation"))
+        .stderr(contains(
+            "[1] /post_pass_action: value is not one of \"park\", \"hold\", \"next_job\"",
+        ));
+}
+
+#[tokio::test]
+async fn a_batch_with_an_unknown_field_is_not_submitted() {
+    let api = station().await;
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(created(7))
+        .expect(0)
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+    let mut misspelt = loopback("2026-10-20T12:00:00Z", "2026-10-20T12:10:00Z");
+    misspelt["polarisation"] = "RHCP".into();
+    let file = write_batch(&sandbox, serde_json::json!([misspelt]));
+
+    cli(&sandbox, &api, &["jobs", "add", "--batch", &file])
+        .assert()
+        .failure()
+        .stderr(contains("/polarisation"));
+}
@@ -0,0 +1,226 @@
+//! Warnings a server sends with a job it still accepts, as plain strings or objects:
+//! shown after the success line, kept in the batch report, and failed on under --strict
+
+mod common;
+
+use common::{cli, start_date, station, Sandbox};
+use predicates::prelude::PredicateBooleanExt;
+use predicates::str::contains;
+use serde_json::{json, Value};
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// A loopback test inside the submit window
+fn loopback() -> Value {
+    let date = start_date();
+    json!({
+        "job_type": "test",
+        "start": format!("{}T12:10:00Z", date),
+        "end": format!("{}T12:20:00Z", date),
+        "rx_frequency": "437.5M",
+        "tx_frequency": 0,
+    })
+}
+
+fn write_batch(sandbox: &Sandbox) -> String {
+    let file = sandbox.path().join("jobs.json");
+    std::fs::write(&file, Value::Array(vec![loopback()]).to_string())
+        .expect("writing the batch file");
+    file.display().to_string()
+}
+
+/// A station that takes jobs as job 7, warning with `warnings`
+async fn warning(warnings: Value) -> MockServer {
+    let api = station().await;
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(ResponseTemplate::new(201).set_body_json(json!({
+            "status": "created",
+            "id": 7,
+            "warnings": warnings,
+        })))
+        .mount(&api)
+        .await;
+    api
+}
+
+fn objects() -> Value {
+    json!([
+        { "code": "stale_tle", "message": "TLE epoch older than recommended", "field": "tle" },
+        { "code": "low_pass", "message": "pass max elevation below 5°" },
+    ])
+}
+
+fn report(sandbox: &Sandbox) -> Value {
+    let report = std::fs::read_to_string(sandbox.path().join("report.json")).expect("a report");
+    serde_json::from_str(&report).expect("a JSON report")
+}
+
+async fn posts(api: &MockServer) -> usize {
+    api.received_requests()
+        .await
+        .unwrap_or_default()
+        .iter()
+        .filter(|r| r.method.as_str() == "POST" && r.url.path() == "/jobs")
+        .count()
+}
+
+#[tokio::test]
+async fn object_warnings_are_shown_with_their_field_and_code() {
+    let api = warning(objects()).await;
+    let sandbox = Sandbox::new();
+    let report_path = sandbox.path().join("report.json").display().to_string();
+
+    cli(
+        &sandbox,
+        &api,
+        &[
+            "jobs",
+            "add",
+            "--batch",
+            &write_batch(&sandbox),
+            "--report",
+            &report_path,
+        ],
+    )
+    .assert()
+    .success()
+    .stderr(contains("✅ [0]"))
+    .stderr(contains(
+        "📣 [0] TLE epoch older than recommended (field tle, code stale_tle)",
+    ))
+    .stderr(contains(
+        "📣 [0] pass max elevation below 5° (code low_pass)",
+    ));
+
+    let entry = &report(&sandbox)["entries"][0];
+    assert_eq!(entry["outcome"], "created", "{}", entry);
+    assert_eq!(entry["warnings"], objects(), "{}", entry);
+}
+
+#[tokio::test]
+async fn plain_string_warnings_are_shown_as_they_came() {
+    let api = warning(json!(["pass max elevation below 5°"])).await;
+    let sandbox = Sandbox::new();
+
+    cli(
+        &sandbox,
+        &api,
+        &["jobs", "add", "--batch", &write_batch(&sandbox)],
+    )
+    .assert()
+    .success()
+    .stderr(contains("📣 [0] pass max elevation below 5°\n"));
+}
+
+#[tokio::test]
+async fn an_answer_without_warnings_shows_none() {
+    let api = warning(json!([])).await;
+    let sandbox = Sandbox::new();
+
+    cli(
+        &sandbox,
+        &api,
+        &["jobs", "add", "--batch", &write_batch(&sandbox)],
+    )
+    .assert()
+    .success()
+    .stderr(contains("📣").not());
+}
+
+#[tokio::test]
+async fn json_output_writes_each_warning_as_a_finding() {
+    let api = warning(objects()).await;
+    let sandbox = Sandbox::new();
+
+    let output = cli(
+        &sandbox,
+        &api,
+        &[
+            "--output",
+            "json",
+            "jobs",
+            "add",
+            "--batch",
+            &write_batch(&sandbox),
+        ],
+    )
+    .assert()
+    .success()
+    .get_output()
+    .stderr
+    .clone();
+    let found: Vec<Value> = String::from_utf8_lossy(&output)
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .filter(|value| value["check"] == "server")
+        .collect();
+    assert_eq!(found.len(), 2, "{:?}", found);
+    assert_eq!(found[0]["code"], "stale_tle");
+    assert_eq!(found[0]["field"], "tle");
+    assert_eq!(found[0]["message"], "TLE epoch older than recommended");
+    assert_eq!(found[0]["entry"], 0);
+    assert_eq!(found[1].get("field"), None);
+}
+
+#[tokio::test]
+async fn strict_fails_a_batch_on_warnings_without_submitting_again() {
+    let api = warning(objects()).await;
+    let sandbox = Sandbox::new();
+
+    cli(
+        &sandbox,
+        &api,
+        &["--strict", "jobs", "add", "--batch", &write_batch(&sandbox)],
+    )
+    .assert()
+    .code(1)
+    .stderr(contains(
+        "the job is scheduled, but --strict fails the command at 2 warnings",
+    ))
+    .stderr(contains("✖ server: [0] TLE epoch older than recommended"))
+    .stderr(contains("1 warned about under --strict"));
+    assert_eq!(posts(&api).await, 1);
+}
+
+/// The editor saves the template as it is
+const EDITOR: &str = "#!/bin/sh\nexit 0\n";
+
+#[tokio::test]
+async fn strict_fails_a_single_job_after_the_success_line() {
+    let api = warning(json!(["TLE epoch older than recommended"])).await;
+    let sandbox = Sandbox::new();
+    let script = sandbox.path().join("editor.sh");
+    std::fs::write(&script, EDITOR).expect("writing the editor script");
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755))
+        .expect("making the editor script executable");
+    let template = sandbox.path().join("job.json");
+    std::fs::write(&template, loopback().to_string()).expect("writing the template");
+
+    let mut command = sandbox.command(
+        &api,
+        &[
+            "--strict",
+            "jobs",
+            "add",
+            "--edit",
+            "--template",
+            &template.display().to_string(),
+        ],
+    );
+    command.env("VISUAL", Path::new(".").join("editor.sh"));
+    let (output, code) = sandbox.spawn_command(command).finish();
+
+    assert_eq!(code, 2, "{}", output);
+    let success = output
+        .find("Job submitted successfully")
+        .unwrap_or_else(|| panic!("no success line in {}", output));
+    let failure = output
+        .find("the job is scheduled, but --strict fails the command at 1 warning")
+        .unwrap_or_else(|| panic!("no --strict failure in {}", output));
+    assert!(success < failure, "{}", output);
+    assert!(output.contains("cancel it with jobs cancel"), "{}", output);
+    assert_eq!(posts(&api).await, 1);
+}
@@ -0,0 +1,118 @@
+//! shell: commands typed one after another against one configuration and client, with
+//! a failing command leaving the prompt open
+
+mod common;
+
+use common::{add_loopback, cli, created, start_date, station, Sandbox, ENTER, UP};
+use predicates::str::contains;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn listing() -> MockServer {
+    let api = station().await;
+    Mock::given(method("GET"))
+        .and(path("/jobs"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+        .mount(&api)
+        .await;
+    api
+}
+
+async fn requests(api: &MockServer, verb: &str, at: &str) -> usize {
+    api.received_requests()
+        .await
+        .unwrap_or_default()
+        .iter()
+        .filter(|r| r.method.as_str() == verb && r.url.path() == at)
+        .count()
+}
+
+#[tokio::test]
+async fn a_failing_command_leaves_the_shell_running() {
+    let api = listing().await;
+    let sandbox = Sandbox::new();
+
+    cli(&sandbox, &api, &["shell"])
+        .write_stdin("doctor --attach-transcript missing.json\nbogus\njobs list\nexit\n")
+        .assert()
+        .success()
+        .stderr(contains("Failed to read the transcript"))
+        .stderr(contains("unrecognized subcommand 'bogus'"))
+        .stdout(contains("No jobs"));
+    assert_eq!(requests(&api, "GET", "/jobs").await, 1);
+}
+
+#[tokio::test]
+async fn the_shell_output_format_applies_to_every_command() {
+    let api = listing().await;
+    let sandbox = Sandbox::new();
+
+    let output = cli(&sandbox, &api, &["--output", "json", "shell"])
+        .write_stdin("jobs list\njobs list --output text\n")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8(output).unwrap();
+    let (json, text) = stdout.split_once("]").expect("the JSON list first");
+    assert_eq!(json.trim(), "[");
+    assert!(text.contains("No jobs"), "{}", stdout);
+}
+
+#[tokio::test]
+async fn flags_that_set_up_the_shell_cannot_change_per_command() {
+    let api = listing().await;
+    let sandbox = Sandbox::new();
+
+    cli(&sandbox, &api, &["shell"])
+        .write_stdin("jobs list --profile lab\n")
+        .assert()
+        .code(2)
+        .stderr(contains("--profile is set for the whole shell"));
+    assert_eq!(requests(&api, "GET", "/jobs").await, 0);
+}
+
+#[tokio::test]
+async fn a_read_only_shell_refuses_each_changing_command() {
+    let api = listing().await;
+    let sandbox = Sandbox::new();
+
+    cli(&sandbox, &api, &["--read-only", "shell"])
+        .write_stdin("jobs cancel 42 --yes\njobs list\n")
+        .assert()
+        .success()
+        .stderr(contains("Not allowed"))
+        .stdout(contains("No jobs"));
+    assert_eq!(requests(&api, "DELETE", "/jobs/42").await, 0);
+}
+
+#[tokio::test]
+async fn commands_prompt_inside_the_shell_and_the_arrows_recall_earlier_ones() {
+    let api = listing().await;
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(created(7))
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+
+    let mut terminal = sandbox.spawn(&api, &["shell"]);
+    terminal.expect("exit leaves").answer("rustar>", "jobs add");
+    add_loopback(&mut terminal, &start_date());
+    terminal
+        .expect("Job submitted successfully")
+        .answer("rustar>", "jobs list")
+        .expect("No jobs")
+        .expect("rustar>")
+        .keys(UP)
+        .keys(ENTER)
+        .expect("No jobs")
+        .answer("rustar>", "exit");
+    let (_, code) = terminal.finish();
+    assert_eq!(code, 0);
+
+    assert_eq!(requests(&api, "POST", "/jobs").await, 1);
+    // Two listings, and the duplicate check before the submission
+    assert_eq!(requests(&api, "GET", "/jobs").await, 3);
+}
@@ -0,0 +1,161 @@
+//! smoke-test: a short job taken through its whole life on a new server, step by step,
+//! stopping at the first failure with what was sent and received
+
+mod common;
+
+use common::{cli, configure, created, MockStation, Sandbox};
+use predicates::prelude::PredicateBooleanExt;
+use predicates::str::contains;
+use serde_json::Value;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// A server that takes the job and lists it, then fails to return it
+async fn failing_on_get() -> MockServer {
+    let api = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/health"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&api)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(created(21))
+        .mount(&api)
+        .await;
+    let start = chrono::Utc::now() + chrono::Duration::minutes(5);
+    let listed = serde_json::json!([{
+        "id": "21",
+        "status": "scheduled",
+        "job_type": "test",
+        "start": start.to_rfc3339(),
+        "end": (start + chrono::Duration::minutes(2)).to_rfc3339(),
+        "rx_frequency": 437_800_000,
+        "tx_frequency": 0,
+    }]);
+    Mock::given(method("GET"))
+        .and(path("/jobs"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(listed))
+        .mount(&api)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/jobs/21"))
+        .respond_with(
+            ResponseTemplate::new(500)
+                .insert_header("x-request-id", "req-5521")
+                .set_body_string("database unavailable"),
+        )
+        .mount(&api)
+        .await;
+    api
+}
+
+#[test]
+fn every_step_passes_against_the_mock_station() {
+    let sandbox = Sandbox::new();
+    let station = MockStation::start(&sandbox.path().join("mock.json"));
+
+    cli(&sandbox, &station, &["smoke-test"])
+        .assert()
+        .success()
+        .stdout(contains("[1/9] health check"))
+        .stdout(contains("[3/9] submit a job"))
+        .stdout(contains("[6/9] update one field"))
+        .stdout(contains("[9/9] check the cancellation"))
+        .stdout(contains("Smoke test passed"))
+        .stdout(contains("❌").not());
+
+    // Nothing is left scheduled behind it
+    let listed = cli(&sandbox, &station, &["--output", "json", "jobs", "list"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let jobs: Value = serde_json::from_slice(&listed).expect("the job list as JSON");
+    assert_eq!(jobs.as_array().map(Vec::len), Some(0), "{}", jobs);
+}
+
+#[test]
+fn json_output_is_one_report_of_every_step() {
+    let sandbox = Sandbox::new();
+    let station = MockStation::start(&sandbox.path().join("mock.json"));
+
+    let output = cli(&sandbox, &station, &["--output", "json", "smoke-test"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let report: Value = serde_json::from_slice(&output).expect("one JSON document");
+    assert_eq!(report["passed"], true);
+    let steps = report["steps"].as_array().expect("the steps");
+    assert_eq!(steps.len(), 9, "{}", report);
+    assert!(
+        steps.iter().all(|step| step["passed"] == true),
+        "{}",
+        report
+    );
+    assert_eq!(steps[2]["sent"]["job_type"], "tracking");
+    assert_eq!(steps[5]["sent"]["rx_frequency"], 437_801_000);
+    assert!(steps[0]["requests"][0]["path"] == "/health", "{}", report);
+}
+
+#[tokio::test]
+async fn a_failing_step_stops_the_run_with_its_diagnostics() {
+    let api = failing_on_get().await;
+    Mock::given(method("DELETE"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+
+    cli(&sandbox, &api, &["smoke-test"])
+        .assert()
+        .failure()
+        .stdout(contains("[4/9] find it in the job list"))
+        .stdout(contains("[5/9] get it back"))
+        .stdout(contains("GET /jobs/21 → 500, request id req-5521"))
+        .stdout(contains("Job 21 is left on the server"))
+        .stdout(contains("[6/9]").not())
+        .stderr(contains("Smoke test failed"))
+        .stderr(contains("database unavailable"));
+}
+
+#[tokio::test]
+async fn cleanup_cancels_the_job_of_a_failed_run() {
+    let api = failing_on_get().await;
+    Mock::given(method("DELETE"))
+        .and(path("/jobs/21"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({ "status": "cancelled" })),
+        )
+        .expect(1)
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+
+    cli(&sandbox, &api, &["smoke-test", "--cleanup"])
+        .assert()
+        .failure()
+        .stdout(contains("Job 21 cancelled (--cleanup)"));
+}
+
+#[tokio::test]
+async fn a_protected_profile_is_refused_before_any_request() {
+    let api = MockServer::start().await;
+    let sandbox = Sandbox::new();
+    configure(&sandbox, "protected = true");
+
+    cli(
+        &sandbox,
+        &api,
+        &["--confirm-protected", "default", "smoke-test"],
+    )
+    .assert()
+    .failure()
+    .stderr(contains("profile default is protected"));
+    let requests = api.received_requests().await.unwrap_or_default();
+    assert!(requests.is_empty(), "{:?}", requests);
+}
@@ -0,0 +1,174 @@
+//! The profile's snap grid: job times are moved onto it before submission, the move is
+//! reported, times already on it are left alone, and `--no-snap` turns it off
+
+mod common;
+
+use common::{cli, created, station, write_config, Sandbox};
+use predicates::prelude::PredicateBooleanExt;
+use predicates::str::contains;
+use serde_json::{json, Value};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer};
+
+/// A station taking every job it is sent
+async fn accepting() -> MockServer {
+    let api = station().await;
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(created(1))
+        .mount(&api)
+        .await;
+    api
+}
+
+/// A sandbox whose default profile keeps jobs on a 10-second grid, moved `direction`
+fn snapping(direction: &str) -> Sandbox {
+    let sandbox = Sandbox::new();
+    write_config(
+        &sandbox,
+        &format!(
+            "[profiles.default]\nsnap = {{ granularity_seconds = 10, direction = \"{}\" }}\n",
+            direction
+        ),
+    );
+    sandbox
+}
+
+/// "HH:MM:SS[.fff]" two days from now, as RFC 3339
+fn at(time: &str) -> String {
+    let day = (chrono::Utc::now() + chrono::Duration::days(2)).format("%Y-%m-%d");
+    format!("{}T{}Z", day, time)
+}
+
+/// A batch file of one loopback test in `[start, end)`
+fn write_batch(sandbox: &Sandbox, start: &str, end: &str) -> String {
+    let job = json!({
+        "job_type": "test",
+        "start": at(start),
+        "end": at(end),
+        "rx_frequency": 437_500_000,
+        "tx_frequency": 0,
+    });
+    let file = sandbox.path().join("jobs.json");
+    std::fs::write(&file, json!([job]).to_string()).expect("writing the batch file");
+    file.display().to_string()
+}
+
+/// Start and end of the jobs the station was sent
+async fn submitted(api: &MockServer) -> Vec<(String, String)> {
+    let requests = api.received_requests().await.unwrap_or_default();
+    requests
+        .iter()
+        .filter(|r| r.method.as_str() == "POST")
+        .map(|r| {
+            let body: Value = r.body_json().expect("the job is JSON");
+            let time = |field: &str| body[field].as_str().expect("a time").to_string();
+            (time("start"), time("end"))
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn times_off_the_grid_go_to_the_nearest_step() {
+    let api = accepting().await;
+    let sandbox = snapping("nearest");
+    let file = write_batch(&sandbox, "12:15:03.250", "12:25:05");
+
+    cli(&sandbox, &api, &["jobs", "add", "--batch", &file])
+        .assert()
+        .success()
+        .stderr(contains("Snapped to the station's 10s grid (nearest)"))
+        .stderr(contains("start 12:15:03.250 → 12:15:00 UTC"))
+        // Halfway goes later
+        .stderr(contains("end 12:25:05 → 12:25:10 UTC"));
+    assert_eq!(
+        submitted(&api).await,
+        vec![(at("12:15:00"), at("12:25:10"))]
+    );
+}
+
+#[tokio::test]
+async fn floor_and_ceil_move_a_millisecond_across_the_step() {
+    let api = accepting().await;
+    let sandbox = snapping("floor");
+    let file = write_batch(&sandbox, "12:15:09.999", "12:25:00.001");
+    cli(&sandbox, &api, &["jobs", "add", "--batch", &file])
+        .assert()
+        .success();
+    assert_eq!(
+        submitted(&api).await,
+        vec![(at("12:15:00"), at("12:25:00"))]
+    );
+
+    let api = accepting().await;
+    let sandbox = snapping("ceil");
+    let file = write_batch(&sandbox, "12:15:00.001", "12:24:59.999");
+    cli(&sandbox, &api, &["jobs", "add", "--batch", &file])
+        .assert()
+        .success();
+    assert_eq!(
+        submitted(&api).await,
+        vec![(at("12:15:10"), at("12:25:00"))]
+    );
+}
+
+#[tokio::test]
+async fn times_on_the_grid_are_left_alone_in_every_direction() {
+    for direction in ["floor", "ceil", "nearest"] {
+        let api = accepting().await;
+        let sandbox = snapping(direction);
+        let file = write_batch(&sandbox, "12:15:00", "12:25:10");
+        cli(&sandbox, &api, &["jobs", "add", "--batch", &file])
+            .assert()
+            .success()
+            .stderr(contains("Snapped").not());
+        assert_eq!(
+            submitted(&api).await,
+            vec![(at("12:15:00"), at("12:25:10"))],
+            "{}",
+            direction
+        );
+    }
+}
+
+#[tokio::test]
+async fn no_snap_sends_the_times_as_entered() {
+    let api = accepting().await;
+    let sandbox = snapping("nearest");
+    let file = write_batch(&sandbox, "12:15:03.250", "12:25:05");
+
+    cli(
+        &sandbox,
+        &api,
+        &["--no-snap", "jobs", "add", "--batch", &file],
+    )
+    .assert()
+    .success()
+    .stderr(contains("Snapped").not());
+    assert_eq!(
+        submitted(&api).await,
+        vec![(at("12:15:03.250"), at("12:25:05"))]
+    );
+}
+
+#[tokio::test]
+async fn the_summary_shows_the_entered_and_snapped_start() {
+    let api = accepting().await;
+    let sandbox = snapping("nearest");
+    let mut terminal = sandbox.spawn(&api, &["jobs", "add"]);
+    terminal
+        .expect("Job type:")
+        .keys(common::DOWN)
+        .keys(common::DOWN)
+        .keys(common::ENTER)
+        .answer("Start date:", &common::start_date())
+        .answer("Start time:", "12:10:03")
+        .answer("Duration", "10m")
+        .answer("Test frequency", "437.5M")
+        // The arrow between them does not survive the terminal in one piece
+        .expect("grid (nearest): start 12:10:03 ")
+        .expect("12:10:00 UTC, end 12:20:03 ")
+        .expect("Job submitted successfully");
+    let (_, code) = terminal.finish();
+    assert_eq!(code, 0);
+}
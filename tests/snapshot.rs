@@ -0,0 +1,149 @@
+//! Job snapshots: each submission kept with its audit entry, shown by history show and
+//! checked again by history replay against the rules of the day
+
+mod common;
+
+use common::{cli, configure, created, station, Sandbox};
+use predicates::prelude::PredicateBooleanExt;
+use predicates::str::contains;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, ResponseTemplate};
+
+/// A batch of one transmitting test job at 10:00 UTC two days from now
+fn write_batch(sandbox: &Sandbox) -> String {
+    let day = (chrono::Utc::now() + chrono::Duration::days(2)).format("%Y-%m-%d");
+    let file = sandbox.path().join("jobs.json");
+    std::fs::write(
+        &file,
+        serde_json::json!([{
+            "job_type": "test",
+            "start": format!("{}T10:00:00Z", day),
+            "end": format!("{}T10:10:00Z", day),
+            "rx_frequency": 437_500_000,
+            "tx_frequency": 145_800_000,
+        }])
+        .to_string(),
+    )
+    .expect("writing the batch file");
+    file.display().to_string()
+}
+
+#[tokio::test]
+async fn a_submission_is_shown_in_full_and_replays_cleanly() {
+    let api = station().await;
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(created(5))
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+    configure(&sandbox, "timezone = \"+02:00\"\n");
+    let file = write_batch(&sandbox);
+    cli(&sandbox, &api, &["jobs", "add", "--batch", &file])
+        .assert()
+        .success();
+
+    cli(&sandbox, &api, &["history", "list"])
+        .assert()
+        .success()
+        .stdout(contains("1. "))
+        .stdout(contains("job 5, loopback test  (add-job,"));
+    cli(&sandbox, &api, &["history", "show", "1", "--full"])
+        .assert()
+        .success()
+        .stdout(contains("Snapshot 1: job 5, loopback test"))
+        .stdout(contains(format!(
+            "by rustar-cli {}",
+            env!("CARGO_PKG_VERSION")
+        )))
+        .stdout(contains(format!(
+            "server {}, timezone +02:00, rounding none",
+            api.uri()
+        )))
+        .stdout(contains("\"tx_frequency\": 145800000"))
+        .stdout(contains("\"status\": \"created\""));
+    cli(&sandbox, &api, &["history", "replay", "1"])
+        .assert()
+        .success()
+        .stdout(contains("would still be accepted today"));
+}
+
+#[tokio::test]
+async fn a_replay_is_refused_under_rules_tightened_since() {
+    let api = station().await;
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(created(5))
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+    configure(&sandbox, "");
+    let file = write_batch(&sandbox);
+    cli(&sandbox, &api, &["jobs", "add", "--batch", &file])
+        .assert()
+        .success();
+
+    configure(
+        &sandbox,
+        "quiet_hours = [\"09:00-11:00\"]\ntimezone = \"utc\"\n",
+    );
+    cli(&sandbox, &api, &["history", "replay", "1"])
+        .assert()
+        .failure()
+        .stderr(contains("Failed to replay the job snapshot"))
+        .stderr(contains("transmits during quiet hours"));
+}
+
+#[tokio::test]
+async fn a_large_answer_is_left_out_of_the_snapshot() {
+    let api = station().await;
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+            "status": "created",
+            "id": 6,
+            "log": "x".repeat(20_000),
+        })))
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+    let file = write_batch(&sandbox);
+    cli(&sandbox, &api, &["jobs", "add", "--batch", &file])
+        .assert()
+        .success();
+
+    cli(&sandbox, &api, &["history", "show", "1", "--full"])
+        .assert()
+        .success()
+        .stdout(contains("Left out to stay under"))
+        .stdout(contains("response"))
+        .stdout(contains("xxxx").not())
+        .stdout(contains("\"rx_frequency\": 437500000"));
+    cli(&sandbox, &api, &["history", "replay", "1"])
+        .assert()
+        .success();
+}
+
+#[tokio::test]
+async fn a_profile_can_keep_no_snapshots() {
+    let api = station().await;
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(created(5))
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+    configure(&sandbox, "job_snapshots = false\n");
+    let file = write_batch(&sandbox);
+    cli(&sandbox, &api, &["jobs", "add", "--batch", &file])
+        .assert()
+        .success();
+
+    let log = std::fs::read_to_string(sandbox.path().join("state/rustar/audit.jsonl"))
+        .expect("reading the audit log");
+    assert!(!log.contains("snapshots"), "{}", log);
+    cli(&sandbox, &api, &["history", "show", "1"])
+        .assert()
+        .failure()
+        .stderr(contains("there is no snapshot 1"));
+}
@@ -0,0 +1,114 @@
+//! Several rustar processes changing the same stores at once: each takes the lock for its
+//! read-modify-write, so no process loses another's entry
+
+mod common;
+
+use common::Sandbox;
+use serde_json::Value;
+use std::process::{Child, Stdio};
+use wiremock::matchers::{method, path};
+use wiremock::Mock;
+
+/// Processes started together
+const WRITERS: usize = 12;
+
+fn json(sandbox: &Sandbox, args: &[&str]) -> Value {
+    let output = assert_cmd::Command::from_std(sandbox.without_api(args))
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    serde_json::from_slice(&output).expect("a JSON document")
+}
+
+#[test]
+fn concurrent_writers_lose_no_alias_and_no_audit_entry() {
+    let sandbox = Sandbox::new();
+    let writers: Vec<Child> = (0..WRITERS)
+        .map(|i| {
+            let name = format!("sat{}", i);
+            let rx = format!("{}M", 430 + i);
+            sandbox
+                .without_api(&["sat", "set-freq", &name, "--norad-id", "25544", "--rx", &rx])
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+                .expect("starting a writer")
+        })
+        .collect();
+    for mut writer in writers {
+        assert!(writer.wait().expect("a writer finishing").success());
+    }
+
+    let book = json(&sandbox, &["--output", "json", "sat", "list"]);
+    let aliases = book.as_object().expect("an alias book");
+    assert_eq!(aliases.len(), WRITERS, "{}", book);
+    for i in 0..WRITERS {
+        let preset = &aliases[&format!("sat{}", i)];
+        assert_eq!(
+            preset["rx_frequency"].as_f64(),
+            Some((430 + i) as f64 * 1e6),
+            "{}",
+            book
+        );
+    }
+
+    let audit = json(&sandbox, &["--output", "json", "audit"]);
+    let entries = audit.as_array().expect("audit entries");
+    assert_eq!(
+        entries
+            .iter()
+            .filter(|entry| entry["command"] == "sat set-freq")
+            .count(),
+        WRITERS,
+        "{}",
+        audit
+    );
+}
+
+#[tokio::test]
+async fn concurrent_submissions_lose_no_baseline_sample() {
+    let api = common::station().await;
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(common::created(4))
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+    let start = chrono::Utc::now() + chrono::Duration::days(3);
+    let writers: Vec<Child> = (0..WRITERS)
+        .map(|i| {
+            let start = start + chrono::Duration::hours(i as i64);
+            let job = serde_json::json!([{
+                "job_type": "test",
+                "start": start.to_rfc3339(),
+                "end": (start + chrono::Duration::minutes(10)).to_rfc3339(),
+                "rx_frequency": 145_800_000,
+                "tx_frequency": 0,
+            }]);
+            let file = sandbox.path().join(format!("job{}.json", i));
+            std::fs::write(&file, job.to_string()).expect("writing the batch file");
+            sandbox
+                .command(&api, &["jobs", "add", "--batch", file.to_str().unwrap()])
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+                .expect("starting a writer")
+        })
+        .collect();
+    for mut writer in writers {
+        assert!(writer.wait().expect("a writer finishing").success());
+    }
+
+    let baselines = std::fs::read_to_string(sandbox.path().join("state/rustar/baselines.json"))
+        .expect("reading the baselines");
+    let baselines: Value = serde_json::from_str(&baselines).expect("a JSON document");
+    let samples: usize = baselines["satellites"]
+        .as_object()
+        .expect("baselines by satellite")
+        .values()
+        .map(|samples| samples.as_array().expect("samples").len())
+        .sum();
+    assert_eq!(samples, WRITERS, "{}", baselines);
+}
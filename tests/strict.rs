@@ -0,0 +1,326 @@
+//! --strict: each advisory warning about a batch submission stops it with exit code 2,
+//! every finding listed, where without the flag the jobs go ahead
+
+mod common;
+
+use common::{checksummed, cli, configure, created, station, Sandbox};
+use predicates::prelude::PredicateBooleanExt;
+use predicates::str::contains;
+use serde_json::{json, Value};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn write_batch(sandbox: &Sandbox, jobs: &[Value]) -> String {
+    let file = sandbox.path().join("jobs.json");
+    std::fs::write(&file, Value::from(jobs.to_vec()).to_string()).expect("writing the batch file");
+    file.display().to_string()
+}
+
+/// A receive-only loopback test `start` from now
+fn loopback(start: chrono::Duration, rx: u64) -> Value {
+    let start = chrono::Utc::now() + start;
+    json!({
+        "job_type": "test",
+        "start": start.to_rfc3339(),
+        "end": (start + chrono::Duration::minutes(10)).to_rfc3339(),
+        "rx_frequency": rx,
+        "tx_frequency": 0,
+    })
+}
+
+fn in_two_days() -> chrono::Duration {
+    chrono::Duration::days(2)
+}
+
+/// A geostationary satellite whose elements are `age_days` old, tracked two days from now
+fn tracking(name: &str, age_days: i64, rx: u64) -> Value {
+    let epoch = chrono::Utc::now() - chrono::Duration::days(age_days);
+    let day = epoch.format("%y%j").to_string();
+    let start = chrono::Utc::now() + in_two_days();
+    json!({
+        "job_type": "tracking",
+        "tle": {
+            "tle0": name,
+            "tle1": checksummed(&format!(
+                "1 36516U 10016A   {}.50000000 -.00000276  00000-0  00000+0 0  999",
+                day
+            )),
+            "tle2": checksummed("2 36516   0.0300  90.0000 0002000  10.0000 100.0000  1.0027000050000"),
+        },
+        "start": start.to_rfc3339(),
+        "end": (start + chrono::Duration::minutes(10)).to_rfc3339(),
+        "rx_frequency": rx,
+        "tx_frequency": 0,
+    })
+}
+
+async fn expect_jobs(api: &MockServer, times: u64) {
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(created(1))
+        .expect(times)
+        .mount(api)
+        .await;
+}
+
+/// Submit `jobs` under --strict, which must refuse them naming `check`
+async fn refused(sandbox: &Sandbox, api: &MockServer, jobs: &[Value], check: &str) {
+    let file = write_batch(sandbox, jobs);
+    cli(sandbox, api, &["--strict", "jobs", "add", "--batch", &file])
+        .assert()
+        .code(2)
+        .stderr(contains("--strict stops the submission"))
+        .stderr(contains(format!("✖ {}:", check)));
+    let posted = api
+        .received_requests()
+        .await
+        .unwrap_or_default()
+        .iter()
+        .filter(|r| r.method.as_str() == "POST")
+        .count();
+    assert_eq!(posted, 0);
+}
+
+#[tokio::test]
+async fn a_job_that_already_started_is_refused() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    let started = loopback(chrono::Duration::minutes(-2), 437_500_000);
+    refused(&sandbox, &api, &[started], "past-start").await;
+}
+
+#[tokio::test]
+async fn stale_elements_are_refused() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    refused(
+        &sandbox,
+        &api,
+        &[tracking("GEOSAT", 30, 1_690_000_000)],
+        "stale-tle",
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn a_downlink_in_an_uplink_band_is_refused() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    refused(
+        &sandbox,
+        &api,
+        &[tracking("GEOSAT", 0, 401_650_000)],
+        "band-hint",
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn elements_named_after_another_satellites_alias_are_refused() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    cli(
+        &sandbox,
+        &api,
+        &[
+            "sat",
+            "set-freq",
+            "geo",
+            "--norad-id",
+            "25544",
+            "--rx",
+            "145.8M",
+        ],
+    )
+    .assert()
+    .success();
+    refused(
+        &sandbox,
+        &api,
+        &[tracking("geo", 0, 1_690_000_000)],
+        "name-mismatch",
+    )
+    .await;
+}
+
+/// The station lists `job` as scheduled job 41
+async fn scheduled(api: &MockServer, job: &Value) {
+    let mut listed = job.clone();
+    listed["id"] = json!(41);
+    listed["status"] = json!("scheduled");
+    Mock::given(method("GET"))
+        .and(path("/jobs"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([listed])))
+        .mount(api)
+        .await;
+}
+
+#[tokio::test]
+async fn a_job_the_station_has_already_is_refused_and_goes_ahead_without_the_flag() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    let job = loopback(in_two_days(), 437_500_000);
+    scheduled(&api, &job).await;
+    refused(&sandbox, &api, std::slice::from_ref(&job), "duplicate").await;
+
+    expect_jobs(&api, 1).await;
+    let file = write_batch(&sandbox, &[job]);
+    cli(&sandbox, &api, &["jobs", "add", "--batch", &file])
+        .assert()
+        .success()
+        .stderr(contains(
+            "[0] The same loopback test is already scheduled, as job 41",
+        ));
+}
+
+#[tokio::test]
+async fn a_station_clock_that_runs_off_is_refused() {
+    let api = MockServer::start().await;
+    let date = (chrono::Utc::now() - chrono::Duration::minutes(10))
+        .format("%a, %d %b %Y %H:%M:%S GMT")
+        .to_string();
+    for (at, body) in [
+        ("/station", json!({})),
+        ("/maintenance", json!([])),
+        ("/jobs", json!([])),
+    ] {
+        Mock::given(method("GET"))
+            .and(path(at))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("date", date.as_str())
+                    .set_body_json(body),
+            )
+            .mount(&api)
+            .await;
+    }
+    let sandbox = Sandbox::new();
+    refused(
+        &sandbox,
+        &api,
+        &[loopback(in_two_days(), 437_500_000)],
+        "clock-skew",
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn a_job_too_close_for_the_round_trip_is_refused() {
+    let api = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/station"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(json!({ "scheduling_lead_seconds": 120 })),
+        )
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+    refused(
+        &sandbox,
+        &api,
+        &[loopback(chrono::Duration::seconds(60), 437_500_000)],
+        "latency",
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn a_receive_only_job_in_quiet_hours_is_refused_by_the_profile_setting() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    configure(
+        &sandbox,
+        "strict = true\nquiet_hours = [\"00:00-23:59\"]\ntimezone = \"utc\"\n",
+    );
+    let file = write_batch(
+        &sandbox,
+        &[loopback(chrono::Duration::hours(48), 437_500_000)],
+    );
+    cli(&sandbox, &api, &["jobs", "add", "--batch", &file])
+        .assert()
+        .code(2)
+        .stderr(contains("✖ quiet-hours: [0] Receive-only"));
+}
+
+#[tokio::test]
+async fn a_rainy_window_is_refused() {
+    let api = station().await;
+    let start = (chrono::Utc::now() + in_two_days())
+        .format("%Y-%m-%dT%H:00")
+        .to_string();
+    Mock::given(method("GET"))
+        .and(path("/forecast"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "hourly": {
+                "time": [start],
+                "precipitation_probability": [90.0],
+                "cloud_cover": [20.0],
+            }
+        })))
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+    configure(
+        &sandbox,
+        &format!(
+            "latitude = -34.6\nlongitude = -58.4\nweather = {{ url = \"{}/forecast\" }}\n",
+            api.uri()
+        ),
+    );
+    refused(
+        &sandbox,
+        &api,
+        &[loopback(in_two_days(), 2_200_000_000)],
+        "weather",
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn every_finding_is_listed_not_just_the_first() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    let job = loopback(chrono::Duration::minutes(-2), 437_500_000);
+    scheduled(&api, &job).await;
+    let file = write_batch(&sandbox, &[job]);
+    cli(
+        &sandbox,
+        &api,
+        &["--strict", "jobs", "add", "--batch", &file],
+    )
+    .assert()
+    .code(2)
+    .stderr(contains("at 2 warnings"))
+    .stderr(contains("✖ past-start: [0]"))
+    .stderr(contains("✖ duplicate: [0]"));
+}
+
+#[tokio::test]
+async fn json_output_writes_each_finding_with_its_severity() {
+    let api = station().await;
+    expect_jobs(&api, 1).await;
+    let sandbox = Sandbox::new();
+    let file = write_batch(
+        &sandbox,
+        &[loopback(chrono::Duration::minutes(-2), 437_500_000)],
+    );
+    let output = cli(
+        &sandbox,
+        &api,
+        &["--output", "json", "jobs", "add", "--batch", &file],
+    )
+    .assert()
+    .success()
+    .stderr(contains("⚠️").not())
+    .get_output()
+    .stderr
+    .clone();
+    let finding: Value = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .find_map(|line| serde_json::from_str(line).ok())
+        .expect("a finding as JSON");
+    assert_eq!(finding["check"], "past-start");
+    assert_eq!(finding["severity"], "warning");
+    assert_eq!(finding["entry"], 0);
+}
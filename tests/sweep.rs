@@ -0,0 +1,257 @@
+//! `jobs sweep` against a scripted station, with a geostationary satellite so a pass is
+//! always in progress over 0°N 0°E and never over 0°N 90°E
+
+mod common;
+
+use common::{cli, configure, created, station, Sandbox};
+use predicates::str::contains;
+use serde_json::{json, Value};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const GEOSAT: &str = "GEOSAT
+1 36516U 10016A   26287.50000000 -.00000276  00000-0  00000+0 0  9994
+2 36516   0.0300  90.0000 0002000  10.0000 100.0000  1.00270000 50004
+";
+
+/// The satellite's elements in the TLE cache, and an alias for it
+fn prepare(sandbox: &Sandbox, api: &impl common::Api) {
+    let dir = sandbox.path().join("cache/rustar/tle");
+    std::fs::create_dir_all(&dir).expect("creating the TLE cache");
+    std::fs::write(dir.join("36516.tle"), GEOSAT).expect("writing the cached elements");
+    cli(
+        sandbox,
+        api,
+        &[
+            "sat",
+            "set-freq",
+            "geo",
+            "--norad-id",
+            "36516",
+            "--rx",
+            "437M",
+        ],
+    )
+    .assert()
+    .success();
+}
+
+async fn taking() -> MockServer {
+    let api = station().await;
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(created(7))
+        .mount(&api)
+        .await;
+    api
+}
+
+fn start() -> String {
+    (chrono::Utc::now() + chrono::Duration::days(1))
+        .format("%Y-%m-%dT12:00:00Z")
+        .to_string()
+}
+
+/// `jobs sweep` of geo over 437.000 to 437.100 MHz in 25 kHz steps of a minute each
+fn sweep<'a>(coords: &'a str, start: Option<&'a str>, extra: &[&'a str]) -> Vec<&'a str> {
+    let mut args = vec![
+        "--coords",
+        coords,
+        "jobs",
+        "sweep",
+        "--satellite",
+        "geo",
+        "--from",
+        "437M",
+        "--to",
+        "437.1M",
+        "--step",
+        "25k",
+        "--dwell",
+        "60s",
+    ];
+    if let Some(start) = start {
+        args.extend(["--start", start]);
+    }
+    args.extend_from_slice(extra);
+    args
+}
+
+async fn submitted(api: &MockServer) -> Vec<Value> {
+    api.received_requests()
+        .await
+        .unwrap_or_default()
+        .iter()
+        .filter(|r| r.method.as_str() == "POST")
+        .map(|r| serde_json::from_slice(&r.body).expect("a JSON job"))
+        .collect()
+}
+
+#[tokio::test]
+async fn a_dry_run_shows_the_whole_plan_and_sends_nothing() {
+    let api = taking().await;
+    let sandbox = Sandbox::new();
+    prepare(&sandbox, &api);
+    let start = start();
+
+    cli(&sandbox, &api, &sweep("0,0", Some(&start), &["--dry-run"]))
+        .assert()
+        .success()
+        .stdout(contains("Sweep of geo"))
+        .stdout(contains("5 job(s) of 1m, back to back: 5m of airtime"))
+        .stdout(contains("[4] 12:04:00 – 12:05:00 UTC"))
+        .stdout(contains("437.1"))
+        .stdout(contains("Campaign sweep-geo-"))
+        .stderr(contains("Dry run: 5 job(s) not submitted"));
+    assert!(submitted(&api).await.is_empty());
+}
+
+#[tokio::test]
+async fn the_jobs_go_out_back_to_back_in_one_campaign() {
+    let api = taking().await;
+    let sandbox = Sandbox::new();
+    prepare(&sandbox, &api);
+    let start = start();
+
+    cli(
+        &sandbox,
+        &api,
+        &sweep("0,0", Some(&start), &["--campaign", "hunt", "--yes"]),
+    )
+    .assert()
+    .success();
+
+    let jobs = submitted(&api).await;
+    let frequencies: Vec<f64> = jobs
+        .iter()
+        .filter_map(|job| job["rx_frequency"].as_f64())
+        .collect();
+    assert_eq!(
+        frequencies,
+        [
+            437_000_000.0,
+            437_025_000.0,
+            437_050_000.0,
+            437_075_000.0,
+            437_100_000.0
+        ]
+    );
+    for pair in jobs.windows(2) {
+        assert_eq!(pair[0]["end"], pair[1]["start"], "{:?}", pair);
+    }
+    for job in &jobs {
+        assert_eq!(job["job_type"], "tracking");
+        assert_eq!(job["tle"]["tle0"], "geo");
+        assert_eq!(job["tx_frequency"].as_f64(), Some(0.0));
+        assert_eq!(job["campaign"], "hunt");
+    }
+}
+
+#[tokio::test]
+async fn without_a_start_the_sweep_opens_on_the_next_pass() {
+    let api = taking().await;
+    let sandbox = Sandbox::new();
+    prepare(&sandbox, &api);
+
+    cli(&sandbox, &api, &sweep("0,0", None, &["--yes"]))
+        .assert()
+        .success();
+
+    let jobs = submitted(&api).await;
+    assert_eq!(jobs.len(), 5);
+    let first: chrono::DateTime<chrono::Utc> = jobs[0]["start"]
+        .as_str()
+        .and_then(|start| start.parse().ok())
+        .expect("a start");
+    let soon = chrono::Utc::now() + chrono::Duration::minutes(1);
+    assert!(first > soon, "{}", first);
+    assert!(first < soon + chrono::Duration::minutes(5), "{}", first);
+}
+
+#[tokio::test]
+async fn a_sweep_the_station_cannot_see_is_refused() {
+    let api = taking().await;
+    let sandbox = Sandbox::new();
+    prepare(&sandbox, &api);
+    let start = start();
+
+    cli(&sandbox, &api, &sweep("0,90", Some(&start), &["--yes"]))
+        .assert()
+        .failure()
+        .stdout(contains("(below the horizon)"))
+        .stderr(contains("covers no pass of geo over the station"));
+    assert!(submitted(&api).await.is_empty());
+}
+
+#[tokio::test]
+async fn more_jobs_than_the_profile_allows_are_refused() {
+    let api = taking().await;
+    let sandbox = Sandbox::new();
+    prepare(&sandbox, &api);
+    configure(&sandbox, "sweep_max_jobs = 4\n");
+    let start = start();
+
+    cli(&sandbox, &api, &sweep("0,0", Some(&start), &["--yes"]))
+        .assert()
+        .failure()
+        .stderr(contains(
+            "the sweep would be 5 jobs, more than the 4 sweep_max_jobs allows",
+        ));
+    assert!(submitted(&api).await.is_empty());
+}
+
+#[tokio::test]
+async fn a_band_upside_down_is_refused() {
+    let api = taking().await;
+    let sandbox = Sandbox::new();
+    prepare(&sandbox, &api);
+    let start = start();
+    let mut args = sweep("0,0", Some(&start), &["--yes"]);
+    // --from 437M --to 437.1M becomes --from 437.1M --to 437M
+    args.swap(7, 9);
+
+    cli(&sandbox, &api, &args)
+        .assert()
+        .failure()
+        .stderr(contains("is not above --from"));
+}
+
+#[tokio::test]
+async fn the_confirmation_shows_the_airtime_and_no_sends_nothing() {
+    let api = taking().await;
+    let sandbox = Sandbox::new();
+    prepare(&sandbox, &api);
+    let start = start();
+
+    let mut terminal = sandbox.spawn(&api, &sweep("0,0", Some(&start), &[]));
+    terminal.answer("Submit 5 jobs, 5m of airtime?", "n");
+    let (output, code) = terminal.finish();
+    assert_eq!(code, 0, "{}", output);
+    assert!(output.contains("Sweep not submitted"), "{}", output);
+    assert!(submitted(&api).await.is_empty());
+}
+
+#[tokio::test]
+async fn maintenance_during_the_sweep_needs_a_person() {
+    let api = MockServer::start().await;
+    let start = start();
+    let from: chrono::DateTime<chrono::Utc> = start.parse().expect("a start");
+    Mock::given(method("GET"))
+        .and(path("/maintenance"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([{
+            "start": (from + chrono::Duration::minutes(2)).to_rfc3339(),
+            "end": (from + chrono::Duration::hours(1)).to_rfc3339(),
+            "reason": "feed swap",
+        }])))
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+    prepare(&sandbox, &api);
+
+    cli(&sandbox, &api, &sweep("0,0", Some(&start), &["--yes"]))
+        .assert()
+        .failure()
+        .stderr(contains("feed swap"))
+        .stderr(contains("scheduling into a maintenance period"));
+    assert!(submitted(&api).await.is_empty());
+}
@@ -0,0 +1,140 @@
+//! `jobs tail`: the jobs that just ended, the running ones and the next ones, followed
+//! through job events or by polling, and kept on screen through an outage
+
+mod common;
+
+use common::{cli, station, Sandbox};
+use predicates::prelude::PredicateBooleanExt;
+use predicates::str::contains;
+use serde_json::{json, Value};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// A loopback test `from` minutes from now lasting `minutes`, as the server lists it
+fn job(id: u64, from: i64, minutes: i64, status: &str) -> Value {
+    let start = chrono::Utc::now() + chrono::Duration::minutes(from);
+    json!({
+        "id": id,
+        "status": status,
+        "job_type": "test",
+        "start": start.to_rfc3339(),
+        "end": (start + chrono::Duration::minutes(minutes)).to_rfc3339(),
+        "rx_frequency": 437_500_000,
+        "tx_frequency": 0,
+    })
+}
+
+/// One job ended an hour ago, one running for another five minutes, one in two hours
+fn schedule() -> Value {
+    json!([
+        job(6, -70, 10, "completed"),
+        job(7, -5, 10, "running"),
+        job(8, 120, 10, "scheduled"),
+    ])
+}
+
+async fn listing() -> MockServer {
+    let api = station().await;
+    Mock::given(method("GET"))
+        .and(path("/jobs"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(schedule()))
+        .mount(&api)
+        .await;
+    api
+}
+
+#[tokio::test]
+async fn the_three_sections_are_shown() {
+    let api = listing().await;
+    let sandbox = Sandbox::new();
+
+    cli(&sandbox, &api, &["jobs", "tail", "--count", "1"])
+        .assert()
+        .success()
+        .stdout(contains("polling every 5s"))
+        .stdout(contains("Recently ended"))
+        .stdout(contains("completed"))
+        .stdout(contains("ended 1 hour ago"))
+        .stdout(contains("Running now"))
+        .stdout(contains("7 loopback test  5m elapsed"))
+        .stdout(contains("left"))
+        .stdout(contains("Coming up"))
+        .stdout(contains("1h 59m").or(contains("2h")));
+}
+
+#[tokio::test]
+async fn an_outage_keeps_the_last_view_with_its_age() {
+    let api = station().await;
+    Mock::given(method("GET"))
+        .and(path("/jobs"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(schedule()))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&api)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/jobs"))
+        .respond_with(ResponseTemplate::new(503))
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+
+    cli(
+        &sandbox,
+        &api,
+        &["jobs", "tail", "--count", "2", "--interval", "1"],
+    )
+    .assert()
+    .success()
+    .stdout(contains("The station cannot be reached"))
+    .stdout(contains("last updated 1 second ago").or(contains("last updated 0 seconds ago")))
+    .stdout(contains("7 loopback test"));
+}
+
+#[tokio::test]
+async fn job_events_bring_frame_counts_and_refreshes() {
+    let api = listing().await;
+    Mock::given(method("GET"))
+        .and(path("/jobs/events"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            ": keepalive\n\nevent: frames\ndata: {\"job_id\": 7, \"frames\": 1234}\n\nevent: job\ndata: {\"id\": 8}\n\n",
+            "text/event-stream",
+        ))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+
+    cli(&sandbox, &api, &["jobs", "tail", "--count", "2"])
+        .assert()
+        .success()
+        .stdout(contains("live from the server's job events"))
+        .stdout(contains("1,234 frames"));
+    let requests = api.received_requests().await.unwrap();
+    let lists = requests.iter().filter(|r| r.url.path() == "/jobs").count();
+    assert_eq!(lists, 2, "the job event asks for a fresh list");
+}
+
+#[tokio::test]
+async fn json_output_is_one_line_per_refresh() {
+    let api = listing().await;
+    let sandbox = Sandbox::new();
+
+    let output = cli(
+        &sandbox,
+        &api,
+        &["--output", "json", "jobs", "tail", "--count", "1"],
+    )
+    .assert()
+    .success()
+    .stdout(contains("Running now").not())
+    .get_output()
+    .stdout
+    .clone();
+    let line: Value = serde_json::from_slice(&output).expect("one JSON object");
+    assert_eq!(line["running"][0]["id"], "7");
+    assert_eq!(line["upcoming"][0]["id"], "8");
+    assert_eq!(line["recent"][0]["status"], "completed");
+    assert_eq!(line["error"], Value::Null);
+}
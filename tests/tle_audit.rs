@@ -0,0 +1,334 @@
+//! jobs audit-tles: upcoming jobs whose elements will be too old at their pass, listed with
+//! an exit status for cron, and brought up to date with --refresh
+
+mod common;
+
+use chrono::{DateTime, Datelike, Duration, Timelike, Utc};
+use common::{checksummed, loopback_job, Sandbox};
+use predicates::str::contains;
+use serde_json::Value;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const ISS_LINE2: &str = "2 25544  51.6355 332.1708 0003307 260.2831  99.7785 15.50129787525648";
+
+fn cli(
+    sandbox: &Sandbox,
+    api: &impl common::Api,
+    source: &MockServer,
+    args: &[&str],
+) -> assert_cmd::Command {
+    let mut command = sandbox.command(api, args);
+    command.env(
+        "TLE_SOURCE_URL",
+        format!("{}/gp.php?CATNR={{norad}}", source.uri()),
+    );
+    assert_cmd::Command::from_std(command)
+}
+
+/// Line 1 of an element set for `norad_id` with its epoch at `epoch`, checksum included
+fn line1(norad_id: u32, epoch: DateTime<Utc>) -> String {
+    let day = epoch.ordinal() as f64 + f64::from(epoch.num_seconds_from_midnight()) / 86_400.0;
+    checksummed(&format!(
+        "1 {:05}U 98067A   {:02}{:012.8}  .00011222  00000+0  20339-3 0  999",
+        norad_id,
+        epoch.year() % 100,
+        day
+    ))
+}
+
+fn tle(name: &str, norad_id: u32, epoch: DateTime<Utc>) -> Value {
+    serde_json::json!({ "tle0": name, "tle1": line1(norad_id, epoch), "tle2": ISS_LINE2 })
+}
+
+/// A tracking job `start_in` from now, flying elements of `epoch`
+fn tracking(id: &str, tle: Value, start_in: Duration) -> Value {
+    let start = Utc::now() + start_in;
+    serde_json::json!({
+        "id": id,
+        "status": "scheduled",
+        "job_type": "tracking",
+        "tle": tle,
+        "start": start.to_rfc3339(),
+        "end": (start + Duration::minutes(10)).to_rfc3339(),
+        "rx_frequency": 145_800_000,
+        "tx_frequency": 0,
+    })
+}
+
+async fn server(jobs: &[Value]) -> MockServer {
+    let api = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/jobs"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(Value::Array(jobs.to_vec())))
+        .mount(&api)
+        .await;
+    for job in jobs {
+        Mock::given(method("GET"))
+            .and(path(format!(
+                "/jobs/{}",
+                job["id"].as_str().unwrap_or_default()
+            )))
+            .respond_with(ResponseTemplate::new(200).set_body_json(job))
+            .mount(&api)
+            .await;
+    }
+    api
+}
+
+fn json(output: &[u8]) -> Value {
+    serde_json::from_slice(output).expect("one JSON document")
+}
+
+/// The jobs sent a change, with the change
+async fn patched(api: &MockServer) -> Vec<(String, Value)> {
+    api.received_requests()
+        .await
+        .unwrap_or_default()
+        .iter()
+        .filter(|r| r.method.as_str() == "PATCH")
+        .map(|r| {
+            (
+                r.url.path().to_string(),
+                r.body_json().expect("the change is JSON"),
+            )
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn jobs_whose_elements_will_be_too_old_are_listed_and_exit_5() {
+    let now = Utc::now();
+    let api = server(&[
+        tracking("1", tle("ISS (ZARYA)", 25544, now), Duration::days(2)),
+        tracking(
+            "2",
+            tle("ISS (ZARYA)", 25544, now - Duration::days(1)),
+            Duration::days(6),
+        ),
+        loopback_job("3"),
+    ])
+    .await;
+    let source = MockServer::start().await;
+    let sandbox = Sandbox::new();
+
+    let output = cli(
+        &sandbox,
+        &api,
+        &source,
+        &["--output", "json", "jobs", "audit-tles"],
+    )
+    .assert()
+    .code(5)
+    .get_output()
+    .stdout
+    .clone();
+    let report = json(&output);
+    assert_eq!(report["checked"], 2, "{}", report);
+    let jobs = report["jobs"].as_array().expect("the jobs found");
+    assert_eq!(jobs.len(), 1, "{}", report);
+    assert_eq!(jobs[0]["id"], "2");
+    assert_eq!(jobs[0]["status"], "stale");
+    assert_eq!(jobs[0]["norad_id"], 25544);
+    let age = jobs[0]["age_at_pass_seconds"].as_i64().expect("an age");
+    assert!((age - 7 * 86_400).abs() < 120, "{}", report);
+
+    // A longer limit lets it through
+    cli(
+        &sandbox,
+        &api,
+        &source,
+        &["jobs", "audit-tles", "--max-age-at-pass", "8d"],
+    )
+    .assert()
+    .success()
+    .stdout(contains("within 8d at pass time"));
+    assert!(source
+        .received_requests()
+        .await
+        .unwrap_or_default()
+        .is_empty());
+    assert!(patched(&api).await.is_empty());
+}
+
+#[tokio::test]
+async fn the_text_listing_names_the_jobs_and_the_fix() {
+    let now = Utc::now();
+    let api = server(&[tracking(
+        "2",
+        tle("ISS (ZARYA)", 25544, now - Duration::days(3)),
+        Duration::days(4),
+    )])
+    .await;
+    let source = MockServer::start().await;
+    let sandbox = Sandbox::new();
+
+    cli(&sandbox, &api, &source, &["jobs", "audit-tles"])
+        .assert()
+        .code(5)
+        .stdout(contains("ISS (ZARYA)"))
+        .stdout(contains("AGE AT PASS"))
+        .stdout(contains("1 of 1 upcoming tracking job(s)"))
+        .stdout(contains("--refresh"));
+}
+
+#[tokio::test]
+async fn refresh_sends_fresh_elements_and_reports_what_it_could_not() {
+    let now = Utc::now();
+    let old = now - Duration::days(10);
+    let api = server(&[
+        tracking("2", tle("ISS", 25544, old), Duration::days(1)),
+        tracking("4", tle("ISS", 25544, old), Duration::days(2)),
+        tracking("6", tle("LOST SAT", 99999, old), Duration::days(1)),
+    ])
+    .await;
+    Mock::given(method("PATCH"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({ "status": "updated" })),
+        )
+        .mount(&api)
+        .await;
+    let source = MockServer::start().await;
+    let fresh = format!(
+        "ISS (ZARYA)\n{}\n{}\n",
+        line1(25544, now - Duration::hours(6)),
+        ISS_LINE2
+    );
+    Mock::given(method("GET"))
+        .and(path("/gp.php"))
+        .and(query_param("CATNR", "25544"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(fresh))
+        // Once for both jobs
+        .expect(1)
+        .mount(&source)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/gp.php"))
+        .and(query_param("CATNR", "99999"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&source)
+        .await;
+    let sandbox = Sandbox::new();
+
+    let output = cli(
+        &sandbox,
+        &api,
+        &source,
+        &["--output", "json", "jobs", "audit-tles", "--refresh"],
+    )
+    .assert()
+    .code(5)
+    .get_output()
+    .stdout
+    .clone();
+    let report = json(&output);
+    let status = |id: &str| {
+        report["jobs"]
+            .as_array()
+            .and_then(|jobs| jobs.iter().find(|job| job["id"] == id))
+            .map(|job| job["status"].clone())
+    };
+    assert_eq!(status("2"), Some("refreshed".into()), "{}", report);
+    assert_eq!(status("4"), Some("refreshed".into()), "{}", report);
+    assert_eq!(status("6"), Some("unresolved".into()), "{}", report);
+    assert!(report["jobs"][0]["new_epoch"].is_string(), "{}", report);
+
+    let changes = patched(&api).await;
+    assert_eq!(changes.len(), 2, "{:?}", changes);
+    assert_eq!(changes[0].0, "/jobs/2");
+    // The name the job was scheduled under stays
+    assert_eq!(changes[0].1["tle"]["tle0"], "ISS");
+    assert_eq!(
+        changes[0].1["tle"]["tle1"],
+        line1(25544, now - Duration::hours(6))
+    );
+
+    // Run again on a server that took the change, so nothing is left: a clean exit
+    let api = server(&[tracking(
+        "2",
+        tle("ISS", 25544, now - Duration::hours(6)),
+        Duration::days(1),
+    )])
+    .await;
+    cli(
+        &sandbox,
+        &api,
+        &source,
+        &["jobs", "audit-tles", "--refresh"],
+    )
+    .assert()
+    .success();
+}
+
+#[tokio::test]
+async fn the_text_report_shows_the_epochs_before_and_after() {
+    let now = Utc::now();
+    let api = server(&[
+        tracking(
+            "2",
+            tle("ISS", 25544, now - Duration::days(10)),
+            Duration::days(1),
+        ),
+        tracking(
+            "6",
+            tle("LOST SAT", 99999, now - Duration::days(10)),
+            Duration::days(1),
+        ),
+    ])
+    .await;
+    Mock::given(method("PATCH"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&api)
+        .await;
+    let source = MockServer::start().await;
+    let fresh_epoch = now - Duration::hours(6);
+    Mock::given(method("GET"))
+        .and(path("/gp.php"))
+        .and(query_param("CATNR", "25544"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+            "ISS (ZARYA)\n{}\n{}\n",
+            line1(25544, fresh_epoch),
+            ISS_LINE2
+        )))
+        .mount(&source)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/gp.php"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&source)
+        .await;
+    let sandbox = Sandbox::new();
+
+    cli(
+        &sandbox,
+        &api,
+        &source,
+        &["jobs", "audit-tles", "--refresh"],
+    )
+    .assert()
+    .code(5)
+    .stdout(contains("NEW EPOCH"))
+    .stdout(contains(fresh_epoch.format("%Y-%m-%d %H:%M").to_string()))
+    .stdout(contains("refreshed"))
+    .stdout(contains("Could not refresh:"))
+    .stdout(contains("job 6 (LOST SAT)"))
+    .stdout(contains("1 of 2 job(s) refreshed; 1 left"));
+}
+
+#[tokio::test]
+async fn a_server_that_cannot_list_jobs_is_an_error_not_a_finding() {
+    let api = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/jobs"))
+        .respond_with(ResponseTemplate::new(503))
+        .mount(&api)
+        .await;
+    let source = MockServer::start().await;
+    let sandbox = Sandbox::new();
+
+    cli(&sandbox, &api, &source, &["jobs", "audit-tles"])
+        .assert()
+        .failure()
+        .code(predicates::ord::ne(5))
+        .stderr(contains("TLE audit failed"));
+}
@@ -0,0 +1,101 @@
+//! `tle compare` between element sets read from files, the cache and stdin
+
+mod common;
+
+use common::{cli, station, Sandbox};
+use predicates::str::contains;
+
+const ISS_A: &str = "ISS (ZARYA)
+1 25544U 98067A   25235.75642456  .00011222  00000+0  20339-3 0  9993
+2 25544  51.6355 332.1708 0003307 260.2831  99.7785 15.50129787525648
+";
+
+/// A day later: the node has regressed and the drag term grown
+const ISS_B: &str = "ISS (ZARYA)
+1 25544U 98067A   25236.50000000  .00011500  00000+0  20800-3 0  9992
+2 25544  51.6352 328.4500 0003290 262.1000 120.3000 15.50135000525762
+";
+
+const GEOSAT: &str = "GEOSAT
+1 36516U 10016A   26287.50000000 -.00000276  00000-0  00000+0 0  9994
+2 36516   0.0300  90.0000 0002000  10.0000 100.0000  1.00270000 50004
+";
+
+fn write(sandbox: &Sandbox, name: &str, tle: &str) -> String {
+    let path = sandbox.path().join(name);
+    std::fs::write(&path, tle).expect("writing the element set");
+    path.display().to_string()
+}
+
+#[tokio::test]
+async fn prints_each_element_and_the_drift() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    let (a, b) = (
+        write(&sandbox, "a.tle", ISS_A),
+        write(&sandbox, "b.tle", ISS_B),
+    );
+
+    cli(
+        &sandbox,
+        &api,
+        &["tle", "compare", &a, &b, "--at", "2025-08-25 12:00"],
+    )
+    .assert()
+    .success()
+    .stdout(contains("+17h 50m"))
+    .stdout(contains("+0.00005213 rev/day"))
+    .stdout(contains("-3.7208°"))
+    .stdout(contains("At 2025-08-25 12:00 UTC"))
+    .stdout(contains("km along track"));
+}
+
+#[tokio::test]
+async fn json_carries_the_numbers() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    let a = write(&sandbox, "a.tle", ISS_A);
+    let output = cli(
+        &sandbox,
+        &api,
+        &[
+            "--output", "json", "tle", "compare", &a, "-", "--at", "now+24h",
+        ],
+    )
+    .write_stdin(ISS_B)
+    .assert()
+    .success()
+    .get_output()
+    .stdout
+    .clone();
+    let document: serde_json::Value = serde_json::from_slice(&output).expect("JSON output");
+    assert_eq!(document["b"]["source"], "stdin");
+    let inclination = &document["changes"][1];
+    assert_eq!(inclination["field"], "inclination");
+    assert!((inclination["delta"].as_f64().unwrap() + 0.0003).abs() < 1e-9);
+    assert!(document["separation"]["total_km"].as_f64().unwrap() > 0.0);
+}
+
+#[tokio::test]
+async fn different_satellites_need_force() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    let dir = sandbox.path().join("cache/rustar/tle");
+    std::fs::create_dir_all(&dir).expect("creating the TLE cache");
+    std::fs::write(dir.join("36516.tle"), GEOSAT).expect("writing the cached elements");
+    let a = write(&sandbox, "a.tle", ISS_A);
+
+    cli(&sandbox, &api, &["tle", "compare", &a, "cache:36516"])
+        .assert()
+        .failure()
+        .stderr(contains("A is NORAD 25544 and B is NORAD 36516"))
+        .stderr(contains("--force"));
+    cli(
+        &sandbox,
+        &api,
+        &["tle", "compare", &a, "cache:36516", "--force"],
+    )
+    .assert()
+    .success()
+    .stdout(contains("GEOSAT from the cache (NORAD 36516)"));
+}
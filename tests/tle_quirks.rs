@@ -0,0 +1,151 @@
+//! Element sets as they have actually reached us, read through `tle compare` against a
+//! clean copy. Add each new quirk here with where it came from.
+
+mod common;
+
+use common::{cli, station, Sandbox};
+use predicates::str::contains;
+
+const LINE1: &str = "1 25544U 98067A   25235.75642456  .00011222  00000+0  20339-3 0  9993";
+const LINE2: &str = "2 25544  51.6355 332.1708 0003307 260.2831  99.7785 15.50129787525648";
+
+/// (where it came from, the text as received); every one reads as ISS (ZARYA) with
+/// `LINE1` and `LINE2`
+const READABLE: &[(&str, &str)] = &[
+    (
+        "Space-Track 3LE, with the 0 before the name",
+        "0 ISS (ZARYA)
+1 25544U 98067A   25235.75642456  .00011222  00000+0  20339-3 0  9993
+2 25544  51.6355 332.1708 0003307 260.2831  99.7785 15.50129787525648
+",
+    ),
+    (
+        "copied from a web page, trailing spaces kept",
+        "ISS (ZARYA)   \n\
+         1 25544U 98067A   25235.75642456  .00011222  00000+0  20339-3 0  9993    \n\
+         2 25544  51.6355 332.1708 0003307 260.2831  99.7785 15.50129787525648 \t\n",
+    ),
+    (
+        "pasted into a tab-indented document",
+        "\tISS (ZARYA)
+\t1 25544U 98067A   25235.75642456  .00011222  00000+0  20339-3 0  9993
+\t2 25544  51.6355 332.1708 0003307 260.2831  99.7785 15.50129787525648
+",
+    ),
+    (
+        "saved on Windows",
+        "ISS (ZARYA)\r
+1 25544U 98067A   25235.75642456  .00011222  00000+0  20339-3 0  9993\r
+2 25544  51.6355 332.1708 0003307 260.2831  99.7785 15.50129787525648\r
+",
+    ),
+    (
+        "lines sorted the wrong way round by a spreadsheet",
+        "ISS (ZARYA)
+2 25544  51.6355 332.1708 0003307 260.2831  99.7785 15.50129787525648
+1 25544U 98067A   25235.75642456  .00011222  00000+0  20339-3 0  9993
+",
+    ),
+    (
+        "blank lines between, from a chat message",
+        "
+
+ISS (ZARYA)
+
+1 25544U 98067A   25235.75642456  .00011222  00000+0  20339-3 0  9993
+
+2 25544  51.6355 332.1708 0003307 260.2831  99.7785 15.50129787525648
+
+",
+    ),
+];
+
+/// Both element lines broken by an email client at its line width
+const WRAPPED: &str = "ISS (ZARYA)
+1 25544U 98067A   25235.75642456  .00011222  00000+0
+20339-3 0  9993
+2 25544  51.6355 332.1708 0003307 260.2831  99.7785
+15.50129787525648
+";
+
+fn write(sandbox: &Sandbox, name: &str, tle: &str) -> String {
+    let path = sandbox.path().join(name);
+    std::fs::write(&path, tle).expect("writing the element set");
+    path.display().to_string()
+}
+
+#[tokio::test]
+async fn every_quirk_received_reads_as_the_clean_set() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    let clean = write(
+        &sandbox,
+        "clean.tle",
+        &format!("ISS (ZARYA)\n{}\n{}\n", LINE1, LINE2),
+    );
+
+    for (index, (origin, text)) in READABLE.iter().enumerate() {
+        let quirky = write(&sandbox, &format!("quirk{}.tle", index), text);
+        let output = cli(
+            &sandbox,
+            &api,
+            &["--output", "json", "tle", "compare", &quirky, &clean],
+        )
+        .output()
+        .expect("running tle compare");
+        assert!(
+            output.status.success(),
+            "{}: {}",
+            origin,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let report: serde_json::Value =
+            serde_json::from_slice(&output.stdout).expect("a JSON comparison");
+        assert_eq!(report["a"]["tle"], report["b"]["tle"], "{}", origin);
+    }
+}
+
+#[tokio::test]
+async fn stdin_gets_the_same_treatment() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    let clean = write(
+        &sandbox,
+        "clean.tle",
+        &format!("ISS (ZARYA)\n{}\n{}\n", LINE1, LINE2),
+    );
+
+    let output = cli(
+        &sandbox,
+        &api,
+        &["--output", "json", "tle", "compare", "-", &clean],
+    )
+    .write_stdin(READABLE[0].1)
+    .assert()
+    .success()
+    .get_output()
+    .stdout
+    .clone();
+    let report: serde_json::Value = serde_json::from_slice(&output).expect("a JSON comparison");
+    assert_eq!(report["a"]["tle"]["tle0"], "ISS (ZARYA)");
+    assert_eq!(report["a"]["tle"]["tle1"], LINE1);
+}
+
+#[tokio::test]
+async fn wrapped_lines_are_refused_with_the_likely_cause() {
+    let api = station().await;
+    let sandbox = Sandbox::new();
+    let wrapped = write(&sandbox, "wrapped.tle", WRAPPED);
+    let clean = write(
+        &sandbox,
+        "clean.tle",
+        &format!("ISS (ZARYA)\n{}\n{}\n", LINE1, LINE2),
+    );
+
+    cli(&sandbox, &api, &["tle", "compare", &wrapped, &clean])
+        .assert()
+        .failure()
+        .stderr(contains("element line 1 has 52 of its 69 characters"))
+        .stderr(contains("'20339-3 0  9993'"))
+        .stderr(contains("looks wrapped"));
+}
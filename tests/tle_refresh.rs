@@ -0,0 +1,235 @@
+//! `tle refresh-all` over a small alias book, with the element-set source played by a
+//! mock server
+
+mod common;
+
+use common::{station, Sandbox};
+use predicates::prelude::PredicateBooleanExt;
+use predicates::str::contains;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const ISS: &str = "ISS (ZARYA)
+1 25544U 98067A   25235.75642456  .00011222  00000+0  20339-3 0  9993
+2 25544  51.6355 332.1708 0003307 260.2831  99.7785 15.50129787525648
+";
+/// The copy already cached, five days older
+const ISS_CACHED: &str = "ISS (ZARYA)
+1 25544U 98067A   25230.50000000  .00011222  00000+0  20339-3 0  9994
+2 25544  51.6355 332.1708 0003307 260.2831  99.7785 15.50129787525648
+";
+const NOAA_19: &str = "NOAA 19
+1 33591U 09005A   25235.50000000  .00000050  00000+0  50000-4 0  9990
+2 33591  99.0000 100.0000 0013000 200.0000 160.0000 14.12500000 85001
+";
+
+fn cli(
+    sandbox: &Sandbox,
+    api: &impl common::Api,
+    source: &MockServer,
+    args: &[&str],
+) -> assert_cmd::Command {
+    let mut command = sandbox.command(api, args);
+    command.env(
+        "TLE_SOURCE_URL",
+        format!("{}/gp.php?CATNR={{norad}}", source.uri()),
+    );
+    assert_cmd::Command::from_std(command)
+}
+
+/// Aliases for the ISS and NOAA 19, one without an ID, and a weather group
+fn alias_book(sandbox: &Sandbox) {
+    let dir = sandbox.path().join("config/rustar");
+    std::fs::create_dir_all(&dir).expect("creating the config directory");
+    std::fs::write(
+        dir.join("satellites.toml"),
+        "version = 1\n\
+         [iss]\nnorad_id = 25544\n\
+         [noaa19]\nnorad_id = 33591\n\
+         [beacon]\nrx_frequency = 145800000\n\
+         [groups]\nweather = [\"noaa19\"]\n",
+    )
+    .expect("writing the alias book");
+}
+
+fn cache(sandbox: &Sandbox, norad_id: u32, tle: &str) {
+    let dir = sandbox.path().join("cache/rustar/tle");
+    std::fs::create_dir_all(&dir).expect("creating the TLE cache");
+    std::fs::write(dir.join(format!("{}.tle", norad_id)), tle).expect("caching an element set");
+}
+
+fn cached(sandbox: &Sandbox, norad_id: u32) -> String {
+    std::fs::read_to_string(
+        sandbox
+            .path()
+            .join(format!("cache/rustar/tle/{}.tle", norad_id)),
+    )
+    .unwrap_or_default()
+}
+
+async fn publishes(source: &MockServer, norad_id: &str, response: ResponseTemplate, times: u64) {
+    Mock::given(method("GET"))
+        .and(path("/gp.php"))
+        .and(query_param("CATNR", norad_id))
+        .respond_with(response)
+        .expect(times)
+        .mount(source)
+        .await;
+}
+
+#[tokio::test]
+async fn every_alias_is_refreshed_with_old_and_new_epochs() {
+    let api = station().await;
+    let source = MockServer::start().await;
+    publishes(
+        &source,
+        "25544",
+        ResponseTemplate::new(200).set_body_string(ISS),
+        1,
+    )
+    .await;
+    publishes(
+        &source,
+        "33591",
+        ResponseTemplate::new(200).set_body_string(NOAA_19),
+        1,
+    )
+    .await;
+    let sandbox = Sandbox::new();
+    alias_book(&sandbox);
+    cache(&sandbox, 25544, ISS_CACHED);
+
+    let output = cli(
+        &sandbox,
+        &api,
+        &source,
+        &["--output", "json", "tle", "refresh-all"],
+    )
+    .assert()
+    .success()
+    .stderr(contains("beacon has no NORAD ID"))
+    .get_output()
+    .stdout
+    .clone();
+    let report: serde_json::Value = serde_json::from_slice(&output).expect("a JSON report");
+    let refreshed = report["refreshed"].as_array().expect("the refreshed list");
+    assert_eq!(refreshed.len(), 2);
+    assert_eq!(refreshed[0]["satellite"], "iss");
+    assert!(refreshed[0]["old_epoch"]
+        .as_str()
+        .is_some_and(|epoch| epoch.starts_with("2025-08-18")));
+    assert!(refreshed[0]["new_epoch"]
+        .as_str()
+        .is_some_and(|epoch| epoch.starts_with("2025-08-23")));
+    assert_eq!(refreshed[1]["satellite"], "noaa19");
+    assert!(refreshed[1]["old_epoch"].is_null());
+    assert_eq!(cached(&sandbox, 25544), ISS);
+    assert_eq!(cached(&sandbox, 33591), NOAA_19);
+}
+
+#[tokio::test]
+async fn a_group_limits_what_is_fetched() {
+    let api = station().await;
+    let source = MockServer::start().await;
+    publishes(
+        &source,
+        "25544",
+        ResponseTemplate::new(200).set_body_string(ISS),
+        0,
+    )
+    .await;
+    publishes(
+        &source,
+        "33591",
+        ResponseTemplate::new(200).set_body_string(NOAA_19),
+        1,
+    )
+    .await;
+    let sandbox = Sandbox::new();
+    alias_book(&sandbox);
+
+    cli(
+        &sandbox,
+        &api,
+        &source,
+        &["tle", "refresh-all", "--group", "weather"],
+    )
+    .assert()
+    .success()
+    .stdout(contains("noaa19"))
+    .stdout(contains("1 of 1 element set(s) refreshed"));
+}
+
+#[tokio::test]
+async fn failures_are_listed_apart_and_do_not_fail_the_run() {
+    let api = station().await;
+    let source = MockServer::start().await;
+    publishes(&source, "25544", ResponseTemplate::new(503), 1).await;
+    publishes(
+        &source,
+        "33591",
+        ResponseTemplate::new(200).set_body_string(NOAA_19),
+        1,
+    )
+    .await;
+    let sandbox = Sandbox::new();
+    alias_book(&sandbox);
+    cache(&sandbox, 25544, ISS_CACHED);
+
+    cli(&sandbox, &api, &source, &["tle", "refresh-all"])
+        .assert()
+        .success()
+        .stdout(contains("Could not refresh:"))
+        .stdout(contains("- iss (NORAD 25544)"))
+        .stdout(contains("1 of 2 element set(s) refreshed"));
+    assert_eq!(cached(&sandbox, 25544), ISS_CACHED);
+}
+
+#[tokio::test]
+async fn the_run_fails_only_when_every_fetch_does() {
+    let api = station().await;
+    let source = MockServer::start().await;
+    publishes(&source, "25544", ResponseTemplate::new(503), 1).await;
+    publishes(&source, "33591", ResponseTemplate::new(503), 1).await;
+    let sandbox = Sandbox::new();
+    alias_book(&sandbox);
+
+    cli(&sandbox, &api, &source, &["tle", "refresh-all"])
+        .assert()
+        .failure()
+        .stdout(contains("0 of 2 element set(s) refreshed"))
+        .stderr(contains("none of the 2 element set(s) could be fetched"))
+        .stderr(contains("API Client initialized").not());
+}
+
+#[tokio::test]
+async fn the_profiles_source_is_used_over_the_default() {
+    let api = station().await;
+    let source = MockServer::start().await;
+    publishes(
+        &source,
+        "25544",
+        ResponseTemplate::new(200).set_body_string(ISS),
+        1,
+    )
+    .await;
+    publishes(
+        &source,
+        "33591",
+        ResponseTemplate::new(200).set_body_string(NOAA_19),
+        1,
+    )
+    .await;
+    let sandbox = Sandbox::new();
+    common::configure(
+        &sandbox,
+        &format!("tle_source = \"{}/gp.php?CATNR={{norad}}\"\n", source.uri()),
+    );
+    alias_book(&sandbox);
+
+    assert_cmd::Command::from_std(sandbox.command(&api, &["tle", "refresh-all"]))
+        .assert()
+        .success()
+        .stdout(contains("2 of 2 element set(s) refreshed"));
+    assert_eq!(cached(&sandbox, 25544), ISS);
+}
@@ -0,0 +1,197 @@
+//! --transcript: the session written down as one JSON document for a support request,
+//! finished however the command ends, and shown again by doctor --attach-transcript
+
+mod common;
+
+use common::{add_loopback, cli, created, start_date, station, write_config, Sandbox};
+use predicates::str::contains;
+use serde_json::Value;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn read(path: &std::path::Path) -> Value {
+    let text = std::fs::read_to_string(path).expect("the transcript file");
+    serde_json::from_str(&text).expect("a JSON transcript")
+}
+
+fn events<'a>(transcript: &'a Value, kind: &str) -> Vec<&'a Value> {
+    transcript["events"]
+        .as_array()
+        .expect("the events")
+        .iter()
+        .filter(|event| event["kind"] == kind)
+        .collect()
+}
+
+#[tokio::test]
+async fn an_interactive_submission_is_written_down_with_its_request_ids() {
+    let api = station().await;
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(created(7).insert_header("x-request-id", "req-4711"))
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+    let file = sandbox.path().join("session.json");
+
+    let mut terminal = sandbox.spawn(
+        &api,
+        &["--transcript", file.to_str().unwrap(), "jobs", "add"],
+    );
+    add_loopback(&mut terminal, &start_date());
+    terminal.expect("Job submitted successfully");
+    let (_, code) = terminal.finish();
+    assert_eq!(code, 0);
+
+    let transcript = read(&file);
+    assert_eq!(transcript["complete"], true);
+    assert_eq!(transcript["outcome"]["exit_code"], 0);
+    assert_eq!(transcript["outcome"]["result"], "success");
+    assert!(transcript["arguments"]
+        .as_array()
+        .unwrap()
+        .contains(&Value::from("add")));
+
+    let prompts = events(&transcript, "prompt");
+    let answer = |prompt: &str| {
+        prompts
+            .iter()
+            .find(|event| event["prompt"].as_str().unwrap().contains(prompt))
+            .map(|event| event["answer"].clone())
+            .unwrap_or_else(|| panic!("no answer to {:?} in {:#?}", prompt, prompts))
+    };
+    assert_eq!(answer("Start time:"), "12:10");
+    assert_eq!(answer("Duration"), "10m");
+
+    // The slot hold is tried first; this station has none, so the job is then added
+    let jobs = events(&transcript, "job");
+    let sent = jobs.last().expect("the job sent");
+    assert_eq!(sent["job"]["rx_frequency"], 437_500_000.0);
+
+    let submission = events(&transcript, "http")
+        .into_iter()
+        .find(|event| event["method"] == "POST" && event["path"] == "/jobs")
+        .expect("the submission request");
+    assert_eq!(submission["status"], 201);
+    assert_eq!(submission["request_id"], "req-4711");
+}
+
+#[tokio::test]
+async fn a_failed_command_still_finishes_its_transcript() {
+    let api = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/jobs"))
+        .respond_with(
+            ResponseTemplate::new(500)
+                .insert_header("x-request-id", "req-500")
+                .set_body_json(serde_json::json!({ "message": "database unavailable" })),
+        )
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+    let file = sandbox.path().join("failed.json");
+
+    cli(
+        &sandbox,
+        &api,
+        &["--transcript", file.to_str().unwrap(), "jobs", "list"],
+    )
+    .assert()
+    .failure();
+
+    let transcript = read(&file);
+    assert_eq!(transcript["complete"], true);
+    assert_eq!(transcript["outcome"]["result"], "failed");
+    assert_ne!(transcript["outcome"]["exit_code"], 0);
+    let errors = events(&transcript, "error");
+    assert!(
+        errors.iter().any(|event| event["text"]
+            .as_str()
+            .unwrap()
+            .contains("database unavailable")),
+        "{:#?}",
+        errors
+    );
+    assert!(events(&transcript, "http")
+        .iter()
+        .any(|event| event["status"] == 500 && event["request_id"] == "req-500"));
+}
+
+#[tokio::test]
+async fn a_profile_directory_records_every_invocation() {
+    let api = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/jobs"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+    let dir = sandbox.path().join("transcripts");
+    write_config(
+        &sandbox,
+        &format!(
+            "[profiles.default]\ntranscript_dir = {:?}\n",
+            dir.to_str().unwrap()
+        ),
+    );
+
+    cli(&sandbox, &api, &["jobs", "list"]).assert().success();
+
+    let files: Vec<_> = std::fs::read_dir(&dir)
+        .expect("the transcript directory")
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    assert_eq!(files.len(), 1, "{:?}", files);
+    let transcript = read(&files[0]);
+    assert_eq!(transcript["complete"], true);
+    assert_eq!(transcript["profile"], "default");
+}
+
+#[tokio::test]
+async fn doctor_shows_an_attached_transcript_and_flags_one_cut_short() {
+    let api = MockServer::start().await;
+    let sandbox = Sandbox::new();
+    let file = sandbox.path().join("cut.json");
+    std::fs::write(
+        &file,
+        serde_json::to_string_pretty(&serde_json::json!({
+            "version": 1,
+            "cli_version": "0.1.0",
+            "started_at": "2026-10-14T12:00:00Z",
+            "finished_at": null,
+            "arguments": ["jobs", "add"],
+            "profile": "default",
+            "events": [
+                {
+                    "kind": "prompt",
+                    "at": "2026-10-14T12:00:01Z",
+                    "prompt": "Job type:",
+                    "answer": "Test"
+                },
+                {
+                    "kind": "http",
+                    "at": "2026-10-14T12:00:05Z",
+                    "method": "POST",
+                    "path": "/jobs",
+                    "status": null,
+                    "request_id": null,
+                    "elapsed_ms": 30000
+                }
+            ],
+            "outcome": null,
+            "complete": false
+        }))
+        .unwrap(),
+    )
+    .unwrap();
+
+    cli(
+        &sandbox,
+        &api,
+        &["doctor", "--attach-transcript", file.to_str().unwrap()],
+    )
+    .assert()
+    .stdout(contains("Job type: Test"))
+    .stdout(contains("POST /jobs → no response in 30000 ms"))
+    .stdout(contains("Incomplete"));
+}
@@ -0,0 +1,205 @@
+//! Satellite names as catalogs and operators write them, run through every renderer:
+//! tables and timelines stay aligned by the columns a name takes on screen, exports keep
+//! the name exactly, and names that differ only in normalization are the same name
+
+mod common;
+
+use common::{station, Sandbox};
+use predicates::str::contains;
+use serde_json::{json, Value};
+use unicode_width::UnicodeWidthStr;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const LINE1: &str = "1 25544U 98067A   24060.51041667  .00016717  00000+0  30153-3 0  9991";
+const LINE2: &str = "2 25544  51.6416 208.5123 0005312  32.3123  75.1234 15.49912345440123";
+
+/// Names that have broken layouts and exports before: brackets, combining marks,
+/// CJK, right-to-left text with and without an override, emoji, a line break, CSV's own
+/// quote and separator, and 200 characters
+fn pathological() -> Vec<String> {
+    vec![
+        "OBJECT WX-3 (DEB) [unconfirmed]".to_string(),
+        "Cafe\u{301} Sat-1 a\u{308}\u{308}".to_string(),
+        "ひまわり8号 (HIMAWARI-8)".to_string(),
+        "قمر صناعي تجريبي".to_string(),
+        "\u{202e}1-TAS desrever".to_string(),
+        "🛰️ CubeSat 👩‍🚀🚀".to_string(),
+        "TWO\nLINES".to_string(),
+        "SAT \"A\", B; C".to_string(),
+        "LONG-".repeat(40),
+    ]
+}
+
+fn tracking(id: usize, name: &str) -> Value {
+    let start = chrono::Utc::now() + chrono::Duration::hours(2 + id as i64);
+    json!({
+        "id": id.to_string(),
+        "status": "scheduled",
+        "job_type": "tracking",
+        "tle": { "tle0": name, "tle1": LINE1, "tle2": LINE2 },
+        "start": start.to_rfc3339(),
+        "end": (start + chrono::Duration::minutes(10)).to_rfc3339(),
+        "rx_frequency": 145_800_000,
+        "tx_frequency": 0,
+    })
+}
+
+async fn listing() -> MockServer {
+    let jobs: Vec<Value> = pathological()
+        .iter()
+        .enumerate()
+        .map(|(i, name)| tracking(i + 1, name))
+        .collect();
+    let api = station().await;
+    Mock::given(method("GET"))
+        .and(path("/jobs"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(Value::Array(jobs)))
+        .mount(&api)
+        .await;
+    api
+}
+
+fn stdout(sandbox: &Sandbox, api: &MockServer, columns: Option<&str>, args: &[&str]) -> String {
+    let mut command = sandbox.command(api, args);
+    match columns {
+        Some(columns) => command.env("COLUMNS", columns),
+        None => command.env_remove("COLUMNS"),
+    };
+    let output = assert_cmd::Command::from_std(command)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    String::from_utf8(output).expect("UTF-8 output")
+}
+
+/// The screen column where `needle` starts in `line`
+fn column_of(line: &str, needle: &str) -> Option<usize> {
+    line.find(needle).map(|at| line[..at].width())
+}
+
+/// Every job row of a `jobs list` table has its start time under the START header
+fn assert_aligned(table: &str) {
+    let mut lines = table.lines().skip_while(|line| !line.contains("START"));
+    let header = lines
+        .next()
+        .unwrap_or_else(|| panic!("no header in {}", table));
+    let start = column_of(header, "START").expect("a START column");
+    let year = chrono::Utc::now().format("%Y-").to_string();
+    let rows: Vec<&str> = lines.filter(|line| line.contains(&year)).collect();
+    assert_eq!(rows.len(), pathological().len(), "{}", table);
+    for row in rows {
+        assert_eq!(column_of(row, &year), Some(start), "{}", table);
+        assert!(!row.contains('\u{202e}'), "an override left in {:?}", row);
+    }
+}
+
+#[tokio::test]
+async fn a_table_of_odd_names_stays_aligned_in_full_and_cut_to_fit() {
+    let api = listing().await;
+    let sandbox = Sandbox::new();
+
+    let full = stdout(&sandbox, &api, None, &["jobs", "list"]);
+    assert!(full.contains(&"LONG-".repeat(40)), "{}", full);
+    assert_aligned(&full);
+
+    let fitted = stdout(&sandbox, &api, Some("80"), &["jobs", "list"]);
+    assert_aligned(&fitted);
+    for line in fitted.lines() {
+        assert!(line.width() <= 80, "{} columns: {:?}", line.width(), line);
+    }
+    assert!(fitted.contains("ひまわり"), "{}", fitted);
+    assert!(fitted.contains('…'), "{}", fitted);
+}
+
+#[tokio::test]
+async fn timeline_bars_start_in_one_column() {
+    let api = listing().await;
+    let sandbox = Sandbox::new();
+
+    let timeline = stdout(&sandbox, &api, None, &["jobs", "list", "--timeline"]);
+    let bars: Vec<usize> = timeline
+        .lines()
+        .filter_map(|line| line.find(['█', '·']).map(|at| line[..at].width()))
+        .collect();
+    assert_eq!(bars.len(), pathological().len(), "{}", timeline);
+    assert!(bars.iter().all(|&at| at == bars[0]), "{}", timeline);
+}
+
+#[tokio::test]
+async fn csv_and_json_exports_keep_each_name_exactly() {
+    let api = listing().await;
+    let sandbox = Sandbox::new();
+
+    let csv = stdout(&sandbox, &api, None, &["--output", "csv", "jobs", "list"]);
+    let mut reader = csv::Reader::from_reader(csv.as_bytes());
+    let column = reader
+        .headers()
+        .expect("a CSV header")
+        .iter()
+        .position(|h| h == "satellite")
+        .expect("a satellite column");
+    let names: Vec<String> = reader
+        .records()
+        .map(|record| record.expect("a CSV record")[column].to_string())
+        .collect();
+    assert_eq!(names, pathological());
+
+    let listed: Value = serde_json::from_str(&stdout(
+        &sandbox,
+        &api,
+        None,
+        &["--output", "json", "jobs", "list"],
+    ))
+    .expect("a JSON job list");
+    let names: Vec<&str> = listed
+        .as_array()
+        .expect("a list")
+        .iter()
+        .filter_map(|job| job["tle"]["tle0"].as_str())
+        .collect();
+    assert_eq!(names, pathological());
+}
+
+#[tokio::test]
+async fn a_name_in_another_normalization_form_is_still_a_duplicate() {
+    let api = station().await;
+    let start = chrono::Utc::now() + chrono::Duration::days(2);
+    let pointing = |name: &str| {
+        json!({
+            "job_type": "pointing",
+            "pointing": { "azimuth": 123.4, "elevation": 45.6, "name": name },
+            "start": start.to_rfc3339(),
+            "end": (start + chrono::Duration::minutes(10)).to_rfc3339(),
+            "rx_frequency": 10_489_550_000u64,
+            "tx_frequency": 0,
+        })
+    };
+    // Listed decomposed, submitted composed
+    let mut listed = pointing("ESTACIO\u{301}N-1");
+    listed["id"] = json!(41);
+    listed["status"] = json!("scheduled");
+    Mock::given(method("GET"))
+        .and(path("/jobs"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([listed])))
+        .mount(&api)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/jobs"))
+        .respond_with(common::created(42))
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+    let file = sandbox.path().join("jobs.json");
+    std::fs::write(&file, json!([pointing("ESTACIÓN-1")]).to_string()).expect("writing the batch");
+
+    assert_cmd::Command::from_std(sandbox.command(
+        &api,
+        &["jobs", "add", "--batch", &file.display().to_string()],
+    ))
+    .assert()
+    .success()
+    .stderr(contains("is already scheduled, as job 41"));
+}
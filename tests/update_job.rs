@@ -0,0 +1,280 @@
+//! `jobs update --patch` against servers that take a merge patch, only a full PUT, or
+//! do not say
+
+mod common;
+
+use common::{cli, loopback_job, station, Sandbox};
+use predicates::prelude::PredicateBooleanExt;
+use predicates::str::contains;
+use serde_json::Value;
+use wiremock::matchers::{body_json, body_partial_json, header, method, path};
+use wiremock::{Mock, ResponseTemplate};
+
+async fn stored_job(api: &wiremock::MockServer, times: u64) {
+    Mock::given(method("GET"))
+        .and(path("/jobs/7"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(loopback_job("7")))
+        .expect(times)
+        .mount(api)
+        .await;
+}
+
+/// GET /jobs/7 answered with `before` once, then with `before` merged with `stored`, as
+/// the server holds the job once it has been changed
+async fn changed_job(api: &wiremock::MockServer, before: &Value, stored: Value) {
+    let mut after = before.clone();
+    for (key, value) in stored.as_object().expect("stored fields") {
+        after[key] = value.clone();
+    }
+    Mock::given(method("GET"))
+        .and(path("/jobs/7"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(before))
+        .up_to_n_times(1)
+        .expect(1)
+        .with_priority(1)
+        .mount(api)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/jobs/7"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(after))
+        .expect(1)
+        .mount(api)
+        .await;
+}
+
+/// The stored loopback job, then the same job with the patched frequency
+async fn patched_job(api: &wiremock::MockServer) {
+    changed_job(
+        api,
+        &loopback_job("7"),
+        serde_json::json!({ "rx_frequency": 437_525_000 }),
+    )
+    .await;
+}
+
+/// OPTIONS /jobs/7 answered with these headers
+async fn options(api: &wiremock::MockServer, headers: &[(&str, &str)]) {
+    let mut response = ResponseTemplate::new(204);
+    for (name, value) in headers {
+        response = response.insert_header(*name, *value);
+    }
+    Mock::given(method("OPTIONS"))
+        .and(path("/jobs/7"))
+        .respond_with(response)
+        .mount(api)
+        .await;
+}
+
+fn updated() -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(serde_json::json!({ "status": "updated" }))
+}
+
+const PATCH: &str = r#"{"rx_frequency": 437525000}"#;
+
+#[tokio::test]
+async fn a_server_taking_merge_patches_gets_only_the_change() {
+    let api = station().await;
+    patched_job(&api).await;
+    options(
+        &api,
+        &[
+            ("Allow", "GET, PATCH, DELETE"),
+            ("Accept-Patch", "application/merge-patch+json"),
+        ],
+    )
+    .await;
+    Mock::given(method("PATCH"))
+        .and(path("/jobs/7"))
+        .and(header("content-type", "application/merge-patch+json"))
+        .and(body_json(
+            serde_json::json!({ "rx_frequency": 437_525_000 }),
+        ))
+        .respond_with(updated())
+        .expect(1)
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+
+    cli(&sandbox, &api, &["jobs", "update", "7", "--patch", PATCH])
+        .assert()
+        .success()
+        .stdout(contains("rx_frequency 437500000 → 437525000"))
+        .stdout(contains("Job updated: updated"))
+        .stdout(contains(
+            "Job 7 now holds rx_frequency: 437.5 MHz → 437.525 MHz",
+        ))
+        .stdout(contains("stored something other").not());
+}
+
+#[tokio::test]
+async fn a_server_allowing_only_put_gets_the_whole_job() {
+    let api = station().await;
+    patched_job(&api).await;
+    options(&api, &[("Allow", "GET, PUT, DELETE")]).await;
+    Mock::given(method("PUT"))
+        .and(path("/jobs/7"))
+        .and(body_partial_json(serde_json::json!({
+            "job_type": "test",
+            "rx_frequency": 437_525_000,
+            "tx_frequency": 0,
+        })))
+        .respond_with(updated())
+        .expect(1)
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+
+    cli(&sandbox, &api, &["jobs", "update", "7", "--patch", PATCH])
+        .assert()
+        .success();
+}
+
+#[tokio::test]
+async fn a_server_not_answering_options_gets_a_merge_patch() {
+    let api = station().await;
+    patched_job(&api).await;
+    Mock::given(method("PATCH"))
+        .and(path("/jobs/7"))
+        .and(body_json(
+            serde_json::json!({ "rx_frequency": 437_525_000 }),
+        ))
+        .respond_with(updated())
+        .expect(1)
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+
+    cli(&sandbox, &api, &["jobs", "update", "7", "--patch", PATCH])
+        .assert()
+        .success();
+}
+
+#[tokio::test]
+async fn unknown_fields_fail_before_anything_is_fetched() {
+    let api = station().await;
+    stored_job(&api, 0).await;
+    let sandbox = Sandbox::new();
+
+    cli(
+        &sandbox,
+        &api,
+        &[
+            "jobs",
+            "update",
+            "7",
+            "--patch",
+            r#"{"polarisation": "rhcp"}"#,
+        ],
+    )
+    .assert()
+    .failure()
+    .stderr(contains("polarisation is not a job field"))
+    .stderr(contains("polarization"))
+    .stderr(contains("rx_frequency"));
+}
+
+#[tokio::test]
+async fn a_patched_job_that_breaks_a_rule_is_not_sent() {
+    let api = station().await;
+    stored_job(&api, 1).await;
+    Mock::given(method("PATCH"))
+        .respond_with(updated())
+        .expect(0)
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+    // An hour before the stored job starts
+    let end = chrono::Utc::now() + chrono::Duration::days(2) - chrono::Duration::hours(1);
+
+    cli(
+        &sandbox,
+        &api,
+        &[
+            "jobs",
+            "update",
+            "7",
+            "--patch",
+            &serde_json::json!({ "end": end.to_rfc3339() }).to_string(),
+        ],
+    )
+    .assert()
+    .failure()
+    .stderr(contains("Failed to update job 7: invalid end"))
+    .stderr(contains("is not after the start"));
+}
+
+#[tokio::test]
+async fn a_value_the_server_stored_differently_is_pointed_out() {
+    let api = station().await;
+    // The server keeps frequencies to the kHz
+    changed_job(
+        &api,
+        &loopback_job("7"),
+        serde_json::json!({ "rx_frequency": 437_526_000 }),
+    )
+    .await;
+    Mock::given(method("PATCH"))
+        .and(path("/jobs/7"))
+        .respond_with(updated())
+        .expect(1)
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+
+    cli(
+        &sandbox,
+        &api,
+        &[
+            "jobs",
+            "update",
+            "7",
+            "--patch",
+            r#"{"rx_frequency": 437525600}"#,
+        ],
+    )
+    .assert()
+    .success()
+    .stdout(contains(
+        "Job 7 now holds rx_frequency: 437.5 MHz → 437.526 MHz",
+    ))
+    .stdout(contains(
+        "stored something other than what was sent (requested → stored): \
+         rx_frequency: 437.5256 MHz → 437.526 MHz",
+    ));
+}
+
+#[tokio::test]
+async fn json_output_holds_the_job_before_and_after_and_the_changes() {
+    let api = station().await;
+    patched_job(&api).await;
+    Mock::given(method("PATCH"))
+        .and(path("/jobs/7"))
+        .respond_with(updated())
+        .expect(1)
+        .mount(&api)
+        .await;
+    let sandbox = Sandbox::new();
+
+    let output = cli(
+        &sandbox,
+        &api,
+        &["--output", "json", "jobs", "update", "7", "--patch", PATCH],
+    )
+    .assert()
+    .success()
+    .get_output()
+    .stdout
+    .clone();
+    let report: Value = serde_json::from_slice(&output).expect("a JSON report");
+    assert_eq!(report["before"]["rx_frequency"], 437_500_000);
+    assert_eq!(report["after"]["rx_frequency"], 437_525_000);
+    assert_eq!(
+        report["changes"],
+        serde_json::json!([{
+            "path": "rx_frequency",
+            "before": 437_500_000,
+            "after": 437_525_000,
+        }])
+    );
+    assert_eq!(report["normalized"], serde_json::json!([]));
+}